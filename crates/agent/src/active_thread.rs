@@ -955,6 +955,10 @@ impl ActiveThread {
             ThreadEvent::NewRequest | ThreadEvent::CompletionCanceled => {
                 cx.notify();
             }
+            ThreadEvent::CompactionChanged => {
+                self.save_thread(cx);
+                cx.notify();
+            }
             ThreadEvent::StreamedCompletion
             | ThreadEvent::SummaryGenerated
             | ThreadEvent::SummaryChanged => {
@@ -1545,6 +1549,253 @@ impl ActiveThread {
             .unwrap_or(&[])
     }
 
+    fn fork_from_message(
+        &mut self,
+        message_id: MessageId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let fork = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.fork_thread(&self.thread, message_id, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let fork = fork.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.open_thread(fork, window, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_original_thread(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(thread_id) = self
+            .thread
+            .read(cx)
+            .forked_from()
+            .map(|fork| fork.thread_id.clone())
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                    panel.update(cx, |panel, cx| {
+                        panel
+                            .open_thread_by_id(&thread_id, window, cx)
+                            .detach_and_log_err(cx)
+                    });
+                }
+            })
+            .log_err();
+    }
+
+    /// Opens the changes the original thread had made after the fork point in a new buffer.
+    fn open_original_diff(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let thread = self.thread.read(cx);
+        let Some(diff) = thread
+            .forked_from()
+            .and_then(|fork| fork.original_diff.clone())
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let title = format!("{} (Original Changes)", thread.summary_or_default());
+        let diff_language = self.language_registry.language_for_name("Diff");
+        cx.spawn_in(window, async move |_, cx| {
+            let diff_language = diff_language.await.log_err();
+            workspace.update_in(cx, |workspace, window, cx| {
+                let project = workspace.project().clone();
+                let buffer = project.update(cx, |project, cx| {
+                    project.create_local_buffer(&diff, diff_language, cx)
+                });
+                let buffer =
+                    cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.clone()));
+
+                workspace.add_item_to_active_pane(
+                    Box::new(cx.new(|cx| {
+                        let mut editor =
+                            Editor::for_multibuffer(buffer, Some(project.clone()), window, cx);
+                        editor.set_breadcrumb_header(title);
+                        editor
+                    })),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens the summary that replaces the compacted messages of the thread in a new buffer.
+    fn open_compaction_summary(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let thread = self.thread.read(cx);
+        let Some(summary) = thread
+            .compaction()
+            .and_then(|compaction| compaction.summary.clone())
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let title = format!("{} (Compacted Messages)", thread.summary_or_default());
+        let markdown_language = self.language_registry.language_for_name("Markdown");
+        cx.spawn_in(window, async move |_, cx| {
+            let markdown_language = markdown_language.await.log_err();
+            workspace.update_in(cx, |workspace, window, cx| {
+                let project = workspace.project().clone();
+                let buffer = project.update(cx, |project, cx| {
+                    project.create_local_buffer(&summary, markdown_language, cx)
+                });
+                let buffer =
+                    cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.clone()));
+
+                workspace.add_item_to_active_pane(
+                    Box::new(cx.new(|cx| {
+                        let mut editor =
+                            Editor::for_multibuffer(buffer, Some(project.clone()), window, cx);
+                        editor.set_read_only(true);
+                        editor.set_breadcrumb_header(title);
+                        editor
+                    })),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Renders the divider between the compacted messages of the thread and the ones that are
+    /// still sent to the model, if the given message is the last compacted one.
+    fn render_compaction_divider(
+        &self,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let thread = self.thread.read(cx);
+        let compaction = thread
+            .compaction()
+            .filter(|compaction| compaction.up_to == message_id)?;
+        let compacted_message_count = thread
+            .messages()
+            .position(|message| message.id == message_id)?
+            + 1;
+        let has_summary = compaction.summary.is_some();
+        let label = match (compacted_message_count, has_summary) {
+            (1, true) => "1 earlier message compacted into a summary".to_string(),
+            (1, false) => "1 earlier message left out to fit the context window".to_string(),
+            (count, true) => format!("{count} earlier messages compacted into a summary"),
+            (count, false) => {
+                format!("{count} earlier messages left out to fit the context window")
+            }
+        };
+
+        Some(
+            h_flex()
+                .pt_2p5()
+                .px_2p5()
+                .w_full()
+                .gap_1()
+                .child(ui::Divider::horizontal())
+                .child(
+                    Label::new(label)
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .when(has_summary, |this| {
+                    this.child(
+                        Button::new("open-compaction-summary", "Show Summary")
+                            .label_size(LabelSize::XSmall)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.open_compaction_summary(window, cx);
+                            })),
+                    )
+                })
+                .child(
+                    Button::new("undo-compaction", "Restore")
+                        .icon(IconName::Undo)
+                        .icon_size(IconSize::XSmall)
+                        .icon_position(IconPosition::Start)
+                        .label_size(LabelSize::XSmall)
+                        .tooltip(Tooltip::text(
+                            "Send the compacted messages to the model again",
+                        ))
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.thread
+                                .update(cx, |thread, cx| thread.undo_compaction(cx));
+                        })),
+                )
+                .child(ui::Divider::horizontal())
+                .into_any_element(),
+        )
+    }
+
+    fn render_fork_banner(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let fork = self.thread.read(cx).forked_from()?;
+        let has_original_diff = fork.original_diff.is_some();
+
+        Some(
+            h_flex()
+                .w_full()
+                .py_1()
+                .px_2p5()
+                .gap_1()
+                .justify_between()
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::GitBranch)
+                                .size(IconSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            Label::new("Forked from another thread")
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        ),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .when(has_original_diff, |this| {
+                            this.child(
+                                Button::new("open-original-diff", "Original Changes")
+                                    .label_size(LabelSize::XSmall)
+                                    .tooltip(Tooltip::text(
+                                        "Show the changes the original thread made after the fork point",
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.open_original_diff(window, cx);
+                                    })),
+                            )
+                        })
+                        .child(
+                            Button::new("open-original-thread", "Open Original")
+                                .label_size(LabelSize::XSmall)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.open_original_thread(window, cx);
+                                })),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+
     fn handle_cancel_click(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         self.cancel_editing_message(&menu::Cancel, window, cx);
     }
@@ -1988,6 +2239,20 @@ impl ActiveThread {
                                     )
                                 }),
                         )
+                        .when(editing_message_state.is_none() && !is_generating, |this| {
+                            this.child(
+                                h_flex().px_2p5().pb_1p5().justify_end().child(
+                                    IconButton::new(("fork-from-message", ix), IconName::GitBranch)
+                                        .shape(ui::IconButtonShape::Square)
+                                        .icon_size(IconSize::XSmall)
+                                        .icon_color(Color::Ignored)
+                                        .tooltip(Tooltip::text("Fork Thread From Here"))
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            this.fork_from_message(message_id, window, cx);
+                                        })),
+                                ),
+                            )
+                        })
                         .when(editing_message_state.is_none(), |this| {
                             this.tooltip(Tooltip::text("Click To Edit"))
                         })
@@ -2117,7 +2382,15 @@ impl ActiveThread {
                         .mb_4()
                         .ml_4()
                         .py_1p5()
-                        .when_some(loading_dots, |this, loading_dots| this.child(loading_dots)),
+                        .gap_2()
+                        .when_some(loading_dots, |this, loading_dots| this.child(loading_dots))
+                        .when(self.thread.read(cx).is_compacting(), |this| {
+                            this.child(
+                                Label::new("Compacting earlier messages…")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
                 )
             })
             .when(show_feedback, move |parent| {
@@ -2197,6 +2470,7 @@ impl ActiveThread {
                     },
                 )
             })
+            .children(self.render_compaction_divider(message_id, cx))
             .when(after_editing_message, |parent| {
                 // Backdrop to dim out the whole thread below the editing user message
                 parent.relative().child(
@@ -3369,6 +3643,7 @@ impl Render for ActiveThread {
                     this.hide_scrollbar_later(cx);
                 }),
             )
+            .children(self.render_fork_banner(cx))
             .child(list(self.list_state.clone()).flex_grow())
             .when_some(self.render_vertical_scrollbar(cx), |this, scrollbar| {
                 this.child(scrollbar)
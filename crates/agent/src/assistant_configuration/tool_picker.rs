@@ -114,7 +114,7 @@ impl ToolPickerDelegate {
         let mut items = Vec::new();
         for (source, tools) in tool_set.read(cx).tools_by_source(cx) {
             match source {
-                ToolSource::Native => {
+                ToolSource::Native | ToolSource::Extension { .. } => {
                     if mode == ToolPickerMode::BuiltinTools {
                         items.extend(tools.into_iter().map(|tool| PickerItem::Tool {
                             name: tool.name().into(),
@@ -5,6 +5,7 @@ use crate::assistant_model_selector::{AssistantModelSelector, ModelType};
 use crate::context::{AgentContextKey, ContextCreasesAddon, ContextLoadResult, load_context};
 use crate::tool_compatibility::{IncompatibleToolsState, IncompatibleToolsTooltip};
 use crate::ui::{AgentPreview, AnimatedLabel, MaxModeTooltip};
+use assistant_settings::AssistantSettings;
 use buffer_diff::BufferDiff;
 use collections::{HashMap, HashSet};
 use editor::actions::{MoveUp, Paste};
@@ -992,6 +993,11 @@ impl MessageEditor {
         } else {
             "Thread reaching the token limit soon"
         };
+        let description = if AssistantSettings::get_global(cx).context_compaction.enabled {
+            "Earlier messages will be compacted when you send the next one, or start a new thread from a summary."
+        } else {
+            "Start a new thread from a summary to continue the conversation."
+        };
 
         h_flex()
             .p_2()
@@ -1031,9 +1037,7 @@ impl MessageEditor {
                             .mr_auto()
                             .child(Label::new(heading).size(LabelSize::Small))
                             .child(
-                                Label::new(
-                                    "Start a new thread from a summary to continue the conversation.",
-                                )
+                                Label::new(description)
                                 .size(LabelSize::Small)
                                 .color(Color::Muted),
                             ),
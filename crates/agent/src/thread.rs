@@ -5,7 +5,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use anyhow::{Result, anyhow};
-use assistant_settings::AssistantSettings;
+use assistant_settings::{AssistantSettings, ContextCompactionStrategy};
 use assistant_tool::{ActionLog, AnyToolCard, Tool, ToolWorkingSet};
 use chrono::{DateTime, Utc};
 use collections::HashMap;
@@ -219,6 +219,51 @@ pub struct ThreadCheckpoint {
     git_checkpoint: GitStoreCheckpoint,
 }
 
+impl ThreadCheckpoint {
+    pub fn git_checkpoint(&self) -> GitStoreCheckpoint {
+        self.git_checkpoint.clone()
+    }
+}
+
+/// Where a [`Thread`] was forked from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadFork {
+    pub thread_id: ThreadId,
+    /// The user message of the original thread that the fork was created at.
+    /// The fork contains all of the messages that precede it.
+    pub message_id: MessageId,
+    /// The changes the original thread had made to the workspace after the fork point,
+    /// captured right before the workspace was restored to the fork point.
+    #[serde(default)]
+    pub original_diff: Option<String>,
+}
+
+/// Older messages of a [`Thread`] that are left out of completion requests to keep the thread
+/// within the context window of its model.
+///
+/// The compacted messages remain in the thread, so the compaction can be undone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadCompaction {
+    /// The last compacted message. It and all of the messages that precede it are left out.
+    pub up_to: MessageId,
+    /// The summary that replaces the compacted messages, or `None` if they were truncated.
+    #[serde(default)]
+    pub summary: Option<SharedString>,
+}
+
+impl ThreadCompaction {
+    fn request_text(&self) -> String {
+        match &self.summary {
+            Some(summary) => format!(
+                "The earlier part of this conversation was compacted to fit the context window. \
+                Here is a summary of it:\n\n{summary}"
+            ),
+            None => "The earlier part of this conversation was left out to fit the context window."
+                .to_string(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ThreadFeedback {
     Positive,
@@ -365,6 +410,9 @@ pub struct Thread {
     >,
     remaining_turns: u32,
     configured_model: Option<ConfiguredModel>,
+    forked_from: Option<ThreadFork>,
+    compaction: Option<ThreadCompaction>,
+    pending_compaction: Option<Task<()>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -426,6 +474,9 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            forked_from: None,
+            compaction: None,
+            pending_compaction: None,
         }
     }
 
@@ -534,6 +585,9 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            forked_from: serialized.forked_from,
+            compaction: serialized.compaction,
+            pending_compaction: None,
         }
     }
 
@@ -635,7 +689,9 @@ impl Thread {
     }
 
     pub fn is_generating(&self) -> bool {
-        !self.pending_completions.is_empty() || !self.all_tools_finished()
+        !self.pending_completions.is_empty()
+            || self.pending_compaction.is_some()
+            || !self.all_tools_finished()
     }
 
     /// Indicates whether streaming of language model events is stale.
@@ -769,6 +825,88 @@ impl Thread {
         self.last_restore_checkpoint.as_ref()
     }
 
+    /// Returns the checkpoint capturing the state of the workspace right before the given
+    /// message was sent.
+    ///
+    /// Checkpoints are only kept for messages whose turn changed the workspace, so when the
+    /// given message doesn't have one, the state is the same as the one captured by the next
+    /// message that does. Returns `None` if no later turn changed the workspace.
+    pub fn checkpoint_at_message(&self, id: MessageId) -> Option<ThreadCheckpoint> {
+        self.checkpoints_by_message
+            .values()
+            .filter(|checkpoint| checkpoint.message_id >= id)
+            .min_by_key(|checkpoint| checkpoint.message_id)
+            .cloned()
+    }
+
+    pub fn forked_from(&self) -> Option<&ThreadFork> {
+        self.forked_from.as_ref()
+    }
+
+    /// Creates a new thread containing all of the messages that precede `message_id`, so the
+    /// conversation can continue on a separate branch from that point.
+    ///
+    /// This doesn't touch the workspace; see [`ThreadStore::fork_thread`] for restoring it to
+    /// the state it was in at the fork point.
+    pub fn fork(
+        &self,
+        message_id: MessageId,
+        original_diff: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Thread>>> {
+        let serialized = self.serialize(cx);
+        let fork = ThreadFork {
+            thread_id: self.id.clone(),
+            message_id,
+            original_diff,
+        };
+        let checkpoints = self
+            .checkpoints_by_message
+            .iter()
+            .filter(|(id, _)| **id < message_id)
+            .map(|(id, checkpoint)| (*id, checkpoint.clone()))
+            .collect::<HashMap<_, _>>();
+        let project = self.project.clone();
+        let tools = self.tools.clone();
+        let prompt_builder = self.prompt_builder.clone();
+        let project_context = self.project_context.clone();
+
+        cx.spawn(async move |_, cx| {
+            let mut serialized = serialized.await?;
+            serialized
+                .messages
+                .retain(|message| message.id < message_id);
+            serialized
+                .request_token_usage
+                .truncate(serialized.messages.len());
+            serialized.detailed_summary_state = DetailedSummaryState::default();
+            serialized.exceeded_window_error = None;
+            if serialized
+                .compaction
+                .as_ref()
+                .is_some_and(|compaction| compaction.up_to >= message_id)
+            {
+                serialized.compaction = None;
+            }
+            serialized.updated_at = Utc::now();
+            serialized.forked_from = Some(fork);
+
+            cx.new(|cx| {
+                let mut thread = Thread::deserialize(
+                    ThreadId::new(),
+                    serialized,
+                    project,
+                    tools,
+                    prompt_builder,
+                    project_context,
+                    cx,
+                );
+                thread.checkpoints_by_message = checkpoints;
+                thread
+            })
+        })
+    }
+
     pub fn truncate(&mut self, message_id: MessageId, cx: &mut Context<Self>) {
         let Some(message_ix) = self
             .messages
@@ -780,6 +918,13 @@ impl Thread {
         for deleted_message in self.messages.drain(message_ix..) {
             self.checkpoints_by_message.remove(&deleted_message.id);
         }
+        if self
+            .compaction
+            .as_ref()
+            .is_some_and(|compaction| compaction.up_to >= message_id)
+        {
+            self.compaction = None;
+        }
         cx.notify();
     }
 
@@ -977,6 +1122,13 @@ impl Thread {
             return false;
         };
         self.messages.remove(index);
+        if self
+            .compaction
+            .as_ref()
+            .is_some_and(|compaction| compaction.up_to == id)
+        {
+            self.compaction = None;
+        }
         self.touch_updated_at();
         cx.emit(ThreadEvent::MessageDeleted(id));
         true
@@ -1088,6 +1240,8 @@ impl Thread {
                         provider: model.provider.id().0.to_string(),
                         model: model.model.id().0.to_string(),
                     }),
+                forked_from: this.forked_from.clone(),
+                compaction: this.compaction.clone(),
             })
         })
     }
@@ -1114,7 +1268,185 @@ impl Thread {
 
         let request = self.to_completion_request(model.clone(), cx);
 
-        self.stream_completion(request, model, window, cx);
+        if self.may_need_compaction(&model, cx) {
+            self.compact_if_needed_and_stream(request, model, window, cx);
+        } else {
+            self.stream_completion(request, model, window, cx);
+        }
+    }
+
+    pub fn compaction(&self) -> Option<&ThreadCompaction> {
+        self.compaction.as_ref()
+    }
+
+    pub fn is_compacting(&self) -> bool {
+        self.pending_compaction.is_some()
+    }
+
+    /// Includes the compacted messages in completion requests again.
+    pub fn undo_compaction(&mut self, cx: &mut Context<Self>) {
+        if self.compaction.take().is_some() {
+            self.touch_updated_at();
+            cx.emit(ThreadEvent::CompactionChanged);
+        }
+    }
+
+    /// Returns the number of messages at the start of the thread that are left out of requests.
+    fn compacted_message_count(&self) -> usize {
+        self.compaction
+            .as_ref()
+            .and_then(|compaction| {
+                self.messages
+                    .iter()
+                    .position(|message| message.id == compaction.up_to)
+            })
+            .map_or(0, |ix| ix + 1)
+    }
+
+    /// Returns the index of the last message that can be compacted while keeping the given
+    /// number of most recent messages, if there are any messages left to compact.
+    fn compaction_boundary(&self, keep_recent_messages: usize) -> Option<usize> {
+        let ix = self
+            .messages
+            .len()
+            .checked_sub(keep_recent_messages.saturating_add(1))?;
+        (ix >= self.compacted_message_count()).then_some(ix)
+    }
+
+    /// Returns whether the thread may be approaching the context window of the model, in which
+    /// case the tokens of the next request are counted before it is sent.
+    fn may_need_compaction(&self, model: &Arc<dyn LanguageModel>, cx: &App) -> bool {
+        let settings = &AssistantSettings::get_global(cx).context_compaction;
+        if !settings.enabled
+            || self
+                .compaction_boundary(settings.keep_recent_messages)
+                .is_none()
+        {
+            return false;
+        }
+
+        if self.exceeded_window_error.is_some() {
+            return true;
+        }
+
+        // The usage of the last request doesn't include the messages added since, so start
+        // counting well before the threshold is reached.
+        let last_token_count = self
+            .token_usage_at_last_message()
+            .map_or(0, |usage| usage.total_tokens());
+        last_token_count as f32 >= model.max_token_count() as f32 * settings.threshold / 2.
+    }
+
+    fn compact_if_needed_and_stream(
+        &mut self,
+        request: LanguageModelRequest,
+        model: Arc<dyn LanguageModel>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut Context<Self>,
+    ) {
+        let settings = AssistantSettings::get_global(cx).context_compaction.clone();
+        let token_count = model.count_tokens(request.clone(), cx);
+
+        self.pending_compaction = Some(cx.spawn(async move |this, cx| {
+            let token_count = token_count.await.log_err().unwrap_or(0);
+            let threshold = (model.max_token_count() as f32 * settings.threshold) as usize;
+            let exceeded_window = this
+                .read_with(cx, |this, _| this.exceeded_window_error.is_some())
+                .unwrap_or(false);
+
+            let request = if exceeded_window || token_count >= threshold {
+                let compaction = this
+                    .update(cx, |this, cx| {
+                        this.compact(
+                            settings.keep_recent_messages,
+                            settings.strategy,
+                            model.clone(),
+                            cx,
+                        )
+                    })
+                    .ok()
+                    .flatten();
+                if let Some(compaction) = compaction {
+                    compaction.await;
+                }
+                this.update(cx, |this, cx| this.to_completion_request(model.clone(), cx))
+            } else {
+                Ok(request)
+            };
+
+            this.update(cx, |this, cx| {
+                this.pending_compaction = None;
+                if let Ok(request) = request {
+                    this.stream_completion(request, model, window, cx);
+                }
+            })
+            .ok();
+        }));
+        cx.emit(ThreadEvent::CompactionChanged);
+    }
+
+    /// Compacts all but the given number of most recent messages, either by replacing them with
+    /// a summary written by the model or by leaving them out of requests.
+    ///
+    /// Returns `None` if there are no messages to compact.
+    pub fn compact(
+        &mut self,
+        keep_recent_messages: usize,
+        strategy: ContextCompactionStrategy,
+        model: Arc<dyn LanguageModel>,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<()>> {
+        let boundary_ix = self.compaction_boundary(keep_recent_messages)?;
+        let up_to = self.messages[boundary_ix].id;
+
+        match strategy {
+            ContextCompactionStrategy::Truncate => {
+                self.set_compaction(
+                    ThreadCompaction {
+                        up_to,
+                        summary: None,
+                    },
+                    cx,
+                );
+                Some(Task::ready(()))
+            }
+            ContextCompactionStrategy::Summarize => {
+                let request = self.to_compaction_request(boundary_ix);
+                Some(cx.spawn(async move |this, cx| {
+                    let summary = async {
+                        let mut messages = model.stream_completion_text(request, &cx).await?;
+                        let mut summary = String::new();
+                        while let Some(chunk) = messages.stream.next().await {
+                            summary.push_str(&chunk?);
+                        }
+                        anyhow::Ok(summary)
+                    }
+                    .await;
+
+                    // Fall back to leaving the messages out if they couldn't be summarized.
+                    let summary = match summary {
+                        Ok(summary) if !summary.trim().is_empty() => Some(summary.into()),
+                        Ok(_) => None,
+                        Err(error) => {
+                            log::error!("failed to summarize thread for compaction: {error:?}");
+                            None
+                        }
+                    };
+
+                    this.update(cx, |this, cx| {
+                        this.set_compaction(ThreadCompaction { up_to, summary }, cx);
+                    })
+                    .ok();
+                }))
+            }
+        }
+    }
+
+    fn set_compaction(&mut self, compaction: ThreadCompaction, cx: &mut Context<Self>) {
+        self.compaction = Some(compaction);
+        self.exceeded_window_error = None;
+        self.touch_updated_at();
+        cx.emit(ThreadEvent::CompactionChanged);
     }
 
     pub fn used_tools_since_last_user_message(&self) -> bool {
@@ -1184,7 +1516,22 @@ impl Thread {
             }));
         }
 
-        for message in &self.messages {
+        let compacted_message_count = self.compacted_message_count();
+        if let Some(compaction) = self
+            .compaction
+            .as_ref()
+            .filter(|_| compacted_message_count > 0)
+        {
+            // The summary stays the same until the next compaction, so it's part of the
+            // cacheable prefix of the request.
+            request.messages.push(LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![MessageContent::Text(compaction.request_text())],
+                cache: true,
+            });
+        }
+
+        for message in &self.messages[compacted_message_count..] {
             let mut request_message = LanguageModelRequestMessage {
                 role: message.role,
                 content: Vec::new(),
@@ -1291,6 +1638,92 @@ impl Thread {
         request
     }
 
+    /// Returns a request for a summary of the messages up to and including the given index, which
+    /// replaces them once the thread is compacted.
+    fn to_compaction_request(&self, up_to_ix: usize) -> LanguageModelRequest {
+        const MAX_TOOL_RESULT_LEN: usize = 4096;
+
+        let mut request = LanguageModelRequest {
+            thread_id: None,
+            prompt_id: None,
+            mode: None,
+            messages: vec![],
+            tools: Vec::new(),
+            stop: Vec::new(),
+            temperature: None,
+        };
+
+        let compacted_message_count = self.compacted_message_count();
+        if let Some(compaction) = self
+            .compaction
+            .as_ref()
+            .filter(|_| compacted_message_count > 0)
+        {
+            request.messages.push(LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![MessageContent::Text(compaction.request_text())],
+                cache: false,
+            });
+        }
+
+        for message in &self.messages[compacted_message_count..=up_to_ix] {
+            let mut request_message = LanguageModelRequestMessage {
+                role: message.role,
+                content: Vec::new(),
+                cache: false,
+            };
+
+            for segment in &message.segments {
+                match segment {
+                    MessageSegment::Text(text) => request_message
+                        .content
+                        .push(MessageContent::Text(text.clone())),
+                    MessageSegment::Thinking { .. } => {}
+                    MessageSegment::RedactedThinking(_) => {}
+                }
+            }
+
+            // Tool uses can't be sent without the tools' definitions, so describe their
+            // results in text instead.
+            for tool_result in self.tool_results_for_message(message.id) {
+                let mut content = tool_result.content.to_string();
+                if content.len() > MAX_TOOL_RESULT_LEN {
+                    let mut end = MAX_TOOL_RESULT_LEN;
+                    while !content.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    content.truncate(end);
+                    content.push_str("\n[...]");
+                }
+                request_message.content.push(MessageContent::Text(format!(
+                    "\n\n[Result of the `{}` tool]\n{content}",
+                    tool_result.tool_name
+                )));
+            }
+
+            if request_message.content.is_empty() {
+                continue;
+            }
+
+            request.messages.push(request_message);
+        }
+
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![MessageContent::Text(
+                "Summarize the conversation above so that the summary can replace it and the \
+                conversation can continue without it. Include the user's goals and requests, \
+                the decisions that were made, the files and code that were read or changed, \
+                the results of tool calls that are still relevant, and any work that remains \
+                to be done. Be thorough but concise, and format the summary in Markdown."
+                    .into(),
+            )],
+            cache: false,
+        });
+
+        request
+    }
+
     fn attached_tracked_files_state(
         &self,
         messages: &mut Vec<LanguageModelRequestMessage>,
@@ -2020,6 +2453,7 @@ impl Thread {
         cx: &mut Context<Self>,
     ) -> bool {
         let mut canceled = self.pending_completions.pop().is_some();
+        canceled |= self.pending_compaction.take().is_some();
 
         for pending_tool_use in self.tool_use.cancel_pending() {
             canceled = true;
@@ -2571,6 +3005,7 @@ pub enum ThreadEvent {
     ToolConfirmationNeeded,
     CancelEditing,
     CompletionCanceled,
+    CompactionChanged,
 }
 
 impl EventEmitter<ThreadEvent> for Thread {}
@@ -2910,6 +3345,194 @@ fn main() {{
         );
     }
 
+    #[gpui::test]
+    async fn test_fork_thread(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_workspace, _thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let (first_message_id, second_message_id) = thread.update(cx, |thread, cx| {
+            let first_message_id = thread.insert_user_message(
+                "Add a test",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            thread.insert_assistant_message(vec![MessageSegment::Text("Done".into())], cx);
+            let second_message_id = thread.insert_user_message(
+                "Now rename it",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            thread.insert_assistant_message(vec![MessageSegment::Text("Renamed".into())], cx);
+            (first_message_id, second_message_id)
+        });
+
+        let git_checkpoint = project
+            .update(cx, |project, cx| {
+                project
+                    .git_store()
+                    .update(cx, |git_store, cx| git_store.checkpoint(cx))
+            })
+            .await
+            .unwrap();
+        thread.update(cx, |thread, cx| {
+            thread.insert_checkpoint(
+                ThreadCheckpoint {
+                    message_id: second_message_id,
+                    git_checkpoint,
+                },
+                cx,
+            );
+        });
+
+        // The first turn didn't change the workspace, so its state is the one
+        // captured before the second message was sent.
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(
+                thread
+                    .checkpoint_at_message(first_message_id)
+                    .map(|checkpoint| checkpoint.message_id),
+                Some(second_message_id)
+            );
+        });
+
+        let fork = thread
+            .update(cx, |thread, cx| {
+                thread.fork(second_message_id, Some("diff".into()), cx)
+            })
+            .await
+            .unwrap();
+
+        let original_id = thread.read_with(cx, |thread, _| thread.id().clone());
+        fork.read_with(cx, |fork, _| {
+            assert_ne!(fork.id(), &original_id);
+            assert_eq!(
+                fork.messages()
+                    .map(|message| (message.role, message.to_string()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (Role::User, "Add a test".to_string()),
+                    (Role::Assistant, "Done".to_string()),
+                ]
+            );
+
+            let forked_from = fork.forked_from().unwrap();
+            assert_eq!(forked_from.thread_id, original_id);
+            assert_eq!(forked_from.message_id, second_message_id);
+            assert_eq!(forked_from.original_diff.as_deref(), Some("diff"));
+            assert!(fork.checkpoint_at_message(first_message_id).is_none());
+        });
+
+        // The original thread is left untouched.
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.messages().len(), 4);
+            assert!(thread.forked_from().is_none());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_compaction(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_workspace, _thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+        let fake_model = Arc::new(FakeLanguageModel::default());
+        let model: Arc<dyn LanguageModel> = fake_model.clone();
+
+        let message_ids = thread.update(cx, |thread, cx| {
+            let mut message_ids = Vec::new();
+            for (question, answer) in [("One", "1"), ("Two", "2"), ("Three", "3")] {
+                message_ids.push(thread.insert_user_message(
+                    question,
+                    ContextLoadResult::default(),
+                    None,
+                    Vec::new(),
+                    cx,
+                ));
+                message_ids.push(
+                    thread.insert_assistant_message(vec![MessageSegment::Text(answer.into())], cx),
+                );
+            }
+            message_ids
+        });
+
+        // Compacting all but the two most recent messages summarizes the first four.
+        let compaction = thread
+            .update(cx, |thread, cx| {
+                thread.compact(2, ContextCompactionStrategy::Summarize, model.clone(), cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+        let summary_request = fake_model.pending_completions().pop().unwrap();
+        assert_eq!(
+            summary_request
+                .messages
+                .iter()
+                .map(|message| message.string_contents())
+                .collect::<Vec<_>>()[..4],
+            ["One", "1", "Two", "2"]
+        );
+        fake_model.stream_last_completion_response("Counted to two".into());
+        fake_model.end_last_completion_stream();
+        compaction.await;
+
+        thread.update(cx, |thread, cx| {
+            let compaction = thread.compaction().unwrap();
+            assert_eq!(compaction.up_to, message_ids[3]);
+            assert_eq!(compaction.summary.as_deref(), Some("Counted to two"));
+
+            let request = thread.to_completion_request(model.clone(), cx);
+            let contents = request
+                .messages
+                .iter()
+                .filter(|message| message.role != Role::System)
+                .map(|message| message.string_contents())
+                .collect::<Vec<_>>();
+            assert_eq!(contents.len(), 3);
+            assert!(contents[0].ends_with("Counted to two"));
+            assert_eq!(contents[1..], ["Three", "3"]);
+
+            // There are no messages left to compact while keeping the two most recent ones.
+            assert!(
+                thread
+                    .compact(2, ContextCompactionStrategy::Truncate, model.clone(), cx)
+                    .is_none()
+            );
+
+            thread.undo_compaction(cx);
+            assert!(thread.compaction().is_none());
+            let request = thread.to_completion_request(model.clone(), cx);
+            assert_eq!(
+                request
+                    .messages
+                    .iter()
+                    .filter(|message| message.role != Role::System)
+                    .count(),
+                6
+            );
+
+            // Truncating leaves the messages out without a summary.
+            thread
+                .compact(4, ContextCompactionStrategy::Truncate, model.clone(), cx)
+                .unwrap()
+                .detach();
+            let compaction = thread.compaction().unwrap();
+            assert_eq!(compaction.up_to, message_ids[1]);
+            assert!(compaction.summary.is_none());
+
+            // Truncating the thread before the end of the compaction undoes it.
+            thread.truncate(message_ids[1], cx);
+            assert!(thread.compaction().is_none());
+        });
+    }
+
     #[gpui::test]
     async fn test_stale_buffer_notification(cx: &mut TestAppContext) {
         init_test_settings(cx);
@@ -484,11 +484,21 @@ impl RenderOnce for PastThread {
             .toggle_state(self.selected)
             .spacing(ListItemSpacing::Sparse)
             .start_slot(
-                div().max_w_4_5().child(
-                    HighlightedLabel::new(summary, self.highlight_positions)
-                        .size(LabelSize::Small)
-                        .truncate(),
-                ),
+                h_flex()
+                    .max_w_4_5()
+                    .gap_1()
+                    .when(self.thread.forked_from.is_some(), |this| {
+                        this.child(
+                            Icon::new(IconName::GitBranch)
+                                .size(IconSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(
+                        HighlightedLabel::new(summary, self.highlight_positions)
+                            .size(LabelSize::Small)
+                            .truncate(),
+                    ),
             )
             .end_slot(
                 h_flex()
@@ -31,7 +31,8 @@ use settings::{Settings as _, SettingsStore};
 use util::ResultExt as _;
 
 use crate::thread::{
-    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadId,
+    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread,
+    ThreadCompaction, ThreadFork, ThreadId,
 };
 
 const RULES_FILE_NAMES: [&'static str; 6] = [
@@ -435,6 +436,66 @@ impl ThreadStore {
         })
     }
 
+    /// Forks `thread` at the given user message and restores the workspace to the state it was
+    /// in before that message was sent.
+    ///
+    /// Both the original thread and the fork are saved, so either branch can be reopened from
+    /// the history later on. The changes the original thread made after the fork point are kept
+    /// on the fork for comparison.
+    pub fn fork_thread(
+        &self,
+        thread: &Entity<Thread>,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Thread>>> {
+        if thread.read(cx).is_generating() {
+            return Task::ready(Err(anyhow!("cannot fork a thread while it is generating")));
+        }
+
+        let checkpoint = thread.read(cx).checkpoint_at_message(message_id);
+        let git_store = self.project.read(cx).git_store().clone();
+        let save_original = self.save_thread(thread, cx);
+        let thread = thread.clone();
+
+        cx.spawn(async move |this, cx| {
+            save_original.await?;
+
+            let mut original_diff = None;
+            if let Some(checkpoint) = checkpoint {
+                let checkpoint = checkpoint.git_checkpoint();
+                let head = git_store
+                    .update(cx, |git_store, cx| git_store.checkpoint(cx))?
+                    .await?;
+                let unchanged = git_store
+                    .update(cx, |git_store, cx| {
+                        git_store.compare_checkpoints(checkpoint.clone(), head.clone(), cx)
+                    })?
+                    .await?;
+                if !unchanged {
+                    original_diff = git_store
+                        .update(cx, |git_store, cx| {
+                            git_store.diff_checkpoints(checkpoint.clone(), head, cx)
+                        })?
+                        .await
+                        .log_err();
+                    git_store
+                        .update(cx, |git_store, cx| {
+                            git_store.restore_checkpoint(checkpoint, cx)
+                        })?
+                        .await
+                        .context("failed to restore the workspace to the fork point")?;
+                }
+            }
+
+            let fork = thread
+                .update(cx, |thread, cx| thread.fork(message_id, original_diff, cx))?
+                .await?;
+            this.update(cx, |this, cx| this.save_thread(&fork, cx))?
+                .await?;
+            Ok(fork)
+        })
+    }
+
     pub fn delete_thread(&mut self, id: &ThreadId, cx: &mut Context<Self>) -> Task<Result<()>> {
         let id = id.clone();
         let database_future = ThreadsDatabase::global_future(cx);
@@ -628,6 +689,9 @@ pub struct SerializedThreadMetadata {
     pub id: ThreadId,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    /// The thread this one was forked from, if any.
+    #[serde(default)]
+    pub forked_from: Option<ThreadId>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -648,6 +712,10 @@ pub struct SerializedThread {
     pub exceeded_window_error: Option<ExceededWindowError>,
     #[serde(default)]
     pub model: Option<SerializedLanguageModel>,
+    #[serde(default)]
+    pub forked_from: Option<ThreadFork>,
+    #[serde(default)]
+    pub compaction: Option<ThreadCompaction>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -791,6 +859,8 @@ impl LegacySerializedThread {
             detailed_summary_state: DetailedSummaryState::default(),
             exceeded_window_error: None,
             model: None,
+            forked_from: None,
+            compaction: None,
         }
     }
 }
@@ -915,6 +985,7 @@ impl ThreadsDatabase {
                     id: key,
                     summary: value.summary,
                     updated_at: value.updated_at,
+                    forked_from: value.forked_from.map(|fork| fork.thread_id),
                 });
             }
 
@@ -76,7 +76,7 @@ impl Render for IncompatibleToolsTooltip {
                                 .map(|tool| h_flex().gap_4().child(Label::new(tool.name()).size(LabelSize::Small)).map(|parent|
                                     match tool.source() {
                                         ToolSource::Native => parent,
-                                        ToolSource::ContextServer { id } => parent.child(Label::new(id).size(LabelSize::Small).color(Color::Muted)),
+                                        ToolSource::ContextServer { id } | ToolSource::Extension { id } => parent.child(Label::new(id).size(LabelSize::Small).color(Color::Muted)),
                                     }
                                 )),
                         ),
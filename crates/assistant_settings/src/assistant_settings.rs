@@ -36,6 +36,58 @@ pub enum NotifyWhenAgentWaiting {
     Never,
 }
 
+/// How agent threads are kept within the context window of their model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContextCompactionSettings {
+    pub enabled: bool,
+    /// The fraction of the model's context window at which older messages are compacted.
+    pub threshold: f32,
+    pub strategy: ContextCompactionStrategy,
+    /// The number of most recent messages that are never compacted.
+    pub keep_recent_messages: usize,
+}
+
+impl Default for ContextCompactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 0.8,
+            strategy: ContextCompactionStrategy::default(),
+            keep_recent_messages: 4,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextCompactionStrategy {
+    /// Replace older messages with a summary written by the model.
+    #[default]
+    Summarize,
+    /// Leave older messages out of the request.
+    Truncate,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ContextCompactionSettingsContent {
+    /// Whether to compact older messages when a thread approaches the context window of its model.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The fraction of the model's context window at which older messages are compacted.
+    ///
+    /// Default: 0.8
+    pub threshold: Option<f32>,
+    /// How to compact older messages.
+    ///
+    /// Default: "summarize"
+    pub strategy: Option<ContextCompactionStrategy>,
+    /// The number of most recent messages that are never compacted.
+    ///
+    /// Default: 4
+    pub keep_recent_messages: Option<usize>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(tag = "name", rename_all = "snake_case")]
 pub enum AssistantProviderContentV1 {
@@ -89,6 +141,7 @@ pub struct AssistantSettings {
     pub notify_when_agent_waiting: NotifyWhenAgentWaiting,
     pub stream_edits: bool,
     pub single_file_review: bool,
+    pub context_compaction: ContextCompactionSettings,
 }
 
 impl Default for AssistantSettings {
@@ -112,6 +165,7 @@ impl Default for AssistantSettings {
             notify_when_agent_waiting: Default::default(),
             stream_edits: Default::default(),
             single_file_review: true,
+            context_compaction: Default::default(),
         }
     }
 }
@@ -251,6 +305,7 @@ impl AssistantSettingsContent {
                     notify_when_agent_waiting: None,
                     stream_edits: None,
                     single_file_review: None,
+                    context_compaction: None,
                 },
                 VersionedAssistantSettingsContent::V2(ref settings) => settings.clone(),
             },
@@ -280,6 +335,7 @@ impl AssistantSettingsContent {
                 notify_when_agent_waiting: None,
                 stream_edits: None,
                 single_file_review: None,
+                context_compaction: None,
             },
             None => AssistantSettingsContentV2::default(),
         }
@@ -532,6 +588,7 @@ impl Default for VersionedAssistantSettingsContent {
             notify_when_agent_waiting: None,
             stream_edits: None,
             single_file_review: None,
+            context_compaction: None,
         })
     }
 }
@@ -595,6 +652,8 @@ pub struct AssistantSettingsContentV2 {
     ///
     /// Default: true
     single_file_review: Option<bool>,
+    /// How to keep agent threads within the context window of their model.
+    context_compaction: Option<ContextCompactionSettingsContent>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -759,6 +818,16 @@ impl Settings for AssistantSettings {
             );
             merge(&mut settings.stream_edits, value.stream_edits);
             merge(&mut settings.single_file_review, value.single_file_review);
+            if let Some(context_compaction) = value.context_compaction {
+                let settings = &mut settings.context_compaction;
+                merge(&mut settings.enabled, context_compaction.enabled);
+                merge(&mut settings.threshold, context_compaction.threshold);
+                merge(&mut settings.strategy, context_compaction.strategy);
+                merge(
+                    &mut settings.keep_recent_messages,
+                    context_compaction.keep_recent_messages,
+                );
+            }
             merge(&mut settings.default_profile, value.default_profile);
 
             if let Some(profiles) = value.profiles {
@@ -892,6 +961,7 @@ mod tests {
                                 notify_when_agent_waiting: None,
                                 stream_edits: None,
                                 single_file_review: None,
+                                context_compaction: None,
                             },
                         )),
                     }
@@ -1,4 +1,5 @@
 mod action_log;
+mod extension_tool;
 pub mod outline;
 mod tool_registry;
 mod tool_schema;
@@ -23,12 +24,14 @@ use project::Project;
 use workspace::Workspace;
 
 pub use crate::action_log::*;
+pub use crate::extension_tool::*;
 pub use crate::tool_registry::*;
 pub use crate::tool_schema::*;
 pub use crate::tool_working_set::*;
 
 pub fn init(cx: &mut App) {
     ToolRegistry::default_global(cx);
+    extension_tool::init(cx);
 }
 
 #[derive(Debug, Clone)]
@@ -141,6 +144,8 @@ pub enum ToolSource {
     Native,
     /// A tool provided by a context server.
     ContextServer { id: SharedString },
+    /// A tool provided by an extension.
+    Extension { id: SharedString },
 }
 
 /// A tool that can be used by a language model.
@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use extension::{
+    AgentTool, Extension, ExtensionAgentToolProxy, ExtensionHostProxy, WorktreeDelegate,
+};
+use gpui::{AnyWindowHandle, App, Entity};
+use icons::IconName;
+use language::LspAdapterDelegate;
+use language_model::{LanguageModelRequestMessage, LanguageModelToolSchemaFormat};
+use project::Project;
+use project::lsp_store::LocalLspAdapterDelegate;
+use util::ResultExt as _;
+
+use crate::{ActionLog, Tool, ToolRegistry, ToolResult, ToolSource};
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_agent_tool_proxy(ToolRegistryProxy {
+        tool_registry: ToolRegistry::default_global(cx),
+    });
+}
+
+struct ToolRegistryProxy {
+    tool_registry: Arc<ToolRegistry>,
+}
+
+impl ExtensionAgentToolProxy for ToolRegistryProxy {
+    fn register_agent_tool(&self, extension: Arc<dyn Extension>, tool: AgentTool) {
+        let extension_id = extension.manifest().id.clone();
+        self.tool_registry
+            .try_register_tool(ExtensionTool::new(extension, tool))
+            .with_context(|| format!("failed to register a tool of extension {extension_id}"))
+            .log_err();
+    }
+
+    fn unregister_agent_tool(&self, extension_id: Arc<str>, tool_name: Arc<str>) {
+        self.tool_registry.unregister_tool_from_source(
+            &tool_name,
+            &ToolSource::Extension {
+                id: extension_id.into(),
+            },
+        );
+    }
+}
+
+/// An adapter that allows an [`LspAdapterDelegate`] to be used as a [`WorktreeDelegate`].
+struct WorktreeDelegateAdapter(Arc<dyn LspAdapterDelegate>);
+
+#[async_trait]
+impl WorktreeDelegate for WorktreeDelegateAdapter {
+    fn id(&self) -> u64 {
+        self.0.worktree_id().to_proto()
+    }
+
+    fn root_path(&self) -> String {
+        self.0.worktree_root_path().to_string_lossy().to_string()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        self.0.read_text_file(path).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        self.0
+            .which(binary_name.as_ref())
+            .await
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.0.shell_env().await.into_iter().collect()
+    }
+}
+
+/// A tool provided by an extension, which runs inside the extension's Wasm module.
+pub struct ExtensionTool {
+    extension: Arc<dyn Extension>,
+    tool: AgentTool,
+}
+
+impl ExtensionTool {
+    pub fn new(extension: Arc<dyn Extension>, tool: AgentTool) -> Self {
+        Self { extension, tool }
+    }
+}
+
+impl Tool for ExtensionTool {
+    fn name(&self) -> String {
+        self.tool.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.tool.description.clone()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Blocks
+    }
+
+    fn source(&self) -> ToolSource {
+        ToolSource::Extension {
+            id: self.extension.manifest().id.clone().into(),
+        }
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        true
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        let mut schema = self.tool.input_schema.clone();
+        crate::adapt_schema_to_format(&mut schema, format)?;
+        Ok(schema)
+    }
+
+    fn ui_text(&self, _input: &serde_json::Value) -> String {
+        format!(
+            "Run `{}` from the {} extension",
+            self.tool.name,
+            self.extension.manifest().name
+        )
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _messages: &[LanguageModelRequestMessage],
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let worktree = project.update(cx, |project, cx| {
+            let worktree = project.visible_worktrees(cx).next()?;
+            let http_client = project.client().http_client();
+            let delegate = project.lsp_store().update(cx, |_, cx| {
+                LocalLspAdapterDelegate::new(
+                    project.languages().clone(),
+                    project.environment(),
+                    cx.weak_entity(),
+                    &worktree,
+                    http_client,
+                    project.fs().clone(),
+                    cx,
+                )
+            });
+            Some(Arc::new(WorktreeDelegateAdapter(delegate)) as Arc<dyn WorktreeDelegate>)
+        });
+        let tool_name: Arc<str> = self.tool.name.clone().into();
+
+        cx.background_spawn(async move {
+            self.extension
+                .run_agent_tool(tool_name, input, worktree)
+                .await
+        })
+        .into()
+    }
+}
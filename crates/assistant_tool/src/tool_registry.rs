@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
+use anyhow::{Result, bail};
 use collections::HashMap;
 use derive_more::{Deref, DerefMut};
 use gpui::Global;
 use gpui::{App, ReadGlobal};
 use parking_lot::RwLock;
 
-use crate::Tool;
+use crate::{Tool, ToolSource};
 
 #[derive(Default, Deref, DerefMut)]
 struct GlobalToolRegistry(Arc<ToolRegistry>);
@@ -51,6 +52,20 @@ impl ToolRegistry {
         state.tools.insert(tool_name, Arc::new(tool));
     }
 
+    /// Registers the provided [`Tool`], unless a tool with the same name is already registered.
+    pub fn try_register_tool(&self, tool: impl Tool) -> Result<()> {
+        let mut state = self.state.write();
+        let tool_name: Arc<str> = tool.name().into();
+        if let Some(existing_tool) = state.tools.get(&tool_name) {
+            bail!(
+                "a tool named `{tool_name}` is already registered by {:?}",
+                existing_tool.source()
+            );
+        }
+        state.tools.insert(tool_name, Arc::new(tool));
+        Ok(())
+    }
+
     /// Unregisters the provided [`Tool`].
     pub fn unregister_tool(&self, tool: impl Tool) {
         self.unregister_tool_by_name(tool.name().as_str())
@@ -62,6 +77,18 @@ impl ToolRegistry {
         state.tools.remove(tool_name);
     }
 
+    /// Unregisters the tool with the given name, if it was registered from the given source.
+    pub fn unregister_tool_from_source(&self, tool_name: &str, source: &ToolSource) {
+        let mut state = self.state.write();
+        if state
+            .tools
+            .get(tool_name)
+            .is_some_and(|tool| &tool.source() == source)
+        {
+            state.tools.remove(tool_name);
+        }
+    }
+
     /// Returns the list of tools in the registry.
     pub fn tools(&self) -> Vec<Arc<dyn Tool>> {
         self.state.read().tools.values().cloned().collect()
@@ -72,3 +99,111 @@ impl ToolRegistry {
         self.state.read().tools.get(name).cloned()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{AnyWindowHandle, App, Entity};
+    use icons::IconName;
+    use language_model::LanguageModelRequestMessage;
+    use project::Project;
+
+    use crate::{ActionLog, ToolResult};
+
+    use super::*;
+
+    struct TestTool {
+        name: &'static str,
+        source: ToolSource,
+    }
+
+    impl Tool for TestTool {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn description(&self) -> String {
+            String::new()
+        }
+
+        fn icon(&self) -> IconName {
+            IconName::Blocks
+        }
+
+        fn source(&self) -> ToolSource {
+            self.source.clone()
+        }
+
+        fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+            false
+        }
+
+        fn ui_text(&self, _: &serde_json::Value) -> String {
+            String::new()
+        }
+
+        fn run(
+            self: Arc<Self>,
+            _: serde_json::Value,
+            _: &[LanguageModelRequestMessage],
+            _: Entity<Project>,
+            _: Entity<ActionLog>,
+            _: Option<AnyWindowHandle>,
+            _: &mut App,
+        ) -> ToolResult {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_extension_tool_name_collision() {
+        let registry = ToolRegistry::new();
+        let extension = ToolSource::Extension {
+            id: "my-extension".into(),
+        };
+        let other_extension = ToolSource::Extension {
+            id: "other-extension".into(),
+        };
+        registry.register_tool(TestTool {
+            name: "terminal",
+            source: ToolSource::Native,
+        });
+
+        // An extension can't replace a native tool, nor a tool of another extension.
+        assert!(
+            registry
+                .try_register_tool(TestTool {
+                    name: "terminal",
+                    source: extension.clone(),
+                })
+                .is_err()
+        );
+        registry
+            .try_register_tool(TestTool {
+                name: "search",
+                source: extension.clone(),
+            })
+            .unwrap();
+        assert!(
+            registry
+                .try_register_tool(TestTool {
+                    name: "search",
+                    source: other_extension.clone(),
+                })
+                .is_err()
+        );
+        assert_eq!(
+            registry.tool("terminal").unwrap().source(),
+            ToolSource::Native
+        );
+        assert_eq!(registry.tool("search").unwrap().source(), extension);
+
+        // Unloading an extension only removes the tools it registered.
+        registry.unregister_tool_from_source("terminal", &extension);
+        registry.unregister_tool_from_source("search", &other_extension);
+        assert!(registry.tool("terminal").is_some());
+        assert!(registry.tool("search").is_some());
+
+        registry.unregister_tool_from_source("search", &extension);
+        assert!(registry.tool("search").is_none());
+    }
+}
@@ -103,6 +103,11 @@ impl ToolWorkingSet {
     }
 
     pub fn is_enabled(&self, source: &ToolSource, name: &Arc<str>) -> bool {
+        // Extension tools are enabled by name in the profile, alongside the native tools.
+        let source = match source {
+            ToolSource::Extension { .. } => &ToolSource::Native,
+            source => source,
+        };
         self.enabled_tools_by_source
             .get(source)
             .map_or(false, |enabled_tools| enabled_tools.contains(name))
@@ -7,7 +7,8 @@ use anyhow::{Error, Result, anyhow};
 use aws_sdk_bedrockruntime as bedrock;
 pub use aws_sdk_bedrockruntime as bedrock_client;
 pub use aws_sdk_bedrockruntime::types::{
-    AutoToolChoice as BedrockAutoToolChoice, ContentBlock as BedrockInnerContent,
+    AutoToolChoice as BedrockAutoToolChoice, CachePointBlock as BedrockCachePointBlock,
+    CachePointType as BedrockCachePointType, ContentBlock as BedrockInnerContent,
     Tool as BedrockTool, ToolChoice as BedrockToolChoice, ToolConfiguration as BedrockToolConfig,
     ToolInputSchema as BedrockToolInputSchema, ToolSpecification as BedrockToolSpec,
 };
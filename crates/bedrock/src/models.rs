@@ -266,6 +266,15 @@ impl Model {
         }
     }
 
+    /// Returns whether the model supports prompt caching through cache points.
+    pub fn supports_caching(&self) -> bool {
+        match self {
+            Self::Claude3_7Sonnet | Self::Claude3_7SonnetThinking | Self::Claude3_5Haiku => true,
+            Self::AmazonNovaPro | Self::AmazonNovaLite | Self::AmazonNovaMicro => true,
+            _ => false,
+        }
+    }
+
     pub fn mode(&self) -> BedrockModelMode {
         match self {
             Model::Claude3_7SonnetThinking => BedrockModelMode::Thinking {
@@ -2,9 +2,11 @@ use futures::channel::oneshot;
 use git2::{DiffLineType as GitDiffLineType, DiffOptions as GitOptions, Patch as GitPatch};
 use gpui::{App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Task, TaskLabel};
 use language::{Language, LanguageRegistry};
+use parking_lot::Mutex;
 use rope::Rope;
 use std::{
     cmp::Ordering,
+    collections::{BTreeMap, HashMap},
     future::Future,
     iter,
     ops::Range,
@@ -14,6 +16,8 @@ use sum_tree::SumTree;
 use text::{Anchor, Bias, BufferId, OffsetRangeExt, Point, ToOffset as _};
 use util::ResultExt;
 
+mod word_diff;
+
 pub static CALCULATE_DIFF_TASK: LazyLock<TaskLabel> = LazyLock::new(TaskLabel::new);
 
 pub struct BufferDiff {
@@ -34,12 +38,20 @@ struct BufferDiffInner {
     pending_hunks: SumTree<PendingHunk>,
     base_text: language::BufferSnapshot,
     base_text_exists: bool,
+    word_diffs: WordDiffCache,
 }
 
+/// Word diffs are only computed when a hunk is displayed, and are cached by the
+/// hunk's base text range along with the buffer version they were computed for.
+type WordDiffCache = Arc<Mutex<HashMap<Range<usize>, (clock::Global, Option<Arc<WordDiff>>)>>>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct DiffHunkStatus {
     pub kind: DiffHunkStatusKind,
     pub secondary: DiffHunkSecondaryStatus,
+    /// Whether this hunk's lines were moved here from, or moved from here to,
+    /// another hunk without being otherwise changed.
+    pub moved: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -68,6 +80,18 @@ pub struct DiffHunk {
     /// The range in the buffer's diff base text to which this hunk corresponds.
     pub diff_base_byte_range: Range<usize>,
     pub secondary_status: DiffHunkSecondaryStatus,
+    /// Whether this is a pure deletion or insertion whose lines match those of
+    /// another hunk, i.e. one side of a moved block.
+    pub moved: bool,
+}
+
+/// The word-level changes within a modified hunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordDiff {
+    /// Byte ranges of the changed words in the diff base text.
+    pub base_text_ranges: Vec<Range<usize>>,
+    /// Byte ranges of the changed words in the buffer.
+    pub buffer_ranges: Vec<Range<usize>>,
 }
 
 /// We store [`InternalDiffHunk`]s internally so we don't need to store the additional row range.
@@ -75,6 +99,7 @@ pub struct DiffHunk {
 struct InternalDiffHunk {
     buffer_range: Range<Anchor>,
     diff_base_byte_range: Range<usize>,
+    moved: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -157,6 +182,7 @@ impl BufferDiffSnapshot {
                 hunks: SumTree::new(buffer),
                 pending_hunks: SumTree::new(buffer),
                 base_text_exists: false,
+                word_diffs: Default::default(),
             },
             secondary_diff: None,
         }
@@ -204,6 +230,7 @@ impl BufferDiffSnapshot {
                     hunks,
                     base_text_exists,
                     pending_hunks: SumTree::new(&buffer),
+                    word_diffs: Default::default(),
                 },
                 secondary_diff: None,
             }
@@ -226,6 +253,7 @@ impl BufferDiffSnapshot {
                         pending_hunks: SumTree::new(&buffer),
                         hunks: compute_hunks(base_text_pair, buffer),
                         base_text_exists,
+                        word_diffs: Default::default(),
                     },
                     secondary_diff: None,
                 }
@@ -269,6 +297,16 @@ impl BufferDiffSnapshot {
         self.inner.hunks_intersecting_range_rev(range, buffer)
     }
 
+    /// Returns the word-level changes within a modified hunk, computing them
+    /// on first use.
+    pub fn word_diff(
+        &self,
+        hunk: &DiffHunk,
+        buffer: &text::BufferSnapshot,
+    ) -> Option<Arc<WordDiff>> {
+        self.inner.word_diff(hunk, buffer)
+    }
+
     pub fn base_text(&self) -> &language::BufferSnapshot {
         &self.inner.base_text
     }
@@ -286,6 +324,50 @@ impl BufferDiffSnapshot {
 }
 
 impl BufferDiffInner {
+    fn word_diff(&self, hunk: &DiffHunk, buffer: &text::BufferSnapshot) -> Option<Arc<WordDiff>> {
+        if !hunk.status().is_modified() {
+            return None;
+        }
+
+        let mut word_diffs = self.word_diffs.lock();
+        if let Some((version, word_diff)) = word_diffs.get(&hunk.diff_base_byte_range) {
+            if version == buffer.version() {
+                return word_diff.clone();
+            }
+        }
+
+        let buffer_range = hunk.buffer_range.to_offset(buffer);
+        let base_text = self
+            .base_text
+            .text_for_range(hunk.diff_base_byte_range.clone())
+            .collect::<String>();
+        let buffer_text = buffer
+            .text_for_range(buffer_range.clone())
+            .collect::<String>();
+        let word_diff = word_diff::word_diff(&base_text, &buffer_text).map(
+            |(base_text_ranges, buffer_ranges)| {
+                let base_start = hunk.diff_base_byte_range.start;
+                Arc::new(WordDiff {
+                    base_text_ranges: base_text_ranges
+                        .into_iter()
+                        .map(|range| base_start + range.start..base_start + range.end)
+                        .collect(),
+                    buffer_ranges: buffer_ranges
+                        .into_iter()
+                        .map(|range| {
+                            buffer_range.start + range.start..buffer_range.start + range.end
+                        })
+                        .collect(),
+                })
+            },
+        );
+        word_diffs.insert(
+            hunk.diff_base_byte_range.clone(),
+            (buffer.version().clone(), word_diff.clone()),
+        );
+        word_diff
+    }
+
     /// Returns the new index text and new pending hunks.
     fn stage_or_unstage_hunks_impl(
         &mut self,
@@ -496,6 +578,89 @@ impl BufferDiffInner {
         Some(new_index_text)
     }
 
+    /// Returns the new index text after staging or unstaging individual lines
+    /// of the given hunks.
+    ///
+    /// Added lines are selected by `buffer_ranges`, and deleted lines by
+    /// `base_text_ranges`, which are offsets into the base text.
+    fn stage_or_unstage_lines_impl(
+        &self,
+        unstaged_diff: &Self,
+        stage: bool,
+        hunks: &[DiffHunk],
+        buffer_ranges: &[Range<usize>],
+        base_text_ranges: &[Range<usize>],
+        buffer: &text::BufferSnapshot,
+    ) -> Option<Rope> {
+        let head_text = self.base_text.as_rope();
+        let index_text = unstaged_diff.base_text.as_rope();
+
+        // Grow each hunk into a region of the buffer whose bounds aren't
+        // touched by either diff, so that it corresponds to a contiguous
+        // range of both HEAD and the index.
+        let mut regions = Vec::<Range<usize>>::new();
+        for hunk in hunks {
+            let mut region = hunk.buffer_range.to_offset(buffer);
+            while expand_to_hunks(&mut region, &self.hunks, buffer)
+                | expand_to_hunks(&mut region, &unstaged_diff.hunks, buffer)
+            {}
+            regions.push(region);
+        }
+        regions.sort_unstable_by_key(|region| region.start);
+        regions.dedup_by(|next, prev| {
+            if next.start <= prev.end {
+                prev.end = prev.end.max(next.end);
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut edits = Vec::new();
+        for region in regions {
+            let head_range = base_text_range(&self.hunks, &region, buffer);
+            let index_range = base_text_range(&unstaged_diff.hunks, &region, buffer);
+            let head = head_text
+                .chunks_in_range(head_range.clone())
+                .collect::<String>();
+            let index = index_text
+                .chunks_in_range(index_range.clone())
+                .collect::<String>();
+            let buffer_region = buffer.text_for_range(region.clone()).collect::<String>();
+
+            let new_index = stage_or_unstage_region_lines(
+                stage,
+                LinesInRange::new(&head, head_range.start),
+                &index,
+                LinesInRange::new(&buffer_region, region.start),
+                buffer_ranges,
+                base_text_ranges,
+            );
+            match new_index {
+                Some(new_index) => edits.push((index_range, new_index)),
+                None => log::warn!(
+                    "index diverges from both HEAD and the buffer in {region:?}, leaving it as is"
+                ),
+            }
+        }
+
+        let mut new_index_text = Rope::new();
+        let mut index_cursor = index_text.cursor(0);
+        for (old_range, replacement_text) in edits {
+            new_index_text.append(index_cursor.slice(old_range.start));
+            index_cursor.seek_forward(old_range.end);
+            new_index_text.push(&replacement_text);
+        }
+        new_index_text.append(index_cursor.suffix());
+
+        // A file that doesn't exist in HEAD leaves the index once all of its lines are unstaged.
+        if !self.base_text_exists && new_index_text.len() == 0 {
+            None
+        } else {
+            Some(new_index_text)
+        }
+    }
+
     fn hunks_intersecting_range<'a>(
         &'a self,
         range: Range<Anchor>,
@@ -521,11 +686,19 @@ impl BufferDiffInner {
             [
                 (
                     &hunk.buffer_range.start,
-                    (hunk.buffer_range.start, hunk.diff_base_byte_range.start),
+                    (
+                        hunk.buffer_range.start,
+                        hunk.diff_base_byte_range.start,
+                        hunk.moved,
+                    ),
                 ),
                 (
                     &hunk.buffer_range.end,
-                    (hunk.buffer_range.end, hunk.diff_base_byte_range.end),
+                    (
+                        hunk.buffer_range.end,
+                        hunk.diff_base_byte_range.end,
+                        hunk.moved,
+                    ),
                 ),
             ]
         });
@@ -544,8 +717,8 @@ impl BufferDiffInner {
         let mut summaries = buffer.summaries_for_anchors_with_payload::<Point, _, _>(anchor_iter);
         iter::from_fn(move || {
             loop {
-                let (start_point, (start_anchor, start_base)) = summaries.next()?;
-                let (mut end_point, (mut end_anchor, end_base)) = summaries.next()?;
+                let (start_point, (start_anchor, start_base, moved)) = summaries.next()?;
+                let (mut end_point, (mut end_anchor, end_base, _)) = summaries.next()?;
 
                 if !start_anchor.is_valid(buffer) {
                     continue;
@@ -616,6 +789,7 @@ impl BufferDiffInner {
                     diff_base_byte_range: start_base..end_base,
                     buffer_range: start_anchor..end_anchor,
                     secondary_status,
+                    moved,
                 });
             }
         })
@@ -646,6 +820,7 @@ impl BufferDiffInner {
                 buffer_range: hunk.buffer_range.clone(),
                 // The secondary status is not used by callers of this method.
                 secondary_status: DiffHunkSecondaryStatus::NoSecondaryHunk,
+                moved: hunk.moved,
             })
         })
     }
@@ -742,6 +917,7 @@ fn compute_hunks(
                 InternalDiffHunk {
                     buffer_range: buffer.anchor_before(0)..buffer.anchor_before(0),
                     diff_base_byte_range: 0..diff_base.len() - 1,
+                    moved: false,
                 },
                 &buffer,
             );
@@ -750,22 +926,26 @@ fn compute_hunks(
 
         if let Some(patch) = patch {
             let mut divergence = 0;
-            for hunk_index in 0..patch.num_hunks() {
-                let hunk = process_patch_hunk(
-                    &patch,
-                    hunk_index,
-                    &diff_base_rope,
-                    &buffer,
-                    &mut divergence,
-                );
-                tree.push(hunk, &buffer);
-            }
+            let mut hunks = (0..patch.num_hunks())
+                .map(|hunk_index| {
+                    process_patch_hunk(
+                        &patch,
+                        hunk_index,
+                        &diff_base_rope,
+                        &buffer,
+                        &mut divergence,
+                    )
+                })
+                .collect::<Vec<_>>();
+            mark_moved_hunks(&mut hunks, &diff_base, &buffer);
+            tree.extend(hunks, &buffer);
         }
     } else {
         tree.push(
             InternalDiffHunk {
                 buffer_range: Anchor::MIN..Anchor::MAX,
                 diff_base_byte_range: 0..0,
+                moved: false,
             },
             &buffer,
         );
@@ -847,7 +1027,327 @@ fn process_patch_hunk(
     InternalDiffHunk {
         buffer_range,
         diff_base_byte_range,
+        moved: false,
+    }
+}
+
+/// Pairs up pure deletions with pure insertions of exactly the same lines, and
+/// marks both sides as moved. Lines are compared including their whitespace, so
+/// that re-indented blocks, which may change behavior, still show up as changes.
+fn mark_moved_hunks(
+    hunks: &mut [InternalDiffHunk],
+    diff_base: &str,
+    buffer: &text::BufferSnapshot,
+) {
+    let mut deletions = HashMap::<String, Vec<usize>>::default();
+    for (ix, hunk) in hunks.iter().enumerate() {
+        if hunk.buffer_range.start == hunk.buffer_range.end {
+            if let Some(key) = moved_block_key(&diff_base[hunk.diff_base_byte_range.clone()]) {
+                deletions.entry(key).or_default().push(ix);
+            }
+        }
+    }
+    if deletions.is_empty() {
+        return;
+    }
+
+    for ix in 0..hunks.len() {
+        if !hunks[ix].diff_base_byte_range.is_empty()
+            || hunks[ix].buffer_range.start == hunks[ix].buffer_range.end
+        {
+            continue;
+        }
+        let text = buffer
+            .text_for_range(hunks[ix].buffer_range.clone())
+            .collect::<String>();
+        let Some(key) = moved_block_key(&text) else {
+            continue;
+        };
+        if let Some(deletion_ix) = deletions.get_mut(&key).and_then(|ixs| ixs.pop()) {
+            hunks[deletion_ix].moved = true;
+            hunks[ix].moved = true;
+        }
+    }
+}
+
+/// Blocks with fewer non-blank lines than this are too common (closing braces,
+/// blank lines, etc.) to be reported as moves.
+const MIN_MOVED_BLOCK_LINES: usize = 2;
+
+fn moved_block_key(text: &str) -> Option<String> {
+    let mut key = String::with_capacity(text.len());
+    let mut non_blank_lines = 0;
+    for line in text.lines() {
+        if !line.trim().is_empty() {
+            non_blank_lines += 1;
+        }
+        key.push_str(line);
+        key.push('\n');
     }
+    (non_blank_lines >= MIN_MOVED_BLOCK_LINES).then_some(key)
+}
+
+/// Grows `region` to cover all the hunks that intersect or touch it.
+/// Returns whether it changed.
+fn expand_to_hunks(
+    region: &mut Range<usize>,
+    hunks: &SumTree<InternalDiffHunk>,
+    buffer: &text::BufferSnapshot,
+) -> bool {
+    let mut changed = false;
+    for hunk in hunks.iter() {
+        let hunk_range = hunk.buffer_range.to_offset(buffer);
+        if hunk_range.start > region.end {
+            break;
+        }
+        if hunk_range.end >= region.start
+            && (hunk_range.start < region.start || hunk_range.end > region.end)
+        {
+            region.start = region.start.min(hunk_range.start);
+            region.end = region.end.max(hunk_range.end);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Maps a region of the buffer, whose bounds aren't touched by any hunk, to
+/// the corresponding range of the base text.
+fn base_text_range(
+    hunks: &SumTree<InternalDiffHunk>,
+    region: &Range<usize>,
+    buffer: &text::BufferSnapshot,
+) -> Range<usize> {
+    let mut start = region.start;
+    let mut end = region.end;
+    for hunk in hunks.iter() {
+        let hunk_range = hunk.buffer_range.to_offset(buffer);
+        if hunk_range.end < region.start {
+            start = region.start - hunk_range.end + hunk.diff_base_byte_range.end;
+        }
+        if hunk_range.end <= region.end {
+            end = region.end - hunk_range.end + hunk.diff_base_byte_range.end;
+        } else {
+            break;
+        }
+    }
+    start..end
+}
+
+/// The lines of a range of some text, along with their offsets in that text.
+struct LinesInRange<'a> {
+    text: &'a str,
+    lines: Vec<(Range<usize>, &'a str)>,
+}
+
+impl<'a> LinesInRange<'a> {
+    fn new(text: &'a str, start_offset: usize) -> Self {
+        let mut offset = start_offset;
+        let lines = text
+            .split_inclusive('\n')
+            .map(|line| {
+                let range = offset..offset + line.len();
+                offset = range.end;
+                (range, line)
+            })
+            .collect();
+        Self { text, lines }
+    }
+
+    fn is_selected(&self, line_ix: usize, selections: &[Range<usize>]) -> bool {
+        let (range, line) = &self.lines[line_ix];
+        let content_end = range.start + line.trim_end_matches('\n').len();
+        selections.iter().any(|selection| {
+            selection.start <= content_end
+                && selection.end >= range.start
+                && !(selection.end == range.start && selection.start < selection.end)
+        })
+    }
+
+    /// The offset at which the given line starts, or where the range ends.
+    fn offset(&self, line_ix: usize) -> usize {
+        match self.lines.get(line_ix) {
+            Some((range, _)) => range.start,
+            None => self.lines.last().map_or(0, |(range, _)| range.end),
+        }
+    }
+}
+
+/// A change between HEAD and the buffer, replacing lines `old` of HEAD with
+/// lines `new` of the buffer, along with which of its lines are in the index.
+#[derive(Debug)]
+struct LineChange {
+    old: Range<usize>,
+    new: Range<usize>,
+    deleted: Vec<bool>,
+    inserted: Vec<bool>,
+}
+
+/// Computes the new contents of a region of the index, after staging or
+/// unstaging the selected lines. Returns `None` when the index contains
+/// changes that aren't between HEAD and the buffer, and can't be updated line
+/// by line.
+fn stage_or_unstage_region_lines(
+    stage: bool,
+    head: LinesInRange,
+    index: &str,
+    buffer: LinesInRange,
+    buffer_selections: &[Range<usize>],
+    base_text_selections: &[Range<usize>],
+) -> Option<String> {
+    let index_lines = index.split_inclusive('\n').collect::<Vec<_>>();
+    let index_edits = line_edits(head.text, index)?;
+    let mut deleted_in_index = vec![false; head.lines.len()];
+    let mut inserted_in_index = BTreeMap::<usize, &[&str]>::new();
+    for (old, new) in index_edits {
+        for line_ix in old.clone() {
+            deleted_in_index[line_ix] = true;
+        }
+        inserted_in_index.insert(old.end, &index_lines[new]);
+    }
+
+    // Find which lines of each change between HEAD and the buffer are already staged.
+    let mut changes = line_edits(head.text, buffer.text)?
+        .into_iter()
+        .map(|(old, new)| {
+            let mut index_insertions = inserted_in_index
+                .get(&old.end)
+                .copied()
+                .unwrap_or_default()
+                .iter()
+                .peekable();
+            LineChange {
+                deleted: old.clone().map(|ix| deleted_in_index[ix]).collect(),
+                inserted: new
+                    .clone()
+                    .map(|ix| {
+                        index_insertions
+                            .next_if(|line| **line == buffer.lines[ix].1)
+                            .is_some()
+                    })
+                    .collect(),
+                old,
+                new,
+            }
+        })
+        .collect::<Vec<_>>();
+    if apply_line_changes(&head, &buffer, &changes) != index {
+        return None;
+    }
+
+    for change in &mut changes {
+        let mut deleted_selected = change
+            .old
+            .clone()
+            .map(|ix| head.is_selected(ix, base_text_selections))
+            .collect::<Vec<_>>();
+        let inserted_selected = change
+            .new
+            .clone()
+            .map(|ix| buffer.is_selected(ix, buffer_selections))
+            .collect::<Vec<_>>();
+
+        // Unless some of the deleted lines were picked, they go along with
+        // the lines replacing them.
+        if !deleted_selected.contains(&true) {
+            let replaced = if change.new.is_empty() {
+                let offset = buffer.offset(change.new.start);
+                buffer_selections
+                    .iter()
+                    .any(|selection| selection.start <= offset && selection.end >= offset)
+            } else {
+                !inserted_selected.contains(&false)
+            };
+            if replaced {
+                deleted_selected.fill(true);
+            }
+        }
+
+        for (staged, selected) in change.deleted.iter_mut().zip(deleted_selected) {
+            if selected {
+                *staged = stage;
+            }
+        }
+        for (staged, selected) in change.inserted.iter_mut().zip(inserted_selected) {
+            if selected {
+                *staged = stage;
+            }
+        }
+    }
+
+    Some(apply_line_changes(&head, &buffer, &changes))
+}
+
+/// Applies the staged lines of each change to HEAD.
+fn apply_line_changes(
+    head: &LinesInRange,
+    buffer: &LinesInRange,
+    changes: &[LineChange],
+) -> String {
+    fn push_line(text: &mut String, line: &str) {
+        // A line that was last in its file may end up in the middle.
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(line);
+    }
+
+    let mut text = String::new();
+    let mut head_ix = 0;
+    for change in changes {
+        for (_, line) in &head.lines[head_ix..change.old.start] {
+            push_line(&mut text, line);
+        }
+        for (line_ix, deleted) in change.old.clone().zip(&change.deleted) {
+            if !deleted {
+                push_line(&mut text, head.lines[line_ix].1);
+            }
+        }
+        for (line_ix, inserted) in change.new.clone().zip(&change.inserted) {
+            if *inserted {
+                push_line(&mut text, buffer.lines[line_ix].1);
+            }
+        }
+        head_ix = change.old.end;
+    }
+    for (_, line) in &head.lines[head_ix..] {
+        push_line(&mut text, line);
+    }
+    text
+}
+
+/// Computes the lines replaced between two texts, as pairs of line ranges.
+fn line_edits(old: &str, new: &str) -> Option<Vec<(Range<usize>, Range<usize>)>> {
+    let mut options = GitOptions::default();
+    options.context_lines(0);
+    let patch = GitPatch::from_buffers(
+        old.as_bytes(),
+        None,
+        new.as_bytes(),
+        None,
+        Some(&mut options),
+    )
+    .log_err()?;
+
+    // Hunks that don't remove or add lines start at the line before them.
+    let start = |start: u32, len: u32| {
+        if len == 0 {
+            start as usize
+        } else {
+            start as usize - 1
+        }
+    };
+    (0..patch.num_hunks())
+        .map(|hunk_ix| {
+            let (hunk, _) = patch.hunk(hunk_ix).log_err()?;
+            let old_start = start(hunk.old_start(), hunk.old_lines());
+            let new_start = start(hunk.new_start(), hunk.new_lines());
+            Some((
+                old_start..old_start + hunk.old_lines() as usize,
+                new_start..new_start + hunk.new_lines() as usize,
+            ))
+        })
+        .collect()
 }
 
 impl std::fmt::Debug for BufferDiff {
@@ -947,6 +1447,39 @@ impl BufferDiff {
         new_index_text
     }
 
+    /// Stages or unstages individual lines of the given hunks, rather than
+    /// whole hunks. Added lines are selected by ranges of the buffer, and
+    /// deleted lines by ranges of the base text.
+    pub fn stage_or_unstage_lines(
+        &mut self,
+        stage: bool,
+        hunks: &[DiffHunk],
+        buffer_ranges: &[Range<usize>],
+        base_text_ranges: &[Range<usize>],
+        buffer: &text::BufferSnapshot,
+        cx: &mut Context<Self>,
+    ) -> Option<Rope> {
+        let new_index_text = self.inner.stage_or_unstage_lines_impl(
+            &self.secondary_diff.as_ref()?.read(cx).inner,
+            stage,
+            hunks,
+            buffer_ranges,
+            base_text_ranges,
+            buffer,
+        );
+
+        cx.emit(BufferDiffEvent::HunksStagedOrUnstaged(
+            new_index_text.clone(),
+        ));
+        if let Some((first, last)) = hunks.first().zip(hunks.last()) {
+            let changed_range = first.buffer_range.start..last.buffer_range.end;
+            cx.emit(BufferDiffEvent::DiffChanged {
+                changed_range: Some(changed_range),
+            });
+        }
+        new_index_text
+    }
+
     pub fn range_to_hunk_range(
         &self,
         range: Range<Anchor>,
@@ -1119,6 +1652,14 @@ impl BufferDiff {
             .hunks_intersecting_range_rev(range, buffer_snapshot)
     }
 
+    pub fn word_diff(
+        &self,
+        hunk: &DiffHunk,
+        buffer: &text::BufferSnapshot,
+    ) -> Option<Arc<WordDiff>> {
+        self.inner.word_diff(hunk, buffer)
+    }
+
     pub fn hunks_in_row_range<'a>(
         &'a self,
         range: Range<u32>,
@@ -1215,6 +1756,7 @@ impl DiffHunk {
         DiffHunkStatus {
             kind,
             secondary: self.secondary_status,
+            moved: self.moved,
         }
     }
 }
@@ -1249,10 +1791,15 @@ impl DiffHunkStatus {
         self.kind == DiffHunkStatusKind::Modified
     }
 
+    pub fn is_moved(&self) -> bool {
+        self.moved
+    }
+
     pub fn added(secondary: DiffHunkSecondaryStatus) -> Self {
         Self {
             kind: DiffHunkStatusKind::Added,
             secondary,
+            moved: false,
         }
     }
 
@@ -1260,6 +1807,7 @@ impl DiffHunkStatus {
         Self {
             kind: DiffHunkStatusKind::Modified,
             secondary,
+            moved: false,
         }
     }
 
@@ -1267,6 +1815,7 @@ impl DiffHunkStatus {
         Self {
             kind: DiffHunkStatusKind::Deleted,
             secondary,
+            moved: false,
         }
     }
 
@@ -1274,6 +1823,7 @@ impl DiffHunkStatus {
         Self {
             kind: DiffHunkStatusKind::Deleted,
             secondary: DiffHunkSecondaryStatus::NoSecondaryHunk,
+            moved: false,
         }
     }
 
@@ -1281,6 +1831,7 @@ impl DiffHunkStatus {
         Self {
             kind: DiffHunkStatusKind::Added,
             secondary: DiffHunkSecondaryStatus::NoSecondaryHunk,
+            moved: false,
         }
     }
 
@@ -1288,6 +1839,7 @@ impl DiffHunkStatus {
         Self {
             kind: DiffHunkStatusKind::Modified,
             secondary: DiffHunkSecondaryStatus::NoSecondaryHunk,
+            moved: false,
         }
     }
 }
@@ -1397,6 +1949,129 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_moved_hunks_and_word_diff(cx: &mut gpui::TestAppContext) {
+        let diff_base = "
+            fn a() {
+                one();
+                two();
+                three();
+            }
+            fn b() {
+                four();
+            }
+            // end
+            let x = 1;
+        "
+        .unindent();
+
+        let buffer_text = "
+            fn b() {
+                four();
+            }
+            fn a() {
+                one();
+                two();
+                three();
+            }
+            // end
+            let x = 2;
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text);
+        let diff = BufferDiffSnapshot::new_sync(buffer.clone(), diff_base.clone(), cx);
+        let moved_block = "fn b() {\n    four();\n}\n";
+        assert_hunks(
+            diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &buffer),
+            &buffer,
+            &diff_base,
+            &[
+                (
+                    0..3,
+                    "",
+                    moved_block,
+                    DiffHunkStatus {
+                        moved: true,
+                        ..DiffHunkStatus::added_none()
+                    },
+                ),
+                (
+                    8..8,
+                    moved_block,
+                    "",
+                    DiffHunkStatus {
+                        moved: true,
+                        ..DiffHunkStatus::deleted_none()
+                    },
+                ),
+                (
+                    9..10,
+                    "let x = 1;\n",
+                    "let x = 2;\n",
+                    DiffHunkStatus::modified_none(),
+                ),
+            ],
+        );
+
+        let hunks = diff
+            .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &buffer)
+            .collect::<Vec<_>>();
+        assert_eq!(diff.word_diff(&hunks[0], &buffer), None);
+        let word_diff = diff.word_diff(&hunks[2], &buffer).unwrap();
+        assert_eq!(
+            word_diff
+                .base_text_ranges
+                .iter()
+                .map(|range| &diff_base[range.clone()])
+                .collect::<Vec<_>>(),
+            ["1"]
+        );
+        assert_eq!(
+            word_diff
+                .buffer_ranges
+                .iter()
+                .map(|range| buffer.text_for_range(range.clone()).collect::<String>())
+                .collect::<Vec<_>>(),
+            ["2"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_reindented_block_is_not_moved(cx: &mut gpui::TestAppContext) {
+        let diff_base = "
+            one();
+            two();
+            fn main() {
+            }
+        "
+        .unindent();
+        let buffer_text = "
+            fn main() {
+                one();
+                two();
+            }
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text);
+        let diff = BufferDiffSnapshot::new_sync(buffer.clone(), diff_base.clone(), cx);
+        assert_hunks(
+            diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &buffer),
+            &buffer,
+            &diff_base,
+            &[
+                (0..0, "one();\ntwo();\n", "", DiffHunkStatus::deleted_none()),
+                (
+                    1..3,
+                    "",
+                    "    one();\n    two();\n",
+                    DiffHunkStatus::added_none(),
+                ),
+            ],
+        );
+    }
+
     #[gpui::test]
     async fn test_buffer_diff_with_secondary(cx: &mut gpui::TestAppContext) {
         let head_text = "
@@ -1891,6 +2566,114 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_stage_and_unstage_lines(cx: &mut TestAppContext) {
+        let head_text = "
+            one
+            two
+            three
+            four
+        "
+        .unindent();
+        let index_text = head_text.clone();
+        let (buffer_text, selections) = marked_text_ranges(
+            &"
+            one
+            TWO
+            «TWO_AND_A_HALF»
+            three
+            ˇFOUR_HUNDRED
+            FIVE_HUNDRED
+            "
+            .unindent(),
+            false,
+        );
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text.clone());
+        let unstaged = BufferDiffSnapshot::new_sync(buffer.clone(), index_text, cx);
+        let uncommitted = BufferDiffSnapshot::new_sync(buffer.clone(), head_text.clone(), cx);
+        let unstaged_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&buffer, cx);
+            diff.set_snapshot(unstaged, &buffer, cx);
+            diff
+        });
+        let uncommitted_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&buffer, cx);
+            diff.set_snapshot(uncommitted, &buffer, cx);
+            diff.set_secondary_diff(unstaged_diff.clone());
+            diff
+        });
+
+        // Lines added next to a deletion are staged without it, unless all of
+        // the lines replacing it are selected.
+        let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+            let hunks = diff.hunks(&buffer, cx).collect::<Vec<_>>();
+            diff.stage_or_unstage_lines(true, &hunks, &selections, &[], &buffer, cx)
+                .unwrap()
+                .to_string()
+        });
+        assert_eq!(
+            new_index_text,
+            "
+            one
+            two
+            TWO_AND_A_HALF
+            three
+            four
+            FOUR_HUNDRED
+            "
+            .unindent()
+        );
+
+        // Deleted lines can be picked on their own, by their offsets in HEAD.
+        let unstaged = BufferDiffSnapshot::new_sync(buffer.clone(), new_index_text, cx);
+        unstaged_diff.update(cx, |diff, cx| diff.set_snapshot(unstaged, &buffer, cx));
+        let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+            let hunks = diff.hunks(&buffer, cx).collect::<Vec<_>>();
+            let two_offset = head_text.find("two").unwrap();
+            let four_offset = head_text.find("four").unwrap();
+            diff.stage_or_unstage_lines(
+                true,
+                &hunks,
+                &[],
+                &[two_offset..two_offset, four_offset..four_offset],
+                &buffer,
+                cx,
+            )
+            .unwrap()
+            .to_string()
+        });
+        assert_eq!(
+            new_index_text,
+            "
+            one
+            TWO_AND_A_HALF
+            three
+            FOUR_HUNDRED
+            "
+            .unindent()
+        );
+
+        // Unstaging a line leaves the other staged lines alone.
+        let unstaged = BufferDiffSnapshot::new_sync(buffer.clone(), new_index_text, cx);
+        unstaged_diff.update(cx, |diff, cx| diff.set_snapshot(unstaged, &buffer, cx));
+        let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+            let hunks = diff.hunks(&buffer, cx).collect::<Vec<_>>();
+            diff.stage_or_unstage_lines(false, &hunks, &selections[..1], &[], &buffer, cx)
+                .unwrap()
+                .to_string()
+        });
+        assert_eq!(
+            new_index_text,
+            "
+            one
+            three
+            FOUR_HUNDRED
+            "
+            .unindent()
+        );
+    }
+
     #[gpui::test]
     async fn test_buffer_diff_compare(cx: &mut TestAppContext) {
         let base_text = "
@@ -0,0 +1,168 @@
+use std::ops::Range;
+
+/// Hunks whose sides have more tokens than this are only highlighted by row.
+const MAX_WORD_DIFF_TOKENS: usize = 512;
+
+/// Computes the word-level changes between the two sides of a modified hunk,
+/// returning the changed byte ranges in `old` and in `new`.
+///
+/// Returns `None` when the sides are too large to compare, or when they share
+/// no words at all, in which case highlighting every word would only add noise.
+pub(crate) fn word_diff(old: &str, new: &str) -> Option<(Vec<Range<usize>>, Vec<Range<usize>>)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len() > MAX_WORD_DIFF_TOKENS || new_tokens.len() > MAX_WORD_DIFF_TOKENS {
+        return None;
+    }
+
+    // Longest common subsequence of the two token lists, where `lengths[i][j]`
+    // is the length of the LCS of `old_tokens[i..]` and `new_tokens[j..]`.
+    let width = new_tokens.len() + 1;
+    let mut lengths = vec![0u32; (old_tokens.len() + 1) * width];
+    for i in (0..old_tokens.len()).rev() {
+        for j in (0..new_tokens.len()).rev() {
+            lengths[i * width + j] = if old[old_tokens[i].clone()] == new[new_tokens[j].clone()] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut old_changes = Vec::new();
+    let mut new_changes = Vec::new();
+    let mut unchanged_words = 0;
+    let (mut i, mut j) = (0, 0);
+    while i < old_tokens.len() || j < new_tokens.len() {
+        if i < old_tokens.len()
+            && j < new_tokens.len()
+            && old[old_tokens[i].clone()] == new[new_tokens[j].clone()]
+        {
+            if !is_whitespace(&old[old_tokens[i].clone()]) {
+                unchanged_words += 1;
+            }
+            i += 1;
+            j += 1;
+        } else if j < new_tokens.len()
+            && (i == old_tokens.len() || lengths[i * width + j + 1] >= lengths[(i + 1) * width + j])
+        {
+            push_change(&mut new_changes, new, new_tokens[j].clone());
+            j += 1;
+        } else {
+            push_change(&mut old_changes, old, old_tokens[i].clone());
+            i += 1;
+        }
+    }
+
+    (unchanged_words > 0).then_some((old_changes, new_changes))
+}
+
+/// Splits text into runs of word characters, runs of whitespace, and
+/// individual punctuation characters.
+fn tokenize(text: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        let mut end = start + ch.len_utf8();
+        let kind = CharKind::of(ch);
+        if kind != CharKind::Punctuation {
+            while let Some(&(ix, next)) = chars.peek() {
+                if CharKind::of(next) != kind {
+                    break;
+                }
+                end = ix + next.len_utf8();
+                chars.next();
+            }
+        }
+        tokens.push(start..end);
+    }
+    tokens
+}
+
+/// Appends a changed token, merging it with the previous change when only
+/// whitespace on the same line separates them. Whitespace-only changes aren't
+/// highlighted on their own.
+fn push_change(changes: &mut Vec<Range<usize>>, text: &str, range: Range<usize>) {
+    if is_whitespace(&text[range.clone()]) {
+        return;
+    }
+    if let Some(last) = changes.last_mut() {
+        let between = &text[last.end..range.start];
+        if is_whitespace(between) && !between.contains('\n') {
+            last.end = range.end;
+            return;
+        }
+    }
+    changes.push(range);
+}
+
+fn is_whitespace(text: &str) -> bool {
+    text.chars().all(char::is_whitespace)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharKind {
+    Word,
+    Whitespace,
+    Punctuation,
+}
+
+impl CharKind {
+    fn of(ch: char) -> Self {
+        if ch.is_alphanumeric() || ch == '_' {
+            Self::Word
+        } else if ch.is_whitespace() {
+            Self::Whitespace
+        } else {
+            Self::Punctuation
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_diff() {
+        let old = "let foo = bar(1, 2);\n";
+        let new = "let foo = baz(1, 2, 3);\n";
+        let (old_changes, new_changes) = word_diff(old, new).unwrap();
+        assert_eq!(
+            old_changes
+                .iter()
+                .map(|range| &old[range.clone()])
+                .collect::<Vec<_>>(),
+            ["bar"]
+        );
+        assert_eq!(
+            new_changes
+                .iter()
+                .map(|range| &new[range.clone()])
+                .collect::<Vec<_>>(),
+            ["baz", ", 3"]
+        );
+
+        // Adjacent changed words are merged, but not across lines.
+        let old = "one two three\nfour\n";
+        let new = "one 2 3\n4\n";
+        let (old_changes, new_changes) = word_diff(old, new).unwrap();
+        assert_eq!(
+            old_changes
+                .iter()
+                .map(|range| &old[range.clone()])
+                .collect::<Vec<_>>(),
+            ["two three", "four"]
+        );
+        assert_eq!(
+            new_changes
+                .iter()
+                .map(|range| &new[range.clone()])
+                .collect::<Vec<_>>(),
+            ["2 3", "4"]
+        );
+
+        // Nothing in common.
+        assert_eq!(word_diff("alpha\n", "beta\n"), None);
+    }
+}
@@ -49,7 +49,9 @@ Examples:
     `zed path-to-your-project`
           Open your project in Zed
     `zed -n path-to-file `
-          Open file/folder in a new window",
+          Open file/folder in a new window
+    `zed agent run --worktree path-to-your-project \"prompt\"`
+          Run the agent without opening a window and print a JSON transcript",
     after_help = "To read from stdin, append '-', e.g. 'ps axf | zed -'"
 )]
 struct Args {
@@ -144,6 +146,18 @@ fn main() -> Result<()> {
             return mac_os::spawn_channel_cli(channel, std::env::args().skip(2).collect());
         }
     }
+
+    // `zed agent` runs the agent headlessly inside of the Zed binary instead of opening a window,
+    // unless there is a file or directory named `agent` to open.
+    if std::env::args().nth(1).as_deref() == Some("agent") && !Path::new("agent").exists() {
+        let app = Detect::detect(None).context("Bundle detection")?;
+        let status = std::process::Command::new(app.path())
+            .args(std::env::args().skip(1))
+            .status()
+            .context("Failed to run the agent")?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
     let args = Args::parse();
 
     // Set custom data directory before any path operations
@@ -460,6 +460,10 @@ CREATE TABLE extension_versions (
     provides_slash_commands BOOLEAN NOT NULL DEFAULT FALSE,
     provides_indexed_docs_providers BOOLEAN NOT NULL DEFAULT FALSE,
     provides_snippets BOOLEAN NOT NULL DEFAULT FALSE,
+    provides_debug_adapters BOOLEAN NOT NULL DEFAULT FALSE,
+    provides_task_providers BOOLEAN NOT NULL DEFAULT FALSE,
+    provides_agent_tools BOOLEAN NOT NULL DEFAULT FALSE,
+    provides_language_model_providers BOOLEAN NOT NULL DEFAULT FALSE,
     PRIMARY KEY (extension_id, version)
 );
 
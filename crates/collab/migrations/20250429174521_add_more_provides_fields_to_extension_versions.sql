@@ -0,0 +1,5 @@
+alter table extension_versions
+add column provides_debug_adapters bool not null default false,
+add column provides_task_providers bool not null default false,
+add column provides_agent_tools bool not null default false,
+add column provides_language_model_providers bool not null default false;
@@ -320,6 +320,20 @@ impl Database {
                         provides_snippets: ActiveValue::Set(
                             version.provides.contains(&ExtensionProvides::Snippets),
                         ),
+                        provides_debug_adapters: ActiveValue::Set(
+                            version.provides.contains(&ExtensionProvides::DebugAdapters),
+                        ),
+                        provides_task_providers: ActiveValue::Set(
+                            version.provides.contains(&ExtensionProvides::TaskProviders),
+                        ),
+                        provides_agent_tools: ActiveValue::Set(
+                            version.provides.contains(&ExtensionProvides::AgentTools),
+                        ),
+                        provides_language_model_providers: ActiveValue::Set(
+                            version
+                                .provides
+                                .contains(&ExtensionProvides::LanguageModelProviders),
+                        ),
                         download_count: ActiveValue::NotSet,
                     }
                 }))
@@ -430,6 +444,23 @@ fn apply_provides_filter(
         condition = condition.add(extension_version::Column::ProvidesSnippets.eq(true));
     }
 
+    if provides_filter.contains(&ExtensionProvides::DebugAdapters) {
+        condition = condition.add(extension_version::Column::ProvidesDebugAdapters.eq(true));
+    }
+
+    if provides_filter.contains(&ExtensionProvides::TaskProviders) {
+        condition = condition.add(extension_version::Column::ProvidesTaskProviders.eq(true));
+    }
+
+    if provides_filter.contains(&ExtensionProvides::AgentTools) {
+        condition = condition.add(extension_version::Column::ProvidesAgentTools.eq(true));
+    }
+
+    if provides_filter.contains(&ExtensionProvides::LanguageModelProviders) {
+        condition =
+            condition.add(extension_version::Column::ProvidesLanguageModelProviders.eq(true));
+    }
+
     condition
 }
 
@@ -454,6 +485,7 @@ fn metadata_from_extension_and_version(
             schema_version: Some(version.schema_version),
             wasm_api_version: version.wasm_api_version,
             provides,
+            archive_checksum: None,
         },
 
         published_at: convert_time_to_chrono(version.published_at),
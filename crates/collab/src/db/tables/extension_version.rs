@@ -27,6 +27,10 @@ pub struct Model {
     pub provides_slash_commands: bool,
     pub provides_indexed_docs_providers: bool,
     pub provides_snippets: bool,
+    pub provides_debug_adapters: bool,
+    pub provides_task_providers: bool,
+    pub provides_agent_tools: bool,
+    pub provides_language_model_providers: bool,
 }
 
 impl Model {
@@ -68,6 +72,22 @@ impl Model {
             provides.insert(ExtensionProvides::Snippets);
         }
 
+        if self.provides_debug_adapters {
+            provides.insert(ExtensionProvides::DebugAdapters);
+        }
+
+        if self.provides_task_providers {
+            provides.insert(ExtensionProvides::TaskProviders);
+        }
+
+        if self.provides_agent_tools {
+            provides.insert(ExtensionProvides::AgentTools);
+        }
+
+        if self.provides_language_model_providers {
+            provides.insert(ExtensionProvides::LanguageModelProviders);
+        }
+
         provides
     }
 }
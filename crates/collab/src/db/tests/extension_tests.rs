@@ -105,6 +105,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(1),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_checksum: None,
                 },
                 published_at: t0_chrono,
                 download_count: 0,
@@ -120,6 +121,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(0),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_checksum: None,
                 },
                 published_at: t0_chrono,
                 download_count: 0
@@ -142,6 +144,7 @@ async fn test_extensions(db: &Arc<Database>) {
                 schema_version: Some(0),
                 wasm_api_version: None,
                 provides: BTreeSet::default(),
+                archive_checksum: None,
             },
             published_at: t0_chrono,
             download_count: 0
@@ -184,6 +187,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(0),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_checksum: None,
                 },
                 published_at: t0_chrono,
                 download_count: 7
@@ -199,6 +203,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(1),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_checksum: None,
                 },
                 published_at: t0_chrono,
                 download_count: 5,
@@ -274,6 +279,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(0),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_checksum: None,
                 },
                 published_at: t0_chrono,
                 download_count: 7
@@ -289,6 +295,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     schema_version: Some(1),
                     wasm_api_version: None,
                     provides: BTreeSet::default(),
+                    archive_checksum: None,
                 },
                 published_at: t0_chrono,
                 download_count: 5,
@@ -415,6 +422,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                     ExtensionProvides::Languages,
                     ExtensionProvides::LanguageServers,
                 ]),
+                archive_checksum: None,
             },
             published_at: t0_chrono,
             download_count: 0,
@@ -589,3 +589,37 @@ impl ProtoConversion for dap_types::Thread {
         }
     }
 }
+
+impl ProtoConversion for dap_types::DisassembledInstruction {
+    type ProtoType = proto::DapDisassembledInstruction;
+    type Output = Self;
+
+    fn to_proto(&self) -> Self::ProtoType {
+        proto::DapDisassembledInstruction {
+            address: self.address.clone(),
+            instruction_bytes: self.instruction_bytes.clone(),
+            instruction: self.instruction.clone(),
+            symbol: self.symbol.clone(),
+            location: self.location.as_ref().map(Source::to_proto),
+            line: self.line,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+        }
+    }
+
+    fn from_proto(payload: Self::ProtoType) -> Self {
+        Self {
+            address: payload.address,
+            instruction_bytes: payload.instruction_bytes,
+            instruction: payload.instruction,
+            symbol: payload.symbol,
+            location: payload.location.map(dap_types::Source::from_proto),
+            line: payload.line,
+            column: payload.column,
+            end_line: payload.end_line,
+            end_column: payload.end_column,
+            presentation_hint: None, // TODO Debugger Collab
+        }
+    }
+}
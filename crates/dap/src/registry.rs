@@ -6,7 +6,10 @@ use parking_lot::RwLock;
 use task::{DebugRequest, SpawnInTerminal};
 
 use crate::adapters::{DebugAdapter, DebugAdapterName};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, btree_map},
+    sync::Arc,
+};
 
 /// Given a user build configuration, locator creates a fill-in debug target ([DebugRequest]) on behalf of the user.
 #[async_trait]
@@ -39,13 +42,28 @@ impl DapRegistry {
         ret
     }
 
-    pub fn add_adapter(&self, adapter: Arc<dyn DebugAdapter>) {
+    /// Registers the given debug adapter, unless one with the same name is already registered.
+    ///
+    /// Returns whether the adapter was registered.
+    pub fn add_adapter(&self, adapter: Arc<dyn DebugAdapter>) -> bool {
         let name = adapter.name();
-        let _previous_value = self.0.write().adapters.insert(name, adapter);
-        debug_assert!(
-            _previous_value.is_none(),
-            "Attempted to insert a new debug adapter when one is already registered"
-        );
+        match self.0.write().adapters.entry(name) {
+            btree_map::Entry::Occupied(entry) => {
+                log::error!(
+                    "Attempted to insert a debug adapter named {} when one is already registered",
+                    entry.key()
+                );
+                false
+            }
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(adapter);
+                true
+            }
+        }
+    }
+
+    pub fn remove_adapter(&self, name: &str) {
+        self.0.write().adapters.remove(name);
     }
 
     pub fn add_locator(&self, name: String, locator: Arc<dyn DapLocator>) {
@@ -68,3 +86,31 @@ impl DapRegistry {
         self.0.read().adapters.keys().cloned().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FakeAdapter;
+
+    #[test]
+    fn test_add_and_remove_adapter() {
+        let registry = DapRegistry::default();
+        assert!(registry.add_adapter(Arc::new(FakeAdapter::new())));
+        assert_eq!(
+            registry.enumerate_adapters(),
+            [DebugAdapterName(FakeAdapter::ADAPTER_NAME.into())]
+        );
+
+        // An adapter with the same name doesn't replace the registered one.
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(!registry.add_adapter(Arc::new(FakeAdapter::new())));
+        assert!(Arc::ptr_eq(
+            &registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap(),
+            &adapter
+        ));
+
+        registry.remove_adapter(FakeAdapter::ADAPTER_NAME);
+        assert!(registry.adapter(FakeAdapter::ADAPTER_NAME).is_none());
+        assert!(registry.enumerate_adapters().is_empty());
+    }
+}
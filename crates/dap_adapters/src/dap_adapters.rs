@@ -1,4 +1,5 @@
 mod codelldb;
+mod extension_dap_adapter;
 mod gdb;
 mod go;
 mod javascript;
@@ -34,7 +35,8 @@ pub fn init(cx: &mut App) {
         registry.add_adapter(Arc::from(JsDebugAdapter));
         registry.add_adapter(Arc::from(GoDebugAdapter));
         registry.add_adapter(Arc::from(GdbDebugAdapter));
-    })
+    });
+    extension_dap_adapter::init(cx);
 }
 
 pub(crate) async fn configure_tcp_connection(
@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use dap::{
+    DapRegistry,
+    adapters::{
+        AdapterVersion, DapDelegate, DebugAdapter, DebugAdapterBinary, DebugAdapterName,
+        DebugTaskDefinition,
+    },
+};
+use extension::{
+    Extension, ExtensionDebugAdapterProviderProxy, ExtensionHostProxy, WorktreeDelegate,
+};
+use fs::Fs;
+use gpui::{App, AsyncApp};
+use parking_lot::Mutex;
+use settings::WorktreeId;
+use util::merge_json_value_into;
+
+pub(crate) fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_debug_adapter_proxy(DapRegistryProxy {
+        dap_registry: DapRegistry::global(cx).clone(),
+        registered_adapters: Mutex::default(),
+    });
+}
+
+struct DapRegistryProxy {
+    dap_registry: DapRegistry,
+    /// The IDs of the extensions that registered each debug adapter, so that an extension
+    /// can't unregister a built-in adapter or one registered by another extension.
+    registered_adapters: Mutex<HashMap<Arc<str>, Arc<str>>>,
+}
+
+impl ExtensionDebugAdapterProviderProxy for DapRegistryProxy {
+    fn register_debug_adapter(&self, extension: Arc<dyn Extension>, debug_adapter_name: Arc<str>) {
+        let extension_id = extension.manifest().id.clone();
+        if self
+            .dap_registry
+            .add_adapter(Arc::new(ExtensionDapAdapter::new(
+                extension,
+                debug_adapter_name.clone(),
+            )))
+        {
+            self.registered_adapters
+                .lock()
+                .insert(debug_adapter_name, extension_id);
+        }
+    }
+
+    fn unregister_debug_adapter(&self, extension_id: Arc<str>, debug_adapter_name: Arc<str>) {
+        let mut registered_adapters = self.registered_adapters.lock();
+        if registered_adapters.get(&debug_adapter_name) == Some(&extension_id) {
+            registered_adapters.remove(&debug_adapter_name);
+            self.dap_registry.remove_adapter(&debug_adapter_name);
+        }
+    }
+}
+
+/// A debug adapter provided by an extension.
+struct ExtensionDapAdapter {
+    extension: Arc<dyn Extension>,
+    debug_adapter_name: Arc<str>,
+}
+
+impl ExtensionDapAdapter {
+    fn new(extension: Arc<dyn Extension>, debug_adapter_name: Arc<str>) -> Self {
+        Self {
+            extension,
+            debug_adapter_name,
+        }
+    }
+}
+
+/// A snapshot of a [`DapDelegate`] that can be handed to an extension as a [`WorktreeDelegate`].
+struct WorktreeDelegateAdapter {
+    worktree_id: WorktreeId,
+    root_path: Arc<Path>,
+    fs: Arc<dyn Fs>,
+    shell_env: HashMap<String, String>,
+}
+
+impl WorktreeDelegateAdapter {
+    async fn new(delegate: &dyn DapDelegate) -> Self {
+        Self {
+            worktree_id: delegate.worktree_id(),
+            root_path: delegate.worktree_root_path().into(),
+            fs: delegate.fs(),
+            shell_env: delegate.shell_env().await,
+        }
+    }
+}
+
+#[async_trait]
+impl WorktreeDelegate for WorktreeDelegateAdapter {
+    fn id(&self) -> u64 {
+        self.worktree_id.to_proto()
+    }
+
+    fn root_path(&self) -> String {
+        self.root_path.to_string_lossy().to_string()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        self.fs.load(&self.root_path.join(path)).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        which::which_in(
+            binary_name,
+            self.shell_env.get("PATH"),
+            self.root_path.as_ref(),
+        )
+        .ok()
+        .map(|path| path.to_string_lossy().to_string())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.shell_env.clone().into_iter().collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for ExtensionDapAdapter {
+    fn name(&self) -> DebugAdapterName {
+        self.debug_adapter_name.as_ref().into()
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &dyn DapDelegate,
+        config: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let worktree = Arc::new(WorktreeDelegateAdapter::new(delegate).await);
+        let mut binary = self
+            .extension
+            .get_dap_binary(
+                self.debug_adapter_name.clone(),
+                config.clone(),
+                user_installed_path,
+                worktree,
+            )
+            .await?;
+        binary.command = self
+            .extension
+            .path_from_extension(binary.command.as_ref())
+            .to_string_lossy()
+            .to_string();
+
+        let mut configuration = self
+            .extension
+            .dap_default_config(self.debug_adapter_name.clone())
+            .await?;
+        merge_json_value_into(binary.request_args.configuration, &mut configuration);
+
+        // The user's initialize arguments are merged into the configuration when the session
+        // starts, so they may turn a launch into an attach (or vice versa).
+        if let Some(initialize_args) = config.initialize_args.clone() {
+            let mut effective_configuration = configuration.clone();
+            merge_json_value_into(initialize_args, &mut effective_configuration);
+            binary.request_args.request = self
+                .extension
+                .dap_request_kind(self.debug_adapter_name.clone(), effective_configuration)
+                .await?;
+        }
+
+        binary.request_args.configuration = configuration;
+        Ok(binary)
+    }
+
+    async fn fetch_latest_adapter_version(&self, _: &dyn DapDelegate) -> Result<AdapterVersion> {
+        bail!("Extension debug adapters are installed by their extension")
+    }
+
+    async fn install_binary(&self, _: AdapterVersion, _: &dyn DapDelegate) -> Result<()> {
+        bail!("Extension debug adapters are installed by their extension")
+    }
+
+    async fn get_installed_binary(
+        &self,
+        _: &dyn DapDelegate,
+        _: &DebugTaskDefinition,
+        _: Option<PathBuf>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        bail!("Extension debug adapters are installed by their extension")
+    }
+}
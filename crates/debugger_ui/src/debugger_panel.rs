@@ -1,8 +1,9 @@
 use crate::persistence::DebuggerPaneItem;
 use crate::{
     ClearAllBreakpoints, Continue, CreateDebuggingSession, Disconnect, FocusBreakpointList,
-    FocusConsole, FocusFrames, FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables,
-    Pause, Restart, StepBack, StepInto, StepOut, StepOver, Stop, ToggleIgnoreBreakpoints,
+    FocusConsole, FocusDisassembly, FocusFrames, FocusLoadedSources, FocusMemory, FocusModules,
+    FocusTerminal, FocusVariables, FocusWatch, Pause, Restart, StepBack, StepInto,
+    StepIntoInstruction, StepOut, StepOver, StepOverInstruction, Stop, ToggleIgnoreBreakpoints,
     persistence,
 };
 use crate::{new_session_modal::NewSessionModal, session::DebugSession};
@@ -119,6 +120,8 @@ impl DebugPanel {
             TypeId::of::<StepOver>(),
             TypeId::of::<StepInto>(),
             TypeId::of::<StepOut>(),
+            TypeId::of::<StepOverInstruction>(),
+            TypeId::of::<StepIntoInstruction>(),
             TypeId::of::<editor::actions::DebuggerRunToCursor>(),
             TypeId::of::<editor::actions::DebuggerEvaluateSelectedText>(),
         ];
@@ -1039,6 +1042,10 @@ impl DebugPanel {
         if let Some(session) = self.active_session() {
             session.update(cx, |session, cx| {
                 session.running_state().update(cx, |running, cx| {
+                    // Panes restored from an older layout may not contain the item yet
+                    if item.is_supported(running.session().read(cx).capabilities()) {
+                        running.ensure_pane_item(item, window, cx);
+                    }
                     running.activate_item(item, window, cx);
                 });
             });
@@ -1207,6 +1214,15 @@ impl Render for DebugPanel {
                     .ok();
                 }
             })
+            .on_action({
+                let this = this.clone();
+                move |_: &FocusWatch, window, cx| {
+                    this.update(cx, |this, cx| {
+                        this.activate_item(DebuggerPaneItem::Watch, window, cx);
+                    })
+                    .ok();
+                }
+            })
             .on_action({
                 let this = this.clone();
                 move |_: &FocusBreakpointList, window, cx| {
@@ -1252,6 +1268,24 @@ impl Render for DebugPanel {
                     .ok();
                 }
             })
+            .on_action({
+                let this = this.clone();
+                move |_: &FocusDisassembly, window, cx| {
+                    this.update(cx, |this, cx| {
+                        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+                    })
+                    .ok();
+                }
+            })
+            .on_action({
+                let this = this.clone();
+                move |_: &FocusMemory, window, cx| {
+                    this.update(cx, |this, cx| {
+                        this.activate_item(DebuggerPaneItem::Memory, window, cx);
+                    })
+                    .ok();
+                }
+            })
             .when(self.active_session.is_some(), |this| {
                 this.on_mouse_down(
                     MouseButton::Right,
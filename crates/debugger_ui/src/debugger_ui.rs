@@ -31,17 +31,22 @@ actions!(
         StepOver,
         StepOut,
         StepBack,
+        StepOverInstruction,
+        StepIntoInstruction,
         Stop,
         ToggleIgnoreBreakpoints,
         ClearAllBreakpoints,
         CreateDebuggingSession,
         FocusConsole,
         FocusVariables,
+        FocusWatch,
         FocusBreakpointList,
         FocusFrames,
         FocusModules,
         FocusLoadedSources,
         FocusTerminal,
+        FocusDisassembly,
+        FocusMemory,
     ]
 );
 
@@ -103,6 +108,28 @@ pub fn init(cx: &mut App) {
                         }
                     }
                 })
+                .register_action(|workspace, _: &StepOverInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item.update(cx, |item, cx| item.step_over_instruction(cx))
+                        }
+                    }
+                })
+                .register_action(|workspace, _: &StepIntoInstruction, _, cx| {
+                    if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
+                        if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
+                            panel
+                                .active_session()
+                                .map(|session| session.read(cx).running_state().clone())
+                        }) {
+                            active_item.update(cx, |item, cx| item.step_in_instruction(cx))
+                        }
+                    }
+                })
                 .register_action(|workspace, _: &StepBack, _, cx| {
                     if let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) {
                         if let Some(active_item) = debug_panel.read_with(cx, |panel, cx| {
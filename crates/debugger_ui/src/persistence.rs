@@ -10,19 +10,23 @@ use workspace::{Member, Pane, PaneAxis, Workspace};
 
 use crate::session::running::{
     self, DebugTerminal, RunningState, SubView, breakpoint_list::BreakpointList, console::Console,
-    loaded_source_list::LoadedSourceList, module_list::ModuleList,
-    stack_frame_list::StackFrameList, variable_list::VariableList,
+    disassembly_view::DisassemblyView, loaded_source_list::LoadedSourceList,
+    memory_view::MemoryView, module_list::ModuleList, stack_frame_list::StackFrameList,
+    variable_list::VariableList, watch_list::WatchList,
 };
 
 #[derive(Clone, Hash, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub(crate) enum DebuggerPaneItem {
     Console,
     Variables,
+    Watch,
     BreakpointList,
     Frames,
     Modules,
     LoadedSources,
     Terminal,
+    Disassembly,
+    Memory,
 }
 
 impl DebuggerPaneItem {
@@ -30,11 +34,14 @@ impl DebuggerPaneItem {
         static VARIANTS: &[DebuggerPaneItem] = &[
             DebuggerPaneItem::Console,
             DebuggerPaneItem::Variables,
+            DebuggerPaneItem::Watch,
             DebuggerPaneItem::BreakpointList,
             DebuggerPaneItem::Frames,
             DebuggerPaneItem::Modules,
             DebuggerPaneItem::LoadedSources,
             DebuggerPaneItem::Terminal,
+            DebuggerPaneItem::Disassembly,
+            DebuggerPaneItem::Memory,
         ];
         VARIANTS
     }
@@ -45,6 +52,12 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::LoadedSources => capabilities
                 .supports_loaded_sources_request
                 .unwrap_or_default(),
+            DebuggerPaneItem::Disassembly => capabilities
+                .supports_disassemble_request
+                .unwrap_or_default(),
+            DebuggerPaneItem::Memory => capabilities
+                .supports_read_memory_request
+                .unwrap_or_default(),
             _ => true,
         }
     }
@@ -53,11 +66,14 @@ impl DebuggerPaneItem {
         match self {
             DebuggerPaneItem::Console => SharedString::new_static("Console"),
             DebuggerPaneItem::Variables => SharedString::new_static("Variables"),
+            DebuggerPaneItem::Watch => SharedString::new_static("Watch"),
             DebuggerPaneItem::BreakpointList => SharedString::new_static("Breakpoints"),
             DebuggerPaneItem::Frames => SharedString::new_static("Frames"),
             DebuggerPaneItem::Modules => SharedString::new_static("Modules"),
             DebuggerPaneItem::LoadedSources => SharedString::new_static("Sources"),
             DebuggerPaneItem::Terminal => SharedString::new_static("Terminal"),
+            DebuggerPaneItem::Disassembly => SharedString::new_static("Disassembly"),
+            DebuggerPaneItem::Memory => SharedString::new_static("Memory"),
         }
     }
 }
@@ -88,6 +104,7 @@ pub(crate) struct SerializedPane {
 }
 
 const DEBUGGER_PANEL_PREFIX: &str = "debugger_panel_";
+const DEBUGGER_WATCH_EXPRESSIONS_PREFIX: &str = "debugger_watch_expressions_";
 
 pub(crate) async fn serialize_pane_layout(
     adapter_name: SharedString,
@@ -162,17 +179,56 @@ pub(crate) async fn get_serialized_pane_layout(
         .and_then(|value| serde_json::from_str::<SerializedPaneLayout>(&value).ok())
 }
 
+/// Watch expressions are stored per project, keyed by the absolute paths of its visible worktrees.
+pub(crate) fn watch_expressions_key(project: &Entity<Project>, cx: &App) -> Option<String> {
+    let mut worktree_paths = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    if worktree_paths.is_empty() {
+        return None;
+    }
+
+    worktree_paths.sort();
+    Some(format!(
+        "{DEBUGGER_WATCH_EXPRESSIONS_PREFIX}{}",
+        worktree_paths.join(",")
+    ))
+}
+
+pub(crate) async fn serialize_watch_expressions(
+    key: String,
+    expressions: Vec<SharedString>,
+) -> anyhow::Result<()> {
+    let serialized_expressions = serde_json::to_string(&expressions)?;
+    KEY_VALUE_STORE.write_kvp(key, serialized_expressions).await
+}
+
+pub(crate) fn get_serialized_watch_expressions(key: &str) -> Vec<SharedString> {
+    KEY_VALUE_STORE
+        .read_kvp(key)
+        .log_err()
+        .flatten()
+        .and_then(|value| serde_json::from_str::<Vec<SharedString>>(&value).ok())
+        .unwrap_or_default()
+}
+
 pub(crate) fn deserialize_pane_layout(
     serialized: SerializedPaneLayout,
     workspace: &WeakEntity<Workspace>,
     project: &Entity<Project>,
     stack_frame_list: &Entity<StackFrameList>,
     variable_list: &Entity<VariableList>,
+    watch_list: &Entity<WatchList>,
     module_list: &Entity<ModuleList>,
     console: &Entity<Console>,
     breakpoint_list: &Entity<BreakpointList>,
     loaded_sources: &Entity<LoadedSourceList>,
     terminal: &Entity<DebugTerminal>,
+    disassembly_view: &Entity<DisassemblyView>,
+    memory_view: &Entity<MemoryView>,
     subscriptions: &mut HashMap<EntityId, Subscription>,
     window: &mut Window,
     cx: &mut Context<RunningState>,
@@ -191,11 +247,14 @@ pub(crate) fn deserialize_pane_layout(
                     project,
                     stack_frame_list,
                     variable_list,
+                    watch_list,
                     module_list,
                     console,
                     breakpoint_list,
                     loaded_sources,
                     terminal,
+                    disassembly_view,
+                    memory_view,
                     subscriptions,
                     window,
                     cx,
@@ -243,6 +302,13 @@ pub(crate) fn deserialize_pane_layout(
                         None,
                         cx,
                     )),
+                    DebuggerPaneItem::Watch => Box::new(SubView::new(
+                        watch_list.focus_handle(cx),
+                        watch_list.clone().into(),
+                        DebuggerPaneItem::Watch,
+                        None,
+                        cx,
+                    )),
                     DebuggerPaneItem::BreakpointList => Box::new(SubView::new(
                         breakpoint_list.focus_handle(cx),
                         breakpoint_list.clone().into(),
@@ -285,6 +351,20 @@ pub(crate) fn deserialize_pane_layout(
                         None,
                         cx,
                     )),
+                    DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                        disassembly_view.focus_handle(cx),
+                        disassembly_view.clone().into(),
+                        DebuggerPaneItem::Disassembly,
+                        None,
+                        cx,
+                    )),
+                    DebuggerPaneItem::Memory => Box::new(SubView::new(
+                        memory_view.focus_handle(cx),
+                        memory_view.clone().into(),
+                        DebuggerPaneItem::Memory,
+                        None,
+                        cx,
+                    )),
                 })
                 .collect();
 
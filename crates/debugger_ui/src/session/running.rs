@@ -1,9 +1,12 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
+pub(crate) mod memory_view;
 pub(crate) mod module_list;
 pub mod stack_frame_list;
 pub mod variable_list;
+pub(crate) mod watch_list;
 
 use std::{any::Any, ops::ControlFlow, path::PathBuf, sync::Arc, time::Duration};
 
@@ -15,15 +18,17 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, RunInTerminalRequestArguments, Thread, client::SessionId,
+    Capabilities, RunInTerminalRequestArguments, SteppingGranularity, Thread, client::SessionId,
     debugger_settings::DebuggerSettings,
 };
+use disassembly_view::DisassemblyView;
 use futures::{SinkExt, channel::mpsc};
 use gpui::{
     Action as _, AnyView, AppContext, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
     NoAction, Pixels, Point, Subscription, Task, WeakEntity,
 };
 use loaded_source_list::LoadedSourceList;
+use memory_view::MemoryView;
 use module_list::ModuleList;
 use project::{
     Project,
@@ -42,7 +47,8 @@ use ui::{
     Styled, Tab, Tooltip, VisibleOnHover, VisualContext, Window, div, h_flex, v_flex,
 };
 use util::ResultExt;
-use variable_list::VariableList;
+use variable_list::{VariableList, VariableListEvent};
+use watch_list::WatchList;
 use workspace::{
     ActivePaneDecorator, DraggedTab, Item, ItemHandle, Member, Pane, PaneGroup, SplitDirection,
     Workspace, item::TabContentParams, move_item, pane::Event,
@@ -56,6 +62,7 @@ pub struct RunningState {
     workspace: WeakEntity<Workspace>,
     session_id: SessionId,
     variable_list: Entity<variable_list::VariableList>,
+    watch_list: Entity<WatchList>,
     _subscriptions: Vec<Subscription>,
     stack_frame_list: Entity<stack_frame_list::StackFrameList>,
     loaded_sources_list: Entity<LoadedSourceList>,
@@ -63,6 +70,8 @@ pub struct RunningState {
     module_list: Entity<module_list::ModuleList>,
     console: Entity<Console>,
     breakpoint_list: Entity<BreakpointList>,
+    disassembly_view: Entity<DisassemblyView>,
+    memory_view: Entity<MemoryView>,
     panes: PaneGroup,
     active_pane: Option<Entity<Pane>>,
     pane_close_subscriptions: HashMap<EntityId, Subscription>,
@@ -100,6 +109,9 @@ impl Render for RunningState {
         self.variable_list.update(cx, |this, cx| {
             this.disabled(thread_status != ThreadStatus::Stopped, cx);
         });
+        self.watch_list.update(cx, |this, cx| {
+            this.disabled(thread_status != ThreadStatus::Stopped, cx);
+        });
         v_flex()
             .size_full()
             .key_context("DebugSessionItem")
@@ -519,6 +531,16 @@ impl RunningState {
         let variable_list =
             cx.new(|cx| VariableList::new(session.clone(), stack_frame_list.clone(), window, cx));
 
+        let watch_list = cx.new(|cx| {
+            WatchList::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                &project,
+                window,
+                cx,
+            )
+        });
+
         let module_list = cx.new(|cx| ModuleList::new(session.clone(), workspace.clone(), cx));
 
         let loaded_source_list = cx.new(|cx| LoadedSourceList::new(session.clone(), cx));
@@ -535,8 +557,22 @@ impl RunningState {
 
         let breakpoint_list = BreakpointList::new(session.clone(), workspace.clone(), &project, cx);
 
+        let disassembly_view =
+            cx.new(|cx| DisassemblyView::new(session.clone(), stack_frame_list.clone(), cx));
+
+        let memory_view = cx.new(|cx| MemoryView::new(session.clone(), window, cx));
+
         let _subscriptions = vec![
             cx.observe(&module_list, |_, _, cx| cx.notify()),
+            cx.subscribe_in(
+                &variable_list,
+                window,
+                |this, _, event, window, cx| match event {
+                    VariableListEvent::OpenMemory(memory_reference) => {
+                        this.open_memory(memory_reference.clone(), window, cx);
+                    }
+                },
+            ),
             cx.subscribe_in(&session, window, |this, _, event, window, cx| {
                 match event {
                     SessionEvent::Stopped(thread_id) => {
@@ -565,6 +601,12 @@ impl RunningState {
                         {
                             this.remove_pane_item(DebuggerPaneItem::LoadedSources, window, cx);
                         }
+                        if !capabilities.supports_disassemble_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
+                        if !capabilities.supports_read_memory_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Memory, window, cx);
+                        }
                     }
                     SessionEvent::RunInTerminal { request, sender } => this
                         .handle_run_in_terminal(request, sender.clone(), window, cx)
@@ -587,11 +629,14 @@ impl RunningState {
                 &project,
                 &stack_frame_list,
                 &variable_list,
+                &watch_list,
                 &module_list,
                 &console,
                 &breakpoint_list,
                 &loaded_source_list,
                 &debug_terminal,
+                &disassembly_view,
+                &memory_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -606,6 +651,7 @@ impl RunningState {
                 &workspace,
                 &stack_frame_list,
                 &variable_list,
+                &watch_list,
                 &module_list,
                 &loaded_source_list,
                 &console,
@@ -623,6 +669,7 @@ impl RunningState {
             workspace,
             focus_handle,
             variable_list,
+            watch_list,
             _subscriptions,
             thread_id: None,
             _remote_id: None,
@@ -633,6 +680,8 @@ impl RunningState {
             module_list,
             console,
             breakpoint_list,
+            disassembly_view,
+            memory_view,
             loaded_sources_list: loaded_source_list,
             pane_close_subscriptions,
             debug_terminal,
@@ -801,6 +850,13 @@ impl RunningState {
                 None,
                 cx,
             )),
+            DebuggerPaneItem::Watch => Box::new(SubView::new(
+                self.watch_list.focus_handle(cx),
+                self.watch_list.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
             DebuggerPaneItem::BreakpointList => Box::new(SubView::new(
                 self.breakpoint_list.focus_handle(cx),
                 self.breakpoint_list.clone().into(),
@@ -836,9 +892,40 @@ impl RunningState {
                 None,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
+            DebuggerPaneItem::Memory => Box::new(SubView::new(
+                self.memory_view.focus_handle(cx),
+                self.memory_view.clone().into(),
+                item_kind,
+                None,
+                cx,
+            )),
         }
     }
 
+    pub(crate) fn open_memory(
+        &mut self,
+        memory_reference: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !DebuggerPaneItem::Memory.is_supported(self.session.read(cx).capabilities()) {
+            return;
+        }
+
+        self.memory_view.update(cx, |memory_view, cx| {
+            memory_view.set_memory_reference(memory_reference, cx);
+        });
+        self.ensure_pane_item(DebuggerPaneItem::Memory, window, cx);
+        self.activate_item(DebuggerPaneItem::Memory, window, cx);
+    }
+
     pub(crate) fn ensure_pane_item(
         &mut self,
         item_kind: DebuggerPaneItem,
@@ -1043,6 +1130,21 @@ impl RunningState {
         &self.variable_list
     }
 
+    #[cfg(test)]
+    pub(crate) fn watch_list(&self) -> &Entity<WatchList> {
+        &self.watch_list
+    }
+
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
+    #[cfg(test)]
+    pub(crate) fn memory_view(&self) -> &Entity<MemoryView> {
+        &self.memory_view
+    }
+
     pub fn capabilities(&self, cx: &App) -> Capabilities {
         self.session().read(cx).capabilities().clone()
     }
@@ -1133,6 +1235,42 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        if !self.supports_instruction_stepping(cx) {
+            return;
+        }
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_in_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        if !self.supports_instruction_stepping(cx) {
+            return;
+        }
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    fn supports_instruction_stepping(&self, cx: &App) -> bool {
+        self.session
+            .read(cx)
+            .capabilities()
+            .supports_stepping_granularity
+            .unwrap_or_default()
+    }
+
     pub(crate) fn step_back(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
@@ -1252,6 +1390,7 @@ impl RunningState {
         workspace: &WeakEntity<Workspace>,
         stack_frame_list: &Entity<StackFrameList>,
         variable_list: &Entity<VariableList>,
+        watch_list: &Entity<WatchList>,
         module_list: &Entity<ModuleList>,
         loaded_source_list: &Entity<LoadedSourceList>,
         console: &Entity<Console>,
@@ -1309,6 +1448,20 @@ impl RunningState {
                 window,
                 cx,
             );
+            this.add_item(
+                Box::new(SubView::new(
+                    watch_list.focus_handle(cx),
+                    watch_list.clone().into(),
+                    DebuggerPaneItem::Watch,
+                    None,
+                    cx,
+                )),
+                false,
+                false,
+                None,
+                window,
+                cx,
+            );
             this.add_item(
                 Box::new(SubView::new(
                     module_list.focus_handle(cx),
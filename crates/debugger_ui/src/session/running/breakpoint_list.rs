@@ -3,7 +3,7 @@ use std::{
     time::Duration,
 };
 
-use dap::ExceptionBreakpointsFilter;
+use dap::{DataBreakpointAccessType, ExceptionBreakpointsFilter};
 use editor::Editor;
 use gpui::{
     AppContext, Entity, FocusHandle, Focusable, ListState, MouseButton, Stateful, Task, WeakEntity,
@@ -14,7 +14,7 @@ use project::{
     Project,
     debugger::{
         breakpoint_store::{BreakpointEditAction, BreakpointStore, SourceBreakpoint},
-        session::Session,
+        session::{DataBreakpointState, Session},
     },
     worktree_store::WorktreeStore,
 };
@@ -207,8 +207,19 @@ impl Render for BreakpointList {
                     }),
                     weak: weak.clone(),
                 });
-        self.breakpoints
-            .extend(breakpoints.chain(exception_breakpoints));
+        let data_breakpoints =
+            self.session
+                .read(cx)
+                .data_breakpoints()
+                .map(|breakpoint| BreakpointEntry {
+                    kind: BreakpointEntryKind::DataBreakpoint(DataBreakpoint(breakpoint.clone())),
+                    weak: weak.clone(),
+                });
+        self.breakpoints.extend(
+            breakpoints
+                .chain(exception_breakpoints)
+                .chain(data_breakpoints),
+        );
         if self.breakpoints.len() != old_len {
             self.list_state.reset(self.breakpoints.len());
         }
@@ -460,9 +471,89 @@ impl ExceptionBreakpoint {
     }
 }
 #[derive(Clone, Debug)]
+struct DataBreakpoint(DataBreakpointState);
+
+impl DataBreakpoint {
+    fn render(self, list: WeakEntity<BreakpointList>) -> ListItem {
+        let color = if self.0.is_enabled {
+            Color::Debugger
+        } else {
+            Color::Muted
+        };
+        let data_id = SharedString::from(&self.0.dap.data_id);
+        ListItem::new(SharedString::from(format!(
+            "data-breakpoint-ui-item-{}",
+            data_id
+        )))
+        .rounded()
+        .start_slot(
+            div()
+                .id(SharedString::from(format!(
+                    "data-breakpoint-ui-item-{}-click-handler",
+                    data_id
+                )))
+                .on_click({
+                    let list = list.clone();
+                    let data_id = data_id.clone();
+                    move |_, _, cx| {
+                        list.update(cx, |this, cx| {
+                            this.session.update(cx, |this, cx| {
+                                this.toggle_data_breakpoint(&data_id, cx);
+                            });
+                            cx.notify();
+                        })
+                        .ok();
+                    }
+                })
+                .cursor_pointer()
+                .child(Indicator::icon(Icon::new(IconName::Binary)).color(color)),
+        )
+        .end_hover_slot(
+            IconButton::new(
+                SharedString::from(format!("data-breakpoint-ui-remove-{}", data_id)),
+                IconName::Close,
+            )
+            .on_click({
+                let data_id = data_id.clone();
+                move |_, _, cx| {
+                    list.update(cx, |this, cx| {
+                        this.session.update(cx, |this, cx| {
+                            this.remove_data_breakpoint(&data_id, cx);
+                        });
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            })
+            .icon_size(ui::IconSize::XSmall),
+        )
+        .child(
+            div()
+                .py_1()
+                .gap_1()
+                .child(
+                    Label::new(self.0.description)
+                        .size(LabelSize::Small)
+                        .line_height_style(ui::LineHeightStyle::UiLabel),
+                )
+                .child(
+                    Label::new(match self.0.dap.access_type {
+                        Some(DataBreakpointAccessType::Read) => "Break on read",
+                        Some(DataBreakpointAccessType::ReadWrite) => "Break on access",
+                        Some(DataBreakpointAccessType::Write) | None => "Break when value changes",
+                    })
+                    .size(LabelSize::XSmall)
+                    .line_height_style(ui::LineHeightStyle::UiLabel)
+                    .color(Color::Muted),
+                ),
+        )
+    }
+}
+#[derive(Clone, Debug)]
 enum BreakpointEntryKind {
     LineBreakpoint(LineBreakpoint),
     ExceptionBreakpoint(ExceptionBreakpoint),
+    DataBreakpoint(DataBreakpoint),
 }
 
 #[derive(Clone, Debug)]
@@ -479,6 +570,9 @@ impl RenderOnce for BreakpointEntry {
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => {
                 exception_breakpoint.render(self.weak)
             }
+            BreakpointEntryKind::DataBreakpoint(data_breakpoint) => {
+                data_breakpoint.render(self.weak)
+            }
         }
     }
 }
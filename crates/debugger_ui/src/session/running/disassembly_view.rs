@@ -0,0 +1,380 @@
+use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
+use dap::{DisassembledInstruction, StackFrameId};
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, MouseButton, ScrollStrategy, Stateful,
+    Subscription, UniformListScrollHandle, uniform_list,
+};
+use project::debugger::session::{Session, SessionEvent};
+use std::ops::Range;
+use ui::{Divider, Scrollbar, ScrollbarState, Tooltip, prelude::*};
+
+use crate::{StepIntoInstruction, StepOverInstruction};
+
+/// The number of instructions requested before the instruction pointer of the selected frame.
+const INSTRUCTIONS_BEFORE: u64 = 50;
+/// The number of instructions requested starting at the instruction pointer of the selected frame.
+const INSTRUCTIONS_AFTER: u64 = 150;
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    stack_frame_list: Entity<StackFrameList>,
+    instruction_pointer: Option<String>,
+    instructions: Vec<DisassembledInstruction>,
+    scroll_to_instruction_pointer: bool,
+    list_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, Self::handle_stack_frame_list_events),
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_) => {
+                    this.instruction_pointer.take();
+                    this.instructions.clear();
+                    cx.notify();
+                }
+                SessionEvent::Disassembly => cx.notify(),
+                _ => {}
+            }),
+        ];
+
+        let list_handle = UniformListScrollHandle::default();
+
+        Self {
+            scrollbar_state: ScrollbarState::new(list_handle.clone()),
+            list_handle,
+            session,
+            stack_frame_list,
+            instruction_pointer: None,
+            instructions: Vec::default(),
+            scroll_to_instruction_pointer: false,
+            focus_handle: cx.focus_handle(),
+            _subscriptions,
+        }
+    }
+
+    fn handle_stack_frame_list_events(
+        &mut self,
+        _: Entity<StackFrameList>,
+        event: &StackFrameListEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            StackFrameListEvent::SelectedStackFrameChanged(stack_frame_id) => {
+                self.select_stack_frame(*stack_frame_id, cx);
+            }
+        }
+    }
+
+    /// Syncs the view with the instruction pointer of the given stack frame.
+    fn select_stack_frame(&mut self, stack_frame_id: StackFrameId, cx: &mut Context<Self>) {
+        let instruction_pointer = self
+            .stack_frame_list
+            .update(cx, |stack_frame_list, cx| {
+                stack_frame_list.dap_stack_frames(cx)
+            })
+            .into_iter()
+            .find(|stack_frame| stack_frame.id == stack_frame_id)
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference);
+
+        if self.instruction_pointer != instruction_pointer {
+            self.instruction_pointer = instruction_pointer;
+            self.instructions.clear();
+        }
+        self.scroll_to_instruction_pointer = true;
+        cx.notify();
+    }
+
+    fn fetch_instructions(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self.instruction_pointer.clone() else {
+            return;
+        };
+
+        self.instructions = self.session.update(cx, |session, cx| {
+            session
+                .disassemble(
+                    memory_reference,
+                    -(INSTRUCTIONS_BEFORE as i64),
+                    INSTRUCTIONS_BEFORE + INSTRUCTIONS_AFTER,
+                    cx,
+                )
+                .to_vec()
+        });
+
+        if self.scroll_to_instruction_pointer {
+            if let Some(ix) = self.instruction_pointer_index() {
+                self.list_handle.scroll_to_item(ix, ScrollStrategy::Center);
+                self.scroll_to_instruction_pointer = false;
+            }
+        }
+    }
+
+    fn instruction_pointer_index(&self) -> Option<usize> {
+        let instruction_pointer = self.instruction_pointer.as_deref()?;
+        self.instructions
+            .iter()
+            .position(|instruction| same_address(&instruction.address, instruction_pointer))
+    }
+
+    fn toggle_instruction_breakpoint(&mut self, address: String, cx: &mut Context<Self>) {
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(address, cx);
+        });
+    }
+
+    #[cfg(test)]
+    pub(crate) fn instructions(&self) -> &[DisassembledInstruction] {
+        &self.instructions
+    }
+
+    #[cfg(test)]
+    pub(crate) fn instruction_pointer(&self) -> Option<&str> {
+        self.instruction_pointer.as_deref()
+    }
+
+    fn render_entries(&mut self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        let instruction_pointer_ix = self.instruction_pointer_index();
+        range
+            .filter_map(|ix| {
+                let instruction = self.instructions.get(ix)?;
+                let show_symbol = instruction.symbol.is_some()
+                    && (ix == 0 || self.instructions[ix - 1].symbol != instruction.symbol);
+                Some(self.render_entry(
+                    ix,
+                    instruction,
+                    Some(ix) == instruction_pointer_ix,
+                    show_symbol,
+                    cx,
+                ))
+            })
+            .collect()
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        instruction: &DisassembledInstruction,
+        is_instruction_pointer: bool,
+        show_symbol: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let session = self.session.read(cx);
+        let supports_instruction_breakpoints = session
+            .capabilities()
+            .supports_instruction_breakpoints
+            .unwrap_or_default();
+        let has_breakpoint = session.has_instruction_breakpoint(&instruction.address);
+
+        let indicator = div()
+            .id(("disassembly-breakpoint", ix))
+            .flex_none()
+            .w_4()
+            .when(supports_instruction_breakpoints, |this| {
+                let address = instruction.address.clone();
+                this.cursor_pointer()
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.toggle_instruction_breakpoint(address.clone(), cx);
+                    }))
+            })
+            .when(has_breakpoint, |this| {
+                this.child(
+                    Icon::new(IconName::DebugBreakpoint)
+                        .size(IconSize::XSmall)
+                        .color(Color::Debugger),
+                )
+            });
+
+        h_flex()
+            .id(("disassembly-instruction", ix))
+            .w_full()
+            .gap_2()
+            .font_buffer(cx)
+            .text_ui_sm(cx)
+            .when(is_instruction_pointer, |this| {
+                this.bg(cx.theme().colors().editor_debugger_active_line_background)
+            })
+            .hover(|this| this.bg(cx.theme().colors().element_hover))
+            .child(indicator)
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(cx.theme().colors().editor_line_number)
+                    .child(instruction.address.clone()),
+            )
+            .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                this.child(
+                    div()
+                        .flex_none()
+                        .min_w(px(120.))
+                        .text_color(cx.theme().colors().text_muted)
+                        .child(bytes),
+                )
+            })
+            .child(div().flex_1().child(instruction.instruction.clone()))
+            .when_some(
+                instruction.symbol.clone().filter(|_| show_symbol),
+                |this, symbol| {
+                    this.child(
+                        div()
+                            .flex_none()
+                            .pr_4()
+                            .text_color(cx.theme().colors().text_accent)
+                            .child(format!("<{symbol}>")),
+                    )
+                },
+            )
+            .into_any_element()
+    }
+
+    fn render_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_stopped = self.session.read(cx).any_stopped_thread();
+        let supports_stepping_granularity = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_stepping_granularity
+            .unwrap_or_default();
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .p_1()
+            .gap_1()
+            .child(
+                IconButton::new("disassembly-step-over", IconName::DebugStepOver)
+                    .icon_size(IconSize::XSmall)
+                    .shape(ui::IconButtonShape::Square)
+                    .disabled(!is_stopped || !supports_stepping_granularity)
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(Box::new(StepOverInstruction), cx)
+                    })
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Step Over Instruction",
+                                &StepOverInstruction,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("disassembly-step-into", IconName::DebugStepInto)
+                    .icon_size(IconSize::XSmall)
+                    .shape(ui::IconButtonShape::Square)
+                    .disabled(!is_stopped || !supports_stepping_granularity)
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(Box::new(StepIntoInstruction), cx)
+                    })
+                    .tooltip(move |window, cx| {
+                        Tooltip::for_action_in(
+                            "Step Into Instruction",
+                            &StepIntoInstruction,
+                            &focus_handle,
+                            window,
+                            cx,
+                        )
+                    }),
+            )
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("disassembly-view-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+}
+
+/// Memory references are opaque strings, but adapters usually report them as hex addresses
+/// and don't agree on zero padding, so compare them numerically when possible.
+fn same_address(lhs: &str, rhs: &str) -> bool {
+    fn parse(address: &str) -> Option<u64> {
+        let address = address.trim();
+        let hex = address
+            .strip_prefix("0x")
+            .or_else(|| address.strip_prefix("0X"))?;
+        u64::from_str_radix(hex, 16).ok()
+    }
+
+    match (parse(lhs), parse(rhs)) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => lhs == rhs,
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.fetch_instructions(cx);
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("DisassemblyView")
+            .size_full()
+            .child(self.render_controls(cx))
+            .child(Divider::horizontal())
+            .child(div().relative().flex_1().size_full().p_1().map(|this| {
+                if self.instruction_pointer.is_none() {
+                    this.child(
+                        Label::new("No instruction pointer for the selected stack frame")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.entity().clone(),
+                            "disassembly-view",
+                            self.instructions.len(),
+                            |this, range, _window, cx| this.render_entries(range, cx),
+                        )
+                        .track_scroll(self.list_handle.clone())
+                        .size_full(),
+                    )
+                    .child(self.render_vertical_scrollbar(cx))
+                }
+            }))
+    }
+}
@@ -0,0 +1,414 @@
+use editor::Editor;
+use gpui::{
+    AnyElement, ClickEvent, Entity, FocusHandle, Focusable, MouseButton, Stateful, Subscription,
+    TextStyleRefinement, UniformListScrollHandle, uniform_list,
+};
+use menu::{Cancel, Confirm};
+use project::debugger::session::{MemoryRegion, Session, SessionEvent};
+use std::ops::Range;
+use ui::{Divider, Scrollbar, ScrollbarState, Tooltip, prelude::*};
+
+const BYTES_PER_ROW: usize = 16;
+const ROWS_PER_PAGE: usize = 32;
+const PAGE_SIZE: i64 = (BYTES_PER_ROW * ROWS_PER_PAGE) as i64;
+
+pub(crate) struct MemoryView {
+    session: Entity<Session>,
+    memory_reference: Option<String>,
+    offset: i64,
+    region: Option<MemoryRegion>,
+    edited_row: Option<(usize, Entity<Editor>)>,
+    list_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MemoryView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+
+        let _subscriptions = vec![
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_) => {
+                    this.edited_row.take();
+                    cx.notify();
+                }
+                SessionEvent::Memory => cx.notify(),
+                _ => {}
+            }),
+            cx.on_focus_out(&focus_handle, window, |this, _, _, cx| {
+                this.edited_row.take();
+                cx.notify();
+            }),
+        ];
+
+        let list_handle = UniformListScrollHandle::default();
+
+        Self {
+            scrollbar_state: ScrollbarState::new(list_handle.clone()),
+            list_handle,
+            session,
+            memory_reference: None,
+            offset: 0,
+            region: None,
+            edited_row: None,
+            focus_handle,
+            _subscriptions,
+        }
+    }
+
+    /// Shows the memory starting at the given memory reference, e.g. the `memoryReference` of a variable.
+    pub(crate) fn set_memory_reference(
+        &mut self,
+        memory_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        self.memory_reference = Some(memory_reference);
+        self.offset = 0;
+        self.region = None;
+        self.edited_row = None;
+        cx.notify();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn memory_reference(&self) -> Option<&str> {
+        self.memory_reference.as_deref()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn region(&self) -> Option<&MemoryRegion> {
+        self.region.as_ref()
+    }
+
+    fn go_to_page(&mut self, offset: i64, cx: &mut Context<Self>) {
+        self.offset = offset;
+        self.region = None;
+        self.edited_row = None;
+        cx.notify();
+    }
+
+    fn fetch_memory(&mut self, cx: &mut Context<Self>) {
+        let Some(memory_reference) = self.memory_reference.clone() else {
+            return;
+        };
+        let offset = self.offset;
+
+        if let Some(region) = self.session.update(cx, |session, cx| {
+            session
+                .read_memory(memory_reference, offset, PAGE_SIZE as u64, cx)
+                .cloned()
+        }) {
+            self.region = Some(region);
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        self.region.as_ref().map_or(0, |region| {
+            let len = region.data.len() + region.unreadable_bytes as usize;
+            len.min(PAGE_SIZE as usize).div_ceil(BYTES_PER_ROW)
+        })
+    }
+
+    fn row_bytes(&self, row: usize) -> Vec<Option<u8>> {
+        let Some(region) = self.region.as_ref() else {
+            return Vec::new();
+        };
+        let start = row * BYTES_PER_ROW;
+        let readable_end = region.data.len();
+        let end = (readable_end + region.unreadable_bytes as usize).min(start + BYTES_PER_ROW);
+
+        (start..end)
+            .map(|ix| region.data.get(ix).copied())
+            .collect()
+    }
+
+    fn row_address(&self, row: usize) -> SharedString {
+        let row_offset = (row * BYTES_PER_ROW) as u64;
+        self.region
+            .as_ref()
+            .and_then(|region| parse_address(&region.address))
+            .map(|address| format!("0x{:016x}", address.wrapping_add(row_offset)))
+            .unwrap_or_else(|| format!("+0x{:x}", self.offset as u64 + row_offset))
+            .into()
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((row, editor)) = self.edited_row.take() else {
+            return;
+        };
+        let Some(memory_reference) = self.memory_reference.clone() else {
+            return;
+        };
+
+        let text = editor.read(cx).text(cx);
+        match parse_hex_bytes(&text) {
+            Some(bytes) if !bytes.is_empty() => {
+                let offset = self.offset + (row * BYTES_PER_ROW) as i64;
+                self.session.update(cx, |session, cx| {
+                    session.write_memory(memory_reference, offset, &bytes, cx);
+                });
+            }
+            _ => log::warn!("Invalid memory contents: {text:?}"),
+        }
+
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.edited_row.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    fn create_row_editor(default: &str, window: &mut Window, cx: &mut App) -> Entity<Editor> {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+
+            let refinement = TextStyleRefinement {
+                font_size: Some(
+                    TextSize::XSmall
+                        .rems(cx)
+                        .to_pixels(window.rem_size())
+                        .into(),
+                ),
+                ..Default::default()
+            };
+            editor.set_text_style_refinement(refinement);
+            editor.set_text(default, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        editor
+    }
+
+    fn render_rows(&mut self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        range.map(|row| self.render_row(row, cx)).collect()
+    }
+
+    fn render_row(&self, row: usize, cx: &mut Context<Self>) -> AnyElement {
+        let bytes = self.row_bytes(row);
+        let hex = bytes
+            .iter()
+            .map(|byte| match byte {
+                Some(byte) => format!("{byte:02x}"),
+                None => "??".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let ascii = bytes
+            .iter()
+            .map(|byte| match byte {
+                Some(byte) if byte.is_ascii_graphic() || *byte == b' ' => *byte as char,
+                _ => '.',
+            })
+            .collect::<String>();
+
+        let is_writable = bytes.iter().all(Option::is_some)
+            && self
+                .session
+                .read(cx)
+                .capabilities()
+                .supports_write_memory_request
+                .unwrap_or_default();
+        let edited_editor = self
+            .edited_row
+            .as_ref()
+            .filter(|(edited_row, _)| *edited_row == row)
+            .map(|(_, editor)| editor.clone());
+
+        h_flex()
+            .id(("memory-row", row))
+            .w_full()
+            .gap_4()
+            .px_1()
+            .font_buffer(cx)
+            .text_ui_sm(cx)
+            .hover(|this| this.bg(cx.theme().colors().element_hover))
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(cx.theme().colors().editor_line_number)
+                    .child(self.row_address(row)),
+            )
+            .map(|this| {
+                if let Some(editor) = edited_editor {
+                    this.child(div().flex_1().child(editor))
+                } else {
+                    this.child(
+                        div()
+                            .id(("memory-row-bytes", row))
+                            .flex_none()
+                            .when(is_writable, |this| {
+                                let hex = hex.clone();
+                                this.on_click(cx.listener(
+                                    move |this, click: &ClickEvent, window, cx| {
+                                        if click.down.click_count < 2 {
+                                            return;
+                                        }
+                                        let editor = Self::create_row_editor(&hex, window, cx);
+                                        this.edited_row = Some((row, editor));
+                                        cx.notify();
+                                    },
+                                ))
+                            })
+                            .child(hex),
+                    )
+                }
+            })
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(cx.theme().colors().text_muted)
+                    .child(ascii),
+            )
+            .into_any_element()
+    }
+
+    fn render_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_memory_reference = self.memory_reference.is_some();
+        let offset = self.offset;
+
+        h_flex()
+            .p_1()
+            .gap_1()
+            .child(
+                IconButton::new("memory-previous-page", IconName::ChevronLeft)
+                    .icon_size(IconSize::XSmall)
+                    .disabled(!has_memory_reference)
+                    .tooltip(Tooltip::text("Previous Page"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.go_to_page(offset - PAGE_SIZE, cx);
+                    })),
+            )
+            .child(
+                IconButton::new("memory-next-page", IconName::ChevronRight)
+                    .icon_size(IconSize::XSmall)
+                    .disabled(!has_memory_reference)
+                    .tooltip(Tooltip::text("Next Page"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.go_to_page(offset + PAGE_SIZE, cx);
+                    })),
+            )
+            .when_some(self.memory_reference.clone(), |this, memory_reference| {
+                this.child(
+                    Label::new(if offset == 0 {
+                        memory_reference
+                    } else {
+                        format!("{memory_reference} {offset:+}")
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+            })
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("memory-view-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+}
+
+fn parse_address(address: &str) -> Option<u64> {
+    let address = address.trim();
+    let hex = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))?;
+    u64::from_str_radix(hex, 16).ok()
+}
+
+/// Parses bytes written as hex, either separated by whitespace (`de ad be ef`) or contiguous (`deadbeef`).
+fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    let digits = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = pair.iter().collect::<String>();
+            u8::from_str_radix(&pair, 16).ok()
+        })
+        .collect()
+}
+
+impl Focusable for MemoryView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for MemoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.fetch_memory(cx);
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("MemoryView")
+            .size_full()
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(self.render_controls(cx))
+            .child(Divider::horizontal())
+            .child(div().relative().flex_1().size_full().p_1().map(|this| {
+                if self.memory_reference.is_none() {
+                    this.child(
+                        Label::new("Open a variable's memory from the Variables panel")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.entity().clone(),
+                            "memory-view",
+                            self.row_count(),
+                            |this, range, _window, cx| this.render_rows(range, cx),
+                        )
+                        .track_scroll(self.list_handle.clone())
+                        .size_full(),
+                    )
+                    .child(self.render_vertical_scrollbar(cx))
+                }
+            }))
+    }
+}
@@ -2,9 +2,9 @@ use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
 use dap::{ScopePresentationHint, StackFrameId, VariablePresentationHintKind, VariableReference};
 use editor::Editor;
 use gpui::{
-    AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, FocusHandle, Focusable,
-    Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription, TextStyleRefinement,
-    UniformListScrollHandle, actions, anchored, deferred, uniform_list,
+    AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription,
+    TextStyleRefinement, UniformListScrollHandle, actions, anchored, deferred, uniform_list,
 };
 use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
 use project::debugger::session::{Session, SessionEvent};
@@ -14,6 +14,11 @@ use util::{debug_panic, maybe};
 
 actions!(variable_list, [ExpandSelectedEntry, CollapseSelectedEntry]);
 
+pub enum VariableListEvent {
+    /// The user asked to inspect the memory behind a variable's `memoryReference`.
+    OpenMemory(String),
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) struct EntryState {
     depth: usize,
@@ -503,6 +508,18 @@ impl VariableList {
 
         let variable_value = dap_var.value.clone();
         let variable_name = dap_var.name.clone();
+        let data_breakpoint_name = dap_var.name.clone();
+        let memory_reference = dap_var.memory_reference.clone();
+        let parent_reference = self
+            .entry_states
+            .get(&variable.path)
+            .map(|state| state.parent_reference);
+        let capabilities = self.session.read(cx).capabilities();
+        let supports_read_memory = capabilities
+            .supports_read_memory_request
+            .unwrap_or_default();
+        let supports_data_breakpoints = capabilities.supports_data_breakpoints.unwrap_or_default();
+        let frame_id = self.selected_stack_frame_id;
         let this = cx.entity().clone();
 
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
@@ -515,13 +532,47 @@ impl VariableList {
                     cx.write_to_clipboard(ClipboardItem::new_string(variable_value.clone()))
                 }
             })
-            .entry("Set value", None, move |window, cx| {
-                this.update(cx, |variable_list, cx| {
-                    let editor = Self::create_variable_editor(&variable_value, window, cx);
-                    variable_list.edited_path = Some((variable.path.clone(), editor));
-
-                    cx.notify();
-                });
+            .entry("Set value", None, {
+                let this = this.clone();
+                let variable = variable.clone();
+                move |window, cx| {
+                    this.update(cx, |variable_list, cx| {
+                        let editor = Self::create_variable_editor(&variable_value, window, cx);
+                        variable_list.edited_path = Some((variable.path.clone(), editor));
+
+                        cx.notify();
+                    });
+                }
+            })
+            .when_some(
+                memory_reference.filter(|_| supports_read_memory),
+                |menu, memory_reference| {
+                    let this = this.clone();
+                    menu.separator().entry("View Memory", None, move |_, cx| {
+                        this.update(cx, |_, cx| {
+                            cx.emit(VariableListEvent::OpenMemory(memory_reference.clone()));
+                        });
+                    })
+                },
+            )
+            .when(supports_data_breakpoints, |menu| {
+                menu.entry("Break When Value Changes", None, {
+                    let this = this.clone();
+                    move |_, cx| {
+                        this.update(cx, |variable_list, cx| {
+                            variable_list.session.update(cx, |session, cx| {
+                                session
+                                    .create_data_breakpoint(
+                                        parent_reference,
+                                        data_breakpoint_name.clone(),
+                                        frame_id,
+                                        cx,
+                                    )
+                                    .detach_and_log_err(cx);
+                            });
+                        });
+                    }
+                })
             })
         });
 
@@ -918,6 +969,8 @@ impl VariableList {
     }
 }
 
+impl EventEmitter<VariableListEvent> for VariableList {}
+
 impl Focusable for VariableList {
     fn focus_handle(&self, _: &App) -> gpui::FocusHandle {
         self.focus_handle.clone()
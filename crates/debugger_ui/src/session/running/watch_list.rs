@@ -0,0 +1,623 @@
+use super::stack_frame_list::{StackFrameList, StackFrameListEvent};
+use collections::HashSet;
+use dap::{StackFrameId, VariableReference};
+use editor::{Editor, EditorElement, EditorStyle};
+use gpui::{
+    AnyElement, ClickEvent, Entity, FocusHandle, Focusable, MouseButton, Stateful, Subscription,
+    Task, TextStyle, TextStyleRefinement, UniformListScrollHandle, uniform_list,
+};
+use menu::Confirm;
+use project::{
+    Project,
+    debugger::session::{Session, SessionEvent, Watcher},
+};
+use settings::Settings;
+use std::{ops::Range, sync::Arc};
+use theme::ThemeSettings;
+use ui::{Divider, ListItem, Scrollbar, ScrollbarState, Tooltip, prelude::*};
+use util::ResultExt;
+
+use crate::persistence;
+
+/// The path of an entry in the watch list, starting with the watch expression
+/// followed by the expanded children leading to it.
+///
+/// Each segment holds the index of the entry among its siblings along with its name,
+/// since siblings can share a name (e.g. shadowed variables).
+type WatchEntryPath = Arc<[(usize, SharedString)]>;
+
+#[derive(Debug, Clone)]
+enum WatchEntryKind {
+    Expression(Watcher),
+    Pending(SharedString),
+    Child(dap::Variable),
+}
+
+#[derive(Debug, Clone)]
+struct WatchEntry {
+    kind: WatchEntryKind,
+    path: WatchEntryPath,
+    variables_reference: VariableReference,
+}
+
+impl WatchEntry {
+    fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    fn item_id(&self) -> SharedString {
+        let path = self
+            .path
+            .iter()
+            .map(|(ix, name)| format!("{ix}-{name}"))
+            .collect::<Vec<_>>();
+        SharedString::from(format!("watch-entry-{}", path.join("-")))
+    }
+}
+
+pub(crate) struct WatchList {
+    session: Entity<Session>,
+    expressions: Vec<SharedString>,
+    entries: Vec<WatchEntry>,
+    expanded: HashSet<WatchEntryPath>,
+    selected_stack_frame_id: Option<StackFrameId>,
+    serialization_key: Option<String>,
+    new_watch_editor: Entity<Editor>,
+    edited_expression: Option<(SharedString, Entity<Editor>)>,
+    list_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    focus_handle: FocusHandle,
+    disabled: bool,
+    _serialize_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl WatchList {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        stack_frame_list: Entity<StackFrameList>,
+        project: &Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+
+        let new_watch_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Add a watch expression", cx);
+            editor.set_use_autoclose(false);
+            editor.set_show_gutter(false, cx);
+            editor.set_show_wrap_guides(false, cx);
+            editor.set_show_indent_guides(false, cx);
+            editor
+        });
+
+        let serialization_key = persistence::watch_expressions_key(project, cx);
+        let expressions = serialization_key
+            .as_deref()
+            .map(persistence::get_serialized_watch_expressions)
+            .unwrap_or_default();
+
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, Self::handle_stack_frame_list_events),
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_) => {
+                    this.edited_expression.take();
+                    this.selected_stack_frame_id.take();
+                    cx.notify();
+                }
+                SessionEvent::Watchers | SessionEvent::Variables => cx.notify(),
+                _ => {}
+            }),
+            cx.on_focus_out(&focus_handle, window, |this, _, _, cx| {
+                this.edited_expression.take();
+                cx.notify();
+            }),
+        ];
+
+        let list_handle = UniformListScrollHandle::default();
+
+        Self {
+            scrollbar_state: ScrollbarState::new(list_handle.clone()),
+            list_handle,
+            session,
+            expressions,
+            entries: Vec::default(),
+            expanded: HashSet::default(),
+            selected_stack_frame_id: None,
+            serialization_key,
+            new_watch_editor,
+            edited_expression: None,
+            focus_handle,
+            disabled: false,
+            _serialize_task: None,
+            _subscriptions,
+        }
+    }
+
+    pub(super) fn disabled(&mut self, disabled: bool, cx: &mut Context<Self>) {
+        if self.disabled != disabled {
+            self.disabled = disabled;
+            cx.notify();
+        }
+    }
+
+    fn handle_stack_frame_list_events(
+        &mut self,
+        _: Entity<StackFrameList>,
+        event: &StackFrameListEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            StackFrameListEvent::SelectedStackFrameChanged(stack_frame_id) => {
+                self.selected_stack_frame_id = Some(*stack_frame_id);
+                self.evaluate_watchers(cx);
+            }
+        }
+    }
+
+    /// Re-evaluates every watch expression in the context of the selected stack frame.
+    fn evaluate_watchers(&mut self, cx: &mut Context<Self>) {
+        let frame_id = self.selected_stack_frame_id;
+        let expressions = self.expressions.clone();
+
+        self.session.update(cx, |session, cx| {
+            session.refresh_watchers(frame_id, cx);
+
+            for expression in expressions {
+                if session.watcher(&expression).is_none() {
+                    session.add_watcher(expression, frame_id, cx).detach();
+                }
+            }
+        });
+        cx.notify();
+    }
+
+    pub(crate) fn add_watch_expression(
+        &mut self,
+        expression: SharedString,
+        cx: &mut Context<Self>,
+    ) {
+        if expression.trim().is_empty() || self.expressions.contains(&expression) {
+            return;
+        }
+
+        self.expressions.push(expression.clone());
+        self.serialize(cx);
+
+        if self.selected_stack_frame_id.is_some() {
+            let frame_id = self.selected_stack_frame_id;
+            self.session.update(cx, |session, cx| {
+                session.add_watcher(expression, frame_id, cx).detach();
+            });
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn remove_watch_expression(&mut self, expression: &str, cx: &mut Context<Self>) {
+        self.expressions
+            .retain(|existing| existing.as_ref() != expression);
+        self.expanded.retain(|path| {
+            path.first()
+                .is_none_or(|(_, root)| root.as_ref() != expression)
+        });
+        self.session.update(cx, |session, cx| {
+            session.remove_watcher(expression, cx);
+        });
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn watch_expressions(&self) -> &[SharedString] {
+        &self.expressions
+    }
+
+    #[cfg(test)]
+    pub(crate) fn toggle_entry_at(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let path = self.entries[ix].path.clone();
+        self.toggle_entry(&path, cx);
+    }
+
+    /// Rebuilds the entries, as rendering does, and returns them indented by their depth
+    /// and marked with whether they're expanded.
+    #[cfg(test)]
+    pub(crate) fn visual_entries(&mut self, cx: &mut Context<Self>) -> Vec<String> {
+        const INDENT: &'static str = "    ";
+
+        self.build_entries(cx);
+        self.entries
+            .iter()
+            .map(|entry| {
+                let name = match &entry.kind {
+                    WatchEntryKind::Expression(watcher) => watcher.expression.to_string(),
+                    WatchEntryKind::Pending(expression) => expression.to_string(),
+                    WatchEntryKind::Child(variable) => variable.name.clone(),
+                };
+                format!(
+                    "{}{} {}",
+                    INDENT.repeat(entry.depth() - 1),
+                    if self.expanded.contains(&entry.path) {
+                        "v"
+                    } else {
+                        ">"
+                    },
+                    name
+                )
+            })
+            .collect()
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let Some(key) = self.serialization_key.clone() else {
+            return;
+        };
+        let expressions = self.expressions.clone();
+
+        self._serialize_task = Some(cx.background_spawn(async move {
+            persistence::serialize_watch_expressions(key, expressions)
+                .await
+                .log_err();
+        }));
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((expression, editor)) = self
+            .edited_expression
+            .take()
+            .filter(|(_, editor)| editor.focus_handle(cx).is_focused(window))
+        {
+            let value = editor.read(cx).text(cx);
+            let frame_id = self.selected_stack_frame_id;
+            self.session.update(cx, |session, cx| {
+                session.set_expression(expression, value, frame_id, cx);
+            });
+            self.focus_handle.focus(window);
+            cx.notify();
+            return;
+        }
+
+        let expression = self.new_watch_editor.update(cx, |editor, cx| {
+            let expression = editor.text(cx);
+            editor.clear(window, cx);
+            expression
+        });
+
+        self.add_watch_expression(expression.trim().to_string().into(), cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.edited_expression.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    fn toggle_entry(&mut self, path: &WatchEntryPath, cx: &mut Context<Self>) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.clone());
+        }
+        cx.notify();
+    }
+
+    fn build_entries(&mut self, cx: &mut Context<Self>) {
+        let mut entries = Vec::with_capacity(self.expressions.len());
+
+        for expression in self.expressions.iter() {
+            let path: WatchEntryPath = Arc::from([(0, expression.clone())]);
+            let Some(watcher) = self.session.read(cx).watcher(expression).cloned() else {
+                entries.push(WatchEntry {
+                    kind: WatchEntryKind::Pending(expression.clone()),
+                    path,
+                    variables_reference: 0,
+                });
+                continue;
+            };
+
+            let variables_reference = watcher.variables_reference;
+            entries.push(WatchEntry {
+                kind: WatchEntryKind::Expression(watcher),
+                path: path.clone(),
+                variables_reference,
+            });
+
+            let mut stack = vec![(path, variables_reference)];
+            while let Some((path, variables_reference)) = stack.pop() {
+                if variables_reference == 0 || !self.expanded.contains(&path) {
+                    continue;
+                }
+
+                let children = self
+                    .session
+                    .update(cx, |session, cx| session.variables(variables_reference, cx));
+                let insert_ix = entries
+                    .iter()
+                    .position(|entry| entry.path == path)
+                    .map_or(entries.len(), |ix| ix + 1);
+
+                let children = children
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, child)| {
+                        let child_path: WatchEntryPath = path
+                            .iter()
+                            .cloned()
+                            .chain(std::iter::once((
+                                ix,
+                                SharedString::from(child.name.clone()),
+                            )))
+                            .collect();
+                        WatchEntry {
+                            variables_reference: child.variables_reference,
+                            path: child_path,
+                            kind: WatchEntryKind::Child(child),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                stack.extend(
+                    children
+                        .iter()
+                        .rev()
+                        .map(|child| (child.path.clone(), child.variables_reference)),
+                );
+                entries.splice(insert_ix..insert_ix, children);
+            }
+        }
+
+        self.entries = entries;
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| {
+                let entry = self.entries.get(ix)?.clone();
+                Some(self.render_entry(&entry, window, cx))
+            })
+            .collect()
+    }
+
+    fn render_entry(
+        &self,
+        entry: &WatchEntry,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let (name, value, is_error) = match &entry.kind {
+            WatchEntryKind::Expression(watcher) => (
+                watcher.expression.clone(),
+                Some(watcher.value.clone()),
+                watcher.is_error,
+            ),
+            WatchEntryKind::Pending(expression) => (expression.clone(), None, false),
+            WatchEntryKind::Child(variable) => (
+                variable.name.clone().into(),
+                Some(variable.value.clone().into()),
+                false,
+            ),
+        };
+
+        let is_root = entry.depth() == 1;
+        let supports_set_expression = is_root
+            && !self.disabled
+            && self
+                .session
+                .read(cx)
+                .capabilities()
+                .supports_set_expression
+                .unwrap_or_default();
+        let edited_editor = self
+            .edited_expression
+            .as_ref()
+            .filter(|(expression, _)| is_root && *expression == name)
+            .map(|(_, editor)| editor.clone());
+
+        let value_color = if is_error {
+            Color::Error
+        } else if self.disabled {
+            Color::Disabled
+        } else {
+            Color::Muted
+        };
+
+        ListItem::new(entry.item_id())
+            .selectable(false)
+            .disabled(self.disabled)
+            .indent_level(entry.depth())
+            .indent_step_size(px(20.))
+            .always_show_disclosure_icon(true)
+            .when(entry.variables_reference > 0, |list_item| {
+                let path = entry.path.clone();
+                list_item
+                    .toggle(self.expanded.contains(&entry.path))
+                    .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_entry(&path, cx)))
+            })
+            .when(is_root, |list_item| {
+                let expression = name.clone();
+                list_item.end_hover_slot(
+                    IconButton::new(
+                        SharedString::from(format!("watch-remove-{}", expression)),
+                        IconName::Close,
+                    )
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(Tooltip::text("Remove Watch Expression"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.remove_watch_expression(&expression, cx);
+                    })),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .text_ui_sm(cx)
+                    .w_full()
+                    .child(Label::new(name.clone()).color(if self.disabled {
+                        Color::Disabled
+                    } else {
+                        Color::Default
+                    }))
+                    .map(|this| {
+                        if let Some(editor) = edited_editor {
+                            this.child(div().size_full().px_2().child(editor))
+                        } else {
+                            this.child(
+                                div()
+                                    .id(SharedString::from(format!("{}-value", entry.item_id())))
+                                    .w_full()
+                                    .when(supports_set_expression, |this| {
+                                        let expression = name.clone();
+                                        let current_value = value.clone().unwrap_or_default();
+                                        this.on_click(cx.listener(
+                                            move |this, click: &ClickEvent, window, cx| {
+                                                if click.down.click_count < 2 {
+                                                    return;
+                                                }
+                                                let editor = Self::create_value_editor(
+                                                    &current_value,
+                                                    window,
+                                                    cx,
+                                                );
+                                                this.edited_expression =
+                                                    Some((expression.clone(), editor));
+                                                cx.notify();
+                                            },
+                                        ))
+                                    })
+                                    .child(
+                                        Label::new(match value {
+                                            Some(value) => format!("=  {value}"),
+                                            None => "=  <not available>".to_string(),
+                                        })
+                                        .single_line()
+                                        .truncate()
+                                        .size(LabelSize::Small)
+                                        .color(value_color),
+                                    ),
+                            )
+                        }
+                    }),
+            )
+            .into_any_element()
+    }
+
+    fn create_value_editor(default: &str, window: &mut Window, cx: &mut App) -> Entity<Editor> {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+
+            let refinement = TextStyleRefinement {
+                font_size: Some(
+                    TextSize::XSmall
+                        .rems(cx)
+                        .to_pixels(window.rem_size())
+                        .into(),
+                ),
+                ..Default::default()
+            };
+            editor.set_text_style_refinement(refinement);
+            editor.set_text(default, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        editor
+    }
+
+    fn editor_style(&self, cx: &Context<Self>) -> EditorStyle {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.buffer_font.family.clone(),
+            font_features: settings.buffer_font.features.clone(),
+            font_size: settings.buffer_font_size(cx).into(),
+            font_weight: settings.buffer_font.weight,
+            line_height: relative(settings.buffer_line_height.value()),
+            ..Default::default()
+        };
+        EditorStyle {
+            background: cx.theme().colors().editor_background,
+            local_player: cx.theme().players().local(),
+            text: text_style,
+            ..Default::default()
+        }
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("watch-list-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+}
+
+impl Focusable for WatchList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for WatchList {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.build_entries(cx);
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("WatchList")
+            .size_full()
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .size_full()
+                    .child(
+                        uniform_list(
+                            cx.entity().clone(),
+                            "watch-list",
+                            self.entries.len(),
+                            move |this, range, window, cx| this.render_entries(range, window, cx),
+                        )
+                        .track_scroll(self.list_handle.clone())
+                        .gap_1_5()
+                        .size_full(),
+                    )
+                    .child(self.render_vertical_scrollbar(cx)),
+            )
+            .child(Divider::horizontal())
+            .child(div().px_1().child(EditorElement::new(
+                &self.new_watch_editor,
+                self.editor_style(cx),
+            )))
+    }
+}
@@ -21,11 +21,17 @@ mod dap_logger;
 #[cfg(test)]
 mod debugger_panel;
 #[cfg(test)]
+mod disassembly_view;
+#[cfg(test)]
+mod memory_view;
+#[cfg(test)]
 mod module_list;
 #[cfg(test)]
 mod stack_frame_list;
 #[cfg(test)]
 mod variable_list;
+#[cfg(test)]
+mod watch_list;
 
 pub fn init_test(cx: &mut gpui::TestAppContext) {
     if std::env::var("RUST_LOG").is_ok() {
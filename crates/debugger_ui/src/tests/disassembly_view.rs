@@ -0,0 +1,205 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use crate::{
+    DebugPanel,
+    persistence::DebuggerPaneItem,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, SteppingGranularity,
+    requests::{Disassemble, Initialize, Next, Scopes, SetInstructionBreakpoints, StackTrace},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use util::path;
+
+fn instruction(address: u64, instruction: &str) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: format!("0x{address:016x}"),
+        instruction_bytes: Some("90".into()),
+        instruction: instruction.into(),
+        symbol: Some("main".into()),
+        location: None,
+        line: None,
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_follows_instruction_pointer(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "src": {
+               "main.c": "int main() { return 0; }",
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1001".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    client.on_request::<Disassemble, _>(move |_, args| {
+        assert_eq!("0x1001", args.memory_reference);
+        assert!(args.instruction_offset.is_some_and(|offset| offset < 0));
+
+        Ok(dap::DisassembleResponse {
+            instructions: vec![
+                instruction(0x1000, "push rbp"),
+                instruction(0x1001, "mov rbp, rsp"),
+                instruction(0x1004, "xor eax, eax"),
+            ],
+        })
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            let running_state = item.running_state().clone();
+            running_state.update(cx, |running_state, cx| {
+                running_state.ensure_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                running_state.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+            });
+            running_state
+        });
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        let disassembly_view = running_state.disassembly_view().read(cx);
+        assert_eq!(Some("0x1001"), disassembly_view.instruction_pointer());
+        assert_eq!(
+            vec!["push rbp", "mov rbp, rsp", "xor eax, eax"],
+            disassembly_view
+                .instructions()
+                .iter()
+                .map(|instruction| instruction.instruction.as_str())
+                .collect::<Vec<_>>()
+        );
+    });
+
+    let set_instruction_breakpoints_called = Arc::new(AtomicBool::new(false));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let called = set_instruction_breakpoints_called.clone();
+        move |_, args| {
+            assert_eq!(1, args.breakpoints.len());
+            assert_eq!(
+                "0x0000000000001004",
+                args.breakpoints[0].instruction_reference
+            );
+            called.store(true, Ordering::SeqCst);
+
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: vec![],
+            })
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x0000000000001004".into(), cx);
+    });
+    cx.run_until_parked();
+
+    assert!(
+        set_instruction_breakpoints_called.load(Ordering::SeqCst),
+        "Toggling an instruction breakpoint should send it to the adapter"
+    );
+    session.update(cx, |session, _| {
+        assert!(session.has_instruction_breakpoint("0x0000000000001004"));
+    });
+
+    let step_called = Arc::new(AtomicBool::new(false));
+    client.on_request::<Next, _>({
+        let step_called = step_called.clone();
+        move |_, args| {
+            assert_eq!(Some(SteppingGranularity::Instruction), args.granularity);
+            step_called.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.step_over_instruction(cx);
+    });
+    cx.run_until_parked();
+
+    assert!(
+        step_called.load(Ordering::SeqCst),
+        "Stepping over an instruction should send a next request with instruction granularity"
+    );
+}
@@ -0,0 +1,319 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    DebugPanel,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame,
+    requests::{
+        DataBreakpointInfo, Initialize, ReadMemory, Scopes, SetDataBreakpoints, StackTrace,
+        WriteMemory,
+    },
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use util::path;
+
+#[gpui::test]
+async fn test_memory_view_and_data_breakpoints(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "src": {
+               "main.c": "int counter = 0;",
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_read_memory_request: Some(true),
+                supports_write_memory_request: Some(true),
+                supports_data_breakpoints: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    client.on_request::<ReadMemory, _>(move |_, args| {
+        assert_eq!("0x2000", args.memory_reference);
+        assert_eq!(Some(0), args.offset);
+
+        Ok(dap::ReadMemoryResponse {
+            address: "0x2000".into(),
+            unreadable_bytes: Some(0),
+            // base64 encoding of [1, 2, 3]
+            data: Some("AQID".into()),
+        })
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            let running_state = item.running_state().clone();
+            running_state.update(cx, |running_state, cx| {
+                running_state.open_memory("0x2000".into(), window, cx);
+            });
+            running_state
+        });
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        let memory_view = running_state.memory_view().read(cx);
+        assert_eq!(Some("0x2000"), memory_view.memory_reference());
+        let region = memory_view.region().expect("memory should have been read");
+        assert_eq!("0x2000", region.address);
+        assert_eq!(&[1, 2, 3], &*region.data);
+    });
+
+    let write_count = Arc::new(AtomicUsize::new(0));
+    client.on_request::<WriteMemory, _>({
+        let write_count = write_count.clone();
+        move |_, args| {
+            assert_eq!("0x2000", args.memory_reference);
+            assert_eq!(Some(1), args.offset);
+            // base64 encoding of [255]
+            assert_eq!("/w==", args.data);
+            write_count.fetch_add(1, Ordering::SeqCst);
+
+            Ok(dap::WriteMemoryResponse {
+                offset: None,
+                bytes_written: Some(1),
+            })
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.write_memory("0x2000".into(), 1, &[0xff], cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(1, write_count.load(Ordering::SeqCst));
+
+    client.on_request::<DataBreakpointInfo, _>(move |_, args| {
+        assert_eq!("counter", args.name);
+        assert_eq!(Some(2), args.variables_reference);
+
+        Ok(dap::DataBreakpointInfoResponse {
+            data_id: Some("counter-id".into()),
+            description: "counter".into(),
+            access_types: Some(vec![dap::DataBreakpointAccessType::Write]),
+            can_persist: None,
+        })
+    });
+
+    let sent_data_breakpoints = Arc::new(AtomicUsize::new(usize::MAX));
+    client.on_request::<SetDataBreakpoints, _>({
+        let sent_data_breakpoints = sent_data_breakpoints.clone();
+        move |_, args| {
+            sent_data_breakpoints.store(args.breakpoints.len(), Ordering::SeqCst);
+            Ok(dap::SetDataBreakpointsResponse {
+                breakpoints: vec![],
+            })
+        }
+    });
+
+    session
+        .update(cx, |session, cx| {
+            session.create_data_breakpoint(Some(2), "counter".into(), Some(1), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(1, sent_data_breakpoints.load(Ordering::SeqCst));
+    session.update(cx, |session, _| {
+        let breakpoints = session.data_breakpoints().cloned().collect::<Vec<_>>();
+        assert_eq!(1, breakpoints.len());
+        assert_eq!("counter-id", breakpoints[0].dap.data_id);
+        assert_eq!(
+            Some(dap::DataBreakpointAccessType::Write),
+            breakpoints[0].dap.access_type
+        );
+        assert!(breakpoints[0].is_enabled);
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_data_breakpoint("counter-id", cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        0,
+        sent_data_breakpoints.load(Ordering::SeqCst),
+        "Disabled data breakpoints shouldn't be sent to the adapter"
+    );
+}
+
+#[gpui::test]
+async fn test_memory_cache_is_keyed_by_request_and_cleared_on_continue(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.c": "int main() {}" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_read_memory_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse { threads: vec![] })
+    });
+
+    let read_count = Arc::new(AtomicUsize::new(0));
+    client.on_request::<ReadMemory, _>({
+        let read_count = read_count.clone();
+        move |_, args| {
+            read_count.fetch_add(1, Ordering::SeqCst);
+            Ok(dap::ReadMemoryResponse {
+                address: "0x2000".into(),
+                unreadable_bytes: Some(0),
+                // base64 encodings of [1, 2, 3] and [1]
+                data: Some(if args.count == 3 { "AQID" } else { "AQ==" }.into()),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let read_memory = |count: u64, cx: &mut VisualTestContext| {
+        session.update(cx, |session, cx| {
+            session
+                .read_memory("0x2000".into(), 0, count, cx)
+                .map(|region| region.data.to_vec())
+        })
+    };
+
+    assert_eq!(None, read_memory(3, cx));
+    cx.run_until_parked();
+    assert_eq!(Some(vec![1, 2, 3]), read_memory(3, cx));
+    assert_eq!(1, read_count.load(Ordering::SeqCst));
+
+    // Reading fewer bytes from the same address doesn't reuse the larger read.
+    assert_eq!(None, read_memory(1, cx));
+    cx.run_until_parked();
+    assert_eq!(Some(vec![1]), read_memory(1, cx));
+    assert_eq!(Some(vec![1, 2, 3]), read_memory(3, cx));
+    assert_eq!(2, read_count.load(Ordering::SeqCst));
+
+    // Memory may change while the debuggee runs, so continuing clears the cache,
+    // and it's read again once the debuggee stops.
+    client
+        .fake_event(dap::messages::Events::Continued(dap::ContinuedEvent {
+            thread_id: 1,
+            all_threads_continued: Some(true),
+        }))
+        .await;
+    cx.run_until_parked();
+    assert_eq!(None, read_memory(3, cx));
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    assert_eq!(None, read_memory(3, cx));
+    cx.run_until_parked();
+    assert_eq!(Some(vec![1, 2, 3]), read_memory(3, cx));
+    assert_eq!(3, read_count.load(Ordering::SeqCst));
+}
@@ -0,0 +1,370 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    DebugPanel,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, Variable,
+    requests::{Evaluate, Initialize, Scopes, SetExpression, StackTrace, Variables},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use util::path;
+
+#[gpui::test]
+async fn test_watch_expressions_are_evaluated_on_stop(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "src": {
+               "test.js": "let counter = 1;",
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_set_expression: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "Stack Frame 1".into(),
+                source: Some(dap::Source {
+                    name: Some("test.js".into()),
+                    path: Some(path!("/project/src/test.js").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    let evaluate_count = Arc::new(AtomicUsize::new(0));
+    client.on_request::<Evaluate, _>({
+        let evaluate_count = evaluate_count.clone();
+        move |_, args| {
+            assert_eq!("counter", args.expression);
+            assert_eq!(Some(1), args.frame_id);
+            assert_eq!(Some(dap::EvaluateArgumentsContext::Watch), args.context);
+
+            let count = evaluate_count.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(dap::EvaluateResponse {
+                result: count.to_string(),
+                type_: Some("number".into()),
+                presentation_hint: None,
+                variables_reference: 0,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    client.on_request::<SetExpression, _>(move |_, args| {
+        assert_eq!("counter", args.expression);
+        assert_eq!("10", args.value);
+        assert_eq!(Some(1), args.frame_id);
+
+        Ok(dap::SetExpressionResponse {
+            value: "10".into(),
+            type_: None,
+            presentation_hint: None,
+            variables_reference: None,
+            named_variables: None,
+            indexed_variables: None,
+            memory_reference: None,
+            value_location_reference: None,
+        })
+    });
+
+    let stopped_event = dap::messages::Events::Stopped(dap::StoppedEvent {
+        reason: dap::StoppedEventReason::Pause,
+        description: None,
+        thread_id: Some(1),
+        preserve_focus_hint: None,
+        text: None,
+        all_threads_stopped: None,
+        hit_breakpoint_ids: None,
+    });
+
+    client.fake_event(stopped_event.clone()).await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.watch_list().update(cx, |watch_list, cx| {
+            watch_list.add_watch_expression("counter".into(), cx);
+        });
+    });
+    cx.run_until_parked();
+
+    running_state.update(cx, |running_state, cx| {
+        let watch_expressions = running_state
+            .watch_list()
+            .read(cx)
+            .watch_expressions()
+            .to_vec();
+        assert_eq!(vec![gpui::SharedString::from("counter")], watch_expressions);
+    });
+    session.update(cx, |session, _| {
+        let watcher = session.watcher("counter").unwrap();
+        assert_eq!("1", watcher.value.as_ref());
+        assert_eq!(Some(1), watcher.frame_id);
+        assert!(!watcher.is_error);
+    });
+
+    client.fake_event(stopped_event).await;
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert_eq!(
+            "2",
+            session.watcher("counter").unwrap().value.as_ref(),
+            "Watch expressions should be re-evaluated when the debuggee stops"
+        );
+    });
+
+    session.update(cx, |session, cx| {
+        session.set_expression("counter".into(), "10".into(), Some(1), cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        3,
+        evaluate_count.load(Ordering::SeqCst),
+        "Watch expressions should be re-evaluated after setting an expression"
+    );
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.watch_list().update(cx, |watch_list, cx| {
+            watch_list.remove_watch_expression("counter", cx);
+        });
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        assert!(session.watcher("counter").is_none());
+    });
+}
+
+#[gpui::test]
+async fn test_watch_children_with_the_same_name_expand_separately(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "src": {
+               "test.js": "let counter = 1;",
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "Stack Frame 1".into(),
+                source: Some(dap::Source {
+                    name: Some("test.js".into()),
+                    path: Some(path!("/project/src/test.js").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    client.on_request::<Evaluate, _>(move |_, args| {
+        assert_eq!("scopes", args.expression);
+        Ok(dap::EvaluateResponse {
+            result: "Scopes".into(),
+            type_: None,
+            presentation_hint: None,
+            variables_reference: 2,
+            named_variables: None,
+            indexed_variables: None,
+            memory_reference: None,
+            value_location_reference: None,
+        })
+    });
+
+    // A shadowed variable appears twice among the children of the watch expression.
+    let variable = |name: &str, variables_reference| Variable {
+        name: name.into(),
+        value: String::new(),
+        type_: None,
+        presentation_hint: None,
+        evaluate_name: None,
+        variables_reference,
+        named_variables: None,
+        indexed_variables: None,
+        memory_reference: None,
+        declaration_location_reference: None,
+        value_location_reference: None,
+    };
+    let variables = Arc::new(collections::HashMap::from_iter([
+        (2, vec![variable("value", 3), variable("value", 4)]),
+        (3, vec![variable("outer", 0)]),
+        (4, vec![variable("inner", 0)]),
+    ]));
+    client.on_request::<Variables, _>(move |_, args| {
+        Ok(dap::VariablesResponse {
+            variables: variables.get(&args.variables_reference).unwrap().clone(),
+        })
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+    cx.run_until_parked();
+
+    let watch_list =
+        running_state.update(cx, |running_state, _| running_state.watch_list().clone());
+    watch_list.update(cx, |watch_list, cx| {
+        watch_list.add_watch_expression("scopes".into(), cx);
+    });
+    cx.run_until_parked();
+
+    watch_list.update(cx, |watch_list, cx| {
+        watch_list.visual_entries(cx);
+        watch_list.toggle_entry_at(0, cx);
+        watch_list.visual_entries(cx);
+    });
+    cx.run_until_parked();
+
+    watch_list.update(cx, |watch_list, cx| {
+        assert_eq!(
+            watch_list.visual_entries(cx),
+            ["v scopes", "    > value", "    > value"]
+        );
+        watch_list.toggle_entry_at(2, cx);
+        watch_list.visual_entries(cx);
+    });
+    cx.run_until_parked();
+
+    // Only the expanded sibling shows its children.
+    watch_list.update(cx, |watch_list, cx| {
+        assert_eq!(
+            watch_list.visual_entries(cx),
+            ["v scopes", "    > value", "    v value", "        > inner"]
+        );
+    });
+}
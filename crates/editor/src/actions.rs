@@ -306,7 +306,9 @@ actions!(
         GoToDefinitionSplit,
         GoToDiagnostic,
         GoToHunk,
+        GoToHunkSkippingMoves,
         GoToPreviousHunk,
+        GoToPreviousHunkSkippingMoves,
         GoToImplementation,
         GoToImplementationSplit,
         GoToNextChange,
@@ -357,6 +359,7 @@ actions!(
         OpenDocs,
         OpenPermalinkToLine,
         OpenSelectionsInMultibuffer,
+        OpenSplitDiff,
         OpenUrl,
         OrganizeImports,
         Outdent,
@@ -37,6 +37,7 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod split_diff_editor;
 pub mod tasks;
 
 #[cfg(test)]
@@ -136,6 +137,7 @@ pub use proposed_changes_editor::{
     ProposedChangeLocation, ProposedChangesEditor, ProposedChangesEditorToolbar,
 };
 use smallvec::smallvec;
+pub use split_diff_editor::SplitDiffEditor;
 use std::{cell::OnceCell, iter::Peekable};
 use task::{ResolvedTask, RunnableTag, TaskTemplate, TaskVariables};
 
@@ -219,6 +221,7 @@ const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LINE_LEN: usize = 1024;
 const MIN_NAVIGATION_HISTORY_ROW_DELTA: i64 = 10;
 const MAX_SELECTION_HISTORY_LEN: usize = 1024;
+pub(crate) const DIFF_WORD_HIGHLIGHT_OPACITY: f32 = 0.3;
 pub(crate) const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
 #[doc(hidden)]
 pub const CODE_ACTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
@@ -13681,6 +13684,15 @@ impl Editor {
             self.hunk_before_position(snapshot, position)
         };
 
+        self.go_to_hunk_row(row, window, cx);
+    }
+
+    fn go_to_hunk_row(
+        &mut self,
+        row: Option<MultiBufferRow>,
+        window: &mut Window,
+        cx: &mut Context<Editor>,
+    ) {
         if let Some(row) = row {
             let destination = Point::new(row.0, 0);
             let autoscroll = Autoscroll::center();
@@ -13738,6 +13750,45 @@ impl Editor {
             .or_else(|| snapshot.buffer_snapshot.diff_hunk_before(Point::MAX))
     }
 
+    /// Like [`Self::go_to_next_hunk`], but skips hunks whose lines were only
+    /// moved, so that reviewers can step through the actual changes.
+    fn go_to_next_hunk_skipping_moves(
+        &mut self,
+        _: &GoToHunkSkippingMoves,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.hide_mouse_cursor(&HideMouseCursorOrigin::MovementAction);
+        let snapshot = self.snapshot(window, cx);
+        let position = self.selections.newest::<Point>(cx).head();
+        let rows = unmoved_hunk_rows(&snapshot);
+        let row = rows
+            .iter()
+            .find(|row| row.0 > position.row)
+            .or_else(|| rows.first())
+            .copied();
+        self.go_to_hunk_row(row, window, cx);
+    }
+
+    fn go_to_prev_hunk_skipping_moves(
+        &mut self,
+        _: &GoToPreviousHunkSkippingMoves,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.hide_mouse_cursor(&HideMouseCursorOrigin::MovementAction);
+        let snapshot = self.snapshot(window, cx);
+        let position = self.selections.newest::<Point>(cx).head();
+        let rows = unmoved_hunk_rows(&snapshot);
+        let row = rows
+            .iter()
+            .rev()
+            .find(|row| row.0 < position.row)
+            .or_else(|| rows.last())
+            .copied();
+        self.go_to_hunk_row(row, window, cx);
+    }
+
     fn go_to_next_change(
         &mut self,
         _: &GoToNextChange,
@@ -15923,6 +15974,79 @@ impl Editor {
         self.do_stage_or_unstage_and_next(false, window, cx);
     }
 
+    pub fn stage_lines(&mut self, _: &::git::StageLines, _: &mut Window, cx: &mut Context<Self>) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        self.stage_or_unstage_diff_lines(true, ranges, cx);
+    }
+
+    pub fn unstage_lines(
+        &mut self,
+        _: &::git::UnstageLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        self.stage_or_unstage_diff_lines(false, ranges, cx);
+    }
+
+    /// Stages or unstages only the changed lines within the given ranges,
+    /// rather than the whole hunks they intersect.
+    pub fn stage_or_unstage_diff_lines(
+        &mut self,
+        stage: bool,
+        ranges: Vec<Range<Anchor>>,
+        cx: &mut Context<Self>,
+    ) {
+        let task = self.save_buffers_for_ranges_if_needed(&ranges, cx);
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |this, cx| {
+                let snapshot = this.buffer.read(cx).snapshot(cx);
+
+                // Deleted lines are shown from the diff's base text, so
+                // selections within them are kept as ranges of that text.
+                let mut buffer_ranges = HashMap::<BufferId, Vec<Range<usize>>>::default();
+                let mut base_text_ranges = HashMap::<BufferId, Vec<Range<usize>>>::default();
+                for range in &ranges {
+                    for (_, range, excerpt_id, deleted_hunk_anchor) in
+                        snapshot.range_to_buffer_ranges_with_deleted_hunks(range.clone())
+                    {
+                        let Some(buffer_id) = snapshot.buffer_id_for_excerpt(excerpt_id) else {
+                            continue;
+                        };
+                        let ranges = if deleted_hunk_anchor.is_some() {
+                            &mut base_text_ranges
+                        } else {
+                            &mut buffer_ranges
+                        };
+                        ranges.entry(buffer_id).or_default().push(range);
+                    }
+                }
+
+                let chunk_by = this
+                    .diff_hunks_in_ranges(&ranges, &snapshot)
+                    .chunk_by(|hunk| hunk.buffer_id);
+                for (buffer_id, hunks) in &chunk_by {
+                    this.do_stage_or_unstage_lines(
+                        stage,
+                        buffer_id,
+                        hunks,
+                        buffer_ranges
+                            .get(&buffer_id)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default(),
+                        base_text_ranges
+                            .get(&buffer_id)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default(),
+                        cx,
+                    );
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn stage_or_unstage_diff_hunks(
         &mut self,
         stage: bool,
@@ -16043,6 +16167,7 @@ impl Editor {
                         buffer_range: hunk.buffer_range,
                         diff_base_byte_range: hunk.diff_base_byte_range,
                         secondary_status: hunk.secondary_status,
+                        moved: hunk.moved,
                         range: Point::zero()..Point::zero(), // unused
                     })
                     .collect::<Vec<_>>(),
@@ -16054,6 +16179,40 @@ impl Editor {
         None
     }
 
+    fn do_stage_or_unstage_lines(
+        &self,
+        stage: bool,
+        buffer_id: BufferId,
+        hunks: impl Iterator<Item = MultiBufferDiffHunk>,
+        buffer_ranges: &[Range<usize>],
+        base_text_ranges: &[Range<usize>],
+        cx: &mut App,
+    ) -> Option<()> {
+        let project = self.project.as_ref()?;
+        let buffer = project.read(cx).buffer_for_id(buffer_id, cx)?;
+        let diff = self.buffer.read(cx).diff_for(buffer_id)?;
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(
+                stage,
+                &hunks
+                    .map(|hunk| buffer_diff::DiffHunk {
+                        buffer_range: hunk.buffer_range,
+                        diff_base_byte_range: hunk.diff_base_byte_range,
+                        secondary_status: hunk.secondary_status,
+                        moved: hunk.moved,
+                        range: Point::zero()..Point::zero(), // unused
+                    })
+                    .collect::<Vec<_>>(),
+                buffer_ranges,
+                base_text_ranges,
+                &buffer_snapshot,
+                cx,
+            )
+        });
+        None
+    }
+
     pub fn expand_selected_diff_hunks(&mut self, cx: &mut Context<Self>) {
         let ranges: Vec<_> = self.selections.disjoint.iter().map(|s| s.range()).collect();
         self.buffer
@@ -17462,6 +17621,87 @@ impl Editor {
         results
     }
 
+    /// Returns highlights for the changed words within the expanded, modified
+    /// diff hunks that intersect the given range.
+    pub fn diff_word_highlights_in_range(
+        &self,
+        search_range: Range<Anchor>,
+        display_snapshot: &DisplaySnapshot,
+        theme: &ThemeColors,
+    ) -> Vec<(Range<DisplayPoint>, Hsla)> {
+        let buffer_snapshot = &display_snapshot.buffer_snapshot;
+        let deleted_color = theme
+            .version_control_deleted
+            .opacity(DIFF_WORD_HIGHLIGHT_OPACITY);
+        let added_color = theme
+            .version_control_added
+            .opacity(DIFF_WORD_HIGHLIGHT_OPACITY);
+
+        let mut results = Vec::new();
+        for hunk in buffer_snapshot.diff_hunks_in_range(search_range) {
+            if !hunk.status().is_modified() {
+                continue;
+            }
+            let (Some(diff), Some(buffer)) = (
+                buffer_snapshot.diff_for_buffer_id(hunk.buffer_id),
+                buffer_snapshot.buffer_for_excerpt(hunk.excerpt_id),
+            ) else {
+                continue;
+            };
+            let base_text = diff.base_text();
+            let base_text_point = |offset: usize| {
+                Anchor {
+                    buffer_id: Some(hunk.buffer_id),
+                    excerpt_id: hunk.excerpt_id,
+                    text_anchor: hunk.buffer_range.start,
+                    diff_base_anchor: Some(base_text.anchor_after(offset)),
+                }
+                .to_display_point(display_snapshot)
+            };
+
+            // The deleted text, and so the hunk's word changes, are only
+            // displayed when the hunk is expanded.
+            if base_text_point(hunk.diff_base_byte_range.start)
+                == base_text_point(hunk.diff_base_byte_range.end)
+            {
+                continue;
+            }
+
+            let diff_hunk = buffer_diff::DiffHunk {
+                range: hunk.buffer_range.to_point(buffer),
+                buffer_range: hunk.buffer_range.clone(),
+                diff_base_byte_range: hunk.diff_base_byte_range.clone(),
+                secondary_status: hunk.secondary_status,
+                moved: hunk.moved,
+            };
+            let Some(word_diff) = diff.word_diff(&diff_hunk, buffer) else {
+                continue;
+            };
+            for range in &word_diff.base_text_ranges {
+                results.push((
+                    base_text_point(range.start)..base_text_point(range.end),
+                    deleted_color,
+                ));
+            }
+            for range in &word_diff.buffer_ranges {
+                let (Some(start), Some(end)) = (
+                    buffer_snapshot
+                        .anchor_in_excerpt(hunk.excerpt_id, buffer.anchor_after(range.start)),
+                    buffer_snapshot
+                        .anchor_in_excerpt(hunk.excerpt_id, buffer.anchor_before(range.end)),
+                ) else {
+                    continue;
+                };
+                results.push((
+                    start.to_display_point(display_snapshot)
+                        ..end.to_display_point(display_snapshot),
+                    added_color,
+                ));
+            }
+        }
+        results
+    }
+
     pub fn background_highlight_row_ranges<T: 'static>(
         &self,
         search_range: Range<Anchor>,
@@ -18049,6 +18289,55 @@ impl Editor {
         });
     }
 
+    /// Opens the buffer under the cursor in a [`SplitDiffEditor`].
+    ///
+    /// Multibuffers that already show a diff for that buffer, like the project
+    /// diff, commit view, agent diff, and proposed changes, pass on their own
+    /// diff, so the split view shows the same changes. Otherwise the buffer's
+    /// uncommitted changes are shown.
+    fn open_split_diff(&mut self, _: &OpenSplitDiff, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace() else {
+            cx.propagate();
+            return;
+        };
+        let Some((buffer, _)) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(self.selections.newest_anchor().head(), cx)
+        else {
+            return;
+        };
+
+        let title = buffer
+            .read(cx)
+            .file()
+            .map(|file| format!("{} (Diff)", file.file_name(cx).to_string_lossy()))
+            .unwrap_or_else(|| "Diff".to_string());
+        let project = self.project.clone();
+        let diff = match self.buffer.read(cx).diff_for(buffer.read(cx).remote_id()) {
+            Some(diff) => Task::ready(Ok(diff)),
+            None => {
+                let Some(project) = project.clone() else {
+                    return;
+                };
+                project.update(cx, |project, cx| {
+                    project.open_uncommitted_diff(buffer.clone(), cx)
+                })
+            }
+        };
+        cx.spawn_in(window, async move |_, cx| {
+            let diff = diff.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let split_diff_editor =
+                    cx.new(|cx| SplitDiffEditor::new(title, buffer, diff, project, window, cx));
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.add_item(Box::new(split_diff_editor), true, true, None, window, cx);
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn open_excerpts_in_split(
         &mut self,
         _: &OpenExcerptsSplit,
@@ -18906,6 +19195,16 @@ fn insert_extra_newline_tree_sitter(buffer: &MultiBufferSnapshot, range: Range<u
             .all(|c| c.is_whitespace() && c != '\n')
 }
 
+/// The start rows of the hunks that aren't one side of a moved block.
+fn unmoved_hunk_rows(snapshot: &EditorSnapshot) -> Vec<MultiBufferRow> {
+    snapshot
+        .buffer_snapshot
+        .diff_hunks_in_range(Point::zero()..snapshot.buffer_snapshot.max_point())
+        .filter(|hunk| !hunk.status().is_moved())
+        .map(|hunk| hunk.row_range.start)
+        .collect()
+}
+
 fn update_uncommitted_diff_for_buffer(
     editor: Entity<Editor>,
     project: &Entity<Project>,
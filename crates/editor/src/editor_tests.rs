@@ -12887,6 +12887,73 @@ async fn go_to_prev_overlapping_diagnostic(executor: BackgroundExecutor, cx: &mu
     "});
 }
 
+#[gpui::test]
+async fn test_go_to_hunk_skipping_moves(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let diff_base = r#"
+        fn a() {
+            one();
+            two();
+        }
+        fn b() {
+            three();
+            four();
+        }
+        let x = 1;
+        "#
+    .unindent();
+
+    // `b` was moved above `a`, and `x` was changed.
+    cx.set_state(
+        &r#"
+        ˇfn b() {
+            three();
+            four();
+        }
+        fn a() {
+            one();
+            two();
+        }
+        let x = 2;
+        "#
+        .unindent(),
+    );
+
+    cx.set_head_text(&diff_base);
+    executor.run_until_parked();
+
+    let expected_state = r#"
+        fn b() {
+            three();
+            four();
+        }
+        fn a() {
+            one();
+            two();
+        }
+        ˇlet x = 2;
+        "#
+    .unindent();
+
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_next_hunk_skipping_moves(&GoToHunkSkippingMoves, window, cx);
+    });
+    cx.assert_editor_state(&expected_state);
+
+    // The changed hunk is the only one, so both directions wrap around to it.
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_next_hunk_skipping_moves(&GoToHunkSkippingMoves, window, cx);
+    });
+    cx.assert_editor_state(&expected_state);
+    cx.update_editor(|editor, window, cx| {
+        editor.go_to_prev_hunk_skipping_moves(&GoToPreviousHunkSkippingMoves, window, cx);
+    });
+    cx.assert_editor_state(&expected_state);
+}
+
 #[gpui::test]
 async fn test_go_to_hunk(executor: BackgroundExecutor, cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -16964,7 +17031,8 @@ async fn test_partially_staged_hunk(cx: &mut TestAppContext) {
             hunks[0].status(),
             DiffHunkStatus {
                 kind: DiffHunkStatusKind::Modified,
-                secondary: DiffHunkSecondaryStatus::OverlapsWithSecondaryHunk
+                secondary: DiffHunkSecondaryStatus::OverlapsWithSecondaryHunk,
+                moved: false,
             }
         );
 
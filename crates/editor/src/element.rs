@@ -355,6 +355,8 @@ impl EditorElement {
         register_action(editor, window, Editor::go_to_prev_diagnostic);
         register_action(editor, window, Editor::go_to_next_hunk);
         register_action(editor, window, Editor::go_to_prev_hunk);
+        register_action(editor, window, Editor::go_to_next_hunk_skipping_moves);
+        register_action(editor, window, Editor::go_to_prev_hunk_skipping_moves);
         register_action(editor, window, |editor, action, window, cx| {
             editor
                 .go_to_definition(action, window, cx)
@@ -416,6 +418,7 @@ impl EditorElement {
         register_action(editor, window, Editor::open_excerpts);
         register_action(editor, window, Editor::open_excerpts_in_split);
         register_action(editor, window, Editor::open_proposed_changes_editor);
+        register_action(editor, window, Editor::open_split_diff);
         register_action(editor, window, Editor::toggle_soft_wrap);
         register_action(editor, window, Editor::toggle_tab_bar);
         register_action(editor, window, Editor::toggle_line_numbers);
@@ -441,6 +444,8 @@ impl EditorElement {
         register_action(editor, window, Editor::toggle_staged_selected_diff_hunks);
         register_action(editor, window, Editor::stage_and_next);
         register_action(editor, window, Editor::unstage_and_next);
+        register_action(editor, window, Editor::stage_lines);
+        register_action(editor, window, Editor::unstage_lines);
         register_action(editor, window, Editor::expand_all_diff_hunks);
         register_action(editor, window, Editor::go_to_previous_change);
         register_action(editor, window, Editor::go_to_next_change);
@@ -4723,6 +4728,11 @@ impl EditorElement {
                 };
 
                 if let Some((hunk_bounds, background_color, corner_radii, status)) = hunk_to_paint {
+                    let background_color = if status.is_moved() {
+                        cx.theme().colors().version_control_renamed
+                    } else {
+                        background_color
+                    };
                     // Flatten the background color with the editor color to prevent
                     // elements below transparent hunks from showing through
                     let flattened_background_color = cx
@@ -5351,6 +5361,9 @@ impl EditorElement {
                                             end_display_row.0 -= 1;
                                         }
                                         let color = match &hunk.status().kind {
+                                            _ if hunk.moved => {
+                                                theme.colors().version_control_renamed
+                                            }
                                             DiffHunkStatusKind::Added => {
                                                 theme.colors().version_control_added
                                             }
@@ -6961,6 +6974,9 @@ impl Element for EditorElement {
                         };
 
                         let background_color = match diff_status.kind {
+                            _ if diff_status.is_moved() => {
+                                cx.theme().colors().version_control_renamed
+                            }
                             DiffHunkStatusKind::Added => cx.theme().colors().version_control_added,
                             DiffHunkStatusKind::Deleted => {
                                 cx.theme().colors().version_control_deleted
@@ -7007,11 +7023,17 @@ impl Element for EditorElement {
                             .or_insert(background);
                     }
 
-                    let highlighted_ranges = self.editor.read(cx).background_highlights_in_range(
+                    let mut highlighted_ranges =
+                        self.editor.read(cx).background_highlights_in_range(
+                            start_anchor..end_anchor,
+                            &snapshot.display_snapshot,
+                            cx.theme().colors(),
+                        );
+                    highlighted_ranges.extend(self.editor.read(cx).diff_word_highlights_in_range(
                         start_anchor..end_anchor,
                         &snapshot.display_snapshot,
                         cx.theme().colors(),
-                    );
+                    ));
                     let highlighted_gutter_ranges =
                         self.editor.read(cx).gutter_highlights_in_range(
                             start_anchor..end_anchor,
@@ -0,0 +1,759 @@
+use crate::{
+    Anchor, DIFF_WORD_HIGHLIGHT_OPACITY, Editor, EditorEvent, RowHighlightOptions, ToPoint as _,
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, ToDisplayPoint,
+    },
+};
+use buffer_diff::{BufferDiff, BufferDiffEvent, DiffHunkStatus};
+use collections::HashSet;
+use gpui::{
+    AnyElement, App, Entity, EventEmitter, Focusable, Hsla, Render, Subscription, Task,
+    pattern_slash,
+};
+use language::{Buffer, Point};
+use multi_buffer::{MultiBuffer, MultiBufferSnapshot};
+use project::Project;
+use std::{any::TypeId, mem, ops::Range, sync::Arc};
+use text::BufferId;
+use theme::ThemeColors;
+use ui::{IconButtonShape, Tooltip, prelude::*};
+use workspace::{
+    Item, ItemHandle as _, Workspace, item::ItemEvent, searchable::SearchableItemHandle,
+};
+
+const HUNK_CONTROLS_WIDTH: Pixels = px(48.);
+
+/// Shows a [`BufferDiff`] in two columns: the diff's base text on the left,
+/// and the live buffer on the right.
+///
+/// This works on one buffer at a time. Multibuffer diff views, like the project
+/// diff, open the buffer under the cursor in it via [`crate::actions::OpenSplitDiff`].
+///
+/// Both editors scroll together, and filler blocks are inserted on the shorter
+/// side of every hunk so that the rows of the two columns stay aligned.
+pub struct SplitDiffEditor {
+    base_editor: Entity<Editor>,
+    editor: Entity<Editor>,
+    base_buffer: Entity<Buffer>,
+    buffer: Entity<Buffer>,
+    diff: Entity<BufferDiff>,
+    title: SharedString,
+    base_text_id: Option<BufferId>,
+    base_filler_blocks: HashSet<CustomBlockId>,
+    filler_blocks: HashSet<CustomBlockId>,
+    hunks: Vec<SplitDiffHunk>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct SplitDiffHunk {
+    base_range: Range<Anchor>,
+    range: Range<Anchor>,
+    status: DiffHunkStatus,
+}
+
+enum SplitDiffRowHighlight {}
+enum SplitDiffWordHighlight {}
+
+impl SplitDiffEditor {
+    pub fn new(
+        title: impl Into<SharedString>,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        project: Option<Entity<Project>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let language_registry = buffer.read(cx).language_registry();
+        let base_buffer = cx.new(|cx| {
+            let base_buffer = Buffer::local("", cx);
+            if let Some(language_registry) = language_registry {
+                base_buffer.set_language_registry(language_registry);
+            }
+            base_buffer
+        });
+        let base_editor = cx.new(|cx| {
+            let mut editor = Editor::for_buffer(base_buffer.clone(), project.clone(), window, cx);
+            editor.set_read_only(true);
+            editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
+            editor.set_show_git_diff_gutter(false, cx);
+            editor
+        });
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_diff(diff.clone(), cx);
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, project, window, cx);
+            editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
+            editor.set_show_git_diff_gutter(false, cx);
+            editor
+        });
+
+        let subscriptions = vec![
+            cx.subscribe_in(&diff, window, |this, _, event, window, cx| match event {
+                BufferDiffEvent::DiffChanged { .. } => this.update_diff(window, cx),
+                BufferDiffEvent::LanguageChanged => {
+                    this.base_text_id = None;
+                    this.update_diff(window, cx);
+                }
+                BufferDiffEvent::HunksStagedOrUnstaged(_) => {}
+            }),
+            cx.subscribe_in(
+                &base_editor,
+                window,
+                |this, base_editor, event, window, cx| {
+                    if let EditorEvent::ScrollPositionChanged { .. } = event {
+                        this.sync_scroll_position(
+                            base_editor.clone(),
+                            this.editor.clone(),
+                            window,
+                            cx,
+                        );
+                    }
+                },
+            ),
+            cx.subscribe_in(&editor, window, |this, editor, event, window, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(editor.clone(), this.base_editor.clone(), window, cx);
+                }
+                cx.emit(event.clone());
+            }),
+            // The hunk controls are positioned using the editor's line height and
+            // scroll position, so re-render whenever the editor does.
+            cx.observe(&editor, |_, _, cx| cx.notify()),
+        ];
+
+        let mut this = Self {
+            base_editor,
+            editor,
+            base_buffer,
+            buffer,
+            diff,
+            title: title.into(),
+            base_text_id: None,
+            base_filler_blocks: HashSet::default(),
+            filler_blocks: HashSet::default(),
+            hunks: Vec::new(),
+            _subscriptions: subscriptions,
+        };
+        this.update_diff(window, cx);
+        this
+    }
+
+    pub fn editor(&self) -> &Entity<Editor> {
+        &self.editor
+    }
+
+    pub fn base_editor(&self) -> &Entity<Editor> {
+        &self.base_editor
+    }
+
+    fn sync_scroll_position(
+        &mut self,
+        source: Entity<Editor>,
+        target: Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let position = source.update(cx, |editor, cx| editor.scroll_position(cx));
+        target.update(cx, |editor, cx| {
+            // Comparing positions stops the target's own scroll event from
+            // bouncing back to the source.
+            if editor.scroll_position(cx) != position {
+                editor.set_scroll_position(position, window, cx);
+            }
+        });
+    }
+
+    fn update_diff(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let buffer_snapshot = self.buffer.read(cx).snapshot();
+        let (base_text, hunks) = {
+            let diff = self.diff.read(cx);
+            let hunks = diff.hunks(&buffer_snapshot, cx).collect::<Vec<_>>();
+            (diff.base_text().clone(), hunks)
+        };
+
+        if self.base_text_id != Some(base_text.remote_id()) {
+            self.base_text_id = Some(base_text.remote_id());
+            self.base_buffer.update(cx, |buffer, cx| {
+                buffer.set_text(base_text.text(), cx);
+                buffer.set_language(base_text.language().cloned(), cx);
+            });
+        }
+
+        let base_snapshot = self.base_buffer.read(cx).snapshot();
+        let base_multibuffer = self.base_editor.read(cx).buffer().read(cx).snapshot(cx);
+        let multibuffer = self.editor.read(cx).buffer().read(cx).snapshot(cx);
+
+        let mut base_fillers = Vec::new();
+        let mut fillers = Vec::new();
+        let mut base_words = Vec::new();
+        let mut words = Vec::new();
+        self.hunks.clear();
+        for hunk in hunks {
+            let base_range = base_snapshot.offset_to_point(hunk.diff_base_byte_range.start)
+                ..base_snapshot.offset_to_point(hunk.diff_base_byte_range.end);
+            let base_row_count = row_count(&base_range);
+            let buffer_row_count = row_count(&hunk.range);
+            if buffer_row_count > base_row_count {
+                base_fillers.push(filler_block(
+                    &base_multibuffer,
+                    base_range.start.row + base_row_count,
+                    buffer_row_count - base_row_count,
+                ));
+            } else if base_row_count > buffer_row_count {
+                fillers.push(filler_block(
+                    &multibuffer,
+                    hunk.range.start.row + buffer_row_count,
+                    base_row_count - buffer_row_count,
+                ));
+            }
+
+            let status = hunk.status();
+            if let Some(word_diff) = self.diff.read(cx).word_diff(&hunk, &buffer_snapshot) {
+                base_words.extend(word_diff.base_text_ranges.iter().map(|range| {
+                    base_multibuffer.anchor_after(range.start)
+                        ..base_multibuffer.anchor_before(range.end)
+                }));
+                words.extend(word_diff.buffer_ranges.iter().map(|range| {
+                    multibuffer.anchor_after(range.start)..multibuffer.anchor_before(range.end)
+                }));
+            }
+
+            self.hunks.push(SplitDiffHunk {
+                base_range: base_multibuffer.anchor_before(base_range.start)
+                    ..base_multibuffer.anchor_after(base_range.end),
+                range: multibuffer.anchor_before(hunk.range.start)
+                    ..multibuffer.anchor_after(hunk.range.end),
+                status,
+            });
+        }
+
+        let opacity = if cx.theme().appearance().is_light() {
+            0.16
+        } else {
+            0.12
+        };
+        let colors = cx.theme().colors();
+        let deleted_color = colors.version_control_deleted.opacity(opacity);
+        let added_color = colors.version_control_added.opacity(opacity);
+        let moved_color = colors.version_control_renamed.opacity(opacity);
+        let base_rows = self
+            .hunks
+            .iter()
+            .map(|hunk| {
+                let color = if hunk.status.is_moved() {
+                    moved_color
+                } else {
+                    deleted_color
+                };
+                (hunk.base_range.clone(), color)
+            })
+            .collect::<Vec<_>>();
+        let rows = self
+            .hunks
+            .iter()
+            .map(|hunk| {
+                let color = if hunk.status.is_moved() {
+                    moved_color
+                } else {
+                    added_color
+                };
+                (hunk.range.clone(), color)
+            })
+            .collect::<Vec<_>>();
+
+        self.base_editor.update(cx, |editor, cx| {
+            update_editor(
+                editor,
+                &mut self.base_filler_blocks,
+                base_fillers,
+                base_rows,
+                &base_words,
+                |colors| {
+                    colors
+                        .version_control_deleted
+                        .opacity(DIFF_WORD_HIGHLIGHT_OPACITY)
+                },
+                cx,
+            )
+        });
+        self.editor.update(cx, |editor, cx| {
+            update_editor(
+                editor,
+                &mut self.filler_blocks,
+                fillers,
+                rows,
+                &words,
+                |colors| {
+                    colors
+                        .version_control_added
+                        .opacity(DIFF_WORD_HIGHLIGHT_OPACITY)
+                },
+                cx,
+            )
+        });
+
+        // Inserting filler blocks may have changed the maximum scroll position
+        // of either side, so bring the base editor back in line.
+        self.sync_scroll_position(self.editor.clone(), self.base_editor.clone(), window, cx);
+        cx.notify();
+    }
+
+    fn stage_or_unstage_hunk(&mut self, stage: bool, ix: usize, cx: &mut Context<Self>) {
+        let Some(hunk) = self.hunks.get(ix) else {
+            return;
+        };
+        let position = hunk.range.start;
+        self.editor.update(cx, |editor, cx| {
+            editor.stage_or_unstage_diff_hunks(stage, vec![position..position], cx);
+        });
+    }
+
+    fn restore_hunk(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(hunk) = self.hunks.get(ix) else {
+            return;
+        };
+        let position = hunk.range.start;
+        self.editor.update(cx, |editor, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            let point = position.to_point(&snapshot.buffer_snapshot);
+            editor.restore_hunks_in_ranges(vec![point..point], window, cx);
+        });
+    }
+
+    fn render_hunk_controls(&self, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        let column = div()
+            .relative()
+            .flex_none()
+            .w(HUNK_CONTROLS_WIDTH)
+            .h_full()
+            .overflow_hidden()
+            .border_x_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_gutter_background);
+
+        let Some(line_height) = self
+            .editor
+            .read(cx)
+            .style()
+            .map(|style| style.text.line_height_in_pixels(window.rem_size()))
+        else {
+            return column;
+        };
+        let snapshot = self
+            .editor
+            .update(cx, |editor, cx| editor.snapshot(window, cx));
+        let base_snapshot = self
+            .base_editor
+            .update(cx, |editor, cx| editor.snapshot(window, cx));
+        let scroll_top = snapshot.scroll_position().y;
+        let has_secondary_diff = self.diff.read(cx).secondary_diff().is_some();
+        let is_created_file = !self.diff.read(cx).base_text_exists();
+        let focus_handle = self.editor.focus_handle(cx);
+
+        column.children(self.hunks.iter().enumerate().map(|(ix, hunk)| {
+            // The hunk's first visible row is the same on both sides, but one of
+            // them may start with a filler block rather than with text.
+            let row = hunk
+                .range
+                .start
+                .to_point(&snapshot.buffer_snapshot)
+                .to_display_point(&snapshot.display_snapshot)
+                .row()
+                .min(
+                    hunk.base_range
+                        .start
+                        .to_point(&base_snapshot.buffer_snapshot)
+                        .to_display_point(&base_snapshot.display_snapshot)
+                        .row(),
+                );
+            let status = hunk.status;
+
+            h_flex()
+                .absolute()
+                .left_0()
+                .top(line_height * (row.as_f32() - scroll_top))
+                .w_full()
+                .h(line_height)
+                .justify_center()
+                .gap_0p5()
+                .when(has_secondary_diff, |this| {
+                    let stage = status.has_secondary_hunk();
+                    this.child(
+                        IconButton::new(
+                            ("stage-hunk", ix),
+                            if stage {
+                                IconName::Plus
+                            } else {
+                                IconName::Dash
+                            },
+                        )
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::XSmall)
+                        .alpha(if status.is_pending() { 0.66 } else { 1.0 })
+                        .tooltip({
+                            let focus_handle = focus_handle.clone();
+                            move |window, cx| {
+                                Tooltip::for_action_in(
+                                    if stage { "Stage Hunk" } else { "Unstage Hunk" },
+                                    &::git::ToggleStaged,
+                                    &focus_handle,
+                                    window,
+                                    cx,
+                                )
+                            }
+                        })
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.stage_or_unstage_hunk(stage, ix, cx)
+                        })),
+                    )
+                })
+                .child(
+                    IconButton::new(("restore-hunk", ix), IconName::ArrowRight)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::XSmall)
+                        .disabled(is_created_file)
+                        .tooltip({
+                            let focus_handle = focus_handle.clone();
+                            move |window, cx| {
+                                Tooltip::for_action_in(
+                                    "Restore Hunk",
+                                    &::git::Restore,
+                                    &focus_handle,
+                                    window,
+                                    cx,
+                                )
+                            }
+                        })
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.restore_hunk(ix, window, cx)
+                        })),
+                )
+        }))
+    }
+}
+
+fn update_editor(
+    editor: &mut Editor,
+    filler_blocks: &mut HashSet<CustomBlockId>,
+    fillers: Vec<BlockProperties<Anchor>>,
+    rows: Vec<(Range<Anchor>, Hsla)>,
+    words: &[Range<Anchor>],
+    word_color: fn(&ThemeColors) -> Hsla,
+    cx: &mut Context<Editor>,
+) {
+    editor.remove_blocks(mem::take(filler_blocks), None, cx);
+    filler_blocks.extend(editor.insert_blocks(fillers, None, cx));
+    editor.clear_row_highlights::<SplitDiffRowHighlight>();
+    for (range, color) in rows {
+        editor.highlight_rows::<SplitDiffRowHighlight>(
+            range,
+            color,
+            RowHighlightOptions::default(),
+            cx,
+        );
+    }
+    editor.highlight_background::<SplitDiffWordHighlight>(words, word_color, cx);
+}
+
+/// The number of rows spanned by a line-based hunk range. A range ending in the
+/// middle of a row only happens at the end of a buffer without a trailing newline.
+fn row_count(range: &Range<Point>) -> u32 {
+    range.end.row - range.start.row + (range.end.column > 0) as u32
+}
+
+fn filler_block(snapshot: &MultiBufferSnapshot, row: u32, height: u32) -> BlockProperties<Anchor> {
+    let max_row = snapshot.max_point().row;
+    let placement = if row <= max_row {
+        BlockPlacement::Above(snapshot.anchor_before(Point::new(row, 0)))
+    } else {
+        BlockPlacement::Below(snapshot.anchor_after(Point::new(max_row, 0)))
+    };
+    BlockProperties {
+        placement,
+        height: Some(height),
+        style: BlockStyle::Fixed,
+        render: Arc::new(render_filler),
+        priority: 0,
+    }
+}
+
+fn render_filler(cx: &mut BlockContext) -> AnyElement {
+    div()
+        .id(cx.block_id)
+        .size_full()
+        .bg(pattern_slash(
+            cx.theme().colors().border_variant.opacity(0.5),
+            1.,
+            6.,
+        ))
+        .into_any_element()
+}
+
+impl Render for SplitDiffEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .size_full()
+            .key_context("SplitDiffEditor")
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                div()
+                    .flex_1()
+                    .h_full()
+                    .overflow_hidden()
+                    .child(self.base_editor.clone()),
+            )
+            .child(self.render_hunk_controls(window, cx))
+            .child(
+                div()
+                    .flex_1()
+                    .h_full()
+                    .overflow_hidden()
+                    .child(self.editor.clone()),
+            )
+    }
+}
+
+impl Focusable for SplitDiffEditor {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for SplitDiffEditor {}
+
+impl Item for SplitDiffEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::added_to_workspace(editor, workspace, window, cx)
+        });
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn std::any::Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, window, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: workspace::ItemNavHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<gpui::Result<()>> {
+        self.editor.update(cx, |editor, cx| {
+            Item::save(editor, format, project, window, cx)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_tests::init_test;
+    use gpui::{TestAppContext, VisualTestContext, point};
+
+    fn build_split_diff_editor<'a>(
+        base_text: &str,
+        text: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (Entity<SplitDiffEditor>, &'a mut VisualTestContext) {
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let diff = cx.new(|cx| BufferDiff::new_with_base_text(base_text, &buffer, cx));
+        let (split_diff_editor, cx) = cx.add_window_view(|window, cx| {
+            SplitDiffEditor::new("Diff", buffer, diff, None, window, cx)
+        });
+        cx.run_until_parked();
+        (split_diff_editor, cx)
+    }
+
+    /// Returns the display rows of the given buffer rows in the base editor
+    /// and the editor, along with the number of display rows of each.
+    fn display_rows(
+        split_diff_editor: &Entity<SplitDiffEditor>,
+        base_rows: &[u32],
+        rows: &[u32],
+        cx: &mut VisualTestContext,
+    ) -> ((Vec<u32>, u32), (Vec<u32>, u32)) {
+        split_diff_editor.update_in(cx, |split_diff_editor, window, cx| {
+            let mut display_rows = |editor: &Entity<Editor>, rows: &[u32]| {
+                editor.update(cx, |editor, cx| {
+                    let snapshot = editor.snapshot(window, cx);
+                    let display_rows = rows
+                        .iter()
+                        .map(|row| {
+                            Point::new(*row, 0)
+                                .to_display_point(&snapshot.display_snapshot)
+                                .row()
+                                .0
+                        })
+                        .collect();
+                    (display_rows, snapshot.display_snapshot.max_point().row().0)
+                })
+            };
+            (
+                display_rows(&split_diff_editor.base_editor.clone(), base_rows),
+                display_rows(&split_diff_editor.editor.clone(), rows),
+            )
+        })
+    }
+
+    #[gpui::test]
+    async fn test_filler_blocks_for_added_lines(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let (split_diff_editor, cx) =
+            build_split_diff_editor("a\nb\nc\nd\ne\n", "a\nb\nX\nY\nc\nd\ne\n", cx);
+
+        // The base text gets two rows of filler above "c", where the lines were added.
+        let (base, buffer) = display_rows(&split_diff_editor, &[0, 1, 2, 4], &[0, 1, 4, 6], cx);
+        assert_eq!(base.0, vec![0, 1, 4, 6]);
+        assert_eq!(buffer.0, vec![0, 1, 4, 6]);
+        assert_eq!(base.1, buffer.1);
+    }
+
+    #[gpui::test]
+    async fn test_filler_blocks_for_deleted_lines(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let (split_diff_editor, cx) = build_split_diff_editor("a\nb\nc\nd\ne\n", "a\nd\ne\n", cx);
+
+        // The buffer gets two rows of filler above "d", where the lines were deleted.
+        let (base, buffer) = display_rows(&split_diff_editor, &[0, 3, 4], &[0, 1, 2], cx);
+        assert_eq!(base.0, vec![0, 3, 4]);
+        assert_eq!(buffer.0, vec![0, 3, 4]);
+        assert_eq!(base.1, buffer.1);
+    }
+
+    #[gpui::test]
+    async fn test_filler_blocks_for_modified_lines(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let (split_diff_editor, cx) =
+            build_split_diff_editor("a\nb\nc\nd\ne\n", "a\nB1\nB2\nB3\nc\nD\n", cx);
+
+        // "b" grew into three lines, so the base text gets two rows of filler
+        // above "c". "d" and "e" shrank into one line, so the buffer gets one row
+        // of filler at its end.
+        let (base, buffer) =
+            display_rows(&split_diff_editor, &[0, 1, 2, 3, 5], &[0, 1, 4, 5, 6], cx);
+        assert_eq!(base.0, vec![0, 1, 4, 5, 7]);
+        assert_eq!(buffer.0, vec![0, 1, 4, 5, 7]);
+        assert_eq!(base.1, buffer.1);
+    }
+
+    #[gpui::test]
+    async fn test_scroll_sync(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let base_text = (0..100)
+            .map(|row| format!("line {row}\n"))
+            .collect::<String>();
+        let text = base_text.replace("line 50\n", "changed line 50\n");
+        let (split_diff_editor, cx) = build_split_diff_editor(&base_text, &text, cx);
+        let (base_editor, editor) = split_diff_editor.read_with(cx, |split_diff_editor, _| {
+            (
+                split_diff_editor.base_editor().clone(),
+                split_diff_editor.editor().clone(),
+            )
+        });
+
+        // Scrolling the editor scrolls the base editor along with it.
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_scroll_position(point(0., 20.), window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            base_editor.update(cx, |editor, cx| editor.scroll_position(cx)),
+            point(0., 20.)
+        );
+
+        // And the other way around.
+        base_editor.update_in(cx, |editor, window, cx| {
+            editor.set_scroll_position(point(0., 45.), window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.scroll_position(cx)),
+            point(0., 45.)
+        );
+    }
+}
@@ -284,7 +284,8 @@ impl ExampleContext {
                 | ThreadEvent::StreamedToolUse { .. }
                 | ThreadEvent::CheckpointChanged
                 | ThreadEvent::UsageUpdated(_)
-                | ThreadEvent::CancelEditing => {
+                | ThreadEvent::CancelEditing
+                | ThreadEvent::CompactionChanged => {
                     tx.try_send(Ok(())).ok();
                     if std::env::var("ZED_EVAL_DEBUG").is_ok() {
                         println!("{}Event: {:#?}", log_prefix, event);
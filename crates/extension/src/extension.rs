@@ -10,6 +10,8 @@ use std::sync::Arc;
 use ::lsp::LanguageServerName;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
+use dap::StartDebuggingRequestArgumentsRequest;
+use dap::adapters::{DebugAdapterBinary, DebugTaskDefinition};
 use fs::normalize_path;
 use gpui::{App, Task};
 use language::LanguageName;
@@ -135,6 +137,58 @@ pub trait Extension: Send + Sync + 'static {
         package_name: Arc<str>,
         kv_store: Arc<dyn KeyValueStoreDelegate>,
     ) -> Result<()>;
+
+    async fn get_dap_binary(
+        &self,
+        dap_name: Arc<str>,
+        config: DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<DebugAdapterBinary>;
+
+    async fn dap_request_kind(
+        &self,
+        dap_name: Arc<str>,
+        config: serde_json::Value,
+    ) -> Result<StartDebuggingRequestArgumentsRequest>;
+
+    async fn dap_default_config(&self, dap_name: Arc<str>) -> Result<serde_json::Value>;
+
+    async fn worktree_tasks(
+        &self,
+        provider_id: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<WorktreeTasks>;
+
+    async fn run_agent_tool(
+        &self,
+        tool_name: Arc<str>,
+        input: serde_json::Value,
+        worktree: Option<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<String>;
+
+    async fn llm_provider_models(
+        &self,
+        provider_id: Arc<str>,
+        api_key: Option<String>,
+    ) -> Result<Vec<LlmModel>>;
+
+    /// Starts streaming a completion, returning the ID of the stream.
+    async fn llm_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+        api_key: Option<String>,
+    ) -> Result<u64>;
+
+    /// Returns the next events of the completion stream, or `None` once it has ended.
+    async fn llm_next_completion_events(
+        &self,
+        stream_id: u64,
+    ) -> Result<Option<Vec<LlmCompletionEvent>>>;
+
+    async fn llm_drop_completion_stream(&self, stream_id: u64) -> Result<()>;
 }
 
 pub fn parse_wasm_extension_version(
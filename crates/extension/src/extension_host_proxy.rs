@@ -8,7 +8,10 @@ use language::{BinaryStatus, LanguageMatcher, LanguageName, LoadedLanguage};
 use lsp::LanguageServerName;
 use parking_lot::RwLock;
 
-use crate::{Extension, SlashCommand};
+use crate::{
+    AgentTool, Extension, LanguageModelProviderManifestEntry, SlashCommand,
+    TaskProviderManifestEntry,
+};
 
 #[derive(Default)]
 struct GlobalExtensionHostProxy(Arc<ExtensionHostProxy>);
@@ -29,6 +32,10 @@ pub struct ExtensionHostProxy {
     slash_command_proxy: RwLock<Option<Arc<dyn ExtensionSlashCommandProxy>>>,
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     indexed_docs_provider_proxy: RwLock<Option<Arc<dyn ExtensionIndexedDocsProviderProxy>>>,
+    debug_adapter_provider_proxy: RwLock<Option<Arc<dyn ExtensionDebugAdapterProviderProxy>>>,
+    task_provider_proxy: RwLock<Option<Arc<dyn ExtensionTaskProviderProxy>>>,
+    agent_tool_proxy: RwLock<Option<Arc<dyn ExtensionAgentToolProxy>>>,
+    language_model_provider_proxy: RwLock<Option<Arc<dyn ExtensionLanguageModelProviderProxy>>>,
 }
 
 impl ExtensionHostProxy {
@@ -54,6 +61,10 @@ impl ExtensionHostProxy {
             slash_command_proxy: RwLock::default(),
             context_server_proxy: RwLock::default(),
             indexed_docs_provider_proxy: RwLock::default(),
+            debug_adapter_provider_proxy: RwLock::default(),
+            task_provider_proxy: RwLock::default(),
+            agent_tool_proxy: RwLock::default(),
+            language_model_provider_proxy: RwLock::default(),
         }
     }
 
@@ -93,6 +104,29 @@ impl ExtensionHostProxy {
             .write()
             .replace(Arc::new(proxy));
     }
+
+    pub fn register_debug_adapter_proxy(&self, proxy: impl ExtensionDebugAdapterProviderProxy) {
+        self.debug_adapter_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
+
+    pub fn register_task_provider_proxy(&self, proxy: impl ExtensionTaskProviderProxy) {
+        self.task_provider_proxy.write().replace(Arc::new(proxy));
+    }
+
+    pub fn register_agent_tool_proxy(&self, proxy: impl ExtensionAgentToolProxy) {
+        self.agent_tool_proxy.write().replace(Arc::new(proxy));
+    }
+
+    pub fn register_language_model_provider_proxy(
+        &self,
+        proxy: impl ExtensionLanguageModelProviderProxy,
+    ) {
+        self.language_model_provider_proxy
+            .write()
+            .replace(Arc::new(proxy));
+    }
 }
 
 pub trait ExtensionThemeProxy: Send + Sync + 'static {
@@ -392,3 +426,138 @@ impl ExtensionIndexedDocsProviderProxy for ExtensionHostProxy {
         proxy.register_indexed_docs_provider(extension, provider_id)
     }
 }
+
+pub trait ExtensionDebugAdapterProviderProxy: Send + Sync + 'static {
+    fn register_debug_adapter(&self, extension: Arc<dyn Extension>, debug_adapter_name: Arc<str>);
+
+    fn unregister_debug_adapter(&self, extension_id: Arc<str>, debug_adapter_name: Arc<str>);
+}
+
+impl ExtensionDebugAdapterProviderProxy for ExtensionHostProxy {
+    fn register_debug_adapter(&self, extension: Arc<dyn Extension>, debug_adapter_name: Arc<str>) {
+        let Some(proxy) = self.debug_adapter_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_debug_adapter(extension, debug_adapter_name)
+    }
+
+    fn unregister_debug_adapter(&self, extension_id: Arc<str>, debug_adapter_name: Arc<str>) {
+        let Some(proxy) = self.debug_adapter_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_debug_adapter(extension_id, debug_adapter_name)
+    }
+}
+
+pub trait ExtensionTaskProviderProxy: Send + Sync + 'static {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: TaskProviderManifestEntry,
+        cx: &mut App,
+    );
+
+    fn unregister_task_provider(&self, extension_id: Arc<str>, provider_id: Arc<str>, cx: &mut App);
+}
+
+impl ExtensionTaskProviderProxy for ExtensionHostProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: TaskProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_task_provider(extension, provider_id, entry, cx)
+    }
+
+    fn unregister_task_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_task_provider(extension_id, provider_id, cx)
+    }
+}
+
+pub trait ExtensionAgentToolProxy: Send + Sync + 'static {
+    fn register_agent_tool(&self, extension: Arc<dyn Extension>, tool: AgentTool);
+
+    fn unregister_agent_tool(&self, extension_id: Arc<str>, tool_name: Arc<str>);
+}
+
+impl ExtensionAgentToolProxy for ExtensionHostProxy {
+    fn register_agent_tool(&self, extension: Arc<dyn Extension>, tool: AgentTool) {
+        let Some(proxy) = self.agent_tool_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_agent_tool(extension, tool)
+    }
+
+    fn unregister_agent_tool(&self, extension_id: Arc<str>, tool_name: Arc<str>) {
+        let Some(proxy) = self.agent_tool_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_agent_tool(extension_id, tool_name)
+    }
+}
+
+pub trait ExtensionLanguageModelProviderProxy: Send + Sync + 'static {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    );
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    );
+}
+
+impl ExtensionLanguageModelProviderProxy for ExtensionHostProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.language_model_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_language_model_provider(extension, provider_id, entry, cx)
+    }
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.language_model_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_language_model_provider(extension_id, provider_id, cx)
+    }
+}
@@ -3,6 +3,7 @@ use collections::{BTreeMap, HashMap};
 use fs::Fs;
 use language::LanguageName;
 use lsp::LanguageServerName;
+use schemars::JsonSchema;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -87,6 +88,14 @@ pub struct ExtensionManifest {
     pub snippets: Option<PathBuf>,
     #[serde(default)]
     pub capabilities: Vec<ExtensionCapability>,
+    #[serde(default)]
+    pub debug_adapters: BTreeMap<Arc<str>, DebugAdapterManifestEntry>,
+    #[serde(default)]
+    pub task_providers: BTreeMap<Arc<str>, TaskProviderManifestEntry>,
+    #[serde(default)]
+    pub agent_tools: BTreeMap<Arc<str>, AgentToolManifestEntry>,
+    #[serde(default)]
+    pub language_model_providers: BTreeMap<Arc<str>, LanguageModelProviderManifestEntry>,
 }
 
 impl ExtensionManifest {
@@ -95,28 +104,10 @@ impl ExtensionManifest {
         desired_command: &str,
         desired_args: &[impl AsRef<str> + std::fmt::Debug],
     ) -> Result<()> {
-        let is_allowed = self.capabilities.iter().any(|capability| match capability {
-            ExtensionCapability::ProcessExec { command, args } if command == desired_command => {
-                for (ix, arg) in args.iter().enumerate() {
-                    if arg == "**" {
-                        return true;
-                    }
-
-                    if ix >= desired_args.len() {
-                        return false;
-                    }
-
-                    if arg != "*" && arg != desired_args[ix].as_ref() {
-                        return false;
-                    }
-                }
-                if args.len() < desired_args.len() {
-                    return false;
-                }
-                true
-            }
-            _ => false,
-        });
+        let is_allowed = self
+            .capabilities
+            .iter()
+            .any(|capability| capability.allows_exec(desired_command, desired_args));
 
         if !is_allowed {
             bail!(
@@ -129,17 +120,165 @@ impl ExtensionManifest {
 }
 
 /// A capability for an extension.
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "kind")]
 pub enum ExtensionCapability {
     #[serde(rename = "process:exec")]
     ProcessExec {
-        /// The command to execute.
+        /// The command to execute. Use `*` for any command.
         command: String,
         /// The arguments to pass to the command. Use `*` for a single wildcard argument.
         /// If the last element is `**`, then any trailing arguments are allowed.
         args: Vec<String>,
     },
+    #[serde(rename = "network:fetch")]
+    NetworkFetch {
+        /// The host that may be fetched from. Use `*` for any host, or `*.example.com`
+        /// for `example.com` and any of its subdomains.
+        host: String,
+    },
+    #[serde(rename = "download:file")]
+    DownloadFile {
+        /// The host that files may be downloaded from, using the same patterns as `network:fetch`.
+        host: String,
+    },
+    #[serde(rename = "npm:install")]
+    NpmInstall {
+        /// The npm package that may be installed. Use `*` for any package.
+        package: String,
+    },
+    #[serde(rename = "fs:read")]
+    FsRead {
+        /// Where the extension may read files.
+        scope: FsScope,
+    },
+    #[serde(rename = "fs:write")]
+    FsWrite {
+        /// Where the extension may write files.
+        scope: FsScope,
+    },
+}
+
+/// A part of the file system that an extension may access.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum FsScope {
+    /// The files within the worktrees of the project.
+    Worktree,
+    /// The extension's own work directory.
+    WorkDir,
+}
+
+impl ExtensionCapability {
+    /// Returns whether this capability allows running the given command.
+    pub fn allows_exec(
+        &self,
+        desired_command: &str,
+        desired_args: &[impl AsRef<str> + std::fmt::Debug],
+    ) -> bool {
+        let Self::ProcessExec { command, args } = self else {
+            return false;
+        };
+        if command != "*" && command != desired_command {
+            return false;
+        }
+
+        for (ix, arg) in args.iter().enumerate() {
+            if arg == "**" {
+                return true;
+            }
+
+            if ix >= desired_args.len() {
+                return false;
+            }
+
+            if arg != "*" && arg != desired_args[ix].as_ref() {
+                return false;
+            }
+        }
+        args.len() >= desired_args.len()
+    }
+
+    /// Returns whether this capability allows fetching from the given host.
+    pub fn allows_fetch(&self, desired_host: &str) -> bool {
+        match self {
+            Self::NetworkFetch { host } => host_matches(host, desired_host),
+            _ => false,
+        }
+    }
+
+    /// Returns whether this capability allows downloading files from the given host.
+    pub fn allows_download(&self, desired_host: &str) -> bool {
+        match self {
+            Self::DownloadFile { host } => host_matches(host, desired_host),
+            _ => false,
+        }
+    }
+
+    /// Returns whether this capability allows installing the given npm package.
+    pub fn allows_npm_install(&self, desired_package: &str) -> bool {
+        match self {
+            Self::NpmInstall { package } => package == "*" || package == desired_package,
+            _ => false,
+        }
+    }
+
+    /// Returns whether this capability allows reading files in the given scope.
+    pub fn allows_fs_read(&self, desired_scope: FsScope) -> bool {
+        matches!(self, Self::FsRead { scope } if *scope == desired_scope)
+    }
+
+    /// Returns whether this capability allows writing files in the given scope.
+    pub fn allows_fs_write(&self, desired_scope: FsScope) -> bool {
+        matches!(self, Self::FsWrite { scope } if *scope == desired_scope)
+    }
+}
+
+impl fmt::Display for ExtensionCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProcessExec { command, args } => {
+                write!(f, "run `{command}")?;
+                for arg in args {
+                    write!(f, " {arg}")?;
+                }
+                write!(f, "`")
+            }
+            Self::NetworkFetch { host } if host == "*" => write!(f, "access the network"),
+            Self::NetworkFetch { host } => write!(f, "access `{host}`"),
+            Self::DownloadFile { host } if host == "*" => write!(f, "download files"),
+            Self::DownloadFile { host } => write!(f, "download files from `{host}`"),
+            Self::NpmInstall { package } if package == "*" => write!(f, "install npm packages"),
+            Self::NpmInstall { package } => write!(f, "install the `{package}` npm package"),
+            Self::FsRead {
+                scope: FsScope::Worktree,
+            } => write!(f, "read files in your projects"),
+            Self::FsRead {
+                scope: FsScope::WorkDir,
+            } => write!(f, "read files in its work directory"),
+            Self::FsWrite {
+                scope: FsScope::Worktree,
+            } => write!(f, "write files in your projects"),
+            Self::FsWrite {
+                scope: FsScope::WorkDir,
+            } => write!(f, "write files in its work directory"),
+        }
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.") {
+        Some(domain) => {
+            host == domain
+                || host
+                    .strip_suffix(domain)
+                    .map_or(false, |subdomain| subdomain.ends_with('.'))
+        }
+        None => pattern == host,
+    }
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -206,6 +345,34 @@ pub struct SlashCommandManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct DebugAdapterManifestEntry {}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TaskProviderManifestEntry {
+    /// The paths, relative to the worktree root, whose changes cause the tasks to be reloaded.
+    #[serde(default)]
+    pub files: Vec<Arc<str>>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct AgentToolManifestEntry {
+    pub description: String,
+    /// The JSON schema of the tool's input.
+    #[serde(default)]
+    pub input_schema: Option<serde_json::Value>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LanguageModelProviderManifestEntry {
+    /// The name of the provider, shown in the model selector.
+    pub name: String,
+    /// Whether the provider requires an API key, which is entered in the agent
+    /// configuration and stored with the system's credentials provider.
+    #[serde(default)]
+    pub requires_api_key: bool,
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -274,6 +441,10 @@ fn manifest_from_old_manifest(
         indexed_docs_providers: BTreeMap::default(),
         snippets: None,
         capabilities: Vec::new(),
+        debug_adapters: BTreeMap::default(),
+        task_providers: BTreeMap::default(),
+        agent_tools: BTreeMap::default(),
+        language_model_providers: BTreeMap::default(),
     }
 }
 
@@ -301,6 +472,10 @@ mod tests {
             indexed_docs_providers: BTreeMap::default(),
             snippets: None,
             capabilities: vec![],
+            debug_adapters: BTreeMap::default(),
+            task_providers: BTreeMap::default(),
+            agent_tools: BTreeMap::default(),
+            language_model_providers: BTreeMap::default(),
         }
     }
 
@@ -379,4 +554,208 @@ mod tests {
         );
         assert!(manifest.allow_exec("docker", &["ps"]).is_err()); // wrong first arg
     }
+
+    #[test]
+    fn test_allow_any_command() {
+        let manifest = ExtensionManifest {
+            capabilities: vec![ExtensionCapability::ProcessExec {
+                command: "*".to_string(),
+                args: vec!["**".to_string()],
+            }],
+            ..extension_manifest()
+        };
+
+        assert!(manifest.allow_exec("ls", &["-la"]).is_ok());
+        assert!(manifest.allow_exec("gleam", &["lsp"]).is_ok());
+        assert!(manifest.allow_exec("pwd", &[] as &[&str]).is_ok());
+    }
+
+    #[test]
+    fn test_allow_fetch_host_patterns() {
+        let any_host = ExtensionCapability::NetworkFetch {
+            host: "*".to_string(),
+        };
+        assert!(any_host.allows_fetch("example.com"));
+
+        let exact_host = ExtensionCapability::NetworkFetch {
+            host: "api.github.com".to_string(),
+        };
+        assert!(exact_host.allows_fetch("api.github.com"));
+        assert!(!exact_host.allows_fetch("github.com"));
+        assert!(!exact_host.allows_download("api.github.com"));
+
+        let subdomains = ExtensionCapability::DownloadFile {
+            host: "*.github.com".to_string(),
+        };
+        assert!(subdomains.allows_download("github.com"));
+        assert!(subdomains.allows_download("objects.github.com"));
+        assert!(!subdomains.allows_download("evilgithub.com"));
+        assert!(!subdomains.allows_fetch("github.com"));
+    }
+
+    #[test]
+    fn test_allow_npm_install() {
+        let any_package = ExtensionCapability::NpmInstall {
+            package: "*".to_string(),
+        };
+        assert!(any_package.allows_npm_install("typescript"));
+
+        let single_package = ExtensionCapability::NpmInstall {
+            package: "typescript".to_string(),
+        };
+        assert!(single_package.allows_npm_install("typescript"));
+        assert!(!single_package.allows_npm_install("prettier"));
+        assert!(!single_package.allows_exec("npm", &["install", "typescript"]));
+    }
+
+    #[test]
+    fn test_parse_capabilities() {
+        let manifest: ExtensionManifest = toml::from_str(
+            r#"
+            id = "test"
+            name = "Test"
+            version = "0.1.0"
+            schema_version = 1
+
+            [[capabilities]]
+            kind = "network:fetch"
+            host = "*.example.com"
+
+            [[capabilities]]
+            kind = "fs:read"
+            scope = "worktree"
+
+            [[capabilities]]
+            kind = "fs:write"
+            scope = "work-dir"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.capabilities,
+            vec![
+                ExtensionCapability::NetworkFetch {
+                    host: "*.example.com".to_string(),
+                },
+                ExtensionCapability::FsRead {
+                    scope: FsScope::Worktree,
+                },
+                ExtensionCapability::FsWrite {
+                    scope: FsScope::WorkDir,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_debug_adapters() {
+        let manifest: ExtensionManifest = toml::from_str(
+            r#"
+            id = "ruby"
+            name = "Ruby"
+            version = "0.1.0"
+            schema_version = 1
+
+            [debug_adapters.rdbg]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.debug_adapters.keys().collect::<Vec<_>>(),
+            vec![&Arc::<str>::from("rdbg")]
+        );
+    }
+
+    #[test]
+    fn test_parse_task_providers() {
+        let manifest: ExtensionManifest = toml::from_str(
+            r#"
+            id = "just"
+            name = "Just"
+            version = "0.1.0"
+            schema_version = 1
+
+            [task_providers.just]
+            files = ["justfile", ".justfile"]
+
+            [task_providers.just-recipes]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.task_providers.get("just"),
+            Some(&TaskProviderManifestEntry {
+                files: vec!["justfile".into(), ".justfile".into()],
+            })
+        );
+        assert_eq!(
+            manifest.task_providers.get("just-recipes"),
+            Some(&TaskProviderManifestEntry { files: Vec::new() })
+        );
+    }
+
+    #[test]
+    fn test_parse_agent_tools() {
+        let manifest: ExtensionManifest = toml::from_str(
+            r#"
+            id = "crates-io"
+            name = "crates.io"
+            version = "0.1.0"
+            schema_version = 1
+
+            [agent_tools.crate_versions]
+            description = "Lists the published versions of a crate"
+
+            [agent_tools.crate_versions.input_schema]
+            type = "object"
+            required = ["name"]
+
+            [agent_tools.crate_versions.input_schema.properties.name]
+            type = "string"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.agent_tools.get("crate_versions"),
+            Some(&AgentToolManifestEntry {
+                description: "Lists the published versions of a crate".to_string(),
+                input_schema: Some(serde_json::json!({
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string" }
+                    }
+                })),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_language_model_providers() {
+        let manifest: ExtensionManifest = toml::from_str(
+            r#"
+            id = "acme-gateway"
+            name = "Acme Gateway"
+            version = "0.1.0"
+            schema_version = 1
+
+            [language_model_providers.acme]
+            name = "Acme"
+            requires_api_key = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.language_model_providers.get("acme"),
+            Some(&LanguageModelProviderManifestEntry {
+                name: "Acme".to_string(),
+                requires_api_key: true,
+            })
+        );
+    }
 }
@@ -1,12 +1,18 @@
+mod agent_tool;
 mod context_server;
+mod llm_provider;
 mod lsp;
 mod slash_command;
+mod task;
 
 use std::ops::Range;
 
+pub use agent_tool::*;
 pub use context_server::*;
+pub use llm_provider::*;
 pub use lsp::*;
 pub use slash_command::*;
+pub use task::*;
 
 /// A list of environment variables.
 pub type EnvVars = Vec<(String, String)>;
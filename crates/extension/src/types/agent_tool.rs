@@ -0,0 +1,10 @@
+/// A tool that an extension provides to the agent.
+#[derive(Debug, Clone)]
+pub struct AgentTool {
+    /// The name of the tool.
+    pub name: String,
+    /// The description of the tool, shown to the language model.
+    pub description: String,
+    /// The JSON schema of the tool's input.
+    pub input_schema: serde_json::Value,
+}
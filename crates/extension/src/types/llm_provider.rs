@@ -0,0 +1,97 @@
+/// A language model offered by an extension's language model provider.
+#[derive(Debug, Clone)]
+pub struct LlmModel {
+    /// The ID of the model, as used by the provider's API.
+    pub id: String,
+    /// The human-readable name of the model.
+    pub display_name: String,
+    /// The maximum number of tokens in the model's context window.
+    pub max_token_count: u64,
+    /// The maximum number of tokens the model can produce in a single response.
+    pub max_output_tokens: Option<u64>,
+    /// Whether the model supports tool use.
+    pub supports_tools: bool,
+    /// Whether the model accepts images as input.
+    pub supports_images: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum LlmMessageRole {
+    User,
+    Assistant,
+    System,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmToolUse {
+    pub id: String,
+    pub name: String,
+    /// The input to the tool, as JSON.
+    pub input: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmToolResult {
+    pub tool_use_id: String,
+    pub tool_name: String,
+    pub is_error: bool,
+    pub content: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum LlmMessageContent {
+    Text(String),
+    /// A base64-encoded PNG image.
+    Image(String),
+    ToolUse(LlmToolUse),
+    ToolResult(LlmToolResult),
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmRequestMessage {
+    pub role: LlmMessageRole,
+    pub content: Vec<LlmMessageContent>,
+    pub cache: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmToolDefinition {
+    pub name: String,
+    pub description: String,
+    /// The JSON schema of the tool's input.
+    pub input_schema: String,
+}
+
+/// A request for a completion from an extension's language model provider.
+#[derive(Debug, Clone)]
+pub struct LlmCompletionRequest {
+    pub messages: Vec<LlmRequestMessage>,
+    pub tools: Vec<LlmToolDefinition>,
+    pub stop: Vec<String>,
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum LlmStopReason {
+    EndTurn,
+    MaxTokens,
+    ToolUse,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LlmTokenUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_creation_input_tokens: u32,
+    pub cache_read_input_tokens: u32,
+}
+
+/// An event in a completion streamed from an extension's language model provider.
+#[derive(Debug, Clone)]
+pub enum LlmCompletionEvent {
+    Text(String),
+    Thinking(String),
+    ToolUse(LlmToolUse),
+    Stop(LlmStopReason),
+    UsageUpdate(LlmTokenUsage),
+}
@@ -0,0 +1,10 @@
+use task::{TaskTemplate, TaskVariables};
+
+/// The tasks that an extension provides for a worktree.
+#[derive(Debug, Clone, Default)]
+pub struct WorktreeTasks {
+    /// The task templates.
+    pub templates: Vec<TaskTemplate>,
+    /// The variables that the task templates can reference.
+    pub variables: TaskVariables,
+}
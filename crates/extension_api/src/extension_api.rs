@@ -1,6 +1,7 @@
 //! The Zed Rust Extension API allows you write extensions for [Zed](https://zed.dev/) in Rust.
 
 pub mod http_client;
+pub mod llm_provider;
 pub mod process;
 pub mod settings;
 
@@ -48,6 +49,20 @@ pub mod lsp {
     };
 }
 
+/// Constructs for interacting with debug adapters over the
+/// Debug Adapter Protocol (DAP).
+pub mod dap {
+    pub use crate::wit::zed::extension::dap::{
+        AttachRequest, DebugAdapterBinary, DebugRequest, DebugTaskDefinition, LaunchRequest,
+        StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest, TcpArguments,
+        TcpArgumentsTemplate,
+    };
+}
+
+pub mod task {
+    pub use crate::wit::zed::extension::task::{TaskTemplate, WorktreeTasks};
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -187,6 +202,91 @@ pub trait Extension: Send + Sync {
     ) -> Result<(), String> {
         Err("`index_docs` not implemented".to_string())
     }
+
+    /// Returns the binary used to start the specified debug adapter for the given debug configuration.
+    fn get_dap_binary(
+        &mut self,
+        _adapter_name: String,
+        _config: dap::DebugTaskDefinition,
+        _user_installed_path: Option<String>,
+        _worktree: &Worktree,
+    ) -> Result<dap::DebugAdapterBinary, String> {
+        Err("`get_dap_binary` not implemented".to_string())
+    }
+
+    /// Returns whether the given debug adapter configuration launches a new program
+    /// or attaches to a running one.
+    ///
+    /// By default, this is determined by the `request` field of the configuration.
+    fn dap_request_kind(
+        &mut self,
+        _adapter_name: String,
+        config: serde_json::Value,
+    ) -> Result<dap::StartDebuggingRequestArgumentsRequest, String> {
+        match config.get("request").and_then(|request| request.as_str()) {
+            Some("launch") => Ok(dap::StartDebuggingRequestArgumentsRequest::Launch),
+            Some("attach") => Ok(dap::StartDebuggingRequestArgumentsRequest::Attach),
+            _ => Err("debug configuration has no valid `request` field".to_string()),
+        }
+    }
+
+    /// Returns the default configuration for the specified debug adapter.
+    ///
+    /// Every configuration sent to the debug adapter is built on top of this one.
+    fn dap_default_config(&mut self, _adapter_name: String) -> Result<serde_json::Value, String> {
+        Ok(serde_json::Value::Object(Default::default()))
+    }
+
+    /// Runs the specified agent tool with the given input, returning the output
+    /// that is sent back to the language model.
+    ///
+    /// The worktree is the first visible worktree of the project the tool is run in, if any.
+    fn run_agent_tool(
+        &mut self,
+        _tool_name: String,
+        _input: serde_json::Value,
+        _worktree: Option<&Worktree>,
+    ) -> Result<String, String> {
+        Err("`run_agent_tool` not implemented".to_string())
+    }
+
+    /// Returns the models offered by the specified language model provider.
+    ///
+    /// The API key is provided once the user has configured one, and the models
+    /// are listed again whenever it changes.
+    fn llm_provider_models(
+        &mut self,
+        _provider_id: String,
+        _api_key: Option<String>,
+    ) -> Result<Vec<llm_provider::LlmModel>, String> {
+        Err("`llm_provider_models` not implemented".to_string())
+    }
+
+    /// Starts streaming a completion from the specified model of a language model provider.
+    ///
+    /// The API key is provided if the provider requires one.
+    fn llm_stream_completion(
+        &mut self,
+        _provider_id: String,
+        _model_id: String,
+        _request: llm_provider::LlmCompletionRequest,
+        _api_key: Option<String>,
+    ) -> Result<Box<dyn llm_provider::LlmCompletionStream>, String> {
+        Err("`llm_stream_completion` not implemented".to_string())
+    }
+
+    /// Returns the tasks that the specified task provider offers for the worktree,
+    /// along with any variables those tasks can reference.
+    fn worktree_tasks(
+        &mut self,
+        _provider_id: String,
+        _worktree: &Worktree,
+    ) -> Result<task::WorktreeTasks, String> {
+        Ok(task::WorktreeTasks {
+            templates: Vec::new(),
+            variables: Vec::new(),
+        })
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -228,7 +328,7 @@ mod wit {
 
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.5.0",
+        path: "./wit/since_v0.6.0",
     });
 }
 
@@ -371,6 +471,71 @@ impl wit::Guest for Component {
     ) -> Result<(), String> {
         extension().index_docs(provider, package, database)
     }
+
+    fn get_dap_binary(
+        adapter_name: String,
+        config: dap::DebugTaskDefinition,
+        user_installed_path: Option<String>,
+        worktree: &Worktree,
+    ) -> Result<dap::DebugAdapterBinary, String> {
+        extension().get_dap_binary(adapter_name, config, user_installed_path, worktree)
+    }
+
+    fn dap_request_kind(
+        adapter_name: String,
+        config: String,
+    ) -> Result<dap::StartDebuggingRequestArgumentsRequest, String> {
+        let config = serde_json::from_str(&config).map_err(|error| error.to_string())?;
+        extension().dap_request_kind(adapter_name, config)
+    }
+
+    fn dap_default_config(adapter_name: String) -> Result<String, String> {
+        let config = extension().dap_default_config(adapter_name)?;
+        serde_json::to_string(&config).map_err(|error| error.to_string())
+    }
+
+    fn worktree_tasks(
+        provider_id: String,
+        worktree: &Worktree,
+    ) -> Result<task::WorktreeTasks, String> {
+        extension().worktree_tasks(provider_id, worktree)
+    }
+
+    fn run_agent_tool(
+        tool_name: String,
+        input: String,
+        worktree: Option<&Worktree>,
+    ) -> Result<String, String> {
+        let input = serde_json::from_str(&input).map_err(|error| error.to_string())?;
+        extension().run_agent_tool(tool_name, input, worktree)
+    }
+
+    fn llm_provider_models(
+        provider_id: String,
+        api_key: Option<String>,
+    ) -> Result<Vec<llm_provider::LlmModel>, String> {
+        extension().llm_provider_models(provider_id, api_key)
+    }
+
+    fn llm_stream_completion(
+        provider_id: String,
+        model_id: String,
+        request: llm_provider::LlmCompletionRequest,
+        api_key: Option<String>,
+    ) -> Result<u64, String> {
+        let stream = extension().llm_stream_completion(provider_id, model_id, request, api_key)?;
+        Ok(llm_provider::insert_completion_stream(stream))
+    }
+
+    fn llm_next_completion_events(
+        stream_id: u64,
+    ) -> Result<Option<Vec<llm_provider::LlmCompletionEvent>>, String> {
+        llm_provider::next_completion_events(stream_id)
+    }
+
+    fn llm_drop_completion_stream(stream_id: u64) {
+        llm_provider::drop_completion_stream(stream_id)
+    }
 }
 
 /// The ID of a language server.
@@ -0,0 +1,54 @@
+//! Language model providers.
+
+use std::collections::BTreeMap;
+
+pub use crate::wit::zed::extension::llm_provider::{
+    LlmCompletionEvent, LlmCompletionRequest, LlmMessageContent, LlmMessageRole, LlmModel,
+    LlmRequestMessage, LlmStopReason, LlmTokenUsage, LlmToolDefinition, LlmToolResult, LlmToolUse,
+};
+
+/// A completion being streamed from a language model.
+///
+/// Implementations typically wrap the [`HttpResponseStream`](crate::http_client::HttpResponseStream)
+/// returned by [`fetch_stream`](crate::http_client::fetch_stream), parsing each chunk of the
+/// response into [`LlmCompletionEvent`]s.
+pub trait LlmCompletionStream: Send {
+    /// Returns the next events of the completion, or `None` once the completion has ended.
+    ///
+    /// Returning an empty list is allowed when a chunk of the response did not contain
+    /// a complete event.
+    fn next_events(&mut self) -> Result<Option<Vec<LlmCompletionEvent>>, String>;
+}
+
+static mut COMPLETION_STREAMS: BTreeMap<u64, Box<dyn LlmCompletionStream>> = BTreeMap::new();
+static mut NEXT_COMPLETION_STREAM_ID: u64 = 0;
+
+#[expect(static_mut_refs)]
+pub(crate) fn insert_completion_stream(stream: Box<dyn LlmCompletionStream>) -> u64 {
+    unsafe {
+        let stream_id = NEXT_COMPLETION_STREAM_ID;
+        NEXT_COMPLETION_STREAM_ID += 1;
+        COMPLETION_STREAMS.insert(stream_id, stream);
+        stream_id
+    }
+}
+
+#[expect(static_mut_refs)]
+pub(crate) fn next_completion_events(
+    stream_id: u64,
+) -> Result<Option<Vec<LlmCompletionEvent>>, String> {
+    let stream = unsafe { COMPLETION_STREAMS.get_mut(&stream_id) }
+        .ok_or_else(|| format!("no completion stream with ID {stream_id}"))?;
+    let events = stream.next_events()?;
+    if events.is_none() {
+        drop_completion_stream(stream_id);
+    }
+    Ok(events)
+}
+
+#[expect(static_mut_refs)]
+pub(crate) fn drop_completion_stream(stream_id: u64) {
+    unsafe {
+        COMPLETION_STREAMS.remove(&stream_id);
+    }
+}
@@ -1,19 +1,26 @@
+mod test_harness;
+
 use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::sync::Arc;
 
 use ::fs::{CopyOptions, Fs, RealFs, copy_recursive};
 use anyhow::{Context, Result, anyhow, bail};
+use async_compression::futures::write::GzipEncoder;
+use async_tar::{EntryType, Header};
 use clap::Parser;
 use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
+use futures::AsyncWriteExt as _;
 use language::LanguageConfig;
 use reqwest_client::ReqwestClient;
 use rpc::ExtensionProvides;
+use sha2::{Digest, Sha256};
+use snippet_provider::format::VsSnippetsFile;
 use tree_sitter::{Language, Query, WasmStore};
+use walkdir::WalkDir;
 
 #[derive(Parser, Debug)]
 #[command(name = "zed-extension")]
@@ -27,6 +34,12 @@ struct Args {
     /// The path to a directory where build dependencies are downloaded
     #[arg(long)]
     scratch_dir: PathBuf,
+    /// The path to a directory to use as the worktree when testing the extension.
+    ///
+    /// If provided, the compiled extension is loaded and its language servers,
+    /// slash commands, and docs providers are run against this worktree.
+    #[arg(long)]
+    test_worktree: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -65,7 +78,7 @@ async fn main() -> Result<()> {
     );
     let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
 
-    let builder = ExtensionBuilder::new(http_client, scratch_dir);
+    let builder = ExtensionBuilder::new(http_client.clone(), scratch_dir.clone());
     builder
         .compile_extension(
             &extension_path,
@@ -75,9 +88,40 @@ async fn main() -> Result<()> {
         .await
         .context("failed to compile extension")?;
 
+    // The builder records the absolute path of the snippets file, but the packaged
+    // manifest needs to refer to it relative to the extension directory.
+    if let Some(snippets_path) = manifest.snippets.as_mut() {
+        if let Ok(relative_path) = snippets_path.strip_prefix(&extension_path) {
+            *snippets_path = relative_path.to_path_buf();
+        }
+    }
+
     let grammars = test_grammars(&manifest, &extension_path, &mut wasm_store)?;
     test_languages(&manifest, &extension_path, &grammars)?;
     test_themes(&manifest, &extension_path, fs.clone()).await?;
+    test_slash_commands(&manifest)?;
+    test_context_servers(&manifest)?;
+    test_indexed_docs_providers(&manifest)?;
+    test_snippets(&manifest, &extension_path)?;
+
+    if let Some(test_worktree) = args.test_worktree {
+        if manifest.lib.kind.is_none() {
+            bail!("--test-worktree requires an extension that provides a library");
+        }
+        log::info!("testing extension against {}", test_worktree.display());
+        let test_worktree = test_worktree
+            .canonicalize()
+            .context("failed to canonicalize test_worktree")?;
+        test_harness::run_extension_tests(
+            manifest.clone(),
+            extension_path.clone(),
+            test_worktree,
+            scratch_dir.join("work"),
+            fs.clone(),
+            http_client.clone(),
+        )
+        .context("extension tests failed")?;
+    }
 
     let archive_dir = output_dir.join("archive");
     fs::remove_dir_all(&archive_dir).ok();
@@ -85,17 +129,9 @@ async fn main() -> Result<()> {
         .await
         .context("failed to copy extension resources")?;
 
-    let tar_output = Command::new("tar")
-        .current_dir(&output_dir)
-        .args(["-czvf", "archive.tar.gz", "-C", "archive", "."])
-        .output()
-        .context("failed to run tar")?;
-    if !tar_output.status.success() {
-        bail!(
-            "failed to create archive.tar.gz: {}",
-            String::from_utf8_lossy(&tar_output.stderr)
-        );
-    }
+    let archive_checksum = create_archive(&archive_dir, &output_dir.join("archive.tar.gz"))
+        .await
+        .context("failed to create archive.tar.gz")?;
 
     let extension_provides = extension_provides(&manifest);
 
@@ -110,6 +146,7 @@ async fn main() -> Result<()> {
             .ok_or_else(|| anyhow!("missing repository in extension manifest"))?,
         wasm_api_version: manifest.lib.version.map(|version| version.to_string()),
         provides: extension_provides,
+        archive_checksum: Some(archive_checksum),
     })?;
     fs::remove_dir_all(&archive_dir)?;
     fs::write(output_dir.join("manifest.json"), manifest_json.as_bytes())?;
@@ -117,6 +154,48 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Writes the contents of `archive_dir` to a gzipped tarball at `archive_path`,
+/// returning the SHA-256 checksum of the archive.
+///
+/// The archive is deterministic: entries are sorted by path, and their timestamps,
+/// ownership, and permissions are normalized.
+async fn create_archive(archive_dir: &Path, archive_path: &Path) -> Result<String> {
+    let mut builder = async_tar::Builder::new(GzipEncoder::new(Vec::new()));
+
+    for entry in WalkDir::new(archive_dir).sort_by_file_name().min_depth(1) {
+        let entry = entry?;
+        let relative_path = entry.path().strip_prefix(archive_dir)?;
+
+        let mut header = Header::new_gnu();
+        header.set_mtime(0);
+        header.set_uid(0);
+        header.set_gid(0);
+        if entry.file_type().is_dir() {
+            header.set_entry_type(EntryType::Directory);
+            header.set_mode(0o755);
+            header.set_size(0);
+            builder
+                .append_data(&mut header, relative_path, futures::io::empty())
+                .await?;
+        } else {
+            let contents = fs::read(entry.path())?;
+            header.set_entry_type(EntryType::Regular);
+            header.set_mode(0o644);
+            header.set_size(contents.len() as u64);
+            builder
+                .append_data(&mut header, relative_path, contents.as_slice())
+                .await?;
+        }
+    }
+
+    let mut encoder = builder.into_inner().await?;
+    encoder.close().await?;
+    let archive = encoder.into_inner();
+    fs::write(archive_path, &archive)?;
+
+    Ok(format!("{:x}", Sha256::digest(&archive)))
+}
+
 /// Returns the set of features provided by the extension.
 fn extension_provides(manifest: &ExtensionManifest) -> BTreeSet<ExtensionProvides> {
     let mut provides = BTreeSet::default();
@@ -152,6 +231,22 @@ fn extension_provides(manifest: &ExtensionManifest) -> BTreeSet<ExtensionProvide
         provides.insert(ExtensionProvides::Snippets);
     }
 
+    if !manifest.debug_adapters.is_empty() {
+        provides.insert(ExtensionProvides::DebugAdapters);
+    }
+
+    if !manifest.task_providers.is_empty() {
+        provides.insert(ExtensionProvides::TaskProviders);
+    }
+
+    if !manifest.agent_tools.is_empty() {
+        provides.insert(ExtensionProvides::AgentTools);
+    }
+
+    if !manifest.language_model_providers.is_empty() {
+        provides.insert(ExtensionProvides::LanguageModelProviders);
+    }
+
     provides
 }
 
@@ -238,6 +333,14 @@ async fn copy_extension_resources(
         .with_context(|| "failed to copy icons")?;
     }
 
+    if let Some(snippets_path) = &manifest.snippets {
+        fs::copy(
+            extension_path.join(snippets_path),
+            output_dir.join(snippets_path),
+        )
+        .with_context(|| format!("failed to copy snippets '{}'", snippets_path.display()))?;
+    }
+
     if !manifest.languages.is_empty() {
         let output_languages_dir = output_dir.join("languages");
         fs::create_dir_all(&output_languages_dir)?;
@@ -357,3 +460,123 @@ async fn test_themes(
 
     Ok(())
 }
+
+fn test_slash_commands(manifest: &ExtensionManifest) -> Result<()> {
+    for (name, slash_command) in &manifest.slash_commands {
+        if manifest.lib.kind.is_none() {
+            bail!("slash command /{name} is provided without an extension library");
+        }
+        if slash_command.description.trim().is_empty() {
+            bail!("slash command /{name} has an empty description");
+        }
+        log::info!("found slash command /{name}");
+    }
+
+    Ok(())
+}
+
+fn test_context_servers(manifest: &ExtensionManifest) -> Result<()> {
+    for id in manifest.context_servers.keys() {
+        if manifest.lib.kind.is_none() {
+            bail!("context server {id} is provided without an extension library");
+        }
+        log::info!("found context server {id}");
+    }
+
+    Ok(())
+}
+
+fn test_indexed_docs_providers(manifest: &ExtensionManifest) -> Result<()> {
+    for id in manifest.indexed_docs_providers.keys() {
+        if manifest.lib.kind.is_none() {
+            bail!("docs provider {id} is provided without an extension library");
+        }
+        log::info!("found docs provider {id}");
+    }
+
+    Ok(())
+}
+
+fn test_snippets(manifest: &ExtensionManifest, extension_path: &Path) -> Result<()> {
+    let Some(snippets_path) = &manifest.snippets else {
+        return Ok(());
+    };
+
+    let snippets_content = fs::read_to_string(extension_path.join(snippets_path))
+        .with_context(|| format!("failed to read snippets '{}'", snippets_path.display()))?;
+    let snippets: VsSnippetsFile = serde_json_lenient::from_str(&snippets_content)
+        .with_context(|| format!("failed to parse snippets '{}'", snippets_path.display()))?;
+    let snippet_count = snippets.validate()?;
+    log::info!("loaded {snippet_count} snippets");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_archive_is_reproducible() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let archive_dir = temp_dir.path().join("archive");
+        fs::create_dir_all(archive_dir.join("grammars")).unwrap();
+        fs::create_dir_all(archive_dir.join("languages/test")).unwrap();
+        fs::write(archive_dir.join("extension.toml"), "id = \"test\"\n").unwrap();
+        fs::write(archive_dir.join("grammars/test.wasm"), [0, 97, 115, 109]).unwrap();
+        fs::write(
+            archive_dir.join("languages/test/config.toml"),
+            "name = \"Test\"\n",
+        )
+        .unwrap();
+
+        let first_path = temp_dir.path().join("first.tar.gz");
+        let first_sha = create_archive(&archive_dir, &first_path).await.unwrap();
+
+        // Rewriting a file bumps its modification time without changing its contents.
+        fs::write(archive_dir.join("extension.toml"), "id = \"test\"\n").unwrap();
+
+        let second_path = temp_dir.path().join("second.tar.gz");
+        let second_sha = create_archive(&archive_dir, &second_path).await.unwrap();
+
+        assert_eq!(first_sha, second_sha);
+        assert_eq!(
+            fs::read(&first_path).unwrap(),
+            fs::read(&second_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_extension_provides() {
+        let manifest: ExtensionManifest = toml::from_str(
+            r#"
+                id = "test"
+                name = "Test"
+                version = "0.1.0"
+                schema_version = 1
+
+                [debug_adapters.my-debugger]
+
+                [task_providers.just]
+                files = ["justfile"]
+
+                [agent_tools.search_issues]
+                description = "Searches the issue tracker."
+
+                [language_model_providers.my-llm]
+                name = "My LLM"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            extension_provides(&manifest),
+            BTreeSet::from_iter([
+                ExtensionProvides::DebugAdapters,
+                ExtensionProvides::TaskProviders,
+                ExtensionProvides::AgentTools,
+                ExtensionProvides::LanguageModelProviders,
+            ])
+        );
+    }
+}
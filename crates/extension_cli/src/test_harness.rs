@@ -0,0 +1,384 @@
+//! Runs an extension's WebAssembly exports headlessly against a fixture worktree.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use ::fs::Fs;
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use extension::{
+    Extension as _, ExtensionHostProxy, ExtensionManifest, KeyValueStoreDelegate, SlashCommand,
+    WorktreeDelegate,
+};
+use extension_host::extension_settings::ExtensionCapabilityOverrides;
+use extension_host::wasm_host::{WasmExtension, WasmHost};
+use gpui::{App, AsyncApp, Task};
+use http_client::HttpClient;
+use node_runtime::NodeRuntime;
+use semantic_version::SemanticVersion;
+
+/// Loads the compiled extension and runs its language server, slash command, and
+/// docs provider exports against the worktree at `worktree_path`.
+pub fn run_extension_tests(
+    manifest: ExtensionManifest,
+    extension_path: PathBuf,
+    worktree_path: PathBuf,
+    work_dir: PathBuf,
+    fs: Arc<dyn Fs>,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<()> {
+    let (result_tx, result_rx) = mpsc::channel();
+
+    gpui::Application::headless().run(move |cx| {
+        release_channel::init(SemanticVersion::default(), cx);
+        settings::init(cx);
+        language::init(cx);
+        project::Project::init_settings(cx);
+
+        let wasm_host = test_wasm_host(&manifest, fs.clone(), http_client, work_dir, cx);
+
+        cx.spawn(async move |cx| {
+            let result = test_extension_exports(
+                Arc::new(manifest),
+                extension_path,
+                worktree_path,
+                fs,
+                wasm_host,
+                cx,
+            )
+            .await;
+            result_tx.send(result).ok();
+            cx.update(|cx| cx.quit()).ok();
+        })
+        .detach();
+    });
+
+    result_rx
+        .try_recv()
+        .context("extension tests did not complete")?
+}
+
+/// Creates the host to run the extension in.
+///
+/// The tests run without anyone to approve the capabilities that the extension
+/// requests, so they are all granted.
+fn test_wasm_host(
+    manifest: &ExtensionManifest,
+    fs: Arc<dyn Fs>,
+    http_client: Arc<dyn HttpClient>,
+    work_dir: PathBuf,
+    cx: &mut App,
+) -> Arc<WasmHost> {
+    let wasm_host = WasmHost::new(
+        fs,
+        http_client,
+        NodeRuntime::unavailable(),
+        Arc::new(ExtensionHostProxy::new()),
+        work_dir,
+        cx,
+    );
+    wasm_host.set_capability_overrides(
+        [(
+            manifest.id.clone(),
+            ExtensionCapabilityOverrides {
+                grant: manifest.capabilities.clone(),
+                deny: Vec::new(),
+            },
+        )]
+        .into_iter()
+        .collect(),
+    );
+    wasm_host
+}
+
+async fn test_extension_exports(
+    manifest: Arc<ExtensionManifest>,
+    extension_path: PathBuf,
+    worktree_path: PathBuf,
+    fs: Arc<dyn Fs>,
+    wasm_host: Arc<WasmHost>,
+    cx: &AsyncApp,
+) -> Result<()> {
+    let extension = WasmExtension::load(extension_path, &manifest, wasm_host, cx).await?;
+    let worktree: Arc<dyn WorktreeDelegate> = Arc::new(FixtureWorktree {
+        root_path: worktree_path,
+        fs,
+    });
+
+    for (language_server_id, entry) in &manifest.language_servers {
+        let Some(language_name) = entry.languages().into_iter().next() else {
+            bail!("language server {language_server_id} does not specify any languages");
+        };
+        let command = extension
+            .language_server_command(language_server_id.clone(), language_name, worktree.clone())
+            .await
+            .with_context(|| {
+                format!("failed to get command for language server {language_server_id}")
+            })?;
+        log::info!(
+            "language server {language_server_id} command: {} {}",
+            command.command,
+            command.args.join(" ")
+        );
+    }
+
+    for (name, entry) in &manifest.slash_commands {
+        let command = SlashCommand {
+            name: name.to_string(),
+            description: entry.description.clone(),
+            tooltip_text: String::new(),
+            requires_argument: entry.requires_argument,
+        };
+
+        let mut arguments = Vec::new();
+        if entry.requires_argument {
+            let completions = extension
+                .complete_slash_command_argument(command.clone(), Vec::new())
+                .await
+                .with_context(|| {
+                    format!("failed to complete argument for slash command /{name}")
+                })?;
+            let Some(completion) = completions.into_iter().next() else {
+                log::warn!(
+                    "skipping slash command /{name}: it requires an argument but suggested none"
+                );
+                continue;
+            };
+            arguments.push(completion.new_text);
+        }
+
+        let output = extension
+            .run_slash_command(command, arguments, Some(worktree.clone()))
+            .await
+            .with_context(|| format!("failed to run slash command /{name}"))?;
+        log::info!(
+            "slash command /{name} produced {} bytes of output in {} sections",
+            output.text.len(),
+            output.sections.len()
+        );
+    }
+
+    for provider in manifest.indexed_docs_providers.keys() {
+        let packages = extension
+            .suggest_docs_packages(provider.clone())
+            .await
+            .with_context(|| format!("failed to suggest packages for docs provider {provider}"))?;
+        let Some(package) = packages.into_iter().next() else {
+            log::warn!("skipping docs provider {provider}: it suggested no packages");
+            continue;
+        };
+
+        let kv_store = Arc::new(FixtureKeyValueStore::default());
+        extension
+            .index_docs(provider.clone(), package.as_str().into(), kv_store.clone())
+            .await
+            .with_context(|| format!("failed to index {package} with docs provider {provider}"))?;
+        log::info!(
+            "docs provider {provider} indexed {} entries for {package}",
+            kv_store.entry_count.load(Ordering::SeqCst)
+        );
+    }
+
+    Ok(())
+}
+
+/// A worktree backed by a directory on disk.
+struct FixtureWorktree {
+    root_path: PathBuf,
+    fs: Arc<dyn Fs>,
+}
+
+#[async_trait]
+impl WorktreeDelegate for FixtureWorktree {
+    fn id(&self) -> u64 {
+        0
+    }
+
+    fn root_path(&self) -> String {
+        self.root_path.to_string_lossy().to_string()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        self.fs.load(&self.root_path.join(path)).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        which::which_in(binary_name, std::env::var_os("PATH"), &self.root_path)
+            .ok()
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        std::env::vars().collect()
+    }
+}
+
+/// A key-value store that discards the indexed docs, counting the entries.
+#[derive(Default)]
+struct FixtureKeyValueStore {
+    entry_count: AtomicUsize,
+}
+
+impl KeyValueStoreDelegate for FixtureKeyValueStore {
+    fn insert(&self, _key: String, _docs: String) -> Task<Result<()>> {
+        self.entry_count.fetch_add(1, Ordering::SeqCst);
+        Task::ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fs::RealFs;
+    use async_compression::futures::bufread::GzipEncoder;
+    use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
+    use futures::{AsyncReadExt as _, io::BufReader};
+    use gpui::TestAppContext;
+    use http_client::{FakeHttpClient, Response};
+    use reqwest_client::ReqwestClient;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+
+    // todo(windows)
+    // The test extension only knows how to download its language server on macOS and Linux.
+    #[gpui::test]
+    #[cfg_attr(target_os = "windows", ignore)]
+    async fn test_capabilities_requested_by_extension_are_granted(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            release_channel::init(SemanticVersion::default(), cx);
+            language::init(cx);
+            project::Project::init_settings(cx);
+        });
+
+        let root_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap();
+        let extension_path = root_dir.join("extensions").join("test-extension");
+        let fs: Arc<dyn Fs> = Arc::new(RealFs::new(None, cx.executor()));
+        let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path)
+            .await
+            .unwrap();
+        let builder_client = Arc::new(ReqwestClient::user_agent("Zed Extension CLI").unwrap());
+        ExtensionBuilder::new(builder_client, root_dir.join("target"))
+            .compile_extension(
+                &extension_path,
+                &mut manifest,
+                CompileExtensionOptions { release: false },
+            )
+            .await
+            .unwrap();
+
+        // The test extension runs `echo`, fetches the latest Gleam release from GitHub
+        // and downloads it into its work dir, each of which needs a capability.
+        let http_client = FakeHttpClient::create(|request| async move {
+            let uri = request.uri().to_string();
+            if uri == "https://api.github.com/repos/gleam-lang/gleam/releases" {
+                let assets = [
+                    "aarch64-apple-darwin",
+                    "x86_64-apple-darwin",
+                    "aarch64-unknown-linux-musl",
+                    "x86_64-unknown-linux-musl",
+                ]
+                .map(|target| {
+                    json!({
+                        "name": format!("gleam-v1.2.3-{target}.tar.gz"),
+                        "browser_download_url": "https://fake-download.example.com/gleam-v1.2.3",
+                    })
+                });
+                let releases = json!([{
+                    "tag_name": "v1.2.3",
+                    "prerelease": false,
+                    "tarball_url": "",
+                    "zipball_url": "",
+                    "assets": assets,
+                }]);
+                Ok(Response::new(releases.to_string().into()))
+            } else if uri == "https://fake-download.example.com/gleam-v1.2.3" {
+                let contents = b"the-binary-contents";
+                let mut bytes = Vec::<u8>::new();
+                let mut archive = async_tar::Builder::new(&mut bytes);
+                let mut header = async_tar::Header::new_gnu();
+                header.set_size(contents.len() as u64);
+                archive
+                    .append_data(&mut header, "gleam", contents.as_slice())
+                    .await?;
+                archive.into_inner().await?;
+                let mut gzipped_bytes = Vec::new();
+                GzipEncoder::new(BufReader::new(bytes.as_slice()))
+                    .read_to_end(&mut gzipped_bytes)
+                    .await?;
+                Ok(Response::new(gzipped_bytes.into()))
+            } else {
+                Ok(Response::builder().status(404).body("not found".into())?)
+            }
+        });
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+        std::fs::create_dir_all(&worktree_path).unwrap();
+        let manifest = Arc::new(manifest);
+
+        // Without the capabilities, the extension can't run its language server.
+        let wasm_host = cx.update(|cx| {
+            WasmHost::new(
+                fs.clone(),
+                http_client.clone(),
+                NodeRuntime::unavailable(),
+                Arc::new(ExtensionHostProxy::new()),
+                temp_dir.path().join("denied-work"),
+                cx,
+            )
+        });
+        let error = test_extension_exports(
+            manifest.clone(),
+            extension_path.clone(),
+            worktree_path.clone(),
+            fs.clone(),
+            wasm_host,
+            &cx.to_async(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            format!("{error:?}").contains("is not granted"),
+            "unexpected error: {error:?}"
+        );
+
+        let wasm_host = cx.update(|cx| {
+            test_wasm_host(
+                &manifest,
+                fs.clone(),
+                http_client.clone(),
+                temp_dir.path().join("work"),
+                cx,
+            )
+        });
+        test_extension_exports(
+            manifest.clone(),
+            extension_path,
+            worktree_path,
+            fs,
+            wasm_host,
+            &cx.to_async(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read(
+                temp_dir
+                    .path()
+                    .join("work/test-extension/gleam-v1.2.3/gleam")
+            )
+            .unwrap(),
+            b"the-binary-contents"
+        );
+    }
+}
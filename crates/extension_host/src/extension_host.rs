@@ -14,9 +14,11 @@ use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map};
 pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
-    ExtensionContextServerProxy, ExtensionEvents, ExtensionGrammarProxy, ExtensionHostProxy,
-    ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionAgentToolProxy, ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy,
+    ExtensionEvents, ExtensionGrammarProxy, ExtensionHostProxy, ExtensionIndexedDocsProviderProxy,
+    ExtensionLanguageModelProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
+    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionTaskProviderProxy,
+    ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -43,7 +45,7 @@ use release_channel::ReleaseChannel;
 use remote::SshRemoteClient;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{Settings, SettingsStore, update_settings_file};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::{
@@ -114,6 +116,9 @@ pub struct ExtensionStore {
     pub modified_extensions: HashSet<Arc<str>>,
     pub wasm_host: Arc<WasmHost>,
     pub wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
+    /// Extensions that aren't loaded because the user hasn't yet answered whether to
+    /// grant some of the capabilities that they request.
+    pub capability_requests: BTreeMap<Arc<str>, Arc<ExtensionManifest>>,
     pub tasks: Vec<Task<()>>,
     pub ssh_clients: HashMap<String, WeakEntity<SshRemoteClient>>,
     pub ssh_registered_tx: UnboundedSender<()>,
@@ -132,6 +137,7 @@ pub enum Event {
     StartedReloading,
     ExtensionInstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    ExtensionCapabilitiesRequested(Arc<str>),
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -255,6 +261,7 @@ impl ExtensionStore {
                 cx,
             ),
             wasm_extensions: Vec::new(),
+            capability_requests: BTreeMap::default(),
             fs,
             http_client,
             telemetry,
@@ -298,6 +305,26 @@ impl ExtensionStore {
             }
         }
 
+        this.wasm_host.set_capability_overrides(
+            ExtensionSettings::get_global(cx)
+                .extension_capabilities
+                .clone(),
+        );
+
+        // Reload any extension whose capability overrides change, so that the
+        // new overrides are applied to its wasm instance.
+        cx.observe_global::<SettingsStore>(|this, cx| {
+            let overrides = ExtensionSettings::get_global(cx)
+                .extension_capabilities
+                .clone();
+            for extension_id in this.wasm_host.set_capability_overrides(overrides) {
+                if this.extension_index.extensions.contains_key(&extension_id) {
+                    drop(this.reload(Some(extension_id), cx));
+                }
+            }
+        })
+        .detach();
+
         // Immediately load all of the extensions in the initial manifest. If the
         // index needs to be rebuild, then enqueue
         let load_initial_extensions = this.extensions_updated(extension_index, cx);
@@ -438,6 +465,53 @@ impl ExtensionStore {
             .map(|extension| &extension.manifest)
     }
 
+    /// Returns the extensions that are waiting for the user to answer whether to
+    /// grant the capabilities that they request.
+    pub fn capability_requests(&self) -> impl Iterator<Item = &Arc<ExtensionManifest>> {
+        self.capability_requests.values()
+    }
+
+    pub fn capability_request(&self, extension_id: &str) -> Option<&Arc<ExtensionManifest>> {
+        self.capability_requests.get(extension_id)
+    }
+
+    /// Records whether the user grants or denies the unanswered capabilities requested
+    /// by an extension that is awaiting approval.
+    ///
+    /// The extension is reloaded once the answer is saved in the user's settings.
+    pub fn answer_capability_request(
+        &mut self,
+        extension_id: &str,
+        allow: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(manifest) = self.capability_requests.remove(extension_id) else {
+            return;
+        };
+        let capabilities = ExtensionSettings::get_global(cx)
+            .unanswered_capabilities(&manifest.id, &manifest.capabilities);
+        update_settings_file::<ExtensionSettings>(self.fs.clone(), cx, move |settings, _| {
+            let overrides = settings
+                .extension_capabilities
+                .entry(manifest.id.clone())
+                .or_default();
+            for capability in capabilities {
+                if allow {
+                    overrides.deny.retain(|denied| denied != &capability);
+                    if !overrides.grant.contains(&capability) {
+                        overrides.grant.push(capability);
+                    }
+                } else {
+                    overrides.grant.retain(|granted| granted != &capability);
+                    if !overrides.deny.contains(&capability) {
+                        overrides.deny.push(capability);
+                    }
+                }
+            }
+        });
+        cx.notify();
+    }
+
     /// Returns the names of themes provided by extensions.
     pub fn extension_themes<'a>(
         &'a self,
@@ -1120,6 +1194,7 @@ impl ExtensionStore {
             .collect::<Vec<_>>();
         let mut grammars_to_remove = Vec::new();
         for extension_id in &extensions_to_unload {
+            self.capability_requests.remove(extension_id);
             let Some(extension) = old_index.extensions.get(extension_id) else {
                 continue;
             };
@@ -1130,6 +1205,30 @@ impl ExtensionStore {
                         .remove_language_server(&language, language_server_name);
                 }
             }
+            for debug_adapter_name in extension.manifest.debug_adapters.keys() {
+                self.proxy.unregister_debug_adapter(
+                    extension.manifest.id.clone(),
+                    debug_adapter_name.clone(),
+                );
+            }
+            for provider_id in extension.manifest.task_providers.keys() {
+                self.proxy.unregister_task_provider(
+                    extension.manifest.id.clone(),
+                    provider_id.clone(),
+                    cx,
+                );
+            }
+            for tool_name in extension.manifest.agent_tools.keys() {
+                self.proxy
+                    .unregister_agent_tool(extension.manifest.id.clone(), tool_name.clone());
+            }
+            for provider_id in extension.manifest.language_model_providers.keys() {
+                self.proxy.unregister_language_model_provider(
+                    extension.manifest.id.clone(),
+                    provider_id.clone(),
+                    cx,
+                );
+            }
         }
 
         self.wasm_extensions
@@ -1267,6 +1366,31 @@ impl ExtensionStore {
                     continue;
                 };
 
+                // Extensions are only loaded once the user has answered whether to
+                // grant each of the capabilities that they request.
+                let awaiting_approval = this
+                    .update(cx, |this, cx| {
+                        let unanswered_capabilities = ExtensionSettings::get_global(cx)
+                            .unanswered_capabilities(
+                                &extension.manifest.id,
+                                &extension.manifest.capabilities,
+                            );
+                        if unanswered_capabilities.is_empty() {
+                            return false;
+                        }
+
+                        this.capability_requests
+                            .insert(extension.manifest.id.clone(), extension.manifest.clone());
+                        cx.emit(Event::ExtensionCapabilitiesRequested(
+                            extension.manifest.id.clone(),
+                        ));
+                        true
+                    })
+                    .unwrap_or(true);
+                if awaiting_approval {
+                    continue;
+                }
+
                 let extension_path = root_dir.join(extension.manifest.id.as_ref());
                 let wasm_extension = WasmExtension::load(
                     extension_path,
@@ -1326,6 +1450,42 @@ impl ExtensionStore {
                         this.proxy
                             .register_indexed_docs_provider(extension.clone(), provider_id.clone());
                     }
+
+                    for debug_adapter_name in manifest.debug_adapters.keys() {
+                        this.proxy
+                            .register_debug_adapter(extension.clone(), debug_adapter_name.clone());
+                    }
+
+                    for (tool_name, tool) in &manifest.agent_tools {
+                        this.proxy.register_agent_tool(
+                            extension.clone(),
+                            extension::AgentTool {
+                                name: tool_name.to_string(),
+                                description: tool.description.clone(),
+                                input_schema: tool.input_schema.clone().unwrap_or_else(
+                                    || serde_json::json!({ "type": "object", "properties": {} }),
+                                ),
+                            },
+                        );
+                    }
+
+                    for (provider_id, provider) in &manifest.task_providers {
+                        this.proxy.register_task_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.clone(),
+                            cx,
+                        );
+                    }
+
+                    for (provider_id, provider) in &manifest.language_model_providers {
+                        this.proxy.register_language_model_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.clone(),
+                            cx,
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use collections::HashMap;
+use extension::ExtensionCapability;
 use gpui::App;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -18,6 +19,24 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     #[serde(default)]
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    /// Per-extension overrides of the capabilities that extensions request in their manifests.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub extension_capabilities: HashMap<Arc<str>, ExtensionCapabilityOverrides>,
+}
+
+/// Overrides of the capabilities requested by an extension.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ExtensionCapabilityOverrides {
+    /// Capabilities granted to the extension, in addition to the ones it requests.
+    #[serde(default)]
+    pub grant: Vec<ExtensionCapability>,
+    /// Capabilities denied to the extension, even if it requests them.
+    ///
+    /// An operation is denied if any of these capabilities would allow it.
+    #[serde(default)]
+    pub deny: Vec<ExtensionCapability>,
 }
 
 impl ExtensionSettings {
@@ -35,6 +54,25 @@ impl ExtensionSettings {
             .copied()
             .unwrap_or(true)
     }
+
+    /// Returns the capabilities requested by the given extension that the user has
+    /// neither granted nor denied yet.
+    pub fn unanswered_capabilities(
+        &self,
+        extension_id: &str,
+        requested: &[ExtensionCapability],
+    ) -> Vec<ExtensionCapability> {
+        let overrides = self.extension_capabilities.get(extension_id);
+        requested
+            .iter()
+            .filter(|capability| {
+                overrides.map_or(true, |overrides| {
+                    !overrides.grant.contains(capability) && !overrides.deny.contains(capability)
+                })
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 impl Settings for ExtensionSettings {
@@ -1,3 +1,4 @@
+use crate::extension_settings::ExtensionCapabilityOverrides;
 use crate::{
     Event, ExtensionIndex, ExtensionIndexEntry, ExtensionIndexLanguageEntry,
     ExtensionIndexThemeEntry, ExtensionManifest, ExtensionSettings, ExtensionStore,
@@ -164,6 +165,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                         capabilities: Vec::new(),
+                        debug_adapters: BTreeMap::default(),
+                        task_providers: BTreeMap::default(),
+                        agent_tools: BTreeMap::default(),
+                        language_model_providers: BTreeMap::default(),
                     }),
                     dev: false,
                 },
@@ -193,6 +198,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                         capabilities: Vec::new(),
+                        debug_adapters: BTreeMap::default(),
+                        task_providers: BTreeMap::default(),
+                        agent_tools: BTreeMap::default(),
+                        language_model_providers: BTreeMap::default(),
                     }),
                     dev: false,
                 },
@@ -359,6 +368,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 indexed_docs_providers: BTreeMap::default(),
                 snippets: None,
                 capabilities: Vec::new(),
+                debug_adapters: BTreeMap::default(),
+                task_providers: BTreeMap::default(),
+                agent_tools: BTreeMap::default(),
+                language_model_providers: BTreeMap::default(),
             }),
             dev: false,
         },
@@ -626,6 +639,32 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
         .await
         .unwrap();
 
+    // The extension isn't loaded until the user approves the capabilities that it requests.
+    extension_store.read_with(cx, |store, _| assert!(store.wasm_extensions.is_empty()));
+
+    let manifest = ExtensionManifest::load(fs.clone(), &test_extension_dir)
+        .await
+        .unwrap();
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ExtensionSettings>(cx, |settings| {
+                settings.extension_capabilities.insert(
+                    test_extension_id.into(),
+                    ExtensionCapabilityOverrides {
+                        grant: manifest.capabilities,
+                        deny: Vec::new(),
+                    },
+                );
+            });
+        });
+    });
+    extension_store
+        .update(cx, |store, cx| {
+            store.reload(Some(test_extension_id.into()), cx)
+        })
+        .await;
+    extension_store.read_with(cx, |store, _| assert_eq!(store.wasm_extensions.len(), 1));
+
     let mut fake_servers = language_registry.register_fake_language_server(
         LanguageServerName("gleam".into()),
         lsp::ServerCapabilities {
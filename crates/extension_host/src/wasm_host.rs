@@ -1,12 +1,19 @@
+mod capability_granter;
 pub mod wit;
 
 use crate::ExtensionManifest;
+use crate::extension_settings::ExtensionCapabilityOverrides;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
+use capability_granter::CapabilityGranter;
+use collections::{HashMap, HashSet};
+use dap::StartDebuggingRequestArgumentsRequest;
+use dap::adapters::{DebugAdapterBinary, DebugTaskDefinition};
 use extension::{
-    CodeLabel, Command, Completion, ContextServerConfiguration, ExtensionHostProxy,
-    KeyValueStoreDelegate, ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion,
-    SlashCommandOutput, Symbol, WorktreeDelegate,
+    CodeLabel, Command, Completion, ContextServerConfiguration, ExtensionHostProxy, FsScope,
+    KeyValueStoreDelegate, LlmCompletionEvent, LlmCompletionRequest, LlmModel, ProjectDelegate,
+    SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
+    WorktreeTasks,
 };
 use fs::{Fs, normalize_path};
 use futures::future::LocalBoxFuture;
@@ -23,6 +30,7 @@ use http_client::HttpClient;
 use language::LanguageName;
 use lsp::LanguageServerName;
 use node_runtime::NodeRuntime;
+use parking_lot::RwLock;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
 use std::{
@@ -44,6 +52,8 @@ pub struct WasmHost {
     pub(crate) proxy: Arc<ExtensionHostProxy>,
     fs: Arc<dyn Fs>,
     pub work_dir: PathBuf,
+    /// The user's overrides of the capabilities requested by extensions, keyed by extension ID.
+    capability_overrides: RwLock<HashMap<Arc<str>, ExtensionCapabilityOverrides>>,
     _main_thread_message_task: Task<()>,
     main_thread_message_tx: mpsc::UnboundedSender<MainThreadCall>,
 }
@@ -374,10 +384,193 @@ impl extension::Extension for WasmExtension {
         })
         .await
     }
+
+    async fn get_dap_binary(
+        &self,
+        dap_name: Arc<str>,
+        config: DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<DebugAdapterBinary> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let dap_binary = extension
+                    .call_get_dap_binary(
+                        store,
+                        &dap_name,
+                        &config.into(),
+                        user_installed_path.as_ref().and_then(|path| path.to_str()),
+                        resource,
+                    )
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                dap_binary.try_into()
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn dap_request_kind(
+        &self,
+        dap_name: Arc<str>,
+        config: serde_json::Value,
+    ) -> Result<StartDebuggingRequestArgumentsRequest> {
+        self.call(|extension, store| {
+            async move {
+                let kind = extension
+                    .call_dap_request_kind(store, &dap_name, &config.to_string())
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(kind.into())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn dap_default_config(&self, dap_name: Arc<str>) -> Result<serde_json::Value> {
+        self.call(|extension, store| {
+            async move {
+                let config = extension
+                    .call_dap_default_config(store, &dap_name)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                serde_json::from_str(&config)
+                    .context("failed to parse default debug adapter configuration")
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn worktree_tasks(
+        &self,
+        provider_id: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<WorktreeTasks> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let tasks = extension
+                    .call_worktree_tasks(store, &provider_id, resource)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(tasks.into())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn run_agent_tool(
+        &self,
+        tool_name: Arc<str>,
+        input: serde_json::Value,
+        worktree: Option<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                let resource = worktree
+                    .map(|worktree| store.data_mut().table().push(worktree))
+                    .transpose()?;
+                let output = extension
+                    .call_run_agent_tool(store, &tool_name, &input.to_string(), resource)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(output)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_provider_models(
+        &self,
+        provider_id: Arc<str>,
+        api_key: Option<String>,
+    ) -> Result<Vec<LlmModel>> {
+        self.call(|extension, store| {
+            async move {
+                let models = extension
+                    .call_llm_provider_models(store, &provider_id, api_key.as_deref())
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(models.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LlmCompletionRequest,
+        api_key: Option<String>,
+    ) -> Result<u64> {
+        self.call(|extension, store| {
+            async move {
+                let stream_id = extension
+                    .call_llm_stream_completion(
+                        store,
+                        &provider_id,
+                        &model_id,
+                        &request.into(),
+                        api_key.as_deref(),
+                    )
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(stream_id)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_next_completion_events(
+        &self,
+        stream_id: u64,
+    ) -> Result<Option<Vec<LlmCompletionEvent>>> {
+        self.call(|extension, store| {
+            async move {
+                let events = extension
+                    .call_llm_next_completion_events(store, stream_id)
+                    .await?
+                    .map_err(|err| anyhow!("{err:?}"))?;
+
+                Ok(events.map(|events| events.into_iter().map(Into::into).collect()))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn llm_drop_completion_stream(&self, stream_id: u64) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_llm_drop_completion_stream(store, stream_id)
+                    .await
+            }
+            .boxed()
+        })
+        .await
+    }
 }
 
 pub struct WasmState {
     manifest: Arc<ExtensionManifest>,
+    capability_granter: CapabilityGranter,
     pub table: ResourceTable,
     ctx: wasi::WasiCtx,
     pub host: Arc<WasmHost>,
@@ -424,6 +617,7 @@ impl WasmHost {
             http_client,
             node_runtime,
             proxy,
+            capability_overrides: RwLock::default(),
             release_channel: ReleaseChannel::global(cx),
             _main_thread_message_task: task,
             main_thread_message_tx: tx,
@@ -444,11 +638,18 @@ impl WasmHost {
             let component = Component::from_binary(&this.engine, &wasm_bytes)
                 .context("failed to compile wasm component")?;
 
+            let capability_granter = CapabilityGranter::new(
+                &manifest,
+                zed_api_version,
+                this.capability_overrides.read().get(&manifest.id),
+            );
+
             let mut store = wasmtime::Store::new(
                 &this.engine,
                 WasmState {
-                    ctx: this.build_wasi_ctx(&manifest).await?,
+                    ctx: this.build_wasi_ctx(&manifest, &capability_granter).await?,
                     manifest: manifest.clone(),
+                    capability_granter,
                     table: ResourceTable::new(),
                     host: this.clone(),
                 },
@@ -485,28 +686,64 @@ impl WasmHost {
         })
     }
 
-    async fn build_wasi_ctx(&self, manifest: &Arc<ExtensionManifest>) -> Result<wasi::WasiCtx> {
+    async fn build_wasi_ctx(
+        &self,
+        manifest: &Arc<ExtensionManifest>,
+        capability_granter: &CapabilityGranter,
+    ) -> Result<wasi::WasiCtx> {
         let extension_work_dir = self.work_dir.join(manifest.id.as_ref());
         self.fs
             .create_dir(&extension_work_dir)
             .await
             .context("failed to create extension work dir")?;
 
-        let file_perms = wasi::FilePerms::all();
-        let dir_perms = wasi::DirPerms::all();
-
-        Ok(wasi::WasiCtxBuilder::new()
+        let mut builder = wasi::WasiCtxBuilder::new();
+        builder
             .inherit_stdio()
-            .preopened_dir(&extension_work_dir, ".", dir_perms, file_perms)?
-            .preopened_dir(
-                &extension_work_dir,
-                extension_work_dir.to_string_lossy(),
-                dir_perms,
-                file_perms,
-            )?
             .env("PWD", extension_work_dir.to_string_lossy())
-            .env("RUST_BACKTRACE", "full")
-            .build())
+            .env("RUST_BACKTRACE", "full");
+
+        // The work dir is only visible to extensions that may read or write it.
+        let perms = if capability_granter.is_fs_write_granted(FsScope::WorkDir) {
+            Some((wasi::FilePerms::all(), wasi::DirPerms::all()))
+        } else if capability_granter.is_fs_read_granted(FsScope::WorkDir) {
+            Some((wasi::FilePerms::READ, wasi::DirPerms::READ))
+        } else {
+            None
+        };
+        if let Some((file_perms, dir_perms)) = perms {
+            builder
+                .preopened_dir(&extension_work_dir, ".", dir_perms, file_perms)?
+                .preopened_dir(
+                    &extension_work_dir,
+                    extension_work_dir.to_string_lossy(),
+                    dir_perms,
+                    file_perms,
+                )?;
+        }
+
+        Ok(builder.build())
+    }
+
+    /// Replaces the user's overrides of extension capabilities.
+    ///
+    /// Returns the IDs of the extensions whose overrides changed. The new overrides
+    /// only take effect once those extensions are reloaded.
+    pub fn set_capability_overrides(
+        &self,
+        overrides: HashMap<Arc<str>, ExtensionCapabilityOverrides>,
+    ) -> HashSet<Arc<str>> {
+        let mut current_overrides = self.capability_overrides.write();
+        let changed_extension_ids = current_overrides
+            .keys()
+            .chain(overrides.keys())
+            .filter(|extension_id| {
+                current_overrides.get(*extension_id) != overrides.get(*extension_id)
+            })
+            .cloned()
+            .collect();
+        *current_overrides = overrides;
+        changed_extension_ids
     }
 
     pub fn writeable_path_from_extension(&self, id: &Arc<str>, path: &Path) -> Result<PathBuf> {
@@ -0,0 +1,436 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, bail};
+use extension::{ExtensionCapability, ExtensionManifest, FsScope};
+use http_client::{AsyncBody, HttpClient, Request, Response, StatusCode, http::header::LOCATION};
+use semantic_version::SemanticVersion;
+use url::Url;
+
+use crate::extension_settings::ExtensionCapabilityOverrides;
+
+/// Decides which operations an extension is allowed to perform, based on the
+/// capabilities that the user has granted it.
+#[derive(Debug, Clone)]
+pub struct CapabilityGranter {
+    extension_id: Arc<str>,
+    granted: Vec<ExtensionCapability>,
+    denied: Vec<ExtensionCapability>,
+}
+
+impl CapabilityGranter {
+    pub fn new(
+        manifest: &ExtensionManifest,
+        zed_api_version: SemanticVersion,
+        overrides: Option<&ExtensionCapabilityOverrides>,
+    ) -> Self {
+        // The capabilities requested in the manifest are only granted once the
+        // user has approved them, at which point they are part of the overrides.
+        let mut granted = Vec::new();
+
+        // Extensions built against earlier versions of the extension API had no way
+        // to declare these capabilities, so they keep the access they always had.
+        if zed_api_version < SemanticVersion::new(0, 6, 0) {
+            granted.extend([
+                ExtensionCapability::ProcessExec {
+                    command: "*".to_string(),
+                    args: vec!["**".to_string()],
+                },
+                ExtensionCapability::NetworkFetch {
+                    host: "*".to_string(),
+                },
+                ExtensionCapability::DownloadFile {
+                    host: "*".to_string(),
+                },
+                ExtensionCapability::NpmInstall {
+                    package: "*".to_string(),
+                },
+                ExtensionCapability::FsRead {
+                    scope: FsScope::Worktree,
+                },
+                ExtensionCapability::FsRead {
+                    scope: FsScope::WorkDir,
+                },
+                ExtensionCapability::FsWrite {
+                    scope: FsScope::WorkDir,
+                },
+            ]);
+        }
+
+        let mut denied = Vec::new();
+        if let Some(overrides) = overrides {
+            granted.extend(overrides.grant.iter().cloned());
+            denied.extend(overrides.deny.iter().cloned());
+        }
+
+        Self {
+            extension_id: manifest.id.clone(),
+            granted,
+            denied,
+        }
+    }
+
+    pub fn grant_exec(&self, command: &str, args: &[String]) -> Result<()> {
+        if !self.is_granted(|capability| capability.allows_exec(command, args)) {
+            bail!(
+                "capability for process:exec {command} {args:?} is not granted to extension {}",
+                self.extension_id
+            );
+        }
+        Ok(())
+    }
+
+    pub fn grant_fetch(&self, url: &str) -> Result<()> {
+        let host = url_host(url)?;
+        if !self.is_granted(|capability| capability.allows_fetch(&host)) {
+            bail!(
+                "capability for network:fetch {host} is not granted to extension {}",
+                self.extension_id
+            );
+        }
+        Ok(())
+    }
+
+    pub fn grant_download(&self, url: &str) -> Result<()> {
+        let host = url_host(url)?;
+        if !self.is_granted(|capability| capability.allows_download(&host)) {
+            bail!(
+                "capability for download:file {host} is not granted to extension {}",
+                self.extension_id
+            );
+        }
+        Ok(())
+    }
+
+    pub fn grant_npm_install(&self, package: &str) -> Result<()> {
+        if !self.is_granted(|capability| capability.allows_npm_install(package)) {
+            bail!(
+                "capability for npm:install {package} is not granted to extension {}",
+                self.extension_id
+            );
+        }
+        Ok(())
+    }
+
+    pub fn grant_fs_read(&self, scope: FsScope) -> Result<()> {
+        if !self.is_fs_read_granted(scope) {
+            bail!(
+                "capability for fs:read {scope:?} is not granted to extension {}",
+                self.extension_id
+            );
+        }
+        Ok(())
+    }
+
+    pub fn grant_fs_write(&self, scope: FsScope) -> Result<()> {
+        if !self.is_fs_write_granted(scope) {
+            bail!(
+                "capability for fs:write {scope:?} is not granted to extension {}",
+                self.extension_id
+            );
+        }
+        Ok(())
+    }
+
+    pub fn is_fs_read_granted(&self, scope: FsScope) -> bool {
+        self.is_granted(|capability| capability.allows_fs_read(scope))
+    }
+
+    pub fn is_fs_write_granted(&self, scope: FsScope) -> bool {
+        self.is_granted(|capability| capability.allows_fs_write(scope))
+    }
+
+    /// Sends the request built by `build_request`, following up to `max_redirects`
+    /// redirects. Redirects are followed here rather than by the HTTP client, so
+    /// that every URL along the way is checked against `network:fetch`.
+    ///
+    /// `build_request` is given the URL to request and, when following a redirect,
+    /// the status code of that redirect.
+    pub async fn fetch(
+        &self,
+        http_client: &dyn HttpClient,
+        url: &str,
+        max_redirects: u32,
+        mut build_request: impl FnMut(&str, Option<StatusCode>) -> Result<Request<AsyncBody>>,
+    ) -> Result<Response<AsyncBody>> {
+        let mut url = url.to_string();
+        let mut redirect_status = None;
+        let mut redirect_count = 0;
+        loop {
+            self.grant_fetch(&url)?;
+            let response = http_client
+                .send(build_request(&url, redirect_status)?)
+                .await?;
+
+            let status = response.status();
+            if !status.is_redirection() || max_redirects == 0 {
+                return Ok(response);
+            }
+            let Some(location) = response.headers().get(LOCATION) else {
+                return Ok(response);
+            };
+            if redirect_count == max_redirects {
+                bail!("too many redirects when fetching {url}");
+            }
+
+            let location = location
+                .to_str()
+                .with_context(|| format!("invalid redirect location from {url}"))?;
+            url = Url::parse(&url)?
+                .join(location)
+                .with_context(|| format!("invalid redirect location from {url}: {location:?}"))?
+                .to_string();
+            redirect_status = Some(status);
+            redirect_count += 1;
+        }
+    }
+
+    fn is_granted(&self, allows: impl Fn(&ExtensionCapability) -> bool) -> bool {
+        self.granted.iter().any(&allows) && !self.denied.iter().any(&allows)
+    }
+}
+
+fn url_host(url: &str) -> Result<String> {
+    let url = Url::parse(url).with_context(|| format!("invalid URL {url:?}"))?;
+    url.host_str()
+        .map(|host| host.to_string())
+        .with_context(|| format!("URL {url} has no host"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_client::FakeHttpClient;
+
+    fn manifest(capabilities: Vec<ExtensionCapability>) -> ExtensionManifest {
+        ExtensionManifest {
+            id: "test".into(),
+            name: "Test".to_string(),
+            version: "1.0.0".into(),
+            schema_version: extension::SchemaVersion::ZERO,
+            description: None,
+            repository: None,
+            authors: vec![],
+            lib: Default::default(),
+            themes: vec![],
+            icon_themes: vec![],
+            languages: vec![],
+            grammars: Default::default(),
+            language_servers: Default::default(),
+            context_servers: Default::default(),
+            slash_commands: Default::default(),
+            indexed_docs_providers: Default::default(),
+            snippets: None,
+            capabilities,
+            debug_adapters: Default::default(),
+            task_providers: Default::default(),
+            agent_tools: Default::default(),
+            language_model_providers: Default::default(),
+        }
+    }
+
+    fn approve(manifest: &ExtensionManifest) -> ExtensionCapabilityOverrides {
+        ExtensionCapabilityOverrides {
+            grant: manifest.capabilities.clone(),
+            deny: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_capability_granter() {
+        let manifest = manifest(vec![
+            ExtensionCapability::NetworkFetch {
+                host: "*.github.com".to_string(),
+            },
+            ExtensionCapability::FsRead {
+                scope: FsScope::Worktree,
+            },
+        ]);
+
+        // Nothing is granted until the user approves it.
+        let granter = CapabilityGranter::new(&manifest, SemanticVersion::new(0, 6, 0), None);
+        assert!(granter.grant_fetch("https://api.github.com/repos").is_err());
+        assert!(granter.grant_fs_read(FsScope::Worktree).is_err());
+
+        let granter = CapabilityGranter::new(
+            &manifest,
+            SemanticVersion::new(0, 6, 0),
+            Some(&approve(&manifest)),
+        );
+        assert!(granter.grant_fetch("https://api.github.com/repos").is_ok());
+        assert!(granter.grant_fetch("https://example.com").is_err());
+        assert!(
+            granter
+                .grant_download("https://github.com/file.tar.gz")
+                .is_err()
+        );
+        assert!(granter.grant_fs_read(FsScope::Worktree).is_ok());
+        assert!(!granter.is_fs_write_granted(FsScope::WorkDir));
+
+        let mut overrides = approve(&manifest);
+        overrides.grant.push(ExtensionCapability::DownloadFile {
+            host: "github.com".to_string(),
+        });
+        overrides.deny.push(ExtensionCapability::NetworkFetch {
+            host: "api.github.com".to_string(),
+        });
+        let granter =
+            CapabilityGranter::new(&manifest, SemanticVersion::new(0, 6, 0), Some(&overrides));
+        assert!(granter.grant_fetch("https://api.github.com/repos").is_err());
+        assert!(granter.grant_fetch("https://github.com").is_ok());
+        assert!(
+            granter
+                .grant_download("https://github.com/file.tar.gz")
+                .is_ok()
+        );
+
+        let legacy_granter = CapabilityGranter::new(&manifest, SemanticVersion::new(0, 5, 0), None);
+        assert!(legacy_granter.grant_fetch("https://example.com").is_ok());
+        assert!(legacy_granter.is_fs_write_granted(FsScope::WorkDir));
+    }
+
+    #[test]
+    fn test_legacy_extensions_keep_their_access() {
+        let manifest = manifest(Vec::new());
+
+        let granter = CapabilityGranter::new(&manifest, SemanticVersion::new(0, 5, 0), None);
+        assert!(granter.grant_exec("gleam", &["lsp".to_string()]).is_ok());
+        assert!(granter.grant_exec("node", &[]).is_ok());
+        assert!(granter.grant_npm_install("typescript").is_ok());
+        assert!(
+            granter
+                .grant_download("https://github.com/file.tar.gz")
+                .is_ok()
+        );
+        assert!(granter.grant_fs_read(FsScope::Worktree).is_ok());
+        assert!(granter.grant_fs_write(FsScope::Worktree).is_err());
+
+        // Extensions built against the current API only get what they are granted.
+        let granter = CapabilityGranter::new(&manifest, SemanticVersion::new(0, 6, 0), None);
+        assert!(granter.grant_exec("node", &[]).is_err());
+
+        // Legacy extensions are still subject to the user's denials.
+        let overrides = ExtensionCapabilityOverrides {
+            grant: Vec::new(),
+            deny: vec![ExtensionCapability::ProcessExec {
+                command: "node".to_string(),
+                args: vec!["**".to_string()],
+            }],
+        };
+        let granter =
+            CapabilityGranter::new(&manifest, SemanticVersion::new(0, 5, 0), Some(&overrides));
+        assert!(granter.grant_exec("node", &[]).is_err());
+        assert!(granter.grant_exec("gleam", &["lsp".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn test_denied_capabilities() {
+        let manifest = manifest(vec![
+            ExtensionCapability::NpmInstall {
+                package: "typescript".to_string(),
+            },
+            ExtensionCapability::FsWrite {
+                scope: FsScope::WorkDir,
+            },
+        ]);
+
+        let granter = CapabilityGranter::new(
+            &manifest,
+            SemanticVersion::new(0, 6, 0),
+            Some(&approve(&manifest)),
+        );
+        assert!(granter.grant_npm_install("typescript").is_ok());
+        assert!(granter.grant_npm_install("prettier").is_err());
+        assert!(granter.grant_fetch("https://api.github.com").is_err());
+        assert!(granter.grant_fs_write(FsScope::WorkDir).is_ok());
+        assert!(granter.grant_fs_write(FsScope::Worktree).is_err());
+        assert!(!granter.is_fs_read_granted(FsScope::WorkDir));
+
+        let overrides = ExtensionCapabilityOverrides {
+            grant: manifest.capabilities.clone(),
+            deny: vec![
+                ExtensionCapability::NpmInstall {
+                    package: "*".to_string(),
+                },
+                ExtensionCapability::FsWrite {
+                    scope: FsScope::WorkDir,
+                },
+            ],
+        };
+        let granter =
+            CapabilityGranter::new(&manifest, SemanticVersion::new(0, 6, 0), Some(&overrides));
+        assert!(granter.grant_npm_install("typescript").is_err());
+        assert!(granter.grant_fs_write(FsScope::WorkDir).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_fetch_checks_redirects() {
+        let manifest = manifest(vec![ExtensionCapability::NetworkFetch {
+            host: "example.com".to_string(),
+        }]);
+        let granter = CapabilityGranter::new(
+            &manifest,
+            SemanticVersion::new(0, 6, 0),
+            Some(&approve(&manifest)),
+        );
+
+        let http_client = FakeHttpClient::create(|request| async move {
+            let response = match request.uri().path() {
+                "/same-host" => Response::builder().status(302).header(LOCATION, "/done"),
+                "/other-host" => Response::builder()
+                    .status(302)
+                    .header(LOCATION, "https://other.example.org/done"),
+                "/loop" => Response::builder().status(302).header(LOCATION, "/loop"),
+                _ => Response::builder().status(200),
+            };
+            Ok(response.body(AsyncBody::default()).unwrap())
+        });
+
+        fn get(url: &str, _redirect_status: Option<StatusCode>) -> Result<Request<AsyncBody>> {
+            Ok(Request::get(url).body(AsyncBody::default())?)
+        }
+
+        let response = granter
+            .fetch(
+                http_client.as_ref(),
+                "https://example.com/same-host",
+                5,
+                get,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Redirects are only followed when asked to.
+        let response = granter
+            .fetch(
+                http_client.as_ref(),
+                "https://example.com/same-host",
+                0,
+                get,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+
+        let error = granter
+            .fetch(
+                http_client.as_ref(),
+                "https://example.com/other-host",
+                5,
+                get,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("network:fetch other.example.org")
+        );
+
+        let error = granter
+            .fetch(http_client.as_ref(), "https://example.com/loop", 5, get)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("too many redirects"));
+    }
+}
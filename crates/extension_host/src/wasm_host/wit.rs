@@ -6,11 +6,12 @@ mod since_v0_2_0;
 mod since_v0_3_0;
 mod since_v0_4_0;
 mod since_v0_5_0;
+mod since_v0_6_0;
 use extension::{KeyValueStoreDelegate, WorktreeDelegate};
 use language::LanguageName;
 use lsp::LanguageServerName;
 use release_channel::ReleaseChannel;
-use since_v0_5_0 as latest;
+use since_v0_6_0 as latest;
 
 use super::{WasmState, wasm_engine};
 use anyhow::{Context as _, Result, anyhow};
@@ -24,8 +25,12 @@ use wasmtime::{
 #[cfg(test)]
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
-    CodeLabel, CodeLabelSpan, Command, ExtensionProject, Range, SlashCommand,
+    CodeLabel, CodeLabelSpan, Command, ExtensionProject, Range, SlashCommand, WorktreeTasks,
     zed::extension::context_server::ContextServerConfiguration,
+    zed::extension::dap::{
+        DebugAdapterBinary, DebugTaskDefinition, StartDebuggingRequestArgumentsRequest,
+    },
+    zed::extension::llm_provider::{LlmCompletionEvent, LlmCompletionRequest, LlmModel},
     zed::extension::lsp::{
         Completion, CompletionKind, CompletionLabelDetails, InsertTextFormat, Symbol, SymbolKind,
     },
@@ -58,11 +63,9 @@ pub fn is_supported_wasm_api_version(
 #[inline(always)]
 pub fn wasm_api_version_range(release_channel: ReleaseChannel) -> RangeInclusive<SemanticVersion> {
     // Note: The release channel can be used to stage a new version of the extension API.
-    let _ = release_channel;
-
     let max_version = match release_channel {
         ReleaseChannel::Dev | ReleaseChannel::Nightly => latest::MAX_VERSION,
-        ReleaseChannel::Stable | ReleaseChannel::Preview => latest::MAX_VERSION,
+        ReleaseChannel::Stable | ReleaseChannel::Preview => since_v0_5_0::MAX_VERSION,
     };
 
     since_v0_0_1::MIN_VERSION..=max_version
@@ -92,6 +95,7 @@ pub fn authorize_access_to_unreleased_wasm_api_version(
 }
 
 pub enum Extension {
+    V0_6_0(since_v0_6_0::Extension),
     V0_5_0(since_v0_5_0::Extension),
     V0_4_0(since_v0_4_0::Extension),
     V0_3_0(since_v0_3_0::Extension),
@@ -110,13 +114,22 @@ impl Extension {
         component: &Component,
     ) -> Result<Self> {
         // Note: The release channel can be used to stage a new version of the extension API.
-        let _ = release_channel;
-
         if version >= latest::MIN_VERSION {
+            authorize_access_to_unreleased_wasm_api_version(release_channel)?;
+
             let extension =
                 latest::Extension::instantiate_async(store, component, latest::linker())
                     .await
                     .context("failed to instantiate wasm extension")?;
+            Ok(Self::V0_6_0(extension))
+        } else if version >= since_v0_5_0::MIN_VERSION {
+            let extension = since_v0_5_0::Extension::instantiate_async(
+                store,
+                component,
+                since_v0_5_0::linker(),
+            )
+            .await
+            .context("failed to instantiate wasm extension")?;
             Ok(Self::V0_5_0(extension))
         } else if version >= since_v0_4_0::MIN_VERSION {
             let extension = since_v0_4_0::Extension::instantiate_async(
@@ -186,6 +199,7 @@ impl Extension {
 
     pub async fn call_init_extension(&self, store: &mut Store<WasmState>) -> Result<()> {
         match self {
+            Extension::V0_6_0(ext) => ext.call_init_extension(store).await,
             Extension::V0_5_0(ext) => ext.call_init_extension(store).await,
             Extension::V0_4_0(ext) => ext.call_init_extension(store).await,
             Extension::V0_3_0(ext) => ext.call_init_extension(store).await,
@@ -205,6 +219,10 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Command, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_server_command(store, &language_server_id.0, resource)
+                    .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_language_server_command(store, &language_server_id.0, resource)
                     .await
@@ -263,6 +281,14 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_server_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_language_server_initialization_options(
                     store,
@@ -344,6 +370,14 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_server_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_language_server_workspace_configuration(
                     store,
@@ -404,6 +438,15 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_server_additional_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    &target_language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_language_server_additional_initialization_options(
                     store,
@@ -439,6 +482,15 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_server_additional_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    &target_language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_language_server_additional_workspace_configuration(
                     store,
@@ -473,10 +525,19 @@ impl Extension {
         completions: Vec<latest::Completion>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V0_5_0(ext) => {
+            Extension::V0_6_0(ext) => {
                 ext.call_labels_for_completions(store, &language_server_id.0, &completions)
                     .await
             }
+            Extension::V0_5_0(ext) => Ok(ext
+                .call_labels_for_completions(store, &language_server_id.0, &completions)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V0_4_0(ext) => Ok(ext
                 .call_labels_for_completions(
                     store,
@@ -553,10 +614,19 @@ impl Extension {
         symbols: Vec<latest::Symbol>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V0_5_0(ext) => {
+            Extension::V0_6_0(ext) => {
                 ext.call_labels_for_symbols(store, &language_server_id.0, &symbols)
                     .await
             }
+            Extension::V0_5_0(ext) => Ok(ext
+                .call_labels_for_symbols(store, &language_server_id.0, &symbols)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V0_4_0(ext) => Ok(ext
                 .call_labels_for_symbols(
                     store,
@@ -633,6 +703,10 @@ impl Extension {
         arguments: &[String],
     ) -> Result<Result<Vec<SlashCommandArgumentCompletion>, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_complete_slash_command_argument(store, command, arguments)
                     .await
@@ -667,6 +741,10 @@ impl Extension {
         resource: Option<Resource<Arc<dyn WorktreeDelegate>>>,
     ) -> Result<Result<SlashCommandOutput, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_run_slash_command(store, command, arguments, resource)
                     .await
@@ -700,6 +778,10 @@ impl Extension {
         project: Resource<ExtensionProject>,
     ) -> Result<Result<Command, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_context_server_command(store, &context_server_id, project)
+                    .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_context_server_command(store, &context_server_id, project)
                     .await
@@ -732,6 +814,10 @@ impl Extension {
         project: Resource<ExtensionProject>,
     ) -> Result<Result<Option<ContextServerConfiguration>, String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_context_server_configuration(store, &context_server_id, project)
+                    .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_context_server_configuration(store, &context_server_id, project)
                     .await
@@ -754,6 +840,7 @@ impl Extension {
         provider: &str,
     ) -> Result<Result<Vec<String>, String>> {
         match self {
+            Extension::V0_6_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V0_5_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V0_4_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V0_3_0(ext) => ext.call_suggest_docs_packages(store, provider).await,
@@ -773,6 +860,10 @@ impl Extension {
         kv_store: Resource<Arc<dyn KeyValueStoreDelegate>>,
     ) -> Result<Result<(), String>> {
         match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_index_docs(store, provider, package_name, kv_store)
+                    .await
+            }
             Extension::V0_5_0(ext) => {
                 ext.call_index_docs(store, provider, package_name, kv_store)
                     .await
@@ -798,6 +889,209 @@ impl Extension {
             }
         }
     }
+
+    pub async fn call_get_dap_binary(
+        &self,
+        store: &mut Store<WasmState>,
+        adapter_name: &str,
+        config: &DebugTaskDefinition,
+        user_installed_path: Option<&str>,
+        resource: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<DebugAdapterBinary, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_get_dap_binary(store, adapter_name, config, user_installed_path, resource)
+                    .await
+            }
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => {
+                Err(anyhow!("`get_dap_binary` not available prior to v0.6.0"))
+            }
+        }
+    }
+
+    pub async fn call_dap_request_kind(
+        &self,
+        store: &mut Store<WasmState>,
+        adapter_name: &str,
+        config: &str,
+    ) -> Result<Result<StartDebuggingRequestArgumentsRequest, String>> {
+        match self {
+            Extension::V0_6_0(ext) => ext.call_dap_request_kind(store, adapter_name, config).await,
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => {
+                Err(anyhow!("`dap_request_kind` not available prior to v0.6.0"))
+            }
+        }
+    }
+
+    pub async fn call_dap_default_config(
+        &self,
+        store: &mut Store<WasmState>,
+        adapter_name: &str,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V0_6_0(ext) => ext.call_dap_default_config(store, adapter_name).await,
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => Err(anyhow!(
+                "`dap_default_config` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_worktree_tasks(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        resource: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<WorktreeTasks, String>> {
+        match self {
+            Extension::V0_6_0(ext) => ext.call_worktree_tasks(store, provider_id, resource).await,
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => {
+                Err(anyhow!("`worktree_tasks` not available prior to v0.6.0"))
+            }
+        }
+    }
+
+    pub async fn call_run_agent_tool(
+        &self,
+        store: &mut Store<WasmState>,
+        tool_name: &str,
+        input: &str,
+        resource: Option<Resource<Arc<dyn WorktreeDelegate>>>,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_run_agent_tool(store, tool_name, input, resource)
+                    .await
+            }
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => {
+                Err(anyhow!("`run_agent_tool` not available prior to v0.6.0"))
+            }
+        }
+    }
+
+    pub async fn call_llm_provider_models(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        api_key: Option<&str>,
+    ) -> Result<Result<Vec<LlmModel>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_llm_provider_models(store, provider_id, api_key)
+                    .await
+            }
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => Err(anyhow!(
+                "`llm_provider_models` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_llm_stream_completion(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        model_id: &str,
+        request: &LlmCompletionRequest,
+        api_key: Option<&str>,
+    ) -> Result<Result<u64, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_llm_stream_completion(store, provider_id, model_id, request, api_key)
+                    .await
+            }
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => Err(anyhow!(
+                "`llm_stream_completion` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_llm_next_completion_events(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<Result<Option<Vec<LlmCompletionEvent>>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => ext.call_llm_next_completion_events(store, stream_id).await,
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => Err(anyhow!(
+                "`llm_next_completion_events` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_llm_drop_completion_stream(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<()> {
+        match self {
+            Extension::V0_6_0(ext) => ext.call_llm_drop_completion_stream(store, stream_id).await,
+            Extension::V0_0_1(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_5_0(_) => Err(anyhow!(
+                "`llm_drop_completion_stream` not available prior to v0.6.0"
+            )),
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use collections::HashSet;
+use extension_host::{Event, ExtensionSettings, ExtensionStore};
+use gpui::{App, AppContext as _, Global, PromptLevel, Window};
+use settings::Settings as _;
+use workspace::Workspace;
+
+/// The state of the capability prompts, which are shared between all workspace windows.
+#[derive(Default)]
+struct CapabilityPrompts {
+    /// Whether a prompt is currently being shown.
+    prompting: bool,
+    /// The extensions whose prompt was dismissed without an answer. They are asked
+    /// again the next time a workspace window is activated.
+    dismissed: HashSet<Arc<str>>,
+}
+
+impl Global for CapabilityPrompts {}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.init_global::<CapabilityPrompts>();
+
+    cx.observe_new(|_: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+
+        // Extensions that request capabilities are usually loaded before any window is
+        // active, so their requests are kept by the extension store until a workspace
+        // window is activated.
+        cx.observe_window_activation(window, |_, window, cx| {
+            if window.is_window_active() {
+                cx.global_mut::<CapabilityPrompts>().dismissed.clear();
+                prompt_for_capabilities(window, cx);
+            }
+        })
+        .detach();
+
+        if let Some(extension_store) = ExtensionStore::try_global(cx) {
+            cx.subscribe_in(&extension_store, window, |_, _, event, window, cx| {
+                if let Event::ExtensionCapabilitiesRequested(_) = event {
+                    prompt_for_capabilities(window, cx);
+                }
+            })
+            .detach();
+        }
+    })
+    .detach();
+}
+
+/// Asks the user whether to grant the capabilities requested by the next extension
+/// that is awaiting approval, and records the answer in the extension capability
+/// overrides.
+///
+/// The extension is not loaded until the answer is recorded, at which point the
+/// extension store reloads it.
+fn prompt_for_capabilities(window: &mut Window, cx: &mut App) {
+    // Every open workspace observes the requests, so only prompt in the active one.
+    if !window.is_window_active() || cx.global::<CapabilityPrompts>().prompting {
+        return;
+    }
+    let Some(extension_store) = ExtensionStore::try_global(cx) else {
+        return;
+    };
+
+    let dismissed = &cx.global::<CapabilityPrompts>().dismissed;
+    let Some(manifest) = extension_store
+        .read(cx)
+        .capability_requests()
+        .find(|manifest| !dismissed.contains(&manifest.id))
+        .cloned()
+    else {
+        return;
+    };
+
+    let capabilities = ExtensionSettings::get_global(cx)
+        .unanswered_capabilities(&manifest.id, &manifest.capabilities);
+    let message = format!("Allow the {} extension to:", manifest.name);
+    let detail = capabilities
+        .iter()
+        .map(|capability| format!("• {capability}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let answer = window.prompt(
+        PromptLevel::Info,
+        &message,
+        Some(&detail),
+        &["Allow", "Deny"],
+        cx,
+    );
+    cx.global_mut::<CapabilityPrompts>().prompting = true;
+
+    let window_handle = window.window_handle();
+    cx.spawn(async move |cx| {
+        let answer = answer.await;
+        cx.update(|cx| {
+            cx.global_mut::<CapabilityPrompts>().prompting = false;
+            match answer {
+                Ok(answer) => extension_store.update(cx, |store, cx| {
+                    store.answer_capability_request(&manifest.id, answer == 0, cx)
+                }),
+                Err(_) => {
+                    cx.global_mut::<CapabilityPrompts>()
+                        .dismissed
+                        .insert(manifest.id.clone());
+                }
+            }
+        })
+        .ok();
+        cx.update_window(window_handle, |_, window, cx| {
+            prompt_for_capabilities(window, cx)
+        })
+        .ok();
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use extension::ExtensionHostProxy;
+    use extension_host::RELOAD_DEBOUNCE_DURATION;
+    use gpui::{SemanticVersion, TestAppContext};
+    use node_runtime::NodeRuntime;
+    use project::Project;
+    use serde_json::json;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_capability_request_unanswered_at_startup_is_prompted_later(
+        cx: &mut TestAppContext,
+    ) {
+        let app_state = cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            release_channel::init(SemanticVersion::default(), cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            extension::init(cx);
+            init(cx);
+            app_state
+        });
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                paths::extensions_dir().join("installed"),
+                json!({
+                    "test-extension": {
+                        "extension.toml": r#"
+                            id = "test-extension"
+                            name = "Test Extension"
+                            version = "0.1.0"
+                            schema_version = 1
+
+                            [lib]
+                            kind = "Rust"
+                            version = "0.6.0"
+
+                            [[capabilities]]
+                            kind = "network:fetch"
+                            host = "api.github.com"
+                        "#,
+                    }
+                }),
+            )
+            .await;
+
+        // The installed extensions are loaded on startup, before any window is active.
+        cx.update(|cx| {
+            extension_host::init(
+                ExtensionHostProxy::global(cx),
+                app_state.fs.clone(),
+                app_state.client.clone(),
+                NodeRuntime::unavailable(),
+                cx,
+            )
+        });
+        cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+        cx.run_until_parked();
+
+        let extension_store = cx.update(|cx| ExtensionStore::global(cx));
+        extension_store.read_with(cx, |store, _| {
+            assert!(store.capability_request("test-extension").is_some());
+        });
+        assert!(!cx.has_pending_prompt());
+
+        // The request is kept until a workspace window is activated.
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (_, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        assert!(!cx.has_pending_prompt());
+
+        cx.update(|window, _| window.activate_window());
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+
+        cx.simulate_prompt_answer("Allow");
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        extension_store.read_with(cx, |store, _| {
+            assert!(store.capability_request("test-extension").is_none());
+        });
+    }
+}
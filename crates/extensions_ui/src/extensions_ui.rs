@@ -1,4 +1,5 @@
 mod components;
+mod extension_capabilities_prompt;
 mod extension_suggest;
 mod extension_version_selector;
 
@@ -9,7 +10,7 @@ use std::{ops::Range, sync::Arc};
 use client::{ExtensionMetadata, ExtensionProvides};
 use collections::{BTreeMap, BTreeSet};
 use editor::{Editor, EditorElement, EditorStyle};
-use extension_host::{ExtensionManifest, ExtensionOperation, ExtensionStore};
+use extension_host::{ExtensionManifest, ExtensionOperation, ExtensionSettings, ExtensionStore};
 use fuzzy::{StringMatchCandidate, match_strings};
 use gpui::{
     Action, App, ClipboardItem, Context, Entity, EventEmitter, Flatten, Focusable,
@@ -41,6 +42,8 @@ use crate::extension_version_selector::{
 actions!(zed, [InstallDevExtension]);
 
 pub fn init(cx: &mut App) {
+    extension_capabilities_prompt::init(cx);
+
     cx.observe_new(move |workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
@@ -64,6 +67,12 @@ pub fn init(cx: &mut App) {
                             ExtensionProvides::IndexedDocsProviders
                         }
                         ExtensionCategoryFilter::Snippets => ExtensionProvides::Snippets,
+                        ExtensionCategoryFilter::DebugAdapters => ExtensionProvides::DebugAdapters,
+                        ExtensionCategoryFilter::TaskProviders => ExtensionProvides::TaskProviders,
+                        ExtensionCategoryFilter::AgentTools => ExtensionProvides::AgentTools,
+                        ExtensionCategoryFilter::LanguageModelProviders => {
+                            ExtensionProvides::LanguageModelProviders
+                        }
                     });
 
                     let existing = workspace
@@ -169,6 +178,10 @@ fn extension_provides_label(provides: ExtensionProvides) -> &'static str {
         ExtensionProvides::SlashCommands => "Slash Commands",
         ExtensionProvides::IndexedDocsProviders => "Indexed Docs Providers",
         ExtensionProvides::Snippets => "Snippets",
+        ExtensionProvides::DebugAdapters => "Debug Adapters",
+        ExtensionProvides::TaskProviders => "Task Providers",
+        ExtensionProvides::AgentTools => "Agent Tools",
+        ExtensionProvides::LanguageModelProviders => "Language Model Providers",
     }
 }
 
@@ -390,6 +403,52 @@ impl ExtensionsPage {
             .any(|dev_extension| dev_extension.id.as_ref() == extension_id)
     }
 
+    /// Renders a button that grants the capabilities requested by the given extension,
+    /// if it isn't loaded because it is awaiting the user's approval.
+    fn render_capability_request(
+        extension_id: &Arc<str>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let manifest = ExtensionStore::global(cx)
+            .read(cx)
+            .capability_request(extension_id)?
+            .clone();
+        let capabilities = ExtensionSettings::get_global(cx)
+            .unanswered_capabilities(&manifest.id, &manifest.capabilities)
+            .iter()
+            .map(|capability| format!("• {capability}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Some(
+            h_flex()
+                .gap_2()
+                .child(
+                    Label::new("Needs Approval")
+                        .size(LabelSize::Small)
+                        .color(Color::Warning),
+                )
+                .child(
+                    Button::new(
+                        SharedString::from(format!("grant-capabilities-{extension_id}")),
+                        "Grant Capabilities",
+                    )
+                    .color(Color::Accent)
+                    .tooltip(Tooltip::text(format!(
+                        "Allow this extension to:\n{capabilities}"
+                    )))
+                    .on_click({
+                        let extension_id = extension_id.clone();
+                        move |_, _, cx| {
+                            ExtensionStore::global(cx).update(cx, |store, cx| {
+                                store.answer_capability_request(&extension_id, true, cx)
+                            });
+                        }
+                    }),
+                ),
+        )
+    }
+
     fn extension_status(extension_id: &str, cx: &mut Context<Self>) -> ExtensionStatus {
         let extension_store = ExtensionStore::global(cx).read(cx);
 
@@ -548,6 +607,7 @@ impl ExtensionsPage {
                         h_flex()
                             .gap_2()
                             .justify_between()
+                            .children(Self::render_capability_request(&extension.id, cx))
                             .child(
                                 Button::new(
                                     SharedString::from(format!("rebuild-{}", extension.id)),
@@ -731,6 +791,7 @@ impl ExtensionsPage {
                         h_flex()
                             .gap_2()
                             .justify_between()
+                            .children(Self::render_capability_request(&extension.id, cx))
                             .children(buttons.upgrade)
                             .children(buttons.configure)
                             .child(buttons.install_or_uninstall),
@@ -6,7 +6,7 @@ use git::{
     blame::Blame,
     repository::{
         AskPassDelegate, Branch, CommitDetails, CommitOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, Worktree,
     },
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
@@ -34,6 +34,7 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    pub worktrees: Vec<Worktree>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
 }
@@ -48,6 +49,7 @@ impl FakeGitRepositoryState {
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            worktrees: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
         }
@@ -344,6 +346,75 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn worktrees(&self) -> BoxFuture<Result<Vec<Worktree>>> {
+        let main_worktree_path = self.dot_git_path.parent().map(PathBuf::from);
+        self.with_state_async(false, move |state| {
+            let main_worktree = Worktree {
+                path: main_worktree_path.context("no main worktree")?,
+                sha: state.refs.get("HEAD").map(|sha| sha.clone().into()),
+                ref_name: state
+                    .current_branch_name
+                    .as_ref()
+                    .map(|name| format!("refs/heads/{name}").into()),
+                is_main: true,
+                is_locked: false,
+                is_prunable: false,
+            };
+            Ok(Some(main_worktree)
+                .into_iter()
+                .chain(state.worktrees.iter().cloned())
+                .collect())
+        })
+    }
+
+    fn create_worktree(
+        &self,
+        branch_name: String,
+        path: PathBuf,
+        create_branch: bool,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if create_branch {
+                state.branches.insert(branch_name.clone());
+            } else if !state.branches.contains(&branch_name) {
+                return Err(anyhow!("invalid reference: {branch_name}"));
+            }
+            if state.worktrees.iter().any(|worktree| {
+                worktree.path == path || worktree.branch_name() == Some(branch_name.as_str())
+            }) {
+                return Err(anyhow!("'{}' already exists", path.display()));
+            }
+            state.worktrees.push(Worktree {
+                path,
+                sha: state.refs.get("HEAD").map(|sha| sha.clone().into()),
+                ref_name: Some(format!("refs/heads/{branch_name}").into()),
+                is_main: false,
+                is_locked: false,
+                is_prunable: false,
+            });
+            Ok(())
+        })
+    }
+
+    fn remove_worktree(&self, path: PathBuf, _force: bool) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ix = state
+                .worktrees
+                .iter()
+                .position(|worktree| worktree.path == path)
+                .with_context(|| format!("'{}' is not a working tree", path.display()))?;
+            state.worktrees.remove(ix);
+            Ok(())
+        })
+    }
+
+    fn prune_worktrees(&self) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, |state| {
+            state.worktrees.retain(|worktree| !worktree.is_prunable);
+            Ok(())
+        })
+    }
+
     fn blame(&self, path: RepoPath, _content: Rope) -> BoxFuture<Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
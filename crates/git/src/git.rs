@@ -36,6 +36,9 @@ actions!(
         ToggleStaged,
         StageAndNext,
         UnstageAndNext,
+        // per-line
+        StageLines,
+        UnstageLines,
         // per-file
         StageFile,
         UnstageFile,
@@ -55,6 +58,9 @@ actions!(
         ExpandCommitEditor,
         GenerateCommitMessage,
         Init,
+        // worktrees
+        OpenWorktree,
+        PruneWorktrees,
     ]
 );
 
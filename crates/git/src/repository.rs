@@ -89,6 +89,29 @@ impl Upstream {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Worktree {
+    /// The absolute path to the worktree's working directory.
+    pub path: PathBuf,
+    /// The commit checked out in the worktree, or `None` for a bare repository.
+    pub sha: Option<SharedString>,
+    /// The branch checked out in the worktree, or `None` if its HEAD is detached.
+    pub ref_name: Option<SharedString>,
+    /// Whether this is the main worktree, rather than a linked one.
+    pub is_main: bool,
+    pub is_locked: bool,
+    /// Whether git considers the worktree stale, e.g. because its directory was deleted.
+    pub is_prunable: bool,
+}
+
+impl Worktree {
+    pub fn branch_name(&self) -> Option<&str> {
+        self.ref_name
+            .as_ref()
+            .map(|ref_name| ref_name.strip_prefix("refs/heads/").unwrap_or(ref_name))
+    }
+}
+
 #[derive(Clone, Copy, Default)]
 pub struct CommitOptions {
     pub amend: bool,
@@ -236,6 +259,23 @@ pub trait GitRepository: Send + Sync {
     fn change_branch(&self, name: String) -> BoxFuture<Result<()>>;
     fn create_branch(&self, name: String) -> BoxFuture<Result<()>>;
 
+    /// Lists the main worktree of the repository, followed by its linked worktrees.
+    fn worktrees(&self) -> BoxFuture<Result<Vec<Worktree>>>;
+
+    /// Creates a linked worktree at the given path with the given branch checked out.
+    /// If `create_branch` is set, a new branch is created from the current HEAD.
+    fn create_worktree(
+        &self,
+        branch_name: String,
+        path: PathBuf,
+        create_branch: bool,
+    ) -> BoxFuture<Result<()>>;
+
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<Result<()>>;
+
+    /// Removes the administrative files of linked worktrees whose directories no longer exist.
+    fn prune_worktrees(&self) -> BoxFuture<Result<()>>;
+
     fn reset(
         &self,
         commit: String,
@@ -928,6 +968,108 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn worktrees(&self) -> BoxFuture<Result<Vec<Worktree>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["worktree", "list", "--porcelain"])
+                    .output()
+                    .await?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list git worktrees:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+
+                parse_worktree_input(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        branch_name: String,
+        path: PathBuf,
+        create_branch: bool,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .args(["worktree", "add"]);
+                if create_branch {
+                    command.arg("-b").arg(&branch_name).arg(&path);
+                } else {
+                    command.arg(&path).arg(&branch_name);
+                }
+                let output = command.output().await?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to create git worktree:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .args(["worktree", "remove"]);
+                if force {
+                    command.arg("--force");
+                }
+                let output = command.arg(&path).output().await?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to remove git worktree:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn prune_worktrees(&self) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["worktree", "prune"])
+                    .output()
+                    .await?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to prune git worktrees:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1741,6 +1883,41 @@ fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     Ok(branches)
 }
 
+fn parse_worktree_input(input: &str) -> Result<Vec<Worktree>> {
+    let mut worktrees = Vec::new();
+    for entry in input.split("\n\n") {
+        let mut lines = entry.lines().filter(|line| !line.is_empty()).peekable();
+        if lines.peek().is_none() {
+            continue;
+        }
+        let path = lines
+            .next()
+            .and_then(|line| line.strip_prefix("worktree "))
+            .context("no worktree path")?;
+        let mut worktree = Worktree {
+            path: PathBuf::from(path),
+            sha: None,
+            ref_name: None,
+            is_main: worktrees.is_empty(),
+            is_locked: false,
+            is_prunable: false,
+        };
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "HEAD" => worktree.sha = Some(value.to_string().into()),
+                "branch" => worktree.ref_name = Some(value.to_string().into()),
+                "locked" => worktree.is_locked = true,
+                "prunable" => worktree.is_prunable = true,
+                _ => {}
+            }
+        }
+        worktrees.push(worktree);
+    }
+
+    Ok(worktrees)
+}
+
 fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     if upstream_track == "" {
         return Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
@@ -2001,6 +2178,44 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_worktrees_parsing() {
+        let input = "worktree /home/user/zed\nHEAD 060964da10574cd9bf06463a53bf6e0769c5c45e\nbranch refs/heads/main\n\nworktree /home/user/zed-review\nHEAD 1b9f4f4c3a0b7d4e1e51d3f9c4a5b3c2d1e0f9a8\ndetached\nlocked\n\nworktree /home/user/zed-feature\nHEAD 2c8e5e5d4b1c8e5f2f62e4a0d5b6c4d3e2f1a0b9\nbranch refs/heads/feature/worktrees\nprunable gitdir file points to non-existent location\n\n";
+        assert_eq!(
+            parse_worktree_input(input).unwrap(),
+            vec![
+                Worktree {
+                    path: PathBuf::from("/home/user/zed"),
+                    sha: Some("060964da10574cd9bf06463a53bf6e0769c5c45e".into()),
+                    ref_name: Some("refs/heads/main".into()),
+                    is_main: true,
+                    is_locked: false,
+                    is_prunable: false,
+                },
+                Worktree {
+                    path: PathBuf::from("/home/user/zed-review"),
+                    sha: Some("1b9f4f4c3a0b7d4e1e51d3f9c4a5b3c2d1e0f9a8".into()),
+                    ref_name: None,
+                    is_main: false,
+                    is_locked: true,
+                    is_prunable: false,
+                },
+                Worktree {
+                    path: PathBuf::from("/home/user/zed-feature"),
+                    sha: Some("2c8e5e5d4b1c8e5f2f62e4a0d5b6c4d3e2f1a0b9".into()),
+                    ref_name: Some("refs/heads/feature/worktrees".into()),
+                    is_main: false,
+                    is_locked: false,
+                    is_prunable: true,
+                },
+            ]
+        );
+        assert_eq!(
+            parse_worktree_input(input).unwrap()[2].branch_name(),
+            Some("feature/worktrees")
+        );
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<Result<()>> {
@@ -18,6 +18,7 @@ pub fn init(cx: &mut App) {
     crate::settings::init(cx);
 
     let provider_registry = GitHostingProviderRegistry::global(cx);
+    provider_registry.register_hosting_provider(Arc::new(AzureDevops::public_instance()));
     provider_registry.register_hosting_provider(Arc::new(Bitbucket::public_instance()));
     provider_registry.register_hosting_provider(Arc::new(Chromium));
     provider_registry.register_hosting_provider(Arc::new(Codeberg));
@@ -43,6 +44,8 @@ pub fn register_additional_providers(
         provider_registry.register_hosting_provider(Arc::new(gitlab_self_hosted));
     } else if let Ok(github_self_hosted) = Github::from_remote_url(&origin_url) {
         provider_registry.register_hosting_provider(Arc::new(github_self_hosted));
+    } else if let Ok(gitea_self_hosted) = Gitea::from_remote_url(&origin_url) {
+        provider_registry.register_hosting_provider(Arc::new(gitea_self_hosted));
     }
 }
 
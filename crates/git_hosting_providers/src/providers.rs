@@ -1,14 +1,18 @@
+mod azure_devops;
 mod bitbucket;
 mod chromium;
 mod codeberg;
+mod gitea;
 mod gitee;
 mod github;
 mod gitlab;
 mod sourcehut;
 
+pub use azure_devops::*;
 pub use bitbucket::*;
 pub use chromium::*;
 pub use codeberg::*;
+pub use gitea::*;
 pub use gitee::*;
 pub use github::*;
 pub use gitlab::*;
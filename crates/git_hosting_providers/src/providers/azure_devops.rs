@@ -0,0 +1,304 @@
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use url::Url;
+
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
+    PullRequest, RemoteUrl,
+};
+
+/// Matches the pull request number in the messages of commits that complete a pull request
+/// (`Merged PR 123: Title`).
+fn pull_request_number_regex() -> &'static Regex {
+    static PULL_REQUEST_NUMBER_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^Merged PR (\d+):").unwrap());
+    &PULL_REQUEST_NUMBER_REGEX
+}
+
+/// [Azure DevOps](https://dev.azure.com) and self-hosted Azure DevOps Server instances.
+///
+/// Repositories belong to a project, so the owner of a parsed remote is the path of the project,
+/// such as `organization/project`.
+#[derive(Debug)]
+pub struct AzureDevops {
+    name: String,
+    base_url: Url,
+}
+
+impl AzureDevops {
+    pub fn new(name: impl Into<String>, base_url: Url) -> Self {
+        Self {
+            name: name.into(),
+            base_url,
+        }
+    }
+
+    pub fn public_instance() -> Self {
+        Self::new("Azure DevOps", Url::parse("https://dev.azure.com").unwrap())
+    }
+
+    fn is_public_instance(&self) -> bool {
+        self.base_url.host_str() == Some("dev.azure.com")
+    }
+
+    fn repository_url(&self, remote: &ParsedGitRemote) -> Url {
+        let ParsedGitRemote { owner, repo } = remote;
+        let mut url = self.base_url();
+        url.set_path(&format!("{owner}/_git/{repo}"));
+        url.set_query(None);
+        url.set_fragment(None);
+        url
+    }
+}
+
+impl GitHostingProvider for AzureDevops {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        format!("line={line}&lineEnd={}", line + 1)
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        format!("line={start_line}&lineEnd={}", end_line + 1)
+    }
+
+    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        let url = RemoteUrl::from_str(url).ok()?;
+        let host = url.host_str()?;
+        let path_segments = url
+            .path_segments()?
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        if self.is_public_instance() {
+            match host {
+                // https://dev.azure.com/{organization}/{project}/_git/{repo}
+                "dev.azure.com" => {
+                    let [organization, project, "_git", repo] = path_segments.as_slice() else {
+                        return None;
+                    };
+                    return Some(ParsedGitRemote {
+                        owner: format!("{organization}/{project}").into(),
+                        repo: repo.trim_end_matches(".git").into(),
+                    });
+                }
+                // git@ssh.dev.azure.com:v3/{organization}/{project}/{repo}
+                // {organization}@vs-ssh.visualstudio.com:v3/{organization}/{project}/{repo}
+                "ssh.dev.azure.com" | "vs-ssh.visualstudio.com" => {
+                    let ["v3", organization, project, repo] = path_segments.as_slice() else {
+                        return None;
+                    };
+                    return Some(ParsedGitRemote {
+                        owner: format!("{organization}/{project}").into(),
+                        repo: repo.trim_end_matches(".git").into(),
+                    });
+                }
+                // https://{organization}.visualstudio.com/[DefaultCollection/]{project}/_git/{repo}
+                _ => {
+                    let organization = host.strip_suffix(".visualstudio.com")?;
+                    let path_segments = match path_segments.as_slice() {
+                        ["DefaultCollection", rest @ ..] => rest,
+                        path_segments => path_segments,
+                    };
+                    let [project, "_git", repo] = path_segments else {
+                        return None;
+                    };
+                    return Some(ParsedGitRemote {
+                        owner: format!("{organization}/{project}").into(),
+                        repo: repo.trim_end_matches(".git").into(),
+                    });
+                }
+            }
+        }
+
+        // Azure DevOps Server: https://{host}/{collection path}/{project}/_git/{repo}
+        if host != self.base_url.host_str()? {
+            return None;
+        }
+        let [owner @ .., "_git", repo] = path_segments.as_slice() else {
+            return None;
+        };
+        if owner.is_empty() {
+            return None;
+        }
+
+        Some(ParsedGitRemote {
+            owner: owner.join("/").into(),
+            repo: repo.trim_end_matches(".git").into(),
+        })
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        let BuildCommitPermalinkParams { sha } = params;
+
+        let mut permalink = self.repository_url(remote);
+        permalink.set_path(&format!("{}/commit/{sha}", permalink.path()));
+        permalink
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        // Azure DevOps selects lines through the query rather than the fragment.
+        let mut query = format!("path=/{path}&version=GC{sha}");
+        if let Some(selection) = selection {
+            query.push('&');
+            query.push_str(&self.line_fragment(&selection));
+            query.push_str("&lineStartColumn=1&lineEndColumn=1&lineStyle=plain");
+        }
+        query.push_str("&_a=contents");
+
+        let mut permalink = self.repository_url(&remote);
+        permalink.set_query(Some(&query));
+        permalink
+    }
+
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let line = message.lines().next()?;
+        let capture = pull_request_number_regex().captures(line)?;
+        let number = capture.get(1)?.as_str().parse::<u32>().ok()?;
+
+        let mut url = self.repository_url(remote);
+        url.set_path(&format!("{}/pullrequest/{number}", url.path()));
+
+        Some(PullRequest { number, url })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_url() {
+        for remote_url in [
+            "https://dev.azure.com/zed-industries/zed/_git/zed",
+            "https://zed-industries@dev.azure.com/zed-industries/zed/_git/zed",
+            "git@ssh.dev.azure.com:v3/zed-industries/zed/zed",
+            "https://zed-industries.visualstudio.com/zed/_git/zed",
+            "https://zed-industries.visualstudio.com/DefaultCollection/zed/_git/zed",
+            "zed-industries@vs-ssh.visualstudio.com:v3/zed-industries/zed/zed",
+        ] {
+            assert_eq!(
+                AzureDevops::public_instance().parse_remote_url(remote_url),
+                Some(ParsedGitRemote {
+                    owner: "zed-industries/zed".into(),
+                    repo: "zed".into(),
+                }),
+                "failed to parse {remote_url:?}"
+            );
+        }
+
+        assert!(
+            AzureDevops::public_instance()
+                .parse_remote_url("https://github.com/zed-industries/zed.git")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_remote_url_given_server_url() {
+        let provider = AzureDevops::new(
+            "BigCorp Azure DevOps",
+            Url::parse("https://tfs.big-corp.com").unwrap(),
+        );
+
+        let parsed_remote = provider
+            .parse_remote_url("https://tfs.big-corp.com/tfs/DefaultCollection/zed/_git/zed")
+            .unwrap();
+        assert_eq!(
+            parsed_remote,
+            ParsedGitRemote {
+                owner: "tfs/DefaultCollection/zed".into(),
+                repo: "zed".into(),
+            }
+        );
+
+        let permalink = provider.build_commit_permalink(
+            &parsed_remote,
+            BuildCommitPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+            },
+        );
+        assert_eq!(
+            permalink.as_str(),
+            "https://tfs.big-corp.com/tfs/DefaultCollection/zed/_git/zed/commit/faa6f979be417239b2e070dbbf6392b909224e0b"
+        );
+    }
+
+    #[test]
+    fn test_build_azure_devops_permalink() {
+        let remote = || ParsedGitRemote {
+            owner: "zed-industries/zed".into(),
+            repo: "zed".into(),
+        };
+
+        let permalink = AzureDevops::public_instance().build_permalink(
+            remote(),
+            BuildPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                path: "crates/editor/src/git/permalink.rs",
+                selection: None,
+            },
+        );
+        let expected_url = "https://dev.azure.com/zed-industries/zed/_git/zed?path=/crates/editor/src/git/permalink.rs&version=GCfaa6f979be417239b2e070dbbf6392b909224e0b&_a=contents";
+        assert_eq!(permalink.to_string(), expected_url.to_string());
+
+        let permalink = AzureDevops::public_instance().build_permalink(
+            remote(),
+            BuildPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                path: "crates/editor/src/git/permalink.rs",
+                selection: Some(23..47),
+            },
+        );
+        let expected_url = "https://dev.azure.com/zed-industries/zed/_git/zed?path=/crates/editor/src/git/permalink.rs&version=GCfaa6f979be417239b2e070dbbf6392b909224e0b&line=24&lineEnd=49&lineStartColumn=1&lineEndColumn=1&lineStyle=plain&_a=contents";
+        assert_eq!(permalink.to_string(), expected_url.to_string());
+    }
+
+    #[test]
+    fn test_azure_devops_pull_requests() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries/zed".into(),
+            repo: "zed".into(),
+        };
+        let azure_devops = AzureDevops::public_instance();
+
+        let message = "This does not contain a pull request (#123)";
+        assert!(
+            azure_devops
+                .extract_pull_request(&remote, message)
+                .is_none()
+        );
+
+        let message = "Merged PR 1234: Fix the build\n\nRelated work items: #42";
+        let pull_request = azure_devops.extract_pull_request(&remote, message).unwrap();
+        assert_eq!(pull_request.number, 1234);
+        assert_eq!(
+            pull_request.url.as_str(),
+            "https://dev.azure.com/zed-industries/zed/_git/zed/pullrequest/1234"
+        );
+    }
+}
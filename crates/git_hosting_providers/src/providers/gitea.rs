@@ -0,0 +1,319 @@
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use anyhow::{Result, bail};
+use regex::Regex;
+use url::Url;
+
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
+    PullRequest, RemoteUrl,
+};
+
+use crate::get_host_from_git_remote_url;
+
+/// Matches the pull request number in squash merge messages (`Title (#123)`) as well as in merge
+/// commit messages (`Merge pull request 'Title' (#123) from branch into main`).
+fn pull_request_number_regex() -> &'static Regex {
+    static PULL_REQUEST_NUMBER_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\(#(\d+)\)(?: from \S+ into \S+)?$").unwrap());
+    &PULL_REQUEST_NUMBER_REGEX
+}
+
+/// A self-hosted [Gitea](https://about.gitea.com) or [Forgejo](https://forgejo.org) instance.
+#[derive(Debug)]
+pub struct Gitea {
+    name: String,
+    base_url: Url,
+}
+
+impl Gitea {
+    pub fn new(name: impl Into<String>, mut base_url: Url) -> Self {
+        // Keep the subpath of the instance when joining paths onto the base URL.
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+
+        Self {
+            name: name.into(),
+            base_url,
+        }
+    }
+
+    pub fn from_remote_url(remote_url: &str) -> Result<Self> {
+        let host = get_host_from_git_remote_url(remote_url)?;
+
+        // Like for self-hosted GitHub and GitLab instances, this only detects instances that
+        // have the name of the forge in their host. Others can be configured in the settings.
+        let name = if host.contains("forgejo") {
+            "Forgejo"
+        } else if host.contains("gitea") {
+            "Gitea"
+        } else {
+            bail!("not a Gitea or Forgejo URL");
+        };
+
+        Ok(Self::new(name, Url::parse(&format!("https://{}", host))?))
+    }
+}
+
+impl GitHostingProvider for Gitea {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn base_url(&self) -> Url {
+        self.base_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        format!("L{line}")
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        format!("L{start_line}-L{end_line}")
+    }
+
+    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        let url = RemoteUrl::from_str(url).ok()?;
+
+        let host = url.host_str()?;
+        if host != self.base_url.host_str()? {
+            return None;
+        }
+
+        // Instances can be served from a subpath, such as `https://example.com/gitea`.
+        let mut path_segments = url.path_segments()?.skip(
+            self.base_url
+                .path_segments()?
+                .filter(|s| !s.is_empty())
+                .count(),
+        );
+        let owner = path_segments.next()?;
+        let repo = path_segments.next()?.trim_end_matches(".git");
+
+        Some(ParsedGitRemote {
+            owner: owner.into(),
+            repo: repo.into(),
+        })
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        let BuildCommitPermalinkParams { sha } = params;
+        let ParsedGitRemote { owner, repo } = remote;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/commit/{sha}"))
+            .unwrap()
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut permalink = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/src/commit/{sha}/{path}"))
+            .unwrap();
+        if path.ends_with(".md") {
+            permalink.set_query(Some("display=source"));
+        }
+        permalink.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        permalink
+    }
+
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let line = message.lines().next()?;
+        let capture = pull_request_number_regex().captures(line)?;
+        let number = capture.get(1)?.as_str().parse::<u32>().ok()?;
+
+        let url = self
+            .base_url()
+            .join(&format!(
+                "{}/{}/pulls/{}",
+                remote.owner, remote.repo, number
+            ))
+            .ok()?;
+
+        Some(PullRequest { number, url })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn gitea() -> Gitea {
+        Gitea::new("Gitea", Url::parse("https://gitea.example.com").unwrap())
+    }
+
+    #[test]
+    fn test_from_remote_url() {
+        let gitea = Gitea::from_remote_url("git@gitea.example.com:zed-industries/zed.git").unwrap();
+        assert_eq!(gitea.name(), "Gitea");
+        assert_eq!(gitea.base_url().as_str(), "https://gitea.example.com/");
+
+        let forgejo =
+            Gitea::from_remote_url("https://forgejo.example.com/zed-industries/zed.git").unwrap();
+        assert_eq!(forgejo.name(), "Forgejo");
+
+        assert!(Gitea::from_remote_url("https://example.com/zed-industries/zed.git").is_err());
+    }
+
+    #[test]
+    fn test_parse_remote_url_given_ssh_url() {
+        let parsed_remote = gitea()
+            .parse_remote_url("git@gitea.example.com:zed-industries/zed.git")
+            .unwrap();
+
+        assert_eq!(
+            parsed_remote,
+            ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_remote_url_given_https_url() {
+        let parsed_remote = gitea()
+            .parse_remote_url("https://gitea.example.com/zed-industries/zed.git")
+            .unwrap();
+
+        assert_eq!(
+            parsed_remote,
+            ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            }
+        );
+
+        assert!(
+            gitea()
+                .parse_remote_url("https://github.com/zed-industries/zed.git")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_remote_url_given_subpath_instance() {
+        let gitea = Gitea::new("Gitea", Url::parse("https://example.com/gitea/").unwrap());
+        let parsed_remote = gitea
+            .parse_remote_url("https://example.com/gitea/zed-industries/zed.git")
+            .unwrap();
+
+        assert_eq!(
+            parsed_remote,
+            ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            }
+        );
+
+        let permalink = gitea.build_commit_permalink(
+            &parsed_remote,
+            BuildCommitPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+            },
+        );
+        assert_eq!(
+            permalink.as_str(),
+            "https://example.com/gitea/zed-industries/zed/commit/faa6f979be417239b2e070dbbf6392b909224e0b"
+        );
+    }
+
+    #[test]
+    fn test_build_gitea_permalink() {
+        let permalink = gitea().build_permalink(
+            ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+            BuildPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                path: "crates/editor/src/git/permalink.rs",
+                selection: Some(23..47),
+            },
+        );
+
+        let expected_url = "https://gitea.example.com/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L24-L48";
+        assert_eq!(permalink.to_string(), expected_url.to_string())
+    }
+
+    #[test]
+    fn test_build_gitea_permalink_with_markdown_file() {
+        let permalink = gitea().build_permalink(
+            ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+            BuildPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                path: "README.md",
+                selection: Some(6..6),
+            },
+        );
+
+        let expected_url = "https://gitea.example.com/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/README.md?display=source#L7";
+        assert_eq!(permalink.to_string(), expected_url.to_string())
+    }
+
+    #[test]
+    fn test_gitea_pull_requests() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let message = "This does not contain a pull request";
+        assert!(gitea().extract_pull_request(&remote, message).is_none());
+
+        // Squash merge
+        let message = indoc! {r#"
+            project panel: do not expand collapsed worktrees (#10687)
+
+            Fixes #10597
+        "#};
+        assert_eq!(
+            gitea()
+                .extract_pull_request(&remote, message)
+                .unwrap()
+                .url
+                .as_str(),
+            "https://gitea.example.com/zed-industries/zed/pulls/10687"
+        );
+
+        // Merge commit
+        let message = "Merge pull request 'Fix the build' (#42) from fix-build into main";
+        let pull_request = gitea().extract_pull_request(&remote, message).unwrap();
+        assert_eq!(pull_request.number, 42);
+        assert_eq!(
+            pull_request.url.as_str(),
+            "https://gitea.example.com/zed-industries/zed/pulls/42"
+        );
+
+        // Pull request number in the middle of the line
+        let message = "Revert (#42) and more";
+        assert!(gitea().extract_pull_request(&remote, message).is_none());
+    }
+}
@@ -9,7 +9,7 @@ use settings::{Settings, SettingsStore};
 use url::Url;
 use util::ResultExt as _;
 
-use crate::{Bitbucket, Github, Gitlab};
+use crate::{AzureDevops, Bitbucket, Gitea, Github, Gitlab};
 
 pub(crate) fn init(cx: &mut App) {
     GitHostingProviderSettings::register(cx);
@@ -37,6 +37,10 @@ fn update_git_hosting_providers_from_settings(cx: &mut App) {
             GitHostingProviderKind::Bitbucket => Arc::new(Bitbucket::new(&provider.name, url)) as _,
             GitHostingProviderKind::Github => Arc::new(Github::new(&provider.name, url)) as _,
             GitHostingProviderKind::Gitlab => Arc::new(Gitlab::new(&provider.name, url)) as _,
+            GitHostingProviderKind::Gitea => Arc::new(Gitea::new(&provider.name, url)) as _,
+            GitHostingProviderKind::AzureDevops => {
+                Arc::new(AzureDevops::new(&provider.name, url)) as _
+            }
         };
 
         provider_registry.register_hosting_provider(provider);
@@ -49,6 +53,10 @@ pub enum GitHostingProviderKind {
     Github,
     Gitlab,
     Bitbucket,
+    /// Gitea or Forgejo.
+    #[serde(alias = "forgejo")]
+    Gitea,
+    AzureDevops,
 }
 
 /// A custom Git hosting provider.
@@ -56,7 +64,7 @@ pub enum GitHostingProviderKind {
 pub struct GitHostingProviderConfig {
     /// The type of the provider.
     ///
-    /// Must be one of `github`, `gitlab`, or `bitbucket`.
+    /// Must be one of `github`, `gitlab`, `bitbucket`, `gitea` (or `forgejo`), or `azure_devops`.
     pub provider: GitHostingProviderKind,
 
     /// The base URL for the provider (e.g., "https://code.corp.big.com").
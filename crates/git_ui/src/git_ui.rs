@@ -29,6 +29,7 @@ pub mod picker_prompt;
 pub mod project_diff;
 pub(crate) mod remote_output;
 pub mod repository_selector;
+pub mod worktree_picker;
 
 actions!(git, [ResetOnboarding]);
 
@@ -54,6 +55,7 @@ pub fn init(cx: &mut App) {
             return;
         }
         if !project.is_via_collab() {
+            worktree_picker::register(workspace);
             workspace.register_action(|workspace, _: &git::Fetch, window, cx| {
                 let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                     return;
@@ -14,7 +14,8 @@ use editor::{
 };
 use futures::StreamExt;
 use git::{
-    Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext,
+    Commit, StageAll, StageAndNext, StageLines, ToggleStaged, UnstageAll, UnstageAndNext,
+    UnstageLines,
     repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::FileStatus,
 };
@@ -914,6 +915,30 @@ impl Render for ProjectDiffToolbar {
                                     this.dispatch_action(&ToggleStaged, window, cx)
                                 })),
                         )
+                        .child(
+                            Button::new("stage-lines", "Stage Lines")
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Stage selected lines",
+                                    &StageLines,
+                                    &focus_handle,
+                                ))
+                                .disabled(!button_states.stage)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.dispatch_action(&StageLines, window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new("unstage-lines", "Unstage Lines")
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Unstage selected lines",
+                                    &UnstageLines,
+                                    &focus_handle,
+                                ))
+                                .disabled(!button_states.unstage)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.dispatch_action(&UnstageLines, window, cx)
+                                })),
+                        )
                     })
                     .when(!button_states.selection, |el| {
                         el.child(
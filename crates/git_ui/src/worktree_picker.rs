@@ -0,0 +1,680 @@
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use fuzzy::StringMatchCandidate;
+use git::{
+    SHORT_SHA_LENGTH,
+    repository::{Branch, Worktree},
+};
+use gpui::{
+    Action, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::{path::PathBuf, sync::Arc};
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, OpenOptions, Workspace};
+
+const OPEN_DISABLED_TOOLTIP: &str = "Worktrees can only be opened by the project's host";
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+    workspace.register_action(prune_worktrees);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::OpenWorktree,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    let repository = project.active_repository(cx).clone();
+    // Collaborators can't open paths on the host's machine, so they can only browse worktrees.
+    let can_open = !project.is_via_collab();
+    let workspace_handle = cx.weak_entity();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        WorktreeList::new(workspace_handle, repository, can_open, window, cx)
+    })
+}
+
+fn prune_worktrees(
+    workspace: &mut Workspace,
+    _: &git::PruneWorktrees,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let prune = repository.update(cx, |repository, _| repository.prune_worktrees());
+    cx.spawn(async move |_, _| prune.await?)
+        .detach_and_prompt_err("Failed to prune worktrees", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+}
+
+/// Opens a git worktree as a worktree of the current project, or as a project in a new window.
+fn open_worktree(
+    workspace: &mut Workspace,
+    path: PathBuf,
+    new_window: bool,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    if !new_window {
+        let add_worktree = project.update(cx, |project, cx| {
+            project.find_or_create_worktree(&path, true, cx)
+        });
+        return cx.spawn(async move |_, _| {
+            add_worktree.await?;
+            Ok(())
+        });
+    }
+
+    let app_state = workspace.app_state().clone();
+    if let Some(connection_options) = project.read(cx).ssh_connection_options(cx) {
+        cx.spawn(async move |_, cx| {
+            recent_projects::open_ssh_project(
+                connection_options,
+                vec![path],
+                app_state,
+                OpenOptions::default(),
+                cx,
+            )
+            .await
+        })
+    } else {
+        let open_paths = workspace::open_paths(
+            &[path],
+            app_state,
+            OpenOptions {
+                open_new_workspace: Some(true),
+                ..Default::default()
+            },
+            cx,
+        );
+        cx.spawn(async move |_, _| {
+            open_paths.await?;
+            Ok(())
+        })
+    }
+}
+
+pub struct WorktreeList {
+    pub picker: Entity<Picker<WorktreeListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl WorktreeList {
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        repository: Option<Entity<Repository>>,
+        can_open: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = WorktreeListDelegate::new(workspace, repository, can_open);
+        let picker = cx.new(|cx| {
+            let picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.reload(window, cx);
+            picker
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for WorktreeList {}
+impl EventEmitter<DismissEvent> for WorktreeList {}
+
+impl Focusable for WorktreeList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WorktreeList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out({
+                cx.listener(move |this, _, window, cx| {
+                    this.picker.update(cx, |this, cx| {
+                        this.cancel(&Default::default(), window, cx);
+                    })
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum WorktreeEntryKind {
+    Worktree(Worktree),
+    Branch(Branch),
+    NewBranch(SharedString),
+}
+
+impl WorktreeEntryKind {
+    fn label(&self) -> SharedString {
+        match self {
+            WorktreeEntryKind::Worktree(worktree) => match worktree.branch_name() {
+                Some(branch_name) => branch_name.to_string().into(),
+                None => {
+                    let sha = worktree.sha.as_deref().unwrap_or_default();
+                    let short_sha = sha.get(..SHORT_SHA_LENGTH).unwrap_or(sha);
+                    format!("detached at {short_sha}").into()
+                }
+            },
+            WorktreeEntryKind::Branch(branch) => branch.name().to_string().into(),
+            WorktreeEntryKind::NewBranch(name) => name.clone(),
+        }
+    }
+
+    fn branch_name(&self) -> Option<&str> {
+        match self {
+            WorktreeEntryKind::Worktree(worktree) => worktree.branch_name(),
+            WorktreeEntryKind::Branch(branch) => Some(branch.name()),
+            WorktreeEntryKind::NewBranch(name) => Some(name.as_ref()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WorktreeEntry {
+    kind: WorktreeEntryKind,
+    positions: Vec<usize>,
+}
+
+pub struct WorktreeListDelegate {
+    workspace: WeakEntity<Workspace>,
+    repo: Option<Entity<Repository>>,
+    worktrees: Option<Vec<Worktree>>,
+    branches: Vec<Branch>,
+    matches: Vec<WorktreeEntry>,
+    selected_index: usize,
+    can_open: bool,
+}
+
+impl WorktreeListDelegate {
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        repo: Option<Entity<Repository>>,
+        can_open: bool,
+    ) -> Self {
+        Self {
+            workspace,
+            repo,
+            can_open,
+            worktrees: None,
+            branches: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn reload(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let (worktrees, branches) = repo.update(cx, |repo, _| (repo.worktrees(), repo.branches()));
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let worktrees = worktrees.await??;
+            let mut branches = branches.await??;
+
+            // Branches can only be checked out in a single worktree, and remote branches
+            // that are tracked by a local branch are already represented by it.
+            let hidden_branches: HashSet<_> = worktrees
+                .iter()
+                .filter_map(|worktree| worktree.ref_name.clone())
+                .chain(branches.iter().filter_map(|branch| {
+                    let upstream = branch.upstream.as_ref()?;
+                    Some(upstream.ref_name.clone())
+                }))
+                .collect();
+            branches.retain(|branch| !hidden_branches.contains(&branch.ref_name));
+            branches.sort_by_key(|branch| {
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| 0 - commit.commit_timestamp)
+            });
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.worktrees = Some(worktrees);
+                picker.delegate.branches = branches;
+                picker.refresh(window, cx);
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// New worktrees are placed next to the main worktree, named after the repository and branch.
+    fn new_worktree_path(&self, branch_name: &str) -> Option<PathBuf> {
+        let main_worktree = self
+            .worktrees
+            .as_ref()?
+            .iter()
+            .find(|worktree| worktree.is_main)?;
+        let repository_name = main_worktree.path.file_name()?.to_string_lossy();
+        let directory_name = format!("{repository_name}-{}", branch_name.replace('/', "-"));
+        Some(main_worktree.path.parent()?.join(directory_name))
+    }
+
+    fn remove_worktree(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(WorktreeEntryKind::Worktree(worktree)) =
+            self.matches.get(ix).map(|entry| &entry.kind)
+        else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let remove = repo.update(cx, |repo, _| {
+            if worktree.is_prunable {
+                repo.prune_worktrees()
+            } else {
+                repo.remove_worktree(worktree.path.clone(), false)
+            }
+        });
+        self.reload_after(remove, "Failed to remove worktree", window, cx);
+    }
+
+    fn prune_worktrees(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let prune = repo.update(cx, |repo, _| repo.prune_worktrees());
+        self.reload_after(prune, "Failed to prune worktrees", window, cx);
+    }
+
+    fn reload_after(
+        &self,
+        task: futures::channel::oneshot::Receiver<Result<()>>,
+        error_message: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        cx.spawn_in(window, async move |picker, cx| {
+            task.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload(window, cx);
+            })
+        })
+        .detach_and_prompt_err(error_message, window, cx, |e, _, _| Some(e.to_string()));
+    }
+}
+
+impl PickerDelegate for WorktreeListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select worktree or branch...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(worktrees) = self.worktrees.clone() else {
+            return Task::ready(());
+        };
+        let branches = self.branches.clone();
+
+        const RECENT_BRANCHES_COUNT: usize = 10;
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<WorktreeEntry> = if query.is_empty() {
+                worktrees
+                    .into_iter()
+                    .map(WorktreeEntryKind::Worktree)
+                    .chain(
+                        branches
+                            .into_iter()
+                            .filter(|branch| !branch.is_remote())
+                            .take(RECENT_BRANCHES_COUNT)
+                            .map(WorktreeEntryKind::Branch),
+                    )
+                    .map(|kind| WorktreeEntry {
+                        kind,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let entries = worktrees
+                    .into_iter()
+                    .map(WorktreeEntryKind::Worktree)
+                    .chain(branches.into_iter().map(WorktreeEntryKind::Branch))
+                    .collect::<Vec<_>>();
+                let candidates = entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| StringMatchCandidate::new(ix, &entry.label()))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| WorktreeEntry {
+                    kind: entries[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+
+            if !query.is_empty()
+                && !matches
+                    .iter()
+                    .any(|entry| entry.kind.branch_name() == Some(query.as_str()))
+            {
+                matches.push(WorktreeEntry {
+                    kind: WorktreeEntryKind::NewBranch(query.clone().into()),
+                    positions: Vec::new(),
+                });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if !self.can_open {
+            return;
+        }
+        let Some(entry) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+
+        let (path, create_worktree) = match &entry.kind {
+            WorktreeEntryKind::Worktree(worktree) => (Some(worktree.path.clone()), None),
+            WorktreeEntryKind::Branch(branch) => {
+                // Checking out a remote branch by its short name makes git create a tracking branch.
+                let branch_name = if branch.is_remote() {
+                    branch
+                        .name()
+                        .split_once('/')
+                        .map_or(branch.name(), |(_, name)| name)
+                } else {
+                    branch.name()
+                };
+                let path = self.new_worktree_path(branch_name);
+                let create_worktree = path.clone().map(|path| {
+                    repo.update(cx, |repo, _| {
+                        repo.create_worktree(branch_name.to_string(), path, false)
+                    })
+                });
+                (path, create_worktree)
+            }
+            WorktreeEntryKind::NewBranch(branch_name) => {
+                let path = self.new_worktree_path(branch_name);
+                let create_worktree = path.clone().map(|path| {
+                    repo.update(cx, |repo, _| {
+                        repo.create_worktree(branch_name.to_string(), path, true)
+                    })
+                });
+                (path, create_worktree)
+            }
+        };
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let path = path.context("Failed to determine the worktree location")?;
+            if let Some(create_worktree) = create_worktree {
+                create_worktree.await??;
+            }
+            workspace
+                .update(cx, |workspace, cx| {
+                    open_worktree(workspace, path, !secondary, cx)
+                })?
+                .await?;
+            picker.update(cx, |_, cx| cx.emit(DismissEvent))
+        })
+        .detach_and_prompt_err("Failed to open worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+
+        let (icon, detail) = match &entry.kind {
+            WorktreeEntryKind::Worktree(worktree) => (
+                IconName::Folder,
+                worktree.path.to_string_lossy().to_string(),
+            ),
+            WorktreeEntryKind::Branch(branch) => (
+                IconName::GitBranch,
+                self.new_worktree_path(branch.name())
+                    .map(|path| format!("create worktree at {}", path.display()))
+                    .unwrap_or_default(),
+            ),
+            WorktreeEntryKind::NewBranch(_) => {
+                let message = if let Some(current_branch) = self
+                    .repo
+                    .as_ref()
+                    .and_then(|repo| repo.read(cx).branch.as_ref().map(|b| b.name()))
+                {
+                    format!("based off {}", current_branch)
+                } else {
+                    "based off the current branch".to_string()
+                };
+                (IconName::Plus, message)
+            }
+        };
+        let worktree = match &entry.kind {
+            WorktreeEntryKind::Worktree(worktree) => Some(worktree),
+            _ => None,
+        };
+        let removable_worktree =
+            worktree.filter(|worktree| !worktree.is_main && !worktree.is_locked);
+
+        Some(
+            ListItem::new(SharedString::from(format!("worktree-menu-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(icon).color(Color::Muted))
+                .child(
+                    v_flex()
+                        .w_full()
+                        .overflow_x_hidden()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(match &entry.kind {
+                                    WorktreeEntryKind::NewBranch(branch_name) => {
+                                        Label::new(format!(
+                                            "Create worktree for new branch \"{branch_name}\"…"
+                                        ))
+                                        .single_line()
+                                        .into_any_element()
+                                    }
+                                    kind => {
+                                        HighlightedLabel::new(kind.label(), entry.positions.clone())
+                                            .truncate()
+                                            .into_any_element()
+                                    }
+                                })
+                                .when_some(worktree, |el, worktree| {
+                                    el.when(worktree.is_main, |el| {
+                                        el.child(
+                                            Label::new("main")
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                                    .when(worktree.is_locked, |el| {
+                                        el.child(
+                                            Label::new("locked")
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                                    .when(
+                                        worktree.is_prunable,
+                                        |el| {
+                                            el.child(
+                                                Label::new("stale")
+                                                    .size(LabelSize::Small)
+                                                    .color(Color::Warning),
+                                            )
+                                        },
+                                    )
+                                }),
+                        )
+                        .child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .truncate()
+                                .color(Color::Muted),
+                        ),
+                )
+                .when_some(removable_worktree, |item, worktree| {
+                    let tooltip = if worktree.is_prunable {
+                        "Prune All Stale Worktrees"
+                    } else {
+                        "Remove Worktree"
+                    };
+                    item.end_hover_slot(
+                        IconButton::new(("remove-worktree", ix), IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text(tooltip))
+                            .on_click(cx.listener(move |picker, _, window, cx| {
+                                cx.stop_propagation();
+                                picker.delegate.remove_worktree(ix, window, cx);
+                            })),
+                    )
+                }),
+        )
+    }
+
+    fn render_footer(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        let has_stale_worktrees = self
+            .worktrees
+            .as_ref()
+            .is_some_and(|worktrees| worktrees.iter().any(|worktree| worktree.is_prunable));
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_2()
+                .gap_2()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("prune", "Prune All Stale")
+                        .label_size(LabelSize::Small)
+                        .disabled(!has_stale_worktrees)
+                        .on_click(cx.listener(|picker, _, window, cx| {
+                            picker.delegate.prune_worktrees(window, cx);
+                        })),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("add-to-project", "Add to Project")
+                                .label_size(LabelSize::Small)
+                                .disabled(!self.can_open)
+                                .when(!self.can_open, |button| {
+                                    button.tooltip(Tooltip::text(OPEN_DISABLED_TOOLTIP))
+                                })
+                                .when_some(
+                                    KeyBinding::for_action(&menu::SecondaryConfirm, window, cx),
+                                    |button, keybind| button.key_binding(keybind),
+                                )
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                                }),
+                        )
+                        .child(
+                            Button::new("open-in-new-window", "Open in New Window")
+                                .label_size(LabelSize::Small)
+                                .disabled(!self.can_open)
+                                .when(!self.can_open, |button| {
+                                    button.tooltip(Tooltip::text(OPEN_DISABLED_TOOLTIP))
+                                })
+                                .when_some(
+                                    KeyBinding::for_action(&menu::Confirm, window, cx),
+                                    |button, keybind| button.key_binding(keybind),
+                                )
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                                }),
+                        ),
+                )
+                .into_any(),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        None
+    }
+}
@@ -8,7 +8,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use crate::RequestUsage;
+use crate::{RequestUsage, usage_ledger};
 
 #[derive(Clone)]
 pub struct RateLimiter {
@@ -45,6 +45,7 @@ impl RateLimiter {
         let guard = self.semaphore.acquire_arc();
         async move {
             let guard = guard.await;
+            usage_ledger::check_budget()?;
             let result = future.await?;
             drop(guard);
             Ok(result)
@@ -62,6 +63,7 @@ impl RateLimiter {
         let guard = self.semaphore.acquire_arc();
         async move {
             let guard = guard.await;
+            usage_ledger::check_budget()?;
             let inner = future.await?;
             Ok(RateLimitGuard {
                 inner,
@@ -87,6 +89,7 @@ impl RateLimiter {
         let guard = self.semaphore.acquire_arc();
         async move {
             let guard = guard.await;
+            usage_ledger::check_budget()?;
             let (inner, usage) = future.await?;
             Ok((
                 RateLimitGuard {
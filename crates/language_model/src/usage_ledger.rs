@@ -0,0 +1,121 @@
+use anyhow::Result;
+use futures::{StreamExt as _, stream::BoxStream};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+use crate::{
+    LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId,
+    LanguageModelProviderId, TokenUsage,
+};
+
+/// Records the token usage of completion requests across all language model
+/// providers, and enforces budget caps on them.
+pub trait UsageLedger: Send + Sync + 'static {
+    /// Returns an error if a budget cap has been exceeded, blocking further requests.
+    ///
+    /// This is called for every request, so it must not block.
+    fn check_budget(&self) -> Result<()>;
+
+    /// Returns the project that a request made now is attributed to.
+    fn active_project(&self) -> Option<String>;
+
+    /// Records the token usage of a completed request.
+    fn record_usage(
+        &self,
+        provider_id: LanguageModelProviderId,
+        model_id: LanguageModelId,
+        project: Option<String>,
+        usage: TokenUsage,
+    );
+}
+
+static USAGE_LEDGER: RwLock<Option<Arc<dyn UsageLedger>>> = RwLock::new(None);
+
+/// Sets the ledger in which the usage of all completion requests is recorded.
+pub fn set_usage_ledger(ledger: Arc<dyn UsageLedger>) {
+    USAGE_LEDGER.write().replace(ledger);
+}
+
+fn usage_ledger() -> Option<Arc<dyn UsageLedger>> {
+    USAGE_LEDGER.read().clone()
+}
+
+/// Returns an error if the usage ledger reports that a budget cap has been exceeded.
+pub(crate) fn check_budget() -> Result<()> {
+    match usage_ledger() {
+        Some(ledger) => ledger.check_budget(),
+        None => Ok(()),
+    }
+}
+
+/// Starts tracking the token usage of a completion request.
+///
+/// This must be called when the request is made, so that its usage is attributed
+/// to the project that was active at that time.
+pub fn track_usage(
+    provider_id: LanguageModelProviderId,
+    model_id: LanguageModelId,
+) -> UsageTracker {
+    UsageTracker {
+        recorder: usage_ledger().map(|ledger| UsageRecorder {
+            project: ledger.active_project(),
+            ledger,
+            provider_id,
+            model_id,
+            usage: None,
+        }),
+    }
+}
+
+/// Tracks the token usage of a single completion request.
+pub struct UsageTracker {
+    recorder: Option<UsageRecorder>,
+}
+
+impl UsageTracker {
+    /// Records the token usage reported by the completion events in the usage ledger,
+    /// once the stream of events ends or is dropped.
+    pub fn track(
+        self,
+        events: BoxStream<
+            'static,
+            Result<LanguageModelCompletionEvent, LanguageModelCompletionError>,
+        >,
+    ) -> BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>
+    {
+        let Some(mut recorder) = self.recorder else {
+            return events;
+        };
+
+        events
+            .map(move |event| {
+                // Providers report the cumulative usage of the request, so the last update wins.
+                if let Ok(LanguageModelCompletionEvent::UsageUpdate(usage)) = &event {
+                    recorder.usage = Some(*usage);
+                }
+                event
+            })
+            .boxed()
+    }
+}
+
+struct UsageRecorder {
+    ledger: Arc<dyn UsageLedger>,
+    provider_id: LanguageModelProviderId,
+    model_id: LanguageModelId,
+    project: Option<String>,
+    usage: Option<TokenUsage>,
+}
+
+impl Drop for UsageRecorder {
+    fn drop(&mut self) {
+        if let Some(usage) = self.usage.take() {
+            self.ledger.record_usage(
+                self.provider_id.clone(),
+                self.model_id.clone(),
+                self.project.take(),
+                usage,
+            );
+        }
+    }
+}
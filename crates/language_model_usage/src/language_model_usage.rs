@@ -0,0 +1,337 @@
+mod language_model_usage_settings;
+mod persistence;
+mod usage_view;
+
+use anyhow::{Result, bail};
+use chrono::{Datelike as _, Local, NaiveDate};
+use gpui::{App, BackgroundExecutor, Context, Global, actions};
+use language_model::{LanguageModelId, LanguageModelProviderId, TokenUsage, UsageLedger};
+use parking_lot::Mutex;
+use settings::{Settings as _, SettingsStore};
+use std::sync::Arc;
+use util::ResultExt as _;
+use workspace::Workspace;
+
+pub use crate::language_model_usage_settings::*;
+use crate::persistence::LANGUAGE_MODEL_USAGE;
+pub use crate::persistence::UsageTotals;
+pub use crate::usage_view::UsageView;
+
+actions!(language_model_usage, [OpenUsage]);
+
+pub fn init(cx: &mut App) {
+    LanguageModelUsageSettings::register(cx);
+
+    let ledger = Arc::new(Ledger::new(
+        LanguageModelUsageSettings::get_global(cx).clone(),
+        cx.background_executor().clone(),
+    ));
+    ledger.refresh_spent();
+    language_model::set_usage_ledger(ledger.clone());
+    cx.set_global(GlobalLedger(ledger.clone()));
+
+    cx.observe_global::<SettingsStore>({
+        let ledger = ledger.clone();
+        move |cx| {
+            ledger.state.lock().settings = LanguageModelUsageSettings::get_global(cx).clone();
+        }
+    })
+    .detach();
+
+    cx.observe_new(
+        move |workspace: &mut Workspace, window, cx: &mut Context<Workspace>| {
+            workspace.register_action(|workspace, _: &OpenUsage, window, cx| {
+                let existing = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items()
+                    .find_map(|item| item.downcast::<UsageView>());
+
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, true, true, window, cx);
+                } else {
+                    let usage_view = UsageView::new(window, cx);
+                    workspace.add_item_to_active_pane(Box::new(usage_view), None, true, window, cx)
+                }
+            });
+
+            let Some(window) = window else {
+                return;
+            };
+
+            // Usage is attributed to the project of the most recently activated window.
+            ledger.set_active_project(active_project_name(workspace, cx));
+            cx.observe_window_activation(window, {
+                let ledger = ledger.clone();
+                move |workspace, window, cx| {
+                    if window.is_window_active() {
+                        ledger.set_active_project(active_project_name(workspace, cx));
+                    }
+                }
+            })
+            .detach();
+            cx.subscribe_in(workspace.project(), window, {
+                let ledger = ledger.clone();
+                move |workspace, _, event, window, cx| match event {
+                    project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
+                        if window.is_window_active() {
+                            ledger.set_active_project(active_project_name(workspace, cx));
+                        }
+                    }
+                    _ => {}
+                }
+            })
+            .detach();
+        },
+    )
+    .detach();
+}
+
+fn active_project_name(workspace: &Workspace, cx: &App) -> Option<String> {
+    let worktree_names = workspace
+        .project()
+        .read(cx)
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).root_name().to_string())
+        .collect::<Vec<_>>();
+    if worktree_names.is_empty() {
+        None
+    } else {
+        Some(worktree_names.join(", "))
+    }
+}
+
+struct GlobalLedger(Arc<Ledger>);
+
+impl Global for GlobalLedger {}
+
+/// Records the usage of language model requests in the database, pricing it
+/// according to [`LanguageModelUsageSettings`].
+struct Ledger {
+    state: Mutex<LedgerState>,
+    executor: BackgroundExecutor,
+    usage_recorded_tx: Arc<async_watch::Sender<()>>,
+    usage_recorded_rx: async_watch::Receiver<()>,
+}
+
+struct LedgerState {
+    settings: LanguageModelUsageSettings,
+    active_project: Option<String>,
+    /// The cost recorded so far today and this month, kept in memory so that
+    /// budgets can be checked without reading the database on every request.
+    spent: Option<Spent>,
+}
+
+struct Spent {
+    day: NaiveDate,
+    daily: f64,
+    monthly: f64,
+}
+
+impl Ledger {
+    fn new(settings: LanguageModelUsageSettings, executor: BackgroundExecutor) -> Self {
+        let (usage_recorded_tx, usage_recorded_rx) = async_watch::channel(());
+        Self {
+            state: Mutex::new(LedgerState {
+                settings,
+                active_project: None,
+                spent: None,
+            }),
+            executor,
+            usage_recorded_tx: Arc::new(usage_recorded_tx),
+            usage_recorded_rx,
+        }
+    }
+
+    fn global(cx: &App) -> Arc<Self> {
+        cx.global::<GlobalLedger>().0.clone()
+    }
+
+    fn set_active_project(&self, project: Option<String>) {
+        self.state.lock().active_project = project;
+    }
+
+    /// Reads the cost recorded today and this month from the database in the background.
+    fn refresh_spent(self: &Arc<Self>) {
+        let this = self.clone();
+        self.executor
+            .spawn(async move {
+                if let Some(spent) = read_spent(Local::now().date_naive()).log_err() {
+                    this.state.lock().spent = Some(spent);
+                }
+            })
+            .detach();
+    }
+
+    /// Returns a receiver that is notified whenever usage has been recorded.
+    fn usage_recorded(&self) -> async_watch::Receiver<()> {
+        self.usage_recorded_rx.clone()
+    }
+}
+
+impl UsageLedger for Ledger {
+    fn check_budget(&self) -> Result<()> {
+        let state = self.state.lock();
+        let budget = &state.settings.budget;
+        let today = Local::now().date_naive();
+        let (spent_today, spent_this_month) = match &state.spent {
+            Some(spent) if spent.day == today => (spent.daily, spent.monthly),
+            Some(spent) if start_of_month(spent.day) == start_of_month(today) => {
+                (0.0, spent.monthly)
+            }
+            _ => (0.0, 0.0),
+        };
+
+        if let Some(daily_budget) = budget.daily {
+            if spent_today >= daily_budget {
+                bail!(
+                    "The daily language model budget of ${daily_budget:.2} has been reached (${spent_today:.2} spent today)"
+                );
+            }
+        }
+
+        if let Some(monthly_budget) = budget.monthly {
+            if spent_this_month >= monthly_budget {
+                bail!(
+                    "The monthly language model budget of ${monthly_budget:.2} has been reached (${spent_this_month:.2} spent this month)"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn active_project(&self) -> Option<String> {
+        self.state.lock().active_project.clone()
+    }
+
+    fn record_usage(
+        &self,
+        provider_id: LanguageModelProviderId,
+        model_id: LanguageModelId,
+        project: Option<String>,
+        usage: TokenUsage,
+    ) {
+        let today = Local::now().date_naive();
+        let cost = {
+            let mut state = self.state.lock();
+            let cost = state
+                .settings
+                .price(&provider_id.0, &model_id.0)
+                .map(|price| price.cost(&usage));
+            if let (Some(cost), Some(spent)) = (cost, state.spent.as_mut()) {
+                if spent.day != today {
+                    if start_of_month(spent.day) != start_of_month(today) {
+                        spent.monthly = 0.0;
+                    }
+                    spent.day = today;
+                    spent.daily = 0.0;
+                }
+                spent.daily += cost;
+                spent.monthly += cost;
+            }
+            cost
+        };
+        let day = day_key(today);
+        let usage_recorded_tx = self.usage_recorded_tx.clone();
+
+        self.executor
+            .spawn(async move {
+                LANGUAGE_MODEL_USAGE
+                    .record_usage(
+                        day,
+                        provider_id.0.to_string(),
+                        model_id.0.to_string(),
+                        project,
+                        usage.input_tokens,
+                        usage.output_tokens,
+                        usage.cache_creation_input_tokens,
+                        usage.cache_read_input_tokens,
+                        cost,
+                    )
+                    .await
+                    .log_err();
+                usage_recorded_tx.send(()).ok();
+            })
+            .detach();
+    }
+}
+
+/// Reads the cost recorded on the given day and in its month from the database.
+fn read_spent(day: NaiveDate) -> Result<Spent> {
+    Ok(Spent {
+        day,
+        daily: LANGUAGE_MODEL_USAGE.cost_since(day_key(day))?,
+        monthly: LANGUAGE_MODEL_USAGE.cost_since(day_key(start_of_month(day)))?,
+    })
+}
+
+/// Returns the key under which usage is recorded for the given day.
+fn day_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn start_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use collections::HashMap;
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_check_budget_uses_recorded_cost(cx: &mut TestAppContext) {
+        let settings = LanguageModelUsageSettings {
+            prices: HashMap::from_iter([(
+                "anthropic".to_string(),
+                HashMap::from_iter([(
+                    "claude-3-7-sonnet-latest".to_string(),
+                    ModelPrice {
+                        input: 1.0,
+                        output: 1.0,
+                        ..Default::default()
+                    },
+                )]),
+            )]),
+            budget: UsageBudget {
+                daily: Some(1.0),
+                monthly: Some(1.5),
+            },
+        };
+        let ledger = Ledger::new(settings, cx.executor());
+        let today = Local::now().date_naive();
+        ledger.state.lock().spent = Some(Spent {
+            day: today,
+            daily: 0.0,
+            monthly: 1.0,
+        });
+        ledger.check_budget().unwrap();
+
+        // Usage is attributed to the project that was active when the request was made.
+        ledger.set_active_project(Some("zed".into()));
+        let project = ledger.active_project();
+        ledger.set_active_project(None);
+        ledger.record_usage(
+            LanguageModelProviderId("anthropic".into()),
+            LanguageModelId("claude-3-7-sonnet-latest".into()),
+            project,
+            TokenUsage {
+                input_tokens: 400_000,
+                output_tokens: 200_000,
+                ..Default::default()
+            },
+        );
+        cx.run_until_parked();
+
+        let error = ledger.check_budget().unwrap_err();
+        assert!(error.to_string().contains("monthly"), "{error}");
+        let projects = LANGUAGE_MODEL_USAGE
+            .totals_by_project(day_key(today))
+            .unwrap();
+        assert_eq!(projects[0].name.as_deref(), Some("zed"));
+        assert_eq!(projects[0].input_tokens, 400_000);
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use collections::HashMap;
+use gpui::App;
+use language_model::TokenUsage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+/// Settings for the ledger of tokens used by language model requests.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LanguageModelUsageSettings {
+    /// The price of each language model, in US dollars per million tokens,
+    /// keyed by provider ID and then by model ID.
+    ///
+    /// Default: `{}`
+    #[serde(default)]
+    pub prices: HashMap<String, HashMap<String, ModelPrice>>,
+    /// Caps on the cost of language model requests, in US dollars.
+    /// Requests are blocked once a cap has been reached.
+    #[serde(default)]
+    pub budget: UsageBudget,
+}
+
+impl LanguageModelUsageSettings {
+    pub fn price(&self, provider_id: &str, model_id: &str) -> Option<&ModelPrice> {
+        self.prices.get(provider_id)?.get(model_id)
+    }
+}
+
+/// The price of a language model, in US dollars per million tokens.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelPrice {
+    /// The price of input tokens.
+    pub input: f64,
+    /// The price of output tokens.
+    pub output: f64,
+    /// The price of input tokens written to the prompt cache.
+    ///
+    /// Default: the price of input tokens
+    #[serde(default)]
+    pub cache_creation_input: Option<f64>,
+    /// The price of input tokens read from the prompt cache.
+    ///
+    /// Default: the price of input tokens
+    #[serde(default)]
+    pub cache_read_input: Option<f64>,
+}
+
+impl ModelPrice {
+    /// Returns the cost of the given usage in US dollars.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cost = |price: f64, tokens: u32| price * tokens as f64 / 1_000_000.;
+        cost(self.input, usage.input_tokens)
+            + cost(self.output, usage.output_tokens)
+            + cost(
+                self.cache_creation_input.unwrap_or(self.input),
+                usage.cache_creation_input_tokens,
+            )
+            + cost(
+                self.cache_read_input.unwrap_or(self.input),
+                usage.cache_read_input_tokens,
+            )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UsageBudget {
+    /// The maximum cost of requests per day.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub daily: Option<f64>,
+    /// The maximum cost of requests per calendar month.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub monthly: Option<f64>,
+}
+
+impl Settings for LanguageModelUsageSettings {
+    const KEY: Option<&'static str> = Some("language_model_usage");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        sources.json_merge()
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
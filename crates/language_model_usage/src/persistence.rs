@@ -0,0 +1,201 @@
+use anyhow::Result;
+use db::{
+    define_connection, query,
+    sqlez::{bindable::Column, statement::Statement},
+    sqlez_macros::sql,
+};
+
+/// The tokens used by, and the cost of, a group of language model requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageTotals {
+    /// The day or project the requests are grouped by.
+    pub name: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    /// The cost of the requests in US dollars, according to the configured prices.
+    pub cost: f64,
+}
+
+impl Column for UsageTotals {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (name, next_index): (Option<String>, i32) = Column::column(statement, start_index)?;
+        let (input_tokens, next_index): (u64, i32) = Column::column(statement, next_index)?;
+        let (output_tokens, next_index): (u64, i32) = Column::column(statement, next_index)?;
+        let (cache_creation_input_tokens, next_index): (u64, i32) =
+            Column::column(statement, next_index)?;
+        let (cache_read_input_tokens, next_index): (u64, i32) =
+            Column::column(statement, next_index)?;
+        let (cost, next_index): (f64, i32) = Column::column(statement, next_index)?;
+
+        let totals = Self {
+            name,
+            input_tokens,
+            output_tokens,
+            cache_creation_input_tokens,
+            cache_read_input_tokens,
+            cost,
+        };
+        Ok((totals, next_index))
+    }
+}
+
+define_connection!(pub static ref LANGUAGE_MODEL_USAGE: LanguageModelUsageDb<()> =
+    &[sql!(
+        CREATE TABLE IF NOT EXISTS language_model_usage(
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp INTEGER DEFAULT (unixepoch()) NOT NULL,
+            day TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            project TEXT,
+            input_tokens INTEGER NOT NULL,
+            output_tokens INTEGER NOT NULL,
+            cache_creation_input_tokens INTEGER NOT NULL,
+            cache_read_input_tokens INTEGER NOT NULL,
+            cost REAL
+        ) STRICT;
+        CREATE INDEX IF NOT EXISTS language_model_usage_day ON language_model_usage(day);
+    )];
+);
+
+impl LanguageModelUsageDb {
+    query! {
+        pub async fn record_usage(
+            day: String,
+            provider: String,
+            model: String,
+            project: Option<String>,
+            input_tokens: u32,
+            output_tokens: u32,
+            cache_creation_input_tokens: u32,
+            cache_read_input_tokens: u32,
+            cost: Option<f64>
+        ) -> Result<()> {
+            INSERT INTO language_model_usage (
+                day,
+                provider,
+                model,
+                project,
+                input_tokens,
+                output_tokens,
+                cache_creation_input_tokens,
+                cache_read_input_tokens,
+                cost
+            ) VALUES ((?), (?), (?), (?), (?), (?), (?), (?), (?))
+        }
+    }
+
+    query! {
+        pub fn totals_by_day(since_day: String) -> Result<Vec<UsageTotals>> {
+            SELECT
+                day,
+                SUM(input_tokens),
+                SUM(output_tokens),
+                SUM(cache_creation_input_tokens),
+                SUM(cache_read_input_tokens),
+                TOTAL(cost)
+            FROM language_model_usage
+            WHERE day >= (?)
+            GROUP BY day
+            ORDER BY day DESC
+        }
+    }
+
+    query! {
+        pub fn totals_by_project(since_day: String) -> Result<Vec<UsageTotals>> {
+            SELECT
+                project,
+                SUM(input_tokens),
+                SUM(output_tokens),
+                SUM(cache_creation_input_tokens),
+                SUM(cache_read_input_tokens),
+                TOTAL(cost)
+            FROM language_model_usage
+            WHERE day >= (?)
+            GROUP BY project
+            ORDER BY TOTAL(cost) DESC, SUM(input_tokens) + SUM(output_tokens) DESC
+        }
+    }
+
+    query! {
+        pub fn cost_since(since_day: String) -> Result<f64> {
+            SELECT TOTAL(cost)
+            FROM language_model_usage
+            WHERE day >= (?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persistence::LanguageModelUsageDb;
+
+    #[gpui::test]
+    async fn test_totals_by_day_and_project() {
+        let db = LanguageModelUsageDb::open_test_db("test_totals_by_day_and_project").await;
+
+        assert_eq!(db.cost_since("2025-01-01".into()).unwrap(), 0.0);
+
+        db.record_usage(
+            "2025-01-01".into(),
+            "anthropic".into(),
+            "claude-3-7-sonnet-latest".into(),
+            Some("zed".into()),
+            1000,
+            200,
+            0,
+            0,
+            Some(0.5),
+        )
+        .await
+        .unwrap();
+        db.record_usage(
+            "2025-01-02".into(),
+            "openai".into(),
+            "gpt-4o".into(),
+            Some("zed".into()),
+            500,
+            100,
+            0,
+            300,
+            Some(0.25),
+        )
+        .await
+        .unwrap();
+        db.record_usage(
+            "2025-01-02".into(),
+            "ollama".into(),
+            "llama3.2".into(),
+            None,
+            100,
+            10,
+            0,
+            0,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let days = db.totals_by_day("2025-01-01".into()).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].name.as_deref(), Some("2025-01-02"));
+        assert_eq!(days[0].input_tokens, 600);
+        assert_eq!(days[0].output_tokens, 110);
+        assert_eq!(days[0].cache_read_input_tokens, 300);
+        assert_eq!(days[0].cost, 0.25);
+        assert_eq!(days[1].name.as_deref(), Some("2025-01-01"));
+        assert_eq!(days[1].cost, 0.5);
+
+        let projects = db.totals_by_project("2025-01-02".into()).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].name.as_deref(), Some("zed"));
+        assert_eq!(projects[0].input_tokens, 500);
+        assert_eq!(projects[1].name, None);
+        assert_eq!(projects[1].cost, 0.0);
+
+        assert_eq!(db.cost_since("2025-01-01".into()).unwrap(), 0.75);
+        assert_eq!(db.cost_since("2025-01-02".into()).unwrap(), 0.25);
+    }
+}
@@ -0,0 +1,209 @@
+use chrono::{Duration, Local};
+use gpui::{App, Entity, EventEmitter, FocusHandle, Focusable, Task};
+use settings::Settings as _;
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::item::{Item, ItemEvent};
+use workspace::{Workspace, WorkspaceId};
+
+use crate::persistence::{LANGUAGE_MODEL_USAGE, UsageTotals};
+use crate::{LanguageModelUsageSettings, Ledger, day_key, start_of_month};
+
+/// The number of days, including today, whose usage is listed in the view.
+const DAYS_SHOWN: i64 = 30;
+
+/// Shows the tokens used by, and the cost of, language model requests per day
+/// and per project.
+pub struct UsageView {
+    focus_handle: FocusHandle,
+    totals_by_day: Vec<UsageTotals>,
+    totals_by_project: Vec<UsageTotals>,
+    _refresh_task: Task<()>,
+}
+
+impl UsageView {
+    pub fn new(_window: &mut Window, cx: &mut Context<Workspace>) -> Entity<Self> {
+        cx.new(|cx| {
+            let mut usage_recorded = Ledger::global(cx).usage_recorded();
+            let refresh_task = cx.spawn(async move |this, cx| {
+                while usage_recorded.changed().await.is_ok() {
+                    if this.update(cx, |this, cx| this.refresh(cx)).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            let mut this = Self {
+                focus_handle: cx.focus_handle(),
+                totals_by_day: Vec::new(),
+                totals_by_project: Vec::new(),
+                _refresh_task: refresh_task,
+            };
+            this.refresh(cx);
+            this
+        })
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let today = Local::now().date_naive();
+        self.totals_by_day = LANGUAGE_MODEL_USAGE
+            .totals_by_day(day_key(today - Duration::days(DAYS_SHOWN - 1)))
+            .log_err()
+            .unwrap_or_default();
+        self.totals_by_project = LANGUAGE_MODEL_USAGE
+            .totals_by_project(day_key(start_of_month(today)))
+            .log_err()
+            .unwrap_or_default();
+        cx.notify();
+    }
+
+    fn render_budget(&self, cx: &App) -> impl IntoElement {
+        let budget = &LanguageModelUsageSettings::get_global(cx).budget;
+        let today = day_key(Local::now().date_naive());
+        let spent_today = self
+            .totals_by_day
+            .iter()
+            .find(|totals| totals.name.as_ref() == Some(&today))
+            .map_or(0., |totals| totals.cost);
+        let spent_this_month = self
+            .totals_by_project
+            .iter()
+            .map(|totals| totals.cost)
+            .sum::<f64>();
+
+        let spend_label = |period: &str, spent: f64, cap: Option<f64>| match cap {
+            Some(cap) => {
+                let color = if spent >= cap {
+                    Color::Error
+                } else {
+                    Color::Default
+                };
+                Label::new(format!("{period}: ${spent:.2} of ${cap:.2}")).color(color)
+            }
+            None => Label::new(format!("{period}: ${spent:.2}")),
+        };
+
+        h_flex()
+            .gap_4()
+            .child(spend_label("Today", spent_today, budget.daily))
+            .child(spend_label("This month", spent_this_month, budget.monthly))
+    }
+
+    fn render_totals(
+        &self,
+        title: &'static str,
+        name_header: &'static str,
+        rows: &[UsageTotals],
+        empty_name: &'static str,
+    ) -> impl IntoElement {
+        fn row(cells: [SharedString; 6], color: Color) -> impl IntoElement {
+            let [name, input, output, cache_creation, cache_read, cost] = cells;
+            h_flex()
+                .gap_2()
+                .child(div().w_64().child(Label::new(name).color(color).truncate()))
+                .children(
+                    [input, output, cache_creation, cache_read, cost]
+                        .into_iter()
+                        .map(move |cell| div().w_32().child(Label::new(cell).color(color))),
+                )
+        }
+
+        v_flex()
+            .gap_1()
+            .child(Label::new(title).size(LabelSize::Large))
+            .child(row(
+                [
+                    name_header.into(),
+                    "Input".into(),
+                    "Output".into(),
+                    "Cache Write".into(),
+                    "Cache Read".into(),
+                    "Cost".into(),
+                ],
+                Color::Muted,
+            ))
+            .when(rows.is_empty(), |this| {
+                this.child(Label::new("No usage recorded").color(Color::Muted))
+            })
+            .children(rows.iter().map(|totals| {
+                row(
+                    [
+                        totals
+                            .name
+                            .clone()
+                            .unwrap_or_else(|| empty_name.to_string())
+                            .into(),
+                        totals.input_tokens.to_string().into(),
+                        totals.output_tokens.to_string().into(),
+                        totals.cache_creation_input_tokens.to_string().into(),
+                        totals.cache_read_input_tokens.to_string().into(),
+                        format!("${:.2}", totals.cost).into(),
+                    ],
+                    Color::Default,
+                )
+            }))
+    }
+}
+
+impl EventEmitter<ItemEvent> for UsageView {}
+
+impl Focusable for UsageView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for UsageView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Language Model Usage".into()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Option<Entity<Self>> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for UsageView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("language-model-usage")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_4()
+            .gap_6()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(Label::new("Language Model Usage").size(LabelSize::Large))
+                    .child(self.render_budget(cx)),
+            )
+            .child(self.render_totals("By Day", "Day", &self.totals_by_day, ""))
+            .child(self.render_totals(
+                "By Project This Month",
+                "Project",
+                &self.totals_by_project,
+                "No Project",
+            ))
+    }
+}
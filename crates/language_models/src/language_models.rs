@@ -23,10 +23,12 @@ pub use crate::settings::*;
 
 pub fn init(user_store: Entity<UserStore>, client: Arc<Client>, fs: Arc<dyn Fs>, cx: &mut App) {
     crate::settings::init(fs, cx);
+    provider::extension::init(cx);
     let registry = LanguageModelRegistry::global(cx);
     registry.update(cx, |registry, cx| {
-        register_language_model_providers(registry, user_store, client, cx);
+        register_language_model_providers(registry, user_store, client.clone(), cx);
     });
+    provider::open_ai_compatible::init(client.http_client(), cx);
 }
 
 fn register_language_model_providers(
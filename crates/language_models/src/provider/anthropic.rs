@@ -471,7 +471,8 @@ impl LanguageModel for AnthropicModel {
                 })?;
             Ok(AnthropicEventMapper::new().map_stream(response))
         });
-        async move { Ok(future.await?.boxed()) }.boxed()
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
+        async move { Ok(usage_tracker.track(future.await?.boxed())) }.boxed()
     }
 
     fn cache_configuration(&self) -> Option<LanguageModelCacheConfiguration> {
@@ -15,11 +15,12 @@ use bedrock::bedrock_client::types::{
     StopReason,
 };
 use bedrock::{
-    BedrockAutoToolChoice, BedrockBlob, BedrockError, BedrockInnerContent, BedrockMessage,
-    BedrockModelMode, BedrockStreamingResponse, BedrockThinkingBlock, BedrockThinkingTextBlock,
-    BedrockTool, BedrockToolChoice, BedrockToolConfig, BedrockToolInputSchema,
-    BedrockToolResultBlock, BedrockToolResultContentBlock, BedrockToolResultStatus,
-    BedrockToolSpec, BedrockToolUseBlock, Model, value_to_aws_document,
+    BedrockAutoToolChoice, BedrockBlob, BedrockCachePointBlock, BedrockCachePointType,
+    BedrockError, BedrockInnerContent, BedrockMessage, BedrockModelMode, BedrockStreamingResponse,
+    BedrockThinkingBlock, BedrockThinkingTextBlock, BedrockTool, BedrockToolChoice,
+    BedrockToolConfig, BedrockToolInputSchema, BedrockToolResultBlock,
+    BedrockToolResultContentBlock, BedrockToolResultStatus, BedrockToolSpec, BedrockToolUseBlock,
+    Model, value_to_aws_document,
 };
 use collections::{BTreeMap, HashMap};
 use credentials_provider::CredentialsProvider;
@@ -47,7 +48,7 @@ use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
 use theme::ThemeSettings;
 use tokio::runtime::Handle;
 use ui::{Icon, IconName, List, Tooltip, prelude::*};
-use util::{ResultExt, default};
+use util::ResultExt;
 
 use crate::AllLanguageModelSettings;
 
@@ -576,6 +577,7 @@ impl LanguageModel for BedrockModel {
             self.model.default_temperature(),
             self.model.max_output_tokens(),
             self.model.mode(),
+            self.model.supports_caching(),
         ) {
             Ok(request) => request,
             Err(err) => return futures::future::ready(Err(err)).boxed(),
@@ -591,7 +593,8 @@ impl LanguageModel for BedrockModel {
                 owned_handle,
             ))
         });
-        async move { Ok(future.await?.boxed()) }.boxed()
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
+        async move { Ok(usage_tracker.track(future.await?.boxed())) }.boxed()
     }
 
     fn cache_configuration(&self) -> Option<LanguageModelCacheConfiguration> {
@@ -605,9 +608,14 @@ pub fn into_bedrock(
     default_temperature: f32,
     max_output_tokens: u32,
     mode: BedrockModelMode,
+    supports_caching: bool,
 ) -> Result<bedrock::Request> {
+    // Bedrock allows at most 4 cache points per request.
+    const MAX_CACHE_POINTS: usize = 4;
+
     let mut new_messages: Vec<BedrockMessage> = Vec::new();
     let mut system_message = String::new();
+    let mut cache_points = 0;
 
     for message in request.messages {
         if message.contents_empty() {
@@ -616,7 +624,8 @@ pub fn into_bedrock(
 
         match message.role {
             Role::User | Role::Assistant => {
-                let bedrock_message_content: Vec<BedrockInnerContent> = message
+                let cache = message.cache;
+                let mut bedrock_message_content: Vec<BedrockInnerContent> = message
                     .content
                     .into_iter()
                     .filter_map(|content| match content {
@@ -674,6 +683,17 @@ pub fn into_bedrock(
                         _ => None,
                     })
                     .collect();
+                if cache && supports_caching && cache_points < MAX_CACHE_POINTS {
+                    if let Some(cache_point) = BedrockCachePointBlock::builder()
+                        .r#type(BedrockCachePointType::Default)
+                        .build()
+                        .context("failed to build Bedrock cache point")
+                        .log_err()
+                    {
+                        bedrock_message_content.push(BedrockInnerContent::CachePoint(cache_point));
+                        cache_points += 1;
+                    }
+                }
                 let bedrock_role = match message.role {
                     Role::User => bedrock::BedrockRole::User,
                     Role::Assistant => bedrock::BedrockRole::Assistant,
@@ -948,8 +968,14 @@ pub fn map_to_language_model_completion_events(
                                                         input_tokens: metadata.input_tokens as u32,
                                                         output_tokens: metadata.output_tokens
                                                             as u32,
-                                                        cache_creation_input_tokens: default(),
-                                                        cache_read_input_tokens: default(),
+                                                        cache_creation_input_tokens: metadata
+                                                            .cache_write_input_tokens
+                                                            .unwrap_or_default()
+                                                            as u32,
+                                                        cache_read_input_tokens: metadata
+                                                            .cache_read_input_tokens
+                                                            .unwrap_or_default()
+                                                            as u32,
                                                     },
                                                 );
                                             return Some((Some(Ok(completion_event)), state));
@@ -793,6 +793,7 @@ impl LanguageModel for CloudLanguageModel {
         let thread_id = request.thread_id.clone();
         let prompt_id = request.prompt_id.clone();
         let mode = request.mode;
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
         match &self.model {
             CloudModel::Anthropic(model) => {
                 let request = into_anthropic(
@@ -853,7 +854,7 @@ impl LanguageModel for CloudLanguageModel {
                 });
                 async move {
                     let (stream, usage) = future.await?;
-                    Ok((stream.boxed(), usage))
+                    Ok((usage_tracker.track(stream.boxed()), usage))
                 }
                 .boxed()
             }
@@ -895,7 +896,7 @@ impl LanguageModel for CloudLanguageModel {
                 });
                 async move {
                     let (stream, usage) = future.await?;
-                    Ok((stream.boxed(), usage))
+                    Ok((usage_tracker.track(stream.boxed()), usage))
                 }
                 .boxed()
             }
@@ -937,7 +938,7 @@ impl LanguageModel for CloudLanguageModel {
                 });
                 async move {
                     let (stream, usage) = future.await?;
-                    Ok((stream.boxed(), usage))
+                    Ok((usage_tracker.track(stream.boxed()), usage))
                 }
                 .boxed()
             }
@@ -283,6 +283,8 @@ impl LanguageModel for CopilotChatLanguageModel {
                 })
                 .await
         });
+        // Usage isn't tracked, as requests are covered by the Copilot subscription
+        // and the API doesn't report their token usage.
         async move { Ok(future.await?.boxed()) }.boxed()
     }
 }
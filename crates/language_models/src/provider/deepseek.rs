@@ -12,6 +12,7 @@ use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest, RateLimiter, Role,
+    TokenUsage,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -336,34 +337,51 @@ impl LanguageModel for DeepSeekLanguageModel {
             self.max_output_tokens(),
         );
         let stream = self.stream_completion(request, cx);
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
 
         async move {
             let stream = stream.await?;
-            Ok(stream
-                .map(|result| {
-                    result
-                        .and_then(|response| {
-                            response
-                                .choices
-                                .first()
-                                .ok_or_else(|| anyhow!("Empty response"))
-                                .map(|choice| {
-                                    choice
-                                        .delta
-                                        .content
-                                        .clone()
-                                        .unwrap_or_default()
-                                        .map(LanguageModelCompletionEvent::Text)
-                                })
+            Ok(usage_tracker.track(
+                stream
+                    .flat_map(|result| {
+                        futures::stream::iter(match result {
+                            Ok(response) => map_stream_response(response),
+                            Err(error) => vec![Err(LanguageModelCompletionError::Other(error))],
                         })
-                        .map_err(LanguageModelCompletionError::Other)
-                })
-                .boxed())
+                    })
+                    .boxed(),
+            ))
         }
         .boxed()
     }
 }
 
+fn map_stream_response(
+    response: deepseek::StreamResponse,
+) -> Vec<Result<LanguageModelCompletionEvent, LanguageModelCompletionError>> {
+    let mut events = Vec::new();
+    if let Some(choice) = response.choices.first() {
+        if let Some(content) = choice.delta.content.clone() {
+            events.push(Ok(LanguageModelCompletionEvent::Text(content)));
+        }
+    } else if response.usage.is_none() {
+        events.push(Err(LanguageModelCompletionError::Other(anyhow!(
+            "Empty response"
+        ))));
+    }
+    if let Some(usage) = response.usage {
+        events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+            input_tokens: usage
+                .prompt_tokens
+                .saturating_sub(usage.prompt_cache_hit_tokens),
+            output_tokens: usage.completion_tokens,
+            cache_read_input_tokens: usage.prompt_cache_hit_tokens,
+            ..Default::default()
+        })));
+    }
+    events
+}
+
 pub fn into_deepseek(
     request: LanguageModelRequest,
     model: String,
@@ -430,6 +448,9 @@ pub fn into_deepseek(
         model,
         messages: merged_messages,
         stream: true,
+        stream_options: Some(deepseek::StreamOptions {
+            include_usage: true,
+        }),
         max_tokens: max_output_tokens,
         temperature: if is_reasoner {
             None
@@ -0,0 +1,727 @@
+use anyhow::{Context as _, Result, anyhow};
+use credentials_provider::CredentialsProvider;
+use editor::{Editor, EditorElement, EditorStyle};
+use extension::{
+    Extension, ExtensionHostProxy, ExtensionLanguageModelProviderProxy,
+    LanguageModelProviderManifestEntry, LlmCompletionEvent, LlmCompletionRequest,
+    LlmMessageContent, LlmMessageRole, LlmModel, LlmRequestMessage, LlmStopReason,
+    LlmToolDefinition, LlmToolResult, LlmToolUse,
+};
+use futures::{FutureExt, Stream, StreamExt, future::BoxFuture, stream::BoxStream};
+use gpui::{
+    AnyView, AppContext as _, AsyncApp, BackgroundExecutor, Entity, FontStyle, Task, TextStyle,
+    WhiteSpace,
+};
+use language_model::{
+    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRegistry,
+    LanguageModelRequest, LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason,
+    TokenUsage,
+};
+use settings::Settings;
+use std::sync::Arc;
+use theme::ThemeSettings;
+use ui::{Icon, IconName, List, prelude::*};
+use util::ResultExt;
+
+use crate::ui::InstructionListItem;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_language_model_provider_proxy(LanguageModelRegistryProxy);
+}
+
+struct LanguageModelRegistryProxy;
+
+impl ExtensionLanguageModelProviderProxy for LanguageModelRegistryProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        let provider = ExtensionLanguageModelProvider::new(extension, provider_id, entry, cx);
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.register_provider(provider, cx);
+        });
+    }
+
+    fn unregister_language_model_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.unregister_provider(registry_provider_id(&extension_id, &provider_id), cx);
+        });
+    }
+}
+
+/// Returns the ID under which an extension's provider is registered.
+///
+/// The ID is namespaced by the extension, so that an extension can't replace a
+/// built-in provider or one from another extension.
+fn registry_provider_id(extension_id: &str, provider_id: &str) -> LanguageModelProviderId {
+    LanguageModelProviderId(format!("{extension_id}:{provider_id}").into())
+}
+
+/// A language model provider backed by an extension.
+pub struct ExtensionLanguageModelProvider {
+    id: LanguageModelProviderId,
+    name: LanguageModelProviderName,
+    /// The ID of the provider within the extension.
+    provider_id: Arc<str>,
+    extension: Arc<dyn Extension>,
+    state: Entity<State>,
+}
+
+pub struct State {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    provider_name: SharedString,
+    requires_api_key: bool,
+    api_key: Option<String>,
+    models: Vec<LlmModel>,
+    fetch_models_task: Task<()>,
+}
+
+impl State {
+    /// The URL under which the provider's API key is stored in the credentials provider.
+    fn credentials_url(&self) -> String {
+        format!(
+            "extension://{}/{}",
+            self.extension.manifest().id,
+            self.provider_id
+        )
+    }
+
+    fn is_authenticated(&self) -> bool {
+        !self.requires_api_key || self.api_key.is_some()
+    }
+
+    /// Lists the provider's models again, as they may depend on the API key.
+    fn fetch_models(&mut self, cx: &mut Context<Self>) {
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        let api_key = self.api_key.clone();
+        self.fetch_models_task = cx.spawn(async move |this, cx| {
+            let Some(models) = extension
+                .llm_provider_models(provider_id.clone(), api_key)
+                .await
+                .with_context(|| format!("failed to list models of {provider_id}"))
+                .log_err()
+            else {
+                return;
+            };
+
+            this.update(cx, |this, cx| {
+                this.models = models;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn reset_api_key(&self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .delete_credentials(&credentials_url, &cx)
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.api_key = None;
+                this.fetch_models(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn set_api_key(&mut self, api_key: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .write_credentials(&credentials_url, "Bearer", api_key.as_bytes(), &cx)
+                .await?;
+            this.update(cx, |this, cx| {
+                this.api_key = Some(api_key);
+                this.fetch_models(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn authenticate(&self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.is_authenticated() {
+            return Task::ready(Ok(()));
+        }
+
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url();
+        let provider_name = self.provider_name.clone();
+        cx.spawn(async move |this, cx| {
+            let (_, api_key) = credentials_provider
+                .read_credentials(&credentials_url, &cx)
+                .await?
+                .ok_or(AuthenticateError::CredentialsNotFound)?;
+            let api_key = String::from_utf8(api_key)
+                .with_context(|| format!("invalid {provider_name} API key"))?;
+
+            this.update(cx, |this, cx| {
+                this.api_key = Some(api_key);
+                this.fetch_models(cx);
+                cx.notify();
+            })?;
+
+            Ok(())
+        })
+    }
+}
+
+impl ExtensionLanguageModelProvider {
+    pub fn new(
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) -> Self {
+        let provider_name = SharedString::from(entry.name);
+        let id = registry_provider_id(&extension.manifest().id, &provider_id);
+        let state = cx.new(|cx| {
+            let mut state = State {
+                extension: extension.clone(),
+                provider_id: provider_id.clone(),
+                provider_name: provider_name.clone(),
+                requires_api_key: entry.requires_api_key,
+                api_key: None,
+                models: Vec::new(),
+                fetch_models_task: Task::ready(()),
+            };
+            state.fetch_models(cx);
+            state
+        });
+
+        Self {
+            id,
+            name: LanguageModelProviderName(provider_name),
+            provider_id,
+            extension,
+            state,
+        }
+    }
+
+    fn create_language_model(&self, model: LlmModel) -> Arc<dyn LanguageModel> {
+        Arc::new(ExtensionLanguageModel {
+            id: LanguageModelId::from(model.id.clone()),
+            model,
+            provider_id: self.id.clone(),
+            extension_provider_id: self.provider_id.clone(),
+            provider_name: self.name.clone(),
+            extension: self.extension.clone(),
+            state: self.state.clone(),
+            request_limiter: RateLimiter::new(4),
+        }) as Arc<dyn LanguageModel>
+    }
+}
+
+impl LanguageModelProviderState for ExtensionLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for ExtensionLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.name.clone()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Ai
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        let model = self.state.read(cx).models.first()?.clone();
+        Some(self.create_language_model(model))
+    }
+
+    fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        None
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        self.state
+            .read(cx)
+            .models
+            .iter()
+            .map(|model| self.create_language_model(model.clone()))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, window: &mut Window, cx: &mut App) -> AnyView {
+        cx.new(|cx| ConfigurationView::new(self.state.clone(), window, cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.reset_api_key(cx))
+    }
+}
+
+pub struct ExtensionLanguageModel {
+    id: LanguageModelId,
+    model: LlmModel,
+    provider_id: LanguageModelProviderId,
+    /// The ID of the provider within the extension.
+    extension_provider_id: Arc<str>,
+    provider_name: LanguageModelProviderName,
+    extension: Arc<dyn Extension>,
+    state: Entity<State>,
+    request_limiter: RateLimiter,
+}
+
+impl LanguageModel for ExtensionLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.display_name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.provider_id.clone()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!(
+            "extension/{}/{}",
+            self.extension.manifest().id,
+            self.model.id
+        )
+    }
+
+    fn max_token_count(&self) -> usize {
+        self.model.max_token_count as usize
+    }
+
+    fn max_output_tokens(&self) -> Option<u32> {
+        self.model
+            .max_output_tokens
+            .map(|tokens| tokens.min(u32::MAX as u64) as u32)
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<usize>> {
+        // Extensions have no way of counting tokens, so approximate the count
+        // with the tokenizer used by most models.
+        cx.background_spawn(async move {
+            let messages = request
+                .messages
+                .into_iter()
+                .map(|message| tiktoken_rs::ChatCompletionRequestMessage {
+                    role: match message.role {
+                        Role::User => "user".into(),
+                        Role::Assistant => "assistant".into(),
+                        Role::System => "system".into(),
+                    },
+                    content: Some(message.string_contents()),
+                    name: None,
+                    function_call: None,
+                })
+                .collect::<Vec<_>>();
+
+            tiktoken_rs::num_tokens_from_messages("gpt-4", &messages)
+        })
+        .boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+        >,
+    > {
+        let Ok(api_key) = cx.read_entity(&self.state, |state, _| state.api_key.clone()) else {
+            return futures::future::ready(Err(anyhow!("App state dropped"))).boxed();
+        };
+
+        let extension = self.extension.clone();
+        let provider_id = self.extension_provider_id.clone();
+        let model_id: Arc<str> = self.model.id.as_str().into();
+        let executor = cx.background_executor().clone();
+        let request = into_llm_request(request);
+
+        let future = self.request_limiter.stream(async move {
+            let stream_id = extension
+                .llm_stream_completion(provider_id, model_id, request, api_key)
+                .await?;
+            Ok(completion_events(CompletionStream {
+                extension,
+                stream_id,
+                executor,
+                is_finished: false,
+            }))
+        });
+
+        let usage_tracker = language_model::track_usage(self.provider_id.clone(), self.id.clone());
+        async move {
+            Ok(usage_tracker.track(
+                future
+                    .await?
+                    .map(|result| result.map_err(LanguageModelCompletionError::Other))
+                    .boxed(),
+            ))
+        }
+        .boxed()
+    }
+}
+
+/// A completion stream in the extension, which is dropped along with this handle
+/// if it has not ended yet.
+struct CompletionStream {
+    extension: Arc<dyn Extension>,
+    stream_id: u64,
+    executor: BackgroundExecutor,
+    is_finished: bool,
+}
+
+impl Drop for CompletionStream {
+    fn drop(&mut self) {
+        if self.is_finished {
+            return;
+        }
+
+        let extension = self.extension.clone();
+        let stream_id = self.stream_id;
+        self.executor
+            .spawn(async move {
+                extension
+                    .llm_drop_completion_stream(stream_id)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
+
+fn completion_events(
+    stream: CompletionStream,
+) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
+    futures::stream::unfold(Some(stream), |stream| async move {
+        let mut stream = stream?;
+        match stream
+            .extension
+            .llm_next_completion_events(stream.stream_id)
+            .await
+        {
+            Ok(Some(events)) => Some((Ok(events), Some(stream))),
+            Ok(None) => {
+                stream.is_finished = true;
+                None
+            }
+            Err(error) => Some((Err(error), None)),
+        }
+    })
+    .flat_map(|result| {
+        let events = match result {
+            Ok(events) => events.into_iter().map(into_completion_event).collect(),
+            Err(error) => vec![Err(error)],
+        };
+        futures::stream::iter(events)
+    })
+}
+
+fn into_completion_event(event: LlmCompletionEvent) -> Result<LanguageModelCompletionEvent> {
+    Ok(match event {
+        LlmCompletionEvent::Text(text) => LanguageModelCompletionEvent::Text(text),
+        LlmCompletionEvent::Thinking(text) => LanguageModelCompletionEvent::Thinking {
+            text,
+            signature: None,
+        },
+        LlmCompletionEvent::ToolUse(tool_use) => {
+            let input = serde_json::from_str(&tool_use.input)
+                .with_context(|| format!("failed to parse input of tool use {}", tool_use.name))?;
+            LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                id: tool_use.id.into(),
+                name: tool_use.name.into(),
+                raw_input: tool_use.input,
+                input,
+                is_input_complete: true,
+            })
+        }
+        LlmCompletionEvent::Stop(reason) => LanguageModelCompletionEvent::Stop(match reason {
+            LlmStopReason::EndTurn => StopReason::EndTurn,
+            LlmStopReason::MaxTokens => StopReason::MaxTokens,
+            LlmStopReason::ToolUse => StopReason::ToolUse,
+        }),
+        LlmCompletionEvent::UsageUpdate(usage) => {
+            LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_input_tokens: usage.cache_creation_input_tokens,
+                cache_read_input_tokens: usage.cache_read_input_tokens,
+            })
+        }
+    })
+}
+
+pub fn into_llm_request(request: LanguageModelRequest) -> LlmCompletionRequest {
+    LlmCompletionRequest {
+        messages: request
+            .messages
+            .into_iter()
+            .map(|message| LlmRequestMessage {
+                role: match message.role {
+                    Role::User => LlmMessageRole::User,
+                    Role::Assistant => LlmMessageRole::Assistant,
+                    Role::System => LlmMessageRole::System,
+                },
+                content: message
+                    .content
+                    .into_iter()
+                    .filter_map(|content| match content {
+                        MessageContent::Text(text) => Some(LlmMessageContent::Text(text)),
+                        MessageContent::Image(image) => {
+                            Some(LlmMessageContent::Image(image.source.to_string()))
+                        }
+                        MessageContent::ToolUse(tool_use) => {
+                            Some(LlmMessageContent::ToolUse(LlmToolUse {
+                                id: tool_use.id.to_string(),
+                                name: tool_use.name.to_string(),
+                                input: tool_use.input.to_string(),
+                            }))
+                        }
+                        MessageContent::ToolResult(tool_result) => {
+                            Some(LlmMessageContent::ToolResult(LlmToolResult {
+                                tool_use_id: tool_result.tool_use_id.to_string(),
+                                tool_name: tool_result.tool_name.to_string(),
+                                is_error: tool_result.is_error,
+                                content: tool_result.content.to_string(),
+                            }))
+                        }
+                        MessageContent::Thinking { .. } | MessageContent::RedactedThinking(_) => {
+                            None
+                        }
+                    })
+                    .collect(),
+                cache: message.cache,
+            })
+            .collect(),
+        tools: request
+            .tools
+            .into_iter()
+            .map(|tool| LlmToolDefinition {
+                name: tool.name,
+                description: tool.description,
+                input_schema: tool.input_schema.to_string(),
+            })
+            .collect(),
+        stop: request.stop,
+        temperature: request.temperature,
+    }
+}
+
+struct ConfigurationView {
+    api_key_editor: Entity<Editor>,
+    state: Entity<State>,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: Entity<State>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let api_key_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Paste your API key here", cx);
+            editor
+        });
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn({
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    let _ = task.await;
+                }
+
+                this.update(cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        let api_key = self.api_key_editor.read(cx).text(cx);
+        if api_key.is_empty() {
+            return;
+        }
+
+        let state = self.state.clone();
+        cx.spawn(async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(api_key, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn reset_api_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.api_key_editor
+            .update(cx, |editor, cx| editor.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn(async move |_, cx| state.update(cx, |state, cx| state.reset_api_key(cx))?.await)
+            .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn render_api_key_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+            white_space: WhiteSpace::Normal,
+            ..Default::default()
+        };
+        EditorElement::new(
+            &self.api_key_editor,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let provider_name = state.provider_name.clone();
+        let requires_api_key = state.requires_api_key;
+        let is_authenticated = state.is_authenticated();
+
+        if !requires_api_key {
+            h_flex()
+                .gap_1()
+                .child(Icon::new(IconName::Check).color(Color::Success))
+                .child(Label::new(format!(
+                    "{provider_name} does not require an API key"
+                )))
+                .into_any()
+        } else if self.load_credentials_task.is_some() {
+            div().child(Label::new("Loading credentials...")).into_any()
+        } else if !is_authenticated {
+            v_flex()
+                .size_full()
+                .on_action(cx.listener(Self::save_api_key))
+                .child(Label::new(format!(
+                    "To use {provider_name} in Zed, you need an API key:"
+                )))
+                .child(List::new().child(InstructionListItem::text_only(
+                    "Paste your API key below and hit enter to start using the assistant",
+                )))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .my_2()
+                        .px_2()
+                        .py_1()
+                        .bg(cx.theme().colors().editor_background)
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .rounded_sm()
+                        .child(self.render_api_key_editor(cx)),
+                )
+                .into_any()
+        } else {
+            h_flex()
+                .mt_1()
+                .p_1()
+                .justify_between()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().background)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(Label::new("API key configured")),
+                )
+                .child(
+                    Button::new("reset-key", "Reset Key")
+                        .label_size(LabelSize::Small)
+                        .icon(Some(IconName::Trash))
+                        .icon_size(IconSize::Small)
+                        .icon_position(IconPosition::Start)
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.reset_api_key(window, cx)),
+                        ),
+                )
+                .into_any()
+        }
+    }
+}
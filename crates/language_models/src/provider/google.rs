@@ -376,7 +376,8 @@ impl LanguageModel for GoogleLanguageModel {
                 .map_err(|err| LanguageModelCompletionError::Other(anyhow!(err)))?;
             Ok(GoogleEventMapper::new().map_stream(response))
         });
-        async move { Ok(future.await?.boxed()) }.boxed()
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
+        async move { Ok(usage_tracker.track(future.await?.boxed())) }.boxed()
     }
 }
 
@@ -3,7 +3,7 @@ use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
 use gpui::{AnyView, App, AsyncApp, Context, Subscription, Task};
 use http_client::HttpClient;
 use language_model::{
-    AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent, TokenUsage,
 };
 use language_model::{
     LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
@@ -11,7 +11,7 @@ use language_model::{
     LanguageModelRequest, RateLimiter, Role,
 };
 use lmstudio::{
-    ChatCompletionRequest, ChatMessage, ModelType, get_models, preload_model,
+    ChatCompletionRequest, ChatMessage, ModelType, StreamOptions, get_models, preload_model,
     stream_chat_completion,
 };
 use schemars::JsonSchema;
@@ -255,6 +255,9 @@ impl LmStudioLanguageModel {
                 })
                 .collect(),
             stream: true,
+            stream_options: Some(StreamOptions {
+                include_usage: true,
+            }),
             max_tokens: Some(-1),
             stop: Some(request.stop),
             temperature: request.temperature.or(Some(0.0)),
@@ -331,20 +334,15 @@ impl LanguageModel for LmStudioLanguageModel {
         let future = self.request_limiter.stream(async move {
             let response = stream_chat_completion(http_client.as_ref(), &api_url, request).await?;
             let stream = response
-                .filter_map(|response| async move {
+                .flat_map(|response| {
+                    let mut events = Vec::new();
                     match response {
                         Ok(fragment) => {
-                            // Skip empty deltas
-                            if fragment.choices[0].delta.is_object()
-                                && fragment.choices[0].delta.as_object().unwrap().is_empty()
-                            {
-                                return None;
-                            }
-
-                            // Try to parse the delta as ChatMessage
-                            if let Ok(chat_message) = serde_json::from_value::<ChatMessage>(
-                                fragment.choices[0].delta.clone(),
-                            ) {
+                            if let Some(content) = fragment.choices.first().and_then(|choice| {
+                                // Try to parse the delta as ChatMessage, skipping empty deltas
+                                let chat_message =
+                                    serde_json::from_value::<ChatMessage>(choice.delta.clone())
+                                        .ok()?;
                                 let content = match chat_message {
                                     ChatMessage::User { content } => content,
                                     ChatMessage::Assistant { content, .. } => {
@@ -352,33 +350,30 @@ impl LanguageModel for LmStudioLanguageModel {
                                     }
                                     ChatMessage::System { content } => content,
                                 };
-                                if !content.is_empty() {
-                                    Some(Ok(content))
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
+                                (!content.is_empty()).then_some(content)
+                            }) {
+                                events.push(Ok(LanguageModelCompletionEvent::Text(content)));
+                            }
+                            if let Some(usage) = fragment.usage {
+                                events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(
+                                    TokenUsage {
+                                        input_tokens: usage.prompt_tokens,
+                                        output_tokens: usage.completion_tokens,
+                                        ..Default::default()
+                                    },
+                                )));
                             }
                         }
-                        Err(error) => Some(Err(error)),
+                        Err(error) => events.push(Err(LanguageModelCompletionError::Other(error))),
                     }
+                    futures::stream::iter(events)
                 })
                 .boxed();
             Ok(stream)
         });
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
 
-        async move {
-            Ok(future
-                .await?
-                .map(|result| {
-                    result
-                        .map(LanguageModelCompletionEvent::Text)
-                        .map_err(LanguageModelCompletionError::Other)
-                })
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(usage_tracker.track(future.await?.boxed())) }.boxed()
     }
 }
 
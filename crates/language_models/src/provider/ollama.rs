@@ -3,7 +3,7 @@ use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
 use gpui::{AnyView, App, AsyncApp, Context, Subscription, Task};
 use http_client::HttpClient;
 use language_model::{
-    AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    AuthenticateError, LanguageModelCompletionError, LanguageModelCompletionEvent, TokenUsage,
 };
 use language_model::{
     LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
@@ -343,7 +343,8 @@ impl LanguageModel for OllamaLanguageModel {
         let future = self.request_limiter.stream(async move {
             let response = stream_chat_completion(http_client.as_ref(), &api_url, request).await?;
             let stream = response
-                .filter_map(|response| async move {
+                .flat_map(|response| {
+                    let mut events = Vec::new();
                     match response {
                         Ok(delta) => {
                             let content = match delta.message {
@@ -351,26 +352,29 @@ impl LanguageModel for OllamaLanguageModel {
                                 ChatMessage::Assistant { content, .. } => content,
                                 ChatMessage::System { content } => content,
                             };
-                            Some(Ok(content))
+                            events.push(Ok(LanguageModelCompletionEvent::Text(content)));
+                            if let (Some(input_tokens), Some(output_tokens)) =
+                                (delta.prompt_eval_count, delta.eval_count)
+                            {
+                                events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(
+                                    TokenUsage {
+                                        input_tokens,
+                                        output_tokens,
+                                        ..Default::default()
+                                    },
+                                )));
+                            }
                         }
-                        Err(error) => Some(Err(error)),
+                        Err(error) => events.push(Err(LanguageModelCompletionError::Other(error))),
                     }
+                    futures::stream::iter(events)
                 })
                 .boxed();
             Ok(stream)
         });
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
 
-        async move {
-            Ok(future
-                .await?
-                .map(|result| {
-                    result
-                        .map(LanguageModelCompletionEvent::Text)
-                        .map_err(LanguageModelCompletionError::Other)
-                })
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(usage_tracker.track(future.await?.boxed())) }.boxed()
     }
 }
 
@@ -12,7 +12,7 @@ use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
     LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
     LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
-    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason,
+    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, TokenUsage,
 };
 use open_ai::{Model, ResponseStreamEvent, stream_completion};
 use schemars::JsonSchema;
@@ -330,9 +330,10 @@ impl LanguageModel for OpenAiLanguageModel {
     > {
         let request = into_open_ai(request, &self.model, self.max_output_tokens());
         let completions = self.stream_completion(request, cx);
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
         async move {
             let mapper = OpenAiEventMapper::new();
-            Ok(mapper.map_stream(completions.await?).boxed())
+            Ok(usage_tracker.track(mapper.map_stream(completions.await?).boxed()))
         }
         .boxed()
     }
@@ -401,6 +402,9 @@ pub fn into_open_ai(
         model: model.id().into(),
         messages,
         stream,
+        stream_options: stream.then_some(open_ai::StreamOptions {
+            include_usage: true,
+        }),
         stop: request.stop,
         temperature: request.temperature.unwrap_or(1.0),
         max_tokens: max_output_tokens,
@@ -422,6 +426,8 @@ pub fn into_open_ai(
             })
             .collect(),
         tool_choice: None,
+        // Requests of the same thread share their prefix, so route them to the same cache.
+        prompt_cache_key: request.thread_id,
     }
 }
 
@@ -453,13 +459,26 @@ impl OpenAiEventMapper {
         &mut self,
         event: ResponseStreamEvent,
     ) -> Vec<Result<LanguageModelCompletionEvent, LanguageModelCompletionError>> {
+        let mut events = Vec::new();
+        if let Some(usage) = event.usage.as_ref() {
+            events.push(Ok(LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                input_tokens: usage.prompt_tokens,
+                output_tokens: usage.completion_tokens,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 0,
+            })));
+        }
+
         let Some(choice) = event.choices.first() else {
-            return vec![Err(LanguageModelCompletionError::Other(anyhow!(
-                "Response contained no choices"
-            )))];
+            // The final chunk of a stream only reports the usage of the request.
+            if events.is_empty() {
+                events.push(Err(LanguageModelCompletionError::Other(anyhow!(
+                    "Response contained no choices"
+                ))));
+            }
+            return events;
         };
 
-        let mut events = Vec::new();
         if let Some(content) = choice.delta.content.clone() {
             events.push(Ok(LanguageModelCompletionEvent::Text(content)));
         }
@@ -0,0 +1,851 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::{BTreeMap, HashMap};
+use credentials_provider::CredentialsProvider;
+use editor::{Editor, EditorElement, EditorStyle};
+use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
+use gpui::{
+    AnyView, App, AsyncApp, Context, Entity, FontStyle, Subscription, Task, TextStyle, WhiteSpace,
+};
+use http_client::HttpClient;
+use language_model::{
+    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRegistry,
+    LanguageModelRequest, RateLimiter,
+};
+use open_ai::ResponseStreamEvent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use theme::ThemeSettings;
+use ui::{Icon, IconName, List, prelude::*};
+use util::ResultExt;
+
+use crate::provider::open_ai::{OpenAiEventMapper, count_open_ai_tokens, into_open_ai};
+use crate::{AllLanguageModelSettings, ui::InstructionListItem};
+
+const DEFAULT_MAX_TOKENS: usize = 8_192;
+
+/// The settings of a named OpenAI-compatible endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenAiCompatibleSettings {
+    pub display_name: Option<String>,
+    pub api_url: String,
+    pub available_models: Vec<AvailableModel>,
+    pub discover_models: bool,
+    pub headers: BTreeMap<String, String>,
+    pub supports_tools: bool,
+    pub max_tokens: usize,
+}
+
+impl Default for OpenAiCompatibleSettings {
+    fn default() -> Self {
+        Self {
+            display_name: None,
+            api_url: String::new(),
+            available_models: Vec::new(),
+            discover_models: false,
+            headers: BTreeMap::default(),
+            supports_tools: true,
+            max_tokens: DEFAULT_MAX_TOKENS,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AvailableModel {
+    /// The model's name in the endpoint's API.
+    pub name: String,
+    /// The model's name in Zed's UI, such as in the model selector dropdown menu in the assistant panel.
+    pub display_name: Option<String>,
+    /// The model's context length. Defaults to the endpoint's `max_tokens`.
+    pub max_tokens: Option<usize>,
+    pub max_output_tokens: Option<u32>,
+    /// Whether the model supports tool calls. Defaults to the endpoint's `supports_tools`.
+    pub supports_tools: Option<bool>,
+}
+
+/// Registers a language model provider for each OpenAI-compatible endpoint in the
+/// settings, keeping the registered providers in sync as the settings change.
+pub fn init(http_client: Arc<dyn HttpClient>, cx: &mut App) {
+    let mut registered_endpoints = HashMap::default();
+    update_registered_endpoints(&mut registered_endpoints, &http_client, cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        update_registered_endpoints(&mut registered_endpoints, &http_client, cx);
+    })
+    .detach();
+}
+
+/// Registers the endpoints that were added to the settings, and unregisters the ones
+/// that were removed. Endpoints whose display name changed are registered again.
+fn update_registered_endpoints(
+    registered_endpoints: &mut HashMap<Arc<str>, Option<String>>,
+    http_client: &Arc<dyn HttpClient>,
+    cx: &mut App,
+) {
+    let endpoints = AllLanguageModelSettings::get_global(cx)
+        .openai_compatible
+        .iter()
+        .map(|(endpoint, settings)| (endpoint.clone(), settings.display_name.clone()))
+        .collect::<HashMap<_, _>>();
+    if &endpoints == registered_endpoints {
+        return;
+    }
+
+    LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+        registered_endpoints.retain(|endpoint, display_name| {
+            if endpoints.get(endpoint) == Some(display_name) {
+                return true;
+            }
+            registry.unregister_provider(LanguageModelProviderId(endpoint.clone().into()), cx);
+            false
+        });
+
+        for (endpoint, display_name) in endpoints {
+            if registered_endpoints.contains_key(&endpoint) {
+                continue;
+            }
+
+            if registry
+                .provider(&LanguageModelProviderId(endpoint.clone().into()))
+                .is_some()
+            {
+                log::error!(
+                    "not registering OpenAI-compatible endpoint {endpoint:?}, as a language model provider with that name already exists"
+                );
+                continue;
+            }
+
+            let provider = OpenAiCompatibleLanguageModelProvider::new(
+                endpoint.clone(),
+                display_name.clone(),
+                http_client.clone(),
+                cx,
+            );
+            registry.register_provider(provider, cx);
+            registered_endpoints.insert(endpoint, display_name);
+        }
+    });
+}
+
+fn endpoint_settings<'a>(endpoint: &str, cx: &'a App) -> Option<&'a OpenAiCompatibleSettings> {
+    AllLanguageModelSettings::get_global(cx)
+        .openai_compatible
+        .get(endpoint)
+}
+
+pub struct OpenAiCompatibleLanguageModelProvider {
+    endpoint: Arc<str>,
+    name: LanguageModelProviderName,
+    http_client: Arc<dyn HttpClient>,
+    state: Entity<State>,
+}
+
+pub struct State {
+    endpoint: Arc<str>,
+    http_client: Arc<dyn HttpClient>,
+    /// The API key is optional, as not every server checks it.
+    api_key: Option<String>,
+    credentials_loaded: bool,
+    discovered_models: Vec<String>,
+    fetch_models_task: Option<Task<Result<()>>>,
+    _subscription: Subscription,
+}
+
+impl State {
+    fn api_url(&self, cx: &App) -> String {
+        endpoint_settings(&self.endpoint, cx)
+            .map(|settings| settings.api_url.clone())
+            .unwrap_or_default()
+    }
+
+    /// API keys are stored by provider, so that endpoints sharing an API URL,
+    /// or whose API URL changes, each keep their own key.
+    fn credentials_url(&self) -> String {
+        format!("openai-compatible://{}", self.endpoint)
+    }
+
+    fn is_authenticated(&self) -> bool {
+        self.credentials_loaded
+    }
+
+    fn reset_api_key(&self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .delete_credentials(&credentials_url, &cx)
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.api_key = None;
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn set_api_key(&mut self, api_key: String, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url();
+        cx.spawn(async move |this, cx| {
+            credentials_provider
+                .write_credentials(&credentials_url, "Bearer", api_key.as_bytes(), &cx)
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.api_key = Some(api_key);
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn authenticate(&self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.is_authenticated() {
+            return Task::ready(Ok(()));
+        }
+
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        let credentials_url = self.credentials_url();
+        let endpoint = self.endpoint.clone();
+        cx.spawn(async move |this, cx| {
+            let api_key = match credentials_provider
+                .read_credentials(&credentials_url, &cx)
+                .await?
+            {
+                Some((_, api_key)) => Some(
+                    String::from_utf8(api_key)
+                        .with_context(|| format!("invalid API key for {endpoint}"))?,
+                ),
+                None => None,
+            };
+
+            this.update(cx, |this, cx| {
+                this.api_key = api_key;
+                this.credentials_loaded = true;
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })?;
+
+            Ok(())
+        })
+    }
+
+    /// Fetches the models served by the endpoint, if model discovery is enabled.
+    fn fetch_models(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some(settings) = endpoint_settings(&self.endpoint, cx) else {
+            return Task::ready(Ok(()));
+        };
+        if !self.credentials_loaded {
+            return Task::ready(Ok(()));
+        }
+        if !settings.discover_models {
+            self.discovered_models.clear();
+            return Task::ready(Ok(()));
+        }
+
+        let http_client = self.http_client.clone();
+        let api_url = settings.api_url.clone();
+        let api_key = self.api_key.clone().unwrap_or_default();
+        let headers = settings.headers.clone().into_iter().collect::<Vec<_>>();
+        cx.spawn(async move |this, cx| {
+            let models =
+                open_ai::list_models(http_client.as_ref(), &api_url, &api_key, &headers).await?;
+
+            this.update(cx, |this, cx| {
+                this.discovered_models = models.into_iter().map(|model| model.id).collect();
+                this.discovered_models.sort();
+                cx.notify();
+            })
+        })
+    }
+
+    fn restart_fetch_models_task(&mut self, cx: &mut Context<Self>) {
+        let task = self.fetch_models(cx);
+        self.fetch_models_task.replace(task);
+    }
+}
+
+impl OpenAiCompatibleLanguageModelProvider {
+    pub fn new(
+        endpoint: Arc<str>,
+        display_name: Option<String>,
+        http_client: Arc<dyn HttpClient>,
+        cx: &mut App,
+    ) -> Self {
+        let state = cx.new(|cx| {
+            let subscription = cx.observe_global::<SettingsStore>({
+                let endpoint = endpoint.clone();
+                let mut settings = endpoint_settings(&endpoint, cx).cloned();
+                move |this: &mut State, cx| {
+                    let new_settings = endpoint_settings(&endpoint, cx);
+                    if settings.as_ref() != new_settings {
+                        settings = new_settings.cloned();
+                        this.restart_fetch_models_task(cx);
+                        cx.notify();
+                    }
+                }
+            });
+
+            State {
+                endpoint: endpoint.clone(),
+                http_client: http_client.clone(),
+                api_key: None,
+                credentials_loaded: false,
+                discovered_models: Vec::new(),
+                fetch_models_task: None,
+                _subscription: subscription,
+            }
+        });
+
+        Self {
+            name: LanguageModelProviderName(
+                display_name.map_or_else(|| endpoint.clone().into(), Into::into),
+            ),
+            endpoint,
+            http_client,
+            state,
+        }
+    }
+
+    fn create_language_model(
+        &self,
+        model: open_ai::Model,
+        supports_tools: bool,
+    ) -> Arc<dyn LanguageModel> {
+        Arc::new(OpenAiCompatibleLanguageModel {
+            id: LanguageModelId::from(model.id().to_string()),
+            endpoint: self.endpoint.clone(),
+            provider_name: self.name.clone(),
+            model,
+            supports_tools,
+            state: self.state.clone(),
+            http_client: self.http_client.clone(),
+            request_limiter: RateLimiter::new(4),
+        })
+    }
+}
+
+impl LanguageModelProviderState for OpenAiCompatibleLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for OpenAiCompatibleLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId(self.endpoint.clone().into())
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.name.clone()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Ai
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        self.provided_models(cx).into_iter().next()
+    }
+
+    fn default_fast_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        self.default_model(cx)
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        let Some(settings) = endpoint_settings(&self.endpoint, cx) else {
+            return Vec::new();
+        };
+
+        let mut models = BTreeMap::default();
+
+        // Add models discovered through the endpoint's API
+        for name in &self.state.read(cx).discovered_models {
+            models.insert(
+                name.clone(),
+                (
+                    open_ai::Model::Custom {
+                        name: name.clone(),
+                        display_name: None,
+                        max_tokens: settings.max_tokens,
+                        max_output_tokens: None,
+                        max_completion_tokens: None,
+                    },
+                    settings.supports_tools,
+                ),
+            );
+        }
+
+        // Override with available models from settings
+        for model in &settings.available_models {
+            models.insert(
+                model.name.clone(),
+                (
+                    open_ai::Model::Custom {
+                        name: model.name.clone(),
+                        display_name: model.display_name.clone(),
+                        max_tokens: model.max_tokens.unwrap_or(settings.max_tokens),
+                        max_output_tokens: model.max_output_tokens,
+                        max_completion_tokens: None,
+                    },
+                    model.supports_tools.unwrap_or(settings.supports_tools),
+                ),
+            );
+        }
+
+        models
+            .into_values()
+            .map(|(model, supports_tools)| self.create_language_model(model, supports_tools))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, window: &mut Window, cx: &mut App) -> AnyView {
+        cx.new(|cx| ConfigurationView::new(self.state.clone(), window, cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.reset_api_key(cx))
+    }
+}
+
+pub struct OpenAiCompatibleLanguageModel {
+    id: LanguageModelId,
+    endpoint: Arc<str>,
+    provider_name: LanguageModelProviderName,
+    model: open_ai::Model,
+    supports_tools: bool,
+    state: Entity<State>,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl OpenAiCompatibleLanguageModel {
+    fn stream_completion(
+        &self,
+        request: open_ai::Request,
+        cx: &AsyncApp,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<ResponseStreamEvent>>>> {
+        let http_client = self.http_client.clone();
+        let Ok((api_key, settings)) = cx.read_entity(&self.state, |state, cx| {
+            (
+                state.api_key.clone(),
+                endpoint_settings(&self.endpoint, cx).cloned(),
+            )
+        }) else {
+            return futures::future::ready(Err(anyhow!("App state dropped"))).boxed();
+        };
+
+        let endpoint = self.endpoint.clone();
+        let future = self.request_limiter.stream(async move {
+            let settings = settings.ok_or_else(|| {
+                anyhow!("OpenAI-compatible endpoint {endpoint} is not configured")
+            })?;
+            let api_key = api_key.unwrap_or_default();
+            let headers = settings.headers.into_iter().collect::<Vec<_>>();
+            let request = open_ai::stream_completion_with_headers(
+                http_client.as_ref(),
+                &settings.api_url,
+                &api_key,
+                &headers,
+                request,
+            );
+            let response = request.await?;
+            Ok(response)
+        });
+
+        async move { Ok(future.await?.boxed()) }.boxed()
+    }
+}
+
+impl LanguageModel for OpenAiCompatibleLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.display_name().to_string())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        LanguageModelProviderId(self.endpoint.clone().into())
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.supports_tools
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("openai_compatible/{}", self.model.id())
+    }
+
+    fn max_token_count(&self) -> usize {
+        self.model.max_token_count()
+    }
+
+    fn max_output_tokens(&self) -> Option<u32> {
+        self.model.max_output_tokens()
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<usize>> {
+        count_open_ai_tokens(request, self.model.clone(), cx)
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+        >,
+    > {
+        let mut request = into_open_ai(request, &self.model, self.max_output_tokens());
+        if !self.supports_tools {
+            request.tools.clear();
+        }
+        // Not every OpenAI-compatible API accepts parameters it doesn't know about.
+        request.prompt_cache_key = None;
+        let completions = self.stream_completion(request, cx);
+        let usage_tracker = language_model::track_usage(self.provider_id(), self.id());
+        async move {
+            let mapper = OpenAiEventMapper::new();
+            Ok(usage_tracker.track(mapper.map_stream(completions.await?).boxed()))
+        }
+        .boxed()
+    }
+}
+
+struct ConfigurationView {
+    api_key_editor: Entity<Editor>,
+    state: Entity<State>,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: Entity<State>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let api_key_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("sk-000000000000000000000000000000000000000000000000", cx);
+            editor
+        });
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn_in(window, {
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    // We don't log an error, because "not signed in" is also an error.
+                    let _ = task.await;
+                }
+
+                this.update(cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let api_key = self.api_key_editor.read(cx).text(cx);
+        if api_key.is_empty() {
+            return;
+        }
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(api_key, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn reset_api_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.api_key_editor
+            .update(cx, |editor, cx| editor.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state.update(cx, |state, cx| state.reset_api_key(cx))?.await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn render_api_key_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            white_space: WhiteSpace::Normal,
+            ..Default::default()
+        };
+        EditorElement::new(
+            &self.api_key_editor,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let has_api_key = state.api_key.is_some();
+        let api_url = state.api_url(cx);
+
+        if self.load_credentials_task.is_some() {
+            div().child(Label::new("Loading credentials...")).into_any()
+        } else if !has_api_key {
+            v_flex()
+                .size_full()
+                .on_action(cx.listener(Self::save_api_key))
+                .child(Label::new(format!(
+                    "Requests to the OpenAI-compatible API at {api_url} are sent without an API key."
+                )))
+                .child(
+                    List::new()
+                        .child(InstructionListItem::text_only(
+                            "An API key is only needed if the server checks it",
+                        ))
+                        .child(InstructionListItem::text_only(
+                            "Paste your API key below and hit enter to send it with every request",
+                        )),
+                )
+                .child(
+                    h_flex()
+                        .w_full()
+                        .my_2()
+                        .px_2()
+                        .py_1()
+                        .bg(cx.theme().colors().editor_background)
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .rounded_sm()
+                        .child(self.render_api_key_editor(cx)),
+                )
+                .into_any()
+        } else {
+            h_flex()
+                .mt_1()
+                .p_1()
+                .justify_between()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().background)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(Label::new("API key configured.")),
+                )
+                .child(
+                    Button::new("reset-key", "Reset Key")
+                        .label_size(LabelSize::Small)
+                        .icon(Some(IconName::Trash))
+                        .icon_size(IconSize::Small)
+                        .icon_position(IconPosition::Start)
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.reset_api_key(window, cx)),
+                        ),
+                )
+                .into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            AllLanguageModelSettings::register(cx);
+            LanguageModelRegistry::test(cx);
+        });
+    }
+
+    fn set_user_settings(content: &str, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.set_user_settings(content, cx).unwrap();
+            });
+        });
+    }
+
+    fn registered_provider_names(cx: &mut TestAppContext) -> Vec<(String, String)> {
+        cx.update(|cx| {
+            let mut providers = LanguageModelRegistry::read_global(cx)
+                .providers()
+                .into_iter()
+                .map(|provider| (provider.id().0.to_string(), provider.name().0.to_string()))
+                .collect::<Vec<_>>();
+            providers.sort();
+            providers
+        })
+    }
+
+    #[gpui::test]
+    fn test_settings(cx: &mut TestAppContext) {
+        init_test(cx);
+        set_user_settings(
+            r#"{
+                "language_models": {
+                    "openai_compatible": {
+                        "vllm": {
+                            "api_url": "http://localhost:8000/v1",
+                            "discover_models": true
+                        },
+                        "litellm": {
+                            "display_name": "LiteLLM",
+                            "api_url": "https://litellm.example.com/v1",
+                            "headers": { "X-Team": "editor" },
+                            "supports_tools": false,
+                            "max_tokens": 32768,
+                            "available_models": [
+                                { "name": "qwen", "max_tokens": 131072, "supports_tools": true }
+                            ]
+                        }
+                    }
+                }
+            }"#,
+            cx,
+        );
+
+        cx.update(|cx| {
+            let settings = &AllLanguageModelSettings::get_global(cx).openai_compatible;
+            assert_eq!(settings.len(), 2);
+            assert_eq!(
+                settings["vllm"],
+                OpenAiCompatibleSettings {
+                    api_url: "http://localhost:8000/v1".into(),
+                    discover_models: true,
+                    ..Default::default()
+                }
+            );
+            assert_eq!(
+                settings["litellm"],
+                OpenAiCompatibleSettings {
+                    display_name: Some("LiteLLM".into()),
+                    api_url: "https://litellm.example.com/v1".into(),
+                    available_models: vec![AvailableModel {
+                        name: "qwen".into(),
+                        display_name: None,
+                        max_tokens: Some(131072),
+                        max_output_tokens: None,
+                        supports_tools: Some(true),
+                    }],
+                    discover_models: false,
+                    headers: BTreeMap::from_iter([("X-Team".into(), "editor".into())]),
+                    supports_tools: false,
+                    max_tokens: 32768,
+                }
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_provider_registration(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fake_provider_id = cx.update(|cx| {
+            LanguageModelRegistry::read_global(cx).providers()[0]
+                .id()
+                .0
+                .to_string()
+        });
+        cx.update(|cx| init(FakeHttpClient::with_404_response(), cx));
+        assert_eq!(registered_provider_names(cx).len(), 1);
+
+        // Endpoints are registered as they are added, unless their name is already taken.
+        set_user_settings(
+            &format!(
+                r#"{{
+                    "language_models": {{
+                        "openai_compatible": {{
+                            "vllm": {{ "api_url": "http://localhost:8000/v1" }},
+                            "litellm": {{ "display_name": "LiteLLM", "api_url": "http://localhost:4000" }},
+                            "{fake_provider_id}": {{ "api_url": "http://localhost:1234/v1" }}
+                        }}
+                    }}
+                }}"#
+            ),
+            cx,
+        );
+        let providers = registered_provider_names(cx);
+        assert!(providers.contains(&("vllm".into(), "vllm".into())));
+        assert!(providers.contains(&("litellm".into(), "LiteLLM".into())));
+        assert_eq!(providers.len(), 3);
+
+        // Renamed endpoints are registered again, and removed ones are unregistered.
+        set_user_settings(
+            r#"{
+                "language_models": {
+                    "openai_compatible": {
+                        "vllm": { "display_name": "vLLM", "api_url": "http://localhost:8000/v1" }
+                    }
+                }
+            }"#,
+            cx,
+        );
+        let providers = registered_provider_names(cx);
+        assert!(providers.contains(&("vllm".into(), "vLLM".into())));
+        assert!(!providers.iter().any(|(id, _)| id == "litellm"));
+        assert_eq!(providers.len(), 2);
+    }
+}
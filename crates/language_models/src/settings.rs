@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use collections::BTreeMap;
+
 use anyhow::Result;
 use gpui::App;
 use language_model::LanguageModelCacheConfiguration;
@@ -20,6 +22,7 @@ use crate::provider::{
     mistral::MistralSettings,
     ollama::OllamaSettings,
     open_ai::OpenAiSettings,
+    open_ai_compatible::OpenAiCompatibleSettings,
 };
 
 /// Initializes the language model settings.
@@ -61,6 +64,7 @@ pub struct AllLanguageModelSettings {
     pub bedrock: AmazonBedrockSettings,
     pub ollama: OllamaSettings,
     pub openai: OpenAiSettings,
+    pub openai_compatible: BTreeMap<Arc<str>, OpenAiCompatibleSettings>,
     pub zed_dot_dev: ZedDotDevSettings,
     pub google: GoogleSettings,
     pub copilot_chat: CopilotChatSettings,
@@ -76,6 +80,8 @@ pub struct AllLanguageModelSettingsContent {
     pub ollama: Option<OllamaSettingsContent>,
     pub lmstudio: Option<LmStudioSettingsContent>,
     pub openai: Option<OpenAiSettingsContent>,
+    /// Named OpenAI-compatible endpoints, each of which appears as a separate provider.
+    pub openai_compatible: Option<BTreeMap<Arc<str>, OpenAiCompatibleSettingsContent>>,
     #[serde(rename = "zed.dev")]
     pub zed_dot_dev: Option<ZedDotDevSettingsContent>,
     pub google: Option<GoogleSettingsContent>,
@@ -257,6 +263,23 @@ pub struct OpenAiSettingsContentV1 {
     pub available_models: Option<Vec<provider::open_ai::AvailableModel>>,
 }
 
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct OpenAiCompatibleSettingsContent {
+    /// The name of the provider in Zed's UI. Defaults to the name of the endpoint.
+    pub display_name: Option<String>,
+    /// The base URL of the API, such as `http://localhost:8000/v1`.
+    pub api_url: Option<String>,
+    pub available_models: Option<Vec<provider::open_ai_compatible::AvailableModel>>,
+    /// Whether to list the models served by the endpoint through its `/models` API.
+    pub discover_models: Option<bool>,
+    /// Headers to send along with every request.
+    pub headers: Option<BTreeMap<String, String>>,
+    /// Whether the endpoint's models support tool calls.
+    pub supports_tools: Option<bool>,
+    /// The context length of the endpoint's models.
+    pub max_tokens: Option<usize>,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct GoogleSettingsContent {
     pub api_url: Option<String>,
@@ -380,6 +403,34 @@ impl settings::Settings for AllLanguageModelSettings {
                 &mut settings.openai.available_models,
                 openai.as_ref().and_then(|s| s.available_models.clone()),
             );
+
+            // OpenAI-compatible endpoints
+            for (endpoint, content) in value.openai_compatible.iter().flatten() {
+                let endpoint_settings = settings
+                    .openai_compatible
+                    .entry(endpoint.clone())
+                    .or_default();
+                merge(
+                    &mut endpoint_settings.display_name,
+                    content.display_name.clone().map(Some),
+                );
+                merge(&mut endpoint_settings.api_url, content.api_url.clone());
+                merge(
+                    &mut endpoint_settings.available_models,
+                    content.available_models.clone(),
+                );
+                merge(
+                    &mut endpoint_settings.discover_models,
+                    content.discover_models,
+                );
+                merge(&mut endpoint_settings.headers, content.headers.clone());
+                merge(
+                    &mut endpoint_settings.supports_tools,
+                    content.supports_tools,
+                );
+                merge(&mut endpoint_settings.max_tokens, content.max_tokens);
+            }
+
             merge(
                 &mut settings.zed_dot_dev.available_models,
                 value
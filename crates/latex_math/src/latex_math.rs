@@ -0,0 +1,1016 @@
+//! A small typesetter for LaTeX math.
+//!
+//! It understands the subset of math mode most commonly found in documents and
+//! produced by libraries such as SymPy: symbols, groups, super- and subscripts,
+//! fractions, square roots, upright and bold text, `\left`/`\right` delimiters
+//! and spacing commands. The result is laid out with approximate font metrics and
+//! written out as an SVG. Commands that are not understood are rendered as their
+//! name, so the output stays readable.
+//!
+//! It is shared by the Markdown preview and the REPL's LaTeX outputs.
+
+use std::fmt::Write as _;
+
+use gpui::Rgba;
+
+const SCRIPT_SCALE: f32 = 0.7;
+const FRACTION_SCALE: f32 = 0.85;
+const MIN_SCALE: f32 = 0.5;
+/// Height of the math axis (where fraction bars sit) above the baseline, in ems.
+const AXIS_HEIGHT: f32 = 0.25;
+const ASCENT: f32 = 0.75;
+const DESCENT: f32 = 0.25;
+const RULE_THICKNESS: f32 = 0.06;
+
+/// Typeset math, ready to be rasterized.
+pub struct MathSvg {
+    pub svg: String,
+    /// The size the math should be displayed at, in pixels.
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Removes the math delimiters that surround a formula, like `$$` or `\[`.
+pub fn strip_delimiters(latex: &str) -> &str {
+    let latex = latex.trim();
+    for (open, close) in [("$$", "$$"), ("\\[", "\\]"), ("\\(", "\\)"), ("$", "$")] {
+        if let Some(inner) = latex
+            .strip_prefix(open)
+            .and_then(|latex| latex.strip_suffix(close))
+        {
+            return inner.trim();
+        }
+    }
+    latex
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FontStyle {
+    Italic,
+    Upright,
+    Bold,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text {
+        text: String,
+        style: FontStyle,
+    },
+    /// A symbol that gets extra space on either side, like `=` or `+`.
+    Operator(String),
+    /// A large operator such as `\sum` or `\int`.
+    LargeOperator(String),
+    Group(Vec<Node>),
+    Scripts {
+        base: Box<Node>,
+        superscript: Option<Box<Node>>,
+        subscript: Option<Box<Node>>,
+    },
+    Fraction(Box<Node>, Box<Node>),
+    Sqrt(Box<Node>),
+    Delimited {
+        left: String,
+        body: Box<Node>,
+        right: String,
+    },
+    Space(f32),
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    /// Parses nodes until the end of input, a closing brace, or `\right`.
+    fn parse_sequence(&mut self) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek().copied() {
+                None | Some('}') => break,
+                Some('\\') if self.peek_command() == "right" => break,
+                _ => {}
+            }
+            if let Some(node) = self.parse_scripts() {
+                nodes.push(node);
+            }
+        }
+        nodes
+    }
+
+    fn peek_command(&self) -> String {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.take_while(|c| c.is_ascii_alphabetic()).collect()
+    }
+
+    fn parse_scripts(&mut self) -> Option<Node> {
+        let mut base = self.parse_atom()?;
+        loop {
+            self.skip_whitespace();
+            let (superscript, subscript) = match self.chars.peek() {
+                Some('^') => (true, false),
+                Some('_') => (false, true),
+                _ => return Some(base),
+            };
+            self.chars.next();
+            let script = Box::new(self.parse_argument());
+
+            base = match base {
+                Node::Scripts {
+                    base,
+                    superscript: existing_superscript,
+                    subscript: existing_subscript,
+                } if (superscript && existing_superscript.is_none())
+                    || (subscript && existing_subscript.is_none()) =>
+                {
+                    Node::Scripts {
+                        base,
+                        superscript: existing_superscript.or(superscript.then(|| script.clone())),
+                        subscript: existing_subscript.or(subscript.then_some(script)),
+                    }
+                }
+                base => Node::Scripts {
+                    base: Box::new(base),
+                    superscript: superscript.then(|| script.clone()),
+                    subscript: subscript.then_some(script),
+                },
+            };
+        }
+    }
+
+    /// Parses a single argument: either a braced group or a single atom.
+    fn parse_argument(&mut self) -> Node {
+        self.skip_whitespace();
+        self.parse_atom().unwrap_or_else(|| Node::Group(Vec::new()))
+    }
+
+    /// Reads the raw text of a braced group, as used by `\text{...}`.
+    fn parse_raw_argument(&mut self) -> String {
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'{').is_none() {
+            return self.chars.next().map(String::from).unwrap_or_default();
+        }
+        let mut depth = 0;
+        let mut text = String::new();
+        for c in self.chars.by_ref() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            text.push(c);
+        }
+        text
+    }
+
+    fn parse_delimiter(&mut self) -> String {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some('.') | None => String::new(),
+            Some('\\') => {
+                let name = self.parse_command_name();
+                match name.as_str() {
+                    "{" | "}" | "|" => name,
+                    "langle" => "⟨".to_string(),
+                    "rangle" => "⟩".to_string(),
+                    "lvert" | "rvert" | "vert" => "|".to_string(),
+                    "lVert" | "rVert" | "Vert" => "‖".to_string(),
+                    "lfloor" => "⌊".to_string(),
+                    "rfloor" => "⌋".to_string(),
+                    "lceil" => "⌈".to_string(),
+                    "rceil" => "⌉".to_string(),
+                    _ => String::new(),
+                }
+            }
+            Some(c) => c.to_string(),
+        }
+    }
+
+    fn parse_command_name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            name.push(c);
+        }
+        if name.is_empty() {
+            if let Some(c) = self.chars.next() {
+                name.push(c);
+            }
+        }
+        name
+    }
+
+    fn parse_atom(&mut self) -> Option<Node> {
+        let c = self.chars.next()?;
+        let node = match c {
+            '{' => {
+                let nodes = self.parse_sequence();
+                self.chars.next_if_eq(&'}');
+                Node::Group(nodes)
+            }
+            '\\' => self.parse_command(),
+            '0'..='9' | '.' => {
+                let mut text = c.to_string();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    text.push(c);
+                }
+                Node::Text {
+                    text,
+                    style: FontStyle::Upright,
+                }
+            }
+            '+' | '=' | '<' | '>' => Node::Operator(c.to_string()),
+            '-' => Node::Operator("−".to_string()),
+            '*' => Node::Operator("∗".to_string()),
+            '&' | '~' => Node::Space(0.25),
+            '^' | '_' => {
+                // A script without a base, like `^2` at the start of the input.
+                let script = self.parse_argument();
+                return Some(Node::Scripts {
+                    base: Box::new(Node::Group(Vec::new())),
+                    superscript: (c == '^').then(|| Box::new(script.clone())),
+                    subscript: (c == '_').then(|| Box::new(script)),
+                });
+            }
+            c if c.is_alphabetic() => Node::Text {
+                text: c.to_string(),
+                style: FontStyle::Italic,
+            },
+            c => Node::Text {
+                text: c.to_string(),
+                style: FontStyle::Upright,
+            },
+        };
+        Some(node)
+    }
+
+    fn parse_command(&mut self) -> Node {
+        let name = self.parse_command_name();
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let numerator = self.parse_argument();
+                let denominator = self.parse_argument();
+                Node::Fraction(Box::new(numerator), Box::new(denominator))
+            }
+            "binom" => {
+                let top = self.parse_argument();
+                let bottom = self.parse_argument();
+                Node::Delimited {
+                    left: "(".to_string(),
+                    body: Box::new(Node::Fraction(Box::new(top), Box::new(bottom))),
+                    right: ")".to_string(),
+                }
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&'[').is_some() {
+                    // The root index is not drawn; skip over it.
+                    while self.chars.next().is_some_and(|c| c != ']') {}
+                }
+                Node::Sqrt(Box::new(self.parse_argument()))
+            }
+            "text" | "textrm" | "mathrm" | "operatorname" | "textnormal" | "mbox" => Node::Text {
+                text: self.parse_raw_argument(),
+                style: FontStyle::Upright,
+            },
+            "mathbf" | "textbf" | "boldsymbol" | "mathbb" => Node::Text {
+                text: self.parse_raw_argument(),
+                style: FontStyle::Bold,
+            },
+            "mathit" | "textit" | "mathcal" => Node::Text {
+                text: self.parse_raw_argument(),
+                style: FontStyle::Italic,
+            },
+            "left" => {
+                let left = self.parse_delimiter();
+                let body = Node::Group(self.parse_sequence());
+                if self.peek_command() == "right" {
+                    self.chars.next();
+                    self.parse_command_name();
+                }
+                let right = self.parse_delimiter();
+                Node::Delimited {
+                    left,
+                    body: Box::new(body),
+                    right,
+                }
+            }
+            "right" => Node::Group(Vec::new()),
+            "displaystyle" | "textstyle" | "scriptstyle" | "limits" | "nolimits" | "big"
+            | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr" => {
+                Node::Group(Vec::new())
+            }
+            "," | "thinspace" => Node::Space(0.17),
+            ":" | ">" | "medspace" => Node::Space(0.22),
+            ";" | "thickspace" => Node::Space(0.28),
+            "!" => Node::Space(-0.17),
+            " " => Node::Space(0.25),
+            "quad" => Node::Space(1.0),
+            "qquad" => Node::Space(2.0),
+            "\\" => Node::Space(1.0),
+            "{" | "}" | "%" | "$" | "_" | "#" | "&" => Node::Text {
+                text: name,
+                style: FontStyle::Upright,
+            },
+            "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "sinh" | "cosh" | "tanh" | "arcsin"
+            | "arccos" | "arctan" | "log" | "ln" | "exp" | "lim" | "max" | "min" | "sup"
+            | "inf" | "det" | "dim" | "ker" | "deg" | "gcd" | "arg" | "Pr" => Node::Text {
+                text: name,
+                style: FontStyle::Upright,
+            },
+            _ => {
+                if let Some(symbol) = large_operator(&name) {
+                    Node::LargeOperator(symbol.to_string())
+                } else if let Some(symbol) = binary_operator(&name) {
+                    Node::Operator(symbol.to_string())
+                } else if let Some(symbol) = symbol(&name) {
+                    let style = if name.chars().next().is_some_and(|c| c.is_lowercase())
+                        && symbol.chars().all(|c| c.is_alphabetic())
+                    {
+                        FontStyle::Italic
+                    } else {
+                        FontStyle::Upright
+                    };
+                    Node::Text {
+                        text: symbol.to_string(),
+                        style,
+                    }
+                } else {
+                    Node::Text {
+                        text: name,
+                        style: FontStyle::Upright,
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn large_operator(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        _ => return None,
+    })
+}
+
+fn binary_operator(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "times" => "×",
+        "cdot" => "·",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "otimes" => "⊗",
+        "oplus" => "⊕",
+        "circ" => "∘",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "mid" => "|",
+        _ => return None,
+    })
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "forall" => "∀",
+        "exists" => "∃",
+        "emptyset" | "varnothing" => "∅",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "aleph" => "ℵ",
+        "prime" => "′",
+        "cdots" | "dots" | "ldots" => "…",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lbrace" => "{",
+        "rbrace" => "}",
+        "vert" | "lvert" | "rvert" => "|",
+        "Vert" | "lVert" | "rVert" => "‖",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "neg" | "lnot" => "¬",
+        "angle" => "∠",
+        "degree" => "°",
+        _ => return None,
+    })
+}
+
+/// A glyph or rule positioned relative to the origin of its enclosing box.
+enum Item {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        style: FontStyle,
+        text: String,
+    },
+    Polyline {
+        points: Vec<(f32, f32)>,
+        thickness: f32,
+    },
+}
+
+impl Item {
+    fn translate(&mut self, dx: f32, dy: f32) {
+        match self {
+            Item::Text { x, y, .. } => {
+                *x += dx;
+                *y += dy;
+            }
+            Item::Polyline { points, .. } => {
+                for (x, y) in points {
+                    *x += dx;
+                    *y += dy;
+                }
+            }
+        }
+    }
+}
+
+/// A laid out node. The origin is on the baseline at the left edge, with y
+/// growing downwards as in SVG.
+#[derive(Default)]
+struct LayoutBox {
+    width: f32,
+    ascent: f32,
+    descent: f32,
+    items: Vec<Item>,
+}
+
+impl LayoutBox {
+    fn append(&mut self, mut other: LayoutBox, dx: f32, dy: f32) {
+        for item in &mut other.items {
+            item.translate(dx, dy);
+        }
+        self.items.extend(other.items);
+        self.ascent = self.ascent.max(other.ascent - dy);
+        self.descent = self.descent.max(other.descent + dy);
+    }
+
+    fn push_horizontal(&mut self, other: LayoutBox) {
+        let x = self.width;
+        self.width += other.width;
+        self.append(other, x, 0.0);
+    }
+}
+
+fn glyph_width(c: char) -> f32 {
+    match c {
+        'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '!' | '\'' | '|' | '′' => 0.3,
+        'f' | 't' | 'r' | '(' | ')' | '[' | ']' | '{' | '}' | 'I' | '1' | ' ' => 0.4,
+        'm' | 'w' | 'M' | 'W' | '…' => 0.85,
+        c if c.is_ascii_uppercase() => 0.7,
+        c if c.is_ascii_alphanumeric() => 0.52,
+        _ => 0.7,
+    }
+}
+
+fn text_box(text: &str, style: FontStyle, size: f32) -> LayoutBox {
+    let width = text.chars().map(glyph_width).sum::<f32>() * size;
+    LayoutBox {
+        width,
+        ascent: ASCENT * size,
+        descent: DESCENT * size,
+        items: vec![Item::Text {
+            x: 0.0,
+            y: 0.0,
+            size,
+            style,
+            text: text.to_string(),
+        }],
+    }
+}
+
+fn layout(node: &Node, size: f32, base_size: f32) -> LayoutBox {
+    let smaller = |scale: f32| (size * scale).max(base_size * MIN_SCALE);
+
+    match node {
+        Node::Text { text, style } => text_box(text, *style, size),
+        Node::Operator(text) => {
+            let padding = 0.22 * size;
+            let mut layout = LayoutBox {
+                width: padding,
+                ..Default::default()
+            };
+            layout.push_horizontal(text_box(text, FontStyle::Upright, size));
+            layout.width += padding;
+            layout
+        }
+        Node::LargeOperator(text) => {
+            let operator_size = size * 1.4;
+            let operator = text_box(text, FontStyle::Upright, operator_size);
+            // Center the enlarged glyph on the math axis.
+            let y = (ASCENT - DESCENT) / 2.0 * operator_size - AXIS_HEIGHT * size;
+            let mut layout = LayoutBox {
+                width: operator.width + 0.1 * size,
+                ..Default::default()
+            };
+            layout.append(operator, 0.0, y);
+            layout
+        }
+        Node::Group(nodes) => {
+            let mut layout = LayoutBox::default();
+            for node in nodes {
+                layout.push_horizontal(self::layout(node, size, base_size));
+            }
+            layout
+        }
+        Node::Space(width) => LayoutBox {
+            width: width * size,
+            ..Default::default()
+        },
+        Node::Scripts {
+            base,
+            superscript,
+            subscript,
+        } => {
+            let mut layout = self::layout(base, size, base_size);
+            let script_size = smaller(SCRIPT_SCALE);
+            let x = layout.width;
+            let mut width: f32 = 0.0;
+
+            if let Some(superscript) = superscript {
+                let script = self::layout(superscript, script_size, base_size);
+                let shift = (0.45 * size).max(layout.ascent - 0.4 * size) + script.descent
+                    - DESCENT * script_size;
+                width = width.max(script.width);
+                layout.append(script, x, -shift);
+            }
+            if let Some(subscript) = subscript {
+                let script = self::layout(subscript, script_size, base_size);
+                let shift = (0.2 * size).max(layout.descent - 0.1 * size);
+                width = width.max(script.width);
+                layout.append(script, x, shift);
+            }
+
+            layout.width += width + 0.05 * size;
+            layout
+        }
+        Node::Fraction(numerator, denominator) => {
+            let part_size = smaller(FRACTION_SCALE);
+            let numerator = self::layout(numerator, part_size, base_size);
+            let denominator = self::layout(denominator, part_size, base_size);
+            let padding = 0.12 * size;
+            let width = numerator.width.max(denominator.width) + 2.0 * padding;
+            let axis = -AXIS_HEIGHT * size;
+            let gap = 0.15 * size;
+
+            let mut layout = LayoutBox {
+                width,
+                ascent: -axis,
+                descent: 0.0,
+                items: vec![Item::Polyline {
+                    points: vec![(0.0, axis), (width, axis)],
+                    thickness: RULE_THICKNESS * size,
+                }],
+            };
+            let numerator_y = axis - gap - numerator.descent;
+            let denominator_y = axis + gap + denominator.ascent;
+            let numerator_x = (width - numerator.width) / 2.0;
+            let denominator_x = (width - denominator.width) / 2.0;
+            layout.append(numerator, numerator_x, numerator_y);
+            layout.append(denominator, denominator_x, denominator_y);
+            layout
+        }
+        Node::Sqrt(body) => {
+            let body = self::layout(body, size, base_size);
+            let gap = 0.12 * size;
+            let thickness = RULE_THICKNESS * size;
+            let top = -(body.ascent + gap);
+            let bottom = body.descent;
+            let radical_width = 0.55 * size;
+            let width = radical_width + body.width + 0.1 * size;
+
+            let mut layout = LayoutBox {
+                width,
+                ascent: -top + thickness,
+                descent: bottom,
+                items: vec![Item::Polyline {
+                    points: vec![
+                        (0.05 * size, (top + bottom) / 2.0 + 0.1 * size),
+                        (0.18 * size, (top + bottom) / 2.0),
+                        (0.3 * size, bottom),
+                        (0.48 * size, top),
+                        (width, top),
+                    ],
+                    thickness,
+                }],
+            };
+            layout.append(body, radical_width, 0.0);
+            layout
+        }
+        Node::Delimited { left, body, right } => {
+            let body = self::layout(body, size, base_size);
+            let height = (body.ascent + body.descent).max((ASCENT + DESCENT) * size);
+            let delimiter_size = height / (ASCENT + DESCENT);
+            // Vertically center the delimiters on the body.
+            let center = (body.descent - body.ascent) / 2.0;
+            let delimiter_y = center + (ASCENT - DESCENT) / 2.0 * delimiter_size;
+
+            let mut layout = LayoutBox::default();
+            if !left.is_empty() {
+                let delimiter = text_box(left, FontStyle::Upright, delimiter_size);
+                layout.width = delimiter.width.min(0.6 * size);
+                layout.append(delimiter, 0.0, delimiter_y);
+            }
+            layout.push_horizontal(body);
+            if !right.is_empty() {
+                let delimiter = text_box(right, FontStyle::Upright, delimiter_size);
+                let x = layout.width;
+                layout.width += delimiter.width.min(0.6 * size);
+                layout.append(delimiter, x, delimiter_y);
+            }
+            layout
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Approximates `latex` as plain text, for math that has to flow with the
+/// surrounding text. Scripts use Unicode super- and subscript characters where
+/// they exist.
+pub fn render_math_text(latex: &str) -> String {
+    let nodes = Parser::new(strip_delimiters(latex)).parse_sequence();
+    let mut text = String::new();
+    for node in &nodes {
+        write_plain_text(node, &mut text);
+    }
+    text.trim().to_string()
+}
+
+fn write_plain_text(node: &Node, text: &mut String) {
+    match node {
+        Node::Text { text: value, .. } | Node::LargeOperator(value) => text.push_str(value),
+        Node::Operator(value) => {
+            text.push(' ');
+            text.push_str(value);
+            text.push(' ');
+        }
+        Node::Group(nodes) => {
+            for node in nodes {
+                write_plain_text(node, text);
+            }
+        }
+        Node::Scripts {
+            base,
+            superscript,
+            subscript,
+        } => {
+            write_plain_text(base, text);
+            if let Some(subscript) = subscript {
+                write_plain_script(subscript, '_', subscript_char, text);
+            }
+            if let Some(superscript) = superscript {
+                write_plain_script(superscript, '^', superscript_char, text);
+            }
+        }
+        Node::Fraction(numerator, denominator) => {
+            text.push('(');
+            write_plain_text(numerator, text);
+            text.push_str(")/(");
+            write_plain_text(denominator, text);
+            text.push(')');
+        }
+        Node::Sqrt(body) => {
+            text.push_str("√(");
+            write_plain_text(body, text);
+            text.push(')');
+        }
+        Node::Delimited { left, body, right } => {
+            text.push_str(left);
+            write_plain_text(body, text);
+            text.push_str(right);
+        }
+        Node::Space(_) => text.push(' '),
+    }
+}
+
+fn write_plain_script(
+    script: &Node,
+    marker: char,
+    map_char: fn(char) -> Option<char>,
+    text: &mut String,
+) {
+    let mut plain = String::new();
+    write_plain_text(script, &mut plain);
+    let plain = plain.trim();
+    if let Some(mapped) = plain.chars().map(map_char).collect::<Option<String>>() {
+        text.push_str(&mapped);
+    } else if plain.chars().count() == 1 {
+        text.push(marker);
+        text.push_str(plain);
+    } else {
+        text.push(marker);
+        text.push('(');
+        text.push_str(plain);
+        text.push(')');
+    }
+}
+
+pub fn superscript_char(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'n' => 'ⁿ',
+        'i' => 'ⁱ',
+        _ => return None,
+    })
+}
+
+pub fn subscript_char(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'n' => 'ₙ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+/// Typesets `latex` as an SVG document rendered at `scale` times its display
+/// size, so it stays crisp once rasterized.
+pub fn render_math_svg(latex: &str, font_size: f32, color: Rgba, scale: f32) -> MathSvg {
+    let nodes = Parser::new(strip_delimiters(latex)).parse_sequence();
+    let layout = layout(&Node::Group(nodes), font_size, font_size);
+
+    let padding = 0.2 * font_size;
+    let width = layout.width.max(1.0) + 2.0 * padding;
+    let height = layout.ascent + layout.descent + 2.0 * padding;
+    let color = format!(
+        "#{:02x}{:02x}{:02x}",
+        (color.r * 255.0).round() as u8,
+        (color.g * 255.0).round() as u8,
+        (color.b * 255.0).round() as u8
+    );
+
+    let mut svg = String::new();
+    write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {width} {height}">"#,
+        width * scale,
+        height * scale,
+    )
+    .ok();
+    write!(
+        svg,
+        r#"<g transform="translate({padding} {})" fill="{color}" stroke="{color}" font-family="serif">"#,
+        padding + layout.ascent,
+    )
+    .ok();
+
+    for item in &layout.items {
+        match item {
+            Item::Text {
+                x,
+                y,
+                size,
+                style,
+                text,
+            } => {
+                let style = match style {
+                    FontStyle::Italic => r#" font-style="italic""#,
+                    FontStyle::Upright => "",
+                    FontStyle::Bold => r#" font-weight="bold""#,
+                };
+                write!(
+                    svg,
+                    r#"<text x="{x}" y="{y}" font-size="{size}" stroke="none"{style}>{}</text>"#,
+                    escape_xml(text)
+                )
+                .ok();
+            }
+            Item::Polyline { points, thickness } => {
+                let points = points
+                    .iter()
+                    .map(|(x, y)| format!("{x},{y}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                write!(
+                    svg,
+                    r#"<polyline points="{points}" fill="none" stroke-width="{thickness}"/>"#
+                )
+                .ok();
+            }
+        }
+    }
+
+    svg.push_str("</g></svg>");
+    MathSvg { svg, width, height }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(latex: &str) -> Vec<Node> {
+        Parser::new(strip_delimiters(latex)).parse_sequence()
+    }
+
+    fn text(text: &str, style: FontStyle) -> Node {
+        Node::Text {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    #[test]
+    fn test_strip_delimiters() {
+        assert_eq!(strip_delimiters("$x$"), "x");
+        assert_eq!(strip_delimiters(" $$x^2$$ "), "x^2");
+        assert_eq!(strip_delimiters("\\[ \\frac{1}{2} \\]"), "\\frac{1}{2}");
+        assert_eq!(strip_delimiters("x + y"), "x + y");
+    }
+
+    #[test]
+    fn test_parse_scripts_and_fractions() {
+        assert_eq!(
+            parse("$x_1^{2}$"),
+            vec![Node::Scripts {
+                base: Box::new(text("x", FontStyle::Italic)),
+                superscript: Some(Box::new(Node::Group(vec![text("2", FontStyle::Upright)]))),
+                subscript: Some(Box::new(text("1", FontStyle::Upright))),
+            }]
+        );
+
+        assert_eq!(
+            parse("\\frac{\\alpha}{2} = \\text{ok}"),
+            vec![
+                Node::Fraction(
+                    Box::new(Node::Group(vec![text("α", FontStyle::Italic)])),
+                    Box::new(Node::Group(vec![text("2", FontStyle::Upright)])),
+                ),
+                Node::Operator("=".to_string()),
+                text("ok", FontStyle::Upright),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_delimiters_and_unknown_commands() {
+        assert_eq!(
+            parse("\\left( x \\right]"),
+            vec![Node::Delimited {
+                left: "(".to_string(),
+                body: Box::new(Node::Group(vec![text("x", FontStyle::Italic)])),
+                right: "]".to_string(),
+            }]
+        );
+
+        assert_eq!(
+            parse("\\unknown"),
+            vec![text("unknown", FontStyle::Upright)]
+        );
+    }
+
+    #[test]
+    fn test_render_math_text() {
+        assert_eq!(render_math_text("$x_1^2 + \\alpha$"), "x₁² + α");
+        assert_eq!(render_math_text("e^{i\\pi}"), "e^(iπ)");
+        assert_eq!(render_math_text("\\frac{a}{b}"), "(a)/(b)");
+    }
+
+    #[test]
+    fn test_render_svg_escapes_text() {
+        let svg = render_math_svg("a < b", 16.0, gpui::rgba(0x000000ff), 2.0).svg;
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("&lt;"));
+        assert!(!svg.contains("< "));
+    }
+}
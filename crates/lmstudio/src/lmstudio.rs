@@ -117,12 +117,20 @@ pub struct ChatCompletionRequest {
     pub model: String,
     pub messages: Vec<ChatMessage>,
     pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
     pub max_tokens: Option<i32>,
     pub stop: Option<Vec<String>>,
     pub temperature: Option<f32>,
     pub tools: Vec<LmStudioTool>,
 }
 
+#[derive(Serialize, Debug)]
+pub struct StreamOptions {
+    /// Whether to stream a final chunk reporting the token usage of the request.
+    pub include_usage: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ChatResponse {
     pub id: String,
@@ -130,6 +138,9 @@ pub struct ChatResponse {
     pub created: u64,
     pub model: String,
     pub choices: Vec<ChoiceDelta>,
+    /// The token usage of the request, reported in the final response.
+    #[serde(default)]
+    pub usage: Option<Usage>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,3 +1,4 @@
+use crate::markdown_mermaid::MermaidLayout;
 use gpui::{
     FontStyle, FontWeight, HighlightStyle, SharedString, StrikethroughStyle, UnderlineStyle, px,
 };
@@ -12,6 +13,12 @@ pub enum ParsedMarkdownElement {
     Table(ParsedMarkdownTable),
     BlockQuote(ParsedMarkdownBlockQuote),
     CodeBlock(ParsedMarkdownCodeBlock),
+    /// A ```` ```mermaid ```` code block that could be laid out as a diagram.
+    Mermaid(ParsedMarkdownMermaidDiagram),
+    /// A footnote's text, rendered at the end of the document.
+    FootnoteDefinition(ParsedMarkdownFootnoteDefinition),
+    /// A `<details>` HTML element and the Markdown it contains.
+    Details(ParsedMarkdownDetails),
     /// A paragraph of text and other inline elements.
     Paragraph(MarkdownParagraph),
     HorizontalRule(Range<usize>),
@@ -25,9 +32,13 @@ impl ParsedMarkdownElement {
             Self::Table(table) => table.source_range.clone(),
             Self::BlockQuote(block_quote) => block_quote.source_range.clone(),
             Self::CodeBlock(code_block) => code_block.source_range.clone(),
+            Self::Mermaid(diagram) => diagram.source_range.clone(),
+            Self::FootnoteDefinition(footnote) => footnote.source_range.clone(),
+            Self::Details(details) => details.source_range.clone(),
             Self::Paragraph(text) => match text.get(0)? {
                 MarkdownParagraphChunk::Text(t) => t.source_range.clone(),
                 MarkdownParagraphChunk::Image(image) => image.source_range.clone(),
+                MarkdownParagraphChunk::Math(math) => math.source_range.clone(),
             },
             Self::HorizontalRule(range) => range.clone(),
         })
@@ -45,6 +56,8 @@ pub type MarkdownParagraph = Vec<MarkdownParagraphChunk>;
 pub enum MarkdownParagraphChunk {
     Text(ParsedMarkdownText),
     Image(Image),
+    /// Display math, typeset on its own line.
+    Math(ParsedMarkdownMath),
 }
 
 #[derive(Debug)]
@@ -80,6 +93,44 @@ pub struct ParsedMarkdownCodeBlock {
     pub highlights: Option<Vec<(Range<usize>, HighlightId)>>,
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMermaidDiagram {
+    pub source_range: Range<usize>,
+    pub contents: SharedString,
+    pub layout: MermaidLayout,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMath {
+    pub source_range: Range<usize>,
+    /// The LaTeX source, without its `$$` delimiters.
+    pub contents: SharedString,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownFootnoteDefinition {
+    pub source_range: Range<usize>,
+    pub label: SharedString,
+    /// The number the footnote is displayed with, in order of first reference.
+    pub number: usize,
+    /// Where the footnote is first referenced, to link back to.
+    pub reference_offset: Option<usize>,
+    pub children: Vec<ParsedMarkdownElement>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownDetails {
+    pub source_range: Range<usize>,
+    pub summary: MarkdownParagraph,
+    /// Whether the element has the `open` attribute.
+    pub open: bool,
+    pub children: Vec<ParsedMarkdownElement>,
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ParsedMarkdownHeading {
@@ -246,6 +297,11 @@ pub enum Link {
         /// The absolute path to the item.
         path: PathBuf,
     },
+    /// A reference to a footnote in the same document.
+    Footnote {
+        /// The footnote's label, as written in the Markdown document.
+        label: SharedString,
+    },
 }
 
 impl Link {
@@ -279,6 +335,7 @@ impl Display for Link {
         match self {
             Link::Web { url } => write!(f, "{}", url),
             Link::Path { display_path, .. } => write!(f, "{}", display_path.display()),
+            Link::Footnote { label } => write!(f, "[^{}]", label),
         }
     }
 }
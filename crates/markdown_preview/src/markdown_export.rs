@@ -0,0 +1,721 @@
+//! Exports parsed Markdown as a self-contained HTML document, styled with the
+//! colors of the active theme.
+
+use crate::markdown_elements::{
+    HeadingLevel, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+    ParsedMarkdownCodeBlock, ParsedMarkdownElement, ParsedMarkdownListItemType,
+    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow, ParsedMarkdownText,
+};
+use crate::markdown_mermaid::{
+    self, MermaidArrowHead, MermaidLayout, MermaidLineStyle, MermaidShapeKind,
+};
+use base64::Engine as _;
+use collections::HashSet;
+use gpui::{App, FontStyle, FontWeight, HighlightStyle, Hsla, Rgba, SharedString};
+use latex_math::render_math_svg;
+use settings::Settings as _;
+use std::{fmt::Write as _, ops::Range, path::Path, sync::Arc};
+use theme::{ActiveTheme as _, SyntaxTheme, ThemeSettings};
+
+/// The colors and fonts an exported document is styled with.
+#[derive(Clone)]
+pub struct ExportStyle {
+    pub text_color: Hsla,
+    pub text_muted_color: Hsla,
+    pub link_color: Hsla,
+    pub background_color: Hsla,
+    pub border_color: Hsla,
+    pub code_block_background_color: Hsla,
+    pub code_span_background_color: Hsla,
+    pub font_family: SharedString,
+    pub buffer_font_family: SharedString,
+    /// The body font size, in pixels.
+    pub font_size: f32,
+    pub syntax_theme: Arc<SyntaxTheme>,
+}
+
+impl ExportStyle {
+    pub fn from_theme(cx: &App) -> Self {
+        let theme = cx.theme();
+        let settings = ThemeSettings::get_global(cx);
+        Self {
+            text_color: theme.colors().text,
+            text_muted_color: theme.colors().text_muted,
+            link_color: theme.colors().text_accent,
+            background_color: theme.colors().editor_background,
+            border_color: theme.colors().border,
+            code_block_background_color: theme.colors().surface_background,
+            code_span_background_color: theme.colors().editor_document_highlight_read_background,
+            font_family: settings.ui_font.family.clone(),
+            buffer_font_family: settings.buffer_font.family.clone(),
+            font_size: settings.buffer_font_size(cx).0,
+            syntax_theme: theme.syntax().clone(),
+        }
+    }
+}
+
+/// Serializes `parsed` as a standalone HTML document. Local images are
+/// embedded, so the document can be shared on its own.
+pub fn export_html(parsed: &ParsedMarkdown, title: &str, style: &ExportStyle) -> String {
+    let mut writer = HtmlWriter {
+        html: String::new(),
+        style,
+        footnote_references: HashSet::default(),
+    };
+    for block in &parsed.children {
+        writer.write_block(block);
+    }
+
+    let mut html = String::new();
+    write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<article>\n{}</article>\n</body>\n</html>\n",
+        escape_html(title),
+        stylesheet(style),
+        writer.html,
+    )
+    .ok();
+    html
+}
+
+fn stylesheet(style: &ExportStyle) -> String {
+    format!(
+        "\
+body {{ margin: 0; background: {background}; color: {text}; font-family: \"{font}\", system-ui, sans-serif; font-size: {size}px; line-height: 1.5; }}
+article {{ max-width: 48em; margin: 0 auto; padding: 2em; }}
+a {{ color: {link}; }}
+h1, h2, h3, h4, h5, h6 {{ line-height: 1.25; margin: 1.2em 0 0.5em; }}
+h6 {{ color: {muted}; }}
+p, blockquote, pre, table, details, .list-item {{ margin: 0 0 0.75em; }}
+blockquote {{ margin-left: 0; padding-left: 1em; border-left: 4px solid {border}; }}
+code, pre, kbd {{ font-family: \"{buffer_font}\", ui-monospace, monospace; }}
+code {{ background: {code_span}; border-radius: 3px; padding: 0 0.2em; }}
+pre {{ background: {code_block}; border-radius: 4px; padding: 0.75em 1em; overflow-x: auto; }}
+pre code {{ background: none; padding: 0; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid {border}; padding: 0.25em 0.5em; }}
+th {{ border-width: 2px; }}
+hr {{ border: none; height: 2px; background: {border}; }}
+img {{ max-width: 100%; }}
+.list-item {{ display: flex; gap: 0.5em; }}
+.list-item > .bullet {{ flex: none; }}
+.math {{ text-align: center; overflow-x: auto; }}
+.diagram {{ background: {code_block}; border-radius: 4px; padding: 0.75em; overflow-x: auto; margin: 0 0 0.75em; }}
+.footnote {{ display: flex; gap: 0.5em; color: {muted}; font-size: 0.875em; }}
+.footnote > div {{ flex: 1; }}
+.footnote a.back-link {{ text-decoration: none; }}
+a.footnote-ref {{ text-decoration: none; }}
+summary {{ cursor: pointer; }}
+",
+        background = css_color(style.background_color),
+        text = css_color(style.text_color),
+        muted = css_color(style.text_muted_color),
+        link = css_color(style.link_color),
+        border = css_color(style.border_color),
+        code_block = css_color(style.code_block_background_color),
+        code_span = css_color(style.code_span_background_color),
+        font = escape_html(&style.font_family),
+        buffer_font = escape_html(&style.buffer_font_family),
+        size = style.font_size,
+    )
+}
+
+struct HtmlWriter<'a> {
+    html: String,
+    style: &'a ExportStyle,
+    /// The footnotes whose first reference was written, and got an anchor to link back to.
+    footnote_references: HashSet<SharedString>,
+}
+
+impl HtmlWriter<'_> {
+    fn write_block(&mut self, block: &ParsedMarkdownElement) {
+        match block {
+            ParsedMarkdownElement::Heading(heading) => {
+                let level = match heading.level {
+                    HeadingLevel::H1 => 1,
+                    HeadingLevel::H2 => 2,
+                    HeadingLevel::H3 => 3,
+                    HeadingLevel::H4 => 4,
+                    HeadingLevel::H5 => 5,
+                    HeadingLevel::H6 => 6,
+                };
+                write!(self.html, "<h{level}>").ok();
+                self.write_paragraph(&heading.contents);
+                writeln!(self.html, "</h{level}>").ok();
+            }
+            ParsedMarkdownElement::ListItem(item) => {
+                let bullet = match &item.item_type {
+                    ParsedMarkdownListItemType::Ordered(order) => format!("{order}."),
+                    ParsedMarkdownListItemType::Unordered => "•".to_string(),
+                    ParsedMarkdownListItemType::Task(checked, _) => format!(
+                        "<input type=\"checkbox\" disabled{}>",
+                        if *checked { " checked" } else { "" }
+                    ),
+                };
+                write!(
+                    self.html,
+                    "<div class=\"list-item\" style=\"margin-left: {}em\"><span class=\"bullet\">{bullet}</span><div>",
+                    (item.depth.saturating_sub(1)) as f32 * 1.5,
+                )
+                .ok();
+                for block in &item.content {
+                    self.write_block(block);
+                }
+                writeln!(self.html, "</div></div>").ok();
+            }
+            ParsedMarkdownElement::Table(table) => {
+                self.html.push_str("<table>\n<thead>\n");
+                self.write_table_row(&table.header, &table.column_alignments, "th");
+                self.html.push_str("</thead>\n<tbody>\n");
+                for row in &table.body {
+                    self.write_table_row(row, &table.column_alignments, "td");
+                }
+                self.html.push_str("</tbody>\n</table>\n");
+            }
+            ParsedMarkdownElement::BlockQuote(block_quote) => {
+                self.html.push_str("<blockquote>\n");
+                for block in &block_quote.children {
+                    self.write_block(block);
+                }
+                self.html.push_str("</blockquote>\n");
+            }
+            ParsedMarkdownElement::CodeBlock(code_block) => self.write_code_block(code_block),
+            ParsedMarkdownElement::Mermaid(diagram) => {
+                self.html.push_str("<div class=\"diagram\">");
+                let svg = mermaid_svg(&diagram.layout, self.style);
+                self.html.push_str(&svg);
+                self.html.push_str("</div>\n");
+            }
+            ParsedMarkdownElement::FootnoteDefinition(footnote) => {
+                let id = anchor_id(&footnote.label);
+                write!(
+                    self.html,
+                    "<div class=\"footnote\" id=\"fn-{id}\"><span>{}.</span><div>",
+                    footnote.number
+                )
+                .ok();
+                for block in &footnote.children {
+                    self.write_block(block);
+                }
+                self.html.push_str("</div>");
+                if footnote.reference_offset.is_some() {
+                    write!(
+                        self.html,
+                        "<a class=\"back-link\" href=\"#fnref-{id}\" title=\"Back to reference\">↩</a>"
+                    )
+                    .ok();
+                }
+                self.html.push_str("</div>\n");
+            }
+            ParsedMarkdownElement::Details(details) => {
+                write!(
+                    self.html,
+                    "<details{}>\n<summary>",
+                    if details.open { " open" } else { "" }
+                )
+                .ok();
+                self.write_paragraph(&details.summary);
+                self.html.push_str("</summary>\n");
+                for block in &details.children {
+                    self.write_block(block);
+                }
+                self.html.push_str("</details>\n");
+            }
+            ParsedMarkdownElement::Paragraph(paragraph) => {
+                self.html.push_str("<p>");
+                self.write_paragraph(paragraph);
+                self.html.push_str("</p>\n");
+            }
+            ParsedMarkdownElement::HorizontalRule(_) => self.html.push_str("<hr>\n"),
+        }
+    }
+
+    fn write_table_row(
+        &mut self,
+        row: &ParsedMarkdownTableRow,
+        alignments: &[ParsedMarkdownTableAlignment],
+        cell_tag: &str,
+    ) {
+        self.html.push_str("<tr>");
+        for (index, cell) in row.children.iter().enumerate() {
+            let alignment = match alignments.get(index) {
+                Some(ParsedMarkdownTableAlignment::Left) => " style=\"text-align: left\"",
+                Some(ParsedMarkdownTableAlignment::Center) => " style=\"text-align: center\"",
+                Some(ParsedMarkdownTableAlignment::Right) => " style=\"text-align: right\"",
+                Some(ParsedMarkdownTableAlignment::None) | None => "",
+            };
+            write!(self.html, "<{cell_tag}{alignment}>").ok();
+            self.write_paragraph(cell);
+            write!(self.html, "</{cell_tag}>").ok();
+        }
+        self.html.push_str("</tr>\n");
+    }
+
+    fn write_code_block(&mut self, code_block: &ParsedMarkdownCodeBlock) {
+        self.html.push_str("<pre><code");
+        if let Some(language) = &code_block.language {
+            write!(
+                self.html,
+                " class=\"language-{}\"",
+                escape_html(&language.replace(char::is_whitespace, "-"))
+            )
+            .ok();
+        }
+        self.html.push('>');
+
+        let contents = code_block.contents.as_ref();
+        let mut offset = 0;
+        for (range, highlight_id) in code_block.highlights.iter().flatten() {
+            if range.start < offset {
+                continue;
+            }
+            self.html
+                .push_str(&escape_html(&contents[offset..range.start]));
+            let text = escape_html(&contents[range.clone()]);
+            match highlight_id
+                .style(&self.style.syntax_theme)
+                .map(|style| css_for_highlight(&style))
+                .filter(|css| !css.is_empty())
+            {
+                Some(css) => write!(self.html, "<span style=\"{css}\">{text}</span>").ok(),
+                None => write!(self.html, "{text}").ok(),
+            };
+            offset = range.end;
+        }
+        self.html.push_str(&escape_html(&contents[offset..]));
+        self.html.push_str("</code></pre>\n");
+    }
+
+    fn write_paragraph(&mut self, paragraph: &MarkdownParagraph) {
+        for chunk in paragraph {
+            match chunk {
+                MarkdownParagraphChunk::Text(text) => self.write_text(text),
+                MarkdownParagraphChunk::Image(image) => {
+                    let src = match &image.link {
+                        Link::Web { url } => url.clone(),
+                        Link::Path { path, display_path } => embedded_image(path)
+                            .unwrap_or_else(|| display_path.to_string_lossy().into_owned()),
+                        Link::Footnote { .. } => continue,
+                    };
+                    write!(
+                        self.html,
+                        "<img src=\"{}\" alt=\"{}\">",
+                        escape_html(&src),
+                        escape_html(image.alt_text.as_deref().unwrap_or_default())
+                    )
+                    .ok();
+                }
+                MarkdownParagraphChunk::Math(math) => {
+                    let svg = render_math_svg(
+                        &math.contents,
+                        self.style.font_size,
+                        Rgba::from(self.style.text_color),
+                        1.0,
+                    );
+                    write!(self.html, "<span class=\"math\">{}</span>", svg.svg).ok();
+                }
+            }
+        }
+    }
+
+    fn write_text(&mut self, text: &ParsedMarkdownText) {
+        let contents = text.contents.as_str();
+        let mut boundaries = vec![0, contents.len()];
+        for range in text
+            .highlights
+            .iter()
+            .map(|(range, _)| range)
+            .chain(&text.region_ranges)
+        {
+            boundaries.push(range.start.min(contents.len()));
+            boundaries.push(range.end.min(contents.len()));
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        for segment in boundaries.windows(2) {
+            let segment = segment[0]..segment[1];
+            let Some(segment_text) = contents.get(segment.clone()) else {
+                continue;
+            };
+
+            let mut highlight = HighlightStyle::default();
+            for (range, markdown_highlight) in &text.highlights {
+                if contains(range, &segment) {
+                    if let Some(style) =
+                        markdown_highlight.to_highlight_style(&self.style.syntax_theme)
+                    {
+                        highlight.highlight(style);
+                    }
+                }
+            }
+            let region = text
+                .region_ranges
+                .iter()
+                .zip(&text.regions)
+                .find(|(range, _)| contains(range, &segment))
+                .map(|(_, region)| region);
+
+            let mut closing_tags = Vec::new();
+            if let Some(link) = region.and_then(|region| region.link.as_ref()) {
+                match link {
+                    Link::Web { url } => {
+                        write!(self.html, "<a href=\"{}\">", escape_html(url)).ok();
+                    }
+                    Link::Path { display_path, .. } => {
+                        write!(
+                            self.html,
+                            "<a href=\"{}\">",
+                            escape_html(&display_path.to_string_lossy())
+                        )
+                        .ok();
+                    }
+                    Link::Footnote { label } => {
+                        let id = anchor_id(label);
+                        write!(self.html, "<a class=\"footnote-ref\" href=\"#fn-{id}\"").ok();
+                        if self.footnote_references.insert(label.clone()) {
+                            write!(self.html, " id=\"fnref-{id}\"").ok();
+                        }
+                        self.html.push('>');
+                    }
+                }
+                closing_tags.push("</a>");
+            }
+            if region.is_some_and(|region| region.code) {
+                self.html.push_str("<code>");
+                closing_tags.push("</code>");
+            }
+            let css = css_for_highlight(&highlight);
+            if !css.is_empty() {
+                write!(self.html, "<span style=\"{css}\">").ok();
+                closing_tags.push("</span>");
+            }
+
+            self.html
+                .push_str(&escape_html(segment_text).replace('\n', "<br>\n"));
+            for tag in closing_tags.into_iter().rev() {
+                self.html.push_str(tag);
+            }
+        }
+    }
+}
+
+fn contains(outer: &Range<usize>, inner: &Range<usize>) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+/// Reads a local image into a `data:` URL, so the document doesn't depend on it.
+fn embedded_image(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let mime_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+    let bytes = std::fs::read(path).ok()?;
+    Some(format!(
+        "data:{mime_type};base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Draws a laid out diagram as an SVG document.
+fn mermaid_svg(layout: &MermaidLayout, style: &ExportStyle) -> String {
+    let em = style.font_size;
+    let text = css_color(style.text_color);
+    let line = css_color(style.text_muted_color);
+    let border = css_color(style.border_color);
+    let background = css_color(style.code_block_background_color);
+    let note = css_color(style.code_span_background_color);
+
+    let mut svg = String::new();
+    write!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"{}\" font-size=\"{em}\">",
+        escape_html(&style.font_family),
+        width = layout.width * em,
+        height = layout.height * em,
+    )
+    .ok();
+
+    for mermaid_line in &layout.lines {
+        let points = mermaid_line
+            .points
+            .iter()
+            .map(|point| format!("{},{}", point.x * em, point.y * em))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (width, dash) = match mermaid_line.style {
+            MermaidLineStyle::Solid => (1.0, ""),
+            MermaidLineStyle::Dashed => (1.0, " stroke-dasharray=\"4 3\""),
+            MermaidLineStyle::Thick => (2.5, ""),
+        };
+        write!(
+            svg,
+            "<polyline points=\"{points}\" fill=\"none\" stroke=\"{line}\" stroke-width=\"{width}\"{dash}/>"
+        )
+        .ok();
+
+        let Some((tip, direction)) = mermaid_line.tip() else {
+            continue;
+        };
+        let along = |distance: f32, across: f32| {
+            format!(
+                "{},{}",
+                (tip.x + direction.x * distance - direction.y * across) * em,
+                (tip.y + direction.y * distance + direction.x * across) * em
+            )
+        };
+        match mermaid_line.head {
+            MermaidArrowHead::None => {}
+            MermaidArrowHead::Arrow => {
+                write!(
+                    svg,
+                    "<polygon points=\"{} {} {}\" fill=\"{line}\"/>",
+                    along(0.0, 0.0),
+                    along(-0.6, 0.3),
+                    along(-0.6, -0.3)
+                )
+                .ok();
+            }
+            MermaidArrowHead::Cross => {
+                write!(
+                    svg,
+                    "<path d=\"M{} L{} M{} L{}\" stroke=\"{line}\" stroke-width=\"1.5\"/>",
+                    along(-0.1, 0.25),
+                    along(-0.6, -0.25),
+                    along(-0.1, -0.25),
+                    along(-0.6, 0.25)
+                )
+                .ok();
+            }
+            MermaidArrowHead::Circle => {
+                write!(
+                    svg,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{line}\"/>",
+                    (tip.x - direction.x * 0.25) * em,
+                    (tip.y - direction.y * 0.25) * em,
+                    0.25 * em
+                )
+                .ok();
+            }
+        }
+    }
+
+    for shape in &layout.shapes {
+        let bounds = shape.bounds;
+        let fill = if shape.kind == MermaidShapeKind::Note {
+            &note
+        } else {
+            &background
+        };
+        if let Some(polygon) = shape.polygon() {
+            let points = polygon
+                .iter()
+                .map(|point| format!("{},{}", point.x * em, point.y * em))
+                .collect::<Vec<_>>()
+                .join(" ");
+            write!(
+                svg,
+                "<polygon points=\"{points}\" fill=\"{fill}\" stroke=\"{border}\"/>"
+            )
+            .ok();
+        } else {
+            let radius = match shape.kind {
+                MermaidShapeKind::Rounded => 0.3 * em,
+                MermaidShapeKind::Stadium | MermaidShapeKind::Circle => {
+                    bounds.size.height * em / 2.0
+                }
+                _ => 0.0,
+            };
+            write!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{radius}\" fill=\"{fill}\" stroke=\"{border}\"/>",
+                bounds.origin.x * em,
+                bounds.origin.y * em,
+                bounds.size.width * em,
+                bounds.size.height * em,
+            )
+            .ok();
+        }
+        let center_x = bounds.origin.x + bounds.size.width / 2.0;
+        let center_y = bounds.origin.y + bounds.size.height / 2.0;
+        write_svg_text(&mut svg, &shape.text, center_x, center_y, em, &text);
+    }
+
+    for label in &layout.labels {
+        if label.opaque {
+            let (width, height) = markdown_mermaid::text_size(&label.text);
+            write!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{background}\"/>",
+                (label.center.x - width / 2.0 - 0.25) * em,
+                (label.center.y - height / 2.0) * em,
+                (width + 0.5) * em,
+                height * em,
+            )
+            .ok();
+        }
+        write_svg_text(
+            &mut svg,
+            &label.text,
+            label.center.x,
+            label.center.y,
+            em,
+            &text,
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Writes text centered on a point given in ems, one `tspan` per line.
+fn write_svg_text(
+    svg: &mut String,
+    text: &str,
+    center_x: f32,
+    center_y: f32,
+    em: f32,
+    color: &str,
+) {
+    let lines = text.lines().collect::<Vec<_>>();
+    let first_line_y =
+        center_y - (lines.len().saturating_sub(1)) as f32 * markdown_mermaid::LINE_HEIGHT / 2.0;
+    write!(
+        svg,
+        "<text text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{color}\">"
+    )
+    .ok();
+    for (index, line) in lines.iter().enumerate() {
+        write!(
+            svg,
+            "<tspan x=\"{}\" y=\"{}\">{}</tspan>",
+            center_x * em,
+            (first_line_y + index as f32 * markdown_mermaid::LINE_HEIGHT) * em,
+            escape_html(line)
+        )
+        .ok();
+    }
+    svg.push_str("</text>");
+}
+
+fn css_for_highlight(style: &HighlightStyle) -> String {
+    let mut css = String::new();
+    if let Some(color) = style.color {
+        write!(css, "color: {}; ", css_color(color)).ok();
+    }
+    if let Some(color) = style.background_color {
+        write!(css, "background-color: {}; ", css_color(color)).ok();
+    }
+    if let Some(weight) = style
+        .font_weight
+        .filter(|weight| *weight != FontWeight::NORMAL)
+    {
+        write!(css, "font-weight: {}; ", weight.0).ok();
+    }
+    if style.font_style == Some(FontStyle::Italic) {
+        css.push_str("font-style: italic; ");
+    }
+    match (style.underline.is_some(), style.strikethrough.is_some()) {
+        (true, true) => css.push_str("text-decoration: underline line-through; "),
+        (true, false) => css.push_str("text-decoration: underline; "),
+        (false, true) => css.push_str("text-decoration: line-through; "),
+        (false, false) => {}
+    }
+    css.trim_end().to_string()
+}
+
+fn css_color(color: Hsla) -> String {
+    format!("#{:08x}", u32::from(Rgba::from(color)))
+}
+
+/// Turns a footnote label into something that can be used in an element id.
+fn anchor_id(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+pub(crate) fn test_style() -> ExportStyle {
+    use gpui::{black, white};
+
+    ExportStyle {
+        text_color: black(),
+        text_muted_color: black(),
+        link_color: black(),
+        background_color: white(),
+        border_color: black(),
+        code_block_background_color: white(),
+        code_span_background_color: white(),
+        font_family: "Zed Plex Sans".into(),
+        buffer_font_family: "Zed Plex Mono".into(),
+        font_size: 16.,
+        syntax_theme: Arc::new(SyntaxTheme::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_parser::parse_markdown;
+
+    async fn export(markdown: &str) -> String {
+        let parsed = parse_markdown(markdown, None, None).await;
+        let html = export_html(&parsed, "Notes", &test_style());
+        let start = html.find("<article>\n").unwrap() + "<article>\n".len();
+        let end = html.find("</article>").unwrap();
+        html[start..end].to_string()
+    }
+
+    #[gpui::test]
+    async fn test_export_html() {
+        assert_eq!(
+            export("# Title <1>\n\nSome **bold** and `code` with a [link](https://zed.dev).").await,
+            "<h1>Title &lt;1&gt;</h1>\n\
+             <p>Some <span style=\"font-weight: 700;\">bold</span> and <code>code</code> with a \
+             <a href=\"https://zed.dev\"><span style=\"text-decoration: underline;\">link</span></a>.</p>\n"
+        );
+        assert_eq!(
+            export("```\nfn main() {}\n```\n\n> Quote\n\n---").await,
+            "<pre><code>fn main() {}</code></pre>\n\
+             <blockquote>\n<p>Quote</p>\n</blockquote>\n\
+             <hr>\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_export_footnotes() {
+        assert_eq!(
+            export("A[^1] and B[^1].\n\n[^1]: The note.").await,
+            "<p>A<a class=\"footnote-ref\" href=\"#fn-1\" id=\"fnref-1\">¹</a> \
+             and B<a class=\"footnote-ref\" href=\"#fn-1\">¹</a>.</p>\n\
+             <div class=\"footnote\" id=\"fn-1\"><span>1.</span><div><p>The note.</p>\n</div>\
+             <a class=\"back-link\" href=\"#fnref-1\" title=\"Back to reference\">↩</a></div>\n"
+        );
+    }
+}
@@ -0,0 +1,611 @@
+//! Support for the subset of raw HTML that is safe and useful to render in the
+//! preview: text formatting, links, images and `<details>` elements. Anything
+//! else is reduced to its text, and elements that could run code or embed
+//! other documents are dropped entirely.
+
+use crate::markdown_elements::{
+    Image, Link, MarkdownHighlight, MarkdownHighlightStyle, MarkdownParagraph,
+    MarkdownParagraphChunk, ParsedMarkdownText, ParsedRegion,
+};
+use gpui::FontWeight;
+use latex_math::{subscript_char, superscript_char};
+use std::{ops::Range, path::PathBuf};
+
+/// Elements that are dropped along with their contents.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "audio", "button", "canvas", "embed", "form", "head", "iframe", "input", "link", "math",
+    "meta", "noscript", "object", "script", "select", "style", "svg", "template", "textarea",
+    "title", "video",
+];
+
+/// Elements that never have contents or a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements that start a new paragraph.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "article",
+    "blockquote",
+    "center",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tr",
+    "ul",
+];
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum HtmlToken {
+    Start(HtmlTag),
+    End(String),
+    Text(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct HtmlTag {
+    /// The lowercased tag name.
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub self_closing: bool,
+}
+
+impl HtmlTag {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn has_contents(&self) -> bool {
+        !self.self_closing && !VOID_ELEMENTS.contains(&self.name.as_str())
+    }
+}
+
+/// Splits HTML into tags and text. Comments, doctypes and processing
+/// instructions are dropped, and character references in text and attribute
+/// values are decoded.
+pub(crate) fn tokenize_html(html: &str) -> Vec<HtmlToken> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            let end = tag.find('>').unwrap_or(tag.len());
+            tokens.push(HtmlToken::End(tag[..end].trim().to_ascii_lowercase()));
+            rest = tag.get(end + 1..).unwrap_or("");
+        } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            let (tag, len) = parse_tag(rest);
+            tokens.push(HtmlToken::Start(tag));
+            rest = &rest[len..];
+        } else {
+            let end = rest[1..].find('<').map_or(rest.len(), |ix| ix + 1);
+            tokens.push(HtmlToken::Text(decode_entities(&rest[..end])));
+            rest = &rest[end..];
+        }
+    }
+    tokens
+}
+
+/// Parses the start tag at the beginning of `html`, returning it along with
+/// its length in bytes.
+fn parse_tag(html: &str) -> (HtmlTag, usize) {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == ':';
+    let name_end = html[1..]
+        .find(|c| !is_name_char(c))
+        .map_or(html.len(), |ix| ix + 1);
+    let mut tag = HtmlTag {
+        name: html[1..name_end].to_ascii_lowercase(),
+        attributes: Vec::new(),
+        self_closing: false,
+    };
+
+    let mut ix = name_end;
+    loop {
+        let rest = &html[ix..];
+        let trimmed = rest.trim_start();
+        ix += rest.len() - trimmed.len();
+        if trimmed.is_empty() {
+            return (tag, html.len());
+        } else if trimmed.starts_with("/>") {
+            tag.self_closing = true;
+            return (tag, ix + 2);
+        } else if trimmed.starts_with('>') {
+            return (tag, ix + 1);
+        } else if trimmed.starts_with('/') {
+            ix += 1;
+            continue;
+        }
+
+        let name_len = trimmed
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(trimmed.len())
+            .max(1);
+        let name = trimmed[..name_len].to_ascii_lowercase();
+        ix += name_len;
+
+        let rest = &html[ix..];
+        let trimmed = rest.trim_start();
+        let mut value = String::new();
+        if let Some(assignment) = trimmed.strip_prefix('=') {
+            let assignment_start = ix + rest.len() - assignment.len();
+            let unquoted = assignment.trim_start();
+            let value_start = assignment_start + assignment.len() - unquoted.len();
+            if let Some(quote) = unquoted.chars().next().filter(|c| *c == '"' || *c == '\'') {
+                let quoted = &unquoted[1..];
+                let end = quoted.find(quote).unwrap_or(quoted.len());
+                value = decode_entities(&quoted[..end]);
+                ix = (value_start + 1 + end + 1).min(html.len());
+            } else {
+                let end = unquoted
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(unquoted.len());
+                value = decode_entities(&unquoted[..end]);
+                ix = value_start + end;
+            }
+        }
+        tag.attributes.push((name, value));
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(ix) = rest.find('&') {
+        decoded.push_str(&rest[..ix]);
+        rest = &rest[ix..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..end + 1])?, end + 2)));
+        if let Some((c, len)) = entity {
+            decoded.push(c);
+            rest = &rest[len..];
+        } else {
+            decoded.push('&');
+            rest = &rest[1..];
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = if let Some(hex) = number.strip_prefix(['x', 'X']) {
+            u32::from_str_radix(hex, 16).ok()?
+        } else {
+            number.parse().ok()?
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "larr" => '←',
+        "rarr" => '→',
+        "uarr" => '↑',
+        "darr" => '↓',
+        _ => return None,
+    })
+}
+
+/// The parts of an HTML block that opens a `<details>` element.
+pub(crate) struct HtmlDetails<'a> {
+    pub open: bool,
+    /// The HTML inside the `<summary>` element, if there is one.
+    pub summary: &'a str,
+    /// The HTML after the summary, up to the closing tag or the end of the block.
+    pub body: &'a str,
+    /// The HTML after the closing tag, if the block closes the element.
+    pub after: Option<&'a str>,
+}
+
+/// Returns the parts of `html` if it starts with a `<details>` element.
+pub(crate) fn parse_details(html: &str) -> Option<HtmlDetails<'_>> {
+    let html = html.trim_start();
+    let lowercase = html.to_ascii_lowercase();
+    let tag_name = lowercase.strip_prefix("<details")?;
+    if !tag_name.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+        return None;
+    }
+    let (tag, tag_len) = parse_tag(html);
+
+    let (body, after) = match split_details_end(&html[tag_len..]) {
+        Some((body, after)) => (body, Some(after)),
+        None => (&html[tag_len..], None),
+    };
+    let mut summary = "";
+    let mut body = body;
+    let body_lowercase = body.to_ascii_lowercase();
+    if let Some(start) = body_lowercase.find("<summary") {
+        if let Some(content_start) = body_lowercase[start..].find('>').map(|ix| start + ix + 1) {
+            let content_end = body_lowercase[content_start..]
+                .find("</summary")
+                .map_or(body.len(), |ix| content_start + ix);
+            let end = body_lowercase[content_end..]
+                .find('>')
+                .map_or(body.len(), |ix| content_end + ix + 1);
+            summary = &body[content_start..content_end];
+            body = &body[end..];
+        }
+    }
+
+    Some(HtmlDetails {
+        open: tag.attribute("open").is_some(),
+        summary,
+        body,
+        after,
+    })
+}
+
+/// Splits `html` around the first `</details>` closing tag.
+pub(crate) fn split_details_end(html: &str) -> Option<(&str, &str)> {
+    let start = html.to_ascii_lowercase().find("</details")?;
+    let end = html[start..]
+        .find('>')
+        .map_or(html.len(), |ix| start + ix + 1);
+    Some((&html[..start], &html[end..]))
+}
+
+/// Converts HTML into paragraphs, keeping its text along with the formatting,
+/// links and images that the preview can render.
+pub(crate) fn html_to_paragraphs(
+    html: &str,
+    source_range: Range<usize>,
+    file_location_directory: Option<PathBuf>,
+) -> Vec<MarkdownParagraph> {
+    let mut builder = ParagraphBuilder::new(source_range, file_location_directory);
+    for token in tokenize_html(html) {
+        match token {
+            HtmlToken::Start(tag) => builder.start(&tag),
+            HtmlToken::End(name) => builder.end(&name),
+            HtmlToken::Text(text) => builder.push_text(&text),
+        }
+    }
+    builder.finish()
+}
+
+/// Maps `text` to Unicode superscript or subscript characters, if they all exist.
+pub(crate) fn map_script_text(text: &str, map_char: fn(char) -> Option<char>) -> Option<String> {
+    text.chars().map(map_char).collect()
+}
+
+#[derive(Default)]
+struct ParagraphBuilder {
+    source_range: Range<usize>,
+    file_location_directory: Option<PathBuf>,
+    paragraphs: Vec<MarkdownParagraph>,
+    chunks: MarkdownParagraph,
+    text: String,
+    highlights: Vec<(Range<usize>, MarkdownHighlight)>,
+    region_ranges: Vec<Range<usize>>,
+    regions: Vec<ParsedRegion>,
+    link: Option<Link>,
+    bold_depth: usize,
+    italic_depth: usize,
+    underline_depth: usize,
+    strikethrough_depth: usize,
+    code_depth: usize,
+    superscript_depth: usize,
+    subscript_depth: usize,
+    preformatted_depth: usize,
+    skipped_depth: usize,
+}
+
+impl ParagraphBuilder {
+    fn new(source_range: Range<usize>, file_location_directory: Option<PathBuf>) -> Self {
+        Self {
+            source_range,
+            file_location_directory,
+            ..Default::default()
+        }
+    }
+
+    fn start(&mut self, tag: &HtmlTag) {
+        let name = tag.name.as_str();
+        if self.skipped_depth > 0 || SKIPPED_ELEMENTS.contains(&name) {
+            if tag.has_contents() {
+                self.skipped_depth += 1;
+            }
+            return;
+        }
+        if BLOCK_ELEMENTS.contains(&name) {
+            self.finish_paragraph();
+        }
+
+        match name {
+            "b" | "strong" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "dt" => self.bold_depth += 1,
+            "i" | "em" | "cite" | "var" => self.italic_depth += 1,
+            "u" | "ins" => self.underline_depth += 1,
+            "s" | "del" | "strike" => self.strikethrough_depth += 1,
+            "code" | "kbd" | "samp" | "tt" => self.code_depth += 1,
+            "pre" => {
+                self.code_depth += 1;
+                self.preformatted_depth += 1;
+            }
+            "sup" => self.superscript_depth += 1,
+            "sub" => self.subscript_depth += 1,
+            "a" => {
+                self.link = tag.attribute("href").and_then(|href| {
+                    Link::identify(self.file_location_directory.clone(), href.to_string())
+                })
+            }
+            "br" => self.text.push('\n'),
+            "li" => self.text.push_str("• "),
+            "img" => {
+                let image = tag.attribute("src").and_then(|src| {
+                    Image::identify(
+                        src.to_string(),
+                        self.source_range.clone(),
+                        self.file_location_directory.clone(),
+                    )
+                });
+                if let Some(mut image) = image {
+                    if let Some(alt) = tag.attribute("alt").filter(|alt| !alt.is_empty()) {
+                        image.set_alt_text(alt.to_string().into());
+                    }
+                    self.finish_text();
+                    self.chunks.push(MarkdownParagraphChunk::Image(image));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if self.skipped_depth > 0 {
+            if SKIPPED_ELEMENTS.contains(&name) {
+                self.skipped_depth -= 1;
+            }
+            return;
+        }
+
+        let depth = match name {
+            "b" | "strong" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "dt" => {
+                Some(&mut self.bold_depth)
+            }
+            "i" | "em" | "cite" | "var" => Some(&mut self.italic_depth),
+            "u" | "ins" => Some(&mut self.underline_depth),
+            "s" | "del" | "strike" => Some(&mut self.strikethrough_depth),
+            "code" | "kbd" | "samp" | "tt" => Some(&mut self.code_depth),
+            "pre" => {
+                self.preformatted_depth = self.preformatted_depth.saturating_sub(1);
+                Some(&mut self.code_depth)
+            }
+            "sup" => Some(&mut self.superscript_depth),
+            "sub" => Some(&mut self.subscript_depth),
+            "a" => {
+                self.link = None;
+                None
+            }
+            _ => None,
+        };
+        if let Some(depth) = depth {
+            *depth = depth.saturating_sub(1);
+        }
+
+        if BLOCK_ELEMENTS.contains(&name) {
+            self.finish_paragraph();
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.skipped_depth > 0 {
+            return;
+        }
+
+        let mut content = String::new();
+        if self.preformatted_depth > 0 {
+            content.push_str(text);
+        } else {
+            let mut after_space = self.text.is_empty() || self.text.ends_with([' ', '\n']);
+            for c in text.chars() {
+                if c.is_whitespace() && c != '\u{a0}' {
+                    if !after_space {
+                        content.push(' ');
+                    }
+                    after_space = true;
+                } else {
+                    content.push(c);
+                    after_space = false;
+                }
+            }
+        }
+        if self.superscript_depth > 0 {
+            content = map_script_text(&content, superscript_char).unwrap_or(content);
+        } else if self.subscript_depth > 0 {
+            content = map_script_text(&content, subscript_char).unwrap_or(content);
+        }
+        if content.is_empty() {
+            return;
+        }
+
+        let range = self.text.len()..self.text.len() + content.len();
+        self.text.push_str(&content);
+
+        let style = MarkdownHighlightStyle {
+            italic: self.italic_depth > 0,
+            underline: self.underline_depth > 0 || self.link.is_some(),
+            strikethrough: self.strikethrough_depth > 0,
+            weight: if self.bold_depth > 0 {
+                FontWeight::BOLD
+            } else {
+                FontWeight::default()
+            },
+        };
+        if style != MarkdownHighlightStyle::default() {
+            let highlight = MarkdownHighlight::Style(style);
+            match self.highlights.last_mut() {
+                Some((last_range, last_highlight))
+                    if last_range.end == range.start && *last_highlight == highlight =>
+                {
+                    last_range.end = range.end;
+                }
+                _ => self.highlights.push((range.clone(), highlight)),
+            }
+        }
+
+        if self.code_depth > 0 || self.link.is_some() {
+            self.region_ranges.push(range);
+            self.regions.push(ParsedRegion {
+                code: self.code_depth > 0,
+                link: self.link.clone(),
+            });
+        }
+    }
+
+    fn finish_text(&mut self) {
+        let contents = std::mem::take(&mut self.text);
+        if contents.trim().is_empty() {
+            self.highlights.clear();
+            self.region_ranges.clear();
+            self.regions.clear();
+            return;
+        }
+        self.chunks
+            .push(MarkdownParagraphChunk::Text(ParsedMarkdownText {
+                source_range: self.source_range.clone(),
+                contents,
+                highlights: std::mem::take(&mut self.highlights),
+                region_ranges: std::mem::take(&mut self.region_ranges),
+                regions: std::mem::take(&mut self.regions),
+            }));
+    }
+
+    fn finish_paragraph(&mut self) {
+        self.finish_text();
+        if !self.chunks.is_empty() {
+            self.paragraphs.push(std::mem::take(&mut self.chunks));
+        }
+    }
+
+    fn finish(mut self) -> Vec<MarkdownParagraph> {
+        self.finish_paragraph();
+        self.paragraphs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(html: &str) -> Vec<Vec<String>> {
+        html_to_paragraphs(html, 0..html.len(), None)
+            .into_iter()
+            .map(|paragraph| {
+                paragraph
+                    .into_iter()
+                    .map(|chunk| match chunk {
+                        MarkdownParagraphChunk::Text(text) => text.contents,
+                        MarkdownParagraphChunk::Image(image) => format!("<img {}>", image.link),
+                        MarkdownParagraphChunk::Math(math) => math.contents.to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_html() {
+        assert_eq!(
+            tokenize_html("<!-- hi --><A HREF='x y' data-x=1 hidden>a &amp; b</a><br/>"),
+            vec![
+                HtmlToken::Start(HtmlTag {
+                    name: "a".into(),
+                    attributes: vec![
+                        ("href".into(), "x y".into()),
+                        ("data-x".into(), "1".into()),
+                        ("hidden".into(), String::new()),
+                    ],
+                    self_closing: false,
+                }),
+                HtmlToken::Text("a & b".into()),
+                HtmlToken::End("a".into()),
+                HtmlToken::Start(HtmlTag {
+                    name: "br".into(),
+                    attributes: Vec::new(),
+                    self_closing: true,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html_to_paragraphs() {
+        assert_eq!(
+            texts("<p align=\"center\">\n  Press <kbd>Ctrl</kbd>\n  +\n  <kbd>S</kbd>\n</p>"),
+            vec![vec!["Press Ctrl + S ".to_string()]]
+        );
+        assert_eq!(
+            texts("<div>H<sub>2</sub>O and x<sup>2</sup></div><p>Second</p>"),
+            vec![vec!["H₂O and x²".to_string()], vec!["Second".to_string()]]
+        );
+        assert_eq!(
+            texts("<p>Before<script>alert('hi')</script><style>p {}</style> after</p>"),
+            vec![vec!["Before after".to_string()]]
+        );
+        assert_eq!(
+            texts("<img src=\"https://zed.dev/logo.png\" alt=\"Logo\"><p>Caption</p>"),
+            vec![
+                vec!["<img https://zed.dev/logo.png>".to_string()],
+                vec!["Caption".to_string()]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_details() {
+        let details =
+            parse_details("<details open>\n<summary>More <b>info</b></summary>\nBody\n").unwrap();
+        assert!(details.open);
+        assert_eq!(details.summary, "More <b>info</b>");
+        assert_eq!(details.body, "\nBody\n");
+        assert_eq!(details.after, None);
+
+        let details = parse_details("<DETAILS><summary>A</summary>B</details>C").unwrap();
+        assert!(!details.open);
+        assert_eq!(details.summary, "A");
+        assert_eq!(details.body, "B");
+        assert_eq!(details.after, Some("C"));
+
+        assert!(parse_details("<detailsx>").is_none());
+        assert!(parse_details("<div>").is_none());
+    }
+}
@@ -0,0 +1,1239 @@
+//! Parses and lays out the subset of [Mermaid](https://mermaid.js.org) diagrams
+//! that the preview can draw natively: flowcharts and sequence diagrams.
+//!
+//! Layouts are computed in ems, so the renderer only has to scale them to the
+//! current font size.
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use gpui::{Bounds, Point, SharedString, point, size};
+
+/// The line height text is laid out with, in ems.
+pub const LINE_HEIGHT: f32 = 1.3;
+/// The approximate width of a character, as we don't have font metrics at parse time.
+const CHAR_WIDTH: f32 = 0.55;
+const NODE_PADDING_X: f32 = 1.0;
+const NODE_PADDING_Y: f32 = 0.6;
+const RANK_GAP: f32 = 3.0;
+const NODE_GAP: f32 = 1.5;
+const ACTOR_GAP: f32 = 2.0;
+const MESSAGE_HEIGHT: f32 = 2.5;
+const NOTE_PADDING: f32 = 0.5;
+
+/// A diagram, laid out in ems.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MermaidLayout {
+    pub width: f32,
+    pub height: f32,
+    pub shapes: Vec<MermaidShape>,
+    pub lines: Vec<MermaidLine>,
+    pub labels: Vec<MermaidLabel>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MermaidShape {
+    pub bounds: Bounds<f32>,
+    pub kind: MermaidShapeKind,
+    pub text: SharedString,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MermaidShapeKind {
+    Rectangle,
+    Rounded,
+    Stadium,
+    Circle,
+    Diamond,
+    Hexagon,
+    Note,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MermaidLine {
+    pub points: Vec<Point<f32>>,
+    pub style: MermaidLineStyle,
+    pub head: MermaidArrowHead,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MermaidLineStyle {
+    Solid,
+    Dashed,
+    Thick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MermaidArrowHead {
+    None,
+    Arrow,
+    Cross,
+    Circle,
+}
+
+impl MermaidShape {
+    /// The corners of the shape's outline, for shapes that aren't drawn as
+    /// rectangles with rounded corners.
+    pub fn polygon(&self) -> Option<Vec<Point<f32>>> {
+        let Bounds { origin, size } = self.bounds;
+        let (left, top) = (origin.x, origin.y);
+        let (right, bottom) = (left + size.width, top + size.height);
+        let middle = top + size.height / 2.0;
+        match self.kind {
+            MermaidShapeKind::Diamond => {
+                let center = left + size.width / 2.0;
+                Some(vec![
+                    point(center, top),
+                    point(right, middle),
+                    point(center, bottom),
+                    point(left, middle),
+                ])
+            }
+            MermaidShapeKind::Hexagon => {
+                let inset = size.height / 2.0;
+                Some(vec![
+                    point(left + inset, top),
+                    point(right - inset, top),
+                    point(right, middle),
+                    point(right - inset, bottom),
+                    point(left + inset, bottom),
+                    point(left, middle),
+                ])
+            }
+            _ => None,
+        }
+    }
+}
+
+impl MermaidLine {
+    /// The point the line ends at, and the direction it points in there as a
+    /// unit vector.
+    pub fn tip(&self) -> Option<(Point<f32>, Point<f32>)> {
+        let [.., from, tip] = self.points[..] else {
+            return None;
+        };
+        let length = ((tip.x - from.x).powi(2) + (tip.y - from.y).powi(2)).sqrt();
+        if length == 0.0 {
+            return None;
+        }
+        Some((
+            tip,
+            point((tip.x - from.x) / length, (tip.y - from.y) / length),
+        ))
+    }
+}
+
+/// Text drawn outside of a shape, such as an edge label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MermaidLabel {
+    pub center: Point<f32>,
+    pub text: SharedString,
+    /// Whether the label is drawn over a line, and needs a background to stay legible.
+    pub opaque: bool,
+}
+
+/// Parses and lays out a Mermaid diagram, or returns an error if the diagram
+/// type or its syntax isn't supported.
+pub fn layout_mermaid(source: &str) -> Result<MermaidLayout> {
+    let mut lines = statements(source);
+    let header = lines.next().context("empty diagram")?;
+    let mut header_words = header.split_whitespace();
+    match header_words.next() {
+        Some("graph" | "flowchart") => {
+            let direction = match header_words.next() {
+                None | Some("TD" | "TB") => Direction::TopDown,
+                Some("BT") => Direction::BottomUp,
+                Some("LR") => Direction::LeftRight,
+                Some("RL") => Direction::RightLeft,
+                Some(direction) => return Err(anyhow!("unknown direction {direction:?}")),
+            };
+            let flowchart = Flowchart::parse(direction, lines)?;
+            Ok(flowchart.layout())
+        }
+        Some("sequenceDiagram") => {
+            let diagram = SequenceDiagram::parse(lines)?;
+            Ok(diagram.layout())
+        }
+        _ => Err(anyhow!("unsupported diagram: {header:?}")),
+    }
+}
+
+/// Splits the source into trimmed, non-empty statements without comments.
+fn statements(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .lines()
+        .flat_map(|line| line.split(';'))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"))
+}
+
+fn text_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split("<br>")
+        .flat_map(|line| line.split("<br/>"))
+        .flat_map(|line| line.split('\n'))
+}
+
+/// Estimates the size of `text` in ems.
+pub fn text_size(text: &str) -> (f32, f32) {
+    let mut width = 0.0f32;
+    let mut line_count = 0;
+    for line in text_lines(text) {
+        width = width.max(line.trim().chars().count() as f32 * CHAR_WIDTH);
+        line_count += 1;
+    }
+    (width, line_count.max(1) as f32 * LINE_HEIGHT)
+}
+
+fn normalize_text(text: &str) -> SharedString {
+    let text = text.trim();
+    let text = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text);
+    text_lines(text)
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .into()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    TopDown,
+    BottomUp,
+    LeftRight,
+    RightLeft,
+}
+
+impl Direction {
+    fn is_horizontal(self) -> bool {
+        matches!(self, Self::LeftRight | Self::RightLeft)
+    }
+
+    fn is_reversed(self) -> bool {
+        matches!(self, Self::BottomUp | Self::RightLeft)
+    }
+}
+
+#[derive(Debug)]
+struct FlowchartNode {
+    id: String,
+    text: SharedString,
+    shape: MermaidShapeKind,
+}
+
+#[derive(Debug)]
+struct FlowchartEdge {
+    from: usize,
+    to: usize,
+    label: Option<SharedString>,
+    style: MermaidLineStyle,
+    head: MermaidArrowHead,
+}
+
+#[derive(Debug)]
+struct Flowchart {
+    direction: Direction,
+    nodes: Vec<FlowchartNode>,
+    edges: Vec<FlowchartEdge>,
+    node_ids: HashMap<String, usize>,
+}
+
+/// A cursor over a single flowchart statement.
+struct StatementParser<'a> {
+    text: &'a str,
+}
+
+impl<'a> StatementParser<'a> {
+    fn skip_whitespace(&mut self) {
+        self.text = self.text.trim_start();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if let Some(rest) = self.text.strip_prefix(prefix) {
+            self.text = rest;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_until(&mut self, delimiter: &str) -> Result<&'a str> {
+        let end = self
+            .text
+            .find(delimiter)
+            .with_context(|| format!("expected {delimiter:?}"))?;
+        let taken = &self.text[..end];
+        self.text = &self.text[end + delimiter.len()..];
+        Ok(taken)
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let end = self
+            .text
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.text.len());
+        if end == 0 {
+            return None;
+        }
+        let identifier = &self.text[..end];
+        self.text = &self.text[end..];
+        Some(identifier)
+    }
+
+    /// Parses a node reference such as `a`, `a[Text]` or `a{Decision}`.
+    fn node(&mut self) -> Result<(&'a str, Option<(&'a str, MermaidShapeKind)>)> {
+        self.skip_whitespace();
+        let id = self.identifier().context("expected a node")?;
+        const SHAPES: &[(&str, &str, MermaidShapeKind)] = &[
+            ("((", "))", MermaidShapeKind::Circle),
+            ("([", "])", MermaidShapeKind::Stadium),
+            ("[[", "]]", MermaidShapeKind::Rectangle),
+            ("[(", ")]", MermaidShapeKind::Rounded),
+            ("{{", "}}", MermaidShapeKind::Hexagon),
+            ("[", "]", MermaidShapeKind::Rectangle),
+            ("(", ")", MermaidShapeKind::Rounded),
+            ("{", "}", MermaidShapeKind::Diamond),
+            (">", "]", MermaidShapeKind::Rectangle),
+        ];
+        for (open, close, shape) in SHAPES {
+            if self.eat(open) {
+                let text = self.take_until(close)?;
+                return Ok((id, Some((text, *shape))));
+            }
+        }
+        Ok((id, None))
+    }
+
+    /// Parses a link such as `-->`, `-- text -->`, `-.->|text|` or `==>`.
+    fn link(&mut self) -> Result<Option<(Option<&'a str>, MermaidLineStyle, MermaidArrowHead)>> {
+        self.skip_whitespace();
+        let start = self.link_chars();
+        if start.is_empty() {
+            return Ok(None);
+        }
+
+        let mut text = None;
+        let mut link = start.to_string();
+        let mut head = self.arrow_marker();
+        // The `-- text -->` form, where the link is split around its label.
+        if head.is_none() && matches!(start, "--" | "==" | "-.") {
+            let rest = self.text;
+            let end = ["-->", "---", "==>", "===", ".->", ".-", "--o", "--x"]
+                .iter()
+                .filter_map(|end| rest.find(end))
+                .min()
+                .context("unterminated link")?;
+            text = Some(&rest[..end]);
+            self.text = &rest[end..];
+            link.push_str(self.link_chars());
+            head = self.arrow_marker();
+        }
+        let head = head.unwrap_or(if link.ends_with('>') {
+            MermaidArrowHead::Arrow
+        } else {
+            MermaidArrowHead::None
+        });
+
+        self.skip_whitespace();
+        if self.eat("|") {
+            text = Some(self.take_until("|")?);
+        }
+
+        let style = if link.contains('.') {
+            MermaidLineStyle::Dashed
+        } else if link.contains('=') {
+            MermaidLineStyle::Thick
+        } else {
+            MermaidLineStyle::Solid
+        };
+        Ok(Some((text, style, head)))
+    }
+
+    /// Parses the circle and cross arrow heads in `--o` and `--x`, as long as
+    /// they don't start the next node.
+    fn arrow_marker(&mut self) -> Option<MermaidArrowHead> {
+        let mut chars = self.text.chars();
+        let head = match chars.next()? {
+            'o' => MermaidArrowHead::Circle,
+            'x' => MermaidArrowHead::Cross,
+            _ => return None,
+        };
+        if chars
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        {
+            return None;
+        }
+        self.text = &self.text[1..];
+        Some(head)
+    }
+
+    fn link_chars(&mut self) -> &'a str {
+        let end = self
+            .text
+            .find(|c: char| !matches!(c, '-' | '=' | '.' | '<' | '>' | '~'))
+            .unwrap_or(self.text.len());
+        let link = &self.text[..end];
+        self.text = &self.text[end..];
+        link
+    }
+}
+
+impl Flowchart {
+    fn parse<'a>(direction: Direction, statements: impl Iterator<Item = &'a str>) -> Result<Self> {
+        let mut flowchart = Flowchart {
+            direction,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            node_ids: HashMap::default(),
+        };
+
+        for statement in statements {
+            let keyword = statement.split_whitespace().next().unwrap_or_default();
+            if matches!(
+                keyword,
+                "subgraph"
+                    | "end"
+                    | "direction"
+                    | "classDef"
+                    | "class"
+                    | "style"
+                    | "linkStyle"
+                    | "click"
+            ) {
+                continue;
+            }
+
+            let mut parser = StatementParser { text: statement };
+            let mut sources = flowchart.node_group(&mut parser)?;
+            while let Some((label, style, head)) = parser.link()? {
+                let targets = flowchart.node_group(&mut parser)?;
+                for &from in &sources {
+                    for &to in &targets {
+                        flowchart.edges.push(FlowchartEdge {
+                            from,
+                            to,
+                            label: label.map(normalize_text).filter(|label| !label.is_empty()),
+                            style,
+                            head,
+                        });
+                    }
+                }
+                sources = targets;
+            }
+            parser.skip_whitespace();
+            if !parser.is_empty() {
+                return Err(anyhow!("unexpected {:?}", parser.text));
+            }
+        }
+
+        Ok(flowchart)
+    }
+
+    /// Parses nodes joined with `&`, returning their indices.
+    fn node_group(&mut self, parser: &mut StatementParser) -> Result<Vec<usize>> {
+        let mut nodes = Vec::new();
+        loop {
+            let (id, shape) = parser.node()?;
+            nodes.push(self.node(id, shape));
+            parser.skip_whitespace();
+            if !parser.eat("&") {
+                return Ok(nodes);
+            }
+        }
+    }
+
+    fn node(&mut self, id: &str, shape: Option<(&str, MermaidShapeKind)>) -> usize {
+        let index = *self.node_ids.entry(id.to_string()).or_insert_with(|| {
+            self.nodes.push(FlowchartNode {
+                id: id.to_string(),
+                text: id.to_string().into(),
+                shape: MermaidShapeKind::Rectangle,
+            });
+            self.nodes.len() - 1
+        });
+        if let Some((text, shape)) = shape {
+            let node = &mut self.nodes[index];
+            node.text = normalize_text(text);
+            node.shape = shape;
+        }
+        index
+    }
+
+    /// Assigns each node a rank, so that edges point from lower to higher ranks
+    /// where possible. Edges that would form a cycle are ignored.
+    fn ranks(&self) -> Vec<usize> {
+        let mut outgoing = vec![Vec::new(); self.nodes.len()];
+        for edge in &self.edges {
+            outgoing[edge.from].push(edge.to);
+        }
+
+        // Find the edges that close a cycle with a depth-first search.
+        #[derive(Clone, Copy, PartialEq)]
+        enum Visit {
+            New,
+            Active,
+            Done,
+        }
+        let mut visits = vec![Visit::New; self.nodes.len()];
+        let mut forward = vec![Vec::new(); self.nodes.len()];
+        for root in 0..self.nodes.len() {
+            if visits[root] != Visit::New {
+                continue;
+            }
+            let mut stack = vec![(root, 0)];
+            visits[root] = Visit::Active;
+            while let Some((node, next_edge)) = stack.last_mut() {
+                let node = *node;
+                if let Some(&target) = outgoing[node].get(*next_edge) {
+                    *next_edge += 1;
+                    match visits[target] {
+                        Visit::New => {
+                            forward[node].push(target);
+                            visits[target] = Visit::Active;
+                            stack.push((target, 0));
+                        }
+                        Visit::Done => forward[node].push(target),
+                        Visit::Active => {}
+                    }
+                } else {
+                    visits[node] = Visit::Done;
+                    stack.pop();
+                }
+            }
+        }
+
+        // Longest path ranking over the remaining acyclic edges.
+        let mut in_degree = vec![0; self.nodes.len()];
+        for targets in &forward {
+            for &target in targets {
+                in_degree[target] += 1;
+            }
+        }
+        let mut ranks = vec![0; self.nodes.len()];
+        let mut queue = (0..self.nodes.len())
+            .filter(|&node| in_degree[node] == 0)
+            .collect::<Vec<_>>();
+        while let Some(node) = queue.pop() {
+            for &target in &forward[node] {
+                ranks[target] = ranks[target].max(ranks[node] + 1);
+                in_degree[target] -= 1;
+                if in_degree[target] == 0 {
+                    queue.push(target);
+                }
+            }
+        }
+        ranks
+    }
+
+    fn node_size(node: &FlowchartNode) -> (f32, f32) {
+        let (text_width, text_height) = text_size(&node.text);
+        let width = (text_width + 2.0 * NODE_PADDING_X).max(3.0);
+        let height = text_height + 2.0 * NODE_PADDING_Y;
+        match node.shape {
+            MermaidShapeKind::Circle => {
+                let diameter = width.max(height);
+                (diameter, diameter)
+            }
+            MermaidShapeKind::Diamond => (width * 1.5, height * 1.6),
+            MermaidShapeKind::Hexagon => (width + height, height),
+            MermaidShapeKind::Stadium => (width + height / 2.0, height),
+            _ => (width, height),
+        }
+    }
+
+    fn layout(&self) -> MermaidLayout {
+        let ranks = self.ranks();
+        let rank_count = ranks.iter().max().map_or(0, |rank| rank + 1);
+        let mut layers = vec![Vec::new(); rank_count];
+        for (node, &rank) in ranks.iter().enumerate() {
+            layers[rank].push(node);
+        }
+
+        // Reduce crossings by ordering each layer by the average position of its
+        // neighbors in the previous layer, sweeping down and then up.
+        let mut neighbors = vec![Vec::new(); self.nodes.len()];
+        for edge in &self.edges {
+            neighbors[edge.from].push(edge.to);
+            neighbors[edge.to].push(edge.from);
+        }
+        let mut positions = vec![0.0f32; self.nodes.len()];
+        let update_positions = |layers: &Vec<Vec<usize>>, positions: &mut Vec<f32>| {
+            for layer in layers {
+                for (index, &node) in layer.iter().enumerate() {
+                    positions[node] = index as f32;
+                }
+            }
+        };
+        update_positions(&layers, &mut positions);
+        for iteration in 0..4 {
+            let order: Vec<usize> = if iteration % 2 == 0 {
+                (1..rank_count).collect()
+            } else {
+                (0..rank_count.saturating_sub(1)).rev().collect()
+            };
+            for rank in order {
+                let adjacent_rank = if iteration % 2 == 0 {
+                    rank - 1
+                } else {
+                    rank + 1
+                };
+                let mut keyed = layers[rank]
+                    .iter()
+                    .map(|&node| {
+                        let adjacent = neighbors[node]
+                            .iter()
+                            .filter(|&&neighbor| ranks[neighbor] == adjacent_rank)
+                            .map(|&neighbor| positions[neighbor])
+                            .collect::<Vec<_>>();
+                        let key = if adjacent.is_empty() {
+                            positions[node]
+                        } else {
+                            adjacent.iter().sum::<f32>() / adjacent.len() as f32
+                        };
+                        (key, node)
+                    })
+                    .collect::<Vec<_>>();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                layers[rank] = keyed.into_iter().map(|(_, node)| node).collect();
+                update_positions(&layers, &mut positions);
+            }
+        }
+
+        // Position the nodes along the main axis (between ranks) and the cross
+        // axis (within a rank), then map those onto x and y.
+        let horizontal = self.direction.is_horizontal();
+        let sizes = self.nodes.iter().map(Self::node_size).collect::<Vec<_>>();
+        let main_size = |node: usize| {
+            if horizontal {
+                sizes[node].0
+            } else {
+                sizes[node].1
+            }
+        };
+        let cross_size = |node: usize| {
+            if horizontal {
+                sizes[node].1
+            } else {
+                sizes[node].0
+            }
+        };
+
+        let rank_sizes = layers
+            .iter()
+            .map(|layer| {
+                layer
+                    .iter()
+                    .map(|&node| main_size(node))
+                    .fold(0.0, f32::max)
+            })
+            .collect::<Vec<_>>();
+        let layer_extents = layers
+            .iter()
+            .map(|layer| {
+                layer.iter().map(|&node| cross_size(node)).sum::<f32>()
+                    + NODE_GAP * layer.len().saturating_sub(1) as f32
+            })
+            .collect::<Vec<_>>();
+        let total_cross = layer_extents.iter().copied().fold(0.0, f32::max);
+        let total_main =
+            rank_sizes.iter().sum::<f32>() + RANK_GAP * rank_count.saturating_sub(1) as f32;
+
+        let mut centers = vec![point(0.0, 0.0); self.nodes.len()];
+        let mut main_offset = 0.0;
+        for (rank, layer) in layers.iter().enumerate() {
+            let mut cross_offset = (total_cross - layer_extents[rank]) / 2.0;
+            let main_center = main_offset + rank_sizes[rank] / 2.0;
+            let main_center = if self.direction.is_reversed() {
+                total_main - main_center
+            } else {
+                main_center
+            };
+            for &node in layer {
+                let cross_center = cross_offset + cross_size(node) / 2.0;
+                centers[node] = if horizontal {
+                    point(main_center, cross_center)
+                } else {
+                    point(cross_center, main_center)
+                };
+                cross_offset += cross_size(node) + NODE_GAP;
+            }
+            main_offset += rank_sizes[rank] + RANK_GAP;
+        }
+
+        let bounds = |node: usize| Bounds {
+            origin: point(
+                centers[node].x - sizes[node].0 / 2.0,
+                centers[node].y - sizes[node].1 / 2.0,
+            ),
+            size: size(sizes[node].0, sizes[node].1),
+        };
+
+        let shapes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| MermaidShape {
+                bounds: bounds(index),
+                kind: node.shape,
+                text: node.text.clone(),
+            })
+            .collect();
+
+        let mut lines = Vec::new();
+        let mut labels = Vec::new();
+        for edge in &self.edges {
+            let points = if edge.from == edge.to {
+                let bounds = bounds(edge.from);
+                let right = bounds.origin.x + bounds.size.width;
+                let top = bounds.origin.y;
+                vec![
+                    point(right - 0.5, top),
+                    point(right - 0.5, top - 1.0),
+                    point(right + 1.0, top - 1.0),
+                    point(right + 1.0, top + 0.5),
+                    point(right, top + 0.5),
+                ]
+            } else {
+                let from = centers[edge.from];
+                let to = centers[edge.to];
+                vec![
+                    clip_to_bounds(from, to, bounds(edge.from)),
+                    clip_to_bounds(to, from, bounds(edge.to)),
+                ]
+            };
+            if let Some(label) = &edge.label {
+                let first = points[0];
+                let last = points[points.len() - 1];
+                labels.push(MermaidLabel {
+                    center: point((first.x + last.x) / 2.0, (first.y + last.y) / 2.0),
+                    text: label.clone(),
+                    opaque: true,
+                });
+            }
+            lines.push(MermaidLine {
+                points,
+                style: edge.style,
+                head: edge.head,
+            });
+        }
+
+        let (width, height) = if horizontal {
+            (total_main, total_cross)
+        } else {
+            (total_cross, total_main)
+        };
+        MermaidLayout {
+            width: width + 2.0,
+            height: height + 2.0,
+            shapes,
+            lines,
+            labels,
+        }
+        .translated(point(1.0, 1.0))
+    }
+}
+
+/// Returns where the segment from the center of `bounds` towards `target` leaves `bounds`.
+fn clip_to_bounds(center: Point<f32>, target: Point<f32>, bounds: Bounds<f32>) -> Point<f32> {
+    let dx = target.x - center.x;
+    let dy = target.y - center.y;
+    let half_width = bounds.size.width / 2.0;
+    let half_height = bounds.size.height / 2.0;
+    if dx == 0.0 && dy == 0.0 {
+        return center;
+    }
+    let scale_x = if dx == 0.0 {
+        f32::INFINITY
+    } else {
+        half_width / dx.abs()
+    };
+    let scale_y = if dy == 0.0 {
+        f32::INFINITY
+    } else {
+        half_height / dy.abs()
+    };
+    let scale = scale_x.min(scale_y).min(1.0);
+    point(center.x + dx * scale, center.y + dy * scale)
+}
+
+impl MermaidLayout {
+    fn translated(mut self, offset: Point<f32>) -> Self {
+        for shape in &mut self.shapes {
+            shape.bounds.origin = shape.bounds.origin + offset;
+        }
+        for line in &mut self.lines {
+            for point in &mut line.points {
+                *point = *point + offset;
+            }
+        }
+        for label in &mut self.labels {
+            label.center = label.center + offset;
+        }
+        self
+    }
+}
+
+#[derive(Debug)]
+enum SequenceItem {
+    Message {
+        from: usize,
+        to: usize,
+        text: SharedString,
+        style: MermaidLineStyle,
+        head: MermaidArrowHead,
+    },
+    Note {
+        first: usize,
+        last: usize,
+        placement: NotePlacement,
+        text: SharedString,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NotePlacement {
+    LeftOf,
+    RightOf,
+    Over,
+}
+
+#[derive(Debug)]
+struct SequenceDiagram {
+    participants: Vec<(String, SharedString)>,
+    items: Vec<SequenceItem>,
+    autonumber: bool,
+}
+
+impl SequenceDiagram {
+    fn parse<'a>(statements: impl Iterator<Item = &'a str>) -> Result<Self> {
+        let mut diagram = SequenceDiagram {
+            participants: Vec::new(),
+            items: Vec::new(),
+            autonumber: false,
+        };
+
+        for statement in statements {
+            let (keyword, rest) = statement
+                .split_once(char::is_whitespace)
+                .unwrap_or((statement, ""));
+            match keyword {
+                "participant" | "actor" => {
+                    let (id, alias) = match rest.split_once(" as ") {
+                        Some((id, alias)) => (id.trim(), normalize_text(alias)),
+                        None => (rest.trim(), normalize_text(rest)),
+                    };
+                    let index = diagram.participant(id);
+                    diagram.participants[index].1 = alias;
+                }
+                "autonumber" => diagram.autonumber = true,
+                "Note" | "note" => {
+                    let (placement, rest) = if let Some(rest) = rest.strip_prefix("left of") {
+                        (NotePlacement::LeftOf, rest)
+                    } else if let Some(rest) = rest.strip_prefix("right of") {
+                        (NotePlacement::RightOf, rest)
+                    } else if let Some(rest) = rest.strip_prefix("over") {
+                        (NotePlacement::Over, rest)
+                    } else {
+                        return Err(anyhow!("invalid note: {statement:?}"));
+                    };
+                    let (targets, text) = rest.split_once(':').context("note without text")?;
+                    let (first, last) = match targets.split_once(',') {
+                        Some((first, last)) => (first.trim(), last.trim()),
+                        None => (targets.trim(), targets.trim()),
+                    };
+                    let first = diagram.participant(first);
+                    let last = diagram.participant(last);
+                    diagram.items.push(SequenceItem::Note {
+                        first: first.min(last),
+                        last: first.max(last),
+                        placement,
+                        text: normalize_text(text),
+                    });
+                }
+                "title" | "activate" | "deactivate" | "loop" | "alt" | "else" | "opt" | "par"
+                | "and" | "critical" | "break" | "rect" | "end" | "box" => {}
+                _ => diagram.parse_message(statement)?,
+            }
+        }
+
+        if diagram.participants.is_empty() {
+            return Err(anyhow!("sequence diagram has no participants"));
+        }
+        Ok(diagram)
+    }
+
+    fn parse_message(&mut self, statement: &str) -> Result<()> {
+        const ARROWS: &[(&str, MermaidLineStyle, MermaidArrowHead)] = &[
+            ("-->>", MermaidLineStyle::Dashed, MermaidArrowHead::Arrow),
+            ("->>", MermaidLineStyle::Solid, MermaidArrowHead::Arrow),
+            ("--x", MermaidLineStyle::Dashed, MermaidArrowHead::Cross),
+            ("-x", MermaidLineStyle::Solid, MermaidArrowHead::Cross),
+            ("--)", MermaidLineStyle::Dashed, MermaidArrowHead::Arrow),
+            ("-)", MermaidLineStyle::Solid, MermaidArrowHead::Arrow),
+            ("-->", MermaidLineStyle::Dashed, MermaidArrowHead::None),
+            ("->", MermaidLineStyle::Solid, MermaidArrowHead::None),
+        ];
+
+        let (link, text) = statement
+            .split_once(':')
+            .with_context(|| format!("invalid statement: {statement:?}"))?;
+        let (start, arrow) = ARROWS
+            .iter()
+            .filter_map(|arrow| Some((link.find(arrow.0)?, arrow)))
+            .min_by_key(|(start, arrow)| (*start, std::cmp::Reverse(arrow.0.len())))
+            .with_context(|| format!("invalid statement: {statement:?}"))?;
+        let (arrow, style, head) = *arrow;
+
+        let from = link[..start].trim();
+        let to = link[start + arrow.len()..]
+            .trim()
+            .trim_start_matches(['+', '-']);
+        if from.is_empty() || to.is_empty() {
+            return Err(anyhow!("invalid message: {statement:?}"));
+        }
+        let from = self.participant(from);
+        let to = self.participant(to);
+        self.items.push(SequenceItem::Message {
+            from,
+            to,
+            text: normalize_text(text),
+            style,
+            head,
+        });
+        Ok(())
+    }
+
+    fn participant(&mut self, id: &str) -> usize {
+        if let Some(index) = self.participants.iter().position(|(other, _)| other == id) {
+            index
+        } else {
+            self.participants.push((id.to_string(), normalize_text(id)));
+            self.participants.len() - 1
+        }
+    }
+
+    fn layout(&self) -> MermaidLayout {
+        let actor_sizes = self
+            .participants
+            .iter()
+            .map(|(_, name)| {
+                let (width, height) = text_size(name);
+                (
+                    (width + 2.0 * NODE_PADDING_X).max(5.0),
+                    height + 2.0 * NODE_PADDING_Y,
+                )
+            })
+            .collect::<Vec<_>>();
+        let actor_height = actor_sizes.iter().map(|size| size.1).fold(0.0, f32::max);
+
+        // Space the lifelines so that every message label fits between them.
+        let mut gaps = actor_sizes
+            .windows(2)
+            .map(|pair| (pair[0].0 + pair[1].0) / 2.0 + ACTOR_GAP)
+            .collect::<Vec<_>>();
+        gaps.push(actor_sizes.last().map_or(0.0, |size| size.0 / 2.0));
+        let mut spans = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| match item {
+                SequenceItem::Message { from, to, text, .. } => {
+                    let text = self.message_text(index, text);
+                    let first = *from.min(to);
+                    let last = if from == to { first + 1 } else { *from.max(to) };
+                    Some((first, last, text_size(&text).0 + ACTOR_GAP))
+                }
+                SequenceItem::Note { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|(first, last, _)| last - first);
+        for (first, last, required) in spans {
+            let last = last.min(gaps.len());
+            let current = gaps[first..last].iter().sum::<f32>();
+            if current < required && last > first {
+                gaps[last - 1] += required - current;
+            }
+        }
+
+        let mut centers = Vec::with_capacity(self.participants.len());
+        let mut x = actor_sizes[0].0 / 2.0;
+        for gap in &gaps {
+            centers.push(x);
+            x += gap;
+        }
+        centers.truncate(self.participants.len());
+        let mut width = x;
+
+        let mut shapes = Vec::new();
+        let mut lines = Vec::new();
+        let mut labels = Vec::new();
+        let mut y = actor_height + 1.0;
+        for (index, item) in self.items.iter().enumerate() {
+            match item {
+                SequenceItem::Message {
+                    from,
+                    to,
+                    text,
+                    style,
+                    head,
+                } => {
+                    let text = self.message_text(index, text);
+                    let (text_width, text_height) = text_size(&text);
+                    let line_y = y + text_height + 0.3;
+                    let (from_x, to_x) = (centers[*from], centers[*to]);
+                    let points = if from == to {
+                        let loop_width = 2.0f32;
+                        y += 1.0;
+                        width = width.max(from_x + loop_width.max(text_width) + 1.0);
+                        vec![
+                            point(from_x, line_y),
+                            point(from_x + loop_width, line_y),
+                            point(from_x + loop_width, line_y + 1.0),
+                            point(from_x, line_y + 1.0),
+                        ]
+                    } else {
+                        vec![point(from_x, line_y), point(to_x, line_y)]
+                    };
+                    let label_x = if from == to {
+                        from_x + text_width / 2.0 + 0.5
+                    } else {
+                        (from_x + to_x) / 2.0
+                    };
+                    labels.push(MermaidLabel {
+                        center: point(label_x, y + text_height / 2.0),
+                        text: text.into(),
+                        opaque: false,
+                    });
+                    lines.push(MermaidLine {
+                        points,
+                        style: *style,
+                        head: *head,
+                    });
+                    y += MESSAGE_HEIGHT + text_height - LINE_HEIGHT;
+                }
+                SequenceItem::Note {
+                    first,
+                    last,
+                    placement,
+                    text,
+                } => {
+                    let (text_width, text_height) = text_size(text);
+                    let note_width = text_width + 2.0 * NOTE_PADDING;
+                    let note_height = text_height + 2.0 * NOTE_PADDING;
+                    let (left, note_width) = match placement {
+                        NotePlacement::LeftOf => (centers[*first] - note_width - 0.5, note_width),
+                        NotePlacement::RightOf => (centers[*last] + 0.5, note_width),
+                        NotePlacement::Over => {
+                            let note_width = note_width.max(centers[*last] - centers[*first] + 2.0);
+                            let center = (centers[*first] + centers[*last]) / 2.0;
+                            (center - note_width / 2.0, note_width)
+                        }
+                    };
+                    width = width.max(left + note_width);
+                    shapes.push(MermaidShape {
+                        bounds: Bounds {
+                            origin: point(left, y),
+                            size: size(note_width, note_height),
+                        },
+                        kind: MermaidShapeKind::Note,
+                        text: text.clone(),
+                    });
+                    y += note_height + 0.8;
+                }
+            }
+        }
+
+        // Draw the lifelines first, so that actors, notes and messages cover them.
+        let bottom = y + 0.5;
+        for center in &centers {
+            lines.insert(
+                0,
+                MermaidLine {
+                    points: vec![point(*center, actor_height), point(*center, bottom)],
+                    style: MermaidLineStyle::Dashed,
+                    head: MermaidArrowHead::None,
+                },
+            );
+        }
+        for ((_, name), (center, (actor_width, _))) in self
+            .participants
+            .iter()
+            .zip(centers.iter().zip(&actor_sizes))
+        {
+            for top in [0.0, bottom] {
+                shapes.push(MermaidShape {
+                    bounds: Bounds {
+                        origin: point(center - actor_width / 2.0, top),
+                        size: size(*actor_width, actor_height),
+                    },
+                    kind: MermaidShapeKind::Rectangle,
+                    text: name.clone(),
+                });
+            }
+        }
+
+        // Notes to the left of the first participant can extend past the origin.
+        let min_x = shapes
+            .iter()
+            .map(|shape| shape.bounds.origin.x)
+            .fold(0.0, f32::min);
+        MermaidLayout {
+            width: width - min_x + 2.0,
+            height: bottom + actor_height + 2.0,
+            shapes,
+            lines,
+            labels,
+        }
+        .translated(point(1.0 - min_x, 1.0))
+    }
+
+    fn message_text(&self, index: usize, text: &SharedString) -> String {
+        if self.autonumber {
+            let number = self.items[..=index]
+                .iter()
+                .filter(|item| matches!(item, SequenceItem::Message { .. }))
+                .count();
+            format!("{number}. {text}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flowchart() {
+        let flowchart = Flowchart::parse(
+            Direction::TopDown,
+            statements(
+                "
+                A[Start] --> B{Is it?}
+                B -- Yes --> C(OK) & D((Done))
+                B -.->|No| A
+                C ==> D; %% comment
+                ",
+            ),
+        )
+        .unwrap();
+
+        let nodes = flowchart
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node.text.as_ref(), node.shape))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nodes,
+            [
+                ("A", "Start", MermaidShapeKind::Rectangle),
+                ("B", "Is it?", MermaidShapeKind::Diamond),
+                ("C", "OK", MermaidShapeKind::Rounded),
+                ("D", "Done", MermaidShapeKind::Circle),
+            ]
+        );
+
+        let edges = flowchart
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    edge.from,
+                    edge.to,
+                    edge.label.as_ref().map(|label| label.to_string()),
+                    edge.style,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            edges,
+            [
+                (0, 1, None, MermaidLineStyle::Solid),
+                (1, 2, Some("Yes".to_string()), MermaidLineStyle::Solid),
+                (1, 3, Some("Yes".to_string()), MermaidLineStyle::Solid),
+                (1, 0, Some("No".to_string()), MermaidLineStyle::Dashed),
+                (2, 3, None, MermaidLineStyle::Thick),
+            ]
+        );
+
+        // The edge back to `A` closes a cycle, so it doesn't affect the ranks.
+        assert_eq!(flowchart.ranks(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_layout_flowchart_direction() {
+        let layout = layout_mermaid("graph LR\n  a --> b").unwrap();
+        assert_eq!(layout.shapes.len(), 2);
+        assert!(layout.shapes[0].bounds.origin.x < layout.shapes[1].bounds.origin.x);
+        assert_eq!(
+            layout.shapes[0].bounds.origin.y,
+            layout.shapes[1].bounds.origin.y
+        );
+
+        let layout = layout_mermaid("flowchart BT\n  a --> b").unwrap();
+        assert!(layout.shapes[0].bounds.origin.y > layout.shapes[1].bounds.origin.y);
+    }
+
+    #[test]
+    fn test_parse_sequence_diagram() {
+        let diagram = SequenceDiagram::parse(statements(
+            "
+            participant A as Alice
+            actor B
+            A->>+B: Hello
+            B-->>-A: Hi!
+            Note over A,B: A note
+            loop Every minute
+                A-xC: Ping
+            end
+            ",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            diagram
+                .participants
+                .iter()
+                .map(|(id, name)| (id.as_str(), name.as_ref()))
+                .collect::<Vec<_>>(),
+            [("A", "Alice"), ("B", "B"), ("C", "C")]
+        );
+        assert_eq!(diagram.items.len(), 4);
+        assert!(matches!(
+            diagram.items[1],
+            SequenceItem::Message {
+                from: 1,
+                to: 0,
+                style: MermaidLineStyle::Dashed,
+                head: MermaidArrowHead::Arrow,
+                ..
+            }
+        ));
+        assert!(matches!(
+            diagram.items[3],
+            SequenceItem::Message {
+                from: 0,
+                to: 2,
+                head: MermaidArrowHead::Cross,
+                ..
+            }
+        ));
+
+        let layout = diagram.layout();
+        // Each participant is drawn at the top and bottom of its lifeline.
+        assert_eq!(layout.shapes.len(), 7);
+        assert_eq!(layout.lines.len(), 6);
+    }
+
+    #[test]
+    fn test_unsupported_diagram() {
+        assert!(layout_mermaid("pie\n  \"a\" : 1").is_err());
+        assert!(layout_mermaid("graph TD\n  a -->").is_err());
+    }
+}
@@ -1,8 +1,11 @@
 use crate::markdown_elements::*;
+use crate::markdown_html::{self, html_to_paragraphs, map_script_text};
+use crate::markdown_mermaid::layout_mermaid;
 use async_recursion::async_recursion;
 use collections::FxHashMap;
 use gpui::FontWeight;
 use language::LanguageRegistry;
+use latex_math::{render_math_text, subscript_char, superscript_char};
 use pulldown_cmark::{Alignment, Event, Options, Parser, Tag, TagEnd};
 use std::{ops::Range, path::PathBuf, sync::Arc, vec};
 
@@ -34,6 +37,8 @@ struct MarkdownParser<'a> {
     parsed: Vec<ParsedMarkdownElement>,
     file_location_directory: Option<PathBuf>,
     language_registry: Option<Arc<LanguageRegistry>>,
+    /// The number of each footnote, and the offset of its first reference.
+    footnotes: FxHashMap<String, (usize, Option<usize>)>,
 }
 
 struct MarkdownListItem {
@@ -56,15 +61,40 @@ impl<'a> MarkdownParser<'a> {
         file_location_directory: Option<PathBuf>,
         language_registry: Option<Arc<LanguageRegistry>>,
     ) -> Self {
+        let footnotes = Self::number_footnotes(&tokens);
         Self {
             tokens,
             file_location_directory,
             language_registry,
+            footnotes,
             cursor: 0,
             parsed: vec![],
         }
     }
 
+    /// Numbers footnotes in the order they are first referenced, followed by
+    /// the footnotes that are never referenced.
+    fn number_footnotes(
+        tokens: &[(Event<'a>, Range<usize>)],
+    ) -> FxHashMap<String, (usize, Option<usize>)> {
+        let mut footnotes = FxHashMap::default();
+        for (event, range) in tokens {
+            if let Event::FootnoteReference(label) = event {
+                let number = footnotes.len() + 1;
+                footnotes
+                    .entry(label.to_string())
+                    .or_insert((number, Some(range.start)));
+            }
+        }
+        for (event, _) in tokens {
+            if let Event::Start(Tag::FootnoteDefinition(label)) = event {
+                let number = footnotes.len() + 1;
+                footnotes.entry(label.to_string()).or_insert((number, None));
+            }
+        }
+        footnotes
+    }
+
     fn eof(&self) -> bool {
         if self.tokens.is_empty() {
             return true;
@@ -102,6 +132,8 @@ impl<'a> MarkdownParser<'a> {
             | Event::Html(_)
             | Event::InlineHtml(_)
             | Event::FootnoteReference(_)
+            | Event::InlineMath(_)
+            | Event::DisplayMath(_)
             | Event::Start(Tag::Link { .. })
             | Event::Start(Tag::Emphasis)
             | Event::Start(Tag::Strong)
@@ -173,8 +205,29 @@ impl<'a> MarkdownParser<'a> {
                     self.cursor += 1;
 
                     let code_block = self.parse_code_block(language).await;
+                    if code_block.language.as_deref() == Some("mermaid") {
+                        if let Ok(layout) = layout_mermaid(&code_block.contents) {
+                            return Some(vec![ParsedMarkdownElement::Mermaid(
+                                ParsedMarkdownMermaidDiagram {
+                                    source_range: code_block.source_range,
+                                    contents: code_block.contents,
+                                    layout,
+                                },
+                            )]);
+                        }
+                    }
                     Some(vec![ParsedMarkdownElement::CodeBlock(code_block)])
                 }
+                Tag::FootnoteDefinition(label) => {
+                    let label = label.to_string();
+                    self.cursor += 1;
+                    let footnote = self.parse_footnote_definition(label, source_range).await;
+                    Some(vec![ParsedMarkdownElement::FootnoteDefinition(footnote)])
+                }
+                Tag::HtmlBlock => {
+                    self.cursor += 1;
+                    Some(self.parse_html_block(source_range).await)
+                }
                 _ => None,
             },
             Event::Rule => {
@@ -202,6 +255,9 @@ impl<'a> MarkdownParser<'a> {
         let mut bold_depth = 0;
         let mut italic_depth = 0;
         let mut strikethrough_depth = 0;
+        let mut kbd_depth = 0;
+        let mut superscript_depth = 0;
+        let mut subscript_depth = 0;
         let mut link: Option<Link> = None;
         let mut image: Option<Image> = None;
         let mut region_ranges: Vec<Range<usize>> = vec![];
@@ -215,7 +271,7 @@ impl<'a> MarkdownParser<'a> {
                 break;
             }
 
-            let (current, _) = self.current().unwrap();
+            let (current, current_range) = self.current().unwrap();
             let prev_len = text.len();
             match current {
                 Event::SoftBreak => {
@@ -229,12 +285,138 @@ impl<'a> MarkdownParser<'a> {
                     text.push('\n');
                 }
 
-                // We want to ignore any inline HTML tags in the text but keep
-                // the text between them
-                Event::InlineHtml(_) => {}
+                // Inline HTML is reduced to the formatting we can render. Other
+                // tags are ignored, but the text between them is kept.
+                Event::InlineHtml(html) => {
+                    for token in markdown_html::tokenize_html(html) {
+                        match token {
+                            markdown_html::HtmlToken::Start(tag) => match tag.name.as_str() {
+                                "b" | "strong" => bold_depth += 1,
+                                "i" | "em" => italic_depth += 1,
+                                "s" | "del" | "strike" => strikethrough_depth += 1,
+                                "kbd" | "code" => kbd_depth += 1,
+                                "sup" => superscript_depth += 1,
+                                "sub" => subscript_depth += 1,
+                                "br" => text.push('\n'),
+                                "a" => {
+                                    link = tag.attribute("href").and_then(|href| {
+                                        Link::identify(
+                                            self.file_location_directory.clone(),
+                                            href.to_string(),
+                                        )
+                                    });
+                                }
+                                "img" => {
+                                    let html_image = tag.attribute("src").and_then(|src| {
+                                        Image::identify(
+                                            src.to_string(),
+                                            source_range.clone(),
+                                            self.file_location_directory.clone(),
+                                        )
+                                    });
+                                    if let Some(mut html_image) = html_image {
+                                        if let Some(alt) = tag.attribute("alt") {
+                                            html_image.set_alt_text(alt.to_string().into());
+                                        }
+                                        if !text.is_empty() {
+                                            markdown_text_like.push(MarkdownParagraphChunk::Text(
+                                                ParsedMarkdownText {
+                                                    source_range: source_range.clone(),
+                                                    contents: std::mem::take(&mut text),
+                                                    highlights: std::mem::take(&mut highlights),
+                                                    region_ranges: std::mem::take(
+                                                        &mut region_ranges,
+                                                    ),
+                                                    regions: std::mem::take(&mut regions),
+                                                },
+                                            ));
+                                        }
+                                        markdown_text_like
+                                            .push(MarkdownParagraphChunk::Image(html_image));
+                                    }
+                                }
+                                _ => {}
+                            },
+                            markdown_html::HtmlToken::End(name) => match name.as_str() {
+                                "b" | "strong" if bold_depth > 0 => bold_depth -= 1,
+                                "i" | "em" if italic_depth > 0 => italic_depth -= 1,
+                                "s" | "del" | "strike" if strikethrough_depth > 0 => {
+                                    strikethrough_depth -= 1
+                                }
+                                "kbd" | "code" if kbd_depth > 0 => kbd_depth -= 1,
+                                "sup" if superscript_depth > 0 => superscript_depth -= 1,
+                                "sub" if subscript_depth > 0 => subscript_depth -= 1,
+                                "a" => link = None,
+                                _ => {}
+                            },
+                            markdown_html::HtmlToken::Text(_) => {}
+                        }
+                    }
+                }
 
-                Event::Text(t) => {
+                Event::FootnoteReference(label) => {
+                    let number = self
+                        .footnotes
+                        .get(label.as_ref())
+                        .map_or(0, |(number, _)| *number);
+                    let marker = map_script_text(&number.to_string(), superscript_char)
+                        .unwrap_or_else(|| format!("[{number}]"));
+                    text.push_str(&marker);
+                    region_ranges.push(prev_len..text.len());
+                    regions.push(ParsedRegion {
+                        code: false,
+                        link: Some(Link::Footnote {
+                            label: label.to_string().into(),
+                        }),
+                    });
+                }
+
+                Event::InlineMath(math) => {
+                    text.push_str(&render_math_text(math));
+                    highlights.push((
+                        prev_len..text.len(),
+                        MarkdownHighlight::Style(MarkdownHighlightStyle {
+                            italic: true,
+                            ..Default::default()
+                        }),
+                    ));
+                }
+
+                Event::DisplayMath(math) => {
+                    if !text.is_empty() {
+                        markdown_text_like.push(MarkdownParagraphChunk::Text(ParsedMarkdownText {
+                            source_range: source_range.clone(),
+                            contents: std::mem::take(&mut text),
+                            highlights: std::mem::take(&mut highlights),
+                            region_ranges: std::mem::take(&mut region_ranges),
+                            regions: std::mem::take(&mut regions),
+                        }));
+                    }
+                    markdown_text_like.push(MarkdownParagraphChunk::Math(ParsedMarkdownMath {
+                        source_range: current_range.clone(),
+                        contents: math.trim().to_string().into(),
+                    }));
+                }
+
+                Event::Text(t) if kbd_depth > 0 => {
                     text.push_str(t.as_ref());
+                    region_ranges.push(prev_len..text.len());
+                    regions.push(ParsedRegion {
+                        code: true,
+                        link: link.clone(),
+                    });
+                }
+
+                Event::Text(t) => {
+                    let t = if superscript_depth > 0 {
+                        map_script_text(t, superscript_char).unwrap_or_else(|| t.to_string())
+                    } else if subscript_depth > 0 {
+                        map_script_text(t, subscript_char).unwrap_or_else(|| t.to_string())
+                    } else {
+                        t.to_string()
+                    };
+                    let t = t.as_str();
+                    text.push_str(t);
                     let mut style = MarkdownHighlightStyle::default();
 
                     if bold_depth > 0 {
@@ -697,6 +879,122 @@ impl<'a> MarkdownParser<'a> {
         }
     }
 
+    async fn parse_footnote_definition(
+        &mut self,
+        label: String,
+        source_range: Range<usize>,
+    ) -> ParsedMarkdownFootnoteDefinition {
+        let mut children = vec![];
+        while !self.eof() {
+            if let Some((Event::End(TagEnd::FootnoteDefinition), _)) = self.current() {
+                self.cursor += 1;
+                break;
+            }
+            if let Some(block) = self.parse_block().await {
+                children.extend(block);
+            } else {
+                self.cursor += 1;
+            }
+        }
+
+        let (number, reference_offset) = self.footnotes.get(&label).copied().unwrap_or_default();
+        ParsedMarkdownFootnoteDefinition {
+            source_range,
+            label: label.into(),
+            number,
+            reference_offset,
+            children,
+        }
+    }
+
+    /// Collects the HTML of the block at the cursor, and advances past its end.
+    fn collect_html_block(&mut self) -> String {
+        let mut html = String::new();
+        while !self.eof() {
+            match self.current_event() {
+                Some(Event::Html(text)) => {
+                    html.push_str(text);
+                    self.cursor += 1;
+                }
+                Some(Event::End(TagEnd::HtmlBlock)) => {
+                    self.cursor += 1;
+                    break;
+                }
+                _ => break,
+            }
+        }
+        html
+    }
+
+    fn html_paragraphs(
+        &self,
+        html: &str,
+        source_range: Range<usize>,
+    ) -> Vec<ParsedMarkdownElement> {
+        html_to_paragraphs(html, source_range, self.file_location_directory.clone())
+            .into_iter()
+            .map(ParsedMarkdownElement::Paragraph)
+            .collect()
+    }
+
+    /// Parses a block of raw HTML. A `<details>` element that isn't closed
+    /// within the block contains the Markdown that follows, up to the HTML
+    /// block that closes it.
+    async fn parse_html_block(&mut self, source_range: Range<usize>) -> Vec<ParsedMarkdownElement> {
+        let html = self.collect_html_block();
+        let Some(details) = markdown_html::parse_details(&html) else {
+            return self.html_paragraphs(&html, source_range);
+        };
+
+        let summary = html_to_paragraphs(
+            details.summary,
+            source_range.clone(),
+            self.file_location_directory.clone(),
+        )
+        .into_iter()
+        .flatten()
+        .collect();
+        let mut children = self.html_paragraphs(details.body, source_range.clone());
+        let mut end = source_range.end;
+        let mut after = details
+            .after
+            .map(|after| self.html_paragraphs(after, source_range.clone()));
+
+        while after.is_none() && !self.eof() {
+            if let Some((Event::Start(Tag::HtmlBlock), range)) = self.current() {
+                let range = range.clone();
+                let cursor = self.cursor;
+                self.cursor += 1;
+                let html = self.collect_html_block();
+                if let Some((body, rest)) = markdown_html::split_details_end(&html) {
+                    children.extend(self.html_paragraphs(body, range.clone()));
+                    after = Some(self.html_paragraphs(rest, range.clone()));
+                    end = range.end;
+                    break;
+                }
+                self.cursor = cursor;
+            }
+
+            if let Some(block) = self.parse_block().await {
+                if let Some(range) = block.last().and_then(|block| block.source_range()) {
+                    end = end.max(range.end);
+                }
+                children.extend(block);
+            } else {
+                self.cursor += 1;
+            }
+        }
+
+        let mut elements = vec![ParsedMarkdownElement::Details(ParsedMarkdownDetails {
+            source_range: source_range.start..end,
+            summary,
+            open: details.open,
+            children,
+        })];
+        elements.extend(after.unwrap_or_default());
+        elements
+    }
+
     async fn parse_code_block(&mut self, language: Option<String>) -> ParsedMarkdownCodeBlock {
         let (_event, source_range) = self.previous().unwrap();
         let source_range = source_range.clone();
@@ -1409,6 +1707,145 @@ fn main() {
         );
     }
 
+    #[gpui::test]
+    async fn test_footnotes() {
+        let parsed = parse("Zed[^editor] is fast.\n\n[^editor]: A code editor.\n").await;
+
+        assert_eq!(parsed.children.len(), 2);
+        let ParsedMarkdownElement::Paragraph(paragraph) = &parsed.children[0] else {
+            panic!("Expected a paragraph");
+        };
+        let MarkdownParagraphChunk::Text(text) = &paragraph[0] else {
+            panic!("Expected a text");
+        };
+        assert_eq!(text.contents, "Zed¹ is fast.");
+        assert_eq!(text.region_ranges, vec![3..5]);
+        assert_eq!(
+            text.regions[0].link,
+            Some(Link::Footnote {
+                label: "editor".into()
+            })
+        );
+
+        let ParsedMarkdownElement::FootnoteDefinition(footnote) = &parsed.children[1] else {
+            panic!("Expected a footnote definition");
+        };
+        assert_eq!(footnote.label, "editor");
+        assert_eq!(footnote.number, 1);
+        assert_eq!(footnote.reference_offset, Some(3));
+        assert_eq!(
+            paragraph_contents(&footnote.children),
+            vec!["A code editor."]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_inline_html_formatting() {
+        let parsed = parse("Press <kbd>Ctrl</kbd> to see H<sub>2</sub>O<br>again").await;
+
+        let ParsedMarkdownElement::Paragraph(paragraph) = &parsed.children[0] else {
+            panic!("Expected a paragraph");
+        };
+        let MarkdownParagraphChunk::Text(text) = &paragraph[0] else {
+            panic!("Expected a text");
+        };
+        assert_eq!(text.contents, "Press Ctrl to see H₂O\nagain");
+        assert_eq!(text.region_ranges, vec![6..10]);
+        assert!(text.regions[0].code);
+    }
+
+    #[gpui::test]
+    async fn test_html_details_block() {
+        let parsed = parse(
+            "\
+<details open>
+<summary>More <b>details</b></summary>
+
+Some *hidden* text.
+
+</details>
+
+After
+",
+        )
+        .await;
+
+        assert_eq!(parsed.children.len(), 2);
+        let ParsedMarkdownElement::Details(details) = &parsed.children[0] else {
+            panic!("Expected a details element");
+        };
+        assert!(details.open);
+        assert_eq!(details.source_range, 0..87);
+        let MarkdownParagraphChunk::Text(summary) = &details.summary[0] else {
+            panic!("Expected a text");
+        };
+        assert_eq!(summary.contents, "More details");
+        assert_eq!(
+            paragraph_contents(&details.children),
+            vec!["Some hidden text."]
+        );
+        assert_eq!(paragraph_contents(&parsed.children[1..]), vec!["After"]);
+    }
+
+    #[gpui::test]
+    async fn test_html_block_drops_scripts() {
+        let parsed = parse("<div>\nHello <script>alert(1)</script>world\n</div>\n").await;
+
+        assert_eq!(paragraph_contents(&parsed.children), vec!["Hello world "]);
+    }
+
+    #[gpui::test]
+    async fn test_math() {
+        let parsed = parse("Area $x^2$ is\n$$\\frac{a}{b}$$").await;
+
+        let ParsedMarkdownElement::Paragraph(paragraph) = &parsed.children[0] else {
+            panic!("Expected a paragraph");
+        };
+        assert_eq!(paragraph.len(), 2);
+        let MarkdownParagraphChunk::Text(text) = &paragraph[0] else {
+            panic!("Expected a text");
+        };
+        assert_eq!(text.contents, "Area x² is ");
+        let MarkdownParagraphChunk::Math(math) = &paragraph[1] else {
+            panic!("Expected math");
+        };
+        assert_eq!(math.contents, "\\frac{a}{b}");
+    }
+
+    #[gpui::test]
+    async fn test_mermaid_code_block() {
+        let parsed = parse("```mermaid\ngraph TD\n  A --> B\n```\n\n```mermaid\npie\n```").await;
+
+        assert_eq!(parsed.children.len(), 2);
+        let ParsedMarkdownElement::Mermaid(diagram) = &parsed.children[0] else {
+            panic!("Expected a mermaid diagram");
+        };
+        assert_eq!(diagram.contents, "graph TD\n  A --> B");
+        assert_eq!(diagram.layout.shapes.len(), 2);
+        assert_eq!(
+            parsed.children[1],
+            code_block(Some("mermaid".to_string()), "pie", 35..53, None)
+        );
+    }
+
+    fn paragraph_contents(elements: &[ParsedMarkdownElement]) -> Vec<String> {
+        elements
+            .iter()
+            .filter_map(|element| match element {
+                ParsedMarkdownElement::Paragraph(paragraph) => Some(
+                    paragraph
+                        .iter()
+                        .filter_map(|chunk| match chunk {
+                            MarkdownParagraphChunk::Text(text) => Some(text.contents.as_str()),
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn rust_lang() -> Arc<Language> {
         Arc::new(Language::new(
             LanguageConfig {
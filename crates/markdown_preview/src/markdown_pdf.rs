@@ -0,0 +1,1226 @@
+//! Exports parsed Markdown as a PDF document.
+//!
+//! The document is laid out on A4 pages using the standard PDF fonts, so no
+//! fonts have to be embedded. This makes the export lossy: text outside of the
+//! Windows-1252 character set can't be represented by those fonts and is
+//! replaced with `?`, which is reported to the user after exporting.
+
+use crate::markdown_elements::{
+    HeadingLevel, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+    ParsedMarkdownCodeBlock, ParsedMarkdownElement, ParsedMarkdownListItemType,
+    ParsedMarkdownTable, ParsedMarkdownTableAlignment, ParsedMarkdownText,
+};
+use crate::markdown_export::ExportStyle;
+use crate::markdown_mermaid::{
+    self, MermaidArrowHead, MermaidLayout, MermaidLineStyle, MermaidShapeKind,
+};
+use gpui::{FontStyle, FontWeight, HighlightStyle, Hsla, Point, Rgba};
+use latex_math::render_math_text;
+use std::fmt::Write as _;
+
+const PAGE_WIDTH: f32 = 595.;
+const PAGE_HEIGHT: f32 = 842.;
+const PAGE_MARGIN: f32 = 56.;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2. * PAGE_MARGIN;
+
+const BODY_FONT_SIZE: f32 = 11.;
+const CODE_FONT_SIZE: f32 = 9.5;
+const FOOTNOTE_FONT_SIZE: f32 = 9.;
+const LINE_HEIGHT: f32 = 1.4;
+const BLOCK_SPACING: f32 = 8.;
+const LIST_INDENT: f32 = 18.;
+const BLOCK_QUOTE_INDENT: f32 = 14.;
+const CODE_BLOCK_PADDING: f32 = 6.;
+
+/// The standard fonts used by the document, in the order of their resource names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Font {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Monospace,
+}
+
+impl Font {
+    const ALL: [(Font, &'static str); 5] = [
+        (Font::Regular, "Helvetica"),
+        (Font::Bold, "Helvetica-Bold"),
+        (Font::Italic, "Helvetica-Oblique"),
+        (Font::BoldItalic, "Helvetica-BoldOblique"),
+        (Font::Monospace, "Courier"),
+    ];
+
+    fn new(bold: bool, italic: bool, monospace: bool) -> Self {
+        match (monospace, bold, italic) {
+            (true, _, _) => Font::Monospace,
+            (false, true, true) => Font::BoldItalic,
+            (false, true, false) => Font::Bold,
+            (false, false, true) => Font::Italic,
+            (false, false, false) => Font::Regular,
+        }
+    }
+
+    fn resource_name(self) -> usize {
+        Font::ALL
+            .iter()
+            .position(|(font, _)| *font == self)
+            .unwrap_or(0)
+            + 1
+    }
+
+    fn is_bold(self) -> bool {
+        matches!(self, Font::Bold | Font::BoldItalic)
+    }
+
+    /// The width of `text` at a font size of 1.
+    fn measure(self, text: &str) -> f32 {
+        text.chars()
+            .map(|c| {
+                let width = match (self, c as u32) {
+                    (Font::Monospace, _) => 600,
+                    (_, code @ 32..=126) if self.is_bold() => {
+                        HELVETICA_BOLD_WIDTHS[code as usize - 32]
+                    }
+                    (_, code @ 32..=126) => HELVETICA_WIDTHS[code as usize - 32],
+                    _ => 556,
+                };
+                width as f32 / 1000.
+            })
+            .sum()
+    }
+}
+
+/// The glyph widths of Helvetica for the printable ASCII characters, in
+/// thousandths of the font size.
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// The glyph widths of Helvetica-Bold for the printable ASCII characters, in
+/// thousandths of the font size.
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Serializes `parsed` as a PDF document.
+pub fn export_pdf(parsed: &ParsedMarkdown, title: &str, style: &ExportStyle) -> Vec<u8> {
+    let mut writer = PdfWriter::new(style);
+    for block in &parsed.children {
+        writer.write_block(block, 0.);
+    }
+    writer.finish(title)
+}
+
+/// A run of text with a single style.
+#[derive(Debug, Clone)]
+struct Run {
+    text: String,
+    font: Font,
+    size: f32,
+    color: Hsla,
+    background: Option<Hsla>,
+    underline: bool,
+    strikethrough: bool,
+    link: Option<String>,
+}
+
+/// A run placed on a line, at an offset from the line's start.
+#[derive(Debug)]
+struct PlacedRun {
+    run: Run,
+    x: f32,
+    width: f32,
+}
+
+#[derive(Default)]
+struct Page {
+    content: String,
+    links: Vec<(Bounds, String)>,
+}
+
+/// A rectangle in PDF coordinates, with its origin at the bottom left.
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    left: f32,
+    bottom: f32,
+    right: f32,
+    top: f32,
+}
+
+struct PdfWriter<'a> {
+    style: &'a ExportStyle,
+    pages: Vec<Page>,
+    /// The distance from the top of the current page to the next line.
+    y: f32,
+}
+
+impl<'a> PdfWriter<'a> {
+    fn new(style: &'a ExportStyle) -> Self {
+        let mut writer = Self {
+            style,
+            pages: Vec::new(),
+            y: 0.,
+        };
+        writer.new_page();
+        writer
+    }
+
+    fn new_page(&mut self) {
+        let mut page = Page::default();
+        writeln!(
+            page.content,
+            "{} 0 0 {PAGE_WIDTH} {PAGE_HEIGHT} re f",
+            fill_color(self.style.background_color)
+        )
+        .ok();
+        self.pages.push(page);
+        self.y = PAGE_MARGIN;
+    }
+
+    fn content(&mut self) -> &mut String {
+        &mut self.pages.last_mut().unwrap().content
+    }
+
+    /// Starts a new page when less than `height` is left on the current one.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y + height > PAGE_HEIGHT - PAGE_MARGIN && self.y > PAGE_MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn add_spacing(&mut self, spacing: f32) {
+        if self.y > PAGE_MARGIN {
+            self.y += spacing;
+        }
+    }
+
+    fn write_block(&mut self, block: &ParsedMarkdownElement, indent: f32) {
+        match block {
+            ParsedMarkdownElement::Heading(heading) => {
+                let size = match heading.level {
+                    HeadingLevel::H1 => 22.,
+                    HeadingLevel::H2 => 18.,
+                    HeadingLevel::H3 => 15.,
+                    HeadingLevel::H4 => 13.,
+                    HeadingLevel::H5 => 12.,
+                    HeadingLevel::H6 => 11.,
+                };
+                self.add_spacing(size * 0.6);
+                let runs = self.paragraph_runs(&heading.contents, size, true);
+                self.write_runs(runs, indent, CONTENT_WIDTH - indent, size);
+                if heading.level == HeadingLevel::H1 {
+                    let y = self.y + 3.;
+                    self.draw_line(
+                        PAGE_MARGIN + indent,
+                        y,
+                        PAGE_MARGIN + CONTENT_WIDTH,
+                        y,
+                        self.style.border_color,
+                        1.,
+                    );
+                    self.y += 4.;
+                }
+                self.y += BLOCK_SPACING;
+            }
+            ParsedMarkdownElement::Paragraph(paragraph) => {
+                let runs = self.paragraph_runs(paragraph, BODY_FONT_SIZE, false);
+                self.write_runs(runs, indent, CONTENT_WIDTH - indent, BODY_FONT_SIZE);
+                self.y += BLOCK_SPACING;
+            }
+            ParsedMarkdownElement::ListItem(item) => {
+                let item_indent = indent + item.depth.saturating_sub(1) as f32 * LIST_INDENT;
+                let bullet = match &item.item_type {
+                    ParsedMarkdownListItemType::Ordered(order) => format!("{order}."),
+                    ParsedMarkdownListItemType::Unordered => "•".to_string(),
+                    ParsedMarkdownListItemType::Task(true, _) => "[x]".to_string(),
+                    ParsedMarkdownListItemType::Task(false, _) => "[ ]".to_string(),
+                };
+                let bullet_width = Font::Regular.measure(&bullet) * BODY_FONT_SIZE + 6.;
+                self.ensure_space(BODY_FONT_SIZE * LINE_HEIGHT);
+                let baseline = self.baseline(BODY_FONT_SIZE);
+                self.draw_text(
+                    PAGE_MARGIN + item_indent,
+                    baseline,
+                    &bullet,
+                    Font::Regular,
+                    BODY_FONT_SIZE,
+                    self.style.text_color,
+                );
+
+                let content_indent = item_indent + bullet_width.max(LIST_INDENT);
+                for (index, block) in item.content.iter().enumerate() {
+                    self.write_block(block, content_indent);
+                    // Keep consecutive list items together.
+                    if index + 1 == item.content.len()
+                        && matches!(block, ParsedMarkdownElement::Paragraph(_))
+                    {
+                        self.y -= BLOCK_SPACING / 2.;
+                    }
+                }
+            }
+            ParsedMarkdownElement::Table(table) => self.write_table(table, indent),
+            ParsedMarkdownElement::BlockQuote(block_quote) => {
+                let start_page = self.pages.len() - 1;
+                let start_y = self.y;
+                for block in &block_quote.children {
+                    self.write_block(block, indent + BLOCK_QUOTE_INDENT);
+                }
+                self.draw_block_quote_bar(start_page, start_y, indent);
+            }
+            ParsedMarkdownElement::CodeBlock(code_block) => {
+                self.write_code_block(code_block, indent)
+            }
+            ParsedMarkdownElement::Mermaid(diagram) => {
+                self.write_mermaid(&diagram.layout, indent);
+                self.y += BLOCK_SPACING;
+            }
+            ParsedMarkdownElement::FootnoteDefinition(footnote) => {
+                self.ensure_space(FOOTNOTE_FONT_SIZE * LINE_HEIGHT);
+                let baseline = self.baseline(FOOTNOTE_FONT_SIZE);
+                self.draw_text(
+                    PAGE_MARGIN + indent,
+                    baseline,
+                    &format!("{}.", footnote.number),
+                    Font::Regular,
+                    FOOTNOTE_FONT_SIZE,
+                    self.style.text_muted_color,
+                );
+                for block in &footnote.children {
+                    match block {
+                        ParsedMarkdownElement::Paragraph(paragraph) => {
+                            let mut runs =
+                                self.paragraph_runs(paragraph, FOOTNOTE_FONT_SIZE, false);
+                            for run in &mut runs {
+                                if run.link.is_none() {
+                                    run.color = self.style.text_muted_color;
+                                }
+                            }
+                            self.write_runs(
+                                runs,
+                                indent + LIST_INDENT,
+                                CONTENT_WIDTH - indent - LIST_INDENT,
+                                FOOTNOTE_FONT_SIZE,
+                            );
+                            self.y += BLOCK_SPACING / 2.;
+                        }
+                        block => self.write_block(block, indent + LIST_INDENT),
+                    }
+                }
+            }
+            ParsedMarkdownElement::Details(details) => {
+                // PDFs are static, so the contents are always shown.
+                let runs = self.paragraph_runs(&details.summary, BODY_FONT_SIZE, true);
+                self.write_runs(runs, indent, CONTENT_WIDTH - indent, BODY_FONT_SIZE);
+                self.y += BLOCK_SPACING / 2.;
+                for block in &details.children {
+                    self.write_block(block, indent + LIST_INDENT);
+                }
+            }
+            ParsedMarkdownElement::HorizontalRule(_) => {
+                self.ensure_space(BLOCK_SPACING * 2.);
+                let y = self.y + BLOCK_SPACING;
+                self.draw_line(
+                    PAGE_MARGIN + indent,
+                    y,
+                    PAGE_MARGIN + CONTENT_WIDTH,
+                    y,
+                    self.style.border_color,
+                    1.5,
+                );
+                self.y += BLOCK_SPACING * 3.;
+            }
+        }
+    }
+
+    fn paragraph_runs(&self, paragraph: &MarkdownParagraph, size: f32, bold: bool) -> Vec<Run> {
+        let mut runs = Vec::new();
+        for chunk in paragraph {
+            match chunk {
+                MarkdownParagraphChunk::Text(text) => {
+                    self.text_runs(text, size, bold, &mut runs);
+                }
+                MarkdownParagraphChunk::Image(image) => {
+                    let text = match (&image.alt_text, &image.link) {
+                        (Some(alt_text), _) => format!("[{alt_text}]"),
+                        (None, link) => format!("[{link}]"),
+                    };
+                    runs.push(Run {
+                        text,
+                        font: Font::new(bold, true, false),
+                        size,
+                        color: self.style.text_muted_color,
+                        background: None,
+                        underline: false,
+                        strikethrough: false,
+                        link: match &image.link {
+                            Link::Web { url } => Some(url.clone()),
+                            _ => None,
+                        },
+                    });
+                }
+                MarkdownParagraphChunk::Math(math) => {
+                    runs.push(Run {
+                        text: render_math_text(&math.contents),
+                        font: Font::new(bold, true, false),
+                        size,
+                        color: self.style.text_color,
+                        background: None,
+                        underline: false,
+                        strikethrough: false,
+                        link: None,
+                    });
+                }
+            }
+        }
+        runs
+    }
+
+    fn text_runs(&self, text: &ParsedMarkdownText, size: f32, bold: bool, runs: &mut Vec<Run>) {
+        let contents = text.contents.as_str();
+        let mut boundaries = vec![0, contents.len()];
+        for range in text
+            .highlights
+            .iter()
+            .map(|(range, _)| range)
+            .chain(&text.region_ranges)
+        {
+            boundaries.push(range.start.min(contents.len()));
+            boundaries.push(range.end.min(contents.len()));
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        for segment in boundaries.windows(2) {
+            let Some(segment_text) = contents.get(segment[0]..segment[1]) else {
+                continue;
+            };
+            let contains = |range: &std::ops::Range<usize>| {
+                range.start <= segment[0] && segment[1] <= range.end
+            };
+
+            let mut highlight = HighlightStyle::default();
+            for (range, markdown_highlight) in &text.highlights {
+                if contains(range) {
+                    if let Some(style) =
+                        markdown_highlight.to_highlight_style(&self.style.syntax_theme)
+                    {
+                        highlight.highlight(style);
+                    }
+                }
+            }
+            let region = text
+                .region_ranges
+                .iter()
+                .zip(&text.regions)
+                .find(|(range, _)| contains(range))
+                .map(|(_, region)| region);
+            let code = region.is_some_and(|region| region.code);
+            let link = region.and_then(|region| region.link.as_ref());
+
+            let is_bold = bold
+                || highlight
+                    .font_weight
+                    .is_some_and(|weight| weight.0 > FontWeight::MEDIUM.0);
+            let is_italic = highlight.font_style == Some(FontStyle::Italic);
+            runs.push(Run {
+                text: segment_text.to_string(),
+                font: Font::new(is_bold, is_italic, code),
+                size: if code { size * 0.9 } else { size },
+                color: if link.is_some() {
+                    self.style.link_color
+                } else {
+                    highlight.color.unwrap_or(self.style.text_color)
+                },
+                background: code.then_some(self.style.code_span_background_color),
+                underline: highlight.underline.is_some(),
+                strikethrough: highlight.strikethrough.is_some(),
+                link: match link {
+                    Some(Link::Web { url }) => Some(url.clone()),
+                    _ => None,
+                },
+            });
+        }
+    }
+
+    /// Wraps the runs into lines of at most `width` and writes them.
+    fn write_runs(&mut self, runs: Vec<Run>, indent: f32, width: f32, size: f32) {
+        for line in wrap_runs(runs, width) {
+            let line_size = line
+                .iter()
+                .map(|placed| placed.run.size)
+                .fold(size, f32::max);
+            self.ensure_space(line_size * LINE_HEIGHT);
+            let baseline = self.baseline(line_size);
+            for placed in line {
+                let x = PAGE_MARGIN + indent + placed.x;
+                self.draw_run(x, baseline, placed.width, &placed.run);
+            }
+            self.y += line_size * LINE_HEIGHT;
+        }
+    }
+
+    fn write_code_block(&mut self, code_block: &ParsedMarkdownCodeBlock, indent: f32) {
+        let contents = code_block.contents.as_ref();
+        let mut runs = Vec::new();
+        let mut offset = 0;
+        let mut push_run = |text: &str, color: Hsla| {
+            runs.push(Run {
+                text: text.to_string(),
+                font: Font::Monospace,
+                size: CODE_FONT_SIZE,
+                color,
+                background: None,
+                underline: false,
+                strikethrough: false,
+                link: None,
+            });
+        };
+        for (range, highlight_id) in code_block.highlights.iter().flatten() {
+            if range.start < offset {
+                continue;
+            }
+            push_run(&contents[offset..range.start], self.style.text_color);
+            let color = highlight_id
+                .style(&self.style.syntax_theme)
+                .and_then(|style| style.color)
+                .unwrap_or(self.style.text_color);
+            push_run(&contents[range.clone()], color);
+            offset = range.end;
+        }
+        push_run(&contents[offset..], self.style.text_color);
+
+        let width = CONTENT_WIDTH - indent - 2. * CODE_BLOCK_PADDING;
+        let line_height = CODE_FONT_SIZE * LINE_HEIGHT;
+        let lines = wrap_runs(runs, width);
+        let line_count = lines.len();
+        for (index, line) in lines.into_iter().enumerate() {
+            let top_padding = if index == 0 { CODE_BLOCK_PADDING } else { 0. };
+            let bottom_padding = if index + 1 == line_count {
+                CODE_BLOCK_PADDING
+            } else {
+                0.
+            };
+            self.ensure_space(line_height + top_padding + bottom_padding);
+            self.fill_rect(
+                PAGE_MARGIN + indent,
+                self.y,
+                CONTENT_WIDTH - indent,
+                line_height + top_padding + bottom_padding,
+                self.style.code_block_background_color,
+            );
+            self.y += top_padding;
+            let baseline = self.baseline(CODE_FONT_SIZE);
+            for placed in line {
+                let x = PAGE_MARGIN + indent + CODE_BLOCK_PADDING + placed.x;
+                self.draw_run(x, baseline, placed.width, &placed.run);
+            }
+            self.y += line_height + bottom_padding;
+        }
+        self.y += BLOCK_SPACING;
+    }
+
+    fn write_table(&mut self, table: &ParsedMarkdownTable, indent: f32) {
+        let column_count = table
+            .body
+            .iter()
+            .chain([&table.header])
+            .map(|row| row.children.len())
+            .max()
+            .unwrap_or(0);
+        if column_count == 0 {
+            return;
+        }
+
+        let cell_padding = 4.;
+        let column_width = (CONTENT_WIDTH - indent) / column_count as f32;
+        for (row_index, row) in [&table.header].into_iter().chain(&table.body).enumerate() {
+            let is_header = row_index == 0;
+            let cells = row
+                .children
+                .iter()
+                .map(|cell| {
+                    let runs = self.paragraph_runs(cell, BODY_FONT_SIZE, is_header);
+                    wrap_runs(runs, column_width - 2. * cell_padding)
+                })
+                .collect::<Vec<_>>();
+            let line_height = BODY_FONT_SIZE * LINE_HEIGHT;
+            let row_height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1) as f32
+                * line_height
+                + 2. * cell_padding;
+
+            self.ensure_space(row_height);
+            for (column, lines) in cells.into_iter().enumerate() {
+                let cell_x = PAGE_MARGIN + indent + column as f32 * column_width;
+                let mut y = self.y + cell_padding;
+                for line in lines {
+                    let baseline = y + line_height * 0.75;
+                    let line_width = line.last().map_or(0., |placed| placed.x + placed.width);
+                    let alignment_offset = match table.column_alignments.get(column) {
+                        Some(ParsedMarkdownTableAlignment::Center) => {
+                            (column_width - 2. * cell_padding - line_width) / 2.
+                        }
+                        Some(ParsedMarkdownTableAlignment::Right) => {
+                            column_width - 2. * cell_padding - line_width
+                        }
+                        _ => 0.,
+                    };
+                    for placed in line {
+                        let x = cell_x + cell_padding + alignment_offset + placed.x;
+                        self.draw_run(x, baseline, placed.width, &placed.run);
+                    }
+                    y += line_height;
+                }
+            }
+            self.stroke_rect(
+                PAGE_MARGIN + indent,
+                self.y,
+                column_width * column_count as f32,
+                row_height,
+                self.style.border_color,
+                if is_header { 1.5 } else { 0.75 },
+            );
+            for column in 1..column_count {
+                let x = PAGE_MARGIN + indent + column as f32 * column_width;
+                self.draw_line(
+                    x,
+                    self.y,
+                    x,
+                    self.y + row_height,
+                    self.style.border_color,
+                    0.75,
+                );
+            }
+            self.y += row_height;
+        }
+        self.y += BLOCK_SPACING;
+    }
+
+    fn write_mermaid(&mut self, layout: &MermaidLayout, indent: f32) {
+        let em = BODY_FONT_SIZE
+            .min((CONTENT_WIDTH - indent) / layout.width.max(1.))
+            .max(1.);
+        let height = layout.height * em;
+        self.ensure_space(height);
+        let origin_x = PAGE_MARGIN + indent;
+        let origin_y = self.y;
+        let to_page = |point: Point<f32>| (origin_x + point.x * em, origin_y + point.y * em);
+
+        for line in &layout.lines {
+            let width = match line.style {
+                MermaidLineStyle::Thick => 2.,
+                MermaidLineStyle::Solid | MermaidLineStyle::Dashed => 0.75,
+            };
+            let dash = match line.style {
+                MermaidLineStyle::Dashed => "[3 2] 0 d",
+                MermaidLineStyle::Solid | MermaidLineStyle::Thick => "[] 0 d",
+            };
+            let mut path = format!(
+                "{} {width} w {dash}\n",
+                stroke_color(self.style.text_muted_color)
+            );
+            for (index, point) in line.points.iter().enumerate() {
+                let (x, y) = to_page(*point);
+                let operator = if index == 0 { "m" } else { "l" };
+                writeln!(path, "{} {} {operator}", x, PAGE_HEIGHT - y).ok();
+            }
+            path.push_str("S [] 0 d\n");
+            self.content().push_str(&path);
+
+            let Some((tip, direction)) = line.tip() else {
+                continue;
+            };
+            let along = |distance: f32, across: f32| {
+                to_page(Point {
+                    x: tip.x + direction.x * distance - direction.y * across,
+                    y: tip.y + direction.y * distance + direction.x * across,
+                })
+            };
+            match line.head {
+                MermaidArrowHead::None => {}
+                MermaidArrowHead::Arrow => {
+                    let corners = [along(0., 0.), along(-0.6, 0.3), along(-0.6, -0.3)];
+                    self.fill_polygon(&corners, self.style.text_muted_color, None);
+                }
+                MermaidArrowHead::Cross => {
+                    let (x1, y1) = along(-0.1, 0.25);
+                    let (x2, y2) = along(-0.6, -0.25);
+                    self.draw_line(x1, y1, x2, y2, self.style.text_muted_color, 1.);
+                    let (x1, y1) = along(-0.1, -0.25);
+                    let (x2, y2) = along(-0.6, 0.25);
+                    self.draw_line(x1, y1, x2, y2, self.style.text_muted_color, 1.);
+                }
+                MermaidArrowHead::Circle => {
+                    let corners = (0..8)
+                        .map(|step| {
+                            let angle = step as f32 * std::f32::consts::FRAC_PI_4;
+                            along(-0.25 + 0.25 * angle.cos(), 0.25 * angle.sin())
+                        })
+                        .collect::<Vec<_>>();
+                    self.fill_polygon(&corners, self.style.text_muted_color, None);
+                }
+            }
+        }
+
+        for shape in &layout.shapes {
+            let fill = if shape.kind == MermaidShapeKind::Note {
+                self.style.code_span_background_color
+            } else {
+                self.style.code_block_background_color
+            };
+            let corners = shape.polygon().unwrap_or_else(|| {
+                let bounds = shape.bounds;
+                vec![
+                    bounds.origin,
+                    Point {
+                        x: bounds.origin.x + bounds.size.width,
+                        y: bounds.origin.y,
+                    },
+                    Point {
+                        x: bounds.origin.x + bounds.size.width,
+                        y: bounds.origin.y + bounds.size.height,
+                    },
+                    Point {
+                        x: bounds.origin.x,
+                        y: bounds.origin.y + bounds.size.height,
+                    },
+                ]
+            });
+            let corners = corners.into_iter().map(to_page).collect::<Vec<_>>();
+            self.fill_polygon(&corners, fill, Some(self.style.border_color));
+
+            let center = Point {
+                x: shape.bounds.origin.x + shape.bounds.size.width / 2.,
+                y: shape.bounds.origin.y + shape.bounds.size.height / 2.,
+            };
+            self.draw_centered_text(&shape.text, to_page(center), em);
+        }
+
+        for label in &layout.labels {
+            if label.opaque {
+                let (width, height) = markdown_mermaid::text_size(&label.text);
+                let (x, y) = to_page(Point {
+                    x: label.center.x - width / 2. - 0.25,
+                    y: label.center.y - height / 2.,
+                });
+                self.fill_rect(
+                    x,
+                    y,
+                    (width + 0.5) * em,
+                    height * em,
+                    self.style.code_block_background_color,
+                );
+            }
+            self.draw_centered_text(&label.text, to_page(label.center), em);
+        }
+
+        self.y += height;
+    }
+
+    /// Draws the bar to the left of a block quote, which may span several pages.
+    fn draw_block_quote_bar(&mut self, start_page: usize, start_y: f32, indent: f32) {
+        let end_page = self.pages.len() - 1;
+        let end_y = self.y - BLOCK_SPACING;
+        let x = PAGE_MARGIN + indent + 2.;
+        for page in start_page..=end_page {
+            let top = if page == start_page {
+                start_y
+            } else {
+                PAGE_MARGIN
+            };
+            let bottom = if page == end_page {
+                end_y
+            } else {
+                PAGE_HEIGHT - PAGE_MARGIN
+            };
+            if bottom > top {
+                writeln!(
+                    self.pages[page].content,
+                    "{} 3 w {x} {} m {x} {} l S",
+                    stroke_color(self.style.border_color),
+                    PAGE_HEIGHT - top,
+                    PAGE_HEIGHT - bottom,
+                )
+                .ok();
+            }
+        }
+    }
+
+    /// The baseline of a line of text with the given font size at the current position.
+    fn baseline(&self, size: f32) -> f32 {
+        self.y + size * LINE_HEIGHT * 0.75
+    }
+
+    fn draw_run(&mut self, x: f32, baseline: f32, width: f32, run: &Run) {
+        if let Some(background) = run.background {
+            self.fill_rect(
+                x - 1.,
+                baseline - run.size * 0.9,
+                width + 2.,
+                run.size * 1.2,
+                background,
+            );
+        }
+        self.draw_text(x, baseline, &run.text, run.font, run.size, run.color);
+        if run.underline {
+            let y = baseline + run.size * 0.12;
+            self.draw_line(x, y, x + width, y, run.color, 0.5);
+        }
+        if run.strikethrough {
+            let y = baseline - run.size * 0.3;
+            self.draw_line(x, y, x + width, y, run.color, 0.5);
+        }
+        if let Some(url) = &run.link {
+            let bounds = Bounds {
+                left: x,
+                bottom: PAGE_HEIGHT - baseline - run.size * 0.25,
+                right: x + width,
+                top: PAGE_HEIGHT - baseline + run.size * 0.9,
+            };
+            self.pages
+                .last_mut()
+                .unwrap()
+                .links
+                .push((bounds, url.clone()));
+        }
+    }
+
+    fn draw_text(&mut self, x: f32, baseline: f32, text: &str, font: Font, size: f32, color: Hsla) {
+        writeln!(
+            self.content(),
+            "BT /F{} {size} Tf {} {x} {} Td ({}) Tj ET",
+            font.resource_name(),
+            fill_color(color),
+            PAGE_HEIGHT - baseline,
+            encode_text(text),
+        )
+        .ok();
+    }
+
+    /// Draws each line of `text` centered around `center`, with a font size of `em`.
+    fn draw_centered_text(&mut self, text: &str, center: (f32, f32), em: f32) {
+        let lines = text.lines().collect::<Vec<_>>();
+        let line_height = markdown_mermaid::LINE_HEIGHT * em;
+        let first_line_center = center.1 - lines.len().saturating_sub(1) as f32 * line_height / 2.;
+        for (index, line) in lines.iter().enumerate() {
+            let width = Font::Regular.measure(line) * em;
+            let baseline = first_line_center + index as f32 * line_height + em * 0.35;
+            self.draw_text(
+                center.0 - width / 2.,
+                baseline,
+                line,
+                Font::Regular,
+                em,
+                self.style.text_color,
+            );
+        }
+    }
+
+    /// Draws a line between two points, given as distances from the top left of the page.
+    fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: Hsla, width: f32) {
+        writeln!(
+            self.content(),
+            "{} {width} w {x1} {} m {x2} {} l S",
+            stroke_color(color),
+            PAGE_HEIGHT - y1,
+            PAGE_HEIGHT - y2,
+        )
+        .ok();
+    }
+
+    /// Fills a rectangle, whose origin is its distance from the top left of the page.
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Hsla) {
+        writeln!(
+            self.content(),
+            "{} {x} {} {width} {height} re f",
+            fill_color(color),
+            PAGE_HEIGHT - y - height,
+        )
+        .ok();
+    }
+
+    fn stroke_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: Hsla,
+        line_width: f32,
+    ) {
+        writeln!(
+            self.content(),
+            "{} {line_width} w {x} {} {width} {height} re S",
+            stroke_color(color),
+            PAGE_HEIGHT - y - height,
+        )
+        .ok();
+    }
+
+    fn fill_polygon(&mut self, corners: &[(f32, f32)], fill: Hsla, border: Option<Hsla>) {
+        let mut path = fill_color(fill);
+        if let Some(border) = border {
+            write!(path, " {} 0.75 w", stroke_color(border)).ok();
+        }
+        path.push('\n');
+        for (index, (x, y)) in corners.iter().enumerate() {
+            let operator = if index == 0 { "m" } else { "l" };
+            writeln!(path, "{x} {} {operator}", PAGE_HEIGHT - y).ok();
+        }
+        path.push_str(if border.is_some() { "b\n" } else { "f\n" });
+        self.content().push_str(&path);
+    }
+
+    /// Serializes the pages, along with the objects they refer to.
+    fn finish(self, title: &str) -> Vec<u8> {
+        const CATALOG_ID: usize = 1;
+        const PAGES_ID: usize = 2;
+        const INFO_ID: usize = 3;
+        const FIRST_FONT_ID: usize = 4;
+        let first_page_id = FIRST_FONT_ID + Font::ALL.len();
+
+        let mut objects = Vec::new();
+        let mut page_ids = Vec::new();
+        let mut next_id = first_page_id;
+        let mut page_objects = Vec::new();
+        for page in self.pages {
+            let page_id = next_id;
+            let content_id = page_id + 1;
+            let first_link_id = page_id + 2;
+            next_id = first_link_id + page.links.len();
+            page_ids.push(page_id);
+
+            let annotations = (first_link_id..next_id)
+                .map(|id| format!("{id} 0 R"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            page_objects.push(format!(
+                "<< /Type /Page /Parent {PAGES_ID} 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << {} >> >> /Contents {content_id} 0 R /Annots [{annotations}] >>",
+                (0..Font::ALL.len())
+                    .map(|index| format!("/F{} {} 0 R", index + 1, FIRST_FONT_ID + index))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ));
+            page_objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ));
+            for (bounds, url) in page.links {
+                page_objects.push(format!(
+                    "<< /Type /Annot /Subtype /Link /Rect [{} {} {} {}] /Border [0 0 0] \
+                     /A << /S /URI /URI ({}) >> >>",
+                    bounds.left,
+                    bounds.bottom,
+                    bounds.right,
+                    bounds.top,
+                    encode_text(&url)
+                ));
+            }
+        }
+
+        objects.push(format!("<< /Type /Catalog /Pages {PAGES_ID} 0 R >>"));
+        objects.push(format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{id} 0 R"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            page_ids.len()
+        ));
+        objects.push(format!(
+            "<< /Title ({}) /Producer (Zed) >>",
+            encode_text(title)
+        ));
+        for (_, font_name) in Font::ALL {
+            objects.push(format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{font_name} /Encoding /WinAnsiEncoding >>"
+            ));
+        }
+        objects.extend(page_objects);
+        debug_assert_eq!(objects.len() + 1, next_id);
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            write!(pdf, "{} 0 obj\n{object}\nendobj\n", index + 1).ok();
+        }
+        let xref_offset = pdf.len();
+        write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).ok();
+        for offset in offsets {
+            write!(pdf, "{offset:010} 00000 n \n").ok();
+        }
+        write!(
+            pdf,
+            "trailer\n<< /Size {} /Root {CATALOG_ID} 0 R /Info {INFO_ID} 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .ok();
+        pdf.into_bytes()
+    }
+}
+
+/// Breaks the runs into lines no wider than `max_width`. Line breaks in the
+/// text always start a new line.
+fn wrap_runs(runs: Vec<Run>, max_width: f32) -> Vec<Vec<PlacedRun>> {
+    let mut lines = vec![Vec::new()];
+    let mut x = 0.;
+    for run in runs {
+        for (index, text_line) in run.text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Vec::new());
+                x = 0.;
+            }
+            for word in split_words(text_line) {
+                let is_whitespace = word.chars().all(char::is_whitespace);
+                if is_whitespace && x == 0. && run.font != Font::Monospace {
+                    continue;
+                }
+                let mut width = run.font.measure(word) * run.size;
+                let mut word = word.to_string();
+                if !is_whitespace && x + width > max_width && x > 0. {
+                    lines.push(Vec::new());
+                    x = 0.;
+                }
+                // Words that don't fit on a line of their own are broken up.
+                while width > max_width && word.chars().count() > 1 {
+                    let mut split_at = 0;
+                    let mut split_width = 0.;
+                    for (offset, c) in word.char_indices() {
+                        let char_width = run.font.measure(&c.to_string()) * run.size;
+                        if split_width + char_width > max_width && offset > 0 {
+                            break;
+                        }
+                        split_width += char_width;
+                        split_at = offset + c.len_utf8();
+                    }
+                    let rest = word.split_off(split_at);
+                    push_placed(&mut lines, &run, word, x, split_width);
+                    lines.push(Vec::new());
+                    x = 0.;
+                    word = rest;
+                    width = run.font.measure(&word) * run.size;
+                }
+                push_placed(&mut lines, &run, word, x, width);
+                x += width;
+            }
+        }
+    }
+    for line in &mut lines {
+        if let Some(last) = line.last_mut() {
+            let trimmed_len = last.run.text.trim_end().len();
+            let trailing_width =
+                last.run.font.measure(&last.run.text[trimmed_len..]) * last.run.size;
+            last.run.text.truncate(trimmed_len);
+            last.width -= trailing_width;
+        }
+        line.retain(|placed| !placed.run.text.is_empty());
+    }
+    lines
+}
+
+fn push_placed(lines: &mut [Vec<PlacedRun>], run: &Run, text: String, x: f32, width: f32) {
+    if text.is_empty() {
+        return;
+    }
+    let line = lines.last_mut().unwrap();
+    // Merge with the previous piece of the same run, to keep the output small.
+    if let Some(last) = line.last_mut() {
+        if last.x + last.width == x && same_style(&last.run, run) {
+            last.run.text.push_str(&text);
+            last.width += width;
+            return;
+        }
+    }
+    line.push(PlacedRun {
+        run: Run {
+            text,
+            ..run.clone()
+        },
+        x,
+        width,
+    });
+}
+
+fn same_style(a: &Run, b: &Run) -> bool {
+    a.font == b.font
+        && a.size == b.size
+        && a.color == b.color
+        && a.background == b.background
+        && a.underline == b.underline
+        && a.strikethrough == b.strikethrough
+        && a.link == b.link
+}
+
+/// Splits text into words and the whitespace between them.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut in_whitespace = None;
+    for (offset, c) in text.char_indices() {
+        let is_whitespace = c.is_whitespace();
+        if in_whitespace.is_some_and(|in_whitespace| in_whitespace != is_whitespace) {
+            words.push(&text[start..offset]);
+            start = offset;
+        }
+        in_whitespace = Some(is_whitespace);
+    }
+    if start < text.len() {
+        words.push(&text[start..]);
+    }
+    words
+}
+
+/// Returns whether `text` contains characters that the standard PDF fonts
+/// can't represent, and that are replaced with `?` when exporting.
+pub fn has_unsupported_characters(text: &str) -> bool {
+    text.chars()
+        .any(|c| !c.is_control() && encode_char(c).is_none())
+}
+
+/// Encodes text as the contents of a PDF string in the Windows-1252 encoding.
+fn encode_text(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                encoded.push('\\');
+                encoded.push(c);
+            }
+            '\t' => encoded.push_str("    "),
+            ' '..='~' => encoded.push(c),
+            _ => {
+                let byte = encode_char(c).unwrap_or(b'?');
+                write!(encoded, "\\{byte:03o}").ok();
+            }
+        }
+    }
+    encoded
+}
+
+/// Returns the Windows-1252 byte for a character, if it has one.
+fn encode_char(c: char) -> Option<u8> {
+    let byte = match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u32 as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        '‰' => 0x89,
+        '‹' => 0x8b,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '™' => 0x99,
+        '›' => 0x9b,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+fn fill_color(color: Hsla) -> String {
+    let color = Rgba::from(color);
+    format!("{:.3} {:.3} {:.3} rg", color.r, color.g, color.b)
+}
+
+fn stroke_color(color: Hsla) -> String {
+    let color = Rgba::from(color);
+    format!("{:.3} {:.3} {:.3} RG", color.r, color.g, color.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_export::test_style;
+    use crate::markdown_parser::parse_markdown;
+
+    #[gpui::test]
+    async fn test_export_pdf() {
+        let markdown = (0..80)
+            .map(|index| format!("Paragraph {index} with a [link](https://zed.dev).\n\n"))
+            .collect::<String>();
+        let parsed = parse_markdown(&markdown, None, None).await;
+        let pdf = String::from_utf8(export_pdf(&parsed, "Notes (draft)", &test_style())).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Title (Notes \\(draft\\))"));
+        assert!(pdf.contains("(Paragraph 79 with a ) Tj"));
+        assert_eq!(pdf.matches("/URI (https://zed.dev)").count(), 80);
+
+        let page_count = pdf.matches("/Type /Page ").count();
+        assert!(page_count > 1);
+        assert!(pdf.contains(&format!("/Count {page_count}")));
+
+        // Every entry of the cross-reference table points at its object.
+        let xref_offset: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|rest| rest.lines().next())
+            .and_then(|offset| offset.parse().ok())
+            .unwrap();
+        let xref = &pdf[xref_offset..];
+        assert!(xref.starts_with("xref\n"));
+        for (index, entry) in xref
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .enumerate()
+        {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", index + 1)));
+        }
+    }
+
+    #[test]
+    fn test_wrap_runs() {
+        let run = Run {
+            text: "aaaa bbbb cccc\ndddd".to_string(),
+            font: Font::Monospace,
+            size: 10.,
+            color: gpui::black(),
+            background: None,
+            underline: false,
+            strikethrough: false,
+            link: None,
+        };
+        let lines = wrap_runs(vec![run], 60.)
+            .into_iter()
+            .map(|line| {
+                line.into_iter()
+                    .map(|placed| placed.run.text)
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec!["aaaa bbbb", "cccc", "dddd"]);
+    }
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(encode_text("a(b)\\c"), "a\\(b\\)\\\\c");
+        assert_eq!(encode_text("café • “x”"), "caf\\351 \\225 \\223x\\224");
+        assert!(!has_unsupported_characters("café • “x”\n"));
+        assert!(has_unsupported_characters("∑ 日本"));
+    }
+}
@@ -2,11 +2,18 @@ use gpui::{App, actions};
 use workspace::Workspace;
 
 pub mod markdown_elements;
+pub mod markdown_export;
+mod markdown_html;
+pub mod markdown_mermaid;
 pub mod markdown_parser;
+pub mod markdown_pdf;
 pub mod markdown_preview_view;
 pub mod markdown_renderer;
 
-actions!(markdown, [OpenPreview, OpenPreviewToTheSide]);
+actions!(
+    markdown,
+    [OpenPreview, OpenPreviewToTheSide, ExportAsHtml, ExportAsPdf]
+);
 
 pub fn init(cx: &mut App) {
     cx.observe_new(|workspace: &mut Workspace, window, cx| {
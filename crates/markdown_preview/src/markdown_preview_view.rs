@@ -1,8 +1,12 @@
 use std::sync::Arc;
 use std::time::Duration;
-use std::{ops::Range, path::PathBuf};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
+use collections::HashSet;
 use editor::scroll::Autoscroll;
 use editor::{Editor, EditorEvent};
 use gpui::{
@@ -15,19 +19,36 @@ use settings::Settings;
 use theme::ThemeSettings;
 use ui::prelude::*;
 use workspace::item::{Item, ItemHandle};
-use workspace::{Pane, Workspace};
+use workspace::notifications::{NotificationId, NotifyTaskExt as _};
+use workspace::{Pane, Toast, Workspace};
 
-use crate::OpenPreviewToTheSide;
 use crate::markdown_elements::ParsedMarkdownElement;
+use crate::markdown_export::{ExportStyle, export_html};
+use crate::markdown_pdf::{export_pdf, has_unsupported_characters};
 use crate::{
-    OpenPreview,
+    ExportAsHtml, ExportAsPdf, OpenPreview, OpenPreviewToTheSide,
     markdown_elements::ParsedMarkdown,
     markdown_parser::parse_markdown,
-    markdown_renderer::{RenderContext, render_markdown_block},
+    markdown_renderer::{FootnoteTarget, RenderContext, render_markdown_block},
 };
 
 const REPARSE_DEBOUNCE: Duration = Duration::from_millis(200);
 
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
 pub struct MarkdownPreviewView {
     workspace: WeakEntity<Workspace>,
     image_cache: Entity<RetainAllImageCache>,
@@ -39,6 +60,8 @@ pub struct MarkdownPreviewView {
     tab_content_text: SharedString,
     language_registry: Arc<LanguageRegistry>,
     parsing_markdown_task: Option<Task<Result<()>>>,
+    /// The source offsets of the `<details>` elements whose open state was toggled.
+    toggled_details: HashSet<usize>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -94,6 +117,83 @@ impl MarkdownPreviewView {
                 cx.notify();
             }
         });
+
+        workspace.register_action(move |workspace, _: &ExportAsHtml, window, cx| {
+            Self::export(workspace, ExportFormat::Html, window, cx);
+        });
+
+        workspace.register_action(move |workspace, _: &ExportAsPdf, window, cx| {
+            Self::export(workspace, ExportFormat::Pdf, window, cx);
+        });
+    }
+
+    /// Exports the Markdown of the active editor, or of the editor shown by the
+    /// active preview, to a file chosen by the user.
+    fn export(
+        workspace: &mut Workspace,
+        format: ExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let preview_editor = workspace
+            .active_item_as::<MarkdownPreviewView>(cx)
+            .and_then(|view| Some(view.read(cx).active_editor.as_ref()?.editor.clone()));
+        let Some(editor) =
+            preview_editor.or_else(|| Self::resolve_active_item_as_markdown_editor(workspace, cx))
+        else {
+            return;
+        };
+
+        let editor = editor.read(cx);
+        let buffer = editor.buffer().read(cx);
+        let contents = buffer.snapshot(cx).text();
+        let title = Path::new(buffer.title(cx).as_ref())
+            .file_stem()
+            .map_or_else(
+                || "Untitled".to_string(),
+                |stem| stem.to_string_lossy().into_owned(),
+            );
+        let file_location = Self::get_folder_for_active_editor(editor, cx);
+        let language_registry = workspace.project().read(cx).languages().clone();
+        let fs = workspace.project().read(cx).fs().clone();
+        let style = ExportStyle::from_theme(cx);
+        let path = cx.prompt_for_new_path(&file_location.clone().unwrap_or_default());
+        let is_lossy = matches!(format, ExportFormat::Pdf) && has_unsupported_characters(&contents);
+
+        cx.spawn(async move |workspace, cx| {
+            let Some(mut path) = path.await?? else {
+                return anyhow::Ok(());
+            };
+            if path.extension().is_none() {
+                path.set_extension(format.extension());
+            }
+
+            let document = cx
+                .background_spawn(async move {
+                    let parsed =
+                        parse_markdown(&contents, file_location, Some(language_registry)).await;
+                    match format {
+                        ExportFormat::Html => export_html(&parsed, &title, &style).into_bytes(),
+                        ExportFormat::Pdf => export_pdf(&parsed, &title, &style),
+                    }
+                })
+                .await;
+            fs.write(&path, &document).await?;
+
+            if is_lossy {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<ExportAsPdf>(),
+                            "Some characters can't be shown by the PDF fonts and were replaced with \"?\". Export as HTML to keep them.",
+                        ),
+                        cx,
+                    );
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
     }
 
     fn find_existing_preview_item_idx(pane: &Pane) -> Option<usize> {
@@ -186,6 +286,24 @@ impl MarkdownPreviewView {
                                                 }
                                             })
                                         }
+                                    })
+                                    .with_footnote_clicked_callback({
+                                        let view = view.clone();
+                                        move |target, _, cx| {
+                                            view.update(cx, |view, cx| {
+                                                view.scroll_to_footnote(target, cx)
+                                            })
+                                            .ok();
+                                        }
+                                    })
+                                    .with_details_toggled_callback(this.toggled_details.clone(), {
+                                        let view = view.clone();
+                                        move |offset, _, cx| {
+                                            view.update(cx, |view, cx| {
+                                                view.toggle_details(offset, cx)
+                                            })
+                                            .ok();
+                                        }
                                     });
 
                             let block = contents.children.get(ix).unwrap();
@@ -265,6 +383,7 @@ impl MarkdownPreviewView {
                 tab_content_text,
                 language_registry,
                 parsing_markdown_task: None,
+                toggled_details: HashSet::default(),
                 image_cache: RetainAllImageCache::new(cx),
             };
 
@@ -467,6 +586,37 @@ impl MarkdownPreviewView {
         block_index.unwrap_or_default()
     }
 
+    fn scroll_to_footnote(&mut self, target: FootnoteTarget, cx: &mut Context<Self>) {
+        let Some(contents) = &self.contents else {
+            return;
+        };
+        let block_index = match target {
+            FootnoteTarget::Definition(label) => contents.children.iter().position(|block| {
+                matches!(
+                    block,
+                    ParsedMarkdownElement::FootnoteDefinition(footnote) if footnote.label == label
+                )
+            }),
+            FootnoteTarget::Reference(offset) => {
+                Some(self.get_block_index_under_cursor(offset..offset))
+            }
+        };
+        if let Some(block_index) = block_index {
+            self.list_state.scroll_to_reveal_item(block_index);
+            cx.notify();
+        }
+    }
+
+    fn toggle_details(&mut self, offset: usize, cx: &mut Context<Self>) {
+        if !self.toggled_details.remove(&offset) {
+            self.toggled_details.insert(offset);
+        }
+        // The list caches the height of its items, so the block has to be remeasured.
+        let block_index = self.get_block_index_under_cursor(offset..offset);
+        self.list_state.splice(block_index..block_index + 1, 1);
+        cx.notify();
+    }
+
     fn should_apply_padding_between(
         current_block: &ParsedMarkdownElement,
         next_block: Option<&ParsedMarkdownElement>,
@@ -1,17 +1,26 @@
 use crate::markdown_elements::{
     HeadingLevel, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
-    ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
-    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
-    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+    ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownDetails,
+    ParsedMarkdownElement, ParsedMarkdownFootnoteDefinition, ParsedMarkdownHeading,
+    ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownMath,
+    ParsedMarkdownMermaidDiagram, ParsedMarkdownTable, ParsedMarkdownTableAlignment,
+    ParsedMarkdownTableRow,
 };
+use crate::markdown_mermaid::{
+    self, MermaidArrowHead, MermaidLine, MermaidLineStyle, MermaidShape, MermaidShapeKind,
+};
+use collections::HashSet;
 use gpui::{
     AbsoluteLength, AnyElement, App, AppContext as _, ClipboardItem, Context, DefiniteLength, Div,
-    Element, ElementId, Entity, HighlightStyle, Hsla, ImageSource, InteractiveText, IntoElement,
-    Keystroke, Length, Modifiers, ParentElement, Render, Resource, SharedString, Styled,
-    StyledText, TextStyle, WeakEntity, Window, div, img, rems,
+    Element, ElementId, Entity, HighlightStyle, Hsla, ImageFormat, ImageSource, InteractiveText,
+    IntoElement, Keystroke, Length, Modifiers, ParentElement, PathBuilder, Point, Render, Resource,
+    Rgba, SharedString, Styled, StyledText, TextStyle, WeakEntity, Window, canvas, div, img, point,
+    px, rems,
 };
+use latex_math::render_math_svg;
 use settings::Settings;
 use std::{
+    hash::{DefaultHasher, Hash, Hasher},
     ops::{Mul, Range},
     sync::Arc,
     vec,
@@ -26,6 +35,17 @@ use ui::{
 use workspace::{OpenOptions, OpenVisible, Workspace};
 
 type CheckboxClickedCallback = Arc<Box<dyn Fn(bool, Range<usize>, &mut Window, &mut App)>>;
+type FootnoteClickedCallback = Arc<Box<dyn Fn(FootnoteTarget, &mut Window, &mut App)>>;
+type DetailsToggledCallback = Arc<Box<dyn Fn(usize, &mut Window, &mut App)>>;
+
+/// Where clicking a footnote link navigates to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FootnoteTarget {
+    /// The definition of the footnote with this label.
+    Definition(SharedString),
+    /// The reference to a footnote at this offset in the source.
+    Reference(usize),
+}
 
 #[derive(Clone)]
 pub struct RenderContext {
@@ -43,6 +63,10 @@ pub struct RenderContext {
     syntax_theme: Arc<SyntaxTheme>,
     indent: usize,
     checkbox_clicked_callback: Option<CheckboxClickedCallback>,
+    footnote_clicked_callback: Option<FootnoteClickedCallback>,
+    details_toggled_callback: Option<DetailsToggledCallback>,
+    /// The source offsets of the `<details>` elements that were toggled in the preview.
+    toggled_details: HashSet<usize>,
 }
 
 impl RenderContext {
@@ -74,6 +98,9 @@ impl RenderContext {
             code_block_background_color: theme.colors().surface_background,
             code_span_background_color: theme.colors().editor_document_highlight_read_background,
             checkbox_clicked_callback: None,
+            footnote_clicked_callback: None,
+            details_toggled_callback: None,
+            toggled_details: HashSet::default(),
         }
     }
 
@@ -85,6 +112,27 @@ impl RenderContext {
         self
     }
 
+    pub fn with_footnote_clicked_callback(
+        mut self,
+        callback: impl Fn(FootnoteTarget, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.footnote_clicked_callback = Some(Arc::new(Box::new(callback)));
+        self
+    }
+
+    /// Renders the `<details>` elements starting at the offsets in `toggled_details`
+    /// in the opposite of their initial state, and calls `callback` with an
+    /// element's offset when it's toggled.
+    pub fn with_details_toggled_callback(
+        mut self,
+        toggled_details: HashSet<usize>,
+        callback: impl Fn(usize, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.toggled_details = toggled_details;
+        self.details_toggled_callback = Some(Arc::new(Box::new(callback)));
+        self
+    }
+
     fn next_id(&mut self, span: &Range<usize>) -> ElementId {
         let id = format!("markdown-{}-{}-{}", self.next_id, span.start, span.end);
         self.next_id += 1;
@@ -148,6 +196,9 @@ pub fn render_markdown_block(block: &ParsedMarkdownElement, cx: &mut RenderConte
         Table(table) => render_markdown_table(table, cx),
         BlockQuote(block_quote) => render_markdown_block_quote(block_quote, cx),
         CodeBlock(code_block) => render_markdown_code_block(code_block, cx),
+        Mermaid(diagram) => render_markdown_mermaid(diagram, cx),
+        FootnoteDefinition(footnote) => render_markdown_footnote_definition(footnote, cx),
+        Details(details) => render_markdown_details(details, cx),
         HorizontalRule(_) => render_markdown_rule(cx),
     }
 }
@@ -428,6 +479,7 @@ fn paragraph_len(paragraphs: &MarkdownParagraph) -> usize {
             MarkdownParagraphChunk::Text(text) => text.contents.len(),
             // TODO: Scale column width based on image size
             MarkdownParagraphChunk::Image(_) => 1,
+            MarkdownParagraphChunk::Math(math) => math.contents.len(),
         })
         .sum()
 }
@@ -600,6 +652,94 @@ fn render_markdown_code_block(
         .into_any()
 }
 
+fn render_markdown_footnote_definition(
+    parsed: &ParsedMarkdownFootnoteDefinition,
+    cx: &mut RenderContext,
+) -> AnyElement {
+    cx.indent += 1;
+
+    let children: Vec<AnyElement> = parsed
+        .children
+        .iter()
+        .map(|child| render_markdown_block(child, cx))
+        .collect();
+
+    cx.indent -= 1;
+
+    let back_link = parsed.reference_offset.map(|offset| {
+        div()
+            .id(cx.next_id(&parsed.source_range))
+            .flex_none()
+            .cursor_pointer()
+            .child("↩")
+            .tooltip(Tooltip::text("Back to reference"))
+            .when_some(cx.footnote_clicked_callback.clone(), |this, callback| {
+                this.on_click(move |_, window, cx| {
+                    callback(FootnoteTarget::Reference(offset), window, cx)
+                })
+            })
+    });
+
+    cx.with_common_p(h_flex())
+        .items_start()
+        .gap(cx.scaled_rems(0.5))
+        .text_size(cx.scaled_rems(0.875))
+        .text_color(cx.text_muted_color)
+        .child(div().flex_none().child(format!("{}.", parsed.number)))
+        .child(div().flex_1().children(children))
+        .children(back_link)
+        .into_any()
+}
+
+fn render_markdown_details(parsed: &ParsedMarkdownDetails, cx: &mut RenderContext) -> AnyElement {
+    let offset = parsed.source_range.start;
+    let is_open = parsed.open != cx.toggled_details.contains(&offset);
+
+    let summary = if parsed.summary.is_empty() {
+        vec!["Details".into_any_element()]
+    } else {
+        render_markdown_text(&parsed.summary, cx)
+    };
+    let header = h_flex()
+        .id(cx.next_id(&parsed.source_range))
+        .gap(cx.scaled_rems(0.25))
+        .cursor_pointer()
+        .child(
+            ui::Icon::new(if is_open {
+                IconName::ChevronDown
+            } else {
+                IconName::ChevronRight
+            })
+            .size(IconSize::Custom(cx.scaled_rems(0.875)))
+            .color(Color::Muted),
+        )
+        .children(summary)
+        .when_some(cx.details_toggled_callback.clone(), |this, callback| {
+            this.on_click(move |_, window, cx| callback(offset, window, cx))
+        });
+
+    let children: Vec<AnyElement> = if is_open {
+        cx.indent += 1;
+        let children = parsed
+            .children
+            .iter()
+            .map(|child| render_markdown_block(child, cx))
+            .collect();
+        cx.indent -= 1;
+        children
+    } else {
+        Vec::new()
+    };
+
+    cx.with_common_p(v_flex())
+        .gap(cx.scaled_rems(0.5))
+        .child(header)
+        .when(is_open, |this| {
+            this.child(div().pl(cx.scaled_rems(1.25)).children(children))
+        })
+        .into_any()
+}
+
 fn render_markdown_paragraph(parsed: &MarkdownParagraph, cx: &mut RenderContext) -> AnyElement {
     cx.with_common_p(div())
         .children(render_markdown_text(parsed, cx))
@@ -615,6 +755,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
     let workspace_clone = cx.workspace.clone();
     let code_span_bg_color = cx.code_span_background_color;
     let text_style = cx.text_style.clone();
+    let footnote_clicked_callback = cx.footnote_clicked_callback.clone();
 
     for parsed_region in parsed_new {
         match parsed_region {
@@ -652,6 +793,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                     }
                 }
                 let workspace = workspace_clone.clone();
+                let footnote_clicked_callback = footnote_clicked_callback.clone();
                 let element = div()
                     .child(
                         InteractiveText::new(
@@ -675,6 +817,15 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                             link_ranges,
                             move |clicked_range_ix, window, cx| match &links[clicked_range_ix] {
                                 Link::Web { url } => cx.open_url(url),
+                                Link::Footnote { label } => {
+                                    if let Some(callback) = &footnote_clicked_callback {
+                                        callback(
+                                            FootnoteTarget::Definition(label.clone()),
+                                            window,
+                                            cx,
+                                        );
+                                    }
+                                }
                                 Link::Path { path, .. } => {
                                     if let Some(workspace) = &workspace {
                                         _ = workspace.update(cx, |workspace, cx| {
@@ -703,6 +854,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                 let image_resource = match image.link.clone() {
                     Link::Web { url } => Resource::Uri(url.into()),
                     Link::Path { path, .. } => Resource::Path(Arc::from(path)),
+                    Link::Footnote { .. } => continue,
                 };
 
                 let element_id = cx.next_id(&image.source_range);
@@ -736,6 +888,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                             if window.modifiers().secondary() {
                                 match &link {
                                     Link::Web { url } => cx.open_url(url),
+                                    Link::Footnote { .. } => {}
                                     Link::Path { path, .. } => {
                                         if let Some(workspace) = &workspace {
                                             _ = workspace.update(cx, |workspace, cx| {
@@ -760,12 +913,280 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                     .into_any();
                 any_element.push(image_element);
             }
+
+            MarkdownParagraphChunk::Math(math) => {
+                any_element.push(render_markdown_math(math, cx));
+            }
         }
     }
 
     any_element
 }
 
+/// Display math is typeset at this multiple of its display size to stay crisp.
+const MATH_RENDER_SCALE: f32 = 2.0;
+
+fn render_markdown_math(parsed: &ParsedMarkdownMath, cx: &mut RenderContext) -> AnyElement {
+    let font_size = cx.buffer_text_style.font_size.to_pixels(cx.window_rem_size);
+    let math = render_math_svg(
+        &parsed.contents,
+        font_size.0,
+        Rgba::from(cx.text_color),
+        MATH_RENDER_SCALE,
+    );
+
+    let mut hasher = DefaultHasher::new();
+    math.svg.hash(&mut hasher);
+    let image = Arc::new(gpui::Image {
+        format: ImageFormat::Svg,
+        bytes: math.svg.into_bytes(),
+        id: hasher.finish(),
+    });
+
+    h_flex()
+        .id(cx.next_id(&parsed.source_range))
+        .w_full()
+        .justify_center()
+        .py(cx.scaled_rems(0.5))
+        .overflow_x_scroll()
+        .child(
+            img(ImageSource::Image(image))
+                .flex_none()
+                .w(px(math.width))
+                .h(px(math.height))
+                .with_fallback({
+                    let contents = parsed.contents.clone();
+                    move || div().child(contents.clone()).into_any_element()
+                }),
+        )
+        .into_any()
+}
+
+fn render_markdown_mermaid(
+    parsed: &ParsedMarkdownMermaidDiagram,
+    cx: &mut RenderContext,
+) -> AnyElement {
+    let em = cx.buffer_text_style.font_size.to_pixels(cx.window_rem_size);
+    let layout = &parsed.layout;
+    let line_color = cx.text_muted_color;
+    let border_color = cx.border_color;
+    let background_color = cx.code_block_background_color;
+    let note_color = cx.code_span_background_color;
+
+    let shapes = layout.shapes.iter().map(|shape| {
+        let element = div()
+            .absolute()
+            .left(em * shape.bounds.origin.x)
+            .top(em * shape.bounds.origin.y)
+            .w(em * shape.bounds.size.width)
+            .h(em * shape.bounds.size.height)
+            .flex()
+            .items_center()
+            .justify_center()
+            .text_center()
+            .child(shape.text.clone());
+        match shape.kind {
+            // These outlines are painted on the canvas.
+            MermaidShapeKind::Diamond | MermaidShapeKind::Hexagon => element,
+            MermaidShapeKind::Rectangle => element.border_1().border_color(border_color),
+            MermaidShapeKind::Rounded => element
+                .border_1()
+                .border_color(border_color)
+                .rounded(em * 0.3),
+            MermaidShapeKind::Stadium | MermaidShapeKind::Circle => {
+                element.border_1().border_color(border_color).rounded_full()
+            }
+            MermaidShapeKind::Note => element.border_1().border_color(border_color).bg(note_color),
+        }
+        .when(shape.kind != MermaidShapeKind::Note, |this| {
+            this.bg(background_color)
+        })
+    });
+
+    let labels = layout.labels.iter().map(|label| {
+        let (width, height) = markdown_mermaid::text_size(&label.text);
+        let width = width + 0.5;
+        div()
+            .absolute()
+            .left(em * (label.center.x - width / 2.0))
+            .top(em * (label.center.y - height / 2.0))
+            .w(em * width)
+            .h(em * height)
+            .flex()
+            .justify_center()
+            .text_center()
+            .child(
+                div()
+                    .when(label.opaque, |this| this.bg(background_color).px(em * 0.25))
+                    .child(label.text.clone()),
+            )
+    });
+
+    let lines = layout.lines.clone();
+    let polygons = layout
+        .shapes
+        .iter()
+        .filter_map(MermaidShape::polygon)
+        .collect::<Vec<_>>();
+    let diagram = div()
+        .relative()
+        .flex_none()
+        .w(em * layout.width)
+        .h(em * layout.height)
+        .text_size(em)
+        .line_height(em * markdown_mermaid::LINE_HEIGHT)
+        .child(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, _| {
+                    let to_pixels = |position: Point<f32>| {
+                        point(
+                            bounds.origin.x + em * position.x,
+                            bounds.origin.y + em * position.y,
+                        )
+                    };
+                    for line in &lines {
+                        paint_mermaid_line(line, line_color, &to_pixels, window);
+                    }
+                    for polygon in &polygons {
+                        paint_mermaid_polygon(
+                            polygon,
+                            background_color,
+                            border_color,
+                            &to_pixels,
+                            window,
+                        );
+                    }
+                },
+            )
+            .absolute()
+            .size_full(),
+        )
+        .children(shapes)
+        .children(labels);
+
+    cx.with_common_p(div())
+        .id(cx.next_id(&parsed.source_range))
+        .p_3()
+        .bg(cx.code_block_background_color)
+        .rounded_sm()
+        .overflow_x_scroll()
+        .child(diagram)
+        .into_any()
+}
+
+fn paint_mermaid_line(
+    line: &MermaidLine,
+    color: Hsla,
+    to_pixels: &impl Fn(Point<f32>) -> Point<Pixels>,
+    window: &mut Window,
+) {
+    let width = match line.style {
+        MermaidLineStyle::Thick => px(2.5),
+        MermaidLineStyle::Solid | MermaidLineStyle::Dashed => px(1.),
+    };
+    let mut builder = PathBuilder::stroke(width);
+    for segment in line.points.windows(2) {
+        let (start, end) = (segment[0], segment[1]);
+        if line.style == MermaidLineStyle::Dashed {
+            let length = ((end.x - start.x).powi(2) + (end.y - start.y).powi(2)).sqrt();
+            let mut offset = 0.0;
+            while offset < length {
+                let dash_end = (offset + 0.3).min(length);
+                builder.move_to(to_pixels(lerp(start, end, offset / length)));
+                builder.line_to(to_pixels(lerp(start, end, dash_end / length)));
+                offset += 0.5;
+            }
+        } else {
+            builder.move_to(to_pixels(start));
+            builder.line_to(to_pixels(end));
+        }
+    }
+    if let Ok(path) = builder.build() {
+        window.paint_path(path, color);
+    }
+
+    let Some((tip, direction)) = line.tip() else {
+        return;
+    };
+    let normal = point(-direction.y, direction.x);
+    let along = |distance: f32, across: f32| {
+        point(
+            tip.x + direction.x * distance + normal.x * across,
+            tip.y + direction.y * distance + normal.y * across,
+        )
+    };
+    match line.head {
+        MermaidArrowHead::None => {}
+        MermaidArrowHead::Arrow => {
+            let mut builder = PathBuilder::fill();
+            builder.move_to(to_pixels(tip));
+            builder.line_to(to_pixels(along(-0.6, 0.3)));
+            builder.line_to(to_pixels(along(-0.6, -0.3)));
+            builder.close();
+            if let Ok(path) = builder.build() {
+                window.paint_path(path, color);
+            }
+        }
+        MermaidArrowHead::Cross => {
+            let mut builder = PathBuilder::stroke(px(1.5));
+            builder.move_to(to_pixels(along(-0.1, 0.25)));
+            builder.line_to(to_pixels(along(-0.6, -0.25)));
+            builder.move_to(to_pixels(along(-0.1, -0.25)));
+            builder.line_to(to_pixels(along(-0.6, 0.25)));
+            if let Ok(path) = builder.build() {
+                window.paint_path(path, color);
+            }
+        }
+        MermaidArrowHead::Circle => {
+            let center = along(-0.25, 0.0);
+            let mut builder = PathBuilder::fill();
+            for step in 0..12 {
+                let angle = step as f32 * std::f32::consts::TAU / 12.0;
+                let position = point(center.x + 0.25 * angle.cos(), center.y + 0.25 * angle.sin());
+                if step == 0 {
+                    builder.move_to(to_pixels(position));
+                } else {
+                    builder.line_to(to_pixels(position));
+                }
+            }
+            builder.close();
+            if let Ok(path) = builder.build() {
+                window.paint_path(path, color);
+            }
+        }
+    }
+}
+
+fn paint_mermaid_polygon(
+    corners: &[Point<f32>],
+    background_color: Hsla,
+    border_color: Hsla,
+    to_pixels: &impl Fn(Point<f32>) -> Point<Pixels>,
+    window: &mut Window,
+) {
+    for (mut builder, color) in [
+        (PathBuilder::fill(), background_color),
+        (PathBuilder::stroke(px(1.)), border_color),
+    ] {
+        builder.move_to(to_pixels(corners[0]));
+        for corner in &corners[1..] {
+            builder.line_to(to_pixels(*corner));
+        }
+        builder.close();
+        if let Ok(path) = builder.build() {
+            window.paint_path(path, color);
+        }
+    }
+}
+
+fn lerp(start: Point<f32>, end: Point<f32>, t: f32) -> Point<f32> {
+    point(
+        start.x + (end.x - start.x) * t,
+        start.y + (end.y - start.y) * t,
+    )
+}
+
 fn render_markdown_rule(cx: &mut RenderContext) -> AnyElement {
     let rule = div().w_full().h(cx.scaled_rems(0.125)).bg(cx.border_color);
     div().py(cx.scaled_rems(0.5)).child(rule).into_any()
@@ -142,6 +142,8 @@ pub struct MultiBufferDiffHunk {
     pub diff_base_byte_range: Range<usize>,
     /// Whether or not this hunk also appears in the 'secondary diff'.
     pub secondary_status: DiffHunkSecondaryStatus,
+    /// Whether this hunk is one side of a moved block.
+    pub moved: bool,
 }
 
 impl MultiBufferDiffHunk {
@@ -156,6 +158,7 @@ impl MultiBufferDiffHunk {
         DiffHunkStatus {
             kind,
             secondary: self.secondary_status,
+            moved: self.moved,
         }
     }
 
@@ -320,6 +323,7 @@ struct DiffTransformHunkInfo {
     excerpt_id: ExcerptId,
     hunk_start_anchor: text::Anchor,
     hunk_secondary_status: DiffHunkSecondaryStatus,
+    hunk_moved: bool,
 }
 
 impl Eq for DiffTransformHunkInfo {}
@@ -3260,6 +3264,7 @@ impl MultiBuffer {
                         excerpt_id: excerpt.id,
                         hunk_start_anchor: hunk.buffer_range.start,
                         hunk_secondary_status: hunk.secondary_status,
+                        hunk_moved: hunk.moved,
                     };
 
                     let hunk_excerpt_start = excerpt_start
@@ -3794,6 +3799,7 @@ impl MultiBufferSnapshot {
                 buffer_range: hunk.buffer_range.clone(),
                 diff_base_byte_range: hunk.diff_base_byte_range.clone(),
                 secondary_status: hunk.secondary_status,
+                moved: hunk.moved,
             })
         })
     }
@@ -6535,9 +6541,10 @@ where
                     excerpt,
                     has_trailing_newline: *has_trailing_newline,
                     is_main_buffer: false,
-                    diff_hunk_status: Some(DiffHunkStatus::deleted(
-                        hunk_info.hunk_secondary_status,
-                    )),
+                    diff_hunk_status: Some(DiffHunkStatus {
+                        moved: hunk_info.hunk_moved,
+                        ..DiffHunkStatus::deleted(hunk_info.hunk_secondary_status)
+                    }),
                     buffer_range: buffer_start..buffer_end,
                     range: start..end,
                 });
@@ -6580,8 +6587,10 @@ where
                     excerpt,
                     has_trailing_newline,
                     is_main_buffer: true,
-                    diff_hunk_status: inserted_hunk_info
-                        .map(|info| DiffHunkStatus::added(info.hunk_secondary_status)),
+                    diff_hunk_status: inserted_hunk_info.map(|info| DiffHunkStatus {
+                        moved: info.hunk_moved,
+                        ..DiffHunkStatus::added(info.hunk_secondary_status)
+                    }),
                     buffer_range: buffer_start..buffer_end,
                     range: start..end,
                 })
@@ -196,6 +196,12 @@ pub struct ChatResponseDelta {
     pub done_reason: Option<String>,
     #[allow(unused)]
     pub done: bool,
+    /// The number of tokens in the prompt, reported in the final response.
+    #[serde(default)]
+    pub prompt_eval_count: Option<u32>,
+    /// The number of tokens generated, reported in the final response.
+    #[serde(default)]
+    pub eval_count: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize)]
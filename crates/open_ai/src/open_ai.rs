@@ -220,6 +220,8 @@ pub struct Request {
     pub messages: Vec<RequestMessage>,
     pub stream: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub stop: Vec<String>,
@@ -231,6 +233,15 @@ pub struct Request {
     pub parallel_tool_calls: Option<bool>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<ToolDefinition>,
+    /// A key that routes requests sharing a prompt prefix to the same cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamOptions {
+    /// Whether to stream a final chunk reporting the token usage of the request.
+    pub include_usage: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -545,12 +556,30 @@ pub async fn stream_completion(
         return Ok(stream::once(future::ready(response_stream_event)).boxed());
     }
 
+    stream_completion_with_headers(client, api_url, api_key, &[], request).await
+}
+
+/// Streams a completion from an OpenAI-compatible API, sending the given
+/// headers along with the request.
+pub async fn stream_completion_with_headers(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: &str,
+    headers: &[(String, String)],
+    request: Request,
+) -> Result<BoxStream<'static, Result<ResponseStreamEvent>>> {
     let uri = format!("{api_url}/chat/completions");
-    let request_builder = HttpRequest::builder()
+    let mut request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_key));
+        .header("Content-Type", "application/json");
+    // Servers that don't check API keys may be used without one.
+    if !api_key.is_empty() {
+        request_builder = request_builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+    for (name, value) in headers {
+        request_builder = request_builder.header(name.as_str(), value.as_str());
+    }
 
     let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
     let mut response = client.send(request).await?;
@@ -607,6 +636,53 @@ pub async fn stream_completion(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListModelsResponse {
+    pub data: Vec<ModelEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ModelEntry {
+    pub id: String,
+}
+
+/// Lists the models served by an OpenAI-compatible API.
+pub async fn list_models(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: &str,
+    headers: &[(String, String)],
+) -> Result<Vec<ModelEntry>> {
+    let uri = format!("{api_url}/models");
+    let mut request_builder = HttpRequest::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Accept", "application/json");
+    if !api_key.is_empty() {
+        request_builder = request_builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+    for (name, value) in headers {
+        request_builder = request_builder.header(name.as_str(), value.as_str());
+    }
+
+    let request = request_builder.body(AsyncBody::default())?;
+    let mut response = client.send(request).await?;
+
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to list models: {} {}",
+        response.status(),
+        body,
+    );
+
+    let response: ListModelsResponse =
+        serde_json::from_str(&body).context("Unable to parse models response")?;
+    Ok(response.data)
+}
+
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum OpenAiEmbeddingModel {
     #[serde(rename = "text-embedding-3-small")]
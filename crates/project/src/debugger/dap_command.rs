@@ -3,9 +3,9 @@ use std::sync::Arc;
 use anyhow::{Ok, Result, anyhow};
 use dap::{
     Capabilities, ContinueArguments, ExceptionFilterOptions, InitializeRequestArguments,
-    InitializeRequestArgumentsPathFormat, NextArguments, SetVariableResponse, SourceBreakpoint,
-    StepInArguments, StepOutArguments, SteppingGranularity, ValueFormat, Variable,
-    VariablesArgumentsFilter,
+    InitializeRequestArgumentsPathFormat, NextArguments, SetExpressionResponse,
+    SetVariableResponse, SourceBreakpoint, StepInArguments, StepOutArguments, SteppingGranularity,
+    ValueFormat, Variable, VariablesArgumentsFilter,
     client::SessionId,
     proto_conversions::ProtoConversion,
     requests::{Continue, Next},
@@ -970,6 +970,94 @@ impl DapCommand for SetVariableValueCommand {
     }
 }
 
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub(crate) struct SetExpressionCommand {
+    pub expression: String,
+    pub value: String,
+    pub frame_id: Option<u64>,
+}
+
+impl LocalDapCommand for SetExpressionCommand {
+    type Response = SetExpressionResponse;
+    type DapRequest = dap::requests::SetExpression;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_set_expression.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetExpressionArguments {
+            expression: self.expression.clone(),
+            value: self.value.clone(),
+            frame_id: self.frame_id,
+            format: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+impl DapCommand for SetExpressionCommand {
+    type ProtoRequest = proto::DapSetExpressionRequest;
+    type ProtoResponse = proto::DapSetExpressionResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapSetExpressionRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            expression: self.expression.clone(),
+            value: self.value.clone(),
+            frame_id: self.frame_id,
+        }
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            expression: request.expression.clone(),
+            value: request.value.clone(),
+            frame_id: request.frame_id,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapSetExpressionResponse {
+            client_id: debug_client_id.to_proto(),
+            value: message.value,
+            variable_type: message.type_,
+            variables_reference: message.variables_reference,
+            named_variables: message.named_variables,
+            indexed_variables: message.indexed_variables,
+            memory_reference: message.memory_reference,
+            value_location_reference: message.value_location_reference,
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(SetExpressionResponse {
+            value: message.value,
+            type_: message.variable_type,
+            presentation_hint: None,
+            variables_reference: message.variables_reference,
+            named_variables: message.named_variables,
+            indexed_variables: message.indexed_variables,
+            memory_reference: message.memory_reference,
+            value_location_reference: message.value_location_reference,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct RestartStackFrameCommand {
     pub stack_frame_id: u64,
@@ -1524,6 +1612,246 @@ impl DapCommand for ThreadsCommand {
     }
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct ReadMemoryCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    pub count: u64,
+}
+
+impl LocalDapCommand for ReadMemoryCommand {
+    type Response = dap::ReadMemoryResponse;
+    type DapRequest = dap::requests::ReadMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_read_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::ReadMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            count: self.count,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+impl DapCommand for ReadMemoryCommand {
+    type ProtoRequest = proto::DapReadMemoryRequest;
+    type ProtoResponse = proto::DapReadMemoryResponse;
+    const CACHEABLE: bool = true;
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapReadMemoryRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            count: self.count,
+        }
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            count: request.count,
+        }
+    }
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(dap::ReadMemoryResponse {
+            address: message.address,
+            unreadable_bytes: message.unreadable_bytes,
+            data: message.data,
+        })
+    }
+
+    fn response_to_proto(
+        _debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapReadMemoryResponse {
+            address: message.address,
+            unreadable_bytes: message.unreadable_bytes,
+            data: message.data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct WriteMemoryCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    /// The bytes to write, encoded using base64.
+    pub data: String,
+}
+
+impl LocalDapCommand for WriteMemoryCommand {
+    type Response = dap::WriteMemoryResponse;
+    type DapRequest = dap::requests::WriteMemory;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_write_memory_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::WriteMemoryArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            allow_partial: Some(false),
+            data: self.data.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+impl DapCommand for WriteMemoryCommand {
+    type ProtoRequest = proto::DapWriteMemoryRequest;
+    type ProtoResponse = proto::DapWriteMemoryResponse;
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapWriteMemoryRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            allow_partial: Some(false),
+            data: self.data.clone(),
+        }
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            data: request.data.clone(),
+        }
+    }
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(dap::WriteMemoryResponse {
+            offset: message.offset,
+            bytes_written: message.bytes_written,
+        })
+    }
+
+    fn response_to_proto(
+        _debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapWriteMemoryResponse {
+            offset: message.offset,
+            bytes_written: message.bytes_written,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct DisassembleCommand {
+    pub memory_reference: String,
+    pub offset: Option<i64>,
+    pub instruction_offset: Option<i64>,
+    pub instruction_count: u64,
+}
+
+impl LocalDapCommand for DisassembleCommand {
+    type Response = Vec<dap::DisassembledInstruction>;
+    type DapRequest = dap::requests::Disassemble;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_disassemble_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DisassembleArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            instruction_offset: self.instruction_offset,
+            instruction_count: self.instruction_count,
+            resolve_symbols: Some(true),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.instructions)
+    }
+}
+
+impl DapCommand for DisassembleCommand {
+    type ProtoRequest = proto::DapDisassembleRequest;
+    type ProtoResponse = proto::DapDisassembleResponse;
+    const CACHEABLE: bool = true;
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapDisassembleRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            memory_reference: self.memory_reference.clone(),
+            offset: self.offset,
+            instruction_offset: self.instruction_offset,
+            instruction_count: self.instruction_count,
+            resolve_symbols: Some(true),
+        }
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            memory_reference: request.memory_reference.clone(),
+            offset: request.offset,
+            instruction_offset: request.instruction_offset,
+            instruction_count: request.instruction_count,
+        }
+    }
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(Vec::from_proto(message.instructions))
+    }
+
+    fn response_to_proto(
+        _debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapDisassembleResponse {
+            instructions: message.to_proto(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub(super) struct Initialize {
     pub(super) adapter_id: String,
@@ -1704,6 +2032,96 @@ impl LocalDapCommand for SetExceptionBreakpoints {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetInstructionBreakpoints {
+    pub(super) breakpoints: Vec<dap::InstructionBreakpoint>,
+}
+
+impl LocalDapCommand for SetInstructionBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetInstructionBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetInstructionBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct DataBreakpointInfoCommand {
+    pub(super) variables_reference: Option<u64>,
+    pub(super) name: String,
+    pub(super) frame_id: Option<u64>,
+}
+
+impl LocalDapCommand for DataBreakpointInfoCommand {
+    type Response = dap::DataBreakpointInfoResponse;
+    type DapRequest = dap::requests::DataBreakpointInfo;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DataBreakpointInfoArguments {
+            variables_reference: self.variables_reference,
+            name: self.name.clone(),
+            frame_id: self.frame_id,
+            bytes: None,
+            as_address: None,
+            mode: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub(super) struct SetDataBreakpoints {
+    pub(super) breakpoints: Vec<dap::DataBreakpoint>,
+}
+
+impl LocalDapCommand for SetDataBreakpoints {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetDataBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_data_breakpoints.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetDataBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub(super) struct LocationsCommand {
     pub(super) reference: u64,
@@ -1774,3 +2192,37 @@ impl DapCommand for LocationsCommand {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_expression_response_proto_round_trip() {
+        let command = SetExpressionCommand {
+            expression: "counter".into(),
+            value: "2".into(),
+            frame_id: Some(1),
+        };
+        let response = SetExpressionResponse {
+            value: "2".into(),
+            type_: Some("int".into()),
+            presentation_hint: None,
+            variables_reference: Some(3),
+            named_variables: Some(4),
+            indexed_variables: Some(5),
+            memory_reference: Some("0x2000".into()),
+            value_location_reference: Some(6),
+        };
+
+        let proto = SetExpressionCommand::response_to_proto(SessionId::from_proto(1), response);
+        let response = command.response_from_proto(proto).unwrap();
+        assert_eq!(response.value, "2");
+        assert_eq!(response.type_.as_deref(), Some("int"));
+        assert_eq!(response.variables_reference, Some(3));
+        assert_eq!(response.named_variables, Some(4));
+        assert_eq!(response.indexed_variables, Some(5));
+        assert_eq!(response.memory_reference.as_deref(), Some("0x2000"));
+        assert_eq!(response.value_location_reference, Some(6));
+    }
+}
@@ -241,11 +241,18 @@ impl DapStore {
                             arguments: ssh
                                 .ssh_args()
                                 .ok_or_else(|| anyhow!("SSH arguments not found"))?,
+                            exec_program: ssh.exec_program(),
+                            tty: false,
                         })
                     })??;
 
                     let mut connection = None;
                     if let Some(c) = binary.connection {
+                        if ssh_command.exec_program.is_some() {
+                            anyhow::bail!(
+                                "debug adapters that connect over TCP are only supported over SSH"
+                            );
+                        }
                         let local_bind_addr = Ipv4Addr::new(127, 0, 0, 1);
                         let port =
                             dap::transport::TcpTransport::unused_port(local_bind_addr).await?;
@@ -524,6 +531,7 @@ impl DapStore {
         DapAdapterDelegate::new(
             local_store.fs.clone(),
             worktree.read(cx).id(),
+            worktree.read(cx).abs_path(),
             local_store.node_runtime.clone(),
             local_store.http_client.clone(),
             local_store.language_registry.clone(),
@@ -809,6 +817,7 @@ impl DapStore {
 pub struct DapAdapterDelegate {
     fs: Arc<dyn Fs>,
     worktree_id: WorktreeId,
+    worktree_root_path: Arc<Path>,
     node_runtime: NodeRuntime,
     http_client: Arc<dyn HttpClient>,
     language_registry: Arc<LanguageRegistry>,
@@ -821,6 +830,7 @@ impl DapAdapterDelegate {
     pub fn new(
         fs: Arc<dyn Fs>,
         worktree_id: WorktreeId,
+        worktree_root_path: Arc<Path>,
         node_runtime: NodeRuntime,
         http_client: Arc<dyn HttpClient>,
         language_registry: Arc<LanguageRegistry>,
@@ -830,6 +840,7 @@ impl DapAdapterDelegate {
         Self {
             fs,
             worktree_id,
+            worktree_root_path,
             http_client,
             node_runtime,
             toolchain_store,
@@ -846,6 +857,10 @@ impl dap::adapters::DapDelegate for DapAdapterDelegate {
         self.worktree_id
     }
 
+    fn worktree_root_path(&self) -> &Path {
+        &self.worktree_root_path
+    }
+
     fn http_client(&self) -> Arc<dyn HttpClient> {
         self.http_client.clone()
     }
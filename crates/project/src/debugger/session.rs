@@ -2,15 +2,18 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetExceptionBreakpoints, SetVariableValueCommand, StackTraceCommand,
-    StepBackCommand, StepCommand, StepInCommand, StepOutCommand, TerminateCommand,
-    TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, Initialize, Launch,
+    LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand, NextCommand,
+    PauseCommand, ReadMemoryCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpoints, SetExceptionBreakpoints, SetExpressionCommand, SetInstructionBreakpoints,
+    SetVariableValueCommand, StackTraceCommand, StepBackCommand, StepCommand, StepInCommand,
+    StepOutCommand, TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    WriteMemoryCommand,
 };
 use super::dap_store::DapStore;
 use anyhow::{Context as _, Result, anyhow};
+use base64::prelude::*;
 use collections::{HashMap, HashSet, IndexMap, IndexSet};
 use dap::adapters::{DebugAdapterBinary, DebugTaskDefinition};
 use dap::messages::Response;
@@ -78,6 +81,36 @@ impl From<dap::StackFrame> for StackFrame {
     }
 }
 
+/// The result of evaluating a user-defined watch expression in a given stack frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Watcher {
+    pub expression: SharedString,
+    pub value: SharedString,
+    pub type_: Option<SharedString>,
+    pub variables_reference: VariableReference,
+    pub memory_reference: Option<String>,
+    pub frame_id: Option<StackFrameId>,
+    pub is_error: bool,
+}
+
+/// A contiguous region of debuggee memory read through the `readMemory` request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryRegion {
+    /// The address of the first byte, as reported by the debug adapter.
+    pub address: String,
+    pub data: Arc<[u8]>,
+    /// The number of bytes after `data` that couldn't be read.
+    pub unreadable_bytes: u64,
+}
+
+/// A breakpoint that is hit when the value of a variable is accessed or changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataBreakpointState {
+    pub dap: dap::DataBreakpoint,
+    pub description: SharedString,
+    pub is_enabled: bool,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum ThreadStatus {
     #[default]
@@ -265,6 +298,20 @@ impl LocalMode {
         self.request(arg)
     }
 
+    fn send_instruction_breakpoints(
+        &self,
+        breakpoints: Vec<dap::InstructionBreakpoint>,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        self.request(SetInstructionBreakpoints { breakpoints })
+    }
+
+    fn send_data_breakpoints(
+        &self,
+        breakpoints: Vec<dap::DataBreakpoint>,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        self.request(SetDataBreakpoints { breakpoints })
+    }
+
     fn send_source_breakpoints(
         &self,
         ignore_breakpoints: bool,
@@ -517,6 +564,7 @@ pub struct Session {
     threads: IndexMap<ThreadId, Thread>,
     thread_states: ThreadStates,
     variables: HashMap<VariableReference, Vec<dap::Variable>>,
+    watchers: IndexMap<SharedString, Watcher>,
     stack_frames: IndexMap<StackFrameId, StackFrame>,
     locations: HashMap<u64, dap::LocationsResponse>,
     is_session_terminated: bool,
@@ -524,6 +572,13 @@ pub struct Session {
     pub(crate) breakpoint_store: Entity<BreakpointStore>,
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
+    instruction_breakpoints: BTreeMap<String, dap::InstructionBreakpoint>,
+    data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    /// Memory regions, keyed by the memory reference, offset and byte count they were read with.
+    memory: HashMap<(String, i64, u64), MemoryRegion>,
+    /// Disassembled instructions, keyed by the memory reference, instruction offset and
+    /// instruction count they were requested with.
+    disassembly: HashMap<(String, i64, u64), Vec<dap::DisassembledInstruction>>,
     background_tasks: Vec<Task<()>>,
 }
 
@@ -606,6 +661,10 @@ pub enum SessionEvent {
     Stopped(Option<ThreadId>),
     StackTrace,
     Variables,
+    Watchers,
+    Disassembly,
+    Memory,
+    DataBreakpoints,
     Threads,
     InvalidateInlineValue,
     CapabilitiesLoaded,
@@ -671,6 +730,7 @@ impl Session {
                 parent_session,
                 capabilities: Capabilities::default(),
                 variables: Default::default(),
+                watchers: Default::default(),
                 stack_frames: Default::default(),
                 thread_states: ThreadStates::default(),
                 output_token: OutputToken(0),
@@ -685,6 +745,10 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 exception_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
+                data_breakpoints: Default::default(),
+                memory: Default::default(),
+                disassembly: Default::default(),
                 definition: template,
             };
 
@@ -1119,6 +1183,7 @@ impl Session {
         }
 
         self.invalidate_generic();
+        self.invalidate_memory_and_disassembly();
         self.threads.clear();
         self.variables.clear();
         cx.emit(SessionEvent::Stopped(
@@ -1152,6 +1217,10 @@ impl Session {
                 }
                 // todo(debugger): We should be able to get away with only invalidating generic if all threads were continued
                 self.invalidate_generic();
+                // Memory and code may change while the debuggee runs.
+                self.invalidate_memory_and_disassembly();
+                cx.emit(SessionEvent::Memory);
+                cx.emit(SessionEvent::Disassembly);
             }
             Events::Exited(_event) => {
                 self.clear_active_debug_line(cx);
@@ -1220,7 +1289,11 @@ impl Session {
                 self.capabilities = self.capabilities.merge(event.capabilities);
                 cx.notify();
             }
-            Events::Memory(_) => {}
+            Events::Memory(_) => {
+                self.invalidate_command_type::<ReadMemoryCommand>();
+                cx.emit(SessionEvent::Memory);
+                cx.notify();
+            }
             Events::Process(_) => {}
             Events::ProgressEnd(_) => {}
             Events::ProgressStart(_) => {}
@@ -1349,6 +1422,13 @@ impl Session {
         self.requests.remove(&std::any::TypeId::of::<Command>());
     }
 
+    fn invalidate_memory_and_disassembly(&mut self) {
+        self.invalidate_command_type::<DisassembleCommand>();
+        self.invalidate_command_type::<ReadMemoryCommand>();
+        self.memory.clear();
+        self.disassembly.clear();
+    }
+
     fn invalidate_generic(&mut self) {
         self.invalidate_command_type::<ModulesCommand>();
         self.invalidate_command_type::<LoadedSourcesCommand>();
@@ -1442,6 +1522,14 @@ impl Session {
 
         self.ignore_breakpoints = ignore;
 
+        if !self.instruction_breakpoints.is_empty() {
+            self.send_instruction_breakpoints(cx);
+        }
+
+        if !self.data_breakpoints.is_empty() {
+            self.send_data_breakpoints(cx);
+        }
+
         if let Some(local) = self.as_local() {
             local.send_source_breakpoints(ignore, &self.breakpoint_store, cx)
         } else {
@@ -1483,6 +1571,167 @@ impl Session {
         }
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &dap::InstructionBreakpoint> {
+        self.instruction_breakpoints.values()
+    }
+
+    pub fn has_instruction_breakpoint(&self, instruction_reference: &str) -> bool {
+        self.instruction_breakpoints
+            .contains_key(instruction_reference)
+    }
+
+    /// Adds or removes a breakpoint on the instruction at the given memory reference.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if !self
+            .capabilities
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+        {
+            return;
+        }
+
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                dap::InstructionBreakpoint {
+                    instruction_reference,
+                    offset: None,
+                    condition: None,
+                    hit_condition: None,
+                    mode: None,
+                },
+            );
+        }
+
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &App) {
+        if let Some(local) = self.as_local() {
+            let breakpoints = if self.ignore_breakpoints {
+                vec![]
+            } else {
+                self.instruction_breakpoints.values().cloned().collect()
+            };
+
+            local
+                .send_instruction_breakpoints(breakpoints)
+                .detach_and_log_err(cx);
+        } else {
+            debug_assert!(false, "Not implemented");
+        }
+    }
+
+    pub fn data_breakpoints(&self) -> impl Iterator<Item = &DataBreakpointState> {
+        self.data_breakpoints.values()
+    }
+
+    /// Asks the debug adapter whether a data breakpoint can be set on the variable `name`
+    /// inside of `variables_reference`, and if so, sets one that triggers when its value changes.
+    pub fn create_data_breakpoint(
+        &mut self,
+        variables_reference: Option<VariableReference>,
+        name: String,
+        frame_id: Option<StackFrameId>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "Data breakpoints are only supported for local sessions"
+            )));
+        };
+
+        let info = local.request(DataBreakpointInfoCommand {
+            variables_reference,
+            name: name.clone(),
+            frame_id,
+        });
+
+        cx.spawn(async move |this, cx| {
+            let info = info.await?;
+            let data_id = info
+                .data_id
+                .ok_or_else(|| anyhow!("Can't set a data breakpoint: {}", info.description))?;
+
+            let access_type = info
+                .access_types
+                .as_ref()
+                .filter(|access_types| !access_types.is_empty())
+                .map(|access_types| {
+                    if access_types.contains(&dap::DataBreakpointAccessType::Write) {
+                        dap::DataBreakpointAccessType::Write
+                    } else {
+                        access_types[0].clone()
+                    }
+                });
+
+            this.update(cx, |this, cx| {
+                this.data_breakpoints.insert(
+                    data_id.clone(),
+                    DataBreakpointState {
+                        dap: dap::DataBreakpoint {
+                            data_id,
+                            access_type,
+                            condition: None,
+                            hit_condition: None,
+                        },
+                        description: info.description.into(),
+                        is_enabled: true,
+                    },
+                );
+                this.send_data_breakpoints(cx);
+                cx.emit(SessionEvent::DataBreakpoints);
+                cx.notify();
+            })
+        })
+    }
+
+    pub fn toggle_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        if let Some(breakpoint) = self.data_breakpoints.get_mut(data_id) {
+            breakpoint.is_enabled = !breakpoint.is_enabled;
+            self.send_data_breakpoints(cx);
+            cx.emit(SessionEvent::DataBreakpoints);
+            cx.notify();
+        }
+    }
+
+    pub fn remove_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        if self.data_breakpoints.remove(data_id).is_some() {
+            self.send_data_breakpoints(cx);
+            cx.emit(SessionEvent::DataBreakpoints);
+            cx.notify();
+        }
+    }
+
+    fn send_data_breakpoints(&mut self, cx: &App) {
+        if let Some(local) = self.as_local() {
+            let breakpoints = if self.ignore_breakpoints {
+                vec![]
+            } else {
+                self.data_breakpoints
+                    .values()
+                    .filter(|breakpoint| breakpoint.is_enabled)
+                    .map(|breakpoint| breakpoint.dap.clone())
+                    .collect()
+            };
+
+            local
+                .send_data_breakpoints(breakpoints)
+                .detach_and_log_err(cx);
+        } else {
+            debug_assert!(false, "Not implemented");
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2027,6 +2276,273 @@ impl Session {
         })
     }
 
+    pub fn watchers(&self) -> impl Iterator<Item = &Watcher> {
+        self.watchers.values()
+    }
+
+    pub fn watcher(&self, expression: &str) -> Option<&Watcher> {
+        self.watchers.get(expression)
+    }
+
+    /// Starts tracking a watch expression and evaluates it in the given stack frame.
+    /// Tracked expressions are re-evaluated by [`Session::refresh_watchers`].
+    pub fn add_watcher(
+        &mut self,
+        expression: SharedString,
+        frame_id: Option<StackFrameId>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.watchers
+            .entry(expression.clone())
+            .or_insert_with(|| Watcher {
+                expression: expression.clone(),
+                value: SharedString::default(),
+                type_: None,
+                variables_reference: 0,
+                memory_reference: None,
+                frame_id,
+                is_error: false,
+            });
+        cx.emit(SessionEvent::Watchers);
+
+        self.evaluate_watcher(expression, frame_id, cx)
+    }
+
+    pub fn remove_watcher(&mut self, expression: &str, cx: &mut Context<Self>) {
+        if self.watchers.shift_remove(expression).is_some() {
+            cx.emit(SessionEvent::Watchers);
+            cx.notify();
+        }
+    }
+
+    pub fn refresh_watchers(&mut self, frame_id: Option<StackFrameId>, cx: &mut Context<Self>) {
+        let expressions = self.watchers.keys().cloned().collect::<Vec<_>>();
+        for expression in expressions {
+            self.evaluate_watcher(expression, frame_id, cx).detach();
+        }
+    }
+
+    fn evaluate_watcher(
+        &mut self,
+        expression: SharedString,
+        frame_id: Option<StackFrameId>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let task = self.request(
+            EvaluateCommand {
+                expression: expression.to_string(),
+                frame_id,
+                context: Some(EvaluateArgumentsContext::Watch),
+                source: None,
+            },
+            move |this, response, cx| {
+                let watcher = match &response {
+                    Ok(response) => Watcher {
+                        expression: expression.clone(),
+                        value: response.result.clone().into(),
+                        type_: response.type_.clone().map(Into::into),
+                        variables_reference: response.variables_reference,
+                        memory_reference: response.memory_reference.clone(),
+                        frame_id,
+                        is_error: false,
+                    },
+                    Err(error) => Watcher {
+                        expression: expression.clone(),
+                        value: error.to_string().into(),
+                        type_: None,
+                        variables_reference: 0,
+                        memory_reference: None,
+                        frame_id,
+                        is_error: true,
+                    },
+                };
+
+                // The watcher could have been removed while the request was in flight
+                if let Some(entry) = this.watchers.get_mut(&expression) {
+                    *entry = watcher;
+                    cx.emit(SessionEvent::Watchers);
+                    cx.notify();
+                }
+
+                response.ok()
+            },
+            cx,
+        );
+
+        cx.background_executor().spawn(async move {
+            task.await
+                .map(|_| ())
+                .ok_or_else(|| anyhow!("failed to evaluate watch expression"))
+        })
+    }
+
+    pub fn set_expression(
+        &mut self,
+        expression: SharedString,
+        value: String,
+        frame_id: Option<StackFrameId>,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .capabilities
+            .supports_set_expression
+            .unwrap_or_default()
+        {
+            self.request(
+                SetExpressionCommand {
+                    expression: expression.to_string(),
+                    value,
+                    frame_id,
+                },
+                move |this, response, cx| {
+                    let response = response.log_err()?;
+                    this.invalidate_command_type::<ScopesCommand>();
+                    this.invalidate_command_type::<VariablesCommand>();
+                    this.refresh_watchers(frame_id, cx);
+                    cx.emit(SessionEvent::InvalidateInlineValue);
+                    cx.notify();
+                    Some(response)
+                },
+                cx,
+            )
+            .detach()
+        }
+    }
+
+    /// Returns the instructions around `memory_reference`, fetching them from the adapter if they aren't cached yet.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> &[dap::DisassembledInstruction] {
+        let command = DisassembleCommand {
+            memory_reference: memory_reference.clone(),
+            offset: None,
+            instruction_offset: Some(instruction_offset),
+            instruction_count,
+        };
+
+        let key = (memory_reference, instruction_offset, instruction_count);
+        self.fetch(
+            command,
+            {
+                let key = key.clone();
+                move |this, instructions, cx| {
+                    let instructions = instructions.log_err()?;
+                    this.disassembly.insert(key, instructions.clone());
+
+                    cx.emit(SessionEvent::Disassembly);
+                    cx.notify();
+                    Some(instructions)
+                }
+            },
+            cx,
+        );
+
+        self.disassembly
+            .get(&key)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns `count` bytes of memory starting at `offset` bytes from `memory_reference`,
+    /// fetching them from the adapter if they aren't cached yet.
+    pub fn read_memory(
+        &mut self,
+        memory_reference: String,
+        offset: i64,
+        count: u64,
+        cx: &mut Context<Self>,
+    ) -> Option<&MemoryRegion> {
+        let key = (memory_reference.clone(), offset, count);
+        let command = ReadMemoryCommand {
+            memory_reference,
+            offset: Some(offset),
+            count,
+        };
+
+        self.fetch(
+            command,
+            {
+                let key = key.clone();
+                move |this, response, cx| {
+                    let response = response.log_err()?;
+                    let data = response
+                        .data
+                        .as_deref()
+                        .map(|data| BASE64_STANDARD.decode(data))
+                        .transpose()
+                        .log_err()?
+                        .unwrap_or_default();
+
+                    this.memory.insert(
+                        key,
+                        MemoryRegion {
+                            address: response.address.clone(),
+                            data: data.into(),
+                            unreadable_bytes: response.unreadable_bytes.unwrap_or_default(),
+                        },
+                    );
+
+                    cx.emit(SessionEvent::Memory);
+                    cx.notify();
+                    Some(response)
+                }
+            },
+            cx,
+        );
+
+        self.memory.get(&key)
+    }
+
+    pub fn write_memory(
+        &mut self,
+        memory_reference: String,
+        offset: i64,
+        bytes: &[u8],
+        cx: &mut Context<Self>,
+    ) {
+        if !self
+            .capabilities
+            .supports_write_memory_request
+            .unwrap_or_default()
+        {
+            return;
+        }
+
+        self.request(
+            WriteMemoryCommand {
+                memory_reference,
+                offset: Some(offset),
+                data: BASE64_STANDARD.encode(bytes),
+            },
+            move |this, response, cx| {
+                let response = response.log_err()?;
+                this.invalidate_command_type::<ReadMemoryCommand>();
+                this.invalidate_command_type::<ScopesCommand>();
+                this.invalidate_command_type::<VariablesCommand>();
+
+                let watchers = this
+                    .watchers
+                    .values()
+                    .map(|watcher| (watcher.expression.clone(), watcher.frame_id))
+                    .collect::<Vec<_>>();
+                for (expression, frame_id) in watchers {
+                    this.evaluate_watcher(expression, frame_id, cx).detach();
+                }
+
+                cx.emit(SessionEvent::Memory);
+                cx.emit(SessionEvent::InvalidateInlineValue);
+                cx.notify();
+                Some(response)
+            },
+            cx,
+        )
+        .detach();
+    }
+
     pub fn location(
         &mut self,
         reference: u64,
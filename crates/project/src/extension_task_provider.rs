@@ -0,0 +1,435 @@
+//! Task providers contributed by extensions, which compute task templates for a worktree
+//! (e.g. from a `justfile` or the scripts in a `package.json`).
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use collections::{BTreeMap, HashMap};
+use extension::{
+    Extension, ExtensionHostProxy, ExtensionTaskProviderProxy, TaskProviderManifestEntry,
+    WorktreeDelegate,
+};
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Global, ReadGlobal as _};
+use worktree::WorktreeId;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_task_provider_proxy(TaskProviderRegistryProxy {
+        registry: TaskProviderRegistry::default_global(cx),
+    });
+}
+
+struct TaskProviderRegistryProxy {
+    registry: Entity<TaskProviderRegistry>,
+}
+
+impl ExtensionTaskProviderProxy for TaskProviderRegistryProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        entry: TaskProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        self.registry.update(cx, |registry, cx| {
+            registry.register(
+                ExtensionTaskProvider {
+                    extension,
+                    provider_id,
+                    files: entry.files,
+                },
+                cx,
+            )
+        });
+    }
+
+    fn unregister_task_provider(
+        &self,
+        extension_id: Arc<str>,
+        provider_id: Arc<str>,
+        cx: &mut App,
+    ) {
+        self.registry.update(cx, |registry, cx| {
+            registry.unregister(&extension_id, &provider_id, cx)
+        });
+    }
+}
+
+/// Returns the ID under which an extension's task provider is registered.
+///
+/// The ID is namespaced by the extension, so that extensions providing task providers
+/// with the same ID don't replace each other.
+fn registry_provider_id(extension_id: &str, provider_id: &str) -> Arc<str> {
+    format!("{extension_id}:{provider_id}").into()
+}
+
+/// A task provider registered by an extension.
+#[derive(Clone)]
+pub(crate) struct ExtensionTaskProvider {
+    pub extension: Arc<dyn Extension>,
+    /// The ID of the provider within the extension.
+    pub provider_id: Arc<str>,
+    /// The worktree-relative paths whose changes cause the provider's tasks to be reloaded.
+    pub files: Vec<Arc<str>>,
+}
+
+impl ExtensionTaskProvider {
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| path == Path::new(file.as_ref()))
+    }
+}
+
+pub(crate) enum TaskProviderRegistryEvent {
+    ProviderAdded(Arc<str>),
+    ProviderRemoved(Arc<str>),
+}
+
+struct GlobalTaskProviderRegistry(Entity<TaskProviderRegistry>);
+
+impl Global for GlobalTaskProviderRegistry {}
+
+/// The task providers registered by the loaded extensions, keyed by IDs namespaced by
+/// their extension.
+#[derive(Default)]
+pub(crate) struct TaskProviderRegistry {
+    providers: BTreeMap<Arc<str>, ExtensionTaskProvider>,
+}
+
+impl EventEmitter<TaskProviderRegistryEvent> for TaskProviderRegistry {}
+
+impl TaskProviderRegistry {
+    /// Returns the global [`TaskProviderRegistry`].
+    ///
+    /// Inserts a default [`TaskProviderRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut App) -> Entity<Self> {
+        if !cx.has_global::<GlobalTaskProviderRegistry>() {
+            let registry = cx.new(|_| Self::default());
+            cx.set_global(GlobalTaskProviderRegistry(registry));
+        }
+        GlobalTaskProviderRegistry::global(cx).0.clone()
+    }
+
+    pub fn provider(&self, provider_id: &str) -> Option<&ExtensionTaskProvider> {
+        self.providers.get(provider_id)
+    }
+
+    pub fn providers(&self) -> impl Iterator<Item = (&Arc<str>, &ExtensionTaskProvider)> {
+        self.providers.iter()
+    }
+
+    pub fn register(&mut self, provider: ExtensionTaskProvider, cx: &mut Context<Self>) {
+        let provider_id =
+            registry_provider_id(&provider.extension.manifest().id, &provider.provider_id);
+        self.providers.insert(provider_id.clone(), provider);
+        cx.emit(TaskProviderRegistryEvent::ProviderAdded(provider_id));
+    }
+
+    pub fn unregister(&mut self, extension_id: &str, provider_id: &str, cx: &mut Context<Self>) {
+        let provider_id = registry_provider_id(extension_id, provider_id);
+        if self.providers.remove(&provider_id).is_some() {
+            cx.emit(TaskProviderRegistryEvent::ProviderRemoved(provider_id));
+        }
+    }
+}
+
+/// A snapshot of a local worktree that can be handed to an extension as a [`WorktreeDelegate`].
+pub(crate) struct TaskProviderWorktreeDelegate {
+    pub worktree_id: WorktreeId,
+    pub root_path: Arc<Path>,
+    pub fs: Arc<dyn Fs>,
+    pub shell_env: HashMap<String, String>,
+}
+
+#[async_trait]
+impl WorktreeDelegate for TaskProviderWorktreeDelegate {
+    fn id(&self) -> u64 {
+        self.worktree_id.to_proto()
+    }
+
+    fn root_path(&self) -> String {
+        self.root_path.to_string_lossy().to_string()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        self.fs.load(&self.root_path.join(path)).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        which::which_in(
+            binary_name,
+            self.shell_env.get("PATH"),
+            self.root_path.as_ref(),
+        )
+        .ok()
+        .map(|path| path.to_string_lossy().to_string())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.shell_env.clone().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap::StartDebuggingRequestArgumentsRequest;
+    use dap::adapters::{DebugAdapterBinary, DebugTaskDefinition};
+    use extension::{
+        CodeLabel, Command, Completion, ContextServerConfiguration, ExtensionManifest,
+        KeyValueStoreDelegate, LlmCompletionEvent, LlmCompletionRequest, LlmModel, ProjectDelegate,
+        SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeTasks,
+    };
+    use gpui::TestAppContext;
+    use language::LanguageName;
+    use lsp::LanguageServerName;
+    use serde_json::json;
+
+    struct TestExtension {
+        manifest: Arc<ExtensionManifest>,
+    }
+
+    impl TestExtension {
+        fn new(id: &str) -> Arc<dyn Extension> {
+            let manifest = serde_json::from_value(json!({
+                "id": id,
+                "name": id,
+                "version": "0.1.0",
+                "schema_version": 1,
+            }))
+            .unwrap();
+            Arc::new(Self {
+                manifest: Arc::new(manifest),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Extension for TestExtension {
+        fn manifest(&self) -> Arc<ExtensionManifest> {
+            self.manifest.clone()
+        }
+
+        fn work_dir(&self) -> Arc<Path> {
+            Path::new("/").into()
+        }
+
+        async fn language_server_command(
+            &self,
+            _: LanguageServerName,
+            _: LanguageName,
+            _: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Command> {
+            unimplemented!()
+        }
+
+        async fn language_server_initialization_options(
+            &self,
+            _: LanguageServerName,
+            _: LanguageName,
+            _: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_workspace_configuration(
+            &self,
+            _: LanguageServerName,
+            _: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_additional_initialization_options(
+            &self,
+            _: LanguageServerName,
+            _: LanguageServerName,
+            _: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_additional_workspace_configuration(
+            &self,
+            _: LanguageServerName,
+            _: LanguageServerName,
+            _: Arc<dyn WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn labels_for_completions(
+            &self,
+            _: LanguageServerName,
+            _: Vec<Completion>,
+        ) -> Result<Vec<Option<CodeLabel>>> {
+            unimplemented!()
+        }
+
+        async fn labels_for_symbols(
+            &self,
+            _: LanguageServerName,
+            _: Vec<Symbol>,
+        ) -> Result<Vec<Option<CodeLabel>>> {
+            unimplemented!()
+        }
+
+        async fn complete_slash_command_argument(
+            &self,
+            _: SlashCommand,
+            _: Vec<String>,
+        ) -> Result<Vec<SlashCommandArgumentCompletion>> {
+            unimplemented!()
+        }
+
+        async fn run_slash_command(
+            &self,
+            _: SlashCommand,
+            _: Vec<String>,
+            _: Option<Arc<dyn WorktreeDelegate>>,
+        ) -> Result<SlashCommandOutput> {
+            unimplemented!()
+        }
+
+        async fn context_server_command(
+            &self,
+            _: Arc<str>,
+            _: Arc<dyn ProjectDelegate>,
+        ) -> Result<Command> {
+            unimplemented!()
+        }
+
+        async fn context_server_configuration(
+            &self,
+            _: Arc<str>,
+            _: Arc<dyn ProjectDelegate>,
+        ) -> Result<Option<ContextServerConfiguration>> {
+            unimplemented!()
+        }
+
+        async fn suggest_docs_packages(&self, _: Arc<str>) -> Result<Vec<String>> {
+            unimplemented!()
+        }
+
+        async fn index_docs(
+            &self,
+            _: Arc<str>,
+            _: Arc<str>,
+            _: Arc<dyn KeyValueStoreDelegate>,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn get_dap_binary(
+            &self,
+            _: Arc<str>,
+            _: DebugTaskDefinition,
+            _: Option<PathBuf>,
+            _: Arc<dyn WorktreeDelegate>,
+        ) -> Result<DebugAdapterBinary> {
+            unimplemented!()
+        }
+
+        async fn dap_request_kind(
+            &self,
+            _: Arc<str>,
+            _: serde_json::Value,
+        ) -> Result<StartDebuggingRequestArgumentsRequest> {
+            unimplemented!()
+        }
+
+        async fn dap_default_config(&self, _: Arc<str>) -> Result<serde_json::Value> {
+            unimplemented!()
+        }
+
+        async fn worktree_tasks(
+            &self,
+            _: Arc<str>,
+            _: Arc<dyn WorktreeDelegate>,
+        ) -> Result<WorktreeTasks> {
+            unimplemented!()
+        }
+
+        async fn run_agent_tool(
+            &self,
+            _: Arc<str>,
+            _: serde_json::Value,
+            _: Option<Arc<dyn WorktreeDelegate>>,
+        ) -> Result<String> {
+            unimplemented!()
+        }
+
+        async fn llm_provider_models(
+            &self,
+            _: Arc<str>,
+            _: Option<String>,
+        ) -> Result<Vec<LlmModel>> {
+            unimplemented!()
+        }
+
+        async fn llm_stream_completion(
+            &self,
+            _: Arc<str>,
+            _: Arc<str>,
+            _: LlmCompletionRequest,
+            _: Option<String>,
+        ) -> Result<u64> {
+            unimplemented!()
+        }
+
+        async fn llm_next_completion_events(
+            &self,
+            _: u64,
+        ) -> Result<Option<Vec<LlmCompletionEvent>>> {
+            unimplemented!()
+        }
+
+        async fn llm_drop_completion_stream(&self, _: u64) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[gpui::test]
+    fn test_extension_task_provider_id_collision(cx: &mut TestAppContext) {
+        let registry = cx.new(|_| TaskProviderRegistry::default());
+        let extension = TestExtension::new("my-extension");
+        let other_extension = TestExtension::new("other-extension");
+
+        // Extensions can register task providers with the same ID without replacing each other.
+        registry.update(cx, |registry, cx| {
+            for extension in [&extension, &other_extension] {
+                registry.register(
+                    ExtensionTaskProvider {
+                        extension: extension.clone(),
+                        provider_id: "just".into(),
+                        files: vec!["justfile".into()],
+                    },
+                    cx,
+                );
+            }
+        });
+        registry.read_with(cx, |registry, _| {
+            assert_eq!(
+                registry
+                    .providers()
+                    .map(|(id, _)| id.as_ref())
+                    .collect::<Vec<_>>(),
+                ["my-extension:just", "other-extension:just"]
+            );
+            let provider = registry.provider("other-extension:just").unwrap();
+            assert_eq!(provider.extension.manifest().id.as_ref(), "other-extension");
+            assert_eq!(provider.provider_id.as_ref(), "just");
+        });
+
+        // Unloading an extension only removes the task providers it registered.
+        registry.update(cx, |registry, cx| {
+            registry.unregister("my-extension", "just", cx)
+        });
+        registry.read_with(cx, |registry, _| {
+            assert!(registry.provider("my-extension:just").is_none());
+            assert!(registry.provider("other-extension:just").is_some());
+        });
+    }
+}
@@ -413,6 +413,10 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_get_branches);
         client.add_entity_request_handler(Self::handle_change_branch);
         client.add_entity_request_handler(Self::handle_create_branch);
+        client.add_entity_request_handler(Self::handle_get_worktrees);
+        client.add_entity_request_handler(Self::handle_create_worktree);
+        client.add_entity_request_handler(Self::handle_remove_worktree);
+        client.add_entity_request_handler(Self::handle_prune_worktrees);
         client.add_entity_request_handler(Self::handle_git_init);
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
@@ -900,6 +904,38 @@ impl GitStore {
         })
     }
 
+    /// Returns the diff between two checkpoints, concatenated across all of the
+    /// repositories that are present in both of them.
+    pub fn diff_checkpoints(
+        &self,
+        base: GitStoreCheckpoint,
+        mut target: GitStoreCheckpoint,
+        cx: &mut App,
+    ) -> Task<Result<String>> {
+        let repositories_by_work_dir_abs_path = self
+            .repositories
+            .values()
+            .map(|repo| (repo.read(cx).snapshot.work_directory_abs_path.clone(), repo))
+            .collect::<HashMap<_, _>>();
+
+        let mut tasks = Vec::new();
+        for (work_dir_abs_path, base_checkpoint) in base.checkpoints_by_work_dir_abs_path {
+            let Some(target_checkpoint) = target
+                .checkpoints_by_work_dir_abs_path
+                .remove(&work_dir_abs_path)
+            else {
+                continue;
+            };
+            if let Some(repository) = repositories_by_work_dir_abs_path.get(&work_dir_abs_path) {
+                let diff = repository.update(cx, |repository, _| {
+                    repository.diff_checkpoints(base_checkpoint, target_checkpoint)
+                });
+                tasks.push(async move { diff.await? });
+            }
+        }
+        cx.background_spawn(async move { Ok(future::try_join_all(tasks).await?.concat()) })
+    }
+
     /// Blames a buffer.
     pub fn blame_buffer(
         &self,
@@ -1815,6 +1851,81 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_get_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitWorktreesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let worktrees = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.worktrees()
+            })?
+            .await??;
+
+        Ok(proto::GitWorktreesResponse {
+            worktrees: worktrees.iter().map(worktree_to_proto).collect::<Vec<_>>(),
+        })
+    }
+
+    async fn handle_create_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let branch_name = envelope.payload.branch_name;
+        let path = PathBuf::from_proto(envelope.payload.path);
+        let create_branch = envelope.payload.create_branch;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_worktree(branch_name, path, create_branch)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_remove_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = PathBuf::from_proto(envelope.payload.path);
+        let force = envelope.payload.force;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.remove_worktree(path, force)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_prune_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPruneWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.prune_worktrees()
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
     async fn handle_change_branch(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitChangeBranch>,
@@ -3865,6 +3976,115 @@ impl Repository {
         )
     }
 
+    pub fn worktrees(&mut self) -> oneshot::Receiver<Result<Vec<git::repository::Worktree>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.worktrees().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetWorktrees {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    let worktrees = response
+                        .worktrees
+                        .into_iter()
+                        .map(proto_to_worktree)
+                        .collect();
+
+                    Ok(worktrees)
+                }
+            }
+        })
+    }
+
+    pub fn create_worktree(
+        &mut self,
+        branch_name: String,
+        path: PathBuf,
+        create_branch: bool,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let job_description = if create_branch {
+            format!("git worktree add -b {branch_name} {}", path.display())
+        } else {
+            format!("git worktree add {} {branch_name}", path.display())
+        };
+        self.send_job(Some(job_description.into()), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend
+                        .create_worktree(branch_name, path, create_branch)
+                        .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitCreateWorktree {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            branch_name,
+                            path: path.to_proto(),
+                            create_branch,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn remove_worktree(&mut self, path: PathBuf, force: bool) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git worktree remove {}", path.display()).into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.remove_worktree(path, force).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitRemoveWorktree {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: path.to_proto(),
+                                force,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn prune_worktrees(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git worktree prune".into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => backend.prune_worktrees().await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitPruneWorktrees {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn change_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
         self.send_job(
@@ -4482,6 +4702,31 @@ fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     }
 }
 
+fn worktree_to_proto(worktree: &git::repository::Worktree) -> proto::GitWorktree {
+    proto::GitWorktree {
+        path: worktree.path.as_path().to_proto(),
+        sha: worktree.sha.as_ref().map(|sha| sha.to_string()),
+        ref_name: worktree
+            .ref_name
+            .as_ref()
+            .map(|ref_name| ref_name.to_string()),
+        is_main: worktree.is_main,
+        is_locked: worktree.is_locked,
+        is_prunable: worktree.is_prunable,
+    }
+}
+
+fn proto_to_worktree(proto: proto::GitWorktree) -> git::repository::Worktree {
+    git::repository::Worktree {
+        path: PathBuf::from_proto(proto.path),
+        sha: proto.sha.map(Into::into),
+        ref_name: proto.ref_name.map(Into::into),
+        is_main: proto.is_main,
+        is_locked: proto.is_locked,
+        is_prunable: proto.is_prunable,
+    }
+}
+
 fn proto_to_branch(proto: &proto::Branch) -> git::repository::Branch {
     git::repository::Branch {
         is_head: proto.is_head,
@@ -3,6 +3,7 @@ mod color_extractor;
 pub mod connection_manager;
 pub mod debounced_delay;
 pub mod debugger;
+mod extension_task_provider;
 pub mod git_store;
 pub mod image_store;
 pub mod lsp_command;
@@ -832,6 +833,7 @@ impl Project {
         GitStore::init(&client);
         SettingsObserver::init(&client);
         TaskStore::init(Some(&client));
+        extension_task_provider::init(cx);
         ToolchainStore::init(&client);
         DapStore::init(&client, cx);
         BreakpointStore::init(&client);
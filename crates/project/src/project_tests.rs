@@ -7255,6 +7255,7 @@ async fn test_single_file_diffs(cx: &mut gpui::TestAppContext) {
                 DiffHunkStatus {
                     kind: DiffHunkStatusKind::Modified,
                     secondary: DiffHunkSecondaryStatus::HasSecondaryHunk,
+                    moved: false,
                 },
             )],
         );
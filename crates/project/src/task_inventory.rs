@@ -9,7 +9,8 @@ use std::{
 };
 
 use anyhow::Result;
-use collections::{HashMap, HashSet, VecDeque};
+use collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use extension::WorktreeTasks;
 use gpui::{App, AppContext as _, Entity, SharedString, Task};
 use itertools::Itertools;
 use language::{
@@ -35,6 +36,7 @@ pub struct Inventory {
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    tasks_from_extensions: HashMap<WorktreeId, BTreeMap<SharedString, WorktreeTasks>>,
 }
 
 // Helper trait for better error messages in [InventoryFor]
@@ -133,6 +135,11 @@ pub enum TaskSourceKind {
     Language { name: SharedString },
     /// Language-specific tasks coming from LSP servers.
     Lsp(LanguageServerId),
+    /// Worktree-specific tasks computed by an extension's task provider.
+    Extension {
+        worktree: WorktreeId,
+        provider: SharedString,
+    },
 }
 
 /// A collection of task contexts, derived from the current state of the workspace.
@@ -205,6 +212,7 @@ impl TaskSourceKind {
             }
             Self::Language { name } => format!("language_{name}"),
             Self::Lsp(server_id) => format!("lsp_{server_id}"),
+            Self::Extension { worktree, provider } => format!("extension_{provider}_{worktree}"),
         }
     }
 }
@@ -251,8 +259,8 @@ impl Inventory {
     }
 
     /// Pulls its task sources relevant to the worktree and the language given,
-    /// returns all task templates with their source kinds, worktree tasks first, language tasks second
-    /// and global tasks last. No specific order inside source kinds groups.
+    /// returns all task templates with their source kinds, worktree tasks first, extension-provided worktree tasks second,
+    /// language tasks third and global tasks last. No specific order inside source kinds groups.
     pub fn list_tasks(
         &self,
         file: Option<Arc<dyn File>>,
@@ -262,6 +270,7 @@ impl Inventory {
     ) -> Vec<(TaskSourceKind, TaskTemplate)> {
         let global_tasks = self.global_templates_from_settings();
         let worktree_tasks = self.worktree_templates_from_settings(worktree);
+        let extension_tasks = self.worktree_templates_from_extensions(worktree);
         let task_source_kind = language.as_ref().map(|language| TaskSourceKind::Language {
             name: language.name().into(),
         });
@@ -277,6 +286,7 @@ impl Inventory {
             .flat_map(|task| Some((task_source_kind.clone()?, task)));
 
         worktree_tasks
+            .chain(extension_tasks)
             .chain(language_tasks)
             .chain(global_tasks)
             .collect()
@@ -355,51 +365,62 @@ impl Inventory {
             .flat_map(|task| Some((task_source_kind.clone()?, task)));
         let worktree_tasks = self
             .worktree_templates_from_settings(worktree)
+            .chain(self.worktree_templates_from_extensions(worktree))
             .chain(language_tasks)
             .chain(global_tasks);
 
         let new_resolved_tasks = worktree_tasks
             .flat_map(|(kind, task)| {
                 let id_base = kind.to_id_base();
-                if let TaskSourceKind::Worktree { id, .. } = &kind {
+                let extension_variables = self.extension_task_variables(&kind);
+                let resolve = |task_context: &TaskContext| match extension_variables {
+                    Some(variables) => {
+                        let mut task_context = task_context.clone();
+                        task_context.task_variables.extend(variables.clone());
+                        task.resolve_task(&id_base, &task_context)
+                    }
+                    None => task.resolve_task(&id_base, task_context),
+                };
+                let task_worktree = match &kind {
+                    TaskSourceKind::Worktree { id, .. }
+                    | TaskSourceKind::Extension { worktree: id, .. } => Some(*id),
+                    _ => None,
+                };
+                if let Some(id) = task_worktree {
                     None.or_else(|| {
                         let (_, _, item_context) = task_contexts
                             .active_item_context
                             .as_ref()
-                            .filter(|(worktree_id, _, _)| Some(id) == worktree_id.as_ref())?;
-                        task.resolve_task(&id_base, item_context)
+                            .filter(|(worktree_id, _, _)| Some(id) == *worktree_id)?;
+                        resolve(item_context)
                     })
                     .or_else(|| {
                         let (_, worktree_context) = task_contexts
                             .active_worktree_context
                             .as_ref()
-                            .filter(|(worktree_id, _)| id == worktree_id)?;
-                        task.resolve_task(&id_base, worktree_context)
+                            .filter(|(worktree_id, _)| id == *worktree_id)?;
+                        resolve(worktree_context)
                     })
                     .or_else(|| {
-                        if let TaskSourceKind::Worktree { id, .. } = &kind {
-                            let worktree_context = task_contexts
-                                .other_worktree_contexts
-                                .iter()
-                                .find(|(worktree_id, _)| worktree_id == id)
-                                .map(|(_, context)| context)?;
-                            task.resolve_task(&id_base, worktree_context)
-                        } else {
-                            None
-                        }
+                        let worktree_context = task_contexts
+                            .other_worktree_contexts
+                            .iter()
+                            .find(|(worktree_id, _)| *worktree_id == id)
+                            .map(|(_, context)| context)?;
+                        resolve(worktree_context)
                     })
                 } else {
                     None.or_else(|| {
                         let (_, _, item_context) = task_contexts.active_item_context.as_ref()?;
-                        task.resolve_task(&id_base, item_context)
+                        resolve(item_context)
                     })
                     .or_else(|| {
                         let (_, worktree_context) =
                             task_contexts.active_worktree_context.as_ref()?;
-                        task.resolve_task(&id_base, worktree_context)
+                        resolve(worktree_context)
                     })
                 }
-                .or_else(|| task.resolve_task(&id_base, &TaskContext::default()))
+                .or_else(|| resolve(&TaskContext::default()))
                 .map(move |resolved_task| (kind.clone(), resolved_task, not_used_score))
             })
             .filter(|(_, resolved_task, _)| {
@@ -482,6 +503,78 @@ impl Inventory {
         self.templates_from_settings.worktree_scenarios(worktree)
     }
 
+    fn worktree_templates_from_extensions(
+        &self,
+        worktree: Option<WorktreeId>,
+    ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
+        worktree.into_iter().flat_map(|worktree| {
+            self.tasks_from_extensions
+                .get(&worktree)
+                .into_iter()
+                .flatten()
+                .flat_map(move |(provider, tasks)| {
+                    tasks.templates.iter().map(move |template| {
+                        (
+                            TaskSourceKind::Extension {
+                                worktree,
+                                provider: provider.clone(),
+                            },
+                            template.clone(),
+                        )
+                    })
+                })
+        })
+    }
+
+    fn extension_task_variables(&self, kind: &TaskSourceKind) -> Option<&TaskVariables> {
+        let TaskSourceKind::Extension { worktree, provider } = kind else {
+            return None;
+        };
+        self.tasks_from_extensions
+            .get(worktree)?
+            .get(provider)
+            .map(|tasks| &tasks.variables)
+    }
+
+    /// Replaces the tasks that an extension's task provider computed for the worktree given.
+    /// Removes the provider's tasks for that worktree, if `None` is passed.
+    pub(crate) fn update_extension_tasks(
+        &mut self,
+        worktree: WorktreeId,
+        provider: SharedString,
+        tasks: Option<WorktreeTasks>,
+    ) {
+        match tasks {
+            Some(tasks) => {
+                self.tasks_from_extensions
+                    .entry(worktree)
+                    .or_default()
+                    .insert(provider, tasks);
+            }
+            None => {
+                if let hash_map::Entry::Occupied(mut o) = self.tasks_from_extensions.entry(worktree)
+                {
+                    o.get_mut().remove(&provider);
+                    if o.get().is_empty() {
+                        o.remove();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes the extension-provided tasks for which the predicate returns `false`,
+    /// given the worktree and the id of the task provider that computed them.
+    pub(crate) fn retain_extension_tasks(
+        &mut self,
+        mut predicate: impl FnMut(WorktreeId, &str) -> bool,
+    ) {
+        self.tasks_from_extensions.retain(|worktree_id, providers| {
+            providers.retain(|provider, _| predicate(*worktree_id, provider));
+            !providers.is_empty()
+        });
+    }
+
     /// Updates in-memory task metadata from the JSON string given.
     /// Will fail if the JSON is not a valid array of objects, but will continue if any object will not parse into a [`TaskTemplate`].
     ///
@@ -629,7 +722,8 @@ fn task_source_kind_preference(kind: &TaskSourceKind) -> u32 {
         TaskSourceKind::Language { .. } => 1,
         TaskSourceKind::UserInput => 2,
         TaskSourceKind::Worktree { .. } => 3,
-        TaskSourceKind::AbsPath { .. } => 4,
+        TaskSourceKind::Extension { .. } => 4,
+        TaskSourceKind::AbsPath { .. } => 5,
     }
 }
 
@@ -1134,6 +1228,73 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_extension_provided_tasks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(Inventory::new);
+        let worktree_1 = WorktreeId::from_usize(1);
+        let worktree_2 = WorktreeId::from_usize(2);
+        let provider = SharedString::from("just");
+
+        inventory.update(cx, |inventory, _| {
+            inventory.update_extension_tasks(
+                worktree_1,
+                provider.clone(),
+                Some(WorktreeTasks {
+                    templates: vec![TaskTemplate {
+                        label: "just build".to_string(),
+                        command: "just".to_string(),
+                        args: vec!["$ZED_CUSTOM_JUST_RECIPE".to_string()],
+                        ..TaskTemplate::default()
+                    }],
+                    variables: TaskVariables::from_iter([(
+                        VariableName::Custom("JUST_RECIPE".into()),
+                        "build".to_string(),
+                    )]),
+                }),
+            );
+        });
+
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_1), cx),
+            vec!["just build".to_string()]
+        );
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_2), cx),
+            Vec::<String>::new(),
+            "Extension tasks should only be listed for the worktree they were computed for"
+        );
+
+        let resolved_args = inventory.update(cx, |inventory, cx| {
+            let mut task_contexts = TaskContexts::default();
+            task_contexts.active_worktree_context = Some((worktree_1, TaskContext::default()));
+            let (_, current) = inventory.used_and_current_resolved_tasks(&task_contexts, cx);
+            current
+                .into_iter()
+                .map(|(kind, task)| (kind, task.resolved.args))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            resolved_args,
+            vec![(
+                TaskSourceKind::Extension {
+                    worktree: worktree_1,
+                    provider: provider.clone(),
+                },
+                vec!["build".to_string()]
+            )],
+            "Extension tasks should be resolved with the variables of their provider"
+        );
+
+        inventory.update(cx, |inventory, _| {
+            inventory.retain_extension_tasks(|_, provider_id| provider_id != provider.as_ref());
+        });
+        assert_eq!(
+            task_template_names(&inventory, Some(worktree_1), cx),
+            Vec::<String>::new(),
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         if std::env::var("RUST_LOG").is_ok() {
             env_logger::try_init().ok();
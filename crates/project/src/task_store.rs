@@ -5,7 +5,7 @@ use std::{
 
 use anyhow::Context as _;
 use collections::HashMap;
-use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Task, WeakEntity};
+use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
 use language::{
     ContextProvider as _, LanguageToolchainStore, Location,
     proto::{deserialize_anchor, serialize_anchor},
@@ -15,10 +15,15 @@ use settings::{InvalidSettingsError, SettingsLocation};
 use task::{TaskContext, TaskVariables, VariableName};
 use text::{BufferId, OffsetRangeExt};
 use util::ResultExt;
+use worktree::WorktreeId;
 
 use crate::{
-    BasicContextProvider, Inventory, ProjectEnvironment, buffer_store::BufferStore,
-    worktree_store::WorktreeStore,
+    BasicContextProvider, Inventory, ProjectEnvironment,
+    buffer_store::BufferStore,
+    extension_task_provider::{
+        TaskProviderRegistry, TaskProviderRegistryEvent, TaskProviderWorktreeDelegate,
+    },
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
 #[allow(clippy::large_enum_variant)] // platform-dependent warning
@@ -39,6 +44,9 @@ enum StoreMode {
     Local {
         downstream_client: Option<(AnyProtoClient, u64)>,
         environment: Entity<ProjectEnvironment>,
+        task_providers: Entity<TaskProviderRegistry>,
+        extension_task_reloads: HashMap<(WorktreeId, Arc<str>), Task<()>>,
+        _subscriptions: [Subscription; 2],
     },
     Remote {
         upstream_client: AnyProtoClient,
@@ -164,10 +172,18 @@ impl TaskStore {
         environment: Entity<ProjectEnvironment>,
         cx: &mut Context<Self>,
     ) -> Self {
+        let task_providers = TaskProviderRegistry::default_global(cx);
+        let subscriptions = [
+            cx.subscribe(&worktree_store, Self::on_worktree_store_event),
+            cx.subscribe(&task_providers, Self::on_task_provider_registry_event),
+        ];
         Self::Functional(StoreState {
             mode: StoreMode::Local {
                 downstream_client: None,
                 environment,
+                task_providers,
+                extension_task_reloads: HashMap::default(),
+                _subscriptions: subscriptions,
             },
             task_inventory: Inventory::new(cx),
             buffer_store,
@@ -279,6 +295,170 @@ impl TaskStore {
         })
     }
 
+    fn on_worktree_store_event(
+        &mut self,
+        _: Entity<WorktreeStore>,
+        event: &WorktreeStoreEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let Self::Functional(StoreState {
+            mode: StoreMode::Local { task_providers, .. },
+            ..
+        }) = self
+        else {
+            return;
+        };
+        match event {
+            WorktreeStoreEvent::WorktreeAdded(worktree) => {
+                let worktree_id = worktree.read(cx).id();
+                let provider_ids = task_providers
+                    .read(cx)
+                    .providers()
+                    .map(|(provider_id, _)| provider_id.clone())
+                    .collect::<Vec<_>>();
+                for provider_id in provider_ids {
+                    self.reload_extension_tasks(worktree_id, provider_id, cx);
+                }
+            }
+            WorktreeStoreEvent::WorktreeRemoved(_, worktree_id) => {
+                self.remove_extension_tasks(|id, _| id == *worktree_id, cx);
+            }
+            WorktreeStoreEvent::WorktreeUpdatedEntries(worktree_id, changes) => {
+                let provider_ids = task_providers
+                    .read(cx)
+                    .providers()
+                    .filter(|(_, provider)| {
+                        changes
+                            .iter()
+                            .any(|(path, _, _)| provider.is_watching(path))
+                    })
+                    .map(|(provider_id, _)| provider_id.clone())
+                    .collect::<Vec<_>>();
+                for provider_id in provider_ids {
+                    self.reload_extension_tasks(*worktree_id, provider_id, cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_task_provider_registry_event(
+        &mut self,
+        _: Entity<TaskProviderRegistry>,
+        event: &TaskProviderRegistryEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let Self::Functional(state) = self else {
+            return;
+        };
+        match event {
+            TaskProviderRegistryEvent::ProviderAdded(provider_id) => {
+                let worktree_ids = state
+                    .worktree_store
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).id())
+                    .collect::<Vec<_>>();
+                for worktree_id in worktree_ids {
+                    self.reload_extension_tasks(worktree_id, provider_id.clone(), cx);
+                }
+            }
+            TaskProviderRegistryEvent::ProviderRemoved(provider_id) => {
+                self.remove_extension_tasks(|_, id| id == provider_id.as_ref(), cx);
+            }
+        }
+    }
+
+    /// Asks the extension behind the task provider given to recompute its tasks for the worktree,
+    /// replacing the previously computed ones.
+    fn reload_extension_tasks(
+        &mut self,
+        worktree_id: WorktreeId,
+        provider_id: Arc<str>,
+        cx: &mut Context<Self>,
+    ) {
+        let Self::Functional(StoreState {
+            mode:
+                StoreMode::Local {
+                    environment,
+                    task_providers,
+                    extension_task_reloads,
+                    ..
+                },
+            task_inventory,
+            worktree_store,
+            ..
+        }) = self
+        else {
+            return;
+        };
+        let Some(provider) = task_providers.read(cx).provider(&provider_id).cloned() else {
+            return;
+        };
+        let Some(worktree) = worktree_store.read(cx).worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        if !worktree.read(cx).is_visible() {
+            return;
+        }
+        let Some(local_worktree) = worktree.read(cx).as_local() else {
+            return;
+        };
+        let root_path = local_worktree.abs_path().clone();
+        let fs = local_worktree.fs().clone();
+        let shell_env = environment.update(cx, |environment, cx| {
+            environment.get_worktree_environment(worktree, cx)
+        });
+        let task_inventory = task_inventory.downgrade();
+
+        let reload = cx.spawn(async move |_, cx| {
+            let delegate = Arc::new(TaskProviderWorktreeDelegate {
+                worktree_id,
+                root_path,
+                fs,
+                shell_env: shell_env.await.unwrap_or_default(),
+            });
+            let tasks = provider
+                .extension
+                .worktree_tasks(provider.provider_id.clone(), delegate)
+                .await
+                .with_context(|| format!("loading tasks from task provider {provider_id}"))
+                .log_err();
+            task_inventory
+                .update(cx, |inventory, _| {
+                    inventory.update_extension_tasks(worktree_id, provider_id.into(), tasks)
+                })
+                .ok();
+        });
+        extension_task_reloads.insert((worktree_id, provider_id), reload);
+    }
+
+    fn remove_extension_tasks(
+        &mut self,
+        mut predicate: impl FnMut(WorktreeId, &str) -> bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Self::Functional(StoreState {
+            mode:
+                StoreMode::Local {
+                    extension_task_reloads,
+                    ..
+                },
+            task_inventory,
+            ..
+        }) = self
+        else {
+            return;
+        };
+        extension_task_reloads
+            .retain(|(worktree_id, provider_id), _| !predicate(*worktree_id, provider_id));
+        task_inventory.update(cx, |inventory, _| {
+            inventory.retain_extension_tasks(|worktree_id, provider_id| {
+                !predicate(worktree_id, provider_id)
+            })
+        });
+    }
+
     pub(super) fn update_user_debug_scenarios(
         &self,
         location: TaskSettingsLocation<'_>,
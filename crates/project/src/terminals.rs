@@ -4,6 +4,7 @@ use collections::HashMap;
 use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, Task, WeakEntity};
 use itertools::Itertools;
 use language::LanguageName;
+use remote::command_session::COMMAND_PLACEHOLDER;
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
 use std::{
@@ -37,6 +38,11 @@ pub enum TerminalKind {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SshCommand {
     pub arguments: Vec<String>,
+    /// The program `arguments` are passed to, when the connection isn't made with `ssh`.
+    pub exec_program: Option<String>,
+    /// Whether `exec_program` should allocate a pseudo-terminal for the command. Commands
+    /// run over `ssh` always get one.
+    pub tty: bool,
 }
 
 impl SshCommand {
@@ -74,7 +80,11 @@ impl Project {
             if let Some(args) = ssh_client.ssh_args() {
                 return Some((
                     ssh_client.connection_options().host.clone(),
-                    SshCommand { arguments: args },
+                    SshCommand {
+                        arguments: args,
+                        exec_program: ssh_client.exec_program(),
+                        tty: true,
+                    },
                 ));
             }
         }
@@ -572,10 +582,32 @@ pub fn wrap_for_ssh(
     } else {
         format!("cd; {env_changes} {to_run}")
     };
+    let mut args = ssh_command.arguments.clone();
+
+    // Programs like `docker exec` take the command as separate arguments rather
+    // than a single string to be interpreted by the remote shell. Command
+    // templates say where the command goes with a placeholder.
+    if let Some(program) = &ssh_command.exec_program {
+        // Programs like `docker exec` take `-t` after the subcommand to allocate a
+        // pseudo-terminal. It would corrupt the output of commands that aren't run in a
+        // terminal, such as debug adapters talking over stdio.
+        if ssh_command.tty && args.first().is_some_and(|arg| arg == "exec") {
+            args.insert(1, "-t".to_string());
+        }
+        if let Some(arg) = args
+            .iter_mut()
+            .find(|arg| arg.contains(COMMAND_PLACEHOLDER))
+        {
+            *arg = arg.replace(COMMAND_PLACEHOLDER, &commands);
+        } else {
+            args.extend(["sh".to_string(), "-c".to_string(), commands]);
+        }
+        return (program.clone(), args);
+    }
+
     let shell_invocation = format!("sh -c {}", shlex::try_quote(&commands).unwrap());
 
     let program = "ssh".to_string();
-    let mut args = ssh_command.arguments.clone();
 
     args.push("-t".to_string());
     args.push(shell_invocation);
@@ -634,4 +666,51 @@ mod tests {
         }
         assert_eq!(env.get("OTHER").unwrap(), "aaa");
     }
+
+    #[test]
+    fn test_wrap_for_ssh_in_container() {
+        let mut ssh_command = super::SshCommand {
+            arguments: vec!["exec".into(), "-i".into(), "my-container".into()],
+            exec_program: Some("docker".into()),
+            tty: true,
+        };
+        let command = ("ls".to_string(), vec!["-la".to_string()]);
+
+        // Terminals are given a pseudo-terminal.
+        let (program, args) = super::wrap_for_ssh(
+            &ssh_command,
+            Some((&command.0, &command.1)),
+            None,
+            HashMap::default(),
+            None,
+        );
+        assert_eq!(program, "docker");
+        assert_eq!(
+            args,
+            [
+                "exec",
+                "-t",
+                "-i",
+                "my-container",
+                "sh",
+                "-c",
+                "cd;  ls -la"
+            ]
+        );
+
+        // Debug adapters talk over stdio, which a pseudo-terminal would corrupt.
+        ssh_command.tty = false;
+        let (program, args) = super::wrap_for_ssh(
+            &ssh_command,
+            Some((&command.0, &command.1)),
+            None,
+            HashMap::default(),
+            None,
+        );
+        assert_eq!(program, "docker");
+        assert_eq!(
+            args,
+            ["exec", "-i", "my-container", "sh", "-c", "cd;  ls -la"]
+        );
+    }
 }
@@ -296,6 +296,11 @@ messages!(
     (AskPassResponse, Background),
     (GitCreateBranch, Background),
     (GitChangeBranch, Background),
+    (GitGetWorktrees, Background),
+    (GitWorktreesResponse, Background),
+    (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitPruneWorktrees, Background),
     (CheckForPushedCommits, Background),
     (CheckForPushedCommitsResponse, Background),
     (GitDiff, Background),
@@ -460,6 +465,10 @@ request_messages!(
     (AskPassRequest, AskPassResponse),
     (GitCreateBranch, Ack),
     (GitChangeBranch, Ack),
+    (GitGetWorktrees, GitWorktreesResponse),
+    (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitPruneWorktrees, Ack),
     (CheckForPushedCommits, CheckForPushedCommitsResponse),
     (GitDiff, GitDiffResponse),
     (GitInit, Ack),
@@ -584,6 +593,10 @@ entity_messages!(
     AskPassRequest,
     GitChangeBranch,
     GitCreateBranch,
+    GitGetWorktrees,
+    GitCreateWorktree,
+    GitRemoveWorktree,
+    GitPruneWorktrees,
     CheckForPushedCommits,
     GitDiff,
     GitInit,
@@ -600,10 +600,11 @@ impl RemoteServerProjects {
     ) -> impl IntoElement {
         let (main_label, aux_label) = if let Some(nickname) = ssh_server.connection.nickname.clone()
         {
-            let aux_label = SharedString::from(format!("({})", ssh_server.connection.host));
+            let aux_label =
+                SharedString::from(format!("({})", ssh_server.connection.display_host()));
             (nickname.into(), Some(aux_label))
         } else {
-            (ssh_server.connection.host.clone(), None)
+            (ssh_server.connection.display_host(), None)
         };
         v_flex()
             .w_full()
@@ -873,6 +874,8 @@ impl RemoteServerProjects {
                     args: connection_options.args.unwrap_or_default(),
                     upload_binary_over_ssh: None,
                     port_forwards: connection_options.port_forwards,
+                    container: connection_options.container,
+                    command: connection_options.command,
                 })
         });
     }
@@ -929,7 +932,7 @@ impl RemoteServerProjects {
                                     .gap_1()
                                     .child(
                                         Label::new(
-                                            "Enter the command you use to SSH into this server.",
+                                            "Enter the command you use to SSH into this server, or `docker exec <container>`.",
                                         )
                                         .color(Color::Muted)
                                         .size(LabelSize::Small),
@@ -962,7 +965,7 @@ impl RemoteServerProjects {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        let connection_string = connection.host.clone();
+        let connection_string = connection.display_host();
 
         let mut view = Navigable::new(
             div()
@@ -1213,7 +1216,7 @@ impl RemoteServerProjects {
                 .track_focus(&self.focus_handle(cx));
         };
 
-        let connection_string = connection.host.clone();
+        let connection_string = connection.display_host();
         let nickname = connection.nickname.clone().map(|s| s.into());
 
         v_flex()
@@ -16,7 +16,9 @@ use language::CursorShape;
 use markdown::{Markdown, MarkdownElement, MarkdownStyle};
 use release_channel::ReleaseChannel;
 use remote::ssh_session::{ConnectionIdentifier, SshPortForwardOption};
-use remote::{SshConnectionOptions, SshPlatform, SshRemoteClient};
+use remote::{
+    CommandOptions, ContainerOptions, SshConnectionOptions, SshPlatform, SshRemoteClient,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
@@ -54,6 +56,8 @@ impl SshSettings {
                     username,
                     port_forwards: conn.port_forwards,
                     password: None,
+                    container: conn.container,
+                    command: conn.command,
                 };
             }
         }
@@ -90,6 +94,26 @@ pub struct SshConnection {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port_forwards: Option<Vec<SshPortForwardOption>>,
+
+    /// When set, `host` is the name of a local Docker or Podman container to
+    /// connect to instead of an SSH host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<ContainerOptions>,
+
+    /// When set, the host is reached by running these commands instead of `ssh`,
+    /// for example to go through `kubectl exec` or a custom tunnel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<CommandOptions>,
+}
+
+impl SshConnection {
+    /// The host as shown in the UI, prefixed with the runtime for containers.
+    pub fn display_host(&self) -> SharedString {
+        match &self.container {
+            Some(container) => format!("{}://{}", container.runtime.program(), self.host).into(),
+            None => self.host.clone(),
+        }
+    }
 }
 
 impl From<SshConnection> for SshConnectionOptions {
@@ -103,6 +127,8 @@ impl From<SshConnection> for SshConnectionOptions {
             nickname: val.nickname,
             upload_binary_over_ssh: val.upload_binary_over_ssh.unwrap_or_default(),
             port_forwards: val.port_forwards,
+            container: val.container,
+            command: val.command,
         }
     }
 }
@@ -0,0 +1,435 @@
+use crate::{
+    shell_script,
+    ssh_session::{
+        RemoteConnection, SshClientDelegate, SshConnectionOptions, SshPlatform,
+        SshRemoteConnection, parse_platform, proxy_command, remote_server_binary_path,
+    },
+};
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use futures::{
+    AsyncWriteExt as _,
+    channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
+};
+use gpui::{App, AsyncApp, SemanticVersion, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use smol::process::{self, Stdio};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering::SeqCst},
+    },
+    time::Instant,
+};
+
+/// Replaced with the shell command to run on the host in command templates.
+pub const COMMAND_PLACEHOLDER: &str = "{command}";
+/// Replaced with the local path of the file or directory to upload.
+pub const SOURCE_PLACEHOLDER: &str = "{src}";
+/// Replaced with the absolute destination path on the host.
+pub const DESTINATION_PLACEHOLDER: &str = "{dest}";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+pub struct CommandOptions {
+    /// Runs a shell command on the host with its stdio connected to Zed, where
+    /// `{command}` is replaced by the command. For example
+    /// `kubectl exec -i my-pod -- sh -c {command}`.
+    pub command: String,
+    /// Copies a local file or directory to the host, where `{src}` and `{dest}`
+    /// are replaced by the local and remote paths. For example
+    /// `kubectl cp {src} my-pod:{dest}`.
+    ///
+    /// When unset, the server binary is streamed over `command` instead, and
+    /// directories cannot be uploaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload_command: Option<String>,
+    /// Like `command`, but used to open terminals, so it should allocate a TTY.
+    /// Defaults to `command`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal_command: Option<String>,
+}
+
+/// Splits a command template into arguments, substituting each placeholder
+/// with its value as part of a single argument.
+fn expand_template(template: &str, replacements: &[(&str, &str)]) -> Result<Vec<String>> {
+    let args = shlex::split(template)
+        .with_context(|| format!("invalid command template: {template:?}"))?;
+    if args.is_empty() {
+        anyhow::bail!("command template is empty");
+    }
+    for (placeholder, _) in replacements {
+        if !args.iter().any(|arg| arg.contains(placeholder)) {
+            anyhow::bail!("command template {template:?} is missing {placeholder}");
+        }
+    }
+    Ok(args
+        .into_iter()
+        .map(|arg| {
+            replacements.iter().fold(arg, |arg, (placeholder, value)| {
+                arg.replace(placeholder, value)
+            })
+        })
+        .collect())
+}
+
+fn template_command(args: &[String]) -> process::Command {
+    let mut command = util::command::new_smol_command(&args[0]);
+    command
+        .args(&args[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    command
+}
+
+pub(crate) struct CommandRemoteConnection {
+    connection_options: SshConnectionOptions,
+    options: CommandOptions,
+    home_dir: PathBuf,
+    remote_binary_path: Option<PathBuf>,
+    killed: AtomicBool,
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandRemoteConnection {
+    async fn kill(&self) -> Result<()> {
+        // Every proxy runs in its own process, which is killed when its task is dropped.
+        self.killed.store(true, SeqCst);
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.killed.load(SeqCst)
+    }
+
+    fn ssh_args(&self) -> Vec<String> {
+        let template = self
+            .options
+            .terminal_command
+            .as_ref()
+            .unwrap_or(&self.options.command);
+        shlex::split(template)
+            .map(|args| args.into_iter().skip(1).collect())
+            .unwrap_or_default()
+    }
+
+    fn exec_program(&self) -> Option<String> {
+        let template = self
+            .options
+            .terminal_command
+            .as_ref()
+            .unwrap_or(&self.options.command);
+        shlex::split(template)?.into_iter().next()
+    }
+
+    fn connection_options(&self) -> SshConnectionOptions {
+        self.connection_options.clone()
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: PathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let dest_path = self.home_dir.join(dest_path);
+        let command = self.upload_command(&src_path, &dest_path);
+
+        cx.background_spawn(async move {
+            let output = command?.output().await?;
+
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "failed to upload directory {} -> {}: {}",
+                    src_path.display(),
+                    dest_path.display(),
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+
+            Ok(())
+        })
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = self.remote_binary_path.clone() else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let start_proxy_command = proxy_command(&remote_binary_path, &unique_identifier, reconnect);
+
+        let proxy_process = match self.command(&start_proxy_command).and_then(|mut command| {
+            // IMPORTANT: we kill this process when we drop the task that uses it.
+            Ok(command.kill_on_drop(true).spawn()?)
+        }) {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        SshRemoteConnection::multiplex(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            &cx,
+        )
+    }
+}
+
+impl CommandRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: SshConnectionOptions,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let options = connection_options
+            .command
+            .clone()
+            .context("missing command options")?;
+
+        let mut this = Self {
+            connection_options,
+            options,
+            home_dir: PathBuf::new(),
+            remote_binary_path: None,
+            killed: AtomicBool::new(false),
+        };
+
+        delegate.set_status(Some("Connecting"), cx);
+        this.home_dir = PathBuf::from(this.run_script("pwd").await?.trim());
+
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await?,
+        );
+
+        Ok(this)
+    }
+
+    /// Runs `script` with the host's shell, from the user's home directory.
+    fn command(&self, script: &str) -> Result<process::Command> {
+        let script = format!("cd; {script}");
+        let args = expand_template(&self.options.command, &[(COMMAND_PLACEHOLDER, &script)])?;
+        log::debug!("running {:?}", args);
+        Ok(template_command(&args))
+    }
+
+    fn upload_command(&self, src_path: &Path, dest_path: &Path) -> Result<process::Command> {
+        let template = self
+            .options
+            .upload_command
+            .as_ref()
+            .context("uploading requires `upload_command` to be configured")?;
+        let args = expand_template(
+            template,
+            &[
+                (SOURCE_PLACEHOLDER, &src_path.to_string_lossy()),
+                (DESTINATION_PLACEHOLDER, &dest_path.to_string_lossy()),
+            ],
+        )?;
+        log::debug!("running {:?}", args);
+        Ok(template_command(&args))
+    }
+
+    async fn run_script(&self, script: &str) -> Result<String> {
+        let output = self.command(script)?.output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(anyhow!(
+                "failed to run command: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+
+    async fn platform(&self) -> Result<SshPlatform> {
+        let uname = self.run_script("uname -sm").await?;
+        parse_platform(&uname)
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn SshClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: SemanticVersion,
+        commit: Option<AppCommitSha>,
+        cx: &mut AsyncApp,
+    ) -> Result<PathBuf> {
+        let dst_path = remote_server_binary_path(release_channel, version, commit);
+
+        #[cfg(debug_assertions)]
+        if std::env::var("ZED_BUILD_REMOTE_SERVER").is_ok() {
+            let src_path =
+                SshRemoteConnection::build_local(self.platform().await?, delegate, cx).await?;
+            self.upload_server_binary(&src_path, &dst_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        let version_script = shell_script!(
+            "{binary_path} version",
+            binary_path = &dst_path.to_string_lossy()
+        );
+        if self.run_script(&version_script).await.is_ok() {
+            return Ok(dst_path);
+        }
+
+        let wanted_version = cx.update(|cx| match release_channel {
+            ReleaseChannel::Nightly => Ok(None),
+            ReleaseChannel::Dev => {
+                anyhow::bail!(
+                    "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
+                    dst_path
+                )
+            }
+            _ => Ok(Some(AppVersion::global(cx))),
+        })??;
+
+        let platform = self.platform().await?;
+        let src_path = delegate
+            .download_server_binary_locally(platform, release_channel, wanted_version, cx)
+            .await?;
+        self.upload_server_binary(&src_path, &dst_path, delegate, cx)
+            .await?;
+        Ok(dst_path)
+    }
+
+    async fn upload_server_binary(
+        &self,
+        src_path: &Path,
+        dst_path: &Path,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let dst_dir = dst_path.parent().unwrap_or(Path::new("."));
+        let tmp_path = format!("{}.tmp", dst_path.to_string_lossy());
+
+        let t0 = Instant::now();
+        delegate.set_status(Some("Uploading remote development server"), cx);
+
+        if self.options.upload_command.is_some() {
+            let tmp_path_gz = PathBuf::from(format!(
+                "/tmp/zed-remote-server-download-{}.gz",
+                std::process::id()
+            ));
+            log::info!("uploading remote development server to {:?}", tmp_path_gz);
+            let output = self
+                .upload_command(src_path, &tmp_path_gz)?
+                .output()
+                .await?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "failed to upload server binary: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+            log::info!("uploaded remote development server in {:?}", t0.elapsed());
+
+            delegate.set_status(Some("Extracting remote development server"), cx);
+            let script = shell_script!(
+                "mkdir -p {dst_dir} && gunzip -c {tmp_path_gz} > {tmp_path} && chmod 755 {tmp_path} && mv {tmp_path} {dst_path}; status=$?; rm -f {tmp_path_gz}; exit $status",
+                dst_dir = &dst_dir.to_string_lossy(),
+                tmp_path_gz = &tmp_path_gz.to_string_lossy(),
+                tmp_path = &tmp_path,
+                dst_path = &dst_path.to_string_lossy(),
+            );
+            self.run_script(&script).await?;
+            return Ok(());
+        }
+
+        // Without an upload command, stream the compressed binary over the
+        // command's stdin and extract it on the fly.
+        log::info!("streaming remote development server to {:?}", dst_path);
+        let script = shell_script!(
+            "mkdir -p {dst_dir} && gunzip -c > {tmp_path} && chmod 755 {tmp_path} && mv {tmp_path} {dst_path}",
+            dst_dir = &dst_dir.to_string_lossy(),
+            tmp_path = &tmp_path,
+            dst_path = &dst_path.to_string_lossy(),
+        );
+        let contents = smol::fs::read(src_path).await?;
+        let mut child = self.command(&script)?.kill_on_drop(true).spawn()?;
+        let mut stdin = child.stdin.take().context("failed to open stdin")?;
+        stdin.write_all(&contents).await?;
+        stdin.close().await?;
+        drop(stdin);
+        let output = child.output().await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "failed to upload server binary: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        log::info!("uploaded remote development server in {:?}", t0.elapsed());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_template() {
+        assert_eq!(
+            expand_template(
+                "kubectl exec -i my-pod -- sh -c {command}",
+                &[(COMMAND_PLACEHOLDER, "cd; uname -sm")]
+            )
+            .unwrap(),
+            [
+                "kubectl",
+                "exec",
+                "-i",
+                "my-pod",
+                "--",
+                "sh",
+                "-c",
+                "cd; uname -sm"
+            ]
+        );
+        assert_eq!(
+            expand_template(
+                "gcloud compute scp {src} 'my vm:{dest}'",
+                &[
+                    (SOURCE_PLACEHOLDER, "/tmp/a b"),
+                    (DESTINATION_PLACEHOLDER, "/home/me/.zed_server")
+                ]
+            )
+            .unwrap(),
+            [
+                "gcloud",
+                "compute",
+                "scp",
+                "/tmp/a b",
+                "my vm:/home/me/.zed_server"
+            ]
+        );
+        assert!(expand_template("kubectl exec -i my-pod", &[(COMMAND_PLACEHOLDER, "ls")]).is_err());
+        assert!(expand_template("", &[]).is_err());
+    }
+}
@@ -0,0 +1,762 @@
+use crate::{
+    shell_script,
+    ssh_session::{
+        RemoteConnection, SshClientDelegate, SshConnectionOptions, SshPlatform,
+        SshRemoteConnection, parse_platform, proxy_command, remote_server_binary_path,
+    },
+};
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::BTreeMap;
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{App, AsyncApp, SemanticVersion, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use smol::process::{self, Stdio};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering::SeqCst},
+    },
+    time::Instant,
+};
+
+/// The label set on containers created from a `devcontainer.json`.
+const DEVCONTAINER_LABEL: &str = "dev.zed.devcontainer";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+pub struct ContainerOptions {
+    /// The container runtime used to run the container.
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    /// A `devcontainer.json` used to create the container when it doesn't exist yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub devcontainer: Option<PathBuf>,
+}
+
+impl SshConnectionOptions {
+    /// Parses `docker exec [-u USER] CONTAINER` (or the same with `podman`), optionally
+    /// with `--devcontainer PATH` to create the container from a `devcontainer.json`.
+    ///
+    /// Returns `None` if the input isn't a container command.
+    pub(crate) fn parse_container_command_line(input: &str) -> Result<Option<Self>> {
+        let mut tokens = shlex::split(input)
+            .ok_or_else(|| anyhow!("invalid input"))?
+            .into_iter()
+            .peekable();
+        let runtime = match tokens.peek().map(String::as_str) {
+            Some("docker") => ContainerRuntime::Docker,
+            Some("podman") => ContainerRuntime::Podman,
+            _ => return Ok(None),
+        };
+        tokens.next();
+        if tokens.peek().map(String::as_str) == Some("exec") {
+            tokens.next();
+        }
+
+        let mut username = None;
+        let mut devcontainer = None;
+        let mut container = None;
+        while let Some(arg) = tokens.next() {
+            match arg.as_str() {
+                "-i" | "-t" | "-it" | "-ti" | "--interactive" | "--tty" => {}
+                "-u" | "--user" => {
+                    username = Some(tokens.next().context("missing user")?);
+                }
+                "--devcontainer" => {
+                    let path = tokens.next().context("missing devcontainer.json path")?;
+                    devcontainer = Some(PathBuf::from(path));
+                }
+                _ => {
+                    if let Some(user) = arg.strip_prefix("--user=") {
+                        username = Some(user.to_string());
+                    } else if let Some(path) = arg.strip_prefix("--devcontainer=") {
+                        devcontainer = Some(PathBuf::from(path));
+                    } else if arg.starts_with('-') || container.is_some() {
+                        anyhow::bail!("unsupported argument: {:?}", arg);
+                    } else {
+                        container = Some(arg);
+                    }
+                }
+            }
+        }
+
+        let host = match (container, &devcontainer) {
+            (Some(container), _) => container,
+            (None, Some(devcontainer)) => default_container_name(devcontainer),
+            (None, None) => anyhow::bail!("missing container name"),
+        };
+
+        Ok(Some(Self {
+            host,
+            username,
+            container: Some(ContainerOptions {
+                runtime,
+                devcontainer,
+            }),
+            ..Default::default()
+        }))
+    }
+}
+
+/// The folder a `devcontainer.json` belongs to, which gets mounted into the container.
+fn devcontainer_workspace_folder(devcontainer: &Path) -> &Path {
+    let config_dir = devcontainer.parent().unwrap_or(Path::new("/"));
+    if config_dir
+        .file_name()
+        .is_some_and(|name| name == ".devcontainer")
+    {
+        config_dir.parent().unwrap_or(config_dir)
+    } else {
+        config_dir
+    }
+}
+
+fn default_container_name(devcontainer: &Path) -> String {
+    let folder_name = devcontainer_workspace_folder(devcontainer)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let folder_name = folder_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    format!("zed-{}", folder_name.trim_matches('-'))
+}
+
+/// The subset of `devcontainer.json` used to create a container.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevContainerConfig {
+    image: Option<String>,
+    build: Option<DevContainerBuild>,
+    #[serde(default)]
+    run_args: Vec<String>,
+    #[serde(default)]
+    container_env: BTreeMap<String, String>,
+    workspace_folder: Option<String>,
+    #[serde(default)]
+    mounts: Vec<serde_json::Value>,
+    container_user: Option<String>,
+    remote_user: Option<String>,
+    post_create_command: Option<DevContainerCommand>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevContainerBuild {
+    dockerfile: Option<String>,
+    context: Option<String>,
+    #[serde(default)]
+    args: BTreeMap<String, String>,
+    target: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DevContainerCommand {
+    Shell(String),
+    Args(Vec<String>),
+    Parallel(BTreeMap<String, DevContainerCommand>),
+}
+
+impl DevContainerConfig {
+    async fn load(path: &Path) -> Result<Self> {
+        let contents = smol::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        let workspace_folder = devcontainer_workspace_folder(path);
+        let contents = contents
+            .replace(
+                "${localWorkspaceFolderBasename}",
+                &workspace_folder
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default(),
+            )
+            .replace(
+                "${localWorkspaceFolder}",
+                &workspace_folder.to_string_lossy(),
+            );
+
+        serde_json_lenient::from_str(&contents)
+            .with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    fn container_workspace_folder(&self, devcontainer: &Path) -> String {
+        self.workspace_folder.clone().unwrap_or_else(|| {
+            let folder_name = devcontainer_workspace_folder(devcontainer)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            format!("/workspaces/{folder_name}")
+        })
+    }
+}
+
+fn runtime_command(runtime: ContainerRuntime) -> process::Command {
+    let mut command = util::command::new_smol_command(runtime.program());
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    command
+}
+
+async fn run_runtime_command(runtime: ContainerRuntime, args: &[&str]) -> Result<String> {
+    log::debug!("{} {:?}", runtime.program(), args);
+    let output = runtime_command(runtime)
+        .args(args)
+        .output()
+        .await
+        .with_context(|| {
+            format!(
+                "failed to run `{}`. Is it installed and on your PATH?",
+                runtime.program()
+            )
+        })?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(anyhow!(
+            "failed to run command: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
+pub(crate) struct ContainerRemoteConnection {
+    connection_options: SshConnectionOptions,
+    runtime: ContainerRuntime,
+    /// The user to run commands as, if not the container's default user.
+    user: Option<String>,
+    home_dir: PathBuf,
+    remote_binary_path: Option<PathBuf>,
+    killed: AtomicBool,
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for ContainerRemoteConnection {
+    async fn kill(&self) -> Result<()> {
+        // The proxy processes are killed when their tasks are dropped, and the
+        // container itself outlives the connection.
+        self.killed.store(true, SeqCst);
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.killed.load(SeqCst)
+    }
+
+    fn ssh_args(&self) -> Vec<String> {
+        let mut args = vec!["exec".to_string(), "-i".to_string()];
+        if let Some(user) = &self.user {
+            args.push("-u".to_string());
+            args.push(user.clone());
+        }
+        args.push(self.connection_options.host.clone());
+        args
+    }
+
+    fn exec_program(&self) -> Option<String> {
+        Some(self.runtime.program().to_string())
+    }
+
+    fn connection_options(&self) -> SshConnectionOptions {
+        self.connection_options.clone()
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: PathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let dest_path = self.home_dir.join(dest_path);
+        let output = runtime_command(self.runtime)
+            .arg("cp")
+            .arg(&src_path)
+            .arg(format!(
+                "{}:{}",
+                self.connection_options.host,
+                dest_path.display()
+            ))
+            .output();
+
+        cx.background_spawn(async move {
+            let output = output.await?;
+
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "failed to upload directory {} -> {}: {}",
+                    src_path.display(),
+                    dest_path.display(),
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+
+            Ok(())
+        })
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = self.remote_binary_path.clone() else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let start_proxy_command = proxy_command(&remote_binary_path, &unique_identifier, reconnect);
+
+        let proxy_process = match self
+            .exec_command(&start_proxy_command)
+            // IMPORTANT: we kill this process when we drop the task that uses it.
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        SshRemoteConnection::multiplex(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            &cx,
+        )
+    }
+}
+
+impl ContainerRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: SshConnectionOptions,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let options = connection_options
+            .container
+            .clone()
+            .context("missing container options")?;
+        let runtime = options.runtime;
+        let container = connection_options.host.clone();
+
+        delegate.set_status(Some("Starting container"), cx);
+
+        let config = match &options.devcontainer {
+            Some(devcontainer) => Some(DevContainerConfig::load(devcontainer).await?),
+            None => None,
+        };
+
+        let state = run_runtime_command(
+            runtime,
+            &[
+                "container",
+                "inspect",
+                "--format",
+                "{{.State.Running}}",
+                &container,
+            ],
+        )
+        .await;
+        match state {
+            Ok(running) if running.trim() == "true" => {}
+            Ok(_) => {
+                run_runtime_command(runtime, &["start", &container])
+                    .await
+                    .with_context(|| format!("failed to start container {container:?}"))?;
+            }
+            Err(error) => {
+                let (Some(devcontainer), Some(config)) = (&options.devcontainer, &config) else {
+                    return Err(error.context(format!("container {container:?} was not found")));
+                };
+                Self::create_container(runtime, &container, devcontainer, config, &delegate, cx)
+                    .await?;
+            }
+        }
+
+        let user = connection_options.username.clone().or_else(|| {
+            config
+                .as_ref()
+                .and_then(|config| config.remote_user.clone().or(config.container_user.clone()))
+        });
+
+        let mut this = Self {
+            connection_options,
+            runtime,
+            user,
+            home_dir: PathBuf::new(),
+            remote_binary_path: None,
+            killed: AtomicBool::new(false),
+        };
+
+        this.home_dir = PathBuf::from(this.run_script("pwd").await?.trim());
+
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await?,
+        );
+
+        Ok(this)
+    }
+
+    async fn create_container(
+        runtime: ContainerRuntime,
+        container: &str,
+        devcontainer: &Path,
+        config: &DevContainerConfig,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let config_dir = devcontainer.parent().unwrap_or(Path::new("/"));
+
+        let image = if let Some(build) = &config.build {
+            delegate.set_status(Some("Building dev container image"), cx);
+            let image = format!("{container}-image");
+            let dockerfile = config_dir.join(build.dockerfile.as_deref().unwrap_or("Dockerfile"));
+            let context = config_dir.join(build.context.as_deref().unwrap_or("."));
+
+            let mut args = vec![
+                "build".to_string(),
+                "--tag".to_string(),
+                image.clone(),
+                "--file".to_string(),
+                dockerfile.to_string_lossy().to_string(),
+            ];
+            for (name, value) in &build.args {
+                args.push("--build-arg".to_string());
+                args.push(format!("{name}={value}"));
+            }
+            if let Some(target) = &build.target {
+                args.push("--target".to_string());
+                args.push(target.clone());
+            }
+            args.push(context.to_string_lossy().to_string());
+
+            let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+            run_runtime_command(runtime, &args)
+                .await
+                .context("failed to build dev container image")?;
+            image
+        } else if let Some(image) = &config.image {
+            image.clone()
+        } else {
+            anyhow::bail!(
+                "{} must specify either an `image` or a `build`",
+                devcontainer.display()
+            );
+        };
+
+        delegate.set_status(Some("Creating dev container"), cx);
+        let workspace_folder = config.container_workspace_folder(devcontainer);
+        let mut args = vec![
+            "run".to_string(),
+            "--detach".to_string(),
+            "--name".to_string(),
+            container.to_string(),
+            "--label".to_string(),
+            format!("{DEVCONTAINER_LABEL}={}", devcontainer.display()),
+            "--mount".to_string(),
+            format!(
+                "type=bind,source={},target={}",
+                devcontainer_workspace_folder(devcontainer).display(),
+                workspace_folder
+            ),
+            "--workdir".to_string(),
+            workspace_folder.clone(),
+        ];
+        for (name, value) in &config.container_env {
+            args.push("--env".to_string());
+            args.push(format!("{name}={value}"));
+        }
+        for mount in &config.mounts {
+            if let Some(mount) = mount.as_str() {
+                args.push("--mount".to_string());
+                args.push(mount.to_string());
+            }
+        }
+        if let Some(user) = &config.container_user {
+            args.push("--user".to_string());
+            args.push(user.clone());
+        }
+        args.extend(config.run_args.iter().cloned());
+        // Keep the container running regardless of the image's entrypoint.
+        args.extend([
+            "--entrypoint".to_string(),
+            "/bin/sh".to_string(),
+            image,
+            "-c".to_string(),
+            "trap 'exit 0' TERM; while sleep 1000 & wait $!; do :; done".to_string(),
+        ]);
+
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        run_runtime_command(runtime, &args)
+            .await
+            .context("failed to create dev container")?;
+
+        if let Some(command) = &config.post_create_command {
+            delegate.set_status(Some("Running postCreateCommand"), cx);
+            let user = config.remote_user.as_deref();
+            Self::run_lifecycle_command(runtime, container, user, &workspace_folder, command)
+                .await
+                .context("postCreateCommand failed")?;
+        }
+
+        Ok(())
+    }
+
+    async fn run_lifecycle_command(
+        runtime: ContainerRuntime,
+        container: &str,
+        user: Option<&str>,
+        workdir: &str,
+        command: &DevContainerCommand,
+    ) -> Result<()> {
+        let mut args = vec!["exec", "--workdir", workdir];
+        if let Some(user) = user {
+            args.extend(["--user", user]);
+        }
+        args.push(container);
+
+        match command {
+            DevContainerCommand::Shell(script) => {
+                args.extend(["sh", "-c", script]);
+                run_runtime_command(runtime, &args).await?;
+            }
+            DevContainerCommand::Args(command) => {
+                args.extend(command.iter().map(String::as_str));
+                run_runtime_command(runtime, &args).await?;
+            }
+            DevContainerCommand::Parallel(commands) => {
+                for command in commands.values() {
+                    Box::pin(Self::run_lifecycle_command(
+                        runtime, container, user, workdir, command,
+                    ))
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `script` with `sh` inside the container, from the user's home directory.
+    fn exec_command(&self, script: &str) -> process::Command {
+        let mut command = runtime_command(self.runtime);
+        command.args(["exec", "-i"]);
+        if let Some(user) = &self.user {
+            command.args(["-u", user]);
+        }
+        command
+            .arg(&self.connection_options.host)
+            .args(["sh", "-c", &format!("cd; {script}")]);
+        log::debug!("{} exec {:?}", self.runtime.program(), script);
+        command
+    }
+
+    async fn run_script(&self, script: &str) -> Result<String> {
+        let output = self.exec_command(script).output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(anyhow!(
+                "failed to run command: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+
+    async fn platform(&self) -> Result<SshPlatform> {
+        let uname = self.run_script("uname -sm").await?;
+        parse_platform(&uname)
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn SshClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: SemanticVersion,
+        commit: Option<AppCommitSha>,
+        cx: &mut AsyncApp,
+    ) -> Result<PathBuf> {
+        let dst_path = remote_server_binary_path(release_channel, version, commit);
+
+        #[cfg(debug_assertions)]
+        if std::env::var("ZED_BUILD_REMOTE_SERVER").is_ok() {
+            let src_path =
+                SshRemoteConnection::build_local(self.platform().await?, delegate, cx).await?;
+            self.upload_server_binary(&src_path, &dst_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        let version_script = shell_script!(
+            "{binary_path} version",
+            binary_path = &dst_path.to_string_lossy()
+        );
+        if self.run_script(&version_script).await.is_ok() {
+            return Ok(dst_path);
+        }
+
+        let wanted_version = cx.update(|cx| match release_channel {
+            ReleaseChannel::Nightly => Ok(None),
+            ReleaseChannel::Dev => {
+                anyhow::bail!(
+                    "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
+                    dst_path
+                )
+            }
+            _ => Ok(Some(AppVersion::global(cx))),
+        })??;
+
+        // Containers often lack curl or wget, so the binary is always downloaded
+        // locally and copied in.
+        let platform = self.platform().await?;
+        let src_path = delegate
+            .download_server_binary_locally(platform, release_channel, wanted_version, cx)
+            .await?;
+        self.upload_server_binary(&src_path, &dst_path, delegate, cx)
+            .await?;
+        Ok(dst_path)
+    }
+
+    async fn upload_server_binary(
+        &self,
+        src_path: &Path,
+        dst_path: &Path,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let tmp_path_gz = PathBuf::from(format!(
+            "/tmp/zed-remote-server-download-{}.gz",
+            std::process::id()
+        ));
+
+        let t0 = Instant::now();
+        delegate.set_status(Some("Uploading remote development server"), cx);
+        log::info!("uploading remote development server to {:?}", tmp_path_gz);
+        run_runtime_command(
+            self.runtime,
+            &[
+                "cp",
+                &src_path.to_string_lossy(),
+                &format!("{}:{}", self.connection_options.host, tmp_path_gz.display()),
+            ],
+        )
+        .await
+        .context("failed to upload server binary")?;
+        log::info!("uploaded remote development server in {:?}", t0.elapsed());
+
+        delegate.set_status(Some("Extracting remote development server"), cx);
+        let dst_dir = dst_path.parent().unwrap_or(Path::new("."));
+        let tmp_path = format!("{}.tmp", dst_path.to_string_lossy());
+        let script = shell_script!(
+            "mkdir -p {dst_dir} && gunzip -c {tmp_path_gz} > {tmp_path} && chmod 755 {tmp_path} && mv {tmp_path} {dst_path}; status=$?; rm -f {tmp_path_gz}; exit $status",
+            dst_dir = &dst_dir.to_string_lossy(),
+            tmp_path_gz = &tmp_path_gz.to_string_lossy(),
+            tmp_path = &tmp_path,
+            dst_path = &dst_path.to_string_lossy(),
+        );
+        self.run_script(&script).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_container_command_line() {
+        let options =
+            SshConnectionOptions::parse_command_line("docker exec -u dev my-box").unwrap();
+        assert_eq!(options.host, "my-box");
+        assert_eq!(options.username.as_deref(), Some("dev"));
+        assert_eq!(
+            options.container,
+            Some(ContainerOptions {
+                runtime: ContainerRuntime::Docker,
+                devcontainer: None,
+            })
+        );
+        assert_eq!(options.connection_string(), "docker://dev@my-box");
+
+        let options = SshConnectionOptions::parse_command_line(
+            "podman --devcontainer '/src/My App/.devcontainer/devcontainer.json'",
+        )
+        .unwrap();
+        assert_eq!(options.host, "zed-my-app");
+        assert_eq!(
+            options.container,
+            Some(ContainerOptions {
+                runtime: ContainerRuntime::Podman,
+                devcontainer: Some(PathBuf::from("/src/My App/.devcontainer/devcontainer.json")),
+            })
+        );
+
+        assert!(SshConnectionOptions::parse_command_line("docker exec").is_err());
+        assert!(SshConnectionOptions::parse_command_line("docker exec a b").is_err());
+        assert!(
+            SshConnectionOptions::parse_command_line("user@docker")
+                .unwrap()
+                .container
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_devcontainer_workspace_folder() {
+        assert_eq!(
+            devcontainer_workspace_folder(Path::new("/src/app/.devcontainer/devcontainer.json")),
+            Path::new("/src/app")
+        );
+        assert_eq!(
+            devcontainer_workspace_folder(Path::new("/src/app/.devcontainer.json")),
+            Path::new("/src/app")
+        );
+    }
+}
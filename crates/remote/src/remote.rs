@@ -1,8 +1,12 @@
+pub mod command_session;
+pub mod container_session;
 pub mod json_log;
 pub mod protocol;
 pub mod proxy;
 pub mod ssh_session;
 
+pub use command_session::CommandOptions;
+pub use container_session::{ContainerOptions, ContainerRuntime};
 pub use ssh_session::{
     ConnectionState, SshClientDelegate, SshConnectionOptions, SshPlatform, SshRemoteClient,
     SshRemoteEvent,
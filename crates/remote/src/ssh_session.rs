@@ -1,4 +1,6 @@
 use crate::{
+    command_session::{CommandOptions, CommandRemoteConnection},
+    container_session::{ContainerOptions, ContainerRemoteConnection},
     json_log::LogRecord,
     protocol::{
         MESSAGE_LEN_SIZE, MessageId, message_len_from_buffer, read_message_with_len, write_message,
@@ -83,6 +85,10 @@ pub struct SshConnectionOptions {
 
     pub nickname: Option<String>,
     pub upload_binary_over_ssh: bool,
+    /// When set, `host` names a local container to exec into instead of an SSH host.
+    pub container: Option<ContainerOptions>,
+    /// When set, the host is reached by running these commands instead of `ssh`.
+    pub command: Option<CommandOptions>,
 }
 
 #[macro_export]
@@ -135,6 +141,10 @@ fn parse_port_forward_spec(spec: &str) -> Result<SshPortForwardOption> {
 
 impl SshConnectionOptions {
     pub fn parse_command_line(input: &str) -> Result<Self> {
+        if let Some(options) = Self::parse_container_command_line(input)? {
+            return Ok(options);
+        }
+
         let input = input.trim_start_matches("ssh ");
         let mut hostname: Option<String> = None;
         let mut username: Option<String> = None;
@@ -234,6 +244,8 @@ impl SshConnectionOptions {
             password: None,
             nickname: None,
             upload_binary_over_ssh: false,
+            container: None,
+            command: None,
         })
     }
 
@@ -291,6 +303,9 @@ impl SshConnectionOptions {
         } else {
             self.host.clone()
         };
+        if let Some(container) = &self.container {
+            return format!("{}://{}", container.runtime.program(), host);
+        }
         if let Some(port) = &self.port {
             format!("{}:{}", host, port)
         } else {
@@ -1076,6 +1091,14 @@ impl SshRemoteClient {
             .map(|ssh_connection| ssh_connection.ssh_args())
     }
 
+    pub fn exec_program(&self) -> Option<String> {
+        self.state
+            .lock()
+            .as_ref()
+            .and_then(|state| state.ssh_connection())
+            .and_then(|ssh_connection| ssh_connection.exec_program())
+    }
+
     pub fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -1240,9 +1263,19 @@ impl ConnectionPool {
                 let opts = opts.clone();
                 let delegate = delegate.clone();
                 async move |cx| {
-                    let connection = SshRemoteConnection::new(opts.clone(), delegate, cx)
-                        .await
-                        .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>);
+                    let connection = if opts.container.is_some() {
+                        ContainerRemoteConnection::new(opts.clone(), delegate, cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                    } else if opts.command.is_some() {
+                        CommandRemoteConnection::new(opts.clone(), delegate, cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                    } else {
+                        SshRemoteConnection::new(opts.clone(), delegate, cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                    };
 
                     cx.update_global(|pool: &mut Self, _| {
                         debug_assert!(matches!(
@@ -1280,7 +1313,7 @@ impl From<SshRemoteClient> for AnyProtoClient {
 }
 
 #[async_trait(?Send)]
-trait RemoteConnection: Send + Sync {
+pub(crate) trait RemoteConnection: Send + Sync {
     fn start_proxy(
         &self,
         unique_identifier: String,
@@ -1296,6 +1329,9 @@ trait RemoteConnection: Send + Sync {
     async fn kill(&self) -> Result<()>;
     fn has_been_killed(&self) -> bool;
     fn ssh_args(&self) -> Vec<String>;
+    /// The program that `ssh_args` are passed to when opening a terminal on the
+    /// remote host, if it isn't `ssh`.
+    fn exec_program(&self) -> Option<String>;
     fn connection_options(&self) -> SshConnectionOptions;
 
     #[cfg(any(test, feature = "test-support"))]
@@ -1328,6 +1364,10 @@ impl RemoteConnection for SshRemoteConnection {
         self.socket.ssh_args()
     }
 
+    fn exec_program(&self) -> Option<String> {
+        None
+    }
+
     fn connection_options(&self) -> SshConnectionOptions {
         self.socket.connection_options.clone()
     }
@@ -1391,29 +1431,7 @@ impl RemoteConnection for SshRemoteConnection {
             return Task::ready(Err(anyhow!("Remote binary path not set")));
         };
 
-        let mut start_proxy_command = shell_script!(
-            "exec {binary_path} proxy --identifier {identifier}",
-            binary_path = &remote_binary_path.to_string_lossy(),
-            identifier = &unique_identifier,
-        );
-
-        if let Some(rust_log) = std::env::var("RUST_LOG").ok() {
-            start_proxy_command = format!(
-                "RUST_LOG={} {}",
-                shlex::try_quote(&rust_log).unwrap(),
-                start_proxy_command
-            )
-        }
-        if let Some(rust_backtrace) = std::env::var("RUST_BACKTRACE").ok() {
-            start_proxy_command = format!(
-                "RUST_BACKTRACE={} {}",
-                shlex::try_quote(&rust_backtrace).unwrap(),
-                start_proxy_command
-            )
-        }
-        if reconnect {
-            start_proxy_command.push_str(" --reconnect");
-        }
+        let start_proxy_command = proxy_command(&remote_binary_path, &unique_identifier, reconnect);
 
         let ssh_proxy_process = match self
             .socket
@@ -1438,6 +1456,94 @@ impl RemoteConnection for SshRemoteConnection {
     }
 }
 
+/// Builds the shell command that starts the remote server's proxy, which relays
+/// messages between its stdio and the server process.
+pub(crate) fn proxy_command(
+    remote_binary_path: &Path,
+    unique_identifier: &str,
+    reconnect: bool,
+) -> String {
+    let mut start_proxy_command = shell_script!(
+        "exec {binary_path} proxy --identifier {identifier}",
+        binary_path = &remote_binary_path.to_string_lossy(),
+        identifier = unique_identifier,
+    );
+
+    if let Some(rust_log) = std::env::var("RUST_LOG").ok() {
+        start_proxy_command = format!(
+            "RUST_LOG={} {}",
+            shlex::try_quote(&rust_log).unwrap(),
+            start_proxy_command
+        )
+    }
+    if let Some(rust_backtrace) = std::env::var("RUST_BACKTRACE").ok() {
+        start_proxy_command = format!(
+            "RUST_BACKTRACE={} {}",
+            shlex::try_quote(&rust_backtrace).unwrap(),
+            start_proxy_command
+        )
+    }
+    if reconnect {
+        start_proxy_command.push_str(" --reconnect");
+    }
+    start_proxy_command
+}
+
+/// Parses the output of `uname -sm` on the remote host.
+pub(crate) fn parse_platform(uname: &str) -> Result<SshPlatform> {
+    let Some((os, arch)) = uname.split_once(" ") else {
+        Err(anyhow!("unknown uname: {uname:?}"))?
+    };
+
+    let os = match os.trim() {
+        "Darwin" => "macos",
+        "Linux" => "linux",
+        _ => Err(anyhow!(
+            "Prebuilt remote servers are not yet available for {os:?}. See https://zed.dev/docs/remote-development"
+        ))?,
+    };
+    // exclude armv5,6,7 as they are 32-bit.
+    let arch = if arch.starts_with("armv8")
+        || arch.starts_with("armv9")
+        || arch.starts_with("arm64")
+        || arch.starts_with("aarch64")
+    {
+        "aarch64"
+    } else if arch.starts_with("x86") {
+        "x86_64"
+    } else {
+        Err(anyhow!(
+            "Prebuilt remote servers are not yet available for {arch:?}. See https://zed.dev/docs/remote-development"
+        ))?
+    };
+
+    Ok(SshPlatform { os, arch })
+}
+
+/// The path of the remote server binary for this version of Zed, relative to
+/// the home directory on the remote host.
+pub(crate) fn remote_server_binary_path(
+    release_channel: ReleaseChannel,
+    version: SemanticVersion,
+    commit: Option<AppCommitSha>,
+) -> PathBuf {
+    let version_str = match release_channel {
+        ReleaseChannel::Nightly => {
+            let commit = commit.map(|s| s.0.to_string()).unwrap_or_default();
+
+            format!("{}-{}", version, commit)
+        }
+        ReleaseChannel::Dev => "build".to_string(),
+        _ => version.to_string(),
+    };
+    let binary_name = format!(
+        "zed-remote-server-{}-{}",
+        release_channel.dev_name(),
+        version_str
+    );
+    paths::remote_server_dir_relative().join(binary_name)
+}
+
 impl SshRemoteConnection {
     #[cfg(not(unix))]
     async fn new(
@@ -1564,36 +1670,10 @@ impl SshRemoteConnection {
 
     async fn platform(&self) -> Result<SshPlatform> {
         let uname = self.socket.run_command("sh", &["-c", "uname -sm"]).await?;
-        let Some((os, arch)) = uname.split_once(" ") else {
-            Err(anyhow!("unknown uname: {uname:?}"))?
-        };
-
-        let os = match os.trim() {
-            "Darwin" => "macos",
-            "Linux" => "linux",
-            _ => Err(anyhow!(
-                "Prebuilt remote servers are not yet available for {os:?}. See https://zed.dev/docs/remote-development"
-            ))?,
-        };
-        // exclude armv5,6,7 as they are 32-bit.
-        let arch = if arch.starts_with("armv8")
-            || arch.starts_with("armv9")
-            || arch.starts_with("arm64")
-            || arch.starts_with("aarch64")
-        {
-            "aarch64"
-        } else if arch.starts_with("x86") {
-            "x86_64"
-        } else {
-            Err(anyhow!(
-                "Prebuilt remote servers are not yet available for {arch:?}. See https://zed.dev/docs/remote-development"
-            ))?
-        };
-
-        Ok(SshPlatform { os, arch })
+        parse_platform(&uname)
     }
 
-    fn multiplex(
+    pub(crate) fn multiplex(
         mut ssh_proxy_process: Child,
         incoming_tx: UnboundedSender<Envelope>,
         mut outgoing_rx: UnboundedReceiver<Envelope>,
@@ -1704,21 +1784,7 @@ impl SshRemoteConnection {
         commit: Option<AppCommitSha>,
         cx: &mut AsyncApp,
     ) -> Result<PathBuf> {
-        let version_str = match release_channel {
-            ReleaseChannel::Nightly => {
-                let commit = commit.map(|s| s.0.to_string()).unwrap_or_default();
-
-                format!("{}-{}", version, commit)
-            }
-            ReleaseChannel::Dev => "build".to_string(),
-            _ => version.to_string(),
-        };
-        let binary_name = format!(
-            "zed-remote-server-{}-{}",
-            release_channel.dev_name(),
-            version_str
-        );
-        let dst_path = paths::remote_server_dir_relative().join(binary_name);
+        let dst_path = remote_server_binary_path(release_channel, version, commit);
         let tmp_path_gz = PathBuf::from(format!(
             "{}-download-{}.gz",
             dst_path.to_string_lossy(),
@@ -1727,9 +1793,7 @@ impl SshRemoteConnection {
 
         #[cfg(debug_assertions)]
         if std::env::var("ZED_BUILD_REMOTE_SERVER").is_ok() {
-            let src_path = self
-                .build_local(self.platform().await?, delegate, cx)
-                .await?;
+            let src_path = Self::build_local(self.platform().await?, delegate, cx).await?;
             self.upload_local_server_binary(&src_path, &tmp_path_gz, delegate, cx)
                 .await?;
             self.extract_server_binary(&dst_path, &tmp_path_gz, delegate, cx)
@@ -1952,8 +2016,7 @@ impl SshRemoteConnection {
     }
 
     #[cfg(debug_assertions)]
-    async fn build_local(
-        &self,
+    pub(crate) async fn build_local(
         platform: SshPlatform,
         delegate: &Arc<dyn SshClientDelegate>,
         cx: &mut AsyncApp,
@@ -2423,6 +2486,10 @@ mod fake {
         fn ssh_args(&self) -> Vec<String> {
             Vec::new()
         }
+
+        fn exec_program(&self) -> Option<String> {
+            None
+        }
         fn upload_directory(
             &self,
             _src_path: PathBuf,
@@ -1548,6 +1548,103 @@ async fn test_remote_git_branches(cx: &mut TestAppContext, server_cx: &mut TestA
     assert_eq!(server_branch.name(), "totally-new-branch");
 }
 
+#[gpui::test]
+async fn test_remote_git_worktrees(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    fs.insert_branches(Path::new(path!("/code/project1/.git")), &["main", "dev"]);
+
+    let (_worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let repository = project.update(cx, |project, cx| project.active_repository(cx).unwrap());
+
+    cx.update(|cx| {
+        repository.update(cx, |repository, _| {
+            repository.create_worktree(
+                "dev".to_string(),
+                PathBuf::from(path!("/code/project1-dev")),
+                false,
+            )
+        })
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    cx.update(|cx| {
+        repository.update(cx, |repository, _| {
+            repository.create_worktree(
+                "totally-new-branch".to_string(),
+                PathBuf::from(path!("/code/project1-new")),
+                true,
+            )
+        })
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    let worktrees = repository
+        .update(cx, |repository, _| repository.worktrees())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        worktrees
+            .iter()
+            .map(|worktree| (worktree.path.clone(), worktree.is_main))
+            .collect::<Vec<_>>(),
+        vec![
+            (PathBuf::from(path!("/code/project1")), true),
+            (PathBuf::from(path!("/code/project1-dev")), false),
+            (PathBuf::from(path!("/code/project1-new")), false),
+        ]
+    );
+    assert_eq!(worktrees[2].branch_name(), Some("totally-new-branch"));
+
+    cx.update(|cx| {
+        repository.update(cx, |repository, _| {
+            repository.remove_worktree(PathBuf::from(path!("/code/project1-dev")), false)
+        })
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    let worktrees = repository
+        .update(cx, |repository, _| repository.worktrees())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        worktrees
+            .iter()
+            .map(|worktree| worktree.path.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            PathBuf::from(path!("/code/project1")),
+            PathBuf::from(path!("/code/project1-new")),
+        ]
+    );
+}
+
 pub async fn init_test(
     server_fs: &Arc<FakeFs>,
     cx: &mut TestAppContext,
@@ -1,22 +1,25 @@
 #![allow(unused, dead_code)]
 use std::sync::Arc;
 
-use editor::{Editor, EditorMode, MultiBuffer};
+use anyhow::Result;
+use editor::{Editor, EditorEvent, EditorMode, MultiBuffer};
 use futures::future::Shared;
 use gpui::{
-    App, Entity, Hsla, RetainAllImageCache, Task, TextStyleRefinement, image_cache, prelude::*,
+    App, Entity, EventEmitter, Focusable, Hsla, MouseButton, MouseDownEvent, RetainAllImageCache,
+    Subscription, Task, TextStyleRefinement, WeakEntity, image_cache, prelude::*,
 };
 use language::{Buffer, Language, LanguageRegistry};
 use markdown_preview::{markdown_parser::parse_markdown, markdown_renderer::render_markdown_block};
 use nbformat::v4::{CellId, CellMetadata, CellType};
 use settings::Settings as _;
 use theme::ThemeSettings;
-use ui::{IconButtonShape, prelude::*};
+use ui::{IconButtonShape, Tooltip, prelude::*};
 use util::ResultExt;
+use workspace::Workspace;
 
 use crate::{
     notebook::{CODE_BLOCK_INSET, GUTTER_WIDTH},
-    outputs::{Output, plain::TerminalOutput, user_error::ErrorView},
+    outputs::{ExecutionStatus, ExecutionView},
 };
 
 #[derive(Copy, Clone, PartialEq, PartialOrd)]
@@ -85,33 +88,91 @@ pub enum Cell {
     Raw(Entity<RawCell>),
 }
 
-fn convert_outputs(
-    outputs: &Vec<nbformat::v4::Output>,
-    window: &mut Window,
-    cx: &mut App,
-) -> Vec<Output> {
-    outputs
-        .into_iter()
-        .map(|output| match output {
-            nbformat::v4::Output::Stream { text, .. } => Output::Stream {
-                content: cx.new(|cx| TerminalOutput::from(&text.0, window, cx)),
-            },
-            nbformat::v4::Output::DisplayData(display_data) => {
-                Output::new(&display_data.data, None, window, cx)
-            }
-            nbformat::v4::Output::ExecuteResult(execute_result) => {
-                Output::new(&execute_result.data, None, window, cx)
-            }
-            nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
-                ename: error.ename.clone(),
-                evalue: error.evalue.clone(),
-                traceback: cx
-                    .new(|cx| TerminalOutput::from(&error.traceback.join("\n"), window, cx)),
-            }),
-        })
+pub enum CellEvent {
+    Run,
+    OutputsCleared,
+}
+
+/// Creates an empty notebook cell of the given type.
+///
+/// This goes through the notebook format so that new cells get the same defaults as cells that
+/// were loaded from disk.
+pub fn new_notebook_cell(cell_type: CellType) -> Result<nbformat::v4::Cell> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let cell = match cell_type {
+        CellType::Code => serde_json::json!({
+            "cell_type": "code",
+            "id": id,
+            "metadata": {},
+            "execution_count": null,
+            "source": [],
+            "outputs": [],
+        }),
+        CellType::Markdown => serde_json::json!({
+            "cell_type": "markdown",
+            "id": id,
+            "metadata": {},
+            "source": [],
+        }),
+        CellType::Raw => serde_json::json!({
+            "cell_type": "raw",
+            "id": id,
+            "metadata": {},
+            "source": [],
+        }),
+    };
+    Ok(serde_json::from_value(cell)?)
+}
+
+/// Splits a cell's source into the lines it's stored as in notebooks.
+fn source_lines(source: &str) -> Vec<String> {
+    source
+        .split_inclusive('\n')
+        .map(ToString::to_string)
         .collect()
 }
 
+fn cell_editor(text: &str, window: &mut Window, cx: &mut App) -> (Entity<Buffer>, Entity<Editor>) {
+    let buffer = cx.new(|cx| Buffer::local(text, cx));
+    let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+
+    let editor = cx.new(|cx| {
+        let mut editor = Editor::new(
+            EditorMode::AutoHeight { max_lines: 1024 },
+            multi_buffer,
+            None,
+            window,
+            cx,
+        );
+
+        let theme = ThemeSettings::get_global(cx);
+
+        let refinement = TextStyleRefinement {
+            font_family: Some(theme.buffer_font.family.clone()),
+            font_size: Some(theme.buffer_font_size(cx).into()),
+            color: Some(cx.theme().colors().editor_foreground),
+            background_color: Some(gpui::transparent_black()),
+            ..Default::default()
+        };
+
+        editor.set_show_gutter(false, cx);
+        editor.set_text_style_refinement(refinement);
+        editor
+    });
+
+    (buffer, editor)
+}
+
+/// Marks the contents of a cell editor as saved, so that it's no longer dirty.
+fn did_save_editor(editor: &Entity<Editor>, cx: &mut App) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    buffer.update(cx, |buffer, cx| {
+        buffer.did_save(buffer.version(), None, cx);
+    });
+}
+
 impl Cell {
     pub fn load(
         cell: &nbformat::v4::Cell,
@@ -125,44 +186,18 @@ impl Cell {
                 id,
                 metadata,
                 source,
-                ..
-            } => {
-                let source = source.join("");
-
-                let entity = cx.new(|cx| {
-                    let markdown_parsing_task = {
-                        let languages = languages.clone();
-                        let source = source.clone();
-
-                        cx.spawn_in(window, async move |this, cx| {
-                            let parsed_markdown = cx
-                                .background_spawn(async move {
-                                    parse_markdown(&source, None, Some(languages)).await
-                                })
-                                .await;
-
-                            this.update(cx, |cell: &mut MarkdownCell, _| {
-                                cell.parsed_markdown = Some(parsed_markdown);
-                            })
-                            .log_err();
-                        })
-                    };
-
-                    MarkdownCell {
-                        markdown_parsing_task,
-                        image_cache: RetainAllImageCache::new(cx),
-                        languages: languages.clone(),
-                        id: id.clone(),
-                        metadata: metadata.clone(),
-                        source: source.clone(),
-                        parsed_markdown: None,
-                        selected: false,
-                        cell_position: None,
-                    }
-                });
-
-                Cell::Markdown(entity)
-            }
+                attachments,
+            } => Cell::Markdown(cx.new(|cx| {
+                MarkdownCell::new(
+                    id.clone(),
+                    metadata.clone(),
+                    attachments.clone(),
+                    source.join(""),
+                    languages,
+                    window,
+                    cx,
+                )
+            })),
             nbformat::v4::Cell::Code {
                 id,
                 metadata,
@@ -170,57 +205,48 @@ impl Cell {
                 source,
                 outputs,
             } => Cell::Code(cx.new(|cx| {
-                let text = source.join("");
-
-                let buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
-                let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
-
-                let editor_view = cx.new(|cx| {
-                    let mut editor = Editor::new(
-                        EditorMode::AutoHeight { max_lines: 1024 },
-                        multi_buffer,
-                        None,
-                        window,
-                        cx,
-                    );
-
-                    let theme = ThemeSettings::get_global(cx);
-
-                    let refinement = TextStyleRefinement {
-                        font_family: Some(theme.buffer_font.family.clone()),
-                        font_size: Some(theme.buffer_font_size(cx).into()),
-                        color: Some(cx.theme().colors().editor_foreground),
-                        background_color: Some(gpui::transparent_black()),
-                        ..Default::default()
-                    };
-
-                    editor.set_text(text, window, cx);
-                    editor.set_show_gutter(false, cx);
-                    editor.set_text_style_refinement(refinement);
-
-                    // editor.set_read_only(true);
-                    editor
-                });
+                let (buffer, editor) = cell_editor(&source.join(""), window, cx);
 
-                let buffer = buffer.clone();
                 let language_task = cx.spawn_in(window, async move |this, cx| {
                     let language = notebook_language.await;
 
-                    buffer.update(cx, |buffer, cx| {
-                        buffer.set_language(language.clone(), cx);
-                    });
+                    buffer
+                        .update(cx, |buffer, cx| {
+                            buffer.set_language(language.clone(), cx);
+                        })
+                        .ok();
                 });
 
+                let execution_view = cx.new(|cx| {
+                    let mut execution_view =
+                        ExecutionView::new(ExecutionStatus::Unknown, WeakEntity::new_invalid(), cx);
+                    for output in outputs {
+                        execution_view.push_notebook_output(output, window, cx);
+                    }
+                    execution_view
+                });
+
+                let execution_view_subscription = cx.observe(
+                    &execution_view,
+                    |cell: &mut CodeCell, execution_view, cx| {
+                        if let Some(execution_count) = execution_view.read(cx).execution_count {
+                            cell.set_execution_count(execution_count);
+                        }
+                        cx.notify();
+                    },
+                );
+
                 CodeCell {
                     id: id.clone(),
                     metadata: metadata.clone(),
                     execution_count: *execution_count,
                     source: source.join(""),
-                    editor: editor_view,
-                    outputs: convert_outputs(outputs, window, cx),
+                    editor,
+                    execution_view,
                     selected: false,
                     language_task,
                     cell_position: None,
+                    _execution_view_subscription: execution_view_subscription,
                 }
             })),
             nbformat::v4::Cell::Raw {
@@ -236,6 +262,63 @@ impl Cell {
             })),
         }
     }
+
+    pub fn id(&self, cx: &App) -> CellId {
+        match self {
+            Cell::Code(cell) => cell.read(cx).id().clone(),
+            Cell::Markdown(cell) => cell.read(cx).id().clone(),
+            Cell::Raw(cell) => cell.read(cx).id().clone(),
+        }
+    }
+
+    pub fn is_dirty(&self, cx: &App) -> bool {
+        match self {
+            Cell::Code(cell) => cell.read(cx).is_dirty(cx),
+            Cell::Markdown(cell) => cell.read(cx).is_dirty(cx),
+            Cell::Raw(_) => false,
+        }
+    }
+
+    pub fn did_save(&self, cx: &mut App) {
+        match self {
+            Cell::Code(cell) => did_save_editor(&cell.read(cx).editor.clone(), cx),
+            Cell::Markdown(cell) => did_save_editor(&cell.read(cx).editor.clone(), cx),
+            Cell::Raw(_) => {}
+        }
+    }
+
+    /// Converts the cell back into the notebook format, for saving
+    pub fn to_nbformat(&self, cx: &App) -> nbformat::v4::Cell {
+        match self {
+            Cell::Code(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Code {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    execution_count: cell.execution_count,
+                    source: source_lines(&cell.text(cx)),
+                    outputs: cell.execution_view.read(cx).notebook_outputs(),
+                }
+            }
+            Cell::Markdown(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Markdown {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.text(cx)),
+                    attachments: cell.attachments.clone(),
+                }
+            }
+            Cell::Raw(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Raw {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.source),
+                }
+            }
+        }
+    }
 }
 
 pub trait RenderableCell: Render {
@@ -332,13 +415,130 @@ pub trait RunnableCell: RenderableCell {
 pub struct MarkdownCell {
     id: CellId,
     metadata: CellMetadata,
+    attachments: Option<serde_json::Value>,
     image_cache: Entity<RetainAllImageCache>,
     source: String,
     parsed_markdown: Option<markdown_preview::markdown_elements::ParsedMarkdown>,
     markdown_parsing_task: Task<()>,
+    editor: Entity<Editor>,
+    editing: bool,
     selected: bool,
     cell_position: Option<CellPosition>,
     languages: Arc<LanguageRegistry>,
+    language_task: Task<()>,
+    _editor_subscription: Subscription,
+}
+
+impl MarkdownCell {
+    fn new(
+        id: CellId,
+        metadata: CellMetadata,
+        attachments: Option<serde_json::Value>,
+        source: String,
+        languages: &Arc<LanguageRegistry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let (buffer, editor) = cell_editor(&source, window, cx);
+
+        let language_task = {
+            let languages = languages.clone();
+            cx.spawn_in(window, async move |_, cx| {
+                let language = languages.language_for_name("Markdown").await.ok();
+
+                buffer
+                    .update(cx, |buffer, cx| {
+                        buffer.set_language(language, cx);
+                    })
+                    .ok();
+            })
+        };
+
+        let editor_subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |cell, _, event: &EditorEvent, window, cx| {
+                if let EditorEvent::Blurred = event {
+                    cell.stop_editing(window, cx);
+                }
+            },
+        );
+
+        let mut cell = Self {
+            id,
+            metadata,
+            attachments,
+            image_cache: RetainAllImageCache::new(cx),
+            source,
+            parsed_markdown: None,
+            markdown_parsing_task: Task::ready(()),
+            editor,
+            editing: false,
+            selected: false,
+            cell_position: None,
+            languages: languages.clone(),
+            language_task,
+            _editor_subscription: editor_subscription,
+        };
+        cell.parse_markdown(window, cx);
+        cell
+    }
+
+    fn parse_markdown(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let languages = self.languages.clone();
+        let source = self.source.clone();
+
+        self.markdown_parsing_task = cx.spawn_in(window, async move |this, cx| {
+            let parsed_markdown = cx
+                .background_spawn(
+                    async move { parse_markdown(&source, None, Some(languages)).await },
+                )
+                .await;
+
+            this.update(cx, |cell: &mut MarkdownCell, cx| {
+                cell.parsed_markdown = Some(parsed_markdown);
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    pub fn editor(&self) -> &Entity<Editor> {
+        &self.editor
+    }
+
+    pub fn text(&self, cx: &App) -> String {
+        self.editor.read(cx).text(cx)
+    }
+
+    pub fn is_dirty(&self, cx: &App) -> bool {
+        self.editor.read(cx).buffer().read(cx).is_dirty(cx)
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    pub fn start_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing = true;
+        window.focus(&self.editor.focus_handle(cx));
+        cx.notify();
+    }
+
+    /// Renders the edited source again
+    pub fn stop_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.editing {
+            return;
+        }
+        self.editing = false;
+
+        let source = self.text(cx);
+        if source != self.source {
+            self.source = source;
+            self.parse_markdown(window, cx);
+        }
+        cx.notify();
+    }
 }
 
 impl RenderableCell for MarkdownCell {
@@ -385,13 +585,62 @@ impl RenderableCell for MarkdownCell {
 
 impl Render for MarkdownCell {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let Some(parsed) = self.parsed_markdown.as_ref() else {
-            return div();
+        let content = if self.editing {
+            div()
+                .py_1p5()
+                .w_full()
+                .child(
+                    div()
+                        .flex()
+                        .size_full()
+                        .flex_1()
+                        .py_3()
+                        .px_5()
+                        .rounded_lg()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(cx.theme().colors().editor_background)
+                        .child(div().w_full().child(self.editor.clone())),
+                )
+                .into_any_element()
+        } else {
+            let mut markdown_render_context =
+                markdown_preview::markdown_renderer::RenderContext::new(None, window, cx);
+            let blocks = self
+                .parsed_markdown
+                .iter()
+                .flat_map(|parsed| parsed.children.iter())
+                .map(|child| {
+                    div().relative().child(
+                        div()
+                            .relative()
+                            .child(render_markdown_block(child, &mut markdown_render_context)),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            v_flex()
+                .image_cache(self.image_cache.clone())
+                .size_full()
+                .flex_1()
+                .p_3()
+                .font_ui(cx)
+                .text_size(TextSize::Default.rems(cx))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|cell, event: &MouseDownEvent, window, cx| {
+                        if event.click_count > 1 {
+                            cell.start_editing(window, cx);
+                        }
+                    }),
+                )
+                .when(self.source.trim().is_empty(), |this| {
+                    this.child(Label::new("Double-click to edit").color(Color::Muted))
+                })
+                .children(blocks)
+                .into_any_element()
         };
 
-        let mut markdown_render_context =
-            markdown_preview::markdown_renderer::RenderContext::new(None, window, cx);
-
         v_flex()
             .size_full()
             // TODO: Move base cell render into trait impl so we don't have to repeat this
@@ -405,20 +654,7 @@ impl Render for MarkdownCell {
                     .gap(DynamicSpacing::Base08.rems(cx))
                     .bg(self.selected_bg_color(window, cx))
                     .child(self.gutter(window, cx))
-                    .child(
-                        v_flex()
-                            .image_cache(self.image_cache.clone())
-                            .size_full()
-                            .flex_1()
-                            .p_3()
-                            .font_ui(cx)
-                            .text_size(TextSize::Default.rems(cx))
-                            .children(parsed.children.iter().map(|child| {
-                                div().relative().child(div().relative().child(
-                                    render_markdown_block(child, &mut markdown_render_context),
-                                ))
-                            })),
-                    ),
+                    .child(content),
             )
             // TODO: Move base cell render into trait impl so we don't have to repeat this
             .children(self.cell_position_spacer(false, window, cx))
@@ -431,26 +667,69 @@ pub struct CodeCell {
     execution_count: Option<i32>,
     source: String,
     editor: Entity<editor::Editor>,
-    outputs: Vec<Output>,
+    execution_view: Entity<ExecutionView>,
     selected: bool,
     cell_position: Option<CellPosition>,
     language_task: Task<()>,
+    _execution_view_subscription: Subscription,
 }
 
+impl EventEmitter<CellEvent> for CodeCell {}
+
 impl CodeCell {
+    pub fn editor(&self) -> &Entity<Editor> {
+        &self.editor
+    }
+
+    pub fn execution_view(&self) -> &Entity<ExecutionView> {
+        &self.execution_view
+    }
+
+    pub fn text(&self, cx: &App) -> String {
+        self.editor.read(cx).text(cx)
+    }
+
     pub fn is_dirty(&self, cx: &App) -> bool {
         self.editor.read(cx).buffer().read(cx).is_dirty(cx)
     }
-    pub fn has_outputs(&self) -> bool {
-        !self.outputs.is_empty()
+
+    pub fn has_outputs(&self, cx: &App) -> bool {
+        !self.execution_view.read(cx).outputs.is_empty()
+    }
+
+    /// Whether the output area should be shown, which is also the case while the cell is
+    /// waiting for the kernel.
+    fn shows_execution_view(&self, cx: &App) -> bool {
+        let execution_view = self.execution_view.read(cx);
+        !execution_view.outputs.is_empty()
+            || !matches!(
+                execution_view.status,
+                ExecutionStatus::Unknown | ExecutionStatus::Finished
+            )
+    }
+
+    pub fn clear_outputs(&mut self, cx: &mut Context<Self>) {
+        if !self.has_outputs(cx) && self.execution_count.is_none() {
+            return;
+        }
+
+        self.execution_view.update(cx, |execution_view, cx| {
+            execution_view.status = ExecutionStatus::Unknown;
+            execution_view.clear(cx);
+        });
+        self.execution_count = None;
+        cx.emit(CellEvent::OutputsCleared);
+        cx.notify();
     }
 
-    pub fn clear_outputs(&mut self) {
-        self.outputs.clear();
+    pub fn set_workspace(&mut self, workspace: WeakEntity<Workspace>, cx: &mut Context<Self>) {
+        self.execution_view.update(cx, |execution_view, _| {
+            execution_view.set_workspace(workspace);
+        });
     }
 
-    fn output_control(&self) -> Option<CellControlType> {
-        if self.has_outputs() {
+    fn output_control(&self, cx: &App) -> Option<CellControlType> {
+        if self.has_outputs(cx) {
             Some(CellControlType::ClearCell)
         } else {
             None
@@ -480,7 +759,7 @@ impl CodeCell {
                             .when(!is_selected, |this| this.bg(cx.theme().colors().border)),
                     ),
             )
-            .when(self.has_outputs(), |this| {
+            .when_some(self.output_control(cx), |this, control_type| {
                 this.child(
                     div()
                         .absolute()
@@ -493,7 +772,12 @@ impl CodeCell {
                         .items_center()
                         .justify_center()
                         .bg(cx.theme().colors().tab_bar_background)
-                        .child(IconButton::new("control", IconName::Ellipsis)),
+                        .child(
+                            CellControl::new("clear-cell-outputs", control_type)
+                                .on_click(cx.listener(|cell, _, _, cx| cell.clear_outputs(cx)))
+                                .button
+                                .tooltip(Tooltip::text("Clear Outputs")),
+                        ),
                 )
             })
     }
@@ -519,14 +803,13 @@ impl RenderableCell for CodeCell {
     }
 
     fn control(&self, window: &mut Window, cx: &mut Context<Self>) -> Option<CellControl> {
-        let cell_control = if self.has_outputs() {
+        let cell_control = if self.has_outputs(cx) {
             CellControl::new("rerun-cell", CellControlType::RerunCell)
         } else {
             CellControl::new("run-cell", CellControlType::RunCell)
-                .on_click(cx.listener(move |this, _, window, cx| this.run(window, cx)))
         };
 
-        Some(cell_control)
+        Some(cell_control.on_click(cx.listener(move |this, _, window, cx| this.run(window, cx))))
     }
 
     fn selected(&self) -> bool {
@@ -550,7 +833,7 @@ impl RenderableCell for CodeCell {
 
 impl RunnableCell for CodeCell {
     fn run(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Running code cell: {}", self.id);
+        cx.emit(CellEvent::Run);
     }
 
     fn execution_count(&self) -> Option<i32> {
@@ -597,69 +880,31 @@ impl Render for CodeCell {
                     ),
             )
             // Output portion
-            .child(
-                h_flex()
-                    .w_full()
-                    .pr_6()
-                    .rounded_xs()
-                    .items_start()
-                    .gap(DynamicSpacing::Base08.rems(cx))
-                    .bg(self.selected_bg_color(window, cx))
-                    .child(self.gutter_output(window, cx))
-                    .child(
-                        div().py_1p5().w_full().child(
-                            div()
-                                .flex()
-                                .size_full()
-                                .flex_1()
-                                .py_3()
-                                .px_5()
-                                .rounded_lg()
-                                .border_1()
-                                // .border_color(cx.theme().colors().border)
-                                // .bg(cx.theme().colors().editor_background)
-                                .child(div().w_full().children(self.outputs.iter().map(
-                                    |output| {
-                                        let content = match output {
-                                            Output::Plain { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Markdown { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Stream { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Image { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Message(message) => Some(
-                                                div().child(message.clone()).into_any_element(),
-                                            ),
-                                            Output::Table { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::ErrorOutput(error_view) => {
-                                                error_view.render(window, cx)
-                                            }
-                                            Output::ClearOutputWaitMarker => None,
-                                        };
-
-                                        div()
-                                            // .w_full()
-                                            // .mt_3()
-                                            // .p_3()
-                                            // .rounded_sm()
-                                            // .bg(cx.theme().colors().editor_background)
-                                            // .border(px(1.))
-                                            // .border_color(cx.theme().colors().border)
-                                            // .shadow_sm()
-                                            .children(content)
-                                    },
-                                ))),
+            .when(self.shows_execution_view(cx), |this| {
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .pr_6()
+                        .rounded_xs()
+                        .items_start()
+                        .gap(DynamicSpacing::Base08.rems(cx))
+                        .bg(self.selected_bg_color(window, cx))
+                        .child(self.gutter_output(window, cx))
+                        .child(
+                            div().py_1p5().w_full().child(
+                                div()
+                                    .flex()
+                                    .size_full()
+                                    .flex_1()
+                                    .py_3()
+                                    .px_5()
+                                    .rounded_lg()
+                                    .border_1()
+                                    .child(div().w_full().child(self.execution_view.clone())),
+                            ),
                         ),
-                    ),
-            )
+                )
+            })
             // TODO: Move base cell render into trait impl so we don't have to repeat this
             .children(self.cell_position_spacer(false, window, cx))
     }
@@ -2,27 +2,35 @@
 use std::future::Future;
 use std::{path::PathBuf, sync::Arc};
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use client::proto::ViewId;
 use collections::HashMap;
+use editor::EditorEvent;
+use editor::actions::{Redo, Undo};
 use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
 use futures::FutureExt;
 use futures::future::Shared;
 use gpui::{
     AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, ListScrollEvent, ListState,
-    Point, Task, actions, list, prelude::*,
+    MouseButton, PathPromptOptions, Point, Subscription, Task, WeakEntity, actions, list,
+    prelude::*,
 };
 use language::{Language, LanguageRegistry};
-use project::{Project, ProjectEntryId, ProjectPath};
+use project::{Project, ProjectEntryId, ProjectPath, WorktreeId};
 use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
 use workspace::item::{ItemEvent, TabContentParams};
 use workspace::searchable::SearchableItemHandle;
-use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
+use workspace::{Item, ItemHandle, OpenOptions, Pane, ProjectItem, ToolbarItemLocation, Workspace};
 use workspace::{ToolbarItemEvent, ToolbarItemView};
 
-use super::{Cell, CellPosition, RenderableCell};
+use super::{Cell, CellEvent, CellPosition, CodeCell, RenderableCell, new_notebook_cell};
+use crate::repl_store::ReplStore;
+use crate::session::SessionEvent;
+use crate::{Interrupt, KernelSpecification, Restart, Session, Shutdown};
 
 use nbformat::v4::CellId;
+use nbformat::v4::CellType;
 use nbformat::v4::Metadata as NotebookMetadata;
 
 actions!(
@@ -35,6 +43,7 @@ actions!(
         MoveCellDown,
         AddMarkdownBlock,
         AddCodeBlock,
+        DeleteCell,
     ]
 );
 
@@ -67,16 +76,27 @@ pub fn init(cx: &mut App) {
 pub struct NotebookEditor {
     languages: Arc<LanguageRegistry>,
     project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
 
     focus_handle: FocusHandle,
     notebook_item: Entity<NotebookItem>,
+    notebook_language: Shared<Task<Option<Arc<Language>>>>,
+    session: Option<Entity<Session>>,
 
     remote_id: Option<ViewId>,
     cell_list: ListState,
 
     selected_cell_index: usize,
     cell_order: Vec<CellId>,
+    /// All cells of the notebook, including the ones that were removed from `cell_order` by
+    /// undoing their insertion, so that they can be restored.
     cell_map: HashMap<CellId, Cell>,
+    /// Previous values of `cell_order`, for undoing cell operations.
+    undo_stack: Vec<Vec<CellId>>,
+    redo_stack: Vec<Vec<CellId>>,
+    saved_cell_order: Vec<CellId>,
+    has_unsaved_outputs: bool,
+    _cell_subscriptions: Vec<Subscription>,
 }
 
 impl NotebookEditor {
@@ -89,38 +109,16 @@ impl NotebookEditor {
         let focus_handle = cx.focus_handle();
 
         let languages = project.read(cx).languages().clone();
-        let language_name = notebook_item.read(cx).language_name();
 
         let notebook_language = notebook_item.read(cx).notebook_language();
         let notebook_language = cx
             .spawn_in(window, async move |_, _| notebook_language.await)
             .shared();
 
-        let mut cell_order = vec![]; // Vec<CellId>
-        let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
-
-        for (index, cell) in notebook_item
-            .read(cx)
-            .notebook
-            .clone()
-            .cells
-            .iter()
-            .enumerate()
-        {
-            let cell_id = cell.id();
-            cell_order.push(cell_id.clone());
-            cell_map.insert(
-                cell_id.clone(),
-                Cell::load(cell, &languages, notebook_language.clone(), window, cx),
-            );
-        }
-
         let notebook_handle = cx.entity().downgrade();
-        let cell_count = cell_order.len();
 
-        let this = cx.entity();
         let cell_list = ListState::new(
-            cell_count,
+            0,
             gpui::ListAlignment::Top,
             px(1000.),
             move |ix, window, cx| {
@@ -143,65 +141,461 @@ impl NotebookEditor {
             },
         );
 
-        Self {
+        let mut this = Self {
             project,
             languages: languages.clone(),
+            workspace: WeakEntity::new_invalid(),
             focus_handle,
             notebook_item,
+            notebook_language,
+            session: None,
             remote_id: None,
             cell_list,
             selected_cell_index: 0,
-            cell_order: cell_order.clone(),
-            cell_map: cell_map.clone(),
+            cell_order: Vec::new(),
+            cell_map: HashMap::default(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            saved_cell_order: Vec::new(),
+            has_unsaved_outputs: false,
+            _cell_subscriptions: Vec::new(),
+        };
+        this.load_cells(window, cx);
+        this
+    }
+
+    /// Replaces all cells with the ones of the notebook item.
+    fn load_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.cell_order.clear();
+        self.cell_map.clear();
+        self._cell_subscriptions.clear();
+
+        let cells = self.notebook_item.read(cx).notebook.cells.clone();
+        for cell in &cells {
+            let cell = Cell::load(
+                cell,
+                &self.languages,
+                self.notebook_language.clone(),
+                window,
+                cx,
+            );
+            self.cell_order.push(cell.id(cx));
+            self.register_cell(cell, window, cx);
         }
+
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.saved_cell_order = self.cell_order.clone();
+        self.has_unsaved_outputs = false;
+        self.selected_cell_index = 0;
+        self.cell_list.reset(self.cell_order.len());
+        cx.notify();
     }
 
-    fn has_outputs(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
-        self.cell_map.values().any(|cell| {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.read(cx).has_outputs()
-            } else {
-                false
+    fn register_cell(&mut self, cell: Cell, window: &mut Window, cx: &mut Context<Self>) {
+        let editor = match &cell {
+            Cell::Code(code_cell) => {
+                let workspace = self.workspace.clone();
+                code_cell.update(cx, |code_cell, cx| code_cell.set_workspace(workspace, cx));
+
+                self._cell_subscriptions.push(cx.subscribe_in(
+                    code_cell,
+                    window,
+                    |this, code_cell, event: &CellEvent, window, cx| match event {
+                        CellEvent::Run => this.run_cell(code_cell.clone(), window, cx),
+                        CellEvent::OutputsCleared => {
+                            this.has_unsaved_outputs = true;
+                            cx.emit(());
+                        }
+                    },
+                ));
+                Some(code_cell.read(cx).editor().clone())
             }
-        })
+            Cell::Markdown(markdown_cell) => Some(markdown_cell.read(cx).editor().clone()),
+            Cell::Raw(_) => None,
+        };
+
+        if let Some(editor) = editor {
+            let cell_id = cell.id(cx);
+            self._cell_subscriptions.push(cx.subscribe_in(
+                &editor,
+                window,
+                move |this, _, event: &EditorEvent, window, cx| match event {
+                    EditorEvent::BufferEdited => cx.emit(()),
+                    EditorEvent::Focused => {
+                        if let Some(index) = this.cell_order.iter().position(|id| id == &cell_id) {
+                            this.set_selected_index(index, false, window, cx);
+                            cx.notify();
+                        }
+                    }
+                    _ => {}
+                },
+            ));
+        }
+
+        self.cell_map.insert(cell.id(cx), cell);
+    }
+
+    fn has_outputs(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        self.cell_order
+            .iter()
+            .filter_map(|cell_id| self.cell_map.get(cell_id))
+            .any(|cell| {
+                if let Cell::Code(code_cell) = cell {
+                    code_cell.read(cx).has_outputs(cx)
+                } else {
+                    false
+                }
+            })
     }
 
     fn clear_outputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         for cell in self.cell_map.values() {
             if let Cell::Code(code_cell) = cell {
-                code_cell.update(cx, |cell, _cx| {
-                    cell.clear_outputs();
+                code_cell.update(cx, |cell, cx| {
+                    cell.clear_outputs(cx);
                 });
             }
         }
     }
 
     fn run_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Cells would all run here, if that was implemented!");
+        let code_cells = self
+            .cell_order
+            .iter()
+            .filter_map(|cell_id| match self.cell_map.get(cell_id) {
+                Some(Cell::Code(code_cell)) => Some(code_cell.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // The kernel executes requests in the order it receives them.
+        for code_cell in code_cells {
+            self.run_cell(code_cell, window, cx);
+        }
+    }
+
+    fn run_cell(
+        &mut self,
+        code_cell: Entity<CodeCell>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let code = code_cell.read(cx).text(cx);
+        if code.trim().is_empty() {
+            return;
+        }
+
+        let Some(session) = self.session(window, cx) else {
+            return;
+        };
+
+        let execution_view = code_cell.read(cx).execution_view().clone();
+        session.update(cx, |session, cx| {
+            session.execute_in_view(code, execution_view, cx);
+        });
+
+        self.has_unsaved_outputs = true;
+        cx.emit(());
+    }
+
+    /// Returns the session that executes the cells, starting one if needed.
+    fn session(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Option<Entity<Session>> {
+        if let Some(session) = self.session.clone() {
+            return Some(session);
+        }
+
+        let store = ReplStore::global(cx);
+        if !store.read(cx).is_enabled() {
+            return None;
+        }
+
+        let notebook_item = self.notebook_item.read(cx);
+        let worktree_id = notebook_item.project_path.worktree_id;
+        let working_directory = notebook_item
+            .path
+            .parent()
+            .map(|path| path.to_path_buf())
+            .unwrap_or_else(std::env::temp_dir);
+
+        let Some(kernel_specification) = self.kernel_specification(worktree_id, cx) else {
+            log::error!(
+                "no kernel found for notebook {:?}",
+                self.notebook_item.read(cx).path
+            );
+            return None;
+        };
+
+        let fs = store.read(cx).fs().clone();
+        let session = cx.new(|cx| {
+            Session::for_notebook(working_directory, fs, kernel_specification, window, cx)
+        });
+
+        let notebook_id = cx.entity_id();
+        cx.subscribe(&session, move |this, _, event, cx| match event {
+            SessionEvent::Shutdown(_) => {
+                this.session = None;
+                ReplStore::global(cx).update(cx, |store, _cx| {
+                    store.remove_session(notebook_id);
+                });
+                cx.notify();
+            }
+        })
+        .detach();
+
+        store.update(cx, |store, _cx| {
+            store.insert_session(notebook_id, session.clone());
+        });
+
+        self.session = Some(session.clone());
+        Some(session)
+    }
+
+    /// Prefers the kernel the notebook was saved with, falling back to the one that was selected
+    /// for the worktree or the notebook's language.
+    fn kernel_specification(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &App,
+    ) -> Option<KernelSpecification> {
+        let store = ReplStore::global(cx);
+        let store = store.read(cx);
+        let notebook_kernel_name = self
+            .notebook_item
+            .read(cx)
+            .notebook
+            .metadata
+            .kernelspec
+            .as_ref()
+            .map(|kernelspec| kernelspec.name.clone());
+
+        notebook_kernel_name
+            .and_then(|name| {
+                store
+                    .kernel_specifications_for_worktree(worktree_id)
+                    .find(|kernel_specification| {
+                        matches!(kernel_specification, KernelSpecification::Jupyter(_))
+                            && kernel_specification.name().as_ref() == name
+                    })
+                    .cloned()
+            })
+            .or_else(|| {
+                let language = self.notebook_language.clone().now_or_never().flatten();
+                store.active_kernelspec(worktree_id, language, cx)
+            })
+    }
+
+    fn interrupt(&mut self, _: &Interrupt, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(session) = self.session.as_ref() {
+            session.update(cx, |session, cx| session.interrupt(cx));
+        }
+    }
+
+    fn restart(&mut self, _: &Restart, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(session) = self.session.as_ref() {
+            session.update(cx, |session, cx| session.restart(window, cx));
+        }
+    }
+
+    fn shutdown(&mut self, _: &Shutdown, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(session) = self.session.as_ref() {
+            session.update(cx, |session, cx| session.shutdown(window, cx));
+        }
+    }
+
+    fn open_notebook(&mut self, _: &OpenNotebook, window: &mut Window, cx: &mut Context<Self>) {
+        let workspace = self.workspace.clone();
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(path) = paths
+                .await
+                .log_err()
+                .and_then(|paths| paths.log_err())
+                .flatten()
+                .and_then(|paths| paths.into_iter().next())
+            else {
+                return;
+            };
+
+            let Some(open_task) = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_abs_path(path, OpenOptions::default(), window, cx)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            open_task.await.log_err();
+        })
+        .detach();
+    }
+
+    /// Records the current cell order, so that the following cell operation can be undone.
+    fn push_undo(&mut self) {
+        self.undo_stack.push(self.cell_order.clone());
+        self.redo_stack.clear();
+    }
+
+    fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(cell_order) = self.undo_stack.pop() {
+            let cell_order = std::mem::replace(&mut self.cell_order, cell_order);
+            self.redo_stack.push(cell_order);
+            self.cell_order_changed(window, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(cell_order) = self.redo_stack.pop() {
+            let cell_order = std::mem::replace(&mut self.cell_order, cell_order);
+            self.undo_stack.push(cell_order);
+            self.cell_order_changed(window, cx);
+        }
     }
 
-    fn open_notebook(&mut self, _: &OpenNotebook, _window: &mut Window, _cx: &mut Context<Self>) {
-        println!("Open notebook triggered");
+    fn cell_order_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.cell_list.reset(self.cell_order.len());
+        let index = self
+            .selected_cell_index
+            .min(self.cell_order.len().saturating_sub(1));
+        self.set_selected_index(index, true, window, cx);
+        cx.emit(());
+        cx.notify();
     }
 
     fn move_cell_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell up triggered");
+        let index = self.selected_index();
+        if index == 0 || index >= self.cell_count() {
+            return;
+        }
+
+        self.push_undo();
+        self.cell_order.swap(index - 1, index);
+        self.cell_list.splice(index - 1..index + 1, 2);
+        self.set_selected_index(index - 1, true, window, cx);
+        cx.emit(());
+        cx.notify();
     }
 
     fn move_cell_down(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell down triggered");
+        let index = self.selected_index();
+        if index + 1 >= self.cell_count() {
+            return;
+        }
+
+        self.push_undo();
+        self.cell_order.swap(index, index + 1);
+        self.cell_list.splice(index..index + 2, 2);
+        self.set_selected_index(index + 1, true, window, cx);
+        cx.emit(());
+        cx.notify();
+    }
+
+    /// Removes the selected cell. It's kept in `cell_map`, so that undoing restores it.
+    fn delete_cell(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.selected_index();
+        if index >= self.cell_count() {
+            return;
+        }
+
+        self.push_undo();
+        self.cell_order.remove(index);
+        self.cell_list.splice(index..index + 1, 0);
+        let index = index.min(self.cell_count().saturating_sub(1));
+        self.set_selected_index(index, true, window, cx);
+        cx.emit(());
+        cx.notify();
     }
 
     fn add_markdown_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add markdown block triggered");
+        if let Some(Cell::Markdown(markdown_cell)) = self.add_cell(CellType::Markdown, window, cx) {
+            markdown_cell.update(cx, |markdown_cell, cx| {
+                markdown_cell.start_editing(window, cx)
+            });
+        }
     }
 
     fn add_code_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add code block triggered");
+        if let Some(Cell::Code(code_cell)) = self.add_cell(CellType::Code, window, cx) {
+            let focus_handle = code_cell.read(cx).editor().focus_handle(cx);
+            window.focus(&focus_handle);
+        }
+    }
+
+    /// Inserts a new cell below the selected one and selects it.
+    fn add_cell(
+        &mut self,
+        cell_type: CellType,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Cell> {
+        let notebook_cell = new_notebook_cell(cell_type).log_err()?;
+        let cell = Cell::load(
+            &notebook_cell,
+            &self.languages,
+            self.notebook_language.clone(),
+            window,
+            cx,
+        );
+
+        let index = if self.cell_order.is_empty() {
+            0
+        } else {
+            self.selected_index().min(self.cell_count() - 1) + 1
+        };
+
+        self.push_undo();
+        self.cell_order.insert(index, cell.id(cx));
+        self.register_cell(cell.clone(), window, cx);
+        self.cell_list.splice(index..index, 1);
+        self.set_selected_index(index, true, window, cx);
+        cx.emit(());
+        cx.notify();
+
+        Some(cell)
+    }
+
+    fn to_notebook(&self, cx: &App) -> nbformat::v4::Notebook {
+        // Start from the loaded notebook, so that its metadata is preserved.
+        let mut notebook = self.notebook_item.read(cx).notebook.clone();
+        notebook.cells = self
+            .cell_order
+            .iter()
+            .filter_map(|cell_id| self.cell_map.get(cell_id))
+            .map(|cell| cell.to_nbformat(cx))
+            .collect();
+        notebook
+    }
+
+    fn save_notebook(&mut self, abs_path: PathBuf, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let notebook = self.to_notebook(cx);
+        let fs = self.project.read(cx).fs().clone();
+
+        cx.spawn(async move |this, cx| {
+            let content = serialize_notebook(&notebook)?;
+            fs.atomic_write(abs_path, content).await?;
+
+            this.update(cx, |this, cx| {
+                this.notebook_item.update(cx, |notebook_item, _| {
+                    notebook_item.notebook = notebook;
+                });
+                for cell in this.cell_map.values() {
+                    cell.did_save(cx);
+                }
+                this.saved_cell_order = this.cell_order.clone();
+                this.has_unsaved_outputs = false;
+                cx.emit(());
+                cx.notify();
+            })
+        })
     }
 
     fn cell_count(&self) -> usize {
-        self.cell_map.len()
+        self.cell_order.len()
     }
 
     fn selected_index(&self) -> usize {
@@ -397,6 +791,20 @@ impl NotebookEditor {
                                 .on_click(|_, window, cx| {
                                     window.dispatch_action(Box::new(MoveCellDown), cx);
                                 }),
+                            )
+                            .child(
+                                Self::render_notebook_control(
+                                    "delete-cell",
+                                    IconName::Trash,
+                                    window,
+                                    cx,
+                                )
+                                .tooltip(move |window, cx| {
+                                    Tooltip::for_action("Delete cell", &DeleteCell, window, cx)
+                                })
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(Box::new(DeleteCell), cx);
+                                }),
                             ),
                     )
                     .child(
@@ -472,7 +880,7 @@ impl NotebookEditor {
 
         let is_selected = index == self.selected_cell_index;
 
-        match cell {
+        let cell = match cell {
             Cell::Code(cell) => {
                 cell.update(cx, |cell, _cx| {
                     cell.set_selected(is_selected)
@@ -494,7 +902,17 @@ impl NotebookEditor {
                 });
                 cell.clone().into_any_element()
             }
-        }
+        };
+
+        div()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _, window, cx| {
+                    this.set_selected_index(index, false, window, cx);
+                    cx.notify();
+                }),
+            )
+            .child(cell)
     }
 }
 
@@ -520,6 +938,12 @@ impl Render for NotebookEditor {
             .on_action(
                 cx.listener(|this, &AddCodeBlock, window, cx| this.add_code_block(window, cx)),
             )
+            .on_action(cx.listener(|this, &DeleteCell, window, cx| this.delete_cell(window, cx)))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::interrupt))
+            .on_action(cx.listener(Self::restart))
+            .on_action(cx.listener(Self::shutdown))
             .on_action(cx.listener(Self::select_next))
             .on_action(cx.listener(Self::select_previous))
             .on_action(cx.listener(Self::select_first))
@@ -557,7 +981,7 @@ pub struct NotebookItem {
     // Raw notebook data
     notebook: nbformat::v4::Notebook,
     // Store our version of the notebook in memory (cell_order, cell_map)
-    id: ProjectEntryId,
+    id: Option<ProjectEntryId>,
 }
 
 impl project::ProjectItem for NotebookItem {
@@ -579,22 +1003,7 @@ impl project::ProjectItem for NotebookItem {
 
                 // todo: watch for changes to the file
                 let file_content = fs.load(&abs_path.as_path()).await?;
-                let notebook = nbformat::parse_notebook(&file_content);
-
-                let notebook = match notebook {
-                    Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                    // 4.1 - 4.4 are converted to 4.5
-                    Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                        // TODO: Decide if we want to mutate the notebook by including Cell IDs
-                        // and any other conversions
-                        let notebook = nbformat::upgrade_legacy_notebook(legacy_notebook)?;
-                        notebook
-                    }
-                    // Bad notebooks and notebooks v4.0 and below are not supported
-                    Err(e) => {
-                        anyhow::bail!("Failed to parse notebook: {:?}", e);
-                    }
-                };
+                let notebook = parse_notebook(&file_content)?;
 
                 let id = project
                     .update(cx, |project, cx| project.entry_for_path(&path, cx))?
@@ -606,7 +1015,7 @@ impl project::ProjectItem for NotebookItem {
                     project_path: path,
                     languages,
                     notebook,
-                    id,
+                    id: Some(id),
                 })
             }))
         } else {
@@ -615,7 +1024,7 @@ impl project::ProjectItem for NotebookItem {
     }
 
     fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
-        Some(self.id)
+        self.id
     }
 
     fn project_path(&self, _: &App) -> Option<ProjectPath> {
@@ -627,6 +1036,33 @@ impl project::ProjectItem for NotebookItem {
     }
 }
 
+fn parse_notebook(content: &str) -> Result<nbformat::v4::Notebook> {
+    match nbformat::parse_notebook(content) {
+        Ok(nbformat::Notebook::V4(notebook)) => Ok(notebook),
+        // 4.1 - 4.4 are converted to 4.5
+        Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+            // TODO: Decide if we want to mutate the notebook by including Cell IDs
+            // and any other conversions
+            let notebook = nbformat::upgrade_legacy_notebook(legacy_notebook)?;
+            Ok(notebook)
+        }
+        // Bad notebooks and notebooks v4.0 and below are not supported
+        Err(e) => {
+            anyhow::bail!("Failed to parse notebook: {:?}", e);
+        }
+    }
+}
+
+/// Serializes the notebook the way Jupyter does, to keep diffs of saved notebooks small.
+fn serialize_notebook(notebook: &nbformat::v4::Notebook) -> Result<String> {
+    let mut content = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+    serde::Serialize::serialize(notebook, &mut serializer)?;
+    content.push(b'\n');
+    Ok(String::from_utf8(content)?)
+}
+
 impl NotebookItem {
     pub fn language_name(&self) -> Option<String> {
         self.notebook
@@ -706,6 +1142,25 @@ impl EventEmitter<()> for NotebookEditor {}
 impl Item for NotebookEditor {
     type Event = ();
 
+    fn to_item_events(_: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(ItemEvent::UpdateTab)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.workspace = workspace.weak_handle();
+        for cell in self.cell_map.values() {
+            if let Cell::Code(code_cell) = cell {
+                let workspace = self.workspace.clone();
+                code_cell.update(cx, |code_cell, cx| code_cell.set_workspace(workspace, cx));
+            }
+        }
+    }
+
     fn clone_on_split(
         &self,
         _workspace_id: Option<workspace::WorkspaceId>,
@@ -775,49 +1230,84 @@ impl Item for NotebookEditor {
         // TODO
     }
 
-    // TODO
     fn can_save(&self, _cx: &App) -> bool {
-        false
+        true
     }
-    // TODO
+
+    fn can_save_as(&self, _cx: &App) -> bool {
+        true
+    }
+
     fn save(
         &mut self,
         _format: bool,
         _project: Entity<Project>,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save() must be implemented if can_save() returns true")
+        let abs_path = self.notebook_item.read(cx).path.clone();
+        self.save_notebook(abs_path, cx)
     }
 
-    // TODO
     fn save_as(
         &mut self,
-        _project: Entity<Project>,
-        _path: ProjectPath,
+        project: Entity<Project>,
+        path: ProjectPath,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save_as() must be implemented if can_save() returns true")
+        let Some(abs_path) = project.read(cx).absolute_path(&path, cx) else {
+            return Task::ready(Err(anyhow!("failed to find the absolute path of {path:?}")));
+        };
+
+        let save = self.save_notebook(abs_path.clone(), cx);
+        let notebook_item = self.notebook_item.clone();
+        cx.spawn(async move |this, cx| {
+            save.await?;
+
+            let id = project.update(cx, |project, cx| {
+                project.entry_for_path(&path, cx).map(|entry| entry.id)
+            })?;
+            notebook_item.update(cx, |notebook_item, _| {
+                notebook_item.path = abs_path;
+                notebook_item.project_path = path;
+                notebook_item.id = id;
+            })?;
+            this.update(cx, |_, cx| cx.emit(()))
+        })
     }
-    // TODO
+
     fn reload(
         &mut self,
-        _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("reload() must be implemented if can_save() returns true")
+        let abs_path = self.notebook_item.read(cx).path.clone();
+        let fs = project.read(cx).fs().clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let file_content = fs.load(&abs_path).await?;
+            let notebook = parse_notebook(&file_content)?;
+
+            this.update_in(cx, |this, window, cx| {
+                this.notebook_item.update(cx, |notebook_item, _| {
+                    notebook_item.notebook = notebook;
+                });
+                this.load_cells(window, cx);
+                cx.emit(());
+            })
+        })
     }
 
     fn is_dirty(&self, cx: &App) -> bool {
-        self.cell_map.values().any(|cell| {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.read(cx).is_dirty(cx)
-            } else {
-                false
-            }
-        })
+        self.has_unsaved_outputs
+            || self.cell_order != self.saved_cell_order
+            || self
+                .cell_order
+                .iter()
+                .filter_map(|cell_id| self.cell_map.get(cell_id))
+                .any(|cell| cell.is_dirty(cx))
     }
 }
 
@@ -840,3 +1330,232 @@ impl ProjectItem for NotebookEditor {
         Self::new(project, item, window, cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+        });
+    }
+
+    async fn open_notebook(
+        notebook: serde_json::Value,
+        cx: &mut TestAppContext,
+    ) -> (Entity<NotebookEditor>, &mut VisualTestContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "notebook.ipynb": notebook.to_string() }))
+            .await;
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+
+        let notebook_item = cx.new(|_| NotebookItem {
+            path: PathBuf::from("/dir/notebook.ipynb"),
+            project_path: ProjectPath {
+                worktree_id,
+                path: std::path::Path::new("notebook.ipynb").into(),
+            },
+            languages: project.read(cx).languages().clone(),
+            notebook: parse_notebook(&notebook.to_string()).unwrap(),
+            id: None,
+        });
+        let (notebook_editor, cx) = cx.add_window_view(|window, cx| {
+            NotebookEditor::new(project.clone(), notebook_item, window, cx)
+        });
+        cx.run_until_parked();
+        (notebook_editor, cx)
+    }
+
+    fn notebook_json() -> serde_json::Value {
+        json!({
+            "cells": [
+                {
+                    "cell_type": "markdown",
+                    "id": "title",
+                    "metadata": { "tags": ["title"] },
+                    "source": ["# Title"]
+                },
+                {
+                    "cell_type": "code",
+                    "execution_count": 2,
+                    "id": "code",
+                    "metadata": {
+                        "jupyter": { "source_hidden": false, "outputs_hidden": true }
+                    },
+                    "outputs": [
+                        {
+                            "name": "stdout",
+                            "output_type": "stream",
+                            "text": ["1\n", "2\n"]
+                        },
+                        {
+                            "data": {
+                                "application/vnd.custom+json": { "points": [1, 2, 3] },
+                                "text/plain": ["<Plot>"]
+                            },
+                            "metadata": {},
+                            "output_type": "display_data"
+                        }
+                    ],
+                    "source": ["print(1)\n", "print(2)"]
+                },
+                {
+                    "cell_type": "raw",
+                    "id": "raw",
+                    "metadata": {},
+                    "source": ["raw text"]
+                }
+            ],
+            "metadata": {
+                "kernelspec": {
+                    "display_name": "Python 3",
+                    "language": "python",
+                    "name": "python3"
+                },
+                "jupytext": { "formats": "ipynb,py:percent" }
+            },
+            "nbformat": 4,
+            "nbformat_minor": 5
+        })
+    }
+
+    fn cell_ids(
+        notebook_editor: &Entity<NotebookEditor>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        notebook_editor.read_with(cx, |notebook_editor, _| {
+            notebook_editor
+                .cell_order
+                .iter()
+                .map(|cell_id| cell_id.to_string())
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_notebook_round_trip(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (notebook_editor, cx) = open_notebook(notebook_json(), cx).await;
+
+        let content = notebook_editor.read_with(cx, |notebook_editor, cx| {
+            serialize_notebook(&notebook_editor.to_notebook(cx)).unwrap()
+        });
+        assert!(content.starts_with("{\n \"cells\": [\n"), "{content}");
+        assert!(content.ends_with("}\n"));
+
+        let saved = serde_json::from_str::<serde_json::Value>(&content).unwrap();
+        let original = notebook_json();
+        for pointer in [
+            "/metadata/jupytext",
+            "/metadata/kernelspec",
+            "/cells/0/id",
+            "/cells/0/metadata/tags",
+            "/cells/0/source",
+            "/cells/1/execution_count",
+            "/cells/1/metadata/jupyter",
+            "/cells/1/outputs",
+            "/cells/1/source",
+            "/cells/2/source",
+            "/nbformat",
+            "/nbformat_minor",
+        ] {
+            assert_eq!(
+                saved.pointer(pointer),
+                original.pointer(pointer),
+                "mismatch at {pointer}"
+            );
+        }
+        notebook_editor.read_with(cx, |notebook_editor, cx| {
+            assert!(!notebook_editor.is_dirty(cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_cell_operations_with_undo(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (notebook_editor, cx) = open_notebook(notebook_json(), cx).await;
+        assert_eq!(cell_ids(&notebook_editor, cx), ["title", "code", "raw"]);
+
+        // Move the first cell down, past the code cell.
+        notebook_editor.update_in(cx, |notebook_editor, window, cx| {
+            notebook_editor.move_cell_down(window, cx);
+            assert_eq!(notebook_editor.selected_index(), 1);
+            assert!(notebook_editor.is_dirty(cx));
+        });
+        assert_eq!(cell_ids(&notebook_editor, cx), ["code", "title", "raw"]);
+
+        // Add a code cell below the selected one.
+        let added_cell_id = notebook_editor.update_in(cx, |notebook_editor, window, cx| {
+            notebook_editor
+                .add_cell(CellType::Code, window, cx)
+                .unwrap()
+                .id(cx)
+                .to_string()
+        });
+        assert_eq!(
+            cell_ids(&notebook_editor, cx),
+            ["code", "title", added_cell_id.as_str(), "raw"]
+        );
+
+        // Delete the raw cell.
+        notebook_editor.update_in(cx, |notebook_editor, window, cx| {
+            notebook_editor.set_selected_index(3, false, window, cx);
+            notebook_editor.delete_cell(window, cx);
+            assert_eq!(notebook_editor.selected_index(), 2);
+        });
+        assert_eq!(
+            cell_ids(&notebook_editor, cx),
+            ["code", "title", added_cell_id.as_str()]
+        );
+
+        // Undo each operation, in reverse order.
+        notebook_editor.update_in(cx, |notebook_editor, window, cx| {
+            notebook_editor.undo(&Undo, window, cx)
+        });
+        assert_eq!(
+            cell_ids(&notebook_editor, cx),
+            ["code", "title", added_cell_id.as_str(), "raw"]
+        );
+        notebook_editor.update_in(cx, |notebook_editor, window, cx| {
+            notebook_editor.undo(&Undo, window, cx)
+        });
+        assert_eq!(cell_ids(&notebook_editor, cx), ["code", "title", "raw"]);
+        notebook_editor.update_in(cx, |notebook_editor, window, cx| {
+            notebook_editor.undo(&Undo, window, cx);
+            assert!(!notebook_editor.is_dirty(cx));
+        });
+        assert_eq!(cell_ids(&notebook_editor, cx), ["title", "code", "raw"]);
+
+        // Redo restores the added cell, which is saved along with the other cells.
+        notebook_editor.update_in(cx, |notebook_editor, window, cx| {
+            notebook_editor.redo(&Redo, window, cx);
+            notebook_editor.redo(&Redo, window, cx);
+        });
+        assert_eq!(
+            cell_ids(&notebook_editor, cx),
+            ["code", "title", added_cell_id.as_str(), "raw"]
+        );
+        let notebook =
+            notebook_editor.read_with(cx, |notebook_editor, cx| notebook_editor.to_notebook(cx));
+        assert_eq!(notebook.cells.len(), 4);
+        assert!(matches!(
+            &notebook.cells[2],
+            nbformat::v4::Cell::Code { id, outputs, .. }
+                if id.to_string() == added_cell_id && outputs.is_empty()
+        ));
+    }
+}
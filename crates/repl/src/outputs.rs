@@ -14,7 +14,10 @@
 //! The module supports several output types, including:
 //! - Plain text
 //! - Markdown
-//! - Images (PNG and JPEG)
+//! - HTML (rendered as Markdown)
+//! - LaTeX math
+//! - Images (PNG, JPEG and SVG)
+//! - JSON
 //! - Tables
 //! - Error messages
 //!
@@ -44,12 +47,24 @@ use language::Buffer;
 use runtimelib::{ExecutionState, JupyterMessageContent, MimeBundle, MimeType};
 use ui::{Context, IntoElement, Styled, Tooltip, Window, div, prelude::*, v_flex};
 
+mod html;
+use html::HtmlView;
+
 mod image;
 use image::ImageView;
 
+mod json;
+use json::JsonView;
+
+mod latex;
+use latex::LatexView;
+
 mod markdown;
 use markdown::MarkdownView;
 
+mod svg;
+use svg::SvgView;
+
 mod table;
 use table::TableView;
 
@@ -63,9 +78,13 @@ use workspace::Workspace;
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
+        MimeType::DataTable(_) => 9,
+        MimeType::Svg(_) => 8,
+        MimeType::Png(_) => 7,
+        MimeType::Jpeg(_) => 6,
+        MimeType::Latex(_) => 5,
+        MimeType::Html(_) => 4,
+        MimeType::Json(_) => 3,
         MimeType::Markdown(_) => 2,
         MimeType::Plain(_) => 1,
         // All other media types are not supported in Zed at this time
@@ -126,6 +145,22 @@ pub enum Output {
         content: Entity<MarkdownView>,
         display_id: Option<String>,
     },
+    Html {
+        content: Entity<HtmlView>,
+        display_id: Option<String>,
+    },
+    Latex {
+        content: Entity<LatexView>,
+        display_id: Option<String>,
+    },
+    Svg {
+        content: Entity<SvgView>,
+        display_id: Option<String>,
+    },
+    Json {
+        content: Entity<JsonView>,
+        display_id: Option<String>,
+    },
     ClearOutputWaitMarker,
 }
 
@@ -216,6 +251,10 @@ impl Output {
             Self::Image { content, .. } => Some(content.clone().into_any_element()),
             Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
             Self::Table { content, .. } => Some(content.clone().into_any_element()),
+            Self::Html { content, .. } => Some(content.clone().into_any_element()),
+            Self::Latex { content, .. } => Some(content.clone().into_any_element()),
+            Self::Svg { content, .. } => Some(content.clone().into_any_element()),
+            Self::Json { content, .. } => Some(content.clone().into_any_element()),
             Self::ErrorOutput(error_view) => error_view.render(window, cx),
             Self::ClearOutputWaitMarker => None,
         };
@@ -247,6 +286,18 @@ impl Output {
                 Self::Table { content, .. } => {
                     Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
                 }
+                Self::Html { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
+                }
+                Self::Latex { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
+                }
+                Self::Svg { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
+                }
+                Self::Json { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), window, cx)
+                }
                 Self::ClearOutputWaitMarker => None,
             })
     }
@@ -260,6 +311,10 @@ impl Output {
             Output::Message(_) => None,
             Output::Table { display_id, .. } => display_id.clone(),
             Output::Markdown { display_id, .. } => display_id.clone(),
+            Output::Html { display_id, .. } => display_id.clone(),
+            Output::Latex { display_id, .. } => display_id.clone(),
+            Output::Svg { display_id, .. } => display_id.clone(),
+            Output::Json { display_id, .. } => display_id.clone(),
             Output::ClearOutputWaitMarker => None,
         }
     }
@@ -270,7 +325,17 @@ impl Output {
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
-        match data.richest(rank_mime_type) {
+        Self::from_mime_type(data, data.richest(rank_mime_type), display_id, window, cx)
+    }
+
+    fn from_mime_type(
+        data: &MimeBundle,
+        mime_type: Option<&MimeType>,
+        display_id: Option<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        match mime_type {
             Some(MimeType::Plain(text)) => Output::Plain {
                 content: cx.new(|cx| TerminalOutput::from(text, window, cx)),
                 display_id,
@@ -293,6 +358,39 @@ impl Output {
                 content: cx.new(|cx| TableView::new(data, window, cx)),
                 display_id,
             },
+            Some(MimeType::Svg(svg)) => match SvgView::from(svg, cx) {
+                Ok(view) => Output::Svg {
+                    content: cx.new(|_| view),
+                    display_id,
+                },
+                Err(error) => Output::Message(format!("Failed to load SVG: {}", error)),
+            },
+            Some(MimeType::Latex(latex)) => match LatexView::from(latex, cx) {
+                Ok(view) => Output::Latex {
+                    content: cx.new(|_| view),
+                    display_id,
+                },
+                Err(error) => Output::Message(format!("Failed to render LaTeX: {}", error)),
+            },
+            Some(MimeType::Html(html)) => match HtmlView::from(html, cx) {
+                Ok(Some(view)) => Output::Html {
+                    content: cx.new(|_| view),
+                    display_id,
+                },
+                // Nothing renderable is left once unsafe tags are removed, so use
+                // the next richest media type instead.
+                Ok(None) | Err(_) => {
+                    let mime_type = data.richest(|mime_type| match mime_type {
+                        MimeType::Html(_) => 0,
+                        mime_type => rank_mime_type(mime_type),
+                    });
+                    Self::from_mime_type(data, mime_type, display_id, window, cx)
+                }
+            },
+            Some(MimeType::Json(json)) => Output::Json {
+                content: cx.new(|_| JsonView::new(serde_json::Value::Object(json.clone()))),
+                display_id,
+            },
             // Any other media types are not supported
             _ => Output::Message("Unsupported media type".to_string()),
         }
@@ -320,6 +418,12 @@ pub struct ExecutionView {
     #[allow(unused)]
     workspace: WeakEntity<Workspace>,
     pub outputs: Vec<Output>,
+    /// The outputs in the notebook format, one for each of `outputs`.
+    ///
+    /// These are kept around so that notebooks can be saved without losing the
+    /// media types that we don't render.
+    notebook_outputs: Vec<Option<nbformat::v4::Output>>,
+    pub execution_count: Option<i32>,
     pub status: ExecutionStatus,
 }
 
@@ -332,10 +436,58 @@ impl ExecutionView {
         Self {
             workspace,
             outputs: Default::default(),
+            notebook_outputs: Default::default(),
+            execution_count: None,
             status,
         }
     }
 
+    pub fn set_workspace(&mut self, workspace: WeakEntity<Workspace>) {
+        self.workspace = workspace;
+    }
+
+    /// Restore an output that was saved in a notebook
+    pub fn push_notebook_output(
+        &mut self,
+        output: &nbformat::v4::Output,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let rendered_output = match output {
+            nbformat::v4::Output::Stream { text, .. } => Output::Stream {
+                content: cx.new(|cx| TerminalOutput::from(&text.0, window, cx)),
+            },
+            nbformat::v4::Output::DisplayData(display_data) => {
+                Output::new(&display_data.data, None, window, cx)
+            }
+            nbformat::v4::Output::ExecuteResult(execute_result) => {
+                Output::new(&execute_result.data, None, window, cx)
+            }
+            nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
+                ename: error.ename.clone(),
+                evalue: error.evalue.clone(),
+                traceback: cx
+                    .new(|cx| TerminalOutput::from(&error.traceback.join("\n"), window, cx)),
+            }),
+        };
+
+        self.outputs.push(rendered_output);
+        self.notebook_outputs.push(Some(output.clone()));
+        cx.notify();
+    }
+
+    /// The outputs of this execution, in the format they're saved to notebooks in
+    pub fn notebook_outputs(&self) -> Vec<nbformat::v4::Output> {
+        self.notebook_outputs.iter().flatten().cloned().collect()
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.outputs.clear();
+        self.notebook_outputs.clear();
+        self.execution_count = None;
+        cx.notify();
+    }
+
     /// Accept a Jupyter message belonging to this execution
     pub fn push_message(
         &mut self,
@@ -343,24 +495,52 @@ impl ExecutionView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let output: Output = match message {
-            JupyterMessageContent::ExecuteResult(result) => Output::new(
-                &result.data,
-                result.transient.as_ref().and_then(|t| t.display_id.clone()),
-                window,
-                cx,
-            ),
-            JupyterMessageContent::DisplayData(result) => Output::new(
-                &result.data,
-                result.transient.as_ref().and_then(|t| t.display_id.clone()),
-                window,
-                cx,
-            ),
+        let (output, notebook_output): (Output, Option<nbformat::v4::Output>) = match message {
+            JupyterMessageContent::ExecuteInput(input) => {
+                self.execution_count = Some(input.execution_count.0 as i32);
+                cx.notify();
+                return;
+            }
+            JupyterMessageContent::ExecuteResult(result) => {
+                let output = Output::new(
+                    &result.data,
+                    result.transient.as_ref().and_then(|t| t.display_id.clone()),
+                    window,
+                    cx,
+                );
+                let mut result = result.clone();
+                result.transient = None;
+                (output, Some(nbformat::v4::Output::ExecuteResult(result)))
+            }
+            JupyterMessageContent::DisplayData(result) => {
+                let output = Output::new(
+                    &result.data,
+                    result.transient.as_ref().and_then(|t| t.display_id.clone()),
+                    window,
+                    cx,
+                );
+                let mut result = result.clone();
+                result.transient = None;
+                (output, Some(nbformat::v4::Output::DisplayData(result)))
+            }
             JupyterMessageContent::StreamContent(result) => {
                 // Previous stream data will combine together, handling colors, carriage returns, etc
                 if let Some(new_terminal) = self.apply_terminal_text(&result.text, window, cx) {
-                    new_terminal
+                    let name = match result.name {
+                        runtimelib::Stdio::Stdout => "stdout",
+                        runtimelib::Stdio::Stderr => "stderr",
+                    };
+                    let notebook_output = nbformat::v4::Output::Stream {
+                        name: name.to_string(),
+                        text: nbformat::v4::MultilineString(result.text.clone()),
+                    };
+                    (new_terminal, Some(notebook_output))
                 } else {
+                    if let Some(Some(nbformat::v4::Output::Stream { text, .. })) =
+                        self.notebook_outputs.last_mut()
+                    {
+                        text.0.push_str(&result.text);
+                    }
                     return;
                 }
             }
@@ -368,17 +548,20 @@ impl ExecutionView {
                 let terminal =
                     cx.new(|cx| TerminalOutput::from(&result.traceback.join("\n"), window, cx));
 
-                Output::ErrorOutput(ErrorView {
+                let output = Output::ErrorOutput(ErrorView {
                     ename: result.ename.clone(),
                     evalue: result.evalue.clone(),
                     traceback: terminal,
-                })
+                });
+                (output, Some(nbformat::v4::Output::Error(result.clone())))
             }
             JupyterMessageContent::ExecuteReply(reply) => {
                 for payload in reply.payload.iter() {
                     if let runtimelib::Payload::Page { data, .. } = payload {
+                        // Like in Jupyter, pager content isn't saved to notebooks.
                         let output = Output::new(data, None, window, cx);
                         self.outputs.push(output);
+                        self.notebook_outputs.push(None);
                     }
                 }
                 cx.notify();
@@ -387,12 +570,13 @@ impl ExecutionView {
             JupyterMessageContent::ClearOutput(options) => {
                 if !options.wait {
                     self.outputs.clear();
+                    self.notebook_outputs.clear();
                     cx.notify();
                     return;
                 }
 
                 // Create a marker to clear the output after we get in a new output
-                Output::ClearOutputWaitMarker
+                (Output::ClearOutputWaitMarker, None)
             }
             JupyterMessageContent::Status(status) => {
                 match status.execution_state {
@@ -413,10 +597,12 @@ impl ExecutionView {
         if let Some(output) = self.outputs.last() {
             if let Output::ClearOutputWaitMarker = output {
                 self.outputs.clear();
+                self.notebook_outputs.clear();
             }
         }
 
         self.outputs.push(output);
+        self.notebook_outputs.push(notebook_output);
 
         cx.notify();
     }
@@ -430,14 +616,26 @@ impl ExecutionView {
     ) {
         let mut any = false;
 
-        self.outputs.iter_mut().for_each(|output| {
-            if let Some(other_display_id) = output.display_id().as_ref() {
-                if other_display_id == display_id {
-                    *output = Output::new(data, Some(display_id.to_owned()), window, cx);
-                    any = true;
+        self.outputs
+            .iter_mut()
+            .zip(self.notebook_outputs.iter_mut())
+            .for_each(|(output, notebook_output)| {
+                if let Some(other_display_id) = output.display_id().as_ref() {
+                    if other_display_id == display_id {
+                        *output = Output::new(data, Some(display_id.to_owned()), window, cx);
+                        match notebook_output {
+                            Some(nbformat::v4::Output::DisplayData(display_data)) => {
+                                display_data.data = data.clone();
+                            }
+                            Some(nbformat::v4::Output::ExecuteResult(execute_result)) => {
+                                execute_result.data = data.clone();
+                            }
+                            _ => {}
+                        }
+                        any = true;
+                    }
                 }
-            }
-        });
+            });
 
         if any {
             cx.notify();
@@ -0,0 +1,104 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::Result;
+use gpui::{App, ClipboardItem, Context, Entity, Window};
+use html_to_markdown::{
+    HandleTag, HtmlElement, MarkdownWriter, StartTagOutcome, TagHandler, convert_html_to_markdown,
+    markdown,
+};
+use language::Buffer;
+use ui::prelude::*;
+
+use crate::outputs::OutputContent;
+use crate::outputs::markdown::MarkdownView;
+
+/// Tags whose contents are never rendered, as they are either executable,
+/// interactive, or cannot be represented as Markdown.
+const UNSAFE_TAGS: &[&str] = &[
+    "head", "script", "style", "iframe", "object", "embed", "form", "input", "button", "select",
+    "textarea", "link", "meta", "noscript", "canvas", "video", "audio", "template",
+];
+
+/// Drops every tag outside of the safe subset of HTML that we render.
+struct UnsafeTagRemover;
+
+impl HandleTag for UnsafeTagRemover {
+    fn should_handle(&self, tag: &str) -> bool {
+        UNSAFE_TAGS.contains(&tag)
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        _tag: &HtmlElement,
+        _writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        StartTagOutcome::Skip
+    }
+}
+
+/// Converts an HTML output into Markdown and renders it with [`MarkdownView`].
+pub struct HtmlView {
+    raw_html: String,
+    markdown: Entity<MarkdownView>,
+}
+
+impl HtmlView {
+    /// Converts the given HTML, returning `None` if nothing renderable remains
+    /// once the unsafe tags have been removed.
+    pub fn from(html: &str, cx: &mut App) -> Result<Option<Self>> {
+        let markdown = html_to_markdown(html)?;
+        if markdown.trim().is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            raw_html: html.to_string(),
+            markdown: cx.new(|cx| MarkdownView::from(markdown, cx)),
+        }))
+    }
+}
+
+fn html_to_markdown(html: &str) -> Result<String> {
+    let mut handlers: Vec<TagHandler> = vec![
+        Rc::new(RefCell::new(UnsafeTagRemover)),
+        Rc::new(RefCell::new(markdown::ParagraphHandler)),
+        Rc::new(RefCell::new(markdown::HeadingHandler)),
+        Rc::new(RefCell::new(markdown::ListHandler)),
+        Rc::new(RefCell::new(markdown::TableHandler::new())),
+        Rc::new(RefCell::new(markdown::StyledTextHandler)),
+        Rc::new(RefCell::new(markdown::CodeHandler)),
+    ];
+
+    convert_html_to_markdown(html.as_bytes(), &mut handlers)
+}
+
+impl OutputContent for HtmlView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.raw_html.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(self.raw_html.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
+
+impl Render for HtmlView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div().child(self.markdown.clone())
+    }
+}
@@ -0,0 +1,147 @@
+use collections::HashSet;
+use gpui::{AnyElement, App, ClipboardItem, Entity, Window};
+use language::Buffer;
+use serde_json::Value;
+use ui::{Disclosure, prelude::*};
+
+use crate::outputs::OutputContent;
+
+/// Containers nested deeper than this start out collapsed.
+const INITIALLY_EXPANDED_DEPTH: usize = 1;
+
+/// JsonView renders `application/json` outputs as a collapsible tree.
+pub struct JsonView {
+    value: Value,
+    /// Paths of the containers whose expanded state differs from the default.
+    toggled: HashSet<String>,
+}
+
+impl JsonView {
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            toggled: HashSet::default(),
+        }
+    }
+
+    fn is_expanded(&self, path: &str, depth: usize) -> bool {
+        (depth < INITIALLY_EXPANDED_DEPTH) != self.toggled.contains(path)
+    }
+
+    fn toggle(&mut self, path: String, cx: &mut Context<Self>) {
+        if !self.toggled.remove(&path) {
+            self.toggled.insert(path);
+        }
+        cx.notify();
+    }
+
+    fn render_value(
+        &self,
+        key: Option<String>,
+        value: &Value,
+        path: String,
+        depth: usize,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| (key.clone(), value))
+                .collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (index.to_string(), value))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let is_container = matches!(value, Value::Object(_) | Value::Array(_));
+        let is_expanded = is_container && self.is_expanded(&path, depth);
+
+        let row = h_flex()
+            .id(SharedString::from(format!("json-{path}")))
+            .gap_1()
+            .pl(px(depth as f32 * 16.))
+            .child(if is_container && !children.is_empty() {
+                Disclosure::new(
+                    SharedString::from(format!("json-toggle-{path}")),
+                    is_expanded,
+                )
+                .on_click(cx.listener({
+                    let path = path.clone();
+                    move |this, _, _, cx| this.toggle(path.clone(), cx)
+                }))
+                .into_any_element()
+            } else {
+                div().w(px(16.)).into_any_element()
+            })
+            .when_some(key, |row, key| {
+                row.child(
+                    Label::new(format!("{key}:"))
+                        .color(Color::Accent)
+                        .buffer_font(cx),
+                )
+            })
+            .child(summary(value, is_expanded).buffer_font(cx));
+        rows.push(row.into_any_element());
+
+        if is_expanded {
+            for (key, child) in children {
+                let child_path = format!("{path}/{key}");
+                self.render_value(Some(key), child, child_path, depth + 1, rows, cx);
+            }
+        }
+    }
+}
+
+/// Describes a value on a single line. Containers show their size.
+fn summary(value: &Value, is_expanded: bool) -> Label {
+    let (text, color) = match value {
+        Value::Null => ("null".to_string(), Color::Muted),
+        Value::Bool(value) => (value.to_string(), Color::Info),
+        Value::Number(number) => (number.to_string(), Color::Info),
+        Value::String(string) => (format!("{string:?}"), Color::Success),
+        Value::Array(items) if is_expanded => (format!("{} items", items.len()), Color::Muted),
+        Value::Array(items) => (format!("[…] {} items", items.len()), Color::Muted),
+        Value::Object(map) if is_expanded => (format!("{} keys", map.len()), Color::Muted),
+        Value::Object(map) => (format!("{{…}} {} keys", map.len()), Color::Muted),
+    };
+    Label::new(text).color(color)
+}
+
+impl Render for JsonView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let value = self.value.clone();
+        let mut rows = Vec::new();
+        self.render_value(None, &value, String::new(), 0, &mut rows, cx);
+
+        v_flex().py_1().children(rows)
+    }
+}
+
+impl OutputContent for JsonView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        let json = serde_json::to_string_pretty(&self.value).ok()?;
+        Some(ClipboardItem::new_string(json))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let json = serde_json::to_string_pretty(&self.value).ok()?;
+        let buffer = cx.new(|cx| {
+            let mut buffer =
+                Buffer::local(json, cx).with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use gpui::{App, ClipboardItem, Entity, Rgba, Window};
+use latex_math::render_math_svg;
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::prelude::*;
+
+use crate::outputs::OutputContent;
+use crate::outputs::svg::SvgView;
+
+/// The math is rendered at this multiple of its display size to stay crisp.
+const RENDER_SCALE: f32 = 2.0;
+
+/// LatexView typesets the LaTeX math emitted by kernels as `text/latex` and
+/// rasterizes it with [`SvgView`].
+pub struct LatexView {
+    raw_latex: String,
+    image: Entity<SvgView>,
+}
+
+impl LatexView {
+    pub fn from(latex: &str, cx: &mut App) -> Result<Self> {
+        let font_size = ThemeSettings::get_global(cx).buffer_font_size(cx).0;
+        let color = Rgba::from(cx.theme().colors().text);
+        let math = render_math_svg(latex, font_size, color, RENDER_SCALE);
+        let view = SvgView::with_scale(&math.svg, RENDER_SCALE, cx)?;
+
+        Ok(Self {
+            raw_latex: latex.to_string(),
+            image: cx.new(|_| view),
+        })
+    }
+}
+
+impl OutputContent for LatexView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.raw_latex.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+}
+
+impl Render for LatexView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div().py_1().child(self.image.clone())
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use gpui::{App, ClipboardItem, Image, ImageFormat, Pixels, RenderImage, Window, img};
+use std::sync::Arc;
+use ui::{IntoElement, Styled, div, prelude::*};
+
+use crate::outputs::OutputContent;
+
+/// SvgView rasterizes an SVG document with GPUI's SVG renderer and displays it inline.
+pub struct SvgView {
+    clipboard_image: Arc<Image>,
+    height: f32,
+    width: f32,
+    image: Arc<RenderImage>,
+}
+
+impl SvgView {
+    pub fn from(svg: &str, cx: &App) -> Result<Self> {
+        Self::with_scale(svg, 1.0, cx)
+    }
+
+    /// Renders the SVG at its natural size, then displays it at `1 / scale` of that size.
+    ///
+    /// This lets callers produce SVGs at a higher resolution so they stay crisp on
+    /// high density displays.
+    pub fn with_scale(svg: &str, scale: f32, cx: &App) -> Result<Self> {
+        let mut image = Image {
+            format: ImageFormat::Svg,
+            bytes: svg.as_bytes().to_vec(),
+            id: 0,
+        };
+        let render_image = image.to_image_data(cx.svg_renderer())?;
+        image.id = render_image.id.0 as u64;
+
+        let size = render_image.size(0);
+
+        Ok(SvgView {
+            clipboard_image: Arc::new(image),
+            height: size.height.0 as f32 / scale,
+            width: size.width.0 as f32 / scale,
+            image: render_image,
+        })
+    }
+}
+
+impl Render for SvgView {
+    fn render(&mut self, _window: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        let image = self.image.clone();
+
+        div()
+            .h(Pixels(self.height))
+            .w(Pixels(self.width))
+            .child(img(image).size_full())
+    }
+}
+
+impl OutputContent for SvgView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_image(self.clipboard_image.as_ref()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+}
@@ -25,7 +25,7 @@ use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
     ShutdownRequest,
 };
-use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
+use std::{env::temp_dir, ops::Range, path::PathBuf, sync::Arc, time::Duration};
 use theme::ActiveTheme;
 use ui::{IconButtonShape, Tooltip, prelude::*};
 use util::ResultExt as _;
@@ -33,8 +33,11 @@ use util::ResultExt as _;
 pub struct Session {
     fs: Arc<dyn Fs>,
     editor: WeakEntity<Editor>,
+    working_directory: Option<PathBuf>,
     pub kernel: Kernel,
     blocks: HashMap<String, EditorBlock>,
+    /// Executions whose outputs are shown outside of the editor, such as in notebook cells.
+    executions: HashMap<String, Entity<ExecutionView>>,
     pub kernel_specification: KernelSpecification,
     _buffer_subscription: Subscription,
 }
@@ -199,6 +202,35 @@ impl Session {
         kernel_specification: KernelSpecification,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) -> Self {
+        Self::create(editor, None, fs, kernel_specification, window, cx)
+    }
+
+    /// Creates a session that isn't attached to an editor, which is what notebooks use.
+    pub fn for_notebook(
+        working_directory: PathBuf,
+        fs: Arc<dyn Fs>,
+        kernel_specification: KernelSpecification,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self::create(
+            WeakEntity::new_invalid(),
+            Some(working_directory),
+            fs,
+            kernel_specification,
+            window,
+            cx,
+        )
+    }
+
+    fn create(
+        editor: WeakEntity<Editor>,
+        working_directory: Option<PathBuf>,
+        fs: Arc<dyn Fs>,
+        kernel_specification: KernelSpecification,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Self {
         let subscription = match editor.upgrade() {
             Some(editor) => {
@@ -219,8 +251,10 @@ impl Session {
         let mut session = Self {
             fs,
             editor,
+            working_directory,
             kernel: Kernel::StartingKernel(Task::ready(()).shared()),
             blocks: HashMap::default(),
+            executions: HashMap::default(),
             kernel_specification,
             _buffer_subscription: subscription,
         };
@@ -233,9 +267,13 @@ impl Session {
         let kernel_language = self.kernel_specification.language();
         let entity_id = self.editor.entity_id();
         let working_directory = self
-            .editor
-            .upgrade()
-            .and_then(|editor| editor.read(cx).working_directory(cx))
+            .working_directory
+            .clone()
+            .or_else(|| {
+                self.editor
+                    .upgrade()
+                    .and_then(|editor| editor.read(cx).working_directory(cx))
+            })
             .unwrap_or_else(temp_dir);
 
         telemetry::event!(
@@ -295,8 +333,14 @@ impl Session {
     pub fn kernel_errored(&mut self, error_message: String, cx: &mut Context<Self>) {
         self.kernel(Kernel::ErroredLaunch(error_message.clone()), cx);
 
-        self.blocks.values().for_each(|block| {
-            block.execution_view.update(cx, |execution_view, cx| {
+        let execution_views = self
+            .blocks
+            .values()
+            .map(|block| &block.execution_view)
+            .chain(self.executions.values());
+
+        execution_views.for_each(|execution_view| {
+            execution_view.update(cx, |execution_view, cx| {
                 match execution_view.status {
                     ExecutionStatus::Finished => {
                         // Do nothing when the output was good
@@ -362,6 +406,73 @@ impl Session {
             .ok();
 
         self.blocks.clear();
+        self.executions.clear();
+    }
+
+    fn execution_status(&self) -> ExecutionStatus {
+        match &self.kernel {
+            Kernel::Restarting => ExecutionStatus::Restarting,
+            Kernel::RunningKernel(_) => ExecutionStatus::Queued,
+            Kernel::StartingKernel(_) => ExecutionStatus::ConnectingToKernel,
+            Kernel::ErroredLaunch(error) => ExecutionStatus::KernelErrored(error.clone()),
+            Kernel::ShuttingDown => ExecutionStatus::ShuttingDown,
+            Kernel::Shutdown => ExecutionStatus::Shutdown,
+        }
+    }
+
+    fn send_when_ready(&mut self, message: JupyterMessage, cx: &mut Context<Self>) {
+        match &self.kernel {
+            Kernel::RunningKernel(_) => {
+                self.send(message, cx).ok();
+            }
+            Kernel::StartingKernel(task) => {
+                // Queue up the execution as a task to run after the kernel starts
+                let task = task.clone();
+
+                cx.spawn(async move |this, cx| {
+                    task.await;
+                    this.update(cx, |session, cx| {
+                        session.send(message, cx).ok();
+                    })
+                    .ok();
+                })
+                .detach();
+            }
+            _ => {}
+        }
+    }
+
+    /// Executes the code, showing its outputs in the given view rather than in an editor block.
+    pub fn execute_in_view(
+        &mut self,
+        code: String,
+        execution_view: Entity<ExecutionView>,
+        cx: &mut Context<Self>,
+    ) {
+        if code.is_empty() {
+            return;
+        }
+
+        let execute_request = ExecuteRequest {
+            code,
+            ..ExecuteRequest::default()
+        };
+
+        let message: JupyterMessage = execute_request.into();
+
+        let status = self.execution_status();
+        execution_view.update(cx, |execution_view, cx| {
+            execution_view.clear(cx);
+            execution_view.status = status;
+        });
+
+        // Outputs of a previous execution in the same view should no longer arrive there.
+        self.executions
+            .retain(|_, existing_view| existing_view != &execution_view);
+        self.executions
+            .insert(message.header.msg_id.clone(), execution_view);
+
+        self.send_when_ready(message, cx);
     }
 
     pub fn execute(
@@ -407,14 +518,7 @@ impl Session {
             })
             .ok();
 
-        let status = match &self.kernel {
-            Kernel::Restarting => ExecutionStatus::Restarting,
-            Kernel::RunningKernel(_) => ExecutionStatus::Queued,
-            Kernel::StartingKernel(_) => ExecutionStatus::ConnectingToKernel,
-            Kernel::ErroredLaunch(error) => ExecutionStatus::KernelErrored(error.clone()),
-            Kernel::ShuttingDown => ExecutionStatus::ShuttingDown,
-            Kernel::Shutdown => ExecutionStatus::Shutdown,
-        };
+        let status = self.execution_status();
 
         let parent_message_id = message.header.msg_id.clone();
         let session_view = cx.entity().downgrade();
@@ -454,26 +558,7 @@ impl Session {
         self.blocks
             .insert(message.header.msg_id.clone(), editor_block);
 
-        match &self.kernel {
-            Kernel::RunningKernel(_) => {
-                self.send(message, cx).ok();
-            }
-            Kernel::StartingKernel(task) => {
-                // Queue up the execution as a task to run after the kernel starts
-                let task = task.clone();
-                let message = message.clone();
-
-                cx.spawn(async move |this, cx| {
-                    task.await;
-                    this.update(cx, |session, cx| {
-                        session.send(message, cx).ok();
-                    })
-                    .ok();
-                })
-                .detach();
-            }
-            _ => {}
-        }
+        self.send_when_ready(message, cx);
 
         if move_down {
             editor.update(cx, move |editor, cx| {
@@ -519,8 +604,14 @@ impl Session {
                     return;
                 };
 
-                self.blocks.iter_mut().for_each(|(_, block)| {
-                    block.execution_view.update(cx, |execution_view, cx| {
+                let execution_views = self
+                    .blocks
+                    .values()
+                    .map(|block| &block.execution_view)
+                    .chain(self.executions.values());
+
+                execution_views.for_each(|execution_view| {
+                    execution_view.update(cx, |execution_view, cx| {
                         execution_view.update_display_data(&update.data, &display_id, window, cx);
                     });
                 });
@@ -531,6 +622,10 @@ impl Session {
 
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(message, window, cx);
+        } else if let Some(execution_view) = self.executions.get(parent_message_id) {
+            execution_view.update(cx, |execution_view, cx| {
+                execution_view.push_message(&message.content, window, cx);
+            });
         }
     }
 
@@ -16,6 +16,9 @@ pub struct ExtensionApiManifest {
     pub wasm_api_version: Option<String>,
     #[serde(default)]
     pub provides: BTreeSet<ExtensionProvides>,
+    /// The SHA-256 checksum of the extension's package archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_checksum: Option<String>,
 }
 
 #[derive(
@@ -45,6 +48,10 @@ pub enum ExtensionProvides {
     SlashCommands,
     IndexedDocsProviders,
     Snippets,
+    DebugAdapters,
+    TaskProviders,
+    AgentTools,
+    LanguageModelProviders,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
@@ -1,3 +1,4 @@
+use anyhow::{Context as _, Result};
 use collections::HashMap;
 use schemars::{
     JsonSchema,
@@ -22,6 +23,16 @@ impl VsSnippetsFile {
 
         serde_json_lenient::to_value(schema).unwrap()
     }
+
+    /// Checks that the body of every snippet in the file is a valid snippet,
+    /// returning the number of snippets.
+    pub fn validate(&self) -> Result<usize> {
+        for (name, snippet) in &self.snippets {
+            snippet::Snippet::parse(&snippet.body.to_string())
+                .with_context(|| format!("invalid body for snippet {name:?}"))?;
+        }
+        Ok(self.snippets.len())
+    }
 }
 
 impl JsonSchema for VsSnippetsFile {
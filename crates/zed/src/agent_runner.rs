@@ -0,0 +1,772 @@
+//! Runs an agent thread to completion without opening a window, for use in scripts and bots.
+//!
+//! Invoked as `zed agent run [OPTIONS] <PROMPT>`, it prints a JSON transcript of the thread
+//! to stdout, including a unified diff of the changes the agent made to the worktree.
+
+use agent::{ContextLoadResult, ThreadEvent, ThreadStore};
+use anyhow::{Context as _, Result, anyhow};
+use assistant_settings::{AgentProfileId, AssistantSettings};
+use assistant_tool::ToolWorkingSet;
+use clap::{Parser, Subcommand};
+use client::{Client, ProxySettings, UserStore};
+use extension::ExtensionHostProxy;
+use fs::{Fs, RealFs};
+use futures::{FutureExt as _, StreamExt as _, channel::mpsc, future};
+use gpui::{App, AppContext as _, Application, AsyncApp, Entity};
+use gpui_tokio::Tokio;
+use http_client::read_proxy_from_env;
+use language::LanguageRegistry;
+use language_model::{
+    ConfiguredModel, LanguageModelProviderId, LanguageModelRegistry, Role, StopReason, TokenUsage,
+};
+use node_runtime::{NodeBinaryOptions, NodeRuntime};
+use project::Project;
+use project::project_settings::ProjectSettings;
+use prompt_store::PromptBuilder;
+use release_channel::AppVersion;
+use reqwest_client::ReqwestClient;
+use serde::Serialize;
+use settings::{Settings, SettingsStore};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use util::ResultExt as _;
+
+#[derive(Parser, Debug)]
+#[command(name = "zed agent", disable_version_flag = true)]
+pub struct AgentArgs {
+    #[command(subcommand)]
+    command: AgentCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum AgentCommand {
+    /// Runs a prompt to completion and prints a JSON transcript, including a unified diff of
+    /// the changes that were made to the worktree.
+    Run(RunArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// The prompt to send to the agent. Pass `-` to read it from stdin.
+    prompt: String,
+    /// The directory the agent works in.
+    #[arg(long, default_value = ".")]
+    worktree: PathBuf,
+    /// The agent profile whose tools the agent is allowed to use, e.g. `write` or `ask`.
+    /// Defaults to the profile configured in the settings.
+    #[arg(long)]
+    profile: Option<String>,
+    /// The model to use, either as `provider/model` or just the model ID.
+    /// Defaults to the model configured in the settings.
+    #[arg(long)]
+    model: Option<String>,
+    /// The maximum number of requests sent to the model.
+    #[arg(long, default_value = "50")]
+    max_turns: u32,
+    /// Writes the transcript to this file instead of stdout.
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Additionally writes the unified diff to this file.
+    #[arg(long, value_name = "FILE")]
+    diff: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Transcript {
+    prompt: String,
+    model: String,
+    profile: String,
+    /// Set when the thread didn't run to completion.
+    error: Option<String>,
+    token_usage: TokenUsage,
+    messages: Vec<TranscriptMessage>,
+    /// The changes made to the worktree, as a unified diff.
+    diff: String,
+}
+
+#[derive(Serialize)]
+struct TranscriptMessage {
+    role: Role,
+    text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_uses: Vec<TranscriptToolUse>,
+}
+
+#[derive(Serialize)]
+struct TranscriptToolUse {
+    name: String,
+    input: serde_json::Value,
+    output: Option<String>,
+    is_error: bool,
+}
+
+/// Runs the `zed agent` command with the given arguments (excluding the binary name),
+/// returning the process' exit code.
+pub fn run(args: impl IntoIterator<Item = String>) -> i32 {
+    let args = match AgentArgs::try_parse_from(args) {
+        Ok(args) => args,
+        Err(error) => {
+            error.print().ok();
+            return error.exit_code();
+        }
+    };
+    let AgentCommand::Run(mut args) = args.command;
+
+    args.prompt = match read_prompt(&args.prompt, io::stdin()) {
+        Ok(prompt) => prompt,
+        Err(error) => {
+            eprintln!("{error:#}");
+            return 1;
+        }
+    };
+    let worktree_path = match args.worktree.canonicalize() {
+        Ok(path) => path,
+        Err(error) => {
+            eprintln!("invalid worktree {:?}: {error}", args.worktree);
+            return 1;
+        }
+    };
+
+    // Stdout is reserved for the transcript.
+    zlog::init();
+    zlog::init_output_file(paths::log_file(), Some(paths::old_log_file())).log_err();
+
+    let exit_code = Arc::new(std::sync::atomic::AtomicI32::new(1));
+    let app = Application::headless();
+    app.run({
+        let exit_code = exit_code.clone();
+        move |cx| {
+            let app_state = init(cx);
+            cx.spawn(async move |cx| {
+                match run_thread(args, worktree_path, app_state, cx).await {
+                    Ok(succeeded) => {
+                        exit_code.store(
+                            if succeeded { 0 } else { 1 },
+                            std::sync::atomic::Ordering::SeqCst,
+                        );
+                    }
+                    Err(error) => eprintln!("{error:#}"),
+                }
+                cx.update(|cx| cx.quit()).log_err();
+            })
+            .detach();
+        }
+    });
+
+    exit_code.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Returns the prompt to send to the agent, reading it from `stdin` if it is `-`.
+fn read_prompt(prompt: &str, mut stdin: impl io::Read) -> Result<String> {
+    if prompt != "-" {
+        return Ok(prompt.trim().to_string());
+    }
+
+    let mut prompt = String::new();
+    stdin
+        .read_to_string(&mut prompt)
+        .context("failed to read the prompt from stdin")?;
+    Ok(prompt.trim().to_string())
+}
+
+struct AgentAppState {
+    languages: Arc<LanguageRegistry>,
+    client: Arc<Client>,
+    user_store: Entity<UserStore>,
+    fs: Arc<dyn Fs>,
+    node_runtime: NodeRuntime,
+    prompt_builder: Arc<PromptBuilder>,
+}
+
+fn init(cx: &mut App) -> AgentAppState {
+    release_channel::init(AppVersion::load(env!("CARGO_PKG_VERSION")), cx);
+    gpui_tokio::init(cx);
+
+    settings::init(cx);
+    if let Ok(user_settings) = std::fs::read_to_string(paths::settings_file()) {
+        SettingsStore::update_global(cx, |store, cx| {
+            store
+                .set_user_settings(&user_settings, cx)
+                .context("failed to load the user settings")
+                .log_err();
+        });
+    }
+    client::init_settings(cx);
+
+    let user_agent = format!(
+        "Zed/{} ({}; {})",
+        AppVersion::global(cx),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let proxy_str = ProxySettings::get_global(cx).proxy.to_owned();
+    let proxy_url = proxy_str
+        .as_ref()
+        .and_then(|input| input.parse().ok())
+        .or_else(read_proxy_from_env);
+    let http = {
+        let _guard = Tokio::handle(cx).enter();
+
+        ReqwestClient::proxy_and_user_agent(proxy_url, &user_agent)
+            .expect("could not start HTTP client")
+    };
+    cx.set_http_client(Arc::new(http));
+
+    Project::init_settings(cx);
+
+    let client = Client::production(cx);
+    cx.set_http_client(client.http_client());
+
+    let fs: Arc<dyn Fs> = Arc::new(RealFs::new(None, cx.background_executor().clone()));
+    <dyn Fs>::set_global(fs.clone(), cx);
+
+    let mut languages = LanguageRegistry::new(cx.background_executor().clone());
+    languages.set_language_server_download_dir(paths::languages_dir().clone());
+    let languages = Arc::new(languages);
+
+    let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+
+    extension::init(cx);
+    let extension_host_proxy = ExtensionHostProxy::global(cx);
+
+    let (tx, rx) = async_watch::channel(None);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let settings = &ProjectSettings::get_global(cx).node;
+        let options = NodeBinaryOptions {
+            allow_path_lookup: !settings.ignore_system_version.unwrap_or_default(),
+            allow_binary_download: true,
+            use_paths: settings.path.as_ref().map(|node_path| {
+                let node_path = PathBuf::from(shellexpand::tilde(node_path).as_ref());
+                let npm_path = settings
+                    .npm_path
+                    .as_ref()
+                    .map(|path| PathBuf::from(shellexpand::tilde(&path).as_ref()));
+                (
+                    node_path.clone(),
+                    npm_path.unwrap_or_else(|| {
+                        let base_path = PathBuf::new();
+                        node_path.parent().unwrap_or(&base_path).join("npm")
+                    }),
+                )
+            }),
+        };
+        tx.send(Some(options)).log_err();
+    })
+    .detach();
+    let node_runtime = NodeRuntime::new(client.http_client(), rx);
+
+    language::init(cx);
+    language_extension::init(extension_host_proxy, languages.clone());
+    languages::init(languages.clone(), node_runtime.clone(), cx);
+    language_model::init(client.clone(), cx);
+    language_models::init(user_store.clone(), client.clone(), fs.clone(), cx);
+    context_server::init(cx);
+    prompt_store::init(cx);
+    let prompt_builder = PromptBuilder::load(fs.clone(), false, cx);
+    agent::init(
+        fs.clone(),
+        client.clone(),
+        prompt_builder.clone(),
+        languages.clone(),
+        cx,
+    );
+    assistant_tools::init(client.http_client(), cx);
+
+    // Nobody is around to confirm tool calls; the profile decides which tools can be used.
+    let mut assistant_settings = AssistantSettings::get_global(cx).clone();
+    assistant_settings.always_allow_tool_actions = true;
+    AssistantSettings::override_global(assistant_settings, cx);
+
+    AgentAppState {
+        languages,
+        client,
+        user_store,
+        fs,
+        node_runtime,
+        prompt_builder,
+    }
+}
+
+/// Runs the thread and writes the transcript, returning whether the thread ran to completion.
+async fn run_thread(
+    args: RunArgs,
+    worktree_path: PathBuf,
+    app_state: AgentAppState,
+    cx: &mut AsyncApp,
+) -> Result<bool> {
+    let profile_id = cx.update(|cx| {
+        let profile_id = args
+            .profile
+            .clone()
+            .map(|profile| AgentProfileId(profile.into()))
+            .unwrap_or_else(|| AssistantSettings::get_global(cx).default_profile.clone());
+        let mut assistant_settings = AssistantSettings::get_global(cx).clone();
+        if !assistant_settings.profiles.contains_key(&profile_id) {
+            return Err(anyhow!("no agent profile named {:?}", profile_id.as_str()));
+        }
+        // The thread store enables the tools of the default profile.
+        assistant_settings.default_profile = profile_id.clone();
+        AssistantSettings::override_global(assistant_settings, cx);
+        anyhow::Ok(profile_id)
+    })??;
+    let model = resolve_model(args.model.as_deref(), cx).await?;
+
+    let project = cx.update(|cx| {
+        Project::local(
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.user_store.clone(),
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            None,
+            cx,
+        )
+    })?;
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.create_worktree(&worktree_path, true, cx)
+        })?
+        .await?;
+    worktree
+        .update(cx, |worktree, _| {
+            worktree
+                .as_local()
+                .context("worktree is not local")
+                .map(|worktree| worktree.scan_complete())
+        })??
+        .await;
+
+    let git_store = project.read_with(cx, |project, _| project.git_store().clone())?;
+    let has_repository =
+        git_store.read_with(cx, |git_store, _| !git_store.repositories().is_empty())?;
+    if !has_repository {
+        log::warn!(
+            "{:?} is not inside of a git repository, no diff will be produced",
+            worktree_path
+        );
+    }
+    let checkpoint_before = git_store
+        .update(cx, |git_store, cx| git_store.checkpoint(cx))?
+        .await?;
+
+    let tools = cx.new(|_| ToolWorkingSet::default())?;
+    let thread_store = cx
+        .update(|cx| {
+            ThreadStore::load(
+                project.clone(),
+                tools,
+                None,
+                app_state.prompt_builder.clone(),
+                cx,
+            )
+        })?
+        .await?;
+    let thread = thread_store.update(cx, |thread_store, cx| thread_store.create_thread(cx))?;
+
+    let (events_tx, mut events_rx) = mpsc::unbounded::<Result<()>>();
+    let _subscription = cx.update(|cx| {
+        cx.subscribe(
+            &thread,
+            move |thread, event: &ThreadEvent, cx| match event {
+                ThreadEvent::ShowError(error) => {
+                    events_tx.unbounded_send(Err(anyhow!(error.clone()))).ok();
+                }
+                ThreadEvent::Stopped(Ok(StopReason::EndTurn)) => events_tx.close_channel(),
+                ThreadEvent::Stopped(Ok(StopReason::ToolUse)) => {
+                    if thread.read(cx).remaining_turns() == 0 {
+                        events_tx
+                            .unbounded_send(Err(anyhow!("exceeded the maximum number of turns")))
+                            .ok();
+                    }
+                }
+                ThreadEvent::Stopped(Ok(StopReason::MaxTokens)) => {
+                    events_tx
+                        .unbounded_send(Err(anyhow!("exceeded the maximum number of tokens")))
+                        .ok();
+                }
+                ThreadEvent::Stopped(Err(error)) => {
+                    events_tx.unbounded_send(Err(anyhow!("{error:#}"))).ok();
+                }
+                ThreadEvent::ToolConfirmationNeeded => {
+                    events_tx
+                        .unbounded_send(Err(anyhow!("a tool unexpectedly required confirmation")))
+                        .ok();
+                }
+                _ => {}
+            },
+        )
+    })?;
+
+    let prompt = args.prompt.clone();
+    thread.update(cx, |thread, cx| {
+        thread.set_configured_model(Some(model.clone()), cx);
+        thread.set_remaining_turns(args.max_turns);
+        thread.insert_user_message(
+            prompt.clone(),
+            ContextLoadResult::default(),
+            None,
+            Vec::new(),
+            cx,
+        );
+        thread.send_to_model(model.model.clone(), None, cx);
+    })?;
+
+    let mut error = None;
+    while let Some(event) = events_rx.next().await {
+        if let Err(event_error) = event {
+            error = Some(format!("{event_error:#}"));
+            thread.update(cx, |thread, cx| thread.cancel_last_completion(None, cx))?;
+            break;
+        }
+    }
+    events_rx.close();
+
+    let diff = if has_repository {
+        let checkpoint_after = git_store
+            .update(cx, |git_store, cx| git_store.checkpoint(cx))?
+            .await?;
+        git_store
+            .update(cx, |git_store, cx| {
+                git_store.diff_checkpoints(checkpoint_before, checkpoint_after, cx)
+            })?
+            .await?
+    } else {
+        String::new()
+    };
+
+    let transcript = thread.read_with(cx, |thread, cx| Transcript {
+        prompt,
+        model: format!("{}/{}", model.provider.id().0, model.model.id().0),
+        profile: profile_id.as_str().to_string(),
+        error: error.clone(),
+        token_usage: thread.cumulative_token_usage(),
+        messages: thread
+            .messages()
+            .map(|message| TranscriptMessage {
+                role: message.role,
+                text: message.to_string(),
+                tool_uses: thread
+                    .tool_uses_for_message(message.id, cx)
+                    .into_iter()
+                    .map(|tool_use| {
+                        let result = thread.tool_result(&tool_use.id);
+                        TranscriptToolUse {
+                            name: tool_use.name.to_string(),
+                            input: tool_use.input,
+                            output: result.map(|result| result.content.to_string()),
+                            is_error: result.map_or(false, |result| result.is_error),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect(),
+        diff: diff.clone(),
+    })?;
+
+    write_transcript(&transcript, args.output.as_deref())?;
+    if let Some(path) = &args.diff {
+        std::fs::write(path, diff)
+            .with_context(|| format!("failed to write the diff to {path:?}"))?;
+    }
+
+    if let Some(error) = &error {
+        eprintln!("{error}");
+    }
+    Ok(error.is_none())
+}
+
+/// Writes the transcript as JSON to `output`, or to stdout if no file is given.
+fn write_transcript(transcript: &Transcript, output: Option<&Path>) -> Result<()> {
+    let transcript = serde_json::to_string_pretty(transcript)?;
+    if let Some(path) = output {
+        std::fs::write(path, transcript)
+            .with_context(|| format!("failed to write the transcript to {path:?}"))?;
+    } else {
+        println!("{transcript}");
+    }
+    Ok(())
+}
+
+/// How long to wait for an authenticated provider to make the requested model available.
+const MODEL_LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Finds the model named `name` (either `provider/model` or a model ID), falling back
+/// to the default model from the settings.
+///
+/// Providers are authenticated before their models are looked up, as some of them, such
+/// as Ollama, only load their models once authenticated.
+async fn resolve_model(name: Option<&str>, cx: &mut AsyncApp) -> Result<ConfiguredModel> {
+    let registry = cx.update(|cx| LanguageModelRegistry::global(cx))?;
+    let (providers, model_id) = cx.update(|cx| {
+        let (provider_id, model_id) = match name {
+            Some(name) => match name.split_once('/') {
+                Some((provider_id, model_id)) => (Some(provider_id.to_string()), model_id),
+                None => (None, name),
+            },
+            None => {
+                let default_model = &AssistantSettings::get_global(cx).default_model;
+                (
+                    Some(default_model.provider.clone()),
+                    default_model.model.as_str(),
+                )
+            }
+        };
+
+        let registry = registry.read(cx);
+        match provider_id.and_then(|id| registry.provider(&LanguageModelProviderId::from(id))) {
+            Some(provider) => (vec![provider], model_id.to_string()),
+            // Model IDs may contain slashes themselves, e.g. `anthropic/claude-3.7-sonnet`.
+            None => (registry.providers(), name.unwrap_or(model_id).to_string()),
+        }
+    })?;
+
+    let authenticate = cx.update(|cx| {
+        providers
+            .iter()
+            .map(|provider| provider.authenticate(cx))
+            .collect::<Vec<_>>()
+    })?;
+    for (provider, result) in providers.iter().zip(future::join_all(authenticate).await) {
+        if let Err(error) = result {
+            let error = anyhow!("failed to authenticate {}: {error}", provider.name().0);
+            // When looking for the model across all providers, it's fine for the
+            // providers that aren't configured to fail.
+            if providers.len() == 1 {
+                return Err(error);
+            }
+            log::info!("{error:#}");
+        }
+    }
+
+    let (models_changed_tx, mut models_changed_rx) = mpsc::unbounded();
+    let _subscription = cx.update(|cx| {
+        cx.subscribe(&registry, move |_, event: &language_model::Event, _| {
+            if matches!(
+                event,
+                language_model::Event::ProviderStateChanged
+                    | language_model::Event::AddedProvider(_)
+            ) {
+                models_changed_tx.unbounded_send(()).ok();
+            }
+        })
+    })?;
+    let mut timeout = cx.background_executor().timer(MODEL_LOAD_TIMEOUT).fuse();
+    loop {
+        let model = cx.update(|cx| {
+            providers.iter().find_map(|provider| {
+                let model = provider
+                    .provided_models(cx)
+                    .into_iter()
+                    .find(|model| model.id().0 == model_id)?;
+                Some(ConfiguredModel {
+                    provider: provider.clone(),
+                    model,
+                })
+            })
+        })?;
+        if let Some(model) = model {
+            return Ok(model);
+        }
+
+        futures::select_biased! {
+            _ = models_changed_rx.next() => {}
+            _ = timeout => {
+                let available_models = cx.update(|cx| {
+                    providers
+                        .iter()
+                        .flat_map(|provider| provider.provided_models(cx))
+                        .map(|model| format!("{}/{}", model.provider_id().0, model.id().0))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })?;
+                return Err(anyhow!(
+                    "no language model named {model_id} is available. Available models: {available_models}"
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AnyView, Task, TestAppContext, Window};
+    use language_model::{
+        AuthenticateError, LanguageModel, LanguageModelProvider, LanguageModelProviderName,
+        LanguageModelProviderState, fake_provider::FakeLanguageModel,
+    };
+    use serde_json::json;
+
+    /// A provider that, like Ollama, only loads its models after it is authenticated.
+    #[derive(Clone)]
+    struct LazyProvider {
+        models: Entity<Vec<Arc<dyn LanguageModel>>>,
+    }
+
+    impl LanguageModelProviderState for LazyProvider {
+        type ObservableEntity = Vec<Arc<dyn LanguageModel>>;
+
+        fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+            Some(self.models.clone())
+        }
+    }
+
+    impl LanguageModelProvider for LazyProvider {
+        fn id(&self) -> LanguageModelProviderId {
+            LanguageModelProviderId::from("lazy".to_string())
+        }
+
+        fn name(&self) -> LanguageModelProviderName {
+            LanguageModelProviderName::from("Lazy".to_string())
+        }
+
+        fn default_model(&self, _: &App) -> Option<Arc<dyn LanguageModel>> {
+            None
+        }
+
+        fn default_fast_model(&self, _: &App) -> Option<Arc<dyn LanguageModel>> {
+            None
+        }
+
+        fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+            self.models.read(cx).clone()
+        }
+
+        fn is_authenticated(&self, cx: &App) -> bool {
+            !self.models.read(cx).is_empty()
+        }
+
+        fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+            let models = self.models.clone();
+            cx.spawn(async move |cx| {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+                models
+                    .update(cx, |models, cx| {
+                        models.push(Arc::new(FakeLanguageModel::default()));
+                        cx.notify();
+                    })
+                    .ok();
+            })
+            .detach();
+            Task::ready(Ok(()))
+        }
+
+        fn configuration_view(&self, _: &mut Window, _: &mut App) -> AnyView {
+            unimplemented!()
+        }
+
+        fn reset_credentials(&self, _: &mut App) -> Task<Result<()>> {
+            Task::ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn test_read_prompt() {
+        assert_eq!(
+            read_prompt("  fix the tests\n", io::empty()).unwrap(),
+            "fix the tests"
+        );
+        assert_eq!(
+            read_prompt("-", "fix the tests\n".as_bytes()).unwrap(),
+            "fix the tests"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_resolve_model(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            LanguageModelRegistry::test(cx);
+            let provider = LazyProvider {
+                models: cx.new(|_| Vec::new()),
+            };
+            LanguageModelRegistry::global(cx)
+                .update(cx, |registry, cx| registry.register_provider(provider, cx));
+        });
+
+        let model = cx
+            .spawn(move |mut cx| async move { resolve_model(Some("fake/fake"), &mut cx).await })
+            .await
+            .unwrap();
+        assert_eq!(model.provider.id().0, "fake");
+        assert_eq!(model.model.id().0, "fake");
+
+        // The lazy provider's models only appear some time after it is authenticated.
+        let model =
+            cx.spawn(move |mut cx| async move { resolve_model(Some("lazy/fake"), &mut cx).await });
+        cx.executor().advance_clock(Duration::from_secs(1));
+        let model = model.await.unwrap();
+        assert_eq!(model.provider.id().0, "lazy");
+        assert_eq!(model.model.id().0, "fake");
+
+        let model = cx
+            .spawn(move |mut cx| async move { resolve_model(Some("fake/unknown"), &mut cx).await });
+        cx.executor().advance_clock(MODEL_LOAD_TIMEOUT);
+        let error = model.await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no language model named unknown is available. Available models: fake/fake"
+        );
+    }
+
+    #[test]
+    fn test_transcript_output() {
+        let transcript = Transcript {
+            prompt: "Fix the tests".to_string(),
+            model: "fake/fake".to_string(),
+            profile: "write".to_string(),
+            error: None,
+            token_usage: TokenUsage {
+                input_tokens: 10,
+                output_tokens: 5,
+                ..Default::default()
+            },
+            messages: vec![
+                TranscriptMessage {
+                    role: Role::User,
+                    text: "Fix the tests".to_string(),
+                    tool_uses: Vec::new(),
+                },
+                TranscriptMessage {
+                    role: Role::Assistant,
+                    text: "Done.".to_string(),
+                    tool_uses: vec![TranscriptToolUse {
+                        name: "edit_file".to_string(),
+                        input: json!({ "path": "src/lib.rs" }),
+                        output: Some("Edited src/lib.rs".to_string()),
+                        is_error: false,
+                    }],
+                },
+            ],
+            diff: "--- a/src/lib.rs\n+++ b/src/lib.rs\n".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&transcript).unwrap(),
+            json!({
+                "prompt": "Fix the tests",
+                "model": "fake/fake",
+                "profile": "write",
+                "error": null,
+                "token_usage": { "input_tokens": 10, "output_tokens": 5 },
+                "messages": [
+                    { "role": "user", "text": "Fix the tests" },
+                    {
+                        "role": "assistant",
+                        "text": "Done.",
+                        "tool_uses": [{
+                            "name": "edit_file",
+                            "input": { "path": "src/lib.rs" },
+                            "output": "Edited src/lib.rs",
+                            "is_error": false
+                        }]
+                    }
+                ],
+                "diff": "--- a/src/lib.rs\n+++ b/src/lib.rs\n"
+            })
+        );
+    }
+}
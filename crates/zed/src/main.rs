@@ -1,6 +1,7 @@
 // Disable command line from opening on release mode
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod agent_runner;
 mod reliability;
 mod zed;
 
@@ -177,6 +178,17 @@ fn main() {
         return;
     }
 
+    // `zed agent` runs the agent headlessly, unless there is a file or directory named `agent`
+    // to open.
+    if std::env::args().nth(1).as_deref() == Some("agent") && !Path::new("agent").exists() {
+        let init_errors = init_paths();
+        if !init_errors.is_empty() {
+            eprintln!("failed to create Zed's directories: {init_errors:?}");
+            process::exit(1);
+        }
+        process::exit(agent_runner::run(std::env::args().skip(1)));
+    }
+
     let args = Args::parse();
 
     if let Some(socket) = &args.askpass {
@@ -494,6 +506,7 @@ fn main() {
             app_state.fs.clone(),
             cx,
         );
+        language_model_usage::init(cx);
         web_search::init(cx);
         web_search_providers::init(app_state.client.clone(), cx);
         snippet_provider::init(cx);
@@ -52,6 +52,10 @@ pub enum ExtensionCategoryFilter {
     SlashCommands,
     IndexedDocsProviders,
     Snippets,
+    DebugAdapters,
+    TaskProviders,
+    AgentTools,
+    LanguageModelProviders,
 }
 
 #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]